@@ -0,0 +1,58 @@
+use bevy_asset::{prelude::*, HandleId};
+use bevy_sprite::prelude::*;
+use bevy_tasks::TaskPool;
+use bevy_tilemap::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn tilemap() -> Tilemap {
+    let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    TilemapBuilder::new()
+        .texture_atlas(texture_atlas_handle)
+        .texture_dimensions(32, 32)
+        .auto_chunk()
+        .finish()
+        .unwrap()
+}
+
+fn tiles(size: i32) -> Vec<Tile<(i32, i32)>> {
+    let mut tiles = Vec::with_capacity((size * size) as usize);
+    for y in -size / 2..size / 2 {
+        for x in -size / 2..size / 2 {
+            tiles.push(Tile {
+                point: (x, y),
+                sprite_index: ((x + y) & 7) as usize,
+                ..Default::default()
+            });
+        }
+    }
+    tiles
+}
+
+fn insert_tiles(c: &mut Criterion) {
+    let task_pool = TaskPool::new();
+    let mut group = c.benchmark_group("insert_tiles");
+    group.sample_size(20);
+    for size in [64, 256, 512].iter() {
+        let tiles = tiles(*size);
+        group.bench_with_input(BenchmarkId::new("serial", size), &tiles, |b, tiles| {
+            b.iter(|| {
+                let mut tilemap = tilemap();
+                tilemap.insert_tiles(black_box(tiles.clone())).unwrap();
+                tilemap
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", size), &tiles, |b, tiles| {
+            b.iter(|| {
+                let mut tilemap = tilemap();
+                tilemap
+                    .insert_tiles_parallel(&task_pool, black_box(tiles.clone()))
+                    .unwrap();
+                tilemap
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, insert_tiles);
+criterion_main!(benches);
@@ -4,7 +4,6 @@ use bevy::{
     prelude::*,
     render::camera::Camera,
     sprite::{TextureAtlas, TextureAtlasBuilder},
-    utils::HashSet,
     window::WindowMode,
 };
 use bevy_tilemap::prelude::*;
@@ -69,23 +68,21 @@ struct PlayerBundle {
 struct GameState {
     map_loaded: bool,
     spawned: bool,
-    collisions: HashSet<(i32, i32)>,
 }
 
-impl GameState {
-    fn try_move_player(
-        &mut self,
-        position: &mut Position,
-        camera_translation: &mut Vec3,
-        delta_xy: (i32, i32),
-    ) {
-        let new_pos = (position.x + delta_xy.0, position.y + delta_xy.1);
-        if !self.collisions.contains(&new_pos) {
-            position.x = position.x + delta_xy.0;
-            position.y = position.y + delta_xy.1;
-            camera_translation.x = camera_translation.x + (delta_xy.0 as f32 * 32.);
-            camera_translation.y = camera_translation.y + (delta_xy.1 as f32 * 32.);
-        }
+fn try_move_player(
+    map: &Tilemap,
+    position: &mut Position,
+    camera_translation: &mut Vec3,
+    delta_xy: (i32, i32),
+) {
+    let new_pos = (position.x + delta_xy.0, position.y + delta_xy.1);
+    // The walls are on a solid layer, so the tilemap knows where they are.
+    if !map.is_solid(new_pos) {
+        position.x = position.x + delta_xy.0;
+        position.y = position.y + delta_xy.1;
+        camera_translation.x = camera_translation.x + (delta_xy.0 as f32 * 32.);
+        camera_translation.y = camera_translation.y + (delta_xy.1 as f32 * 32.);
     }
 }
 
@@ -132,6 +129,15 @@ fn load(
                 },
                 0,
             )
+            // The walls go on their own layer which blocks the dwarf.
+            .add_layer(
+                TilemapLayer {
+                    kind: LayerKind::Sparse,
+                    ..Default::default()
+                },
+                1,
+            )
+            .solid_layer(1)
             .texture_atlas(atlas_handle)
             .finish()
             .unwrap();
@@ -201,15 +207,15 @@ fn build_random_dungeon(
             tiles.push(Tile {
                 point: tile_a,
                 sprite_index: wall_idx,
+                sprite_order: 1,
                 ..Default::default()
             });
             tiles.push(Tile {
                 point: tile_b,
                 sprite_index: wall_idx,
+                sprite_order: 1,
                 ..Default::default()
             });
-            game_state.collisions.insert(tile_a);
-            game_state.collisions.insert(tile_b);
         }
 
         // Then the wall tiles on the Y axis.
@@ -220,15 +226,15 @@ fn build_random_dungeon(
             tiles.push(Tile {
                 point: tile_a,
                 sprite_index: wall_idx,
+                sprite_order: 1,
                 ..Default::default()
             });
             tiles.push(Tile {
                 point: tile_b,
                 sprite_index: wall_idx,
+                sprite_order: 1,
                 ..Default::default()
             });
-            game_state.collisions.insert(tile_a);
-            game_state.collisions.insert(tile_b);
         }
 
         // Lets just generate some random walls to sparsely place around the dungeon!
@@ -242,9 +248,9 @@ fn build_random_dungeon(
                 tiles.push(Tile {
                     point: (x, y),
                     sprite_index: wall_idx,
+                    sprite_order: 1,
                     ..Default::default()
                 });
-                game_state.collisions.insert((x, y));
             }
         }
 
@@ -252,27 +258,24 @@ fn build_random_dungeon(
         // we are missing a hero! First, we need to add a layer. We must make
         // this layer `Sparse` else we will lose efficiency with our data!
         //
-        // You might've noticed that we didn't create a layer for z_layer 0 but
-        // yet it still works and exists. By default if a layer doesn't exist
-        // and tiles need to be written there then a Dense layer is created
-        // automatically.
+        // The layer goes above the floors on layer 0 and the walls on layer 1.
         map.add_layer(
             TilemapLayer {
                 kind: LayerKind::Sparse,
                 ..Default::default()
             },
-            1,
+            2,
         )
         .unwrap();
 
         // Now lets add in a dwarf friend!
         let dwarf_sprite: Handle<Texture> = asset_server.get_handle("textures/square-dwarf.png");
         let dwarf_sprite_index = texture_atlas.get_texture_index(&dwarf_sprite).unwrap();
-        // We add in a Z order of 1 to place the tile above the background on Z
-        // order 0.
+        // We add in a Z order of 2 to place the tile above the walls on Z
+        // order 1.
         let dwarf_tile = Tile {
             point: (0, 0),
-            sprite_order: 1,
+            sprite_order: 2,
             sprite_index: dwarf_sprite_index,
             ..Default::default()
         };
@@ -283,7 +286,7 @@ fn build_random_dungeon(
             position: Position { x: 0, y: 0 },
             render: Render {
                 sprite_index: dwarf_sprite_index,
-                sprite_order: 1,
+                sprite_order: 2,
             },
         });
 
@@ -301,8 +304,11 @@ fn move_sprite(
     render: &Render,
 ) {
     // We need to first remove where we were prior.
-    map.clear_tile((previous_position.x, previous_position.y), 1)
-        .unwrap();
+    map.clear_tile(
+        (previous_position.x, previous_position.y),
+        render.sprite_order,
+    )
+    .unwrap();
     // We then need to update where we are going!
     let tile = Tile {
         point: (position.x, position.y),
@@ -314,7 +320,7 @@ fn move_sprite(
 }
 
 fn character_movement(
-    mut game_state: ResMut<GameState>,
+    game_state: Res<GameState>,
     keyboard_input: Res<Input<KeyCode>>,
     time: Res<Time>,
     mut map_query: Query<(&mut Tilemap, &mut Timer)>,
@@ -342,50 +348,58 @@ fn character_movement(
                     use KeyCode::*;
                     match key {
                         W | Numpad8 | Up | K => {
-                            game_state.try_move_player(
+                            try_move_player(
+                                &map,
                                 &mut position,
                                 &mut camera_transform.translation,
                                 (0, 1),
                             );
                         }
                         A | Numpad4 | Left | H => {
-                            game_state.try_move_player(
+                            try_move_player(
+                                &map,
                                 &mut position,
                                 &mut camera_transform.translation,
                                 (-1, 0),
                             );
                         }
                         S | Numpad2 | Down | J => {
-                            game_state.try_move_player(
+                            try_move_player(
+                                &map,
                                 &mut position,
                                 &mut camera_transform.translation,
                                 (0, -1),
                             );
                         }
                         D | Numpad6 | Right | L => {
-                            game_state.try_move_player(
+                            try_move_player(
+                                &map,
                                 &mut position,
                                 &mut camera_transform.translation,
                                 (1, 0),
                             );
                         }
 
-                        Numpad9 | U => game_state.try_move_player(
+                        Numpad9 | U => try_move_player(
+                            &map,
                             &mut position,
                             &mut camera_transform.translation,
                             (1, 1),
                         ),
-                        Numpad3 | M => game_state.try_move_player(
+                        Numpad3 | M => try_move_player(
+                            &map,
                             &mut position,
                             &mut camera_transform.translation,
                             (1, -1),
                         ),
-                        Numpad1 | N => game_state.try_move_player(
+                        Numpad1 | N => try_move_player(
+                            &map,
                             &mut position,
                             &mut camera_transform.translation,
                             (-1, -1),
                         ),
-                        Numpad7 | Y => game_state.try_move_player(
+                        Numpad7 | Y => try_move_player(
+                            &map,
                             &mut position,
                             &mut camera_transform.translation,
                             (-1, 1),
@@ -6,7 +6,12 @@ use bevy::{
     utils::HashSet,
     window::WindowMode,
 };
-use bevy_tilemap::prelude::*;
+use bevy_tilemap::{
+    hex,
+    point::Point2,
+    prelude::*,
+    worldgen::{random, Noise},
+};
 use rand::Rng;
 
 fn main() {
@@ -249,43 +254,41 @@ fn build_random_world(
             game_state.collisions.insert(tile_a);
             game_state.collisions.insert(tile_b);
         }
+        // Every seed makes a different world, and the same seed always
+        // makes the same one.
+        let seed: u64 = rand::thread_rng().gen();
         // Lets just generate some random walls to sparsely place around the
-        // world!
-        let range = (chunk_width * chunk_height) as usize / 5;
-        let mut rng = rand::thread_rng();
-        for _ in 0..range {
-            let x = rng.gen_range((-chunk_width / 2)..(chunk_width / 2));
-            let y = rng.gen_range((-chunk_height / 2)..(chunk_height / 2));
-            let coord = (x, y, 0i32);
-            if coord != (0, 0, 0) {
-                if rng.gen_bool(0.5) {
+        // world! White noise picks about a fifth of the tiles, and a second
+        // seed whether they get a boulder or trees.
+        let dirt_noise = Noise::new(seed);
+        for y in (-chunk_height / 2)..(chunk_height / 2) {
+            for x in (-chunk_width / 2)..(chunk_width / 2) {
+                let point = Point2::new(x, y);
+                if (x, y) != (0, 0) && random(seed, point) < 0.2 {
+                    let sprite_index = if random(seed.wrapping_add(1), point) < 0.5 {
+                        boulder_index
+                    } else {
+                        trees_index
+                    };
                     tiles.push(Tile {
                         point: (x, y),
-                        sprite_index: boulder_index,
+                        sprite_index,
                         sprite_order: 1,
                         ..Default::default()
                     });
-                } else {
+                    game_state.collisions.insert((x, y));
+                }
+                // Lets finally vary it up and add some dirt patches where
+                // the smooth noise is low.
+                if dirt_noise.get(point) < 0.35 {
                     tiles.push(Tile {
                         point: (x, y),
-                        sprite_index: trees_index,
-                        sprite_order: 1,
+                        sprite_index: dirt_index,
                         ..Default::default()
                     });
                 }
-                game_state.collisions.insert((x, y));
             }
         }
-        // Lets finally vary it up and add some dirt patches.
-        for _ in 0..range {
-            let x = rng.gen_range((-chunk_width / 2)..(chunk_width / 2));
-            let y = rng.gen_range((-chunk_height / 2)..(chunk_height / 2));
-            tiles.push(Tile {
-                point: (x, y),
-                sprite_index: dirt_index,
-                ..Default::default()
-            });
-        }
 
         // Now lets add in a dwarf friend!
         let dwarf_sprite: Handle<Texture> = asset_server.get_handle("textures/hex-dwarf.png");
@@ -362,58 +365,42 @@ fn character_movement(
                 let previous_position = *position;
 
                 // Of course we need to control where we are going to move our
-                // dwarf friend. Since this map is hex, we most handle movement
-                // different depending on if we are on an even Y axis or not.
+                // dwarf friend. The hex module finds the neighbour in each
+                // direction, so we don't need to care about which rows are
+                // offset. Hexes have no straight up or down, so those zig zag
+                // between the two diagonals to stay in the same column.
                 use KeyCode::*;
-                if position.y % 2 == 0 {
-                    match key {
-                        W | Numpad8 | Up | Q | Numpad7 => {
-                            game_state.try_move_player(&mut position, (0, 1));
-                        }
-                        A | Numpad4 | Left => {
-                            game_state.try_move_player(&mut position, (-1, 0));
-                        }
-                        X | Numpad2 | Down | Z | Numpad1 => {
-                            game_state.try_move_player(&mut position, (0, -1));
-                        }
-                        D | Numpad6 | Right => {
-                            game_state.try_move_player(&mut position, (1, 0));
-                        }
-
-                        E | Numpad9 => {
-                            game_state.try_move_player(&mut position, (1, 1));
-                        }
-                        C | Numpad3 => {
-                            game_state.try_move_player(&mut position, (1, -1));
+                let odd_row = position.y % 2 != 0;
+                let direction = match key {
+                    D | Numpad6 | Right => 0,
+                    C | Numpad3 => 1,
+                    Z | Numpad1 => 2,
+                    A | Numpad4 | Left => 3,
+                    Q | Numpad7 => 4,
+                    E | Numpad9 => 5,
+                    W | Numpad8 | Up => {
+                        if odd_row {
+                            5
+                        } else {
+                            4
                         }
-
-                        _ => {}
                     }
-                } else {
-                    match key {
-                        W | Numpad8 | Up | E | Numpad9 => {
-                            game_state.try_move_player(&mut position, (0, 1));
-                        }
-                        A | Numpad4 | Left => {
-                            game_state.try_move_player(&mut position, (-1, 0));
-                        }
-                        X | Numpad2 | Down | C | Numpad3 => {
-                            game_state.try_move_player(&mut position, (0, -1));
-                        }
-                        D | Numpad6 | Right => {
-                            game_state.try_move_player(&mut position, (1, 0));
+                    X | Numpad2 | Down => {
+                        if odd_row {
+                            1
+                        } else {
+                            2
                         }
-
-                        Q | Numpad7 => {
-                            game_state.try_move_player(&mut position, (-1, 1));
-                        }
-                        Z | Numpad1 => {
-                            game_state.try_move_player(&mut position, (-1, -1));
-                        }
-
-                        _ => {}
                     }
-                }
+                    _ => continue,
+                };
+                let neighbour = hex::neighbour(
+                    GridTopology::HexEvenRows,
+                    (position.x, position.y),
+                    direction,
+                );
+                let delta = (neighbour.x - position.x, neighbour.y - position.y);
+                game_state.try_move_player(&mut position, delta);
 
                 // If we are standing still or hit something, don't do anything.
                 if previous_position == *position {
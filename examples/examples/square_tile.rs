@@ -1,5 +1,5 @@
-use bevy::{asset::LoadState, prelude::*, sprite::TextureAtlasBuilder, window::WindowMode};
-use bevy_tilemap::prelude::*;
+use bevy::{prelude::*, window::WindowMode};
+use bevy_tilemap::{event::TilesetReadyEvent, prelude::*, tileset::Tileset};
 
 fn main() {
     App::build()
@@ -12,7 +12,7 @@ fn main() {
             mode: WindowMode::Windowed,
             ..Default::default()
         })
-        .init_resource::<SpriteHandles>()
+        .init_resource::<TilesetHandle>()
         .init_resource::<GameState>()
         .add_plugins(DefaultPlugins)
         .add_plugins(TilemapDefaultPlugins)
@@ -23,10 +23,7 @@ fn main() {
 }
 
 #[derive(Default, Clone)]
-struct SpriteHandles {
-    handles: Vec<HandleUntyped>,
-    atlas_loaded: bool,
-}
+struct TilesetHandle(Handle<Tileset>);
 
 #[derive(Default, Clone)]
 struct GameState {
@@ -34,33 +31,15 @@ struct GameState {
     spawned: bool,
 }
 
-fn setup(mut tile_sprite_handles: ResMut<SpriteHandles>, asset_server: Res<AssetServer>) {
-    tile_sprite_handles.handles = asset_server.load_folder("textures").unwrap();
+fn setup(mut tileset_handle: ResMut<TilesetHandle>, asset_server: Res<AssetServer>) {
+    // The manifest lists the textures of the tiles by name.
+    tileset_handle.0 = asset_server.load("square.tileset");
 }
 
-fn load(
-    mut commands: Commands,
-    mut sprite_handles: ResMut<SpriteHandles>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut textures: ResMut<Assets<Texture>>,
-    asset_server: Res<AssetServer>,
-) {
-    if sprite_handles.atlas_loaded {
-        return;
-    }
-
-    // Lets load all our textures from our folder!
-    let mut texture_atlas_builder = TextureAtlasBuilder::default();
-    if let LoadState::Loaded =
-        asset_server.get_group_load_state(sprite_handles.handles.iter().map(|handle| handle.id))
-    {
-        for handle in sprite_handles.handles.iter() {
-            let texture = textures.get(handle).unwrap();
-            texture_atlas_builder.add_texture(handle.clone_weak().typed::<Texture>(), &texture);
-        }
-
-        let texture_atlas = texture_atlas_builder.finish(&mut textures).unwrap();
-        let atlas_handle = texture_atlases.add(texture_atlas);
+fn load(mut commands: Commands, mut ready_events: EventReader<TilesetReadyEvent>) {
+    // Once all the textures are loaded the texture atlas is built for us.
+    for event in ready_events.iter() {
+        let atlas_handle = event.texture_atlas.clone();
 
         let tilemap = Tilemap::builder()
             .auto_chunk()
@@ -90,15 +69,13 @@ fn load(
             .spawn()
             .insert_bundle(tilemap_components)
             .insert(Timer::from_seconds(0.075, true));
-
-        sprite_handles.atlas_loaded = true;
     }
 }
 
 fn build_world(
     mut game_state: ResMut<GameState>,
-    texture_atlases: Res<Assets<TextureAtlas>>,
-    asset_server: Res<AssetServer>,
+    tileset_handle: Res<TilesetHandle>,
+    tilesets: Res<Assets<Tileset>>,
     mut query: Query<&mut Tilemap>,
 ) {
     if game_state.map_loaded {
@@ -109,9 +86,8 @@ fn build_world(
         let chunk_width = (map.width().unwrap() * map.chunk_width()) as i32;
         let chunk_height = (map.height().unwrap() * map.chunk_height()) as i32;
 
-        let floor: Handle<Texture> = asset_server.get_handle("textures/square-floor_alt.png");
-        let texture_atlas = texture_atlases.get(map.texture_atlas()).unwrap();
-        let floor_index = texture_atlas.get_texture_index(&floor).unwrap();
+        let tileset = tilesets.get(&tileset_handle.0).unwrap();
+        let floor_index = tileset.registry().get("floor_alt").unwrap();
 
         let mut tiles = Vec::new();
         for y in 0..chunk_height {
@@ -2,7 +2,7 @@
 
 use crate::{
     lib::*,
-    point::{Point2, Point3},
+    point::{Coord, Point2, Point3},
 };
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -63,37 +63,59 @@ impl Dimension2 {
         self.width * self.height
     }
 
-    /// The minimum X value of this dimension.
-    pub fn x_min(&self) -> i32 {
-        -(self.width as i32) / 2
+    /// The minimum X value of this dimension when centered on the origin.
+    pub fn x_min(&self) -> Coord {
+        -(self.width as Coord) / 2
     }
 
-    /// The minimum Y value of this dimension.
-    pub fn y_min(&self) -> i32 {
-        -(self.height as i32) / 2
+    /// The minimum Y value of this dimension when centered on the origin.
+    pub fn y_min(&self) -> Coord {
+        -(self.height as Coord) / 2
     }
 
-    /// The maximum X value of this dimension.
-    pub fn x_max(&self) -> i32 {
-        self.width as i32 / 2
+    /// The maximum X value of this dimension when centered on the origin.
+    pub fn x_max(&self) -> Coord {
+        self.width as Coord / 2
     }
 
-    /// The maximum Y value of this dimension.
-    pub fn y_max(&self) -> i32 {
-        self.height as i32 / 2
+    /// The maximum Y value of this dimension when centered on the origin.
+    pub fn y_max(&self) -> Coord {
+        self.height as Coord / 2
     }
 
     /// Returns the center of the `Map` as a `Vec2` `Chunk` coordinate.
     pub fn center(&self) -> Point2 {
-        Point2::new((self.width / 2) as i32, (self.height / 2) as i32)
+        Point2::new((self.width / 2) as Coord, (self.height / 2) as Coord)
     }
 
-    /// Checks if a coordinate is valid and inbounds.
+    /// Checks if a coordinate is valid and inbounds, with the dimensions
+    /// centered on the origin. This is the same as [`check_centered_point`].
+    ///
+    /// [`check_centered_point`]: Dimension2::check_centered_point
     ///
     /// # Errors
     ///
     /// If the point does not exist in the dimensions, an error is returned.
     pub fn check_point(&self, point: Point2) -> DimensionResult<()> {
+        self.check_centered_point(point)
+    }
+
+    /// Checks if a coordinate is within the dimensions when centered on the
+    /// origin, from [`x_min`] and [`y_min`] to [`x_max`] and [`y_max`].
+    ///
+    /// Centered points do not encode to indexes, use
+    /// [`check_encodable_point`] for those.
+    ///
+    /// [`x_min`]: Dimension2::x_min
+    /// [`y_min`]: Dimension2::y_min
+    /// [`x_max`]: Dimension2::x_max
+    /// [`y_max`]: Dimension2::y_max
+    /// [`check_encodable_point`]: Dimension2::check_encodable_point
+    ///
+    /// # Errors
+    ///
+    /// If the point does not exist in the dimensions, an error is returned.
+    pub fn check_centered_point(&self, point: Point2) -> DimensionResult<()> {
         if point.x > self.x_max()
             || point.y > self.y_max()
             || point.x < self.x_min()
@@ -104,12 +126,35 @@ impl Dimension2 {
             Ok(())
         }
     }
-    /// Checks if an index is valid and inbounds.
+
+    /// Checks if a coordinate encodes to an index, starting at 0 the same as
+    /// the indexes, so that negative coordinates and coordinates equal to the
+    /// dimensions are out of bounds.
     ///
     /// # Errors
     ///
     /// If the point does not exist in the dimensions, an error is returned.
+    pub fn check_encodable_point(&self, point: Point2) -> DimensionResult<()> {
+        if point.x < 0
+            || point.y < 0
+            || point.x >= self.width as Coord
+            || point.y >= self.height as Coord
+        {
+            Err(ErrorKind::OutOfBounds.into())
+        } else {
+            Ok(())
+        }
+    }
 
+    /// Checks if an index is valid and inbounds.
+    ///
+    /// This lets the index equal to the area through, which decodes to a
+    /// coordinate out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// If the point does not exist in the dimensions, an error is returned.
+    #[deprecated(since = "0.5.0", note = "Please use `check_decodable_index` instead")]
     pub fn check_index(&self, index: usize) -> DimensionResult<()> {
         if index > (self.width * self.height) as usize {
             Err(ErrorKind::OutOfBounds.into())
@@ -117,10 +162,23 @@ impl Dimension2 {
             Ok(())
         }
     }
-    /// Encodes a coordinate and returns an index value, unchecked.
 
+    /// Checks if an index decodes to a coordinate, which is below the area.
+    ///
+    /// # Errors
+    ///
+    /// If the index does not exist in the dimensions, an error is returned.
+    pub fn check_decodable_index(&self, index: usize) -> DimensionResult<()> {
+        if index >= self.area() as usize {
+            Err(ErrorKind::OutOfBounds.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Encodes a coordinate and returns an index value, unchecked.
     pub fn encode_point_unchecked(&self, point: Point2) -> usize {
-        ((point.y * self.width as i32) + point.x) as usize
+        ((point.y * self.width as Coord) + point.x) as usize
     }
 
     /// Encodes a coordinate and returns an index value.
@@ -129,14 +187,29 @@ impl Dimension2 {
     ///
     /// If the point does not exist in the dimensions, an error is returned.
     pub fn encode_point(&self, point: Point2) -> DimensionResult<usize> {
-        self.check_point(point)?;
+        self.check_encodable_point(point)?;
         Ok(self.encode_point_unchecked(point))
     }
 
+    /// Converts a coordinate to an index value, returning an error instead of
+    /// wrapping around to the index of another coordinate.
+    ///
+    /// Unlike [`encode_point_unchecked`], this never returns an index for a
+    /// coordinate which is out of bounds.
+    ///
+    /// [`encode_point_unchecked`]: Dimension2::encode_point_unchecked
+    ///
+    /// # Errors
+    ///
+    /// If the point does not exist in the dimensions, an error is returned.
+    pub fn try_to_index(&self, point: Point2) -> DimensionResult<usize> {
+        self.encode_point(point)
+    }
+
     /// Decodes an index value and returns a coordinate, unchecked.
     pub fn decode_point_unchecked(&self, index: usize) -> Point2 {
-        let y = index as i32 / self.height as i32;
-        let x = index as i32 % self.width as i32;
+        let y = index as Coord / self.width as Coord;
+        let x = index as Coord % self.width as Coord;
         Point2::new(x, y)
     }
 
@@ -146,7 +219,7 @@ impl Dimension2 {
     ///
     /// If the point does not exist in the dimensions, an error is returned.
     pub fn decode_point(&self, index: usize) -> DimensionResult<Point2> {
-        self.check_index(index)?;
+        self.check_decodable_index(index)?;
         Ok(self.decode_point_unchecked(index))
     }
 }
@@ -169,6 +242,8 @@ impl From<Extent3d> for Dimension2 {
     }
 }
 
+/// Implements `From` glam vectors of any number type for [`Dimension2`], casting each
+/// component.
 macro_rules! dimension2_glam_impl {
     ($vec: ty) => {
         impl From<$vec> for Dimension2 {
@@ -191,8 +266,11 @@ macro_rules! dimension2_glam_impl {
 dimension2_glam_impl!(Vec2);
 dimension2_glam_impl!(Vec3);
 
+/// Implements `From` arrays of any number type for [`Dimension2`], casting each
+/// component.
 macro_rules! dimension2_arr_impl {
     ($arr: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$arr> for Dimension2 {
             fn from(arr: $arr) -> Dimension2 {
                 Dimension2 {
@@ -202,6 +280,7 @@ macro_rules! dimension2_arr_impl {
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$arr> for Dimension2 {
             fn from(arr: &$arr) -> Dimension2 {
                 Dimension2::from(*arr)
@@ -232,8 +311,11 @@ dimension2_arr_impl!([u32; 3]);
 dimension2_arr_impl!([u16; 3]);
 dimension2_arr_impl!([u8; 3]);
 
+/// Implements `From` tuples of any number type for [`Dimension2`], casting each
+/// component.
 macro_rules! dimension2_tuple_impl {
     ($t: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$t> for Dimension2 {
             fn from(int: $t) -> Dimension2 {
                 Dimension2 {
@@ -243,6 +325,7 @@ macro_rules! dimension2_tuple_impl {
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$t> for Dimension2 {
             fn from(int: &$t) -> Dimension2 {
                 Dimension2 {
@@ -377,21 +460,48 @@ impl Dimension3 {
     /// Returns the center as a `Vec3`.
     pub fn center(&self) -> Point3 {
         Point3::new(
-            self.width as i32 / 2,
-            self.height as i32 / 2,
-            self.depth as i32 / 2,
+            self.width as Coord / 2,
+            self.height as Coord / 2,
+            self.depth as Coord / 2,
         )
     }
 
     /// Checks if a given coordinate is within bounds of the `Chunk`.
     ///
+    /// This lets negative coordinates and coordinates equal to the dimensions
+    /// through, which encode to the index of another tile.
+    ///
     /// # Errors
     ///
     /// If the point does not exist in the dimensions, an error is returned.
+    #[deprecated(since = "0.5.0", note = "Please use `check_encodable_point` instead")]
     pub fn check_point(&self, point: Point3) -> DimensionResult<()> {
-        if point.x > self.width as i32
-            || point.y > self.height as i32
-            || point.z > self.depth as i32
+        if point.x > self.width as Coord
+            || point.y > self.height as Coord
+            || point.z > self.depth as Coord
+        {
+            Err(ErrorKind::OutOfBounds.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks if a coordinate encodes to an index of the `Chunk`.
+    ///
+    /// Negative coordinates and coordinates past the maximum values are out
+    /// of bounds, as they would otherwise encode to the index of another
+    /// tile.
+    ///
+    /// # Errors
+    ///
+    /// If the point does not exist in the dimensions, an error is returned.
+    pub fn check_encodable_point(&self, point: Point3) -> DimensionResult<()> {
+        if point.x < 0
+            || point.y < 0
+            || point.z < 0
+            || point.x >= self.width as Coord
+            || point.y >= self.height as Coord
+            || point.z >= self.depth as Coord
         {
             Err(ErrorKind::OutOfBounds.into())
         } else {
@@ -401,9 +511,13 @@ impl Dimension3 {
 
     /// Checks if a given index is within bounds of the `Chunk`.
     ///
+    /// This lets the index equal to the area through, which decodes to a
+    /// coordinate out of bounds.
+    ///
     /// # Errors
     ///
     /// If the point does not exist in the dimensions, an error is returned.
+    #[deprecated(since = "0.5.0", note = "Please use `check_decodable_index` instead")]
     pub fn check_index(&self, index: usize) -> DimensionResult<()> {
         if index > (self.width * self.height * self.depth) as usize {
             Err(ErrorKind::OutOfBounds.into())
@@ -412,10 +526,24 @@ impl Dimension3 {
         }
     }
 
+    /// Checks if an index decodes to a coordinate of the `Chunk`, which is
+    /// below the area.
+    ///
+    /// # Errors
+    ///
+    /// If the index does not exist in the dimensions, an error is returned.
+    pub fn check_decodable_index(&self, index: usize) -> DimensionResult<()> {
+        if index >= self.area() as usize {
+            Err(ErrorKind::OutOfBounds.into())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Encodes a Vec3 coordinate to an usize index to use in the Tile vector, unchecked.
     pub fn encode_point_unchecked(&self, point: Point3) -> usize {
-        ((point.z * self.width as i32 * self.height as i32)
-            + (point.y * self.width as i32)
+        ((point.z * self.width as Coord * self.height as Coord)
+            + (point.y * self.width as Coord)
             + point.x) as usize
     }
 
@@ -425,10 +553,25 @@ impl Dimension3 {
     ///
     /// If the point does not exist in the dimensions, an error is returned.
     pub fn encode_point(&self, point: Point3) -> DimensionResult<usize> {
-        self.check_point(point)?;
+        self.check_encodable_point(point)?;
         Ok(self.encode_point_unchecked(point))
     }
 
+    /// Converts a coordinate to an index value, returning an error instead of
+    /// wrapping around to the index of another coordinate.
+    ///
+    /// Unlike [`encode_point_unchecked`], this never returns an index for a
+    /// coordinate which is out of bounds.
+    ///
+    /// [`encode_point_unchecked`]: Dimension3::encode_point_unchecked
+    ///
+    /// # Errors
+    ///
+    /// If the point does not exist in the dimensions, an error is returned.
+    pub fn try_to_index(&self, point: Point3) -> DimensionResult<usize> {
+        self.encode_point(point)
+    }
+
     /// Decodes a Tile index and returns the coordinates in the Chunk, unchecked.
     ///
     /// # Errors
@@ -437,9 +580,9 @@ impl Dimension3 {
     pub fn decode_coord_unchecked(&self, index: usize) -> Point3 {
         let z = index as u32 / (self.width * self.height);
         let index = index as u32 - (z * self.width * self.height);
-        let y = self.y_max() - (index / self.width);
+        let y = index / self.width;
         let x = index % self.width;
-        Point3::new(x as i32, y as i32, z as i32)
+        Point3::new(x as Coord, y as Coord, z as Coord)
     }
 
     /// Decodes a `Tile` index and returns the coordinates in the `Chunk`.
@@ -447,9 +590,8 @@ impl Dimension3 {
     /// # Errors
     ///
     /// If the point does not exist in the dimensions, an error is returned.
-
     pub fn decode_coord(&self, index: usize) -> DimensionResult<Point3> {
-        self.check_index(index)?;
+        self.check_decodable_index(index)?;
         Ok(self.decode_coord_unchecked(index))
     }
 }
@@ -488,6 +630,8 @@ impl From<Dimension3> for Extent3d {
     }
 }
 
+/// Implements `From` glam vectors of any number type for [`Dimension3`], casting each
+/// component.
 macro_rules! dimension3_glam_impl {
     ($vec: ty) => {
         impl From<$vec> for Dimension3 {
@@ -510,8 +654,11 @@ macro_rules! dimension3_glam_impl {
 
 dimension3_glam_impl!(Vec3);
 
+/// Implements `From` arrays of any number type for [`Dimension3`], casting each
+/// component.
 macro_rules! dimension3_arr_impl {
     ($arr: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$arr> for Dimension3 {
             fn from(arr: $arr) -> Dimension3 {
                 Dimension3 {
@@ -522,6 +669,7 @@ macro_rules! dimension3_arr_impl {
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$arr> for Dimension3 {
             fn from(arr: &$arr) -> Dimension3 {
                 Dimension3::from(*arr)
@@ -541,8 +689,11 @@ dimension3_arr_impl!([u32; 3]);
 dimension3_arr_impl!([u16; 3]);
 dimension3_arr_impl!([u8; 3]);
 
+/// Implements `From` tuples of any number type for [`Dimension3`], casting each
+/// component.
 macro_rules! dimension3_tuple_impl {
     ($t: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$t> for Dimension3 {
             fn from(int: $t) -> Dimension3 {
                 Dimension3 {
@@ -553,6 +704,7 @@ macro_rules! dimension3_tuple_impl {
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$t> for Dimension3 {
             fn from(int: &$t) -> Dimension3 {
                 Dimension3 {
@@ -663,3 +815,61 @@ impl SubAssign for Dimension3 {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimension2_round_trip() {
+        let dimensions = Dimension2::new(4, 3);
+        for index in 0..dimensions.area() as usize {
+            let point = dimensions.decode_point(index).unwrap();
+            assert_eq!(dimensions.try_to_index(point), Ok(index));
+        }
+        assert!(dimensions.check_decodable_index(12).is_err());
+        assert!(dimensions.decode_point(12).is_err());
+    }
+
+    #[test]
+    fn test_dimension2_out_of_bounds() {
+        let dimensions = Dimension2::new(4, 3);
+        // These would wrap around to the index of another point.
+        for point in [(-1, 0), (4, 0), (0, -1), (0, 3)].iter() {
+            let point = Point2::from(*point);
+            assert!(dimensions.check_encodable_point(point).is_err());
+            assert!(dimensions.try_to_index(point).is_err());
+        }
+        // Centered on the origin the range is from -2 to 2 and -1 to 1, which
+        // is what `check_point` has always checked.
+        for point in [(-2, -1), (2, 1)].iter() {
+            let point = Point2::from(*point);
+            assert!(dimensions.check_centered_point(point).is_ok());
+            assert!(dimensions.check_point(point).is_ok());
+        }
+        assert!(dimensions.check_centered_point(Point2::new(0, 2)).is_err());
+        assert!(dimensions.check_point(Point2::new(0, 2)).is_err());
+    }
+
+    #[test]
+    fn test_dimension3_round_trip() {
+        let dimensions = Dimension3::new(4, 3, 2);
+        for index in 0..dimensions.area() as usize {
+            let point = dimensions.decode_coord(index).unwrap();
+            assert_eq!(dimensions.try_to_index(point), Ok(index));
+        }
+        assert_eq!(dimensions.decode_coord_unchecked(5), Point3::new(1, 1, 0));
+        assert!(dimensions.check_decodable_index(24).is_err());
+        assert!(dimensions.decode_coord(24).is_err());
+    }
+
+    #[test]
+    fn test_dimension3_out_of_bounds() {
+        let dimensions = Dimension3::new(4, 3, 2);
+        for point in [(-1, 0, 0), (4, 0, 0), (0, 3, 0), (0, 0, 2), (0, 0, -1)].iter() {
+            let point = Point3::from(*point);
+            assert!(dimensions.check_encodable_point(point).is_err());
+            assert!(dimensions.try_to_index(point).is_err());
+        }
+    }
+}
@@ -29,6 +29,9 @@
     clippy::print_stdout,
     clippy::unwrap_in_result
 )]
+// Casts to `Coord` are only lossless with the `i64_coordinates` feature, where
+// `Coord::from` would not build without it.
+#![cfg_attr(feature = "i64_coordinates", allow(clippy::cast_lossless))]
 
 #[no_implicit_prelude]
 pub mod dimension;
@@ -72,4 +75,7 @@ mod lib {
     #[cfg(debug_assertions)]
     #[allow(unused_imports)]
     pub(crate) use std::println;
+
+    #[cfg(test)]
+    pub(crate) use std::{assert, assert_eq};
 }
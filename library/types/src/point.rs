@@ -2,19 +2,34 @@
 
 use crate::lib::*;
 
+/// The integer type of a coordinate.
+///
+/// This is `i32` by default, which limits a tilemap to about 2^31 tiles in
+/// each direction. Enabling the `i64_coordinates` feature changes it to `i64`
+/// for tilemaps which are too big for that, such as endless procedurally
+/// generated worlds.
+#[cfg(not(feature = "i64_coordinates"))]
+pub type Coord = i32;
+
+/// The integer type of a coordinate.
+///
+/// This is `i64` as the `i64_coordinates` feature is enabled.
+#[cfg(feature = "i64_coordinates")]
+pub type Coord = i64;
+
 /// A point which contains a X,Y coordinate.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Point2 {
     /// X value of a point.
-    pub x: i32,
+    pub x: Coord,
     /// Y value of a point.
-    pub y: i32,
+    pub y: Coord,
 }
 
 impl Point2 {
     /// Constructs a new point with a X,Y coordinate.
-    pub fn new(x: i32, y: i32) -> Point2 {
+    pub fn new(x: Coord, y: Coord) -> Point2 {
         Point2 { x, y }
     }
 }
@@ -43,13 +58,15 @@ impl From<&Point2> for Vec2 {
     }
 }
 
+/// Implements `From` glam vectors of any number type for [`Point2`], casting each
+/// component.
 macro_rules! point2_glam_impl {
     ($vec: ty) => {
         impl From<$vec> for Point2 {
             fn from(vec: $vec) -> Point2 {
                 Point2 {
-                    x: vec.x as i32,
-                    y: vec.y as i32,
+                    x: vec.x as Coord,
+                    y: vec.y as Coord,
                 }
             }
         }
@@ -57,8 +74,8 @@ macro_rules! point2_glam_impl {
         impl From<&$vec> for Point2 {
             fn from(vec: &$vec) -> Point2 {
                 Point2 {
-                    x: vec.x as i32,
-                    y: vec.y as i32,
+                    x: vec.x as Coord,
+                    y: vec.y as Coord,
                 }
             }
         }
@@ -68,22 +85,26 @@ macro_rules! point2_glam_impl {
 point2_glam_impl!(Vec2);
 point2_glam_impl!(Vec3);
 
+/// Implements `From` arrays of any number type for [`Point2`], casting each
+/// component.
 macro_rules! point2_arr_impl {
     ($arr: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$arr> for Point2 {
             fn from(arr: $arr) -> Point2 {
                 Point2 {
-                    x: arr[0] as i32,
-                    y: arr[1] as i32,
+                    x: arr[0] as Coord,
+                    y: arr[1] as Coord,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$arr> for Point2 {
             fn from(arr: &$arr) -> Point2 {
                 Point2 {
-                    x: arr[0] as i32,
-                    y: arr[1] as i32,
+                    x: arr[0] as Coord,
+                    y: arr[1] as Coord,
                 }
             }
         }
@@ -112,22 +133,26 @@ point2_arr_impl!([u32; 3]);
 point2_arr_impl!([u16; 3]);
 point2_arr_impl!([u8; 3]);
 
+/// Implements `From` tuples of any number type for [`Point2`], casting each
+/// component.
 macro_rules! point2_tuple_impl {
     ($t: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$t> for Point2 {
             fn from(int: $t) -> Point2 {
                 Point2 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$t> for Point2 {
             fn from(int: &$t) -> Point2 {
                 Point2 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
                 }
             }
         }
@@ -252,16 +277,16 @@ impl SubAssign for Point2 {
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Point3 {
     /// X value of a point.
-    pub x: i32,
+    pub x: Coord,
     /// Y value of a point.
-    pub y: i32,
+    pub y: Coord,
     /// Z value of a point.
-    pub z: i32,
+    pub z: Coord,
 }
 
 impl Point3 {
     /// Constructs a new point with a X,Y,Z coordinate.
-    pub fn new(x: i32, y: i32, z: i32) -> Point3 {
+    pub fn new(x: Coord, y: Coord, z: Coord) -> Point3 {
         Point3 { x, y, z }
     }
 
@@ -321,22 +346,22 @@ impl From<&Point3> for Vec3 {
 
 impl From<Vec2> for Point3 {
     fn from(vec: Vec2) -> Point3 {
-        Point3::new(vec.x as i32, vec.y as i32, 0)
+        Point3::new(vec.x as Coord, vec.y as Coord, 0)
     }
 }
 
 impl From<&Vec2> for Point3 {
     fn from(vec: &Vec2) -> Point3 {
-        Point3::new(vec.x as i32, vec.y as i32, 0)
+        Point3::new(vec.x as Coord, vec.y as Coord, 0)
     }
 }
 
 impl From<Vec3> for Point3 {
     fn from(vec: Vec3) -> Point3 {
         Point3 {
-            x: vec.x as i32,
-            y: vec.y as i32,
-            z: vec.z as i32,
+            x: vec.x as Coord,
+            y: vec.y as Coord,
+            z: vec.z as Coord,
         }
     }
 }
@@ -344,30 +369,34 @@ impl From<Vec3> for Point3 {
 impl From<&Vec3> for Point3 {
     fn from(vec: &Vec3) -> Point3 {
         Point3 {
-            x: vec.x as i32,
-            y: vec.y as i32,
-            z: vec.z as i32,
+            x: vec.x as Coord,
+            y: vec.y as Coord,
+            z: vec.z as Coord,
         }
     }
 }
 
+/// Implements `From` arrays of any number type for [`Point3`], casting each
+/// component.
 macro_rules! point2_arr_impl {
     ($arr: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$arr> for Point3 {
             fn from(arr: $arr) -> Point3 {
                 Point3 {
-                    x: arr[0] as i32,
-                    y: arr[1] as i32,
+                    x: arr[0] as Coord,
+                    y: arr[1] as Coord,
                     z: 0,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$arr> for Point3 {
             fn from(arr: &$arr) -> Point3 {
                 Point3 {
-                    x: arr[0] as i32,
-                    y: arr[1] as i32,
+                    x: arr[0] as Coord,
+                    y: arr[1] as Coord,
                     z: 0,
                 }
             }
@@ -386,24 +415,28 @@ point2_arr_impl!([u32; 2]);
 point2_arr_impl!([u16; 2]);
 point2_arr_impl!([u8; 2]);
 
+/// Implements `From` arrays of any number type for [`Point3`], casting each
+/// component.
 macro_rules! point3_arr_impl {
     ($arr: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$arr> for Point3 {
             fn from(vec: $arr) -> Point3 {
                 Point3 {
-                    x: vec[0] as i32,
-                    y: vec[1] as i32,
-                    z: vec[2] as i32,
+                    x: vec[0] as Coord,
+                    y: vec[1] as Coord,
+                    z: vec[2] as Coord,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$arr> for Point3 {
             fn from(vec: &$arr) -> Point3 {
                 Point3 {
-                    x: vec[0] as i32,
-                    y: vec[1] as i32,
-                    z: vec[2] as i32,
+                    x: vec[0] as Coord,
+                    y: vec[1] as Coord,
+                    z: vec[2] as Coord,
                 }
             }
         }
@@ -421,23 +454,27 @@ point3_arr_impl!([u32; 3]);
 point3_arr_impl!([u16; 3]);
 point3_arr_impl!([u8; 3]);
 
+/// Implements `From` tuples of any number type for [`Point3`], casting each
+/// component.
 macro_rules! point3_tuple2_impl {
     ($t: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$t> for Point3 {
             fn from(int: $t) -> Point3 {
                 Point3 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
                     z: 0,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$t> for Point3 {
             fn from(int: &$t) -> Point3 {
                 Point3 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
                     z: 0,
                 }
             }
@@ -456,24 +493,28 @@ point3_tuple2_impl!((u32, u32));
 point3_tuple2_impl!((u16, u16));
 point3_tuple2_impl!((u8, u8));
 
+/// Implements `From` tuples of any number type for [`Point3`], casting each
+/// component.
 macro_rules! point3_impl {
     ($t: ty) => {
+        #[allow(clippy::cast_lossless)]
         impl From<$t> for Point3 {
             fn from(int: $t) -> Point3 {
                 Point3 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
-                    z: int.2 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
+                    z: int.2 as Coord,
                 }
             }
         }
 
+        #[allow(clippy::cast_lossless)]
         impl From<&$t> for Point3 {
             fn from(int: &$t) -> Point3 {
                 Point3 {
-                    x: int.0 as i32,
-                    y: int.1 as i32,
-                    z: int.2 as i32,
+                    x: int.0 as Coord,
+                    y: int.1 as Coord,
+                    z: int.2 as Coord,
                 }
             }
         }
@@ -0,0 +1,268 @@
+//! Copying regions of tiles and stamping them elsewhere.
+//!
+//! [`Tilemap::copy_region`] copies every tile of a rectangular region, across
+//! sprite layers and z depths, into a [`TileBrush`]. The brush can be flipped
+//! or rotated and then stamped anywhere with [`Tilemap::paste`], which makes
+//! it useful for prefab rooms or as an undo buffer of an editor. With the
+//! `serialize` feature brushes can be saved too.
+//!
+//! A brush remembers the topology of the tilemap it was copied from. Flipping
+//! a brush moves its tiles as on a square grid, so on hex topologies a flipped
+//! brush does not keep its shape, and rotating a brush of a hex topology is an
+//! error. Copying and pasting works on every topology.
+//!
+//! [`Tilemap::copy_region`]: crate::tilemap::Tilemap::copy_region
+//! [`Tilemap::paste`]: crate::tilemap::Tilemap::paste
+//!
+//! # Stamping a room
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 1, ..Default::default() }).unwrap();
+//! tilemap.insert_tile(Tile { point: (2, 1), sprite_index: 2, ..Default::default() }).unwrap();
+//!
+//! let brush = tilemap.copy_region((0, 0), (2, 1), None);
+//! assert_eq!((brush.width(), brush.height()), (3, 2));
+//!
+//! tilemap.paste(&brush.rotated().unwrap(), (5, 5)).unwrap();
+//!
+//! assert_eq!(tilemap.get_tile((5, 7), 0).map(|tile| tile.index), Some(1));
+//! assert_eq!(tilemap.get_tile((6, 5), 0).map(|tile| tile.index), Some(2));
+//! ```
+
+use crate::{
+    chunk::RawTile,
+    lib::*,
+    prelude::GridTopology,
+    tile::FlipAxis,
+    tilemap::{ErrorKind, TilemapResult},
+};
+
+/// The tiles of a rectangular region, relative to its bottom left corner.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileBrush {
+    /// The width of the region in tiles.
+    width: u32,
+    /// The height of the region in tiles.
+    height: u32,
+    /// The topology of the tilemap the region was copied from.
+    #[cfg_attr(feature = "serde", serde(default = "default_topology"))]
+    topology: GridTopology,
+    /// The tiles by their point in the region and sprite order.
+    tiles: Vec<(Point3, usize, RawTile)>,
+}
+
+/// The topology of brushes saved before brushes had one.
+#[cfg(feature = "serde")]
+fn default_topology() -> GridTopology {
+    GridTopology::Square
+}
+
+impl Default for TileBrush {
+    fn default() -> TileBrush {
+        TileBrush::new(0, 0, GridTopology::Square, Vec::new())
+    }
+}
+
+impl TileBrush {
+    /// Constructs a brush of a region with tiles at points relative to its
+    /// bottom left corner.
+    pub(crate) fn new(
+        width: u32,
+        height: u32,
+        topology: GridTopology,
+        tiles: Vec<(Point3, usize, RawTile)>,
+    ) -> TileBrush {
+        TileBrush {
+            width,
+            height,
+            topology,
+            tiles,
+        }
+    }
+
+    /// Returns the width of the region in tiles.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the region in tiles.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns an iterator over the tiles, with their point relative to the
+    /// bottom left corner of the region and their sprite order.
+    pub fn tiles(&self) -> impl Iterator<Item = (Point3, usize, &RawTile)> {
+        self.tiles
+            .iter()
+            .map(|(point, sprite_order, tile)| (*point, *sprite_order, tile))
+    }
+
+    /// Returns the topology of the tilemap the region was copied from.
+    pub fn topology(&self) -> GridTopology {
+        self.topology
+    }
+
+    /// Returns the number of tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns `true` if the brush has no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the brush mirrored across an axis, with its sprites mirrored
+    /// too.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// let brush = tilemap.copy_region((0, 0), (3, 0), None).flipped(FlipAxis::X);
+    /// tilemap.paste(&brush, (0, 1)).unwrap();
+    ///
+    /// let tile = tilemap.get_tile((3, 1), 0).unwrap();
+    /// assert_eq!((tile.index, tile.flip_x), (1, true));
+    /// ```
+    pub fn flipped(&self, axis: FlipAxis) -> TileBrush {
+        let (width, height) = (self.width as Coord, self.height as Coord);
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|(point, sprite_order, tile)| {
+                let point = match axis {
+                    FlipAxis::X => Point3::new(width - 1 - point.x, point.y, point.z),
+                    FlipAxis::Y => Point3::new(point.x, height - 1 - point.y, point.z),
+                };
+                (point, *sprite_order, tile.mirrored(axis))
+            })
+            .collect();
+        TileBrush::new(self.width, self.height, self.topology, tiles)
+    }
+
+    /// Returns the brush rotated a quarter turn clockwise, with its sprites
+    /// rotated too.
+    ///
+    /// The width and height of the rotated brush are swapped, and so are the
+    /// scales of its sprites along each axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the brush was copied from a hex topology, where a
+    /// quarter turn does not map tiles onto tiles.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexY)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let brush = tilemap.copy_region((0, 0), (3, 0), None);
+    /// assert!(brush.rotated().is_err());
+    /// ```
+    pub fn rotated(&self) -> TilemapResult<TileBrush> {
+        if self.topology != GridTopology::Square {
+            return Err(ErrorKind::UnsupportedTopology(self.topology).into());
+        }
+        let width = self.width as Coord;
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|(point, sprite_order, tile)| {
+                let mut tile = *tile;
+                tile.rotation = (tile.rotation + 1) & 0b11;
+                tile.offset = Vec2::new(tile.offset.y, -tile.offset.x);
+                tile.scale = Vec2::new(tile.scale.y, tile.scale.x);
+                let point = Point3::new(point.y, width - 1 - point.x, point.z);
+                (point, *sprite_order, tile)
+            })
+            .collect();
+        Ok(TileBrush::new(
+            self.height,
+            self.width,
+            self.topology,
+            tiles,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brush_transforms() {
+        let tile = RawTile {
+            index: 1,
+            offset: Vec2::new(2.0, 0.0),
+            scale: Vec2::new(2.0, 0.5),
+            ..Default::default()
+        };
+        let brush = TileBrush::new(
+            3,
+            2,
+            GridTopology::Square,
+            vec![(Point3::new(0, 1, 0), 0, tile)],
+        );
+
+        let flipped = brush.flipped(FlipAxis::X);
+        let (point, _, flipped_tile) = flipped.tiles().next().unwrap();
+        assert_eq!(point, Point3::new(2, 1, 0));
+        assert!(flipped_tile.flip_x);
+        assert_eq!(flipped_tile.offset, Vec2::new(-2.0, 0.0));
+
+        let rotated = brush.rotated().unwrap();
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        let (point, _, rotated_tile) = rotated.tiles().next().unwrap();
+        assert_eq!(point, Point3::new(1, 2, 0));
+        assert_eq!(rotated_tile.rotation, 1);
+        assert_eq!(rotated_tile.offset, Vec2::new(0.0, -2.0));
+        // The sprite is stretched along the other axis after a quarter turn.
+        assert_eq!(rotated_tile.scale, Vec2::new(0.5, 2.0));
+
+        let full_turn = (0..4).try_fold(brush.clone(), |brush, _| brush.rotated());
+        assert_eq!(full_turn.unwrap().tiles, brush.tiles);
+        assert_eq!(brush.flipped(FlipAxis::Y).flipped(FlipAxis::Y), brush);
+    }
+
+    #[test]
+    fn test_rotate_hex_brush() {
+        let brush = TileBrush::new(1, 1, GridTopology::HexX, Vec::new());
+        assert_eq!(
+            brush.rotated(),
+            Err(ErrorKind::UnsupportedTopology(GridTopology::HexX).into())
+        );
+        assert!(brush.flipped(FlipAxis::X).rotated().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::lib::*;
+use crate::{chunk::render, lib::*};
 use ::bevy_ecs;
 use ::std;
 
@@ -6,6 +6,122 @@ use ::std;
 #[derive(Debug, Default, PartialEq, Eq)]
 pub(crate) struct Modified(pub usize);
 
+/// A component with the tiles of a chunk which changed since its mesh was
+/// last updated by z depth, sprite order and index, or `None` if the whole
+/// mesh needs updating.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct DirtyTiles(pub Option<HashSet<(usize, usize, usize)>>);
+
+impl DirtyTiles {
+    /// Adds the dirty tiles of a chunk, where `None` means the whole mesh.
+    pub(crate) fn merge(&mut self, tiles: Option<&HashSet<(usize, usize, usize)>>) {
+        match tiles {
+            Some(tiles) => {
+                if let Some(dirty) = &mut self.0 {
+                    dirty.extend(tiles.iter().copied());
+                }
+            }
+            None => self.0 = None,
+        }
+    }
+}
+
+/// A component with the time in seconds of the chunk shaders, which moves the
+/// vertices of wave layers.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct ChunkTime(pub f32);
+
+impl RenderResources for ChunkTime {
+    fn render_resources_len(&self) -> usize {
+        1
+    }
+
+    fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
+        match index {
+            0 => Some(&self.0),
+            _ => None,
+        }
+    }
+
+    fn get_render_resource_name(&self, index: usize) -> Option<&str> {
+        match index {
+            0 => Some(render::UNIFORM_CHUNK_TIME.name),
+            _ => None,
+        }
+    }
+
+    fn iter(&self) -> RenderResourceIterator<'_> {
+        RenderResourceIterator::new(self)
+    }
+}
+
+/// A component with the secondary texture of a chunk, such as a normal or
+/// emissive map laid out like its texture atlas.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct SecondaryTexture(pub Option<Handle<Texture>>);
+
+impl RenderResources for SecondaryTexture {
+    fn render_resources_len(&self) -> usize {
+        1
+    }
+
+    fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
+        match index {
+            0 => Some(&self.0),
+            _ => None,
+        }
+    }
+
+    fn get_render_resource_name(&self, index: usize) -> Option<&str> {
+        match index {
+            0 => Some(render::UNIFORM_SECONDARY_TEXTURE.name),
+            _ => None,
+        }
+    }
+
+    fn iter(&self) -> RenderResourceIterator<'_> {
+        RenderResourceIterator::new(self)
+    }
+}
+
+/// A component with the color grading lookup tables of a chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChunkColorGrading(pub Handle<Texture>);
+
+impl Default for ChunkColorGrading {
+    fn default() -> ChunkColorGrading {
+        ChunkColorGrading(render::IDENTITY_COLOR_GRADING.typed())
+    }
+}
+
+impl RenderResources for ChunkColorGrading {
+    fn render_resources_len(&self) -> usize {
+        1
+    }
+
+    fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
+        match index {
+            0 => Some(&self.0),
+            _ => None,
+        }
+    }
+
+    fn get_render_resource_name(&self, index: usize) -> Option<&str> {
+        match index {
+            0 => Some(render::UNIFORM_COLOR_GRADING_TEXTURE.name),
+            _ => None,
+        }
+    }
+
+    fn iter(&self) -> RenderResourceIterator<'_> {
+        RenderResourceIterator::new(self)
+    }
+}
+
+/// A component with the id of the texture atlas a chunk entity renders.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct AtlasId(pub usize);
+
 /// A component bundle for `Chunk` entities.
 #[derive(Bundle)]
 pub(crate) struct ChunkBundle {
@@ -13,6 +129,8 @@ pub(crate) struct ChunkBundle {
     pub point: Point2,
     /// The handle for a TextureAtlas which contains multiple textures.
     pub texture_atlas: Handle<TextureAtlas>,
+    /// The id of the texture atlas in the tilemap.
+    pub atlas: AtlasId,
     /// A component that indicates how to draw a component.
     pub draw: Draw,
     /// A component that indicates if the component is visible.
@@ -30,4 +148,12 @@ pub(crate) struct ChunkBundle {
     pub global_transform: GlobalTransform,
     /// If a layer has been modified, all are set here.
     pub modified: Modified,
+    /// The tiles which need updating in the mesh.
+    pub dirty_tiles: DirtyTiles,
+    /// The time of the chunk shaders.
+    pub time: ChunkTime,
+    /// The secondary texture of the chunk shaders.
+    pub secondary_texture: SecondaryTexture,
+    /// The color grading lookup tables of the chunk shaders.
+    pub color_grading: ChunkColorGrading,
 }
@@ -1,4 +1,8 @@
-use crate::{chunk::raw_tile::RawTile, lib::*};
+use crate::{
+    chunk::raw_tile::{RawTile, TileAttributes},
+    lib::*,
+    property::TileProperties,
+};
 
 /// Common methods for layers in a chunk.
 pub(super) trait Layer: 'static {
@@ -17,11 +21,95 @@ pub(super) trait Layer: 'static {
     /// Gets all the tile indices in the layer that exist.
     fn get_tile_indices(&self) -> Vec<usize>;
 
+    /// Returns `true` if a tile exists at an index.
+    fn is_occupied(&self, index: usize) -> bool;
+
+    /// Returns an iterator over the tiles in the layer that exist along with
+    /// their indices.
+    fn iter_tiles(&self) -> Box<dyn Iterator<Item = (usize, &RawTile)> + '_>;
+
     /// Clears a layer of all sprites.
     fn clear(&mut self);
 
-    /// Takes all the tiles in the layer and returns attributes for the renderer.
-    fn tiles_to_attributes(&self, dimension: Dimension3) -> (Vec<f32>, Vec<[f32; 4]>);
+    /// Takes all the tiles in the layer and adds their attributes for the
+    /// renderer to the end of the attributes.
+    ///
+    /// Tiles which are not in the texture atlas are left transparent.
+    fn extend_attributes(
+        &self,
+        dimension: Dimension3,
+        atlas: usize,
+        attributes: &mut TileAttributes,
+    );
+
+    /// Frees the storage which is not needed for the tiles in the layer.
+    fn compact(&mut self);
+
+    /// Returns the number of bytes the tiles of the layer take up on the heap.
+    fn heap_size(&self) -> usize;
+}
+
+/// A bitset of the indices of a layer which have a tile.
+///
+/// An empty bitset has not been built yet, such as after a layer was
+/// deserialized, and is built from the tiles before the layer changes.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub(super) struct Occupancy {
+    /// The bits of the indices, 64 to a word.
+    words: Vec<u64>,
+}
+
+impl Occupancy {
+    /// Constructs a bitset of an area without any tiles.
+    fn new(area: usize) -> Occupancy {
+        Occupancy {
+            words: vec![0; area.div_ceil(64)],
+        }
+    }
+
+    /// Constructs a bitset of an area with the indices of its tiles.
+    fn from_indices<I: IntoIterator<Item = usize>>(area: usize, indices: I) -> Occupancy {
+        let mut occupancy = Occupancy::new(area);
+        for index in indices {
+            occupancy.insert(index);
+        }
+        occupancy
+    }
+
+    /// Returns `true` if the bitset has not been built.
+    fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns `true` if an index has a tile.
+    fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Marks an index as having a tile.
+    fn insert(&mut self, index: usize) {
+        let word = index / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        if let Some(word) = self.words.get_mut(word) {
+            *word |= 1 << (index % 64);
+        }
+    }
+
+    /// Marks an index as not having a tile.
+    fn remove(&mut self, index: usize) {
+        if let Some(word) = self.words.get_mut(index / 64) {
+            *word &= !(1 << (index % 64));
+        }
+    }
+
+    /// Returns the number of bytes the bitset takes up on the heap.
+    fn heap_size(&self) -> usize {
+        self.words.capacity() * mem::size_of::<u64>()
+    }
 }
 
 /// A layer with dense sprite tiles.
@@ -35,12 +123,33 @@ pub(super) struct DenseLayer {
     tiles: Vec<RawTile>,
     /// A count of the tiles to keep track if layer is empty or not.
     tile_count: usize,
+    /// The number of tiles in the layer, which are allocated when the first
+    /// tile is set if the layer was cleared or compacted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    area: usize,
+    /// The indices which have a tile.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    occupied: Occupancy,
 }
 
 impl Layer for DenseLayer {
     fn set_tile(&mut self, index: usize, tile: RawTile) {
+        self.build_occupancy();
+        if self.tiles.is_empty() {
+            let empty = RawTile {
+                index: 0,
+                color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                ..Default::default()
+            };
+            self.tiles = vec![empty; self.area];
+        }
         if let Some(inner_tile) = self.tiles.get_mut(index) {
             self.tile_count += 1;
+            if tile.color.a() == 0.0 {
+                self.occupied.remove(index);
+            } else {
+                self.occupied.insert(index);
+            }
             *inner_tile = tile;
         } else {
             warn!(
@@ -51,10 +160,12 @@ impl Layer for DenseLayer {
     }
 
     fn remove_tile(&mut self, index: usize) {
+        self.build_occupancy();
         if let Some(tile) = self.tiles.get_mut(index) {
             if self.tile_count != 0 {
                 self.tile_count -= 1;
                 tile.color.set_a(0.0);
+                self.occupied.remove(index);
             }
         }
     }
@@ -90,12 +201,56 @@ impl Layer for DenseLayer {
         indices
     }
 
+    fn is_occupied(&self, index: usize) -> bool {
+        if self.occupied.is_empty() {
+            return self.get_tile(index).is_some();
+        }
+        self.occupied.contains(index)
+    }
+
+    fn iter_tiles(&self) -> Box<dyn Iterator<Item = (usize, &RawTile)> + '_> {
+        Box::new(
+            self.tiles
+                .iter()
+                .enumerate()
+                .filter(|(_, tile)| tile.color.a() != 0.0),
+        )
+    }
+
     fn clear(&mut self) {
         self.tiles.clear();
+        self.occupied = Occupancy::default();
     }
 
-    fn tiles_to_attributes(&self, _dimension: Dimension3) -> (Vec<f32>, Vec<[f32; 4]>) {
-        crate::chunk::raw_tile::dense_tiles_to_attributes(&self.tiles)
+    fn extend_attributes(
+        &self,
+        dimension: Dimension3,
+        atlas: usize,
+        attributes: &mut TileAttributes,
+    ) {
+        if self.tiles.is_empty() {
+            // The layer still takes up its area in the mesh.
+            crate::chunk::raw_tile::extend_sparse_attributes(
+                dimension,
+                &HashMap::default(),
+                atlas,
+                attributes,
+            );
+            return;
+        }
+        crate::chunk::raw_tile::extend_dense_attributes(&self.tiles, atlas, attributes);
+    }
+
+    fn compact(&mut self) {
+        if self.get_tile_indices().is_empty() {
+            self.tiles = Vec::new();
+            self.tile_count = 0;
+            self.occupied = Occupancy::default();
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        self.tiles.capacity() * mem::size_of::<RawTile>() + self.occupied.heap_size()
     }
 }
 
@@ -103,10 +258,20 @@ impl DenseLayer {
     /// Constructs a new dense layer with tiles.
     pub fn new(tiles: Vec<RawTile>) -> DenseLayer {
         DenseLayer {
+            area: tiles.len(),
+            occupied: Occupancy::new(tiles.len()),
             tiles,
             tile_count: 0,
         }
     }
+
+    /// Builds the bitset of the tiles if it has not been built.
+    fn build_occupancy(&mut self) {
+        if self.occupied.is_empty() && !self.tiles.is_empty() {
+            let area = self.area.max(self.tiles.len());
+            self.occupied = Occupancy::from_indices(area, self.get_tile_indices());
+        }
+    }
 }
 
 /// A layer with sparse sprite tiles.
@@ -115,18 +280,25 @@ impl DenseLayer {
 pub(super) struct SparseLayer {
     /// A map of all the tiles in the chunk.
     tiles: HashMap<usize, RawTile>,
+    /// The indices which have a tile.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    occupied: Occupancy,
 }
 
 impl Layer for SparseLayer {
     fn set_tile(&mut self, index: usize, tile: RawTile) {
+        self.build_occupancy();
         if tile.color.a() == 0.0 {
             self.tiles.remove(&index);
         }
         self.tiles.insert(index, tile);
+        self.occupied.insert(index);
     }
 
     fn remove_tile(&mut self, index: usize) {
+        self.build_occupancy();
         self.tiles.remove(&index);
+        self.occupied.remove(index);
     }
 
     fn get_tile(&self, index: usize) -> Option<&RawTile> {
@@ -145,29 +317,251 @@ impl Layer for SparseLayer {
         indices
     }
 
+    fn is_occupied(&self, index: usize) -> bool {
+        if self.occupied.is_empty() {
+            return self.tiles.contains_key(&index);
+        }
+        self.occupied.contains(index)
+    }
+
+    fn iter_tiles(&self) -> Box<dyn Iterator<Item = (usize, &RawTile)> + '_> {
+        Box::new(self.tiles.iter().map(|(index, tile)| (*index, tile)))
+    }
+
     fn clear(&mut self) {
         self.tiles.clear();
+        self.occupied = Occupancy::default();
+    }
+
+    fn extend_attributes(
+        &self,
+        dimension: Dimension3,
+        atlas: usize,
+        attributes: &mut TileAttributes,
+    ) {
+        crate::chunk::raw_tile::extend_sparse_attributes(dimension, &self.tiles, atlas, attributes);
+    }
+
+    fn compact(&mut self) {
+        self.tiles.shrink_to_fit();
     }
 
-    fn tiles_to_attributes(&self, dimension: Dimension3) -> (Vec<f32>, Vec<[f32; 4]>) {
-        crate::chunk::raw_tile::sparse_tiles_to_attributes(dimension, &self.tiles)
+    fn heap_size(&self) -> usize {
+        self.tiles.capacity() * mem::size_of::<(usize, RawTile)>() + self.occupied.heap_size()
     }
 }
 
 impl SparseLayer {
     /// Constructs a new sparse layer with a tile hashmap.
     pub fn new(tiles: HashMap<usize, RawTile>) -> SparseLayer {
-        SparseLayer { tiles }
+        let occupied = Occupancy::from_indices(0, tiles.keys().copied());
+        SparseLayer { tiles, occupied }
+    }
+
+    /// Builds the bitset of the tiles if it has not been built.
+    fn build_occupancy(&mut self) {
+        if self.occupied.is_empty() && !self.tiles.is_empty() {
+            self.occupied = Occupancy::from_indices(0, self.tiles.keys().copied());
+        }
     }
 }
 
-/// Specifies which kind of layer to construct, either a dense or a sparse
-/// sprite layer.
+/// A layer with tiles stored as runs of equal tiles.
+///
+/// Each run covers the tiles from the index it starts at up to the start of
+/// the next run, or the end of the layer. Neighbouring runs differ, so a layer
+/// of the same tile is a single run however large the chunk is.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct RunLengthLayer {
+    /// The runs in the order of their starts, the first starting at 0, with
+    /// the tile of each run or `None` for a run without tiles.
+    runs: Vec<(usize, Option<RawTile>)>,
+    /// The number of tiles in the layer.
+    area: usize,
+}
+
+impl Layer for RunLengthLayer {
+    fn set_tile(&mut self, index: usize, tile: RawTile) {
+        if index >= self.area {
+            warn!(
+                "tile is out of bounds at index {} and can not be set",
+                index
+            );
+            return;
+        }
+        let tile = if tile.color.a() == 0.0 {
+            None
+        } else {
+            Some(tile)
+        };
+        self.replace(index, tile);
+    }
+
+    fn remove_tile(&mut self, index: usize) {
+        if index < self.area {
+            self.replace(index, None);
+        }
+    }
+
+    fn get_tile(&self, index: usize) -> Option<&RawTile> {
+        let position = self.position(index)?;
+        self.runs.get(position).and_then(|(_, tile)| tile.as_ref())
+    }
+
+    fn get_tile_mut(&mut self, index: usize) -> Option<&mut RawTile> {
+        self.get_tile(index)?;
+        // The tile may be changed, so it gets a run of its own.
+        let position = self.split(index)?;
+        self.runs
+            .get_mut(position)
+            .and_then(|(_, tile)| tile.as_mut())
+    }
+
+    fn get_tile_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for (position, (start, tile)) in self.runs.iter().enumerate() {
+            if tile.is_some() {
+                indices.extend(*start..self.run_end(position));
+            }
+        }
+        indices
+    }
+
+    fn is_occupied(&self, index: usize) -> bool {
+        self.get_tile(index).is_some()
+    }
+
+    fn iter_tiles(&self) -> Box<dyn Iterator<Item = (usize, &RawTile)> + '_> {
+        Box::new(
+            self.runs
+                .iter()
+                .enumerate()
+                .filter_map(move |(position, (start, tile))| {
+                    let tile = tile.as_ref()?;
+                    Some((*start..self.run_end(position)).map(move |index| (index, tile)))
+                })
+                .flatten(),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.runs = vec![(0, None)];
+    }
+
+    fn extend_attributes(
+        &self,
+        _dimension: Dimension3,
+        atlas: usize,
+        attributes: &mut TileAttributes,
+    ) {
+        for (position, (start, tile)) in self.runs.iter().enumerate() {
+            crate::chunk::raw_tile::extend_run_attributes(
+                tile.as_ref(),
+                self.run_end(position) - start,
+                atlas,
+                attributes,
+            );
+        }
+    }
+
+    fn compact(&mut self) {
+        let mut runs: Vec<(usize, Option<RawTile>)> = Vec::with_capacity(self.runs.len());
+        for (start, tile) in self.runs.drain(..) {
+            if runs.last().is_none_or(|(_, last)| *last != tile) {
+                runs.push((start, tile));
+            }
+        }
+        runs.shrink_to_fit();
+        self.runs = runs;
+    }
+
+    fn heap_size(&self) -> usize {
+        self.runs.capacity() * mem::size_of::<(usize, Option<RawTile>)>()
+    }
+}
+
+impl RunLengthLayer {
+    /// Constructs a new run length layer without tiles.
+    pub fn new(area: usize) -> RunLengthLayer {
+        RunLengthLayer {
+            runs: vec![(0, None)],
+            area,
+        }
+    }
+
+    /// Returns the position of the run which covers an index.
+    fn position(&self, index: usize) -> Option<usize> {
+        if index >= self.area {
+            return None;
+        }
+        match self.runs.binary_search_by_key(&index, |(start, _)| *start) {
+            Ok(position) => Some(position),
+            Err(position) => position.checked_sub(1),
+        }
+    }
+
+    /// Returns the index past the last tile of the run at a position.
+    fn run_end(&self, position: usize) -> usize {
+        self.runs
+            .get(position + 1)
+            .map_or(self.area, |(start, _)| *start)
+    }
+
+    /// Splits the run which covers an index so the index has a run of its
+    /// own, returning its position.
+    fn split(&mut self, index: usize) -> Option<usize> {
+        let mut position = self.position(index)?;
+        let (start, tile) = *self.runs.get(position)?;
+        if index + 1 < self.run_end(position) {
+            self.runs.insert(position + 1, (index + 1, tile));
+        }
+        if index > start {
+            position += 1;
+            self.runs.insert(position, (index, tile));
+        }
+        Some(position)
+    }
+
+    /// Replaces the tile at an index, merging its run with the runs next to
+    /// it which have the same tile.
+    fn replace(&mut self, index: usize, tile: Option<RawTile>) {
+        let current = self
+            .position(index)
+            .and_then(|position| self.runs.get(position));
+        match current {
+            Some((_, current)) if *current != tile => {}
+            _ => return,
+        }
+        let position = match self.split(index) {
+            Some(position) => position,
+            None => return,
+        };
+        if let Some(run) = self.runs.get_mut(position) {
+            run.1 = tile;
+        }
+        if self.runs.get(position + 1).map(|(_, next)| *next) == Some(tile) {
+            self.runs.remove(position + 1);
+        }
+        let previous = position
+            .checked_sub(1)
+            .and_then(|previous| self.runs.get(previous));
+        if previous.map(|(_, previous)| *previous) == Some(tile) {
+            self.runs.remove(position);
+        }
+    }
+}
+
+/// Specifies which kind of layer to construct, either a dense, a sparse or a
+/// run length sprite layer.
 ///
 /// The difference between a dense and sparse layer is namely the storage kind.
 /// A dense layer uses a vector and must fully contain tiles. This is ideal for
 /// backgrounds. A sparse layer on the other hand uses a map with coordinates
-/// to a tile. This is ideal for entities, objects or items.
+/// to a tile. This is ideal for entities, objects or items. A run length layer
+/// stores runs of equal tiles, so it only grows with the changes between
+/// neighbouring tiles. This is ideal for large areas of the same tile, such as
+/// oceans or grass, though finding a tile takes a search through its runs.
 ///
 /// It is highly recommended to adhere to the above principles to get the lowest
 /// amount of byte usage.
@@ -178,16 +572,20 @@ pub enum LayerKind {
     Dense,
     /// Specifies the tilemap to add a sparse sprite layer.
     Sparse,
+    /// Specifies the tilemap to add a run length sprite layer.
+    RunLength,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
-/// Inner enum used for storing either a dense or sparse layer.
+/// Inner enum used for storing either a dense, sparse or run length layer.
 pub(super) enum LayerKindInner {
     /// Inner dense layer storage.
     Dense(DenseLayer),
     /// Inner sparse layer storage.
     Sparse(SparseLayer),
+    /// Inner run length layer storage.
+    RunLength(RunLengthLayer),
 }
 
 impl AsRef<dyn Layer> for LayerKindInner {
@@ -195,6 +593,7 @@ impl AsRef<dyn Layer> for LayerKindInner {
         match self {
             LayerKindInner::Dense(s) => s,
             LayerKindInner::Sparse(s) => s,
+            LayerKindInner::RunLength(s) => s,
         }
     }
 }
@@ -204,14 +603,51 @@ impl AsMut<dyn Layer> for LayerKindInner {
         match self {
             LayerKindInner::Dense(s) => s,
             LayerKindInner::Sparse(s) => s,
+            LayerKindInner::RunLength(s) => s,
         }
     }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
-/// A sprite layer which can either store a sparse or dense layer.
+/// A sprite layer which can either store a dense, sparse or run length layer.
 pub(super) struct SpriteLayer {
     /// Enum storage of the kind of layer.
     pub inner: LayerKindInner,
+    /// The properties of the tiles in the layer by index.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub properties: HashMap<usize, TileProperties>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_occupancy() {
+        let tile = RawTile::default();
+        let mut sparse = SparseLayer::new(HashMap::default());
+        sparse.set_tile(3, tile);
+        sparse.set_tile(100, tile);
+        // A deserialized layer has lost its bitset.
+        sparse.occupied = Occupancy::default();
+        assert!(sparse.is_occupied(100));
+        sparse.remove_tile(3);
+        assert!(!sparse.is_occupied(3));
+        assert!(sparse.is_occupied(100));
+        assert!(!sparse.occupied.is_empty());
+
+        let empty = RawTile {
+            color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            ..Default::default()
+        };
+        let mut dense = DenseLayer::new(vec![empty; 70]);
+        dense.set_tile(65, tile);
+        dense.occupied = Occupancy::default();
+        assert!(dense.is_occupied(65));
+        dense.set_tile(2, tile);
+        assert!(dense.is_occupied(65));
+        assert!(dense.is_occupied(2));
+        assert!(!dense.is_occupied(3));
+    }
 }
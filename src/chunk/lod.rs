@@ -0,0 +1,158 @@
+use crate::{
+    chunk::{Chunk, RawTile},
+    lib::*,
+};
+
+/// Returns the average color of a sprite in a texture atlas, weighted by the
+/// alpha of its pixels.
+///
+/// Returns `None` if the texture of the atlas is not loaded or is not 8 bits
+/// per channel RGBA.
+pub(crate) fn average_sprite_color(
+    texture_atlas: &TextureAtlas,
+    textures: &Assets<Texture>,
+    index: usize,
+) -> Option<Color> {
+    let rect = texture_atlas.textures.get(index)?;
+    let texture = textures.get(&texture_atlas.texture)?;
+    if texture.format.pixel_size() != 4 {
+        return None;
+    }
+
+    let width = texture.size.width as usize;
+    let mut color_sum = [0u64; 3];
+    let mut alpha_sum = 0u64;
+    let mut count = 0u64;
+    for y in rect.min.y as usize..rect.max.y as usize {
+        for x in rect.min.x as usize..rect.max.x as usize {
+            let start = (y * width + x) * 4;
+            if let Some(pixel) = texture.data.get(start..start + 4) {
+                let alpha = pixel.get(3).map_or(0, |alpha| u64::from(*alpha));
+                for (sum, channel) in color_sum.iter_mut().zip(pixel) {
+                    *sum += u64::from(*channel) * alpha;
+                }
+                alpha_sum += alpha;
+                count += 1;
+            }
+        }
+    }
+    if count == 0 {
+        return None;
+    }
+    if alpha_sum == 0 {
+        return Some(Color::NONE);
+    }
+
+    let [red, green, blue] = color_sum;
+    Some(Color::rgba_u8(
+        (red / alpha_sum) as u8,
+        (green / alpha_sum) as u8,
+        (blue / alpha_sum) as u8,
+        (alpha_sum / count) as u8,
+    ))
+}
+
+/// Bakes a texture of a chunk with one pixel per tile.
+///
+/// Each pixel takes the color of the top most tile at that point, which is
+/// given by `tile_color`. Points without tiles are transparent.
+pub(crate) fn bake_chunk_texture<F>(
+    chunk: &Chunk,
+    dimensions: Dimension3,
+    mut tile_color: F,
+) -> Texture
+where
+    F: FnMut(&RawTile) -> Color,
+{
+    let width = dimensions.width as usize;
+    let height = dimensions.height as usize;
+    let area = width * height;
+
+    let mut top_tiles: Vec<Option<((usize, usize), &RawTile)>> = vec![None; area];
+    for (z_depth, sprite_order, index, tile) in chunk.raw_tiles() {
+        if let Some(top_tile) = top_tiles.get_mut(index % area) {
+            let order = (z_depth, sprite_order);
+            let is_above = match top_tile {
+                Some((top_order, _)) => order >= *top_order,
+                None => true,
+            };
+            if is_above {
+                *top_tile = Some((order, tile));
+            }
+        }
+    }
+
+    let mut data = vec![0; area * 4];
+    for (index, top_tile) in top_tiles.into_iter().enumerate() {
+        let (_, tile) = if let Some(top_tile) = top_tile {
+            top_tile
+        } else {
+            continue;
+        };
+        let sprite_color = tile_color(tile);
+        let (x, y) = (index % width, index / width);
+        // Textures start at the top while tiles start at the bottom.
+        let start = ((height - 1 - y) * width + x) * 4;
+        if let Some(pixel) = data.get_mut(start..start + 4) {
+            let channels = [
+                sprite_color.r() * tile.color.r(),
+                sprite_color.g() * tile.color.g(),
+                sprite_color.b() * tile.color.b(),
+                sprite_color.a() * tile.color.a(),
+            ];
+            for (byte, channel) in pixel.iter_mut().zip(channels.iter()) {
+                *byte = (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+    }
+
+    let mut texture = Texture::new(
+        Extent3d::new(dimensions.width, dimensions.height, 1),
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    texture.sampler.mag_filter = FilterMode::Nearest;
+    texture.sampler.min_filter = FilterMode::Nearest;
+    texture
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::LayerKind;
+
+    #[test]
+    fn bake_top_tiles() {
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let red = RawTile {
+            color: Color::RED,
+            ..Default::default()
+        };
+        let blue = RawTile {
+            color: Color::BLUE,
+            ..Default::default()
+        };
+        // Bottom left is red, covered by blue on the sprite layer above.
+        chunk.set_raw_tile(0, 0, 0, red);
+        chunk.set_raw_tile(0, 1, 0, blue);
+        // Top right is only red.
+        chunk.set_raw_tile(3, 0, 0, red);
+
+        let texture = bake_chunk_texture(&chunk, dimensions, |_| Color::WHITE);
+        assert_eq!(
+            texture.data,
+            vec![
+                0, 0, 0, 0, // top left
+                255, 0, 0, 255, // top right
+                0, 0, 255, 255, // bottom left
+                0, 0, 0, 0, // bottom right
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::lib::*;
+use crate::{chunk::render, lib::*};
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, PartialEq, PartialOrd, Debug, Default)]
@@ -11,11 +11,6 @@ pub struct ChunkMesh {
 }
 
 impl ChunkMesh {
-    /// Vertex attribute of the tile's index.
-    pub(crate) const ATTRIBUTE_TILE_INDEX: &'static str = "Vertex_Tile_Index";
-    /// Vertex attribute of the tile's color.
-    pub(crate) const ATTRIBUTE_TILE_COLOR: &'static str = "Vertex_Tile_Color";
-
     /// Constructs a new chunk mesh.
     pub(crate) fn new(dimensions: Dimension3, layers: u32, z_offset: Vec2) -> ChunkMesh {
         let layers = layers as i32;
@@ -53,13 +48,36 @@ impl ChunkMesh {
 
         ChunkMesh { indices, vertices }
     }
+
+    /// Returns the mesh with a number of layers left out at every z depth,
+    /// for a chunk whose baked layers are not in its meshes.
+    pub(crate) fn without_layers(&self, dimensions: Dimension3, removed: usize) -> ChunkMesh {
+        // Each tile has four vertices.
+        let layer_len = (dimensions.width * dimensions.height) as usize * 4;
+        let depth = (dimensions.depth as usize).max(1);
+        let layers = self.vertices.len() / layer_len.max(1) / depth;
+        let kept = layers.saturating_sub(removed);
+        let vertices: Vec<[f32; 3]> = self
+            .vertices
+            .chunks(layers.max(1) * layer_len)
+            .flat_map(|z_layer| z_layer.iter().take(kept * layer_len).copied())
+            .collect();
+        let indices = (0..(vertices.len() / 4) as u32)
+            .flat_map(|i| {
+                let i = i * 4;
+                vec![i, i + 2, i + 1, i, i + 3, i + 2]
+            })
+            .collect::<Vec<_>>();
+
+        ChunkMesh { indices, vertices }
+    }
 }
 
 impl From<&ChunkMesh> for Mesh {
     fn from(chunk_mesh: &ChunkMesh) -> Mesh {
         let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
         mesh.set_indices(Some(Indices::U32(chunk_mesh.indices.clone())));
-        mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, chunk_mesh.vertices.clone());
+        mesh.set_attribute(render::ATTRIBUTE_POSITION.name, chunk_mesh.vertices.clone());
 
         mesh
     }
@@ -59,23 +59,82 @@
 pub(crate) mod entity;
 /// Sparse and dense chunk layers.
 mod layer;
+/// Low resolution stand-ins for far away chunks.
+pub(crate) mod lod;
 /// Meshes for rendering to vertices.
 pub(crate) mod mesh;
 /// Raw tile that is stored in the chunks.
 pub mod raw_tile;
 /// Files and helpers for rendering.
-pub(crate) mod render;
+pub mod render;
 /// Systems for chunks.
 pub(crate) mod system;
 
-use crate::{lib::*, tile::Tile};
+use crate::{
+    lib::*,
+    property::TileProperties,
+    tile::Tile,
+    tilemap::{ColorGrading, Wave},
+};
 pub use layer::LayerKind;
-use layer::{DenseLayer, LayerKindInner, SparseLayer, SpriteLayer};
+use layer::{DenseLayer, LayerKindInner, RunLengthLayer, SparseLayer, SpriteLayer};
 pub use raw_tile::RawTile;
+use raw_tile::{tint, TileAttributes};
 
 /// A type for sprite layers.
 type SpriteLayers = Vec<Option<SpriteLayer>>;
 
+/// The rows above and below the origin over which the tiles of y-sorted
+/// layers are sorted. Tiles past them share the depth of the last row.
+const Y_SORT_ROWS: f32 = 4096.0;
+
+/// The memory reclaimed by compacting the sprite layers of chunks.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CompactionReport {
+    /// The number of chunks which were compacted.
+    pub chunks: usize,
+    /// The number of bytes which were freed, estimated from the capacity of
+    /// the freed tile storage.
+    pub reclaimed_bytes: usize,
+}
+
+/// The settings of a tilemap which decide how the tiles of its chunks are
+/// turned into mesh attributes.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct MeshSettings<'a> {
+    /// The sprite layers which are hidden.
+    pub hidden_layers: &'a HashSet<usize>,
+    /// The waves of the sprite layers which move over time.
+    pub waves: &'a HashMap<usize, Wave>,
+    /// The sprite layers whose opaque tiles hide the tiles below them.
+    pub occluding_layers: &'a HashSet<usize>,
+    /// The sprite layers whose tiles are depth sorted by their row.
+    pub y_sorted_layers: &'a HashSet<usize>,
+    /// The keys deciding the render order of the sprite layers.
+    pub sort_keys: &'a HashMap<usize, i32>,
+    /// The depths the sprite layers render at which were set.
+    pub layer_depths: &'a HashMap<usize, f32>,
+    /// The range of z which the depths are fitted into, if any.
+    pub depth_range: Option<(f32, f32)>,
+    /// The color gradings of the sprite layers.
+    pub gradings: &'a HashMap<usize, ColorGrading>,
+    /// The sprite indices that logical tile ids are rendered with.
+    pub theme: &'a HashMap<u32, usize>,
+}
+
+impl<'a> MeshSettings<'a> {
+    /// Returns the settings with other hidden sprite layers, if there are any.
+    pub(crate) fn with_hidden_layers(self, hidden_layers: Option<&'a HashSet<usize>>) -> Self {
+        match hidden_layers {
+            Some(hidden_layers) => MeshSettings {
+                hidden_layers,
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 /// A chunk which holds all the tiles to be rendered.
@@ -86,11 +145,42 @@ pub(crate) struct Chunk {
     z_layers: Vec<SpriteLayers>,
     /// Ephemeral user data that can be used for flags or other purposes.
     user_data: u128,
-    /// A chunks mesh used for rendering.
+    /// The chunk's meshes used for rendering, one per texture atlas id.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    meshes: HashMap<usize, Handle<Mesh>>,
+    /// The entities which are tied to this chunk, one per texture atlas id.
+    entities: HashMap<usize, Entity>,
+    /// The entity of the low resolution stand-in of the chunk, if it is far
+    /// away.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    lod_entity: Option<Entity>,
+    /// The sprite orders of the static layers which are baked into a texture
+    /// while the chunk is spawned, and left out of its meshes.
     #[cfg_attr(feature = "serde", serde(skip))]
-    mesh: Option<Handle<Mesh>>,
-    /// An entity which is tied to this chunk.
-    entity: Option<Entity>,
+    baked_layers: HashSet<usize>,
+    /// The entity of the sprite which draws the baked layers, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    baked_entity: Option<Entity>,
+    /// The tiles which changed since the meshes were last updated by z depth,
+    /// sprite order and index, or `None` if the whole meshes need updating.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dirty_tiles: Option<HashSet<(usize, usize, usize)>>,
+    /// The tile attributes built ahead of spawning by texture atlas id, which
+    /// are cleared when any tile changes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    prewarmed: HashMap<usize, TileAttributes>,
+    /// The light falling on every tile by index, which tints the tiles, or
+    /// `None` if the chunk is not lit.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    light: Option<Vec<[f32; 3]>>,
+    /// The shade of the corners of every tile by index from the ambient
+    /// occlusion, or `None` if the chunk is not shaded.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    shade: Option<Vec<[f32; 4]>>,
+    /// The uniforms of the sprite layers of the chunk for custom shaders by
+    /// sprite order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_uniforms: HashMap<usize, [f32; 4]>,
 }
 
 impl Chunk {
@@ -104,8 +194,16 @@ impl Chunk {
             point,
             z_layers: vec![vec![None; sprite_layers.len()]; dimensions.depth as usize],
             user_data: 0,
-            mesh: None,
-            entity: None,
+            meshes: HashMap::default(),
+            entities: HashMap::default(),
+            lod_entity: None,
+            baked_layers: HashSet::default(),
+            baked_entity: None,
+            dirty_tiles: None,
+            prewarmed: HashMap::default(),
+            light: None,
+            shade: None,
+            layer_uniforms: HashMap::default(),
         };
 
         for (sprite_order, kind) in sprite_layers.iter().enumerate() {
@@ -125,13 +223,16 @@ impl Chunk {
         sprite_order: usize,
         dimensions: Dimension3,
     ) {
+        self.mark_all_dirty();
+        self.grow_sprite_layers(sprite_order + 1);
         for z in 0..dimensions.depth as usize {
             match kind {
                 LayerKind::Dense => {
                     let tiles = vec![
                         RawTile {
                             index: 0,
-                            color: Color::rgba(0.0, 0.0, 0.0, 0.0)
+                            color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                            ..Default::default()
                         };
                         (dimensions.width * dimensions.height) as usize
                     ];
@@ -140,6 +241,7 @@ impl Chunk {
                             if !sprite_order_layer.is_some() {
                                 *sprite_order_layer = Some(SpriteLayer {
                                     inner: LayerKindInner::Dense(DenseLayer::new(tiles)),
+                                    properties: HashMap::default(),
                                 });
                             }
                         } else {
@@ -157,6 +259,25 @@ impl Chunk {
                                     inner: LayerKindInner::Sparse(SparseLayer::new(
                                         HashMap::default(),
                                     )),
+                                    properties: HashMap::default(),
+                                });
+                            }
+                        } else {
+                            error!("sprite layer {} is out of bounds", sprite_order);
+                        }
+                    } else {
+                        error!("sprite layer {} is out of bounds", sprite_order);
+                    }
+                }
+                LayerKind::RunLength => {
+                    if let Some(z_layer) = self.z_layers.get_mut(z) {
+                        if let Some(sprite_order_layer) = z_layer.get_mut(sprite_order) {
+                            if !sprite_order_layer.is_some() {
+                                *sprite_order_layer = Some(SpriteLayer {
+                                    inner: LayerKindInner::RunLength(RunLengthLayer::new(
+                                        (dimensions.width * dimensions.height) as usize,
+                                    )),
+                                    properties: HashMap::default(),
                                 });
                             }
                         } else {
@@ -177,6 +298,8 @@ impl Chunk {
 
     /// Moves a layer from a z layer to another.
     pub(crate) fn move_sprite_layer(&mut self, from_layer_z: usize, to_layer_z: usize) {
+        self.mark_all_dirty();
+        self.grow_sprite_layers(from_layer_z.max(to_layer_z) + 1);
         for sprite_layers in &mut self.z_layers {
             if let Some(layer) = sprite_layers.get(to_layer_z) {
                 if layer.is_some() {
@@ -186,52 +309,141 @@ impl Chunk {
             }
             sprite_layers.swap(from_layer_z, to_layer_z);
         }
+        if let Some(uniform) = self.layer_uniforms.remove(&from_layer_z) {
+            self.layer_uniforms.insert(to_layer_z, uniform);
+        }
+        if self.baked_layers.remove(&from_layer_z) {
+            self.baked_layers.insert(to_layer_z);
+        }
     }
 
     /// Removes a layer from the specified layer.
     pub(crate) fn remove_sprite_layer(&mut self, sprite_layer: usize) {
+        self.mark_all_dirty();
+        for z_layer in &mut self.z_layers {
+            if sprite_layer < z_layer.len() {
+                z_layer.remove(sprite_layer);
+            }
+        }
+        self.layer_uniforms.remove(&sprite_layer);
+        self.baked_layers.remove(&sprite_layer);
+    }
+
+    /// Makes sure every z depth has at least a number of sprite layer slots,
+    /// as compacting drops the empty slots at the end.
+    fn grow_sprite_layers(&mut self, len: usize) {
+        for z_layer in &mut self.z_layers {
+            if z_layer.len() < len {
+                z_layer.resize_with(len, || None);
+            }
+        }
+    }
+
+    /// Frees the tile storage of dense sprite layers without tiles, the spare
+    /// capacity of sparse and run length sprite layers and the empty sprite
+    /// layer slots at the end, and merges the runs of run length sprite layers
+    /// which changed tiles split. Returns the number of bytes which were
+    /// reclaimed.
+    ///
+    /// Freed dense sprite layers are allocated again when a tile is set.
+    pub(crate) fn compact(&mut self) -> usize {
+        let before = self.heap_size();
         for z_layer in &mut self.z_layers {
-            z_layer.remove(sprite_layer);
+            for layer in z_layer.iter_mut().flatten() {
+                layer.inner.as_mut().compact();
+                layer.properties.shrink_to_fit();
+            }
+            while let Some(None) = z_layer.last() {
+                z_layer.pop();
+            }
+            z_layer.shrink_to_fit();
         }
+        before.saturating_sub(self.heap_size())
+    }
+
+    /// Returns the number of bytes the sprite layers of the chunk take up on
+    /// the heap, not counting tile properties.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.z_layers
+            .iter()
+            .map(|z_layer| {
+                let tiles: usize = z_layer
+                    .iter()
+                    .flatten()
+                    .map(|layer| layer.inner.as_ref().heap_size())
+                    .sum();
+                z_layer.capacity() * mem::size_of::<Option<SpriteLayer>>() + tiles
+            })
+            .sum()
+    }
+
+    /// Returns the number of tiles in the dense, sparse and run length sprite
+    /// layers of the chunk.
+    pub(crate) fn tile_counts(&self) -> [usize; 3] {
+        let mut counts = [0; 3];
+        for layer in self.z_layers.iter().flatten().flatten() {
+            let count = layer.inner.as_ref().iter_tiles().count();
+            match layer.inner {
+                LayerKindInner::Dense(_) => counts[0] += count,
+                LayerKindInner::Sparse(_) => counts[1] += count,
+                LayerKindInner::RunLength(_) => counts[2] += count,
+            }
+        }
+        counts
+    }
+
+    /// Sets the mesh for the chunk to use for a texture atlas.
+    pub(crate) fn set_mesh(&mut self, atlas: usize, mesh: Handle<Mesh>) {
+        self.meshes.insert(atlas, mesh);
     }
 
-    /// Sets the mesh for the chunk layer to use.
-    pub(crate) fn set_mesh(&mut self, mesh: Handle<Mesh>) {
-        self.mesh = Some(mesh);
+    /// Returns an iterator over the texture atlas ids and meshes of the chunk.
+    pub(crate) fn meshes(&self) -> impl Iterator<Item = (usize, &Handle<Mesh>)> {
+        self.meshes.iter().map(|(atlas, mesh)| (*atlas, mesh))
     }
 
-    /// Returns a reference to the chunk's mesh.
-    pub(crate) fn mesh(&self) -> Option<&Handle<Mesh>> {
-        self.mesh.as_ref()
+    /// Returns `true` if the chunk has any meshes, which is when it is spawned.
+    pub(crate) fn has_mesh(&self) -> bool {
+        !self.meshes.is_empty()
     }
 
-    /// Takes the mesh handle.
-    pub(crate) fn take_mesh(&mut self) -> Option<Handle<Mesh>> {
-        self.mesh.take()
+    /// Takes all the mesh handles.
+    pub(crate) fn take_meshes(&mut self) -> Vec<Handle<Mesh>> {
+        self.dirty_tiles = None;
+        self.meshes.drain().map(|(_, mesh)| mesh).collect()
     }
 
     /// Sets a single raw tile to be added to a z layer and index.
     pub(crate) fn set_tile(&mut self, index: usize, tile: Tile<Point3>) {
-        if let Some(z_depth) = self.z_layers.get_mut(tile.point.z as usize) {
-            if let Some(layer) = z_depth.get_mut(tile.sprite_order) {
-                let raw_tile = RawTile {
-                    index: tile.sprite_index,
-                    color: tile.tint,
-                };
+        let raw_tile = tile.to_raw_tile();
+        self.set_raw_tile(index, tile.sprite_order, tile.point.z as usize, raw_tile);
+    }
+
+    /// Sets a raw tile at an index of a sprite layer and z depth.
+    pub(crate) fn set_raw_tile(
+        &mut self,
+        index: usize,
+        sprite_order: usize,
+        z_depth: usize,
+        raw_tile: RawTile,
+    ) {
+        if let Some(layers) = self.z_layers.get_mut(z_depth) {
+            if let Some(layer) = layers.get_mut(sprite_order) {
                 if let Some(layer) = layer {
                     layer.inner.as_mut().set_tile(index, raw_tile);
+                    self.mark_dirty(z_depth, sprite_order, index);
                 } else {
-                    error!("sprite layer {} does not exist", tile.sprite_order);
+                    error!("sprite layer {} does not exist", sprite_order);
                 }
             } else {
                 error!(
                     "{} exceeded max number of sprite layers: {}",
-                    tile.sprite_order,
-                    z_depth.len()
+                    sprite_order,
+                    layers.len()
                 );
             }
         } else {
-            error!("z layer {} does not exist", tile.point.z);
+            error!("z layer {} does not exist", z_depth);
         }
     }
 
@@ -241,6 +453,7 @@ impl Chunk {
             if let Some(layer) = layers.get_mut(sprite_layer) {
                 if let Some(layer) = layer {
                     layer.inner.as_mut().remove_tile(index);
+                    self.mark_dirty(z_depth, sprite_layer, index);
                 } else {
                     error!("sprite layer {} does not exist", index);
                 }
@@ -256,19 +469,138 @@ impl Chunk {
         }
     }
 
-    /// Adds an entity to a z layer, always when it is spawned.
-    pub(crate) fn set_entity(&mut self, entity: Entity) {
-        self.entity = Some(entity);
+    /// Adds the entity of a texture atlas, always when it is spawned.
+    pub(crate) fn set_entity(&mut self, atlas: usize, entity: Entity) {
+        self.entities.insert(atlas, entity);
     }
 
-    /// Gets the mesh entity of the chunk.
-    pub(crate) fn get_entity(&self) -> Option<Entity> {
-        self.entity
+    /// Gets the mesh entity of the chunk for a texture atlas.
+    pub(crate) fn get_entity(&self, atlas: usize) -> Option<Entity> {
+        self.entities.get(&atlas).copied()
     }
 
-    /// Gets the layers entity, if any. Useful for despawning.
-    pub(crate) fn take_entity(&mut self) -> Option<Entity> {
-        self.entity.take()
+    /// Gets all the mesh entities of the chunk, along with the sprite of its
+    /// baked layers.
+    pub(crate) fn entities(&self) -> Vec<Entity> {
+        self.entities
+            .values()
+            .copied()
+            .chain(self.baked_entity)
+            .collect()
+    }
+
+    /// Takes all the entities of the chunk. Useful for despawning.
+    ///
+    /// The baked layers are put back in the meshes for the next spawn.
+    pub(crate) fn take_entities(&mut self) -> Vec<Entity> {
+        self.baked_layers.clear();
+        self.entities
+            .drain()
+            .map(|(_, entity)| entity)
+            .chain(self.baked_entity.take())
+            .collect()
+    }
+
+    /// Leaves static layers out of the meshes of the chunk, as a sprite
+    /// draws them baked into a texture.
+    pub(crate) fn set_baked_layers(&mut self, baked_layers: HashSet<usize>, entity: Entity) {
+        self.mark_all_dirty();
+        self.baked_layers = baked_layers;
+        self.baked_entity = Some(entity);
+    }
+
+    /// Returns the number of sprite layers at every z depth which are baked
+    /// and left out of the meshes of the chunk.
+    pub(crate) fn baked_layer_count(&self) -> usize {
+        self.z_layers.first().map_or(0, |sprite_layers| {
+            self.baked_layers
+                .iter()
+                .filter(|sprite_order| {
+                    sprite_layers
+                        .get(**sprite_order)
+                        .is_some_and(|layer| layer.is_some())
+                })
+                .count()
+        })
+    }
+
+    /// Returns the static layers from a set which the chunk has, to be baked.
+    pub(crate) fn sprite_layers_in(&self, sprite_orders: &HashSet<usize>) -> HashSet<usize> {
+        self.z_layers
+            .iter()
+            .flat_map(|sprite_layers| {
+                sprite_layers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_sprite_order, layer)| layer.is_some())
+                    .map(|(sprite_order, _layer)| sprite_order)
+            })
+            .filter(|sprite_order| sprite_orders.contains(sprite_order))
+            .collect()
+    }
+
+    /// Sets the entity of the low resolution stand-in of the chunk.
+    pub(crate) fn set_lod_entity(&mut self, entity: Entity) {
+        self.lod_entity = Some(entity);
+    }
+
+    /// Gets the entity of the low resolution stand-in of the chunk, if any.
+    pub(crate) fn lod_entity(&self) -> Option<Entity> {
+        self.lod_entity
+    }
+
+    /// Takes the entity of the low resolution stand-in of the chunk.
+    pub(crate) fn take_lod_entity(&mut self) -> Option<Entity> {
+        self.lod_entity.take()
+    }
+
+    /// Returns the ids of all the texture atlases which are used by the tiles
+    /// of the chunk.
+    pub(crate) fn atlases(&self) -> HashSet<usize> {
+        self.raw_tiles()
+            .into_iter()
+            .map(|(_, _, _, tile)| tile.atlas)
+            .collect()
+    }
+
+    /// Returns `true` if the chunk has no tiles.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.z_layers
+            .iter()
+            .flatten()
+            .flatten()
+            .all(|layer| layer.inner.as_ref().get_tile_indices().is_empty())
+    }
+
+    /// Returns the smallest rectangle of tile points in the chunk which
+    /// contains every tile, or only the tiles of a z depth.
+    pub(crate) fn tile_bounds(
+        &self,
+        dimensions: Dimension3,
+        z_depth: Option<usize>,
+    ) -> Option<(Point2, Point2)> {
+        let width = dimensions.width as usize;
+        let layer_size = width * dimensions.height as usize;
+        let mut bounds: Option<(Point2, Point2)> = None;
+        for (z, layers) in self.z_layers.iter().enumerate() {
+            if z_depth.is_some_and(|z_depth| z_depth != z) {
+                continue;
+            }
+            for layer in layers.iter().flatten() {
+                for index in layer.inner.as_ref().get_tile_indices() {
+                    let index = index % layer_size;
+                    let point = Point2::new((index % width) as Coord, (index / width) as Coord);
+                    bounds = Some(match bounds {
+                        Some((min, max)) => (
+                            Point2::new(min.x.min(point.x), min.y.min(point.y)),
+                            Point2::new(max.x.max(point.x), max.y.max(point.y)),
+                        ),
+                        None => (point, point),
+                    });
+                }
+            }
+        }
+        bounds
     }
 
     /// Gets a reference to a tile from a provided z order and index.
@@ -287,6 +619,15 @@ impl Chunk {
         })
     }
 
+    /// Returns `true` if a sprite layer has a tile at an index.
+    pub(crate) fn is_occupied(&self, index: usize, sprite_order: usize, z_depth: usize) -> bool {
+        self.z_layers
+            .get(z_depth)
+            .and_then(|z_depth| z_depth.get(sprite_order))
+            .and_then(|layer| layer.as_ref())
+            .is_some_and(|layer| layer.inner.as_ref().is_occupied(index))
+    }
+
     /// Gets a mutable reference to a tile from a provided z order and index.
     pub(crate) fn get_tile_mut(
         &mut self,
@@ -294,6 +635,8 @@ impl Chunk {
         sprite_order: usize,
         z_depth: usize,
     ) -> Option<&mut RawTile> {
+        // The tile may be changed through the reference.
+        self.mark_dirty(z_depth, sprite_order, index);
         self.z_layers.get_mut(z_depth).and_then(|z_depth| {
             z_depth.get_mut(sprite_order).and_then(|layer| {
                 layer
@@ -303,34 +646,640 @@ impl Chunk {
         })
     }
 
+    /// Gets a reference to the properties of the tiles in a sprite layer by
+    /// index.
+    pub(crate) fn layer_properties(
+        &self,
+        sprite_order: usize,
+        z_depth: usize,
+    ) -> Option<&HashMap<usize, TileProperties>> {
+        self.z_layers
+            .get(z_depth)
+            .and_then(|z_depth| z_depth.get(sprite_order))
+            .and_then(|layer| layer.as_ref())
+            .map(|layer| &layer.properties)
+    }
+
+    /// Gets a mutable reference to the properties of the tiles in a sprite
+    /// layer by index.
+    pub(crate) fn layer_properties_mut(
+        &mut self,
+        sprite_order: usize,
+        z_depth: usize,
+    ) -> Option<&mut HashMap<usize, TileProperties>> {
+        self.z_layers
+            .get_mut(z_depth)
+            .and_then(|z_depth| z_depth.get_mut(sprite_order))
+            .and_then(|layer| layer.as_mut())
+            .map(|layer| &mut layer.properties)
+    }
+
+    /// Replaces the sprite indices of all the tiles in a texture atlas which
+    /// are in the remap.
+    pub(crate) fn remap_sprite_indices(&mut self, atlas: usize, remap: &HashMap<usize, usize>) {
+        self.mark_all_dirty();
+        for layers in &mut self.z_layers {
+            for layer in layers.iter_mut().flatten() {
+                let layer = layer.inner.as_mut();
+                for index in layer.get_tile_indices() {
+                    if let Some(tile) = layer.get_tile_mut(index) {
+                        if tile.atlas != atlas {
+                            continue;
+                        }
+                        if let Some(sprite_index) = remap.get(&tile.index) {
+                            tile.index = *sprite_index;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns every tile that exists in the chunk along with its z depth,
+    /// sprite order and index.
+    pub(crate) fn raw_tiles(&self) -> Vec<(usize, usize, usize, &RawTile)> {
+        self.iter_tiles(None).collect()
+    }
+
+    /// Returns an iterator over every tile that exists in the chunk, or only
+    /// in a sprite layer if given, along with its z depth, sprite order and
+    /// index.
+    pub(crate) fn iter_tiles(
+        &self,
+        sprite_order: Option<usize>,
+    ) -> impl Iterator<Item = (usize, usize, usize, &RawTile)> + '_ {
+        self.z_layers
+            .iter()
+            .enumerate()
+            .flat_map(move |(z_depth, layers)| {
+                layers
+                    .iter()
+                    .enumerate()
+                    .filter(move |(order, _)| sprite_order.is_none_or(|s| s == *order))
+                    .filter_map(|(order, layer)| layer.as_ref().map(|layer| (order, layer)))
+                    .flat_map(move |(order, layer)| {
+                        layer
+                            .inner
+                            .as_ref()
+                            .iter_tiles()
+                            .map(move |(index, tile)| (z_depth, order, index, tile))
+                    })
+            })
+    }
+
+    /// Returns `true` if another chunk has the same kinds of sprite layers at
+    /// the same sprite orders and z depths.
+    #[cfg(feature = "scene")]
+    pub(crate) fn has_layers_of(&self, other: &Chunk) -> bool {
+        self.z_layers.len() == other.z_layers.len()
+            && self
+                .z_layers
+                .iter()
+                .zip(other.z_layers.iter())
+                .all(|(layers, other_layers)| {
+                    (0..layers.len().max(other_layers.len())).all(|sprite_order| {
+                        let layer = layers.get(sprite_order).and_then(Option::as_ref);
+                        let other_layer = other_layers.get(sprite_order).and_then(Option::as_ref);
+                        match (layer, other_layer) {
+                            (Some(layer), Some(other_layer)) => {
+                                mem::discriminant(&layer.inner)
+                                    == mem::discriminant(&other_layer.inner)
+                            }
+                            (None, None) => true,
+                            _ => false,
+                        }
+                    })
+                })
+    }
+
+    /// Takes the tiles, properties and layer uniforms of a reloaded chunk with
+    /// the same sprite layers, marking only the tiles which differ as changed.
+    /// Returns `true` if any tile or layer uniform changed.
+    #[cfg(feature = "scene")]
+    pub(crate) fn patch(&mut self, reloaded: Chunk) -> bool {
+        let mut changed = Vec::new();
+        for (z_depth, (layers, reloaded_layers)) in self
+            .z_layers
+            .iter()
+            .zip(reloaded.z_layers.iter())
+            .enumerate()
+        {
+            for (sprite_order, (layer, reloaded_layer)) in
+                layers.iter().zip(reloaded_layers.iter()).enumerate()
+            {
+                let (layer, reloaded_layer) = match (layer, reloaded_layer) {
+                    (Some(layer), Some(reloaded_layer)) => {
+                        (layer.inner.as_ref(), reloaded_layer.inner.as_ref())
+                    }
+                    _ => continue,
+                };
+                let mut indices = layer.get_tile_indices();
+                indices.extend(reloaded_layer.get_tile_indices());
+                indices.sort_unstable();
+                indices.dedup();
+                for index in indices {
+                    if layer.get_tile(index) != reloaded_layer.get_tile(index) {
+                        changed.push((z_depth, sprite_order, index));
+                    }
+                }
+            }
+        }
+
+        let uniforms_changed = self.layer_uniforms != reloaded.layer_uniforms;
+        self.z_layers = reloaded.z_layers;
+        self.user_data = reloaded.user_data;
+        self.layer_uniforms = reloaded.layer_uniforms;
+        if uniforms_changed {
+            self.mark_all_dirty();
+        }
+        for (z_depth, sprite_order, index) in changed.iter() {
+            self.mark_dirty(*z_depth, *sprite_order, *index);
+        }
+        uniforms_changed || !changed.is_empty()
+    }
+
     /// Clears a given layer of all sprites.
+    ///
+    /// Only the cleared tiles are marked as changed, so sparse layers are
+    /// patched in the meshes rather than rebuilt.
     pub(crate) fn clear_layer(&mut self, layer: usize) {
+        let mut cleared = Vec::new();
         if let Some(sprite_layer) = self.z_layers.get_mut(layer) {
-            for layer in sprite_layer.iter_mut().flatten() {
-                layer.inner.as_mut().clear();
+            for (sprite_order, sprite_layer) in sprite_layer.iter_mut().enumerate() {
+                if let Some(sprite_layer) = sprite_layer {
+                    for index in sprite_layer.inner.as_ref().get_tile_indices() {
+                        cleared.push((sprite_order, index));
+                    }
+                    sprite_layer.inner.as_mut().clear();
+                }
+            }
+        }
+        for (sprite_order, index) in cleared {
+            self.mark_dirty(layer, sprite_order, index);
+        }
+    }
+
+    /// Marks a tile as changed since the meshes were last updated.
+    ///
+    /// Nothing is tracked while the chunk has no meshes, as they are built in
+    /// full when it spawns.
+    fn mark_dirty(&mut self, z_depth: usize, sprite_order: usize, index: usize) {
+        self.prewarmed.clear();
+        if !self.has_mesh() {
+            return;
+        }
+        if let Some(dirty_tiles) = &mut self.dirty_tiles {
+            dirty_tiles.insert((z_depth, sprite_order, index));
+        }
+    }
+
+    /// Marks every tile as changed so the whole meshes are updated.
+    pub(crate) fn mark_all_dirty(&mut self) {
+        self.dirty_tiles = None;
+        self.prewarmed.clear();
+    }
+
+    /// Sets the light falling on every tile by index, marking the tiles whose
+    /// light changed. Returns `true` if any light changed.
+    pub(crate) fn set_light(&mut self, light: Option<Vec<[f32; 3]>>) -> bool {
+        if self.light == light {
+            return false;
+        }
+        let changed = changed_indices(&self.light, &light);
+        self.light = light;
+        self.mark_indices_dirty(changed);
+        true
+    }
+
+    /// Sets the shade of the corners of every tile by index, marking the
+    /// tiles whose shade changed. Returns `true` if any shade changed.
+    pub(crate) fn set_shade(&mut self, shade: Option<Vec<[f32; 4]>>) -> bool {
+        if self.shade == shade {
+            return false;
+        }
+        let changed = changed_indices(&self.shade, &shade);
+        self.shade = shade;
+        self.mark_indices_dirty(changed);
+        true
+    }
+
+    /// Adds the tiles of every layer at the indices of the changed tiles of
+    /// occluding layers, as those may now be hidden or shown.
+    pub(crate) fn add_covered_tiles(
+        &self,
+        tiles: &mut HashSet<(usize, usize, usize)>,
+        occluding_layers: &HashSet<usize>,
+    ) {
+        let indices: Vec<usize> = tiles
+            .iter()
+            .filter(|(_, sprite_order, _)| occluding_layers.contains(sprite_order))
+            .map(|(_, _, index)| *index)
+            .collect();
+        for (z_depth, layers) in self.z_layers.iter().enumerate() {
+            for (sprite_order, layer) in layers.iter().enumerate() {
+                if layer.is_some() {
+                    for index in indices.iter() {
+                        tiles.insert((z_depth, sprite_order, *index));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marks the tiles of every layer at some indices as changed, or every
+    /// tile with `None`.
+    fn mark_indices_dirty(&mut self, indices: Option<Vec<usize>>) {
+        match indices {
+            Some(indices) => {
+                let layers: Vec<(usize, usize)> = self
+                    .z_layers
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(z_depth, layers)| {
+                        layers
+                            .iter()
+                            .enumerate()
+                            .filter(|(_sprite_order, layer)| layer.is_some())
+                            .map(move |(sprite_order, _layer)| (z_depth, sprite_order))
+                    })
+                    .collect();
+                for index in indices {
+                    for (z_depth, sprite_order) in layers.iter() {
+                        self.mark_dirty(*z_depth, *sprite_order, index);
+                    }
+                }
+            }
+            None => self.mark_all_dirty(),
+        }
+    }
+
+    /// Returns the light falling on a tile, which is full light if the chunk
+    /// is not lit.
+    pub(crate) fn light(&self, index: usize) -> [f32; 3] {
+        self.light
+            .as_ref()
+            .and_then(|light| light.get(index).copied())
+            .unwrap_or([1.0; 3])
+    }
+
+    /// Returns the shade of the corners of a tile, which is unshaded if the
+    /// chunk is not shaded.
+    pub(crate) fn shade(&self, index: usize) -> [f32; 4] {
+        self.shade
+            .as_ref()
+            .and_then(|shade| shade.get(index).copied())
+            .unwrap_or([1.0; 4])
+    }
+
+    /// Sets the uniform of a sprite layer, or `None` to remove it. Returns
+    /// `true` if the uniform changed, in which case the whole meshes need
+    /// updating.
+    pub(crate) fn set_layer_uniform(
+        &mut self,
+        sprite_order: usize,
+        uniform: Option<[f32; 4]>,
+    ) -> bool {
+        let previous = match uniform {
+            Some(uniform) => self.layer_uniforms.insert(sprite_order, uniform),
+            None => self.layer_uniforms.remove(&sprite_order),
+        };
+        if previous == uniform {
+            return false;
+        }
+        self.mark_all_dirty();
+        true
+    }
+
+    /// Returns the uniform of a sprite layer, if it has one.
+    pub(crate) fn layer_uniform(&self, sprite_order: usize) -> Option<[f32; 4]> {
+        self.layer_uniforms.get(&sprite_order).copied()
+    }
+
+    /// Stores the tile attributes of a texture atlas built ahead of spawning.
+    pub(crate) fn set_prewarmed(&mut self, atlas: usize, attributes: TileAttributes) {
+        self.prewarmed.insert(atlas, attributes);
+    }
+
+    /// Takes the tile attributes of a texture atlas if they were built ahead
+    /// of spawning and no tile changed since.
+    pub(crate) fn take_prewarmed(&mut self, atlas: usize) -> Option<TileAttributes> {
+        self.prewarmed.remove(&atlas)
+    }
+
+    /// Returns `true` if the tile attributes were built ahead of spawning.
+    pub(crate) fn is_prewarmed(&self) -> bool {
+        !self.prewarmed.is_empty()
+    }
+
+    /// Takes the tiles which changed since the meshes were last updated, or
+    /// `None` if the whole meshes need updating.
+    pub(crate) fn take_dirty_tiles(&mut self) -> Option<HashSet<(usize, usize, usize)>> {
+        self.dirty_tiles.replace(HashSet::default())
+    }
+
+    /// Returns the sprite layers which exist in the order of the chunk's
+    /// meshes, along with their z depth and sprite order.
+    ///
+    /// The layers are ordered by z depth, then by the sort keys of their
+    /// sprite orders, then by sprite order. A sprite order without a sort key
+    /// is its own sort key. Baked layers are left out.
+    fn mesh_layers(&self, sort_keys: &HashMap<usize, i32>) -> Vec<(usize, usize, &SpriteLayer)> {
+        let mut layers = Vec::new();
+        for (z_depth, sprite_layers) in self.z_layers.iter().enumerate() {
+            let start = layers.len();
+            for (sprite_order, layer) in sprite_layers.iter().enumerate() {
+                if let Some(layer) = layer {
+                    if !self.baked_layers.contains(&sprite_order) {
+                        layers.push((z_depth, sprite_order, layer));
+                    }
+                }
+            }
+            if let Some(depth_layers) = layers.get_mut(start..).filter(|_| !sort_keys.is_empty()) {
+                depth_layers.sort_by_key(|(_z_depth, sprite_order, _layer)| {
+                    let key = sort_keys
+                        .get(sprite_order)
+                        .copied()
+                        .unwrap_or(*sprite_order as i32);
+                    (key, *sprite_order)
+                });
             }
         }
+        layers
+    }
+
+    /// Returns the position of a tile in the attribute buffers of the
+    /// chunk's meshes, if its sprite layer exists.
+    ///
+    /// Each sprite layer that exists takes up the area of the chunk, in the
+    /// order of the meshes.
+    pub(crate) fn tile_slot(
+        &self,
+        dimensions: Dimension3,
+        sort_keys: &HashMap<usize, i32>,
+        z_depth: usize,
+        sprite_order: usize,
+        index: usize,
+    ) -> Option<usize> {
+        let area = (dimensions.width * dimensions.height) as usize;
+        self.mesh_layers(sort_keys)
+            .iter()
+            .position(|(z, order, _layer)| *z == z_depth && *order == sprite_order)
+            .map(|position| position * area + index)
+    }
+
+    /// Returns the number of sprite layers up to and including the top most
+    /// layer with a tile which covers each tile of a texture atlas, by index,
+    /// or `None` if no sprite layer occludes.
+    ///
+    /// The layers are counted in the order of the mesh, by z depth then sort
+    /// key, so a tile is covered if the position of its layer in the mesh is
+    /// below the count of its index. Only the visible and still occluding
+    /// layers cover tiles.
+    pub(crate) fn occlusion(
+        &self,
+        dimensions: Dimension3,
+        atlas: usize,
+        settings: &MeshSettings,
+    ) -> Option<Vec<usize>> {
+        if settings.occluding_layers.is_empty() {
+            return None;
+        }
+        let area = (dimensions.width * dimensions.height) as usize;
+        let mut occlusion = vec![0; area];
+        for (position, (_z_depth, sprite_order, layer)) in
+            self.mesh_layers(settings.sort_keys).into_iter().enumerate()
+        {
+            if !settings.occluding_layers.contains(&sprite_order)
+                || settings.hidden_layers.contains(&sprite_order)
+                || settings.waves.contains_key(&sprite_order)
+            {
+                continue;
+            }
+            let layer = layer.inner.as_ref();
+            for (index, covered) in occlusion.iter_mut().enumerate() {
+                if layer
+                    .get_tile(index)
+                    .is_some_and(|tile| tile.covers_cell(atlas))
+                {
+                    *covered = position + 1;
+                }
+            }
+        }
+        Some(occlusion)
+    }
+
+    /// Returns `true` if the tile in a slot of the attribute buffers is
+    /// hidden by a tile above it, given the occlusion of the chunk.
+    ///
+    /// Tiles in wave layers move out of their cell, so they are never hidden.
+    pub(crate) fn is_occluded(
+        occlusion: &[usize],
+        dimensions: Dimension3,
+        slot: usize,
+        tile: &RawTile,
+        has_wave: bool,
+    ) -> bool {
+        let area = (dimensions.width * dimensions.height) as usize;
+        !has_wave
+            && tile.fits_cell()
+            && occlusion
+                .get(slot % area)
+                .is_some_and(|covered| slot / area + 1 < *covered)
+    }
+
+    /// Returns the depth of the tiles of a y-sorted layer in a row of the
+    /// chunk, between 0 and 1. Lower rows are in front of higher rows, also
+    /// across chunks.
+    fn y_sort_depth(&self, dimensions: Dimension3, row: usize) -> f32 {
+        let y = self.point.y * dimensions.height as Coord + row as Coord;
+        let y = (y as f32).clamp(-Y_SORT_ROWS, Y_SORT_ROWS);
+        (Y_SORT_ROWS - y) / (2.0 * Y_SORT_ROWS + 1.0)
     }
 
     /// At the given z layer, changes the tiles into attributes for use with
-    /// the renderer using the given dimensions.
+    /// the renderer using the given dimensions. Only the tiles in the texture
+    /// atlas, not in a hidden sprite layer and not covered by an occluding
+    /// layer are visible. The tiles of y-sorted layers are sorted by their
+    /// row and the layers above them are raised above them, unless the layers
+    /// have a depth of their own. The depths are fitted into the depth range
+    /// if there is one. The layers with a color grading are graded by the
+    /// chunk shaders. The sprite indices are looked up in the theme.
     ///
     /// Easier to pass in the dimensions opposed to storing it everywhere.
     pub(crate) fn tiles_to_renderer_parts(
         &self,
         dimensions: Dimension3,
-    ) -> (Vec<f32>, Vec<[f32; 4]>) {
-        let mut tile_indices = Vec::new();
-        let mut tile_colors = Vec::new();
-        for depth in &self.z_layers {
-            for layer in depth.iter().flatten() {
-                let (mut indices, mut colors) =
-                    layer.inner.as_ref().tiles_to_attributes(dimensions);
-                tile_indices.append(&mut indices);
-                tile_colors.append(&mut colors);
+        atlas: usize,
+        settings: &MeshSettings,
+    ) -> TileAttributes {
+        let mut attributes = TileAttributes::default();
+        self.write_renderer_parts(dimensions, atlas, settings, &mut attributes);
+        attributes
+    }
+
+    /// Same as [`tiles_to_renderer_parts`], but replaces the contents of
+    /// existing attributes so their buffers can be reused.
+    ///
+    /// [`tiles_to_renderer_parts`]: Chunk::tiles_to_renderer_parts
+    pub(crate) fn write_renderer_parts(
+        &self,
+        dimensions: Dimension3,
+        atlas: usize,
+        settings: &MeshSettings,
+        attributes: &mut TileAttributes,
+    ) {
+        let MeshSettings {
+            hidden_layers,
+            waves,
+            y_sorted_layers,
+            sort_keys,
+            layer_depths,
+            depth_range,
+            gradings,
+            theme,
+            ..
+        } = *settings;
+        let area = (dimensions.width * dimensions.height) as usize;
+        let occlusion = self.occlusion(dimensions, atlas, settings);
+        attributes.clear();
+        let mut layer_depth = 0.0;
+        for (position, (_z_depth, sprite_order, layer)) in
+            self.mesh_layers(sort_keys).into_iter().enumerate()
+        {
+            let start = attributes.indexes.len();
+            layer
+                .inner
+                .as_ref()
+                .extend_attributes(dimensions, atlas, attributes);
+            let end = attributes.indexes.len();
+            let layer_colors = attributes.colors.get_mut(start..).unwrap_or(&mut []);
+            if let Some(light) = &self.light {
+                // Each tile has four vertices.
+                for (vertex, color) in layer_colors.iter_mut().enumerate() {
+                    if let Some(light) = light.get(vertex / 4) {
+                        *color = tint(*color, *light);
+                    }
+                }
+            }
+            if let Some(shade) = &self.shade {
+                for (vertex, color) in layer_colors.iter_mut().enumerate() {
+                    let shade = shade
+                        .get(vertex / 4)
+                        .and_then(|shade| shade.get(vertex % 4));
+                    if let Some(shade) = shade {
+                        *color = tint(*color, [*shade; 3]);
+                    }
+                }
+            }
+            if hidden_layers.contains(&sprite_order) {
+                for color in layer_colors.iter_mut() {
+                    *color = [0.0, 0.0, 0.0, 0.0];
+                }
+            }
+            if let Some(occlusion) = &occlusion {
+                let has_wave = waves.contains_key(&sprite_order);
+                for index in 0..area {
+                    let occluded = layer.inner.as_ref().get_tile(index).is_some_and(|tile| {
+                        Chunk::is_occluded(
+                            occlusion,
+                            dimensions,
+                            position * area + index,
+                            tile,
+                            has_wave,
+                        )
+                    });
+                    if occluded {
+                        // Each tile has four vertices.
+                        for color in layer_colors.iter_mut().skip(index * 4).take(4) {
+                            *color = [0.0, 0.0, 0.0, 0.0];
+                        }
+                    }
+                }
+            }
+            let wave = waves
+                .get(&sprite_order)
+                .map_or([0.0; 3], |wave| wave.attribute());
+            attributes.waves.resize(end, wave);
+            let uniform = self.layer_uniform(sprite_order).unwrap_or([0.0; 4]);
+            attributes.uniforms.resize(end, uniform);
+            let grading = gradings
+                .get(&sprite_order)
+                .map_or([0.0; 2], |grading| grading.attribute());
+            attributes.gradings.resize(end, grading);
+            let depth = layer_depths
+                .get(&sprite_order)
+                .copied()
+                .unwrap_or(layer_depth);
+            if y_sorted_layers.contains(&sprite_order) {
+                let width = dimensions.width as usize;
+                // Each tile has four vertices.
+                attributes.depths.extend(
+                    (0..end - start)
+                        .map(|vertex| depth + self.y_sort_depth(dimensions, vertex / 4 / width)),
+                );
+                if !layer_depths.contains_key(&sprite_order) {
+                    layer_depth += 1.0;
+                }
+            } else {
+                attributes.depths.resize(end, depth);
+            }
+        }
+        if let Some(depth_range) = depth_range {
+            let bounds = depth_bounds(y_sorted_layers, layer_depths);
+            for depth in attributes.depths.iter_mut() {
+                *depth = fit_depth(*depth, bounds, depth_range);
+            }
+        }
+        if !theme.is_empty() {
+            for index in attributes.indexes.iter_mut() {
+                if let Some(sprite_index) = theme.get(&(*index as u32)) {
+                    *index = *sprite_index as f32;
+                }
             }
         }
-        (tile_indices, tile_colors)
+    }
+}
+
+/// Returns the lowest and highest depth of anything drawn for a chunk,
+/// relative to the chunk.
+///
+/// The baked layers are drawn 0.5 below their depth and the tiles of y-sorted
+/// layers up to 1.0 above it.
+pub(crate) fn depth_bounds(
+    y_sorted_layers: &HashSet<usize>,
+    layer_depths: &HashMap<usize, f32>,
+) -> (f32, f32) {
+    let raised = y_sorted_layers
+        .iter()
+        .filter(|sprite_order| !layer_depths.contains_key(sprite_order))
+        .count() as f32;
+    let (low, high) = layer_depths
+        .values()
+        .fold((0.0, raised), |(low, high): (f32, f32), depth| {
+            (low.min(*depth), high.max(*depth))
+        });
+    (low - 0.5, high + 1.0)
+}
+
+/// Fits a depth relative to a chunk into a depth range, returning it
+/// relative to the lowest depth of the range.
+pub(crate) fn fit_depth(depth: f32, (low, high): (f32, f32), (min, max): (f32, f32)) -> f32 {
+    (depth - low) * (max - min) / (high - low)
+}
+
+/// Returns the indices whose values differ between two per tile values, or
+/// `None` if they can not be compared and every tile changed.
+fn changed_indices<T: PartialEq>(old: &Option<Vec<T>>, new: &Option<Vec<T>>) -> Option<Vec<usize>> {
+    match (old, new) {
+        (Some(old), Some(new)) if old.len() == new.len() => Some(
+            old.iter()
+                .zip(new.iter())
+                .enumerate()
+                .filter(|(_index, (old, new))| old != new)
+                .map(|(index, _value)| index)
+                .collect(),
+        ),
+        _ => None,
     }
 }
 
@@ -338,10 +1287,40 @@ impl Chunk {
 mod tests {
     use super::*;
 
+    /// Owns the settings that the meshes of the chunks in the tests are
+    /// built with, which are all empty.
+    #[derive(Default)]
+    struct Settings {
+        hidden_layers: HashSet<usize>,
+        waves: HashMap<usize, Wave>,
+        occluding_layers: HashSet<usize>,
+        y_sorted_layers: HashSet<usize>,
+        sort_keys: HashMap<usize, i32>,
+        layer_depths: HashMap<usize, f32>,
+        gradings: HashMap<usize, ColorGrading>,
+        theme: HashMap<u32, usize>,
+    }
+
+    impl Settings {
+        fn mesh(&self) -> MeshSettings<'_> {
+            MeshSettings {
+                hidden_layers: &self.hidden_layers,
+                waves: &self.waves,
+                occluding_layers: &self.occluding_layers,
+                y_sorted_layers: &self.y_sorted_layers,
+                sort_keys: &self.sort_keys,
+                layer_depths: &self.layer_depths,
+                depth_range: None,
+                gradings: &self.gradings,
+                theme: &self.theme,
+            }
+        }
+    }
+
     #[test]
     fn test_layer() {
         let point = Point2::new(0, 0);
-        let layers = vec![
+        let layers = [
             Some(LayerKind::Dense),
             Some(LayerKind::Sparse),
             None,
@@ -352,7 +1331,7 @@ mod tests {
         let mut chunk = Chunk::new(point, &[None, None, None, None, None], dimensions);
         for (x, layer) in layers.iter().enumerate() {
             if let Some(layer) = layer {
-                chunk.add_sprite_layer(&layer, x, dimensions);
+                chunk.add_sprite_layer(layer, x, dimensions);
             }
         }
 
@@ -362,9 +1341,9 @@ mod tests {
         }
 
         chunk.move_sprite_layer(1, 2);
-        let sprite_layers = chunk.z_layers.get(0).unwrap();
+        let sprite_layers = chunk.z_layers.first().unwrap();
         assert_eq!(sprite_layers.get(1).unwrap().as_ref(), None);
-        assert!(sprite_layers.get(0).unwrap().as_ref().is_some());
+        assert!(sprite_layers.first().unwrap().as_ref().is_some());
 
         chunk.remove_sprite_layer(0);
         assert_eq!(chunk.z_layers.len(), 3);
@@ -372,4 +1351,612 @@ mod tests {
             assert_eq!(layer.len(), 4);
         }
     }
+
+    #[test]
+    fn test_iter_tiles() {
+        let dimensions = Dimension3::new(4, 4, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[
+                Some(LayerKind::Dense),
+                Some(LayerKind::Sparse),
+                Some(LayerKind::RunLength),
+            ],
+            dimensions,
+        );
+        let tile = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+        for sprite_order in 0..3 {
+            chunk.set_raw_tile(2 + sprite_order, sprite_order, 0, tile);
+            chunk.set_raw_tile(3 + sprite_order, sprite_order, 0, tile);
+        }
+
+        let mut tiles: Vec<(usize, usize, usize)> = chunk
+            .iter_tiles(None)
+            .map(|(z_depth, sprite_order, index, _tile)| (z_depth, sprite_order, index))
+            .collect();
+        tiles.sort_unstable();
+        assert_eq!(
+            tiles,
+            vec![
+                (0, 0, 2),
+                (0, 0, 3),
+                (0, 1, 3),
+                (0, 1, 4),
+                (0, 2, 4),
+                (0, 2, 5)
+            ]
+        );
+        let run_length: Vec<usize> = chunk
+            .iter_tiles(Some(2))
+            .map(|(_z_depth, _sprite_order, index, _tile)| index)
+            .collect();
+        assert_eq!(run_length, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_is_occupied() {
+        let dimensions = Dimension3::new(8, 8, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[
+                Some(LayerKind::Dense),
+                Some(LayerKind::Sparse),
+                Some(LayerKind::RunLength),
+            ],
+            dimensions,
+        );
+        let tile = RawTile::default();
+        for sprite_order in 0..3 {
+            chunk.set_raw_tile(3, sprite_order, 0, tile);
+            chunk.set_raw_tile(63, sprite_order, 0, tile);
+            chunk.remove_tile(3, sprite_order, 0);
+            assert!(!chunk.is_occupied(3, sprite_order, 0));
+            assert!(chunk.is_occupied(63, sprite_order, 0));
+            assert!(!chunk.is_occupied(64, sprite_order, 0));
+        }
+        // Transparent tiles do not count in dense layers.
+        chunk.set_raw_tile(
+            5,
+            0,
+            0,
+            RawTile {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.0),
+                ..Default::default()
+            },
+        );
+        assert!(!chunk.is_occupied(5, 0, 0));
+    }
+
+    #[test]
+    fn test_compact() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(4, 4, 2);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse), None],
+            dimensions,
+        );
+        let tile = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+        chunk.set_raw_tile(5, 0, 0, tile);
+
+        let before = chunk.heap_size();
+        let reclaimed = chunk.compact();
+        assert!(reclaimed >= 16 * mem::size_of::<RawTile>());
+        assert_eq!(chunk.heap_size(), before - reclaimed);
+        assert_eq!(chunk.z_layers[0].len(), 2);
+        assert_eq!(chunk.get_tile(5, 0, 0), Some(&tile));
+        assert_eq!(chunk.compact(), 0);
+
+        // The freed dense layer still renders its whole area.
+        let attributes = chunk.tiles_to_renderer_parts(dimensions, 0, &settings.mesh());
+        assert_eq!(attributes.colors.len(), 4 * 16 * 4);
+        assert_eq!(attributes.waves.len(), attributes.colors.len());
+
+        chunk.set_raw_tile(3, 0, 1, tile);
+        assert_eq!(chunk.get_tile(3, 0, 1), Some(&tile));
+        assert_eq!(chunk.get_tile(4, 0, 1), None);
+
+        chunk.add_sprite_layer(&LayerKind::Sparse, 2, dimensions);
+        assert!(chunk.layer_properties(2, 1).is_some());
+    }
+
+    #[test]
+    fn test_run_length_layer() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(32, 32, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::RunLength), Some(LayerKind::Dense)],
+            dimensions,
+        );
+        let grass = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+        let flower = RawTile {
+            index: 2,
+            ..Default::default()
+        };
+        for index in 0..32 * 32 {
+            chunk.set_raw_tile(index, 0, 0, grass);
+            chunk.set_raw_tile(index, 1, 0, grass);
+        }
+        chunk.set_raw_tile(40, 0, 0, flower);
+        chunk.remove_tile(41, 0, 0);
+        assert_eq!(chunk.get_tile(39, 0, 0), Some(&grass));
+        assert_eq!(chunk.get_tile(40, 0, 0), Some(&flower));
+        assert_eq!(chunk.get_tile(41, 0, 0), None);
+        assert_eq!(chunk.get_tile(42, 0, 0), Some(&grass));
+        assert_eq!(chunk.get_tile(32 * 32, 0, 0), None);
+
+        // Changing a tile splits its run, which is merged again by compacting.
+        if let Some(tile) = chunk.get_tile_mut(40, 0, 0) {
+            tile.index = 1;
+        }
+        chunk.set_raw_tile(41, 0, 0, grass);
+        chunk.compact();
+        let layer = chunk.z_layers[0][0].as_ref().unwrap().inner.as_ref();
+        assert_eq!(layer.get_tile_indices().len(), 32 * 32);
+        let dense = chunk.z_layers[0][1].as_ref().unwrap().inner.as_ref();
+        assert!(layer.heap_size() * 100 < dense.heap_size());
+
+        // Both layers render the same tiles.
+        let attributes = chunk.tiles_to_renderer_parts(dimensions, 0, &settings.mesh());
+        let (run_length, dense) = attributes.indexes.split_at(32 * 32 * 4);
+        assert_eq!(run_length, dense);
+    }
+
+    #[test]
+    fn test_atlases() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let tile = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+        chunk.set_raw_tile(0, 0, 0, tile);
+        chunk.set_raw_tile(1, 1, 0, RawTile { atlas: 2, ..tile });
+
+        let mut atlases: Vec<usize> = chunk.atlases().into_iter().collect();
+        atlases.sort_unstable();
+        assert_eq!(atlases, vec![0, 2]);
+
+        let visible = |atlas| {
+            chunk
+                .tiles_to_renderer_parts(dimensions, atlas, &settings.mesh())
+                .colors
+                .chunks(4)
+                .filter(|colors| colors.iter().all(|color| color[3] != 0.0))
+                .count()
+        };
+        assert_eq!(visible(0), 1);
+        assert_eq!(visible(2), 1);
+        assert_eq!(visible(1), 0);
+    }
+
+    #[test]
+    fn test_write_renderer_parts() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let tile = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+        chunk.set_raw_tile(0, 0, 0, tile);
+        chunk.set_raw_tile(1, 1, 0, RawTile { atlas: 2, ..tile });
+        let y_sorted_layers = vec![1].into_iter().collect();
+        let parts = |atlas| {
+            chunk.tiles_to_renderer_parts(
+                dimensions,
+                atlas,
+                &MeshSettings {
+                    y_sorted_layers: &y_sorted_layers,
+                    ..settings.mesh()
+                },
+            )
+        };
+
+        // A reused scratch buffer ends up the same as new attributes.
+        let mut scratch = TileAttributes::default();
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        for atlas in [0, 2, 0].iter() {
+            chunk.write_renderer_parts(
+                dimensions,
+                *atlas,
+                &MeshSettings {
+                    y_sorted_layers: &y_sorted_layers,
+                    ..settings.mesh()
+                },
+                &mut scratch,
+            );
+            assert_eq!(scratch, parts(*atlas));
+            scratch.copy_to_mesh(&mut mesh);
+        }
+
+        let mut expected = Mesh::new(PrimitiveTopology::TriangleList);
+        parts(0).set_mesh_attributes(&mut expected);
+        for name in [
+            render::ATTRIBUTE_TILE_INDEX.name,
+            render::ATTRIBUTE_TILE_COLOR.name,
+            render::ATTRIBUTE_TILE_DEPTH.name,
+        ]
+        .iter()
+        {
+            assert_eq!(
+                mesh.attribute(*name).map(|values| values.get_bytes()),
+                expected.attribute(*name).map(|values| values.get_bytes())
+            );
+        }
+    }
+
+    #[test]
+    fn test_layer_uniforms() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let wind = [0.5, 0.0, 0.0, 1.0];
+        chunk.take_dirty_tiles();
+        assert!(chunk.set_layer_uniform(1, Some(wind)));
+        assert_eq!(chunk.take_dirty_tiles(), None);
+        assert!(!chunk.set_layer_uniform(1, Some(wind)));
+        assert_eq!(chunk.take_dirty_tiles(), Some(HashSet::default()));
+
+        let attributes = chunk.tiles_to_renderer_parts(dimensions, 0, &settings.mesh());
+        // Each layer has four tiles of four vertices.
+        assert_eq!(attributes.uniforms.len(), 32);
+        assert!(attributes.uniforms[..16]
+            .iter()
+            .all(|uniform| *uniform == [0.0; 4]));
+        assert!(attributes.uniforms[16..]
+            .iter()
+            .all(|uniform| *uniform == wind));
+
+        chunk.move_sprite_layer(1, 2);
+        assert_eq!(chunk.layer_uniform(1), None);
+        assert_eq!(chunk.layer_uniform(2), Some(wind));
+        chunk.remove_sprite_layer(2);
+        assert_eq!(chunk.layer_uniform(2), None);
+    }
+
+    #[test]
+    fn test_layer_gradings() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let mut gradings = HashMap::default();
+        gradings.insert(
+            1,
+            ColorGrading {
+                lut: 3,
+                strength: 2.0,
+            },
+        );
+
+        let attributes = chunk.tiles_to_renderer_parts(
+            dimensions,
+            0,
+            &MeshSettings {
+                gradings: &gradings,
+                ..settings.mesh()
+            },
+        );
+        // Each layer has four tiles of four vertices, and the strength is
+        // clamped.
+        assert_eq!(attributes.gradings.len(), 32);
+        assert!(attributes.gradings[..16]
+            .iter()
+            .all(|grading| *grading == [0.0; 2]));
+        assert!(attributes.gradings[16..]
+            .iter()
+            .all(|grading| *grading == [3.0, 1.0]));
+    }
+
+    #[test]
+    fn test_theme() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(Point2::new(0, 0), &[Some(LayerKind::Sparse)], dimensions);
+        chunk.set_raw_tile(
+            0,
+            0,
+            0,
+            RawTile {
+                index: 3,
+                ..Default::default()
+            },
+        );
+        chunk.set_raw_tile(
+            1,
+            0,
+            0,
+            RawTile {
+                index: 4,
+                ..Default::default()
+            },
+        );
+
+        let mut theme = HashMap::default();
+        theme.insert(3, 7);
+        let attributes = chunk.tiles_to_renderer_parts(
+            dimensions,
+            0,
+            &MeshSettings {
+                theme: &theme,
+                ..settings.mesh()
+            },
+        );
+        assert_eq!(attributes.indexes[0], 7.0);
+        assert_eq!(attributes.indexes[4], 4.0);
+    }
+
+    #[test]
+    fn test_baked_layers() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse), None],
+            dimensions,
+        );
+        let mut static_layers = HashSet::default();
+        static_layers.insert(0);
+        static_layers.insert(2);
+        let baked_layers = chunk.sprite_layers_in(&static_layers);
+        assert_eq!(baked_layers.len(), 1);
+        assert!(baked_layers.contains(&0));
+
+        chunk.set_baked_layers(baked_layers, Entity::new(7));
+        assert_eq!(chunk.baked_layer_count(), 1);
+        assert_eq!(chunk.entities(), vec![Entity::new(7)]);
+        // Only the sparse layer is in the meshes.
+        assert_eq!(
+            chunk.tile_slot(dimensions, &HashMap::default(), 0, 1, 3),
+            Some(3)
+        );
+        let attributes = chunk.tiles_to_renderer_parts(dimensions, 0, &settings.mesh());
+        assert_eq!(attributes.indexes.len(), 2 * 2 * 4);
+        let chunk_mesh = mesh::ChunkMesh::new(dimensions, 2, Vec2::ZERO);
+        let baked_mesh = chunk_mesh.without_layers(dimensions, 1);
+        assert_eq!(baked_mesh.vertices.len(), attributes.indexes.len());
+        assert_eq!(baked_mesh.indices.len(), 2 * 2 * 6);
+
+        // Despawning puts the layers back in the meshes.
+        assert_eq!(chunk.take_entities(), vec![Entity::new(7)]);
+        assert_eq!(chunk.baked_layer_count(), 0);
+    }
+
+    #[test]
+    fn test_dirty_tiles() {
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), None, Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let tile = RawTile::default();
+
+        // Nothing is tracked before the chunk is spawned.
+        chunk.set_raw_tile(0, 0, 0, tile);
+        assert_eq!(chunk.take_dirty_tiles(), None);
+
+        chunk.set_mesh(0, Handle::weak(HandleId::random::<Mesh>()));
+        assert_eq!(chunk.take_dirty_tiles(), Some(HashSet::default()));
+        chunk.set_raw_tile(1, 0, 0, tile);
+        chunk.remove_tile(3, 2, 0);
+        let mut dirty: Vec<(usize, usize, usize)> =
+            chunk.take_dirty_tiles().unwrap().into_iter().collect();
+        dirty.sort_unstable();
+        assert_eq!(dirty, vec![(0, 0, 1), (0, 2, 3)]);
+
+        assert_eq!(
+            chunk.tile_slot(dimensions, &HashMap::default(), 0, 0, 1),
+            Some(1)
+        );
+        assert_eq!(
+            chunk.tile_slot(dimensions, &HashMap::default(), 0, 2, 3),
+            Some(7)
+        );
+        assert_eq!(
+            chunk.tile_slot(dimensions, &HashMap::default(), 0, 1, 0),
+            None
+        );
+
+        // Sorting the last layer first moves it to the front of the meshes.
+        let mut sort_keys = HashMap::default();
+        sort_keys.insert(2, -1);
+        assert_eq!(chunk.tile_slot(dimensions, &sort_keys, 0, 2, 3), Some(3));
+        assert_eq!(chunk.tile_slot(dimensions, &sort_keys, 0, 0, 1), Some(5));
+
+        // A changed tile of an occluding layer updates the tiles it covers.
+        let mut occluding_layers = HashSet::default();
+        occluding_layers.insert(2);
+        let mut tiles = HashSet::default();
+        tiles.insert((0, 2, 3));
+        chunk.add_covered_tiles(&mut tiles, &occluding_layers);
+        let mut covered: Vec<(usize, usize, usize)> = tiles.into_iter().collect();
+        covered.sort_unstable();
+        assert_eq!(covered, vec![(0, 0, 3), (0, 2, 3)]);
+
+        // Clearing a layer only marks the tiles it had.
+        chunk.clear_layer(0);
+        let mut dirty: Vec<(usize, usize, usize)> =
+            chunk.take_dirty_tiles().unwrap().into_iter().collect();
+        dirty.sort_unstable();
+        assert_eq!(dirty, vec![(0, 0, 0), (0, 0, 1)]);
+    }
+
+    #[test]
+    fn test_occlusion() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut chunk = Chunk::new(
+            Point2::new(0, 0),
+            &[Some(LayerKind::Dense), Some(LayerKind::Sparse)],
+            dimensions,
+        );
+        let tile = RawTile::default();
+        chunk.set_raw_tile(0, 0, 0, tile);
+        chunk.set_raw_tile(1, 0, 0, tile);
+        chunk.set_raw_tile(
+            2,
+            0,
+            0,
+            RawTile {
+                scale: Vec2::new(1.0, 2.0),
+                ..tile
+            },
+        );
+        chunk.set_raw_tile(0, 1, 0, tile);
+        chunk.set_raw_tile(
+            1,
+            1,
+            0,
+            RawTile {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+                ..tile
+            },
+        );
+        chunk.set_raw_tile(2, 1, 0, tile);
+
+        let mut occluding_layers = HashSet::default();
+        let visible = |occluding_layers: &HashSet<usize>| -> Vec<bool> {
+            chunk
+                .tiles_to_renderer_parts(
+                    dimensions,
+                    0,
+                    &MeshSettings {
+                        occluding_layers,
+                        ..settings.mesh()
+                    },
+                )
+                .colors
+                .chunks(4)
+                .map(|colors| colors[0][3] != 0.0)
+                .collect()
+        };
+        assert_eq!(&visible(&occluding_layers)[..3], &[true, true, true]);
+
+        occluding_layers.insert(1);
+        let occlusion = chunk
+            .occlusion(
+                dimensions,
+                0,
+                &MeshSettings {
+                    occluding_layers: &occluding_layers,
+                    ..settings.mesh()
+                },
+            )
+            .unwrap();
+        assert_eq!(occlusion, vec![2, 0, 2, 0]);
+        // Only the opaque tile hides the tile below it, not the one which
+        // overhangs its cell.
+        let visible = visible(&occluding_layers);
+        assert_eq!(&visible[..3], &[false, true, true]);
+        assert_eq!(&visible[4..7], &[true, true, true]);
+    }
+
+    #[test]
+    fn test_y_sort() {
+        let settings = Settings::default();
+        let dimensions = Dimension3::new(2, 2, 1);
+        let chunk = Chunk::new(
+            Point2::new(0, 1),
+            &[
+                Some(LayerKind::Dense),
+                Some(LayerKind::Sparse),
+                Some(LayerKind::Dense),
+            ],
+            dimensions,
+        );
+        let mut y_sorted_layers = HashSet::default();
+        y_sorted_layers.insert(1);
+        let depths = chunk
+            .tiles_to_renderer_parts(
+                dimensions,
+                0,
+                &MeshSettings {
+                    y_sorted_layers: &y_sorted_layers,
+                    ..settings.mesh()
+                },
+            )
+            .depths;
+        assert_eq!(depths.len(), 3 * 4 * 4);
+
+        // Each tile has four vertices and each layer four tiles.
+        let sorted = &depths[16..32];
+        assert!(depths[..16].iter().all(|depth| *depth == 0.0));
+        assert!(sorted.iter().all(|depth| *depth > 0.0 && *depth < 1.0));
+        assert_eq!(sorted[0], sorted[4]);
+        assert!(sorted[0] > sorted[8]);
+        assert!(depths[32..].iter().all(|depth| *depth == 1.0));
+
+        // Layers with a depth of their own keep it and do not raise the
+        // layers above them.
+        let layer_depths = vec![(1, 5.0), (0, -2.0)].into_iter().collect();
+        let depths = chunk
+            .tiles_to_renderer_parts(
+                dimensions,
+                0,
+                &MeshSettings {
+                    y_sorted_layers: &y_sorted_layers,
+                    layer_depths: &layer_depths,
+                    ..settings.mesh()
+                },
+            )
+            .depths;
+        assert!(depths[..16].iter().all(|depth| *depth == -2.0));
+        assert!(depths[16..32]
+            .iter()
+            .all(|depth| *depth > 5.0 && *depth < 6.0));
+        assert!(depths[32..].iter().all(|depth| *depth == 0.0));
+
+        // A depth range fits every depth into it.
+        let depths = chunk
+            .tiles_to_renderer_parts(
+                dimensions,
+                0,
+                &MeshSettings {
+                    y_sorted_layers: &y_sorted_layers,
+                    layer_depths: &layer_depths,
+                    depth_range: Some((10.0, 18.5)),
+                    ..settings.mesh()
+                },
+            )
+            .depths;
+        assert_eq!(depth_bounds(&y_sorted_layers, &layer_depths), (-2.5, 6.0));
+        assert!(depths[..16].iter().all(|depth| *depth == 0.5));
+        assert!(depths[16..32]
+            .iter()
+            .all(|depth| *depth > 7.5 && *depth < 8.5));
+        assert!(depths[32..].iter().all(|depth| *depth == 2.5));
+
+        // The lowest row of the chunk is behind the highest row of the chunk
+        // below it.
+        let below = Chunk::new(Point2::new(0, 0), &[Some(LayerKind::Dense)], dimensions);
+        assert!(below.y_sort_depth(dimensions, 1) > chunk.y_sort_depth(dimensions, 0));
+    }
 }
@@ -1,48 +1,424 @@
-use crate::lib::*;
+use crate::{chunk::render, lib::*, tile::FlipAxis};
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Debug)]
-/// A raw tile composed of simply an index and a color.
+/// A raw tile composed of a sprite index and a color, along with how the
+/// sprite is drawn: its texture atlas, flips, rotation, scale and offset.
+///
+/// Only the index and color are required when deserializing. The other fields
+/// default to drawing the sprite as is, so maps saved without them still
+/// load.
 pub struct RawTile {
     /// The index of the tile in the sprite sheet.
     pub index: usize,
+    /// The id of the texture atlas the sprite is in, 0 being the tilemap's
+    /// main texture atlas.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub atlas: usize,
     /// The color, or tint, of the tile.
     pub color: Color,
+    /// If the sprite is flipped horizontally.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_x: bool,
+    /// If the sprite is flipped vertically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_y: bool,
+    /// The number of 90 degree clockwise turns of the sprite, applied after
+    /// flipping. Only the lowest 2 bits are used.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation: u8,
+    /// The scale of the sprite, anchored at the bottom center of the tile. A
+    /// scale larger than 1 lets a sprite overhang its tile.
+    #[cfg_attr(feature = "serde", serde(default = "default_scale"))]
+    pub scale: Vec2,
+    /// The offset of the sprite in pixels.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub offset: Vec2,
+}
+
+/// The scale of a sprite which is drawn at the size of its tile.
+#[cfg(feature = "serde")]
+fn default_scale() -> Vec2 {
+    Vec2::ONE
 }
 
 impl Default for RawTile {
     fn default() -> Self {
         RawTile {
             index: 0,
+            atlas: 0,
             color: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+            rotation: 0,
+            scale: Vec2::ONE,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+
+impl RawTile {
+    /// Packs the flip and rotation flags into bits.
+    ///
+    /// Bit 0 is the horizontal flip, bit 1 is the vertical flip and bits 2 and
+    /// 3 are the rotation.
+    pub(crate) fn flag_bits(&self) -> u8 {
+        let mut flags = (self.rotation & 0b11) << 2;
+        if self.flip_x {
+            flags |= 0b01;
+        }
+        if self.flip_y {
+            flags |= 0b10;
+        }
+        flags
+    }
+
+    /// Unpacks the flip and rotation flags from bits made by [`flag_bits`].
+    ///
+    /// [`flag_bits`]: RawTile::flag_bits
+    pub(crate) fn set_flag_bits(&mut self, flags: u8) {
+        self.flip_x = flags & 0b01 != 0;
+        self.flip_y = flags & 0b10 != 0;
+        self.rotation = (flags >> 2) & 0b11;
+    }
+
+    /// Returns the tile as it looks mirrored across an axis.
+    ///
+    /// Mirroring flips the sprite, reverses its rotation and mirrors its
+    /// offset.
+    pub(crate) fn mirrored(mut self, axis: FlipAxis) -> RawTile {
+        match axis {
+            FlipAxis::X => {
+                self.flip_x = !self.flip_x;
+                self.offset.x = -self.offset.x;
+            }
+            FlipAxis::Y => {
+                self.flip_y = !self.flip_y;
+                self.offset.y = -self.offset.y;
+            }
+        }
+        self.rotation = (4 - (self.rotation & 0b11)) & 0b11;
+        self
+    }
+
+    /// Packs the flip and rotation flags into a single value for the renderer.
+    pub(crate) fn packed_flags(&self) -> f32 {
+        f32::from(self.flag_bits())
+    }
+
+    /// Returns `true` if the tile fully covers its cell with a sprite of a
+    /// texture atlas, so it can hide the tiles below it.
+    pub(crate) fn covers_cell(&self, atlas: usize) -> bool {
+        self.atlas == atlas
+            && self.color.a() >= 1.0
+            && self.scale == Vec2::ONE
+            && self.offset == Vec2::ZERO
+    }
+
+    /// Returns `true` if the sprite of the tile stays within its cell, so it
+    /// can be hidden by a tile above it.
+    pub(crate) fn fits_cell(&self) -> bool {
+        self.scale.x <= 1.0 && self.scale.y <= 1.0 && self.offset == Vec2::ZERO
+    }
+}
+
+/// The per vertex attributes of tiles which are used by the renderer.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct TileAttributes {
+    /// The index of the tile in the sprite sheet, per vertex.
+    pub indexes: Vec<f32>,
+    /// The color of the tile, per vertex.
+    pub colors: Vec<[f32; 4]>,
+    /// The packed flip and rotation flags of the tile, per vertex.
+    pub flags: Vec<f32>,
+    /// The scale of the tile, per vertex.
+    pub scales: Vec<[f32; 2]>,
+    /// The pixel offset of the tile, per vertex.
+    pub offsets: Vec<[f32; 2]>,
+    /// The wave of the layer of the tile, per vertex. It is the same for the
+    /// whole layer, so it is only set once the layers are put together.
+    pub waves: Vec<[f32; 3]>,
+    /// The depth added to the z of the tile, per vertex. It depends on the
+    /// layer of the tile, so it is only set once the layers are put together.
+    pub depths: Vec<f32>,
+    /// The uniform of the layer of the tile in its chunk, per vertex. It is
+    /// the same for the whole layer, so it is only set once the layers are
+    /// put together.
+    pub uniforms: Vec<[f32; 4]>,
+    /// The color grading of the layer of the tile, per vertex. It is the same
+    /// for the whole layer, so it is only set once the layers are put
+    /// together.
+    pub gradings: Vec<[f32; 2]>,
+}
+
+impl TileAttributes {
+    /// Removes all the attributes, keeping the allocated memory for reuse.
+    pub(crate) fn clear(&mut self) {
+        self.indexes.clear();
+        self.colors.clear();
+        self.flags.clear();
+        self.scales.clear();
+        self.offsets.clear();
+        self.waves.clear();
+        self.depths.clear();
+        self.uniforms.clear();
+        self.gradings.clear();
+    }
+
+    /// Sets all the attributes on a mesh.
+    pub(crate) fn set_mesh_attributes(self, mesh: &mut Mesh) {
+        mesh.set_attribute(render::ATTRIBUTE_TILE_INDEX.name, self.indexes);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_COLOR.name, self.colors);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_FLAGS.name, self.flags);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_SCALE.name, self.scales);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_OFFSET.name, self.offsets);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_WAVE.name, self.waves);
+        mesh.set_attribute(render::ATTRIBUTE_TILE_DEPTH.name, self.depths);
+        mesh.set_attribute(render::ATTRIBUTE_LAYER_UNIFORM.name, self.uniforms);
+        mesh.set_attribute(render::ATTRIBUTE_LAYER_GRADING.name, self.gradings);
+    }
+
+    /// Copies all the attributes into a mesh.
+    ///
+    /// The buffers the mesh already has are reused, so unlike
+    /// [`set_mesh_attributes`] nothing is allocated when the attributes are
+    /// built in a reused scratch buffer and the mesh is updated again.
+    ///
+    /// [`set_mesh_attributes`]: TileAttributes::set_mesh_attributes
+    pub(crate) fn copy_to_mesh(&self, mesh: &mut Mesh) {
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_INDEX.name, &self.indexes);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_COLOR.name, &self.colors);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_FLAGS.name, &self.flags);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_SCALE.name, &self.scales);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_OFFSET.name, &self.offsets);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_WAVE.name, &self.waves);
+        copy_attribute(mesh, render::ATTRIBUTE_TILE_DEPTH.name, &self.depths);
+        copy_attribute(mesh, render::ATTRIBUTE_LAYER_UNIFORM.name, &self.uniforms);
+        copy_attribute(mesh, render::ATTRIBUTE_LAYER_GRADING.name, &self.gradings);
+    }
+}
+
+/// A value of a vertex attribute whose buffer can be borrowed from a mesh.
+trait AttributeValue: Copy + Sized {
+    /// Returns the buffer of the values if they are of this type.
+    fn values_mut(values: &mut VertexAttributeValues) -> Option<&mut Vec<Self>>;
+}
+
+impl AttributeValue for f32 {
+    fn values_mut(values: &mut VertexAttributeValues) -> Option<&mut Vec<f32>> {
+        match values {
+            VertexAttributeValues::Float(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl AttributeValue for [f32; 2] {
+    fn values_mut(values: &mut VertexAttributeValues) -> Option<&mut Vec<[f32; 2]>> {
+        match values {
+            VertexAttributeValues::Float2(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl AttributeValue for [f32; 3] {
+    fn values_mut(values: &mut VertexAttributeValues) -> Option<&mut Vec<[f32; 3]>> {
+        match values {
+            VertexAttributeValues::Float3(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl AttributeValue for [f32; 4] {
+    fn values_mut(values: &mut VertexAttributeValues) -> Option<&mut Vec<[f32; 4]>> {
+        match values {
+            VertexAttributeValues::Float4(values) => Some(values),
+            _ => None,
         }
     }
 }
 
-/// A utility function that takes an array of `Tile`s and splits the indexes and
-/// colors and returns them as separate vectors for use in the renderer.
-pub(crate) fn dense_tiles_to_attributes(tiles: &[RawTile]) -> (Vec<f32>, Vec<[f32; 4]>) {
-    let capacity = tiles.len() * 4;
-    let mut tile_indexes: Vec<f32> = Vec::with_capacity(capacity);
-    let mut tile_colors: Vec<[f32; 4]> = Vec::with_capacity(capacity);
+/// Copies values into the buffer of an attribute of a mesh, or sets the
+/// attribute if the mesh does not have it with the same type.
+fn copy_attribute<T>(mesh: &mut Mesh, name: &'static str, values: &[T])
+where
+    T: AttributeValue,
+    Vec<T>: Into<VertexAttributeValues>,
+{
+    if let Some(buffer) = mesh.attribute_mut(name).and_then(T::values_mut) {
+        buffer.clear();
+        buffer.extend_from_slice(values);
+        return;
+    }
+    mesh.set_attribute(name, values.to_vec());
+}
+
+/// Sets the 4 vertices of a tile slot to a value, returning `false` if the
+/// slot is out of bounds.
+fn set_slot_vertices<T: Copy>(values: &mut [T], slot: usize, value: T) -> bool {
+    match values.get_mut(slot * 4..slot * 4 + 4) {
+        Some(vertices) => {
+            for vertex in vertices.iter_mut() {
+                *vertex = value;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Tints a color by the light falling on a tile, keeping its alpha.
+pub(crate) fn tint(color: [f32; 4], light: [f32; 3]) -> [f32; 4] {
+    [
+        color[0] * light[0],
+        color[1] * light[1],
+        color[2] * light[2],
+        color[3],
+    ]
+}
+
+/// Updates the attributes of a single tile slot in a mesh which already has
+/// all the attributes set.
+///
+/// A missing tile or a tile which is not in the texture atlas is transparent.
+/// The color is tinted by the light falling on the tile and the shade of its
+/// corners, and the sprite index is looked up in the theme. Returns `false` if the mesh does not have the attributes or the slot is out
+/// of their bounds, in which case they need to be set in full.
+pub(crate) fn set_tile_mesh_attributes(
+    mesh: &mut Mesh,
+    slot: usize,
+    tile: Option<&RawTile>,
+    light: [f32; 3],
+    shade: [f32; 4],
+    atlas: usize,
+    theme: &HashMap<u32, usize>,
+) -> bool {
+    let (index, color, flags, scale, offset) = match tile.filter(|tile| tile.atlas == atlas) {
+        Some(tile) => (
+            theme
+                .get(&(tile.index as u32))
+                .map_or(tile.index, |sprite_index| *sprite_index) as f32,
+            tint(tile.color.into(), light),
+            tile.packed_flags(),
+            tile.scale.into(),
+            tile.offset.into(),
+        ),
+        None => (0.0, [0.0; 4], 0.0, [1.0; 2], [0.0; 2]),
+    };
+    let set_index = match mesh.attribute_mut(render::ATTRIBUTE_TILE_INDEX.name) {
+        Some(VertexAttributeValues::Float(values)) => set_slot_vertices(values, slot, index),
+        _ => false,
+    };
+    let set_color = match mesh.attribute_mut(render::ATTRIBUTE_TILE_COLOR.name) {
+        Some(VertexAttributeValues::Float4(values)) => {
+            match values.get_mut(slot * 4..slot * 4 + 4) {
+                Some(vertices) => {
+                    for (vertex, shade) in vertices.iter_mut().zip(shade.iter()) {
+                        *vertex = tint(color, [*shade; 3]);
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+        _ => false,
+    };
+    let set_flags = match mesh.attribute_mut(render::ATTRIBUTE_TILE_FLAGS.name) {
+        Some(VertexAttributeValues::Float(values)) => set_slot_vertices(values, slot, flags),
+        _ => false,
+    };
+    let set_scale = match mesh.attribute_mut(render::ATTRIBUTE_TILE_SCALE.name) {
+        Some(VertexAttributeValues::Float2(values)) => set_slot_vertices(values, slot, scale),
+        _ => false,
+    };
+    let set_offset = match mesh.attribute_mut(render::ATTRIBUTE_TILE_OFFSET.name) {
+        Some(VertexAttributeValues::Float2(values)) => set_slot_vertices(values, slot, offset),
+        _ => false,
+    };
+    set_index && set_color && set_flags && set_scale && set_offset
+}
+
+/// A utility function that takes an array of `Tile`s and splits the indexes,
+/// colors, flags and transforms and adds them to the attributes for use in
+/// the renderer.
+///
+/// Tiles which are not in the texture atlas are transparent.
+pub(crate) fn extend_dense_attributes(
+    tiles: &[RawTile],
+    atlas: usize,
+    attributes: &mut TileAttributes,
+) {
     for tile in tiles.iter() {
-        tile_indexes.extend([tile.index as f32; 4].iter());
-        tile_colors.extend([tile.color.into(); 4].iter());
+        let color = if tile.atlas == atlas {
+            tile.color.into()
+        } else {
+            [0.0, 0.0, 0.0, 0.0]
+        };
+        attributes.indexes.extend([tile.index as f32; 4].iter());
+        attributes.colors.extend([color; 4].iter());
+        attributes.flags.extend([tile.packed_flags(); 4].iter());
+        attributes.scales.extend([tile.scale.into(); 4].iter());
+        attributes.offsets.extend([tile.offset.into(); 4].iter());
+    }
+}
+
+/// A utility function that adds the attributes of a run of tiles which are
+/// all the same tile, or all empty, for use in the renderer.
+///
+/// Tiles which are not in the texture atlas are left transparent.
+pub(crate) fn extend_run_attributes(
+    tile: Option<&RawTile>,
+    len: usize,
+    atlas: usize,
+    attributes: &mut TileAttributes,
+) {
+    let end = attributes.indexes.len() + len * 4;
+    match tile.filter(|tile| tile.atlas == atlas) {
+        Some(tile) => {
+            attributes.indexes.resize(end, tile.index as f32);
+            attributes.colors.resize(end, tile.color.into());
+            attributes.flags.resize(end, tile.packed_flags());
+            attributes.scales.resize(end, tile.scale.into());
+            attributes.offsets.resize(end, tile.offset.into());
+        }
+        None => {
+            attributes.indexes.resize(end, 0.);
+            attributes.colors.resize(end, [0.0, 0.0, 0.0, 0.0]);
+            attributes.flags.resize(end, 0.);
+            attributes.scales.resize(end, [1.0, 1.0]);
+            attributes.offsets.resize(end, [0.0, 0.0]);
+        }
     }
-    (tile_indexes, tile_colors)
 }
 
-/// A utility function that takes a sparse map of `Tile`s and splits the indexes
-/// and colors and returns them as separate vectors for use in the renderer.
-pub(crate) fn sparse_tiles_to_attributes(
+/// A utility function that takes a sparse map of `Tile`s and splits the indexes,
+/// colors, flags and transforms and adds them to the attributes for use in
+/// the renderer.
+///
+/// Tiles which are not in the texture atlas are left out.
+pub(crate) fn extend_sparse_attributes(
     dimension: Dimension3,
     tiles: &HashMap<usize, RawTile>,
-) -> (Vec<f32>, Vec<[f32; 4]>) {
+    atlas: usize,
+    attributes: &mut TileAttributes,
+) {
     let area = (dimension.width * dimension.height) as usize;
-    let mut tile_indexes = vec![0.; area * 4];
+    let start = attributes.indexes.len();
+    let end = start + area * 4;
+    attributes.indexes.resize(end, 0.);
     // If tiles are set with an alpha of 0, they are discarded.
-    let mut tile_colors = vec![[0.0, 0.0, 0.0, 0.0]; area * 4];
-    for (index, tile) in tiles.iter() {
+    attributes.colors.resize(end, [0.0, 0.0, 0.0, 0.0]);
+    attributes.flags.resize(end, 0.);
+    attributes.scales.resize(end, [1.0, 1.0]);
+    attributes.offsets.resize(end, [0.0, 0.0]);
+    let tile_indexes = attributes.indexes.get_mut(start..).unwrap_or(&mut []);
+    let tile_colors = attributes.colors.get_mut(start..).unwrap_or(&mut []);
+    let tile_flags = attributes.flags.get_mut(start..).unwrap_or(&mut []);
+    let tile_scales = attributes.scales.get_mut(start..).unwrap_or(&mut []);
+    let tile_offsets = attributes.offsets.get_mut(start..).unwrap_or(&mut []);
+    for (index, tile) in tiles.iter().filter(|(_, tile)| tile.atlas == atlas) {
         for i in 0..4 {
             if let Some(index) = tile_indexes.get_mut(index * 4 + i) {
                 *index = tile.index as f32;
@@ -50,7 +426,37 @@ pub(crate) fn sparse_tiles_to_attributes(
             if let Some(index) = tile_colors.get_mut(index * 4 + i) {
                 *index = tile.color.into();
             }
+            if let Some(index) = tile_flags.get_mut(index * 4 + i) {
+                *index = tile.packed_flags();
+            }
+            if let Some(index) = tile_scales.get_mut(index * 4 + i) {
+                *index = tile.scale.into();
+            }
+            if let Some(index) = tile_offsets.get_mut(index * 4 + i) {
+                *index = tile.offset.into();
+            }
         }
     }
-    (tile_indexes, tile_colors)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_index_and_color() {
+        let tile: RawTile = from_ron_bytes(
+            b"(index: 3, color: Rgba(red: 1.0, green: 0.5, blue: 0.25, alpha: 1.0))",
+        )
+        .unwrap();
+        assert_eq!(
+            tile,
+            RawTile {
+                index: 3,
+                color: Color::rgba(1.0, 0.5, 0.25, 1.0),
+                ..Default::default()
+            }
+        );
+        assert_eq!(tile.scale, Vec2::ONE);
+    }
 }
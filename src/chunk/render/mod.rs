@@ -1,104 +1,411 @@
-use crate::lib::*;
-
-macro_rules! build_chunk_pipeline {
-    ($handle: ident, $id: expr, $name: ident, $file: expr) => {
-        /// The constant render pipeline for a chunk.
-        pub(crate) const $handle: HandleUntyped =
-            HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, $id);
-
-        /// Builds the chunk render pipeline.
-        fn $name(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
-            PipelineDescriptor {
-                color_target_states: vec![ColorTargetState {
-                    format: TextureFormat::default(),
-                    color_blend: BlendState {
-                        src_factor: BlendFactor::SrcAlpha,
-                        dst_factor: BlendFactor::OneMinusSrcAlpha,
-                        operation: BlendOperation::Add,
-                    },
-                    alpha_blend: BlendState {
-                        src_factor: BlendFactor::One,
-                        dst_factor: BlendFactor::One,
-                        operation: BlendOperation::Add,
-                    },
-                    write_mask: ColorWrite::ALL,
-                }],
-                depth_stencil: Some(DepthStencilState {
-                    format: TextureFormat::Depth32Float,
-                    depth_write_enabled: true,
-                    depth_compare: CompareFunction::LessEqual,
-                    stencil: StencilState {
-                        front: StencilFaceState::IGNORE,
-                        back: StencilFaceState::IGNORE,
-                        read_mask: 0,
-                        write_mask: 0,
-                    },
-                    bias: DepthBiasState {
-                        constant: 0,
-                        slope_scale: 0.0,
-                        clamp: 0.0,
-                    },
-                    clamp_depth: false,
-                }),
-                ..PipelineDescriptor::new(ShaderStages {
-                    vertex: shaders
-                        .add(Shader::from_glsl(ShaderStage::Vertex, include_str!($file))),
-                    fragment: Some(shaders.add(Shader::from_glsl(
-                        ShaderStage::Fragment,
-                        include_str!("tilemap.frag"),
-                    ))),
-                })
-            }
-        }
-    };
+//! The layout of the chunk meshes and shaders.
+//!
+//! Custom pipelines, render graph nodes and shaders for chunks can rely on the
+//! constants here for the names, locations and formats of the chunk mesh
+//! vertex attributes and the bindings of the uniforms. They are part of the
+//! public API and only change with a breaking release.
+//!
+//! Every tile in a chunk mesh is a quad of 4 vertices and every vertex of the
+//! quad has the same tile attributes. The vertex shader is chosen by the
+//! [`GridTopology`] while all of them share the same layout.
+//!
+//! # Writing a custom vertex shader
+//! ```
+//! use bevy_tilemap::chunk::render::*;
+//!
+//! let inputs: Vec<String> = VERTEX_ATTRIBUTES
+//!     .iter()
+//!     .map(|attribute| {
+//!         format!(
+//!             "layout(location = {}) in {} {};",
+//!             attribute.location, attribute.glsl_type, attribute.name
+//!         )
+//!     })
+//!     .collect();
+//!
+//! assert_eq!(inputs[1], "layout(location = 1) in float Vertex_Tile_Index;");
+//! assert_eq!(
+//!     (UNIFORM_CHUNK_TRANSFORM.set, UNIFORM_CHUNK_TRANSFORM.binding),
+//!     (2, 0)
+//! );
+//! ```
+
+use crate::{
+    chunk::entity::{ChunkColorGrading, ChunkTime, SecondaryTexture},
+    lib::*,
+};
+
+/// The layout of a vertex attribute of the chunk meshes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct VertexAttributeLayout {
+    /// The name of the attribute in the mesh and the shaders.
+    pub name: &'static str,
+    /// The location of the attribute in the vertex shaders.
+    pub location: u32,
+    /// The GLSL type of the attribute in the vertex shaders.
+    pub glsl_type: &'static str,
+}
+
+/// The layout of a uniform binding of the chunk shaders.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BindingLayout {
+    /// The name of the uniform in the shaders.
+    pub name: &'static str,
+    /// The bind group set of the uniform.
+    pub set: u32,
+    /// The binding of the uniform in its set.
+    pub binding: u32,
+}
+
+/// The position of a vertex within the chunk, in tiles.
+pub const ATTRIBUTE_POSITION: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Position",
+    location: 0,
+    glsl_type: "vec3",
+};
+
+/// The sprite index of the tile in the texture atlas.
+pub const ATTRIBUTE_TILE_INDEX: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Index",
+    location: 1,
+    glsl_type: "float",
+};
+
+/// The color of the tile. A transparent color hides the tile.
+pub const ATTRIBUTE_TILE_COLOR: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Color",
+    location: 2,
+    glsl_type: "vec4",
+};
+
+/// The flip and rotation flags of the tile. Bit 0 is the horizontal flip, bit
+/// 1 is the vertical flip and bits 2 and 3 are the number of clockwise turns.
+pub const ATTRIBUTE_TILE_FLAGS: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Flags",
+    location: 3,
+    glsl_type: "float",
+};
+
+/// The scale of the tile, anchored at its bottom center.
+pub const ATTRIBUTE_TILE_SCALE: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Scale",
+    location: 4,
+    glsl_type: "vec2",
+};
+
+/// The offset of the tile in pixels.
+pub const ATTRIBUTE_TILE_OFFSET: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Offset",
+    location: 5,
+    glsl_type: "vec2",
+};
+
+/// The wave of the layer of the tile as its amplitude in pixels, frequency in
+/// waves per second and the inverse of its wavelength in pixels. Layers
+/// without a wave have an amplitude of 0.
+pub const ATTRIBUTE_TILE_WAVE: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Wave",
+    location: 6,
+    glsl_type: "vec3",
+};
+
+/// The depth added to the z of the tile. The tiles of y-sorted layers are
+/// sorted by their row, and the layers above a y-sorted layer are raised
+/// above it. It is 0 if the tilemap has no y-sorted layers.
+pub const ATTRIBUTE_TILE_DEPTH: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Tile_Depth",
+    location: 7,
+    glsl_type: "float",
+};
+
+/// The uniform of the layer of the tile in its chunk, which is set with
+/// [`Tilemap::set_layer_uniform`]. The crate's shaders pass it on to the
+/// fragment shader as `vec4 v_Uniform` without using it, so custom shaders
+/// can use it for effects such as the strength of wind in a chunk. It is 0
+/// for layers without a uniform.
+///
+/// [`Tilemap::set_layer_uniform`]: crate::tilemap::Tilemap::set_layer_uniform
+pub const ATTRIBUTE_LAYER_UNIFORM: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Layer_Uniform",
+    location: 8,
+    glsl_type: "vec4",
+};
+
+/// The color grading of the layer of the tile, which is set with
+/// [`Tilemap::set_layer_grading`], as the index of its lookup table in the
+/// color grading texture and the strength it is blended in with. It is 0 for
+/// layers without a color grading.
+///
+/// [`Tilemap::set_layer_grading`]: crate::tilemap::Tilemap::set_layer_grading
+pub const ATTRIBUTE_LAYER_GRADING: VertexAttributeLayout = VertexAttributeLayout {
+    name: "Vertex_Layer_Grading",
+    location: 9,
+    glsl_type: "vec2",
+};
+
+/// All the vertex attributes of the chunk meshes, ordered by location.
+pub const VERTEX_ATTRIBUTES: [VertexAttributeLayout; 10] = [
+    ATTRIBUTE_POSITION,
+    ATTRIBUTE_TILE_INDEX,
+    ATTRIBUTE_TILE_COLOR,
+    ATTRIBUTE_TILE_FLAGS,
+    ATTRIBUTE_TILE_SCALE,
+    ATTRIBUTE_TILE_OFFSET,
+    ATTRIBUTE_TILE_WAVE,
+    ATTRIBUTE_TILE_DEPTH,
+    ATTRIBUTE_LAYER_UNIFORM,
+    ATTRIBUTE_LAYER_GRADING,
+];
+
+/// Returns the number of bytes of the vertex attributes of a chunk mesh vertex.
+pub(crate) fn vertex_size() -> usize {
+    VERTEX_ATTRIBUTES
+        .iter()
+        .map(|attribute| match attribute.glsl_type {
+            "vec2" => 8,
+            "vec3" => 12,
+            "vec4" => 16,
+            _ => 4,
+        })
+        .sum()
 }
 
-build_chunk_pipeline!(
-    CHUNK_SQUARE_PIPELINE,
-    2110840099625352487,
-    build_chunk_square_pipeline,
-    "tilemap-square.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEX_X_PIPELINE,
-    7038597873061171051,
-    build_chunk_hex_x,
-    "tilemap-hex-x.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEX_Y_PIPELINE,
-    4304966217182648108,
-    build_chunk_hex_y,
-    "tilemap-hex-y.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEXCOLS_EVEN_PIPELINE,
-    7604280309043018950,
-    build_chunk_hexcols_even,
-    "tilemap-hexcols-even.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEXCOLS_ODD_PIPELINE,
-    3111565682159860869,
-    build_chunk_hexcols_odd,
-    "tilemap-hexcols-odd.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEXROWS_EVEN_PIPELINE,
-    1670470246078408352,
-    build_chunk_hexrows_even,
-    "tilemap-hexrows-even.vert"
-);
-build_chunk_pipeline!(
-    CHUNK_HEXROWS_ODD_PIPELINE,
-    8160067835497533408,
-    build_chunk_hexrows_odd,
-    "tilemap-hexrows-odd.vert"
-);
+/// The `mat4 ViewProj` of the camera.
+pub const UNIFORM_CAMERA_VIEW_PROJ: BindingLayout = BindingLayout {
+    name: "CameraViewProj",
+    set: 0,
+    binding: 0,
+};
+
+/// The `vec2 AtlasSize` of the texture atlas in pixels.
+pub const UNIFORM_ATLAS_SIZE: BindingLayout = BindingLayout {
+    name: "TextureAtlas_size",
+    set: 1,
+    binding: 0,
+};
+
+/// The storage buffer of the `Rect`s of the sprites in the texture atlas,
+/// each a `vec2 begin` and `vec2 end` in pixels.
+pub const UNIFORM_ATLAS_TEXTURES: BindingLayout = BindingLayout {
+    name: "TextureAtlas_textures",
+    set: 1,
+    binding: 1,
+};
+
+/// The `texture2D` of the texture atlas.
+pub const UNIFORM_ATLAS_TEXTURE: BindingLayout = BindingLayout {
+    name: "TextureAtlas_texture",
+    set: 1,
+    binding: 2,
+};
+
+/// The `sampler` of the texture atlas.
+pub const UNIFORM_ATLAS_SAMPLER: BindingLayout = BindingLayout {
+    name: "TextureAtlas_texture_sampler",
+    set: 1,
+    binding: 3,
+};
+
+/// The `mat4 ChunkTransform` of the chunk entity.
+pub const UNIFORM_CHUNK_TRANSFORM: BindingLayout = BindingLayout {
+    name: "Transform",
+    set: 2,
+    binding: 0,
+};
+
+/// The `float Time` in seconds which moves the vertices of wave layers.
+pub const UNIFORM_CHUNK_TIME: BindingLayout = BindingLayout {
+    name: "ChunkTime",
+    set: 2,
+    binding: 1,
+};
+
+/// The `texture2D` of the secondary texture of the tilemap, such as a normal
+/// or emissive map. It has the same layout as the texture atlas, so it is
+/// sampled at the same `v_Uv`.
+///
+/// The crate's shaders do not use it, it is only bound for custom fragment
+/// shaders of chunks of the main texture atlas while the tilemap has a
+/// secondary texture.
+pub const UNIFORM_SECONDARY_TEXTURE: BindingLayout = BindingLayout {
+    name: "ChunkSecondary_texture",
+    set: 3,
+    binding: 0,
+};
+
+/// The `sampler` of the secondary texture of the tilemap.
+pub const UNIFORM_SECONDARY_SAMPLER: BindingLayout = BindingLayout {
+    name: "ChunkSecondary_texture_sampler",
+    set: 3,
+    binding: 1,
+};
+
+/// The `texture2D` of the color grading lookup tables of the tilemap, see
+/// [`Tilemap::set_color_grading`]. Chunks of tilemaps without one bind a
+/// texture which keeps the colors as they are.
+///
+/// [`Tilemap::set_color_grading`]: crate::tilemap::Tilemap::set_color_grading
+pub const UNIFORM_COLOR_GRADING_TEXTURE: BindingLayout = BindingLayout {
+    name: "ChunkColorGrading_texture",
+    set: 3,
+    binding: 2,
+};
+
+/// The `sampler` of the color grading lookup tables of the tilemap.
+pub const UNIFORM_COLOR_GRADING_SAMPLER: BindingLayout = BindingLayout {
+    name: "ChunkColorGrading_texture_sampler",
+    set: 3,
+    binding: 3,
+};
+
+/// The name of the render graph node which sets the time of the chunks.
+pub(crate) const CHUNK_TIME_NODE: &str = "chunk_time";
+
+/// The name of the render graph node which binds the secondary textures of
+/// the chunks.
+pub(crate) const CHUNK_SECONDARY_TEXTURE_NODE: &str = "chunk_secondary_texture";
+
+/// The name of the render graph node which binds the color grading textures
+/// of the chunks.
+pub(crate) const CHUNK_COLOR_GRADING_NODE: &str = "chunk_color_grading";
+
+/// The texture of a color grading lookup table which keeps the colors as
+/// they are, bound by the chunks of tilemaps without color grading.
+pub(crate) const IDENTITY_COLOR_GRADING: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 5813047266311759042);
+
+/// The source of the fragment shader of the chunks.
+const FRAGMENT_SHADER: &str = include_str!("tilemap.frag");
+
+/// The constant render pipeline for square chunks.
+pub(crate) const CHUNK_SQUARE_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 2110840099625352487);
+/// The constant render pipeline for [`GridTopology::HexX`] chunks.
+pub(crate) const CHUNK_HEX_X_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 7038597873061171051);
+/// The constant render pipeline for [`GridTopology::HexY`] chunks.
+pub(crate) const CHUNK_HEX_Y_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 4304966217182648108);
+/// The constant render pipeline for [`GridTopology::HexEvenCols`] chunks.
+pub(crate) const CHUNK_HEXCOLS_EVEN_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 7604280309043018950);
+/// The constant render pipeline for [`GridTopology::HexOddCols`] chunks.
+pub(crate) const CHUNK_HEXCOLS_ODD_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 3111565682159860869);
+/// The constant render pipeline for [`GridTopology::HexEvenRows`] chunks.
+pub(crate) const CHUNK_HEXROWS_EVEN_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 1670470246078408352);
+/// The constant render pipeline for [`GridTopology::HexOddRows`] chunks.
+pub(crate) const CHUNK_HEXROWS_ODD_PIPELINE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 8160067835497533408);
+
+/// Builds a chunk render pipeline for a topology, where the vertex or
+/// fragment shader of the crate can be replaced by a custom shader.
+///
+/// The pipeline has the blending and depth testing the chunks need. Custom
+/// vertex shaders must take the vertex attributes and uniforms of this
+/// module, custom fragment shaders the outputs of the crate's vertex shaders,
+/// which are `vec2 v_Uv` at location 0, `vec4 v_Color` at location 1,
+/// `vec4 v_Uniform` at location 2 and `vec2 v_Grading` at location 3.
+///
+/// # Examples
+/// ```
+/// use bevy_asset::prelude::*;
+/// use bevy_ecs::prelude::*;
+/// use bevy_render::{
+///     pipeline::PipelineDescriptor,
+///     shader::{Shader, ShaderStage},
+/// };
+/// use bevy_tilemap::{chunk::render, prelude::*};
+///
+/// const GRAYSCALE: &str = r#"
+/// #version 450
+///
+/// layout(location = 0) in vec2 v_Uv;
+/// layout(location = 1) in vec4 v_Color;
+///
+/// layout(location = 0) out vec4 o_Target;
+///
+/// layout(set = 1, binding = 2) uniform texture2D TextureAtlas_texture;
+/// layout(set = 1, binding = 3) uniform sampler TextureAtlas_texture_sampler;
+///
+/// void main() {
+///     vec4 color = v_Color * texture(
+///         sampler2D(TextureAtlas_texture, TextureAtlas_texture_sampler),
+///         v_Uv
+///     );
+///     float gray = dot(color.rgb, vec3(0.299, 0.587, 0.114));
+///     o_Target = vec4(vec3(gray), color.a);
+/// }
+/// "#;
+///
+/// fn setup(
+///     mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+///     mut shaders: ResMut<Assets<Shader>>,
+/// ) {
+///     let fragment = shaders.add(Shader::from_glsl(ShaderStage::Fragment, GRAYSCALE));
+///     let pipeline = render::chunk_pipeline(GridTopology::Square, None, Some(fragment), &mut shaders);
+///     let pipeline_handle = pipelines.add(pipeline);
+///
+///     // Used with `TilemapBuilder::render_pipeline(pipeline_handle)`.
+/// }
+/// ```
+pub fn chunk_pipeline(
+    topology: GridTopology,
+    vertex: Option<Handle<Shader>>,
+    fragment: Option<Handle<Shader>>,
+    shaders: &mut Assets<Shader>,
+) -> PipelineDescriptor {
+    let vertex = vertex.unwrap_or_else(|| {
+        shaders.add(Shader::from_glsl(
+            ShaderStage::Vertex,
+            topology.vertex_shader(),
+        ))
+    });
+    let fragment = fragment
+        .unwrap_or_else(|| shaders.add(Shader::from_glsl(ShaderStage::Fragment, FRAGMENT_SHADER)));
+    PipelineDescriptor {
+        color_target_states: vec![ColorTargetState {
+            format: TextureFormat::default(),
+            color_blend: BlendState {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendState {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            write_mask: ColorWrite::ALL,
+        }],
+        depth_stencil: Some(DepthStencilState {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::LessEqual,
+            stencil: StencilState {
+                front: StencilFaceState::IGNORE,
+                back: StencilFaceState::IGNORE,
+                read_mask: 0,
+                write_mask: 0,
+            },
+            bias: DepthBiasState {
+                constant: 0,
+                slope_scale: 0.0,
+                clamp: 0.0,
+            },
+            clamp_depth: false,
+        }),
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex,
+            fragment: Some(fragment),
+        })
+    }
+}
 
 /// Topology of the tilemap grid (square or hex)
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GridTopology {
     /// Square grid
     Square,
@@ -116,7 +423,32 @@ pub enum GridTopology {
     HexOddCols,
 }
 
+/// Every grid topology.
+const TOPOLOGIES: [GridTopology; 7] = [
+    GridTopology::Square,
+    GridTopology::HexY,
+    GridTopology::HexX,
+    GridTopology::HexEvenRows,
+    GridTopology::HexOddRows,
+    GridTopology::HexEvenCols,
+    GridTopology::HexOddCols,
+];
+
 impl GridTopology {
+    /// Returns the source of the vertex shader of the topology.
+    fn vertex_shader(self) -> &'static str {
+        use GridTopology::*;
+        match self {
+            Square => include_str!("tilemap-square.vert"),
+            HexY => include_str!("tilemap-hex-y.vert"),
+            HexX => include_str!("tilemap-hex-x.vert"),
+            HexEvenRows => include_str!("tilemap-hexrows-even.vert"),
+            HexOddRows => include_str!("tilemap-hexrows-odd.vert"),
+            HexEvenCols => include_str!("tilemap-hexcols-even.vert"),
+            HexOddCols => include_str!("tilemap-hexcols-odd.vert"),
+        }
+    }
+
     /// Takes a grid topology and returns a handle.
     pub(crate) fn into_pipeline_handle(self) -> HandleUntyped {
         use GridTopology::*;
@@ -132,23 +464,131 @@ impl GridTopology {
     }
 }
 
+/// Returns a color grading lookup table of 2 by 2 by 2 colors which keeps the
+/// colors as they are, as the linear filtering blends between its corners.
+fn identity_color_grading() -> Texture {
+    let mut data = Vec::with_capacity(32);
+    for green in 0..2u8 {
+        for blue in 0..2u8 {
+            for red in 0..2u8 {
+                data.extend_from_slice(&[red * 255, green * 255, blue * 255, 255]);
+            }
+        }
+    }
+    let mut texture = Texture::new(
+        Extent3d::new(4, 2, 1),
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+    );
+    texture.sampler.mag_filter = FilterMode::Linear;
+    texture.sampler.min_filter = FilterMode::Linear;
+    texture
+}
+
 /// Adds the tilemap graph to the pipeline and shaders.
 pub(crate) fn add_tilemap_graph(
+    render_graph: &mut RenderGraph,
     pipelines: &mut Assets<PipelineDescriptor>,
     shaders: &mut Assets<Shader>,
+    textures: &mut Assets<Texture>,
 ) {
-    // Might need graph.add_system_node here...?
-    pipelines.set_untracked(CHUNK_SQUARE_PIPELINE, build_chunk_square_pipeline(shaders));
-    pipelines.set_untracked(CHUNK_HEX_X_PIPELINE, build_chunk_hex_x(shaders));
-    pipelines.set_untracked(CHUNK_HEX_Y_PIPELINE, build_chunk_hex_y(shaders));
-    pipelines.set_untracked(
-        CHUNK_HEXCOLS_EVEN_PIPELINE,
-        build_chunk_hexcols_even(shaders),
+    render_graph.add_system_node(CHUNK_TIME_NODE, RenderResourcesNode::<ChunkTime>::new(true));
+    render_graph
+        .add_node_edge(CHUNK_TIME_NODE, node::MAIN_PASS)
+        .unwrap();
+    render_graph.add_system_node(
+        CHUNK_SECONDARY_TEXTURE_NODE,
+        RenderResourcesNode::<SecondaryTexture>::new(true),
     );
-    pipelines.set_untracked(CHUNK_HEXCOLS_ODD_PIPELINE, build_chunk_hexcols_odd(shaders));
-    pipelines.set_untracked(
-        CHUNK_HEXROWS_EVEN_PIPELINE,
-        build_chunk_hexrows_even(shaders),
+    render_graph
+        .add_node_edge(CHUNK_SECONDARY_TEXTURE_NODE, node::MAIN_PASS)
+        .unwrap();
+    render_graph.add_system_node(
+        CHUNK_COLOR_GRADING_NODE,
+        RenderResourcesNode::<ChunkColorGrading>::new(true),
     );
-    pipelines.set_untracked(CHUNK_HEXROWS_ODD_PIPELINE, build_chunk_hexrows_odd(shaders));
+    render_graph
+        .add_node_edge(CHUNK_COLOR_GRADING_NODE, node::MAIN_PASS)
+        .unwrap();
+    textures.set_untracked(IDENTITY_COLOR_GRADING, identity_color_grading());
+    for topology in TOPOLOGIES.iter() {
+        pipelines.set_untracked(
+            topology.into_pipeline_handle(),
+            chunk_pipeline(*topology, None, None, shaders),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_matches_shaders() {
+        for shader in TOPOLOGIES.iter().map(|topology| topology.vertex_shader()) {
+            for attribute in VERTEX_ATTRIBUTES.iter() {
+                let input = format!(
+                    "layout(location = {}) in {} {};",
+                    attribute.location, attribute.glsl_type, attribute.name
+                );
+                assert!(shader.contains(&input), "missing `{}`", input);
+            }
+            for uniform in [
+                UNIFORM_CAMERA_VIEW_PROJ,
+                UNIFORM_ATLAS_SIZE,
+                UNIFORM_ATLAS_TEXTURES,
+                UNIFORM_CHUNK_TRANSFORM,
+                UNIFORM_CHUNK_TIME,
+            ]
+            .iter()
+            {
+                let binding = format!(
+                    "layout(set = {}, binding = {}) ",
+                    uniform.set, uniform.binding
+                );
+                let line = shader
+                    .lines()
+                    .find(|line| line.starts_with(&binding))
+                    .unwrap();
+                assert!(line.contains(uniform.name), "missing `{}`", uniform.name);
+            }
+        }
+
+        let fragment = FRAGMENT_SHADER;
+        for uniform in [
+            UNIFORM_ATLAS_TEXTURE,
+            UNIFORM_ATLAS_SAMPLER,
+            UNIFORM_COLOR_GRADING_TEXTURE,
+            UNIFORM_COLOR_GRADING_SAMPLER,
+        ]
+        .iter()
+        {
+            let binding = format!(
+                "layout(set = {}, binding = {}) uniform {} {};",
+                uniform.set,
+                uniform.binding,
+                if uniform.name.ends_with("sampler") {
+                    "sampler"
+                } else {
+                    "texture2D"
+                },
+                uniform.name
+            );
+            assert!(fragment.contains(&binding), "missing `{}`", binding);
+        }
+    }
+
+    #[test]
+    fn test_secondary_sampler_name() {
+        // Bevy names the sampler of a texture resource after the texture.
+        assert_eq!(
+            UNIFORM_SECONDARY_SAMPLER.name,
+            format!("{}_sampler", UNIFORM_SECONDARY_TEXTURE.name)
+        );
+        assert_eq!(
+            UNIFORM_COLOR_GRADING_SAMPLER.name,
+            format!("{}_sampler", UNIFORM_COLOR_GRADING_TEXTURE.name)
+        );
+    }
 }
@@ -3,9 +3,18 @@
 layout(location = 0) in vec3 Vertex_Position;
 layout(location = 1) in float Vertex_Tile_Index;
 layout(location = 2) in vec4 Vertex_Tile_Color;
+layout(location = 3) in float Vertex_Tile_Flags;
+layout(location = 4) in vec2 Vertex_Tile_Scale;
+layout(location = 5) in vec2 Vertex_Tile_Offset;
+layout(location = 6) in vec3 Vertex_Tile_Wave;
+layout(location = 7) in float Vertex_Tile_Depth;
+layout(location = 8) in vec4 Vertex_Layer_Uniform;
+layout(location = 9) in vec2 Vertex_Layer_Grading;
 
 layout(location = 0) out vec2 v_Uv;
 layout(location = 1) out vec4 v_Color;
+layout(location = 2) out vec4 v_Uniform;
+layout(location = 3) out vec2 v_Grading;
 
 layout(set = 0, binding = 0) uniform CameraViewProj {
     mat4 ViewProj;
@@ -31,6 +40,40 @@ layout(set = 2, binding = 0) uniform Transform {
     mat4 ChunkTransform;
 };
 
+layout(set = 2, binding = 1) uniform ChunkTime {
+    float Time;
+};
+
+// Maps a corner of the quad to the corner of the sprite in the atlas, after
+// the sprite is flipped and then rotated clockwise.
+int atlas_corner(int corner) {
+    int flags = int(Vertex_Tile_Flags);
+    corner = (corner - ((flags >> 2) & 3) + 4) % 4;
+    if ((flags & 1) != 0) {
+        corner = 3 - corner;
+    }
+    if ((flags & 2) != 0) {
+        corner = corner ^ 1;
+    }
+    return corner;
+}
+
+// Moves a corner of the quad by the tile's scale, anchored at the bottom
+// center of the tile, and by the tile's pixel offset.
+vec2 tile_transform(int corner, vec2 sprite_dimensions) {
+    vec2 anchored = vec2(
+        (corner == 2 || corner == 3) ? 0.5 : -0.5,
+        (corner == 1 || corner == 2) ? 1.0 : 0.0
+    );
+    return anchored * (Vertex_Tile_Scale - 1.0) * sprite_dimensions + Vertex_Tile_Offset;
+}
+
+// Moves a vertex of a wave layer up and down over time. The phase follows the
+// world position so the shared corners of neighbouring tiles move together.
+float wave_displacement(float x) {
+    return Vertex_Tile_Wave.x * sin(6.28318530718 * (Vertex_Tile_Wave.y * Time + x * Vertex_Tile_Wave.z));
+}
+
 void main() {
     Rect sprite_rect = Textures[int(Vertex_Tile_Index)];
     vec2 sprite_dimensions = sprite_rect.end - sprite_rect.begin;
@@ -61,7 +104,13 @@ void main() {
         vec2(sprite_rect.end.x, sprite_rect.begin.y),
         sprite_rect.end
     );
-    v_Uv = floor(atlas_positions[local_index]) / AtlasSize;
+    v_Uv = floor(atlas_positions[atlas_corner(local_index)]) / AtlasSize;
     v_Color = Vertex_Tile_Color;
-    gl_Position = ViewProj * ChunkTransform * vec4(ceil(vertex_position), 1.0);
+    v_Uniform = Vertex_Layer_Uniform;
+    v_Grading = Vertex_Layer_Grading;
+    vertex_position.xy += tile_transform(local_index, sprite_dimensions);
+    vec4 world_position = ChunkTransform * vec4(ceil(vertex_position), 1.0);
+    world_position.y += wave_displacement(world_position.x);
+    world_position.z += Vertex_Tile_Depth;
+    gl_Position = ViewProj * world_position;
 }
@@ -2,18 +2,56 @@
 
 layout(location = 0) in vec2 v_Uv;
 layout(location = 1) in vec4 v_Color;
+layout(location = 3) in vec2 v_Grading;
 
 layout(location = 0) out vec4 o_Target;
 
 layout(set = 1, binding = 2) uniform texture2D TextureAtlas_texture;
 layout(set = 1, binding = 3) uniform sampler TextureAtlas_texture_sampler;
 
+layout(set = 3, binding = 2) uniform texture2D ChunkColorGrading_texture;
+layout(set = 3, binding = 3) uniform sampler ChunkColorGrading_texture_sampler;
+
+// Looks a color up in a lookup table of the color grading texture. A table of
+// N colors per channel is a row of N squares of N by N pixels, one per blue
+// level, with red to the right and green downwards. The tables are stacked
+// from the top, and an index past the last table picks the last one.
+vec3 grade(vec3 color, float lut) {
+    vec2 size = vec2(textureSize(
+        sampler2D(ChunkColorGrading_texture, ChunkColorGrading_texture_sampler),
+        0
+    ));
+    float n = floor(sqrt(size.x) + 0.5);
+    lut = min(lut, max(floor(size.y / n) - 1.0, 0.0));
+    color = clamp(color, 0.0, 1.0) * (n - 1.0);
+    float slice = floor(color.b);
+    float next = min(slice + 1.0, n - 1.0);
+    vec2 texel = color.rg + 0.5 + vec2(0.0, lut * n);
+    vec3 low = texture(
+        sampler2D(ChunkColorGrading_texture, ChunkColorGrading_texture_sampler),
+        (texel + vec2(slice * n, 0.0)) / size
+    ).rgb;
+    vec3 high = texture(
+        sampler2D(ChunkColorGrading_texture, ChunkColorGrading_texture_sampler),
+        (texel + vec2(next * n, 0.0)) / size
+    ).rgb;
+    return mix(low, high, color.b - slice);
+}
+
 void main() {
     if (v_Color.a == 0.0) {
         discard;
     }
-    o_Target = v_Color * texture(
+    vec4 color = v_Color * texture(
         sampler2D(TextureAtlas_texture, TextureAtlas_texture_sampler),
         v_Uv
     );
+    // Transparent pixels must not hide the y-sorted tiles behind them.
+    if (color.a == 0.0) {
+        discard;
+    }
+    if (v_Grading.y > 0.0) {
+        color.rgb = mix(color.rgb, grade(color.rgb, v_Grading.x), v_Grading.y);
+    }
+    o_Target = color;
 }
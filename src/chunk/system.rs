@@ -1,98 +1,322 @@
+#[cfg(feature = "failure_injection")]
+use crate::failure::InjectedFailure;
 use crate::{
-    chunk::{entity::Modified, mesh::ChunkMesh},
+    chunk::{
+        entity::{AtlasId, ChunkColorGrading, ChunkTime, DirtyTiles, Modified, SecondaryTexture},
+        lod::{average_sprite_color, bake_chunk_texture},
+        raw_tile::{set_tile_mesh_attributes, TileAttributes},
+        Chunk, MeshSettings,
+    },
+    diagnostics::TilemapFrameStats,
     lib::*,
+    system::{chunk_parent, chunk_translation},
     Tilemap,
 };
 
 /// The chunk update system that is used to set attributes of the tiles and
-/// tints if they need updating.
+/// tints if they need updating, as well as the texture atlas, secondary
+/// texture and render pipeline if they were swapped.
+///
+/// Only the attributes of the dirty tiles are updated when possible, otherwise
+/// all of them are. Those are built in a scratch buffer which is reused for
+/// every chunk and frame, and copied into the buffers the mesh already has, so
+/// updating many chunks at once does not allocate.
+///
+/// Updating the dirty tiles only saves writes on the CPU. Bevy uploads every
+/// vertex buffer of a changed mesh as a whole, so the upload is the same as
+/// for a rebuilt mesh and is counted as such in the attribute bytes of the
+/// diagnostics.
 pub(crate) fn chunk_update(
+    mut scratch: Local<TileAttributes>,
     mut meshes: ResMut<Assets<Mesh>>,
     map_query: Query<&Tilemap>,
-    mut chunk_query: Query<(&Parent, &Point2, &Handle<Mesh>), Changed<Modified>>,
+    mut chunk_query: Query<
+        (
+            &Parent,
+            &Point2,
+            &AtlasId,
+            &Handle<Mesh>,
+            &mut Handle<TextureAtlas>,
+            &mut SecondaryTexture,
+            &mut ChunkColorGrading,
+            &mut RenderPipelines,
+            &mut DirtyTiles,
+        ),
+        Changed<Modified>,
+    >,
+    frame_stats: Option<ResMut<TilemapFrameStats>>,
 ) {
-    for (parent, point, mesh_handle) in chunk_query.iter_mut() {
+    let mut stats = TilemapFrameStats::default();
+    for (
+        parent,
+        point,
+        atlas,
+        mesh_handle,
+        mut texture_atlas,
+        mut secondary_texture,
+        mut color_grading,
+        mut render_pipelines,
+        mut dirty_tiles,
+    ) in chunk_query.iter_mut()
+    {
         let tilemap = if let Ok(tilemap) = map_query.get(**parent) {
             tilemap
         } else {
             error!("`Tilemap` is missing, can not update chunk");
-            return;
+            continue;
         };
         let chunk = if let Some(chunk) = tilemap.get_chunk(point) {
             chunk
         } else {
             error!("`Chunk` is missing, can not update chunk");
-            return;
+            continue;
         };
-        let mesh = if let Some(mesh) = meshes.get_mut(mesh_handle) {
+        let mesh = meshes.get_mut(mesh_handle);
+        #[cfg(feature = "failure_injection")]
+        let mesh = mesh.filter(|_| !tilemap.take_injected_failure(InjectedFailure::MissingMesh));
+        let mesh = if let Some(mesh) = mesh {
             mesh
         } else {
             error!("`Mesh` is missing, can not update chunk");
-            return;
+            continue;
+        };
+        let chunk_dimensions = tilemap.chunk_dimensions();
+        let held_back_layers = tilemap.chunk_hidden_layers(*point);
+        let settings = tilemap
+            .mesh_settings()
+            .with_hidden_layers(held_back_layers.as_ref());
+        let MeshSettings {
+            hidden_layers,
+            waves,
+            occluding_layers,
+            sort_keys: layer_sort_keys,
+            theme,
+            ..
+        } = settings;
+        let updated = match dirty_tiles.0.replace(HashSet::default()) {
+            Some(mut tiles) => {
+                // A changed tile of an occluding layer can hide or show the
+                // tiles below it, which are updated along with it.
+                if tiles
+                    .iter()
+                    .any(|(_, sprite_order, _)| occluding_layers.contains(sprite_order))
+                {
+                    chunk.add_covered_tiles(&mut tiles, occluding_layers);
+                }
+                let occlusion = chunk.occlusion(chunk_dimensions, atlas.0, &settings);
+                tiles.iter().all(|(z_depth, sprite_order, index)| {
+                    match chunk.tile_slot(
+                        chunk_dimensions,
+                        layer_sort_keys,
+                        *z_depth,
+                        *sprite_order,
+                        *index,
+                    ) {
+                        Some(slot) => set_tile_mesh_attributes(
+                            mesh,
+                            slot,
+                            chunk
+                                .get_tile(*index, *sprite_order, *z_depth)
+                                .filter(|_| !hidden_layers.contains(sprite_order))
+                                .filter(|tile| {
+                                    !occlusion.as_ref().is_some_and(|occlusion| {
+                                        Chunk::is_occluded(
+                                            occlusion,
+                                            chunk_dimensions,
+                                            slot,
+                                            tile,
+                                            waves.contains_key(sprite_order),
+                                        )
+                                    })
+                                }),
+                            chunk.light(*index),
+                            chunk.shade(*index),
+                            atlas.0,
+                            theme,
+                        ),
+                        None => false,
+                    }
+                })
+            }
+            _ => false,
         };
-        let (indexes, colors) = chunk.tiles_to_renderer_parts(tilemap.chunk_dimensions());
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes);
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors);
+        if !updated {
+            chunk.write_renderer_parts(chunk_dimensions, atlas.0, &settings, &mut scratch);
+            scratch.copy_to_mesh(mesh);
+            stats.add_rebuild(mesh);
+        } else {
+            stats.add_upload(mesh);
+        }
+        if let Some(handle) = tilemap.get_texture_atlas(atlas.0) {
+            if *texture_atlas != *handle {
+                *texture_atlas = handle.clone_weak();
+            }
+        }
+        let secondary_handle = tilemap.chunk_secondary_texture(atlas.0);
+        if secondary_texture.0 != secondary_handle {
+            secondary_texture.0 = secondary_handle;
+        }
+        let color_grading_handle = tilemap.chunk_color_grading();
+        if color_grading.0 != color_grading_handle {
+            color_grading.0 = color_grading_handle;
+        }
+        let pipeline_handle = tilemap.chunk_pipeline();
+        if render_pipelines
+            .pipelines
+            .iter()
+            .any(|pipeline| pipeline.pipeline != pipeline_handle)
+        {
+            for pipeline in render_pipelines.pipelines.iter_mut() {
+                pipeline.pipeline = pipeline_handle.clone();
+            }
+        }
+    }
+    if let Some(mut frame_stats) = frame_stats {
+        frame_stats.merge(stats);
     }
 }
 
+/// The chunk time system that advances the time of the chunk shaders of the
+/// tilemaps with wave layers.
+pub(crate) fn chunk_time(
+    time: Res<Time>,
+    map_query: Query<&Tilemap>,
+    mut chunk_query: Query<(&Parent, &mut ChunkTime)>,
+) {
+    let seconds = time.seconds_since_startup() as f32;
+    for (parent, mut chunk_time) in chunk_query.iter_mut() {
+        let has_waves = map_query
+            .get(**parent)
+            .is_ok_and(|tilemap| !tilemap.layer_waves().is_empty());
+        if has_waves {
+            chunk_time.0 = seconds;
+        }
+    }
+}
+
+/// Returns the spawn dimensions in chunks of the tilemap which cover the
+/// spawn dimensions around a camera, for a rotated or scaled tilemap.
+fn local_spawn_dimensions(
+    tilemap: &Tilemap,
+    tilemap_transform: &GlobalTransform,
+    spawn_dimensions: Dimension2,
+) -> Dimension2 {
+    let chunk_width = (tilemap.chunk_width() * tilemap.tile_width()) as f32;
+    let chunk_height = (tilemap.chunk_height() * tilemap.tile_height()) as f32;
+    let width = spawn_dimensions.width as f32 * chunk_width;
+    let height = spawn_dimensions.height as f32 * chunk_height;
+    // The bounds of the turned rectangle around the camera, in the tilemap.
+    let axis = tilemap_transform.rotation * Vec3::X;
+    let (cos, sin) = (axis.x.abs(), axis.y.abs());
+    let scale_x = tilemap_transform.scale.x.abs().max(f32::EPSILON);
+    let scale_y = tilemap_transform.scale.y.abs().max(f32::EPSILON);
+    let local_width = (width * cos + height * sin) / scale_x;
+    let local_height = (width * sin + height * cos) / scale_y;
+    // Rounding errors of the rotation should not reach out another chunk.
+    let chunks = |length: f32| (length - 0.001).ceil().max(0.0) as u32;
+    Dimension2::new(
+        chunks(local_width / chunk_width),
+        chunks(local_height / chunk_height),
+    )
+}
+
 /// Actual method used to spawn chunks.
+///
+/// Queues the chunks within the spawn dimensions which are not spawned yet and
+/// the spawned chunks past the spawn dimensions and margin, then processes the
+/// queues. The camera and spawn dimensions are taken into the space of the
+/// tilemap, which may be translated, rotated and scaled.
 fn auto_spawn(
     camera_transform: &Transform,
-    tilemap_transform: &Transform,
+    tilemap_transform: &GlobalTransform,
     tilemap: &mut Tilemap,
     spawn_dimensions: Dimension2,
 ) {
-    let translation = camera_transform.translation - tilemap_transform.translation;
-    let point_x = translation.x / tilemap.tile_width() as f32;
-    let point_y = translation.y / tilemap.tile_height() as f32;
-    let (chunk_x, chunk_y) = tilemap.point_to_chunk_point((point_x as i32, point_y as i32));
-    let mut new_spawned: Vec<Point2> = Vec::new();
-    let spawn_width = spawn_dimensions.width as i32;
-    let spawn_height = spawn_dimensions.height as i32;
-    for y in -spawn_width as i32..spawn_width + 1 {
-        for x in -spawn_height..spawn_height + 1 {
+    let camera_point =
+        tilemap.world_to_point(tilemap_transform, camera_transform.translation.truncate());
+    let (chunk_x, chunk_y) = tilemap.point_to_chunk_point(camera_point);
+    let spawn_dimensions = local_spawn_dimensions(tilemap, tilemap_transform, spawn_dimensions);
+    let spawn_width = spawn_dimensions.width as Coord;
+    let spawn_height = spawn_dimensions.height as Coord;
+    let margin = tilemap.auto_spawn_margin();
+    let keep_width = spawn_width + margin.width as Coord;
+    let keep_height = spawn_height + margin.height as Coord;
+
+    let mut spawn_queue: Vec<Point2> = Vec::new();
+    for y in -spawn_height..spawn_height + 1 {
+        for x in -spawn_width..spawn_width + 1 {
             let chunk_x = x + chunk_x;
             let chunk_y = y + chunk_y;
             if let Some(width) = tilemap.width() {
-                let width = (width / tilemap.chunk_width()) as i32 / 2;
+                let width = (width / tilemap.chunk_width()) as Coord / 2;
                 if chunk_x < -width || chunk_x > width {
                     continue;
                 }
             }
             if let Some(height) = tilemap.height() {
-                let height = (height / tilemap.chunk_height()) as i32 / 2;
+                let height = (height / tilemap.chunk_height()) as Coord / 2;
                 if chunk_y < -height || chunk_y > height {
                     continue;
                 }
             }
-
-            if let Err(e) = tilemap.spawn_chunk(Point2::new(chunk_x, chunk_y)) {
-                warn!("{}", e);
+            let point = Point2::new(chunk_x, chunk_y);
+            if !tilemap.spawned_chunks().contains(&(chunk_x, chunk_y))
+                && tilemap.can_auto_spawn(point)
+            {
+                spawn_queue.push(point);
             }
-            new_spawned.push(Point2::new(chunk_x, chunk_y));
         }
     }
+    // The queue is popped from the back, so the nearest chunks go last.
+    spawn_queue.sort_by_key(|point| {
+        let distance = (point.x - chunk_x).abs().max((point.y - chunk_y).abs());
+        Reverse(distance)
+    });
 
-    let spawned_list = tilemap.spawned_chunks_mut().clone();
-    for point in spawned_list.iter() {
-        if !new_spawned.contains(&point.into()) {
-            if let Err(e) = tilemap.despawn_chunk(point) {
-                warn!("{}", e);
-            }
+    let despawn_queue = tilemap
+        .spawned_chunks()
+        .iter()
+        .filter(|(x, y)| (x - chunk_x).abs() > keep_width || (y - chunk_y).abs() > keep_height)
+        .map(Point2::from)
+        .filter(|point| tilemap.can_auto_despawn(*point))
+        .collect();
+
+    tilemap.set_auto_spawn_queues(spawn_queue, despawn_queue);
+    auto_spawn_queued(tilemap);
+}
+
+/// Spawns and despawns the queued chunks, up to the budget if there is one.
+///
+/// The chunks spawned in earlier frames reveal their next held back layers
+/// first.
+fn auto_spawn_queued(tilemap: &mut Tilemap) {
+    tilemap.reveal_held_back_layers();
+    let mut budget = tilemap.auto_spawn_budget().unwrap_or(u32::MAX);
+    while budget > 0 {
+        let result = match tilemap.pop_auto_spawn_queue() {
+            Some((point, true)) => tilemap.auto_spawn_chunk(point),
+            Some((point, false)) => tilemap.unload_chunk(point),
+            None => break,
+        };
+        if let Err(e) = result {
+            warn!("{}", e);
         }
+        budget -= 1;
     }
 }
 
 /// On window size change, the radius of chunks changes if needed.
 pub(crate) fn chunk_auto_radius(
     window_resized_events: Res<Events<WindowResized>>,
-    mut tilemap_query: Query<(&mut Tilemap, &Transform)>,
+    mut tilemap_query: Query<(&mut Tilemap, &GlobalTransform)>,
     camera_query: Query<(&Camera, &Transform)>,
 ) {
     let mut window_reader = window_resized_events.get_reader();
     for event in window_reader.iter(&window_resized_events) {
         for (mut tilemap, tilemap_transform) in tilemap_query.iter_mut() {
+            if tilemap.window().is_some_and(|window| window != event.id) {
+                continue;
+            }
             let window_width = event.width as u32;
             let window_height = event.height as u32;
             let chunk_px_width = tilemap.chunk_width() * tilemap.tile_width();
@@ -101,10 +325,16 @@ pub(crate) fn chunk_auto_radius(
             let chunks_high = (window_height as f32 / chunk_px_height as f32).ceil() as u32 + 1;
             let spawn_dimensions = Dimension2::new(chunks_wide, chunks_high);
             tilemap.set_auto_spawn(spawn_dimensions);
-            for (_camera, camera_transform) in camera_query.iter() {
+            if tilemap.is_paused() {
+                continue;
+            }
+            for (camera, camera_transform) in camera_query.iter() {
+                if !tilemap.views_camera(camera) {
+                    continue;
+                }
                 auto_spawn(
                     camera_transform,
-                    &tilemap_transform,
+                    tilemap_transform,
                     &mut tilemap,
                     spawn_dimensions,
                 );
@@ -115,12 +345,27 @@ pub(crate) fn chunk_auto_radius(
 
 /// Spawns and despawns chunks automatically based on a camera's position.
 pub(crate) fn chunk_auto_spawn(
-    mut tilemap_query: Query<(&mut Tilemap, &Transform)>,
-    camera_query: Query<(&Camera, &Transform), Changed<Transform>>,
+    mut tilemap_query: Query<(
+        &mut Tilemap,
+        &GlobalTransform,
+        ChangeTrackers<GlobalTransform>,
+    )>,
+    camera_query: Query<(&Camera, &Transform, ChangeTrackers<Transform>)>,
 ) {
     // For the transform, get chunk coord.
-    for (mut tilemap, tilemap_transform) in tilemap_query.iter_mut() {
-        for (_camera, camera_transform) in camera_query.iter() {
+    for (mut tilemap, tilemap_transform, tilemap_tracker) in tilemap_query.iter_mut() {
+        if tilemap.is_paused() {
+            continue;
+        }
+        // Cameras may have moved while the tilemap was paused, and a moved,
+        // turned or scaled tilemap is under the cameras somewhere else.
+        let moved = tilemap.take_resumed() || tilemap_tracker.is_changed();
+        let mut camera_changed = false;
+        for (camera, camera_transform, camera_tracker) in camera_query.iter() {
+            if !tilemap.views_camera(camera) || !(moved || camera_tracker.is_changed()) {
+                continue;
+            }
+            camera_changed = true;
             let spawn_dimensions = if let Some(dimensions) = tilemap.auto_spawn() {
                 dimensions
             } else {
@@ -128,18 +373,498 @@ pub(crate) fn chunk_auto_spawn(
             };
             auto_spawn(
                 camera_transform,
-                &tilemap_transform,
+                tilemap_transform,
                 &mut tilemap,
                 spawn_dimensions,
             );
         }
+        // Chunks past the budget of earlier frames are still queued.
+        if !camera_changed {
+            auto_spawn_queued(&mut tilemap);
+        }
+    }
+}
+
+/// Generates the chunks auto spawn queued in parallel on the compute task
+/// pool, then inserts and spawns them.
+pub(crate) fn chunk_generate(
+    task_pool: Res<ComputeTaskPool>,
+    mut tilemap_query: Query<&mut Tilemap>,
+) {
+    for mut tilemap in tilemap_query.iter_mut() {
+        if tilemap.is_paused() {
+            continue;
+        }
+        let (generator, generate_queue) = match tilemap.take_generate_queue() {
+            Some(queued) => queued,
+            None => continue,
+        };
+        let chunk_dimensions = tilemap.chunk_dimensions();
+        let generated = task_pool.scope(|scope| {
+            for point in generate_queue.iter() {
+                let generator = &generator;
+                scope.spawn(async move { (*point, generator.generate(*point, chunk_dimensions)) });
+            }
+        });
+        for (point, tiles) in generated {
+            if let Err(e) = tilemap.insert_generated_chunk(point, tiles) {
+                warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// Spawns the chunks along the preload paths of the tilemaps ahead of their
+/// cameras.
+pub(crate) fn chunk_preload(
+    time: Res<Time>,
+    mut tilemap_query: Query<(&mut Tilemap, &GlobalTransform)>,
+    camera_query: Query<(&Camera, &Transform)>,
+) {
+    for (mut tilemap, tilemap_transform) in tilemap_query.iter_mut() {
+        if tilemap.is_paused() || !tilemap.is_preloading() {
+            continue;
+        }
+        let camera_transform = camera_query
+            .iter()
+            .find(|(camera, _camera_transform)| tilemap.views_camera(camera))
+            .map(|(_camera, camera_transform)| camera_transform);
+        if let Some(camera_transform) = camera_transform {
+            let translation =
+                Tilemap::world_to_local(tilemap_transform, camera_transform.translation.truncate());
+            let position = Vec2::new(
+                translation.x / tilemap.tile_width() as f32,
+                translation.y / tilemap.tile_height() as f32,
+            );
+            tilemap.advance_preload_path(position, time.delta());
+        }
+    }
+}
+
+/// Spawns the low resolution stand-in of a chunk.
+fn spawn_chunk_lod(
+    commands: &mut Commands,
+    textures: &mut Assets<Texture>,
+    materials: &mut Assets<ColorMaterial>,
+    texture_atlases: &Assets<TextureAtlas>,
+    tilemap_entity: Entity,
+    tilemap_visible: &Visible,
+    tilemap: &mut Tilemap,
+    point: Point2,
+) {
+    let chunk_dimensions = tilemap.chunk_dimensions();
+    let texture_dimensions = tilemap.texture_dimensions();
+    let mut lod_colors = mem::take(tilemap.lod_colors_mut());
+    let texture = {
+        let chunk = if let Some(chunk) = tilemap.get_chunk(&point) {
+            chunk
+        } else {
+            warn!("Can not get chunk at {}, skipping", &point);
+            return;
+        };
+        bake_chunk_texture(chunk, chunk_dimensions, |tile| {
+            let sprite_index = tilemap.themed_sprite_index(tile.index);
+            let key = (tile.atlas, sprite_index);
+            if let Some(color) = lod_colors.get(&key) {
+                return *color;
+            }
+            let texture_atlas = tilemap
+                .get_texture_atlas(tile.atlas)
+                .and_then(|handle| texture_atlases.get(handle));
+            #[cfg(feature = "failure_injection")]
+            let texture_atlas = texture_atlas
+                .filter(|_| !tilemap.take_injected_failure(InjectedFailure::UnloadedTextureAtlas));
+            // Sprites are only cached once their texture is loaded.
+            match texture_atlas.and_then(|texture_atlas| {
+                average_sprite_color(texture_atlas, textures, sprite_index)
+            }) {
+                Some(color) => {
+                    lod_colors.insert(key, color);
+                    color
+                }
+                None => Color::WHITE,
+            }
+        })
+    };
+    *tilemap.lod_colors_mut() = lod_colors;
+
+    let (translation_x, translation_y) = chunk_translation(tilemap, point);
+    let size = Vec2::new(
+        (chunk_dimensions.width * texture_dimensions.width) as f32,
+        (chunk_dimensions.height * texture_dimensions.height) as f32,
+    );
+    let material = materials.add(ColorMaterial::texture(textures.add(texture)));
+    let entity = commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(size),
+            material,
+            visible: tilemap_visible.clone(),
+            transform: Transform::from_translation(Vec3::new(
+                translation_x,
+                translation_y,
+                tilemap.depth_z(0.0),
+            )),
+            ..Default::default()
+        })
+        .id();
+    let parent = chunk_parent(commands, tilemap_entity, tilemap, point);
+    commands.entity(parent).push_children(&[entity]);
+
+    if let Some(chunk) = tilemap.chunks_mut().get_mut(&point) {
+        chunk.set_lod_entity(entity);
+    }
+}
+
+/// Swaps spawned chunks which are further than the level of detail distance
+/// from every camera to their low resolution stand-ins, and back again once a
+/// camera is near.
+pub(crate) fn chunk_lod(
+    mut commands: Commands,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    camera_query: Query<(&Camera, &Transform)>,
+    mut tilemap_query: Query<(Entity, &mut Tilemap, &GlobalTransform, &Visible)>,
+    mut visibles: Query<&mut Visible, Without<Tilemap>>,
+) {
+    for (tilemap_entity, mut tilemap, tilemap_transform, tilemap_visible) in
+        tilemap_query.iter_mut()
+    {
+        if tilemap.is_paused() {
+            continue;
+        }
+        let camera_chunks: Vec<Point2> = camera_query
+            .iter()
+            .filter(|(camera, _camera_transform)| tilemap.views_camera(camera))
+            .map(|(_camera, camera_transform)| {
+                let point = tilemap
+                    .world_to_point(tilemap_transform, camera_transform.translation.truncate());
+                tilemap.point_to_chunk_point(point).into()
+            })
+            .collect();
+        let lod_distance = tilemap.lod_distance();
+        let spawned: Vec<Point2> = tilemap.spawned_chunks().iter().map(Point2::from).collect();
+        for point in spawned {
+            let far = match lod_distance {
+                Some(distance) => {
+                    !camera_chunks.is_empty()
+                        && camera_chunks.iter().all(|camera| {
+                            let distance_x = (point.x - camera.x).unsigned_abs();
+                            let distance_y = (point.y - camera.y).unsigned_abs();
+                            // The distances are `u64`s with `i64_coordinates`.
+                            #[allow(clippy::useless_conversion)]
+                            let far_distance = u64::from(distance_x.max(distance_y));
+                            far_distance > u64::from(distance)
+                        })
+                }
+                None => false,
+            };
+            let has_lod = tilemap
+                .get_chunk(&point)
+                .and_then(|chunk| chunk.lod_entity())
+                .is_some();
+            match (far, has_lod) {
+                (true, false) => spawn_chunk_lod(
+                    &mut commands,
+                    &mut textures,
+                    &mut materials,
+                    &texture_atlases,
+                    tilemap_entity,
+                    tilemap_visible,
+                    &mut tilemap,
+                    point,
+                ),
+                (false, true) => {
+                    if let Some(chunk) = tilemap.chunks_mut().get_mut(&point) {
+                        if let Some(entity) = chunk.take_lod_entity() {
+                            commands.entity(entity).despawn_recursive();
+                        }
+                        for entity in chunk.entities() {
+                            if let Ok(mut visible) = visibles.get_mut(entity) {
+                                visible.is_visible = tilemap_visible.is_visible;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            // Entities of the chunk may be spawned after the stand-in is.
+            if let Some(chunk) = tilemap.get_chunk(&point) {
+                if chunk.lod_entity().is_some() {
+                    for entity in chunk.entities() {
+                        if let Ok(mut visible) = visibles.get_mut(entity) {
+                            if visible.is_visible {
+                                visible.is_visible = false;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Hides the spawned chunks of culling tilemaps which are outside of the view
+/// of every camera driving them, and shows them again once they are inside.
+pub(crate) fn chunk_culling(
+    camera_query: Query<(&Camera, &GlobalTransform, Option<&OrthographicProjection>)>,
+    mut tilemap_query: Query<(&mut Tilemap, &GlobalTransform, &Visible)>,
+    mut visibles: Query<&mut Visible, Without<Tilemap>>,
+) {
+    for (mut tilemap, tilemap_transform, tilemap_visible) in tilemap_query.iter_mut() {
+        // Chunks culled before culling was disabled still need to be shown.
+        if !tilemap.culling() && tilemap.culled_chunks_mut().is_empty() {
+            continue;
+        }
+        let mut views = Vec::new();
+        let mut unbounded = !tilemap.culling();
+        for (camera, camera_transform, projection) in camera_query.iter() {
+            if !tilemap.views_camera(camera) {
+                continue;
+            }
+            let projection = if let Some(projection) = projection {
+                projection
+            } else {
+                unbounded = true;
+                break;
+            };
+            let scale = camera_transform.scale * projection.scale;
+            let min = Vec2::new(projection.left * scale.x, projection.bottom * scale.y);
+            let max = Vec2::new(projection.right * scale.x, projection.top * scale.y);
+            let translation = camera_transform.translation.truncate();
+            views.push(Rect {
+                min: translation + min,
+                max: translation + max,
+            });
+        }
+
+        let spawned: Vec<Point2> = tilemap.spawned_chunks().iter().map(Point2::from).collect();
+        for point in spawned {
+            let on_screen = unbounded || {
+                // The bounds of the chunk as the tilemap is moved, turned and
+                // scaled in the world.
+                let rect = tilemap.chunk_rect(point);
+                let mut min = Vec2::new(f32::MAX, f32::MAX);
+                let mut max = Vec2::new(f32::MIN, f32::MIN);
+                for corner in [
+                    rect.min,
+                    Vec2::new(rect.max.x, rect.min.y),
+                    rect.max,
+                    Vec2::new(rect.min.x, rect.max.y),
+                ]
+                .iter()
+                {
+                    let corner = tilemap_transform
+                        .mul_vec3(Vec3::new(corner.x, corner.y, 0.0))
+                        .truncate();
+                    min = min.min(corner);
+                    max = max.max(corner);
+                }
+                views.iter().any(|view| {
+                    min.x <= view.max.x
+                        && max.x >= view.min.x
+                        && min.y <= view.max.y
+                        && max.y >= view.min.y
+                })
+            };
+            // Culled chunks are hidden every frame, as their entities may be
+            // spawned or shown by other systems after they were culled.
+            if on_screen {
+                if !tilemap.culled_chunks_mut().remove(&point) {
+                    continue;
+                }
+            } else {
+                tilemap.culled_chunks_mut().insert(point);
+            }
+            let chunk = if let Some(chunk) = tilemap.get_chunk(&point) {
+                chunk
+            } else {
+                continue;
+            };
+            let is_visible = on_screen && tilemap_visible.is_visible;
+            // Chunks with a low resolution stand-in keep their meshes hidden.
+            let entities = match chunk.lod_entity() {
+                Some(lod_entity) => vec![lod_entity],
+                None => chunk.entities(),
+            };
+            for entity in entities {
+                if let Ok(mut visible) = visibles.get_mut(entity) {
+                    if visible.is_visible != is_visible {
+                        visible.is_visible = is_visible;
+                    }
+                }
+            }
+        }
+        // Despawned chunks are no longer culled.
+        let spawned_chunks = tilemap.spawned_chunks().clone();
+        tilemap
+            .culled_chunks_mut()
+            .retain(|point| spawned_chunks.contains(&(point.x, point.y)));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{entity::TilemapBundle, system::tilemap_events, tilemap::TilemapBuilder, Tile};
+    use crate::{
+        chunk::render, entity::TilemapBundle, event::TilemapChunkEvent,
+        spawn_policy::ChunkSpawnPolicy, system::tilemap_events, tilemap::TilemapBuilder, Tile,
+    };
+
+    /// Takes the spawned and despawned chunks of a tilemap, marking the
+    /// spawned chunks as spawned like the tilemap events system does.
+    fn take_spawned(tilemap: &mut Tilemap) -> (Vec<Point2>, Vec<Point2>) {
+        let mut spawned = Vec::new();
+        let mut despawned = Vec::new();
+        for event in tilemap.take_chunk_events() {
+            match event {
+                TilemapChunkEvent::Spawned { point } => {
+                    tilemap.spawned_chunks_mut().insert((point.x, point.y));
+                    spawned.push(point);
+                }
+                TilemapChunkEvent::Despawned { point } => despawned.push(point),
+                _ => {}
+            }
+        }
+        (spawned, despawned)
+    }
+
+    /// Returns a camera transform over the center of a chunk of 32 by 32
+    /// pixels.
+    fn camera_over(chunk_x: Coord) -> Transform {
+        Transform::from_translation(Vec3::new(chunk_x as f32 * 32.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn test_auto_spawn_margin_and_budget() {
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(1, 1, 1)
+            .auto_chunk()
+            .auto_spawn(1, 1)
+            .auto_spawn_margin(1, 0)
+            .auto_spawn_budget(4)
+            .finish()
+            .unwrap();
+        let transform = GlobalTransform::identity();
+        let spawn_dimensions = Dimension2::new(1, 1);
+
+        // The nine chunks around the camera spawn four per frame, nearest
+        // first.
+        auto_spawn(&camera_over(0), &transform, &mut tilemap, spawn_dimensions);
+        let (spawned, _) = take_spawned(&mut tilemap);
+        assert_eq!(spawned.len(), 4);
+        assert_eq!(spawned[0], Point2::new(0, 0));
+        auto_spawn_queued(&mut tilemap);
+        assert_eq!(take_spawned(&mut tilemap).0.len(), 4);
+        auto_spawn_queued(&mut tilemap);
+        assert_eq!(take_spawned(&mut tilemap).0.len(), 1);
+        auto_spawn_queued(&mut tilemap);
+        assert_eq!(take_spawned(&mut tilemap), (Vec::new(), Vec::new()));
+        assert_eq!(tilemap.spawned_chunks().len(), 9);
+
+        // Moving a chunk over keeps the chunks left behind within the margin.
+        auto_spawn(&camera_over(1), &transform, &mut tilemap, spawn_dimensions);
+        let (spawned, despawned) = take_spawned(&mut tilemap);
+        assert_eq!(spawned.len(), 3);
+        assert!(spawned.iter().all(|point| point.x == 2));
+        assert!(despawned.is_empty());
+        assert!(tilemap.spawned_chunks().contains(&(-1, 0)));
+
+        // Moving back and forth over the edge no longer spawns anything.
+        auto_spawn(&camera_over(0), &transform, &mut tilemap, spawn_dimensions);
+        assert_eq!(take_spawned(&mut tilemap), (Vec::new(), Vec::new()));
+
+        // Only past the margin are chunks despawned, within the budget too.
+        auto_spawn(&camera_over(2), &transform, &mut tilemap, spawn_dimensions);
+        let (spawned, despawned) = take_spawned(&mut tilemap);
+        assert_eq!(spawned.len() + despawned.len(), 4);
+        auto_spawn_queued(&mut tilemap);
+        let (more_spawned, more_despawned) = take_spawned(&mut tilemap);
+        assert_eq!(spawned.len() + more_spawned.len(), 3);
+        let mut despawned = [despawned, more_despawned].concat();
+        despawned.sort_by_key(|point| point.y);
+        assert_eq!(
+            despawned,
+            vec![Point2::new(-1, -1), Point2::new(-1, 0), Point2::new(-1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_local_spawn_dimensions() {
+        let tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 2, 1)
+            .finish()
+            .unwrap();
+        let spawn_dimensions = Dimension2::new(3, 2);
+
+        let transform = GlobalTransform::identity();
+        assert_eq!(
+            local_spawn_dimensions(&tilemap, &transform, spawn_dimensions),
+            spawn_dimensions
+        );
+        // Zoomed in, fewer chunks fill the view.
+        let transform = GlobalTransform::from_scale(Vec3::new(2.0, 2.0, 1.0));
+        assert_eq!(
+            local_spawn_dimensions(&tilemap, &transform, spawn_dimensions),
+            Dimension2::new(2, 1)
+        );
+        // Turned a quarter, the width of the view runs along the columns.
+        let transform =
+            GlobalTransform::from_rotation(Quat::from_rotation_z(::std::f32::consts::FRAC_PI_2));
+        assert_eq!(
+            local_spawn_dimensions(&tilemap, &transform, spawn_dimensions),
+            Dimension2::new(1, 6)
+        );
+    }
+
+    /// Never spawns chunks left of the center and keeps the center chunk.
+    struct EastOnly;
+
+    impl ChunkSpawnPolicy for EastOnly {
+        fn can_spawn(&self, point: Point2) -> bool {
+            point.x >= 0
+        }
+
+        fn can_despawn(&self, point: Point2) -> bool {
+            point != Point2::new(0, 0)
+        }
+    }
+
+    #[test]
+    fn test_auto_spawn_policy() {
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(1, 1, 1)
+            .auto_chunk()
+            .auto_spawn(1, 1)
+            .finish()
+            .unwrap();
+        tilemap.set_spawn_policy(EastOnly);
+        let transform = GlobalTransform::identity();
+        let spawn_dimensions = Dimension2::new(1, 1);
+
+        auto_spawn(&camera_over(0), &transform, &mut tilemap, spawn_dimensions);
+        let (spawned, _) = take_spawned(&mut tilemap);
+        assert_eq!(spawned.len(), 6);
+        assert!(spawned.iter().all(|point| point.x >= 0));
+
+        // The center chunk stays spawned after the camera moved away.
+        auto_spawn(&camera_over(3), &transform, &mut tilemap, spawn_dimensions);
+        let (_, despawned) = take_spawned(&mut tilemap);
+        assert_eq!(despawned.len(), 5);
+        assert!(!despawned.contains(&Point2::new(0, 0)));
+        assert!(tilemap.spawned_chunks().contains(&(0, 0)));
+
+        // Spawning by hand is not affected.
+        tilemap.spawn_chunk((-1, 0)).unwrap();
+        assert_eq!(take_spawned(&mut tilemap).0, vec![Point2::new(-1, 0)]);
+    }
 
     #[test]
     fn test_chunk_update() {
@@ -153,6 +878,9 @@ mod tests {
             .add_system_to_stage("update", tilemap_events.system())
             .add_system_to_stage("update", chunk_update.system())
             .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
             .app;
         let mut command_queue = CommandQueue::default();
         let mut commands = Commands::new(&mut command_queue, &app.world);
@@ -201,6 +929,7 @@ mod tests {
                         sprite_order: 0,
                         sprite_index: 1,
                         tint: Color::BLUE,
+                        ..Default::default()
                     })
                     .unwrap();
                 tilemap.spawn_chunk(Point2::new(0, 0)).unwrap();
@@ -220,7 +949,7 @@ mod tests {
             assert_eq!(meshes.len(), 1);
             let (_, mesh) = meshes.iter().next().unwrap();
             let tile_index = mesh
-                .attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX)
+                .attribute(render::ATTRIBUTE_TILE_INDEX.name)
                 .unwrap()
                 .get_bytes();
             assert_eq!(tile_index.len(), 5 * 5 * 4 * 4); // chunk * width * f32 size * byte len
@@ -242,7 +971,7 @@ mod tests {
             }
 
             let tile_colors = mesh
-                .attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR)
+                .attribute(render::ATTRIBUTE_TILE_COLOR.name)
                 .unwrap()
                 .get_bytes();
             assert_eq!(tile_colors.len(), 5 * 5 * 4 * 4 * 4); // chunk * width * f32 size * byte len * 4 bytes in a color
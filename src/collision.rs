@@ -0,0 +1,809 @@
+//! A lightweight collision map over the solid sprite layers of a tilemap.
+//!
+//! Sprite layers marked solid with [`TilemapBuilder::solid_layer`] or
+//! [`Tilemap::set_layer_solid`] block movement wherever they have a tile.
+//! [`Tilemap::is_solid`] tests a single point, [`Tilemap::raycast`] finds the
+//! first solid tile along a ray and [`Tilemap::move_and_collide`] moves a box
+//! as far as it can go without entering a solid tile.
+//! [`Tilemap::raycast_layer`] casts a ray against the tiles of any sprite
+//! layer instead. Both rays follow the hexagons of hexagonal tilemaps.
+//! [`Tilemap::overlaps_solid`] tests if a [`Circle`] overlaps a solid tile
+//! and [`Tilemap::tiles_in_polygon`] finds the tiles inside an area, such as
+//! the site of a building or the blast of an explosion, following the
+//! hexagons as well.
+//!
+//! Collision works in tile units on the square grid of points, where the tile
+//! at `(x, y)` covers `x..x + 1` and `y..y + 1`, on z depth 0. Turning world
+//! positions into tile units is left to the user, which is a division by the
+//! tile dimensions relative to the tilemap.
+//!
+//! For a physics engine such as `bevy_rapier2d`, [`Tilemap::solid_colliders`]
+//! turns the solid tiles into colliders, merging neighbouring square tiles
+//! into large cuboids so a big map needs only a handful of them. The shape of
+//! the collider of a sprite, such as a half tile or a slope, and whether it is
+//! a sensor is set with [`Tilemap::set_tile_collider`]. The queries above only
+//! tell solid cells apart, so any tile which is not a sensor blocks its whole
+//! cell there.
+//!
+//! Spawning hundreds of colliders in one frame makes physics engines hitch.
+//! With [`TilemapBuilder::collider_budget`] the colliders of spawned chunks
+//! are instead built a few at a time, nearest to the camera first, and sent
+//! with a [`TilemapCollidersEvent`] which tells when a chunk is complete.
+//!
+//! [`TilemapBuilder::solid_layer`]: crate::tilemap::TilemapBuilder::solid_layer
+//! [`Tilemap::set_layer_solid`]: crate::tilemap::Tilemap::set_layer_solid
+//! [`Tilemap::is_solid`]: crate::tilemap::Tilemap::is_solid
+//! [`Tilemap::raycast`]: crate::tilemap::Tilemap::raycast
+//! [`Tilemap::raycast_layer`]: crate::tilemap::Tilemap::raycast_layer
+//! [`Tilemap::move_and_collide`]: crate::tilemap::Tilemap::move_and_collide
+//! [`Tilemap::overlaps_solid`]: crate::tilemap::Tilemap::overlaps_solid
+//! [`Tilemap::tiles_in_polygon`]: crate::tilemap::Tilemap::tiles_in_polygon
+//! [`Tilemap::solid_colliders`]: crate::tilemap::Tilemap::solid_colliders
+//! [`Tilemap::set_tile_collider`]: crate::tilemap::Tilemap::set_tile_collider
+//! [`TilemapBuilder::collider_budget`]: crate::tilemap::TilemapBuilder::collider_budget
+//! [`TilemapCollidersEvent`]: crate::event::TilemapCollidersEvent
+//!
+//! # Walking into a wall
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_math::Vec2;
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{collision::Aabb, point::Point2, prelude::*};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+//!     .auto_chunk()
+//!     .solid_layer(1)
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.insert_tile(Tile { point: (3, 0), sprite_order: 1, ..Default::default() }).unwrap();
+//! assert!(tilemap.is_solid((3, 0)));
+//!
+//! let player = Aabb::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+//! let moved = tilemap.move_and_collide(player, Vec2::new(5.0, 0.0));
+//! assert!(moved.blocked_x);
+//! assert_eq!(moved.aabb.max.x, 3.0);
+//!
+//! let hit = tilemap.raycast(Vec2::new(0.5, 0.5), Vec2::new(10.0, 0.0)).unwrap();
+//! assert_eq!(hit.point, Point2::new(3, 0));
+//! assert_eq!(hit.distance, 2.5);
+//! ```
+
+use crate::lib::*;
+
+/// The tolerance for a box touching the edge of a tile without entering it.
+const EPSILON: f32 = 1e-4;
+
+/// How far past the edge a ray leaves a tile through it is followed to find
+/// the tile it enters.
+const PROBE: f32 = 1e-3;
+
+/// An axis aligned bounding box in tile units.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Aabb {
+    /// The lower left corner.
+    pub min: Vec2,
+    /// The upper right corner.
+    pub max: Vec2,
+}
+
+impl Aabb {
+    /// Constructs a box from its lower left and upper right corners.
+    pub fn new(min: Vec2, max: Vec2) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// Returns the box moved by an offset.
+    pub fn translated(self, offset: Vec2) -> Aabb {
+        Aabb {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+/// A circle in tile units.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Circle {
+    /// The center.
+    pub center: Vec2,
+    /// The radius.
+    pub radius: f32,
+}
+
+impl Circle {
+    /// Constructs a circle from its center and radius.
+    pub fn new(center: Vec2, radius: f32) -> Circle {
+        Circle { center, radius }
+    }
+}
+
+/// The first solid tile hit by a ray.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RaycastHit {
+    /// The point of the solid tile.
+    pub point: Point2,
+    /// The position where the ray enters the tile, in tile units.
+    pub position: Vec2,
+    /// The distance from the start of the ray to the hit, in tiles.
+    pub distance: f32,
+    /// The normal of the edge of the tile the ray entered through, or zero if
+    /// the ray starts inside the tile.
+    pub normal: Vec2,
+}
+
+/// The outcome of moving a box with [`Tilemap::move_and_collide`].
+///
+/// [`Tilemap::move_and_collide`]: crate::tilemap::Tilemap::move_and_collide
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CollisionMove {
+    /// The box where it stopped.
+    pub aabb: Aabb,
+    /// The motion that was made, which is shorter than the wanted motion
+    /// along the axes that were blocked.
+    pub motion: Vec2,
+    /// If a solid tile stopped the motion along the x axis.
+    pub blocked_x: bool,
+    /// If a solid tile stopped the motion along the y axis.
+    pub blocked_y: bool,
+}
+
+/// A half of a tile.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum HalfTile {
+    /// The lower half.
+    Bottom,
+    /// The upper half.
+    Top,
+    /// The left half.
+    Left,
+    /// The right half.
+    Right,
+}
+
+/// A slope across a tile, named after the corner of its right angle.
+///
+/// A `BottomLeft` slope rises from the right to the left, filling the
+/// triangle of the bottom left, bottom right and top left corners.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Slope {
+    /// The right angle is at the bottom left corner.
+    BottomLeft,
+    /// The right angle is at the bottom right corner.
+    BottomRight,
+    /// The right angle is at the top left corner.
+    TopLeft,
+    /// The right angle is at the top right corner.
+    TopRight,
+}
+
+/// The shape of the collider of a tile.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum TileShape {
+    /// The whole tile.
+    #[default]
+    Full,
+    /// A half of the tile.
+    HalfTile(HalfTile),
+    /// A triangle across the tile.
+    Slope(Slope),
+    /// A convex polygon with its vertices in tile units relative to the lower
+    /// left corner of the tile, in counter clockwise order.
+    ConvexPolygon(Vec<Vec2>),
+}
+
+impl TileShape {
+    /// Returns the lower left and upper right corners of the shape relative
+    /// to the tile if it is a rectangle.
+    fn rect(&self) -> Option<(Vec2, Vec2)> {
+        let (min, max) = match self {
+            TileShape::Full => ((0.0, 0.0), (1.0, 1.0)),
+            TileShape::HalfTile(HalfTile::Bottom) => ((0.0, 0.0), (1.0, 0.5)),
+            TileShape::HalfTile(HalfTile::Top) => ((0.0, 0.5), (1.0, 1.0)),
+            TileShape::HalfTile(HalfTile::Left) => ((0.0, 0.0), (0.5, 1.0)),
+            TileShape::HalfTile(HalfTile::Right) => ((0.5, 0.0), (1.0, 1.0)),
+            _ => return None,
+        };
+        Some((Vec2::new(min.0, min.1), Vec2::new(max.0, max.1)))
+    }
+
+    /// Returns the vertices of the shape relative to the tile in counter
+    /// clockwise order.
+    fn polygon(&self) -> Vec<Vec2> {
+        if let Some((min, max)) = self.rect() {
+            return vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        }
+        let corners = match self {
+            TileShape::Slope(Slope::BottomLeft) => [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            TileShape::Slope(Slope::BottomRight) => [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)],
+            TileShape::Slope(Slope::TopLeft) => [(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+            TileShape::Slope(Slope::TopRight) => [(1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+            TileShape::ConvexPolygon(vertices) => return vertices.clone(),
+            _ => return Vec::new(),
+        };
+        corners.iter().map(|(x, y)| Vec2::new(*x, *y)).collect()
+    }
+}
+
+/// The collider of the tiles of a sprite.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TileCollider {
+    /// The shape of the collider.
+    pub shape: TileShape,
+    /// If the collider only detects overlaps instead of blocking.
+    pub sensor: bool,
+}
+
+/// The shape of a collider made from solid tiles, in tile units.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ColliderShape {
+    /// A rectangle, which may span many tiles.
+    Cuboid(Aabb),
+    /// A convex polygon with its vertices in counter clockwise order.
+    ConvexPolygon(Vec<Vec2>),
+}
+
+/// A collider made from solid tiles by [`Tilemap::solid_colliders`].
+///
+/// [`Tilemap::solid_colliders`]: crate::tilemap::Tilemap::solid_colliders
+#[derive(Clone, PartialEq, Debug)]
+pub struct Collider {
+    /// The shape of the collider.
+    pub shape: ColliderShape,
+    /// If the collider only detects overlaps instead of blocking.
+    pub sensor: bool,
+}
+
+/// Colliders of a spawned chunk handed out by [`Tilemap::build_colliders`].
+///
+/// [`Tilemap::build_colliders`]: crate::tilemap::Tilemap::build_colliders
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChunkColliders {
+    /// The point of the chunk.
+    pub point: Point2,
+    /// The colliders of the chunk which were built this time.
+    pub colliders: Vec<Collider>,
+    /// If these are the last colliders of the chunk, so all of them are
+    /// built.
+    pub complete: bool,
+}
+
+/// Makes the colliders of solid tiles, merging the tiles with the same
+/// rectangular shape into as few cuboids as it can.
+///
+/// Runs of tiles along a row are merged first when their shape spans the
+/// width of a tile, then runs over the same columns in neighbouring rows are
+/// merged when their shape spans the height of a tile.
+pub(crate) fn merge_colliders(tiles: &[(Point2, &TileCollider)]) -> Vec<Collider> {
+    let mut colliders = Vec::new();
+    let mut rects = Vec::new();
+    for (point, collider) in tiles.iter() {
+        match collider.shape.rect() {
+            Some(rect) => rects.push((*point, rect, collider.sensor)),
+            None => colliders.push(polygon_collider(*point, collider)),
+        }
+    }
+    rects.sort_by_key(|(point, _rect, _sensor)| (point.y, point.x));
+
+    // The runs along the rows, as the row, the first and last column, the
+    // rect within the tiles and the sensor flag.
+    let mut runs: Vec<(Coord, Coord, Coord, (Vec2, Vec2), bool)> = Vec::new();
+    for (point, rect, sensor) in rects.into_iter() {
+        let spans_width = rect.0.x == 0.0 && rect.1.x == 1.0;
+        if let Some(run) = runs.last_mut() {
+            if spans_width
+                && run.0 == point.y
+                && run.2 + 1 == point.x
+                && run.3 == rect
+                && run.4 == sensor
+            {
+                run.2 = point.x;
+                continue;
+            }
+        }
+        runs.push((point.y, point.x, point.x, rect, sensor));
+    }
+
+    // The merged rectangles as the first and last row and the run, with the
+    // latest rectangle over each span of columns.
+    let mut merged: Vec<(Coord, Coord, (Coord, Coord, (Vec2, Vec2), bool))> = Vec::new();
+    let mut open: HashMap<(Coord, Coord), usize> = HashMap::default();
+    for (y, first, last, rect, sensor) in runs.into_iter() {
+        let spans_height = rect.0.y == 0.0 && rect.1.y == 1.0;
+        let run = (first, last, rect, sensor);
+        if spans_height {
+            let open_run = open
+                .get(&(first, last))
+                .and_then(|index| merged.get_mut(*index));
+            if let Some((_bottom, top, open_run)) = open_run {
+                if *top + 1 == y && *open_run == run {
+                    *top = y;
+                    continue;
+                }
+            }
+            open.insert((first, last), merged.len());
+        }
+        merged.push((y, y, run));
+    }
+
+    for (bottom, top, (first, last, (min, max), sensor)) in merged.into_iter() {
+        colliders.push(Collider {
+            shape: ColliderShape::Cuboid(Aabb::new(
+                Vec2::new(first as f32 + min.x, bottom as f32 + min.y),
+                Vec2::new(last as f32 + max.x, top as f32 + max.y),
+            )),
+            sensor,
+        });
+    }
+    colliders
+}
+
+/// Makes a collider for every solid tile without merging them.
+pub(crate) fn tile_colliders(tiles: &[(Point2, &TileCollider)]) -> Vec<Collider> {
+    tiles
+        .iter()
+        .map(|(point, collider)| match collider.shape.rect() {
+            Some((min, max)) => {
+                let offset = Vec2::new(point.x as f32, point.y as f32);
+                Collider {
+                    shape: ColliderShape::Cuboid(Aabb::new(min + offset, max + offset)),
+                    sensor: collider.sensor,
+                }
+            }
+            None => polygon_collider(*point, collider),
+        })
+        .collect()
+}
+
+/// Makes the convex polygon collider of a tile.
+fn polygon_collider(point: Point2, collider: &TileCollider) -> Collider {
+    let offset = Vec2::new(point.x as f32, point.y as f32);
+    Collider {
+        shape: ColliderShape::ConvexPolygon(
+            collider
+                .shape
+                .polygon()
+                .into_iter()
+                .map(|vertex| vertex + offset)
+                .collect(),
+        ),
+        sensor: collider.sensor,
+    }
+}
+
+/// Returns the first solid point along a ray from a start to the start plus
+/// a direction, in tile units.
+pub(crate) fn raycast<F>(start: Vec2, direction: Vec2, is_solid: F) -> Option<RaycastHit>
+where
+    F: Fn(Point2) -> bool,
+{
+    let mut cell = Point2::new(start.x.floor() as Coord, start.y.floor() as Coord);
+    if is_solid(cell) {
+        return Some(RaycastHit {
+            point: cell,
+            position: start,
+            distance: 0.0,
+            normal: Vec2::ZERO,
+        });
+    }
+    let length = direction.length();
+    if length == 0.0 {
+        return None;
+    }
+    let direction = direction / length;
+    let step_x: Coord = if direction.x > 0.0 { 1 } else { -1 };
+    let step_y: Coord = if direction.y > 0.0 { 1 } else { -1 };
+    let (mut next_x, delta_x) = first_edge(start.x, direction.x);
+    let (mut next_y, delta_y) = first_edge(start.y, direction.y);
+    loop {
+        let (distance, normal) = if next_x < next_y {
+            let distance = next_x;
+            cell.x += step_x;
+            next_x += delta_x;
+            (distance, Vec2::new(-step_x as f32, 0.0))
+        } else {
+            let distance = next_y;
+            cell.y += step_y;
+            next_y += delta_y;
+            (distance, Vec2::new(0.0, -step_y as f32))
+        };
+        if distance > length {
+            return None;
+        }
+        if is_solid(cell) {
+            return Some(RaycastHit {
+                point: cell,
+                position: start + direction * distance,
+                distance,
+                normal,
+            });
+        }
+    }
+}
+
+/// Returns the first solid point along a ray through tiles of any convex
+/// shape, up to a distance in tile units.
+///
+/// The ray walks from the tile at the start into the neighbour behind the
+/// edge it leaves each tile through. The polygons are counter clockwise and
+/// need to cover the plane without gaps, with every neighbour of a tile
+/// within one point of it.
+pub(crate) fn polygon_raycast<P, F>(
+    start: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+    start_point: Point2,
+    polygon: P,
+    is_solid: F,
+) -> Option<RaycastHit>
+where
+    P: Fn(Point2) -> Vec<Vec2>,
+    F: Fn(Point2) -> bool,
+{
+    let mut cell = start_point;
+    if is_solid(cell) {
+        return Some(RaycastHit {
+            point: cell,
+            position: start,
+            distance: 0.0,
+            normal: Vec2::ZERO,
+        });
+    }
+    let length = direction.length();
+    if length == 0.0 {
+        return None;
+    }
+    let direction = direction / length;
+    // Every tile is crossed in a step, so the walk can not go on forever if
+    // a polygon is not what it is expected to be.
+    let max_steps = (max_distance.max(0.0) * 4.0) as usize + 8;
+    for _ in 0..max_steps {
+        let (distance, normal) = exit_edge(&polygon(cell), start, direction)?;
+        if distance > max_distance {
+            return None;
+        }
+        let probe = start + direction * (distance + PROBE);
+        cell = (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point2::new(x, y)))
+            .filter(|offset| *offset != Point2::new(0, 0))
+            .map(|offset| cell + offset)
+            .find(|neighbour| polygon_contains(&polygon(*neighbour), probe))?;
+        if is_solid(cell) {
+            return Some(RaycastHit {
+                point: cell,
+                position: start + direction * distance,
+                distance: distance.max(0.0),
+                normal: -normal,
+            });
+        }
+    }
+    None
+}
+
+/// Returns the start and end of each edge of a polygon, wrapping around from
+/// the last vertex to the first.
+fn edges(polygon: &[Vec2]) -> impl Iterator<Item = (&Vec2, &Vec2)> {
+    polygon.iter().zip(polygon.iter().cycle().skip(1))
+}
+
+/// Returns the distance along a ray to the edge of a convex polygon it leaves
+/// through and the outward normal of the edge.
+fn exit_edge(polygon: &[Vec2], start: Vec2, direction: Vec2) -> Option<(f32, Vec2)> {
+    let mut exit: Option<(f32, Vec2)> = None;
+    for (vertex, next) in edges(polygon) {
+        let edge = *next - *vertex;
+        let normal = Vec2::new(edge.y, -edge.x).normalize();
+        let speed = direction.dot(normal);
+        if speed <= 0.0 {
+            continue;
+        }
+        let distance = (*vertex - start).dot(normal) / speed;
+        if exit.is_none_or(|(nearest, _)| distance < nearest) {
+            exit = Some((distance, normal));
+        }
+    }
+    exit
+}
+
+/// Returns `true` if a counter clockwise convex polygon contains a position,
+/// including its edges.
+fn polygon_contains(polygon: &[Vec2], position: Vec2) -> bool {
+    edges(polygon).all(|(vertex, next)| {
+        let edge = *next - *vertex;
+        let offset = position - *vertex;
+        edge.x * offset.y - edge.y * offset.x >= -EPSILON
+    })
+}
+
+/// Returns `true` if a circle overlaps a counter clockwise convex polygon,
+/// without counting a circle which only touches it.
+pub(crate) fn circle_overlaps_polygon(circle: Circle, polygon: &[Vec2]) -> bool {
+    if polygon_contains(polygon, circle.center) {
+        return true;
+    }
+    edges(polygon).any(|(vertex, next)| {
+        let edge = *next - *vertex;
+        let t = ((circle.center - *vertex).dot(edge) / edge.length_squared()).clamp(0.0, 1.0);
+        let nearest = *vertex + edge * t;
+        nearest.distance(circle.center) < circle.radius - EPSILON
+    })
+}
+
+/// Returns `true` if a polygon, which may be concave and in either winding,
+/// encloses a position by the even-odd rule.
+pub(crate) fn encloses(polygon: &[Vec2], position: Vec2) -> bool {
+    let mut inside = false;
+    for (vertex, next) in edges(polygon) {
+        if (vertex.y > position.y) != (next.y > position.y) {
+            let x = vertex.x + (position.y - vertex.y) / (next.y - vertex.y) * (next.x - vertex.x);
+            if position.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Returns the distance along a ray to the first tile edge it crosses on an
+/// axis and the distance between the edges, which are both infinite if the
+/// ray does not move along the axis.
+fn first_edge(start: f32, direction: f32) -> (f32, f32) {
+    if direction == 0.0 {
+        return (f32::INFINITY, f32::INFINITY);
+    }
+    let delta = (1.0 / direction).abs();
+    let next = if direction > 0.0 {
+        (start.floor() + 1.0 - start) * delta
+    } else {
+        (start - start.floor()) * delta
+    };
+    (next, delta)
+}
+
+/// Returns the tiles a box covers along an axis, from the first to the last.
+fn covered(min: f32, max: f32) -> (Coord, Coord) {
+    (
+        (min + EPSILON).floor() as Coord,
+        (max - EPSILON).ceil() as Coord - 1,
+    )
+}
+
+/// Moves a box along one axis until it touches a solid tile, returning how
+/// far it moved and if it was blocked.
+///
+/// With `vertical` the box moves along the y axis and the points are
+/// flipped so the axis of motion is always the first coordinate.
+fn sweep_axis<F>(aabb: Aabb, motion: f32, vertical: bool, is_solid: &F) -> (f32, bool)
+where
+    F: Fn(Point2) -> bool,
+{
+    let (min, max, cross_min, cross_max) = if vertical {
+        (aabb.min.y, aabb.max.y, aabb.min.x, aabb.max.x)
+    } else {
+        (aabb.min.x, aabb.max.x, aabb.min.y, aabb.max.y)
+    };
+    let (first, last) = covered(cross_min, cross_max);
+    let blocked = |line: Coord| {
+        (first..=last).any(|cross| {
+            if vertical {
+                is_solid(Point2::new(cross, line))
+            } else {
+                is_solid(Point2::new(line, cross))
+            }
+        })
+    };
+    if motion > 0.0 {
+        let (_, from) = covered(min, max);
+        let (_, to) = covered(min + motion, max + motion);
+        for line in from + 1..=to {
+            if blocked(line) {
+                return (line as f32 - max, true);
+            }
+        }
+    } else {
+        // Without motion no lines are crossed.
+        let (from, _) = covered(min, max);
+        let (to, _) = covered(min + motion, max + motion);
+        for line in (to..from).rev() {
+            if blocked(line) {
+                return (line as f32 + 1.0 - min, true);
+            }
+        }
+    }
+    (motion, false)
+}
+
+/// Moves a box by a motion along the x axis and then the y axis, stopping
+/// each axis at the first solid tile.
+pub(crate) fn move_and_collide<F>(aabb: Aabb, motion: Vec2, is_solid: F) -> CollisionMove
+where
+    F: Fn(Point2) -> bool,
+{
+    let (x, blocked_x) = sweep_axis(aabb, motion.x, false, &is_solid);
+    let aabb = aabb.translated(Vec2::new(x, 0.0));
+    let (y, blocked_y) = sweep_axis(aabb, motion.y, true, &is_solid);
+    CollisionMove {
+        aabb: aabb.translated(Vec2::new(0.0, y)),
+        motion: Vec2::new(x, y),
+        blocked_x,
+        blocked_y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall(point: Point2) -> bool {
+        point.x == 3 || point.y == -2
+    }
+
+    #[test]
+    fn test_raycast() {
+        let hit = raycast(Vec2::new(0.5, 0.5), Vec2::new(4.0, 0.0), wall).unwrap();
+        assert_eq!(hit.point, Point2::new(3, 0));
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        assert!(raycast(Vec2::new(0.5, 0.5), Vec2::new(2.0, 0.0), wall).is_none());
+
+        let hit = raycast(Vec2::new(0.5, 0.5), Vec2::new(0.0, -10.0), wall).unwrap();
+        assert_eq!(hit.point, Point2::new(0, -2));
+        assert_eq!(hit.distance, 1.5);
+        assert_eq!(hit.normal, Vec2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_polygon_raycast() {
+        let square = |point: Point2| {
+            let min = Vec2::new(point.x as f32, point.y as f32);
+            vec![
+                min,
+                min + Vec2::new(1.0, 0.0),
+                min + Vec2::new(1.0, 1.0),
+                min + Vec2::new(0.0, 1.0),
+            ]
+        };
+        let start = Vec2::new(0.5, 0.25);
+        for direction in [
+            Vec2::new(4.0, 0.5),
+            Vec2::new(0.5, -4.0),
+            Vec2::new(3.0, -2.0),
+        ]
+        .iter()
+        {
+            let walked =
+                polygon_raycast(start, *direction, 10.0, Point2::new(0, 0), square, wall).unwrap();
+            let stepped = raycast(start, *direction * 10.0, wall).unwrap();
+            assert_eq!(walked.point, stepped.point);
+            assert_eq!(walked.normal, stepped.normal);
+            assert!((walked.distance - stepped.distance).abs() < 0.001);
+        }
+        assert!(polygon_raycast(
+            start,
+            Vec2::new(1.0, 0.0),
+            2.0,
+            Point2::new(0, 0),
+            square,
+            wall
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_shape_overlaps() {
+        let square = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        assert!(circle_overlaps_polygon(
+            Circle::new(Vec2::new(0.5, 0.5), 0.1),
+            &square
+        ));
+        assert!(circle_overlaps_polygon(
+            Circle::new(Vec2::new(1.5, 0.5), 0.6),
+            &square
+        ));
+        // Touching the edge or missing the corner does not overlap.
+        assert!(!circle_overlaps_polygon(
+            Circle::new(Vec2::new(1.5, 0.5), 0.5),
+            &square
+        ));
+        assert!(!circle_overlaps_polygon(
+            Circle::new(Vec2::new(1.5, 1.5), 0.7),
+            &square
+        ));
+
+        // An L shape, which is concave and clockwise.
+        let l_shape = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+        assert!(encloses(&l_shape, Vec2::new(0.5, 1.5)));
+        assert!(encloses(&l_shape, Vec2::new(1.5, 0.5)));
+        assert!(!encloses(&l_shape, Vec2::new(1.5, 1.5)));
+        assert!(!encloses(&l_shape, Vec2::new(-0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_move_and_collide() {
+        let aabb = Aabb::new(Vec2::new(0.25, 0.0), Vec2::new(0.75, 1.5));
+
+        // Sliding along the floor into the wall.
+        let moved = move_and_collide(aabb, Vec2::new(10.0, -10.0), wall);
+        assert!(moved.blocked_x && moved.blocked_y);
+        assert_eq!(moved.aabb.max.x, 3.0);
+        assert_eq!(moved.aabb.min.y, -1.0);
+
+        // Touching the wall does not count as being in it.
+        let moved = move_and_collide(moved.aabb, Vec2::new(0.0, 2.0), wall);
+        assert!(!moved.blocked_x && !moved.blocked_y);
+        assert_eq!(moved.motion, Vec2::new(0.0, 2.0));
+
+        let moved = move_and_collide(moved.aabb, Vec2::new(-1.0, 0.0), wall);
+        assert_eq!(moved.motion, Vec2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_merge_colliders() {
+        let full = TileCollider::default();
+        let half = TileCollider {
+            shape: TileShape::HalfTile(HalfTile::Bottom),
+            ..Default::default()
+        };
+        let slope = TileCollider {
+            shape: TileShape::Slope(Slope::BottomRight),
+            sensor: true,
+        };
+        let mut tiles = Vec::new();
+        // A 3 by 2 block, a row of half tiles and a slope.
+        for y in 0..2 {
+            for x in 0..3 {
+                tiles.push((Point2::new(x, y), &full));
+            }
+        }
+        tiles.push((Point2::new(0, 5), &half));
+        tiles.push((Point2::new(1, 5), &half));
+        tiles.push((Point2::new(4, 0), &slope));
+
+        let colliders = merge_colliders(&tiles);
+        assert_eq!(colliders.len(), 3);
+        assert!(colliders.contains(&Collider {
+            shape: ColliderShape::Cuboid(Aabb::new(Vec2::new(0.0, 0.0), Vec2::new(3.0, 2.0))),
+            sensor: false,
+        }));
+        assert!(colliders.contains(&Collider {
+            shape: ColliderShape::Cuboid(Aabb::new(Vec2::new(0.0, 5.0), Vec2::new(2.0, 5.5))),
+            sensor: false,
+        }));
+        assert!(colliders.contains(&Collider {
+            shape: ColliderShape::ConvexPolygon(vec![
+                Vec2::new(4.0, 0.0),
+                Vec2::new(5.0, 0.0),
+                Vec2::new(5.0, 1.0),
+            ]),
+            sensor: true,
+        }));
+
+        let colliders = tile_colliders(&tiles);
+        assert_eq!(colliders.len(), 9);
+        assert!(colliders.contains(&Collider {
+            shape: ColliderShape::Cuboid(Aabb::new(Vec2::new(1.0, 5.0), Vec2::new(2.0, 5.5))),
+            sensor: false,
+        }));
+    }
+}
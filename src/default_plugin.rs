@@ -20,6 +20,6 @@ pub struct TilemapDefaultPlugins;
 
 impl PluginGroup for TilemapDefaultPlugins {
     fn build(&mut self, group: &mut PluginGroupBuilder) {
-        group.add(crate::TilemapPlugin::default());
+        group.add(crate::TilemapPlugin);
     }
 }
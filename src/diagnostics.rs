@@ -0,0 +1,249 @@
+//! Bevy diagnostics of the tilemaps.
+//!
+//! The [`TilemapDiagnosticsPlugin`] measures the tilemaps of an app every
+//! frame and reports them as Bevy diagnostics, which can be logged with the
+//! `LogDiagnosticsPlugin` or read from the `Diagnostics` resource to draw an
+//! overlay or tune chunk sizes, layer kinds and auto spawn radiuses.
+//!
+//! The diagnostics are the number of chunks and of spawned chunks, the number
+//! of tiles in dense, sparse and run length layers, the chunk meshes rebuilt
+//! in the frame, the bytes of vertex attributes uploaded for the chunk meshes
+//! and the chunk events handled in the frame and left queued after it. Bevy
+//! uploads every vertex buffer of a changed mesh, so a mesh whose dirty tiles
+//! are updated counts with all its vertices, same as a rebuilt mesh.
+//!
+//! The tiles are counted by walking every layer of every chunk each frame,
+//! so the plugin is meant for development builds rather than releases.
+//!
+//! The `DiagnosticsPlugin` of Bevy needs to be added as well, which the
+//! default plugins of Bevy already do.
+//!
+//! # Logging the diagnostics
+//! ```no_run
+//! use bevy_app::prelude::*;
+//! use bevy_diagnostic::{DiagnosticsPlugin, LogDiagnosticsPlugin};
+//! use bevy_tilemap::{diagnostics::TilemapDiagnosticsPlugin, prelude::*};
+//!
+//! App::build()
+//!     .add_plugin(DiagnosticsPlugin)
+//!     .add_plugin(LogDiagnosticsPlugin::default())
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_plugin(TilemapDiagnosticsPlugin)
+//!     .run()
+//! ```
+
+use crate::{chunk::render, lib::*, Tilemap};
+
+/// A plugin which reports diagnostics of the tilemaps.
+#[derive(Default)]
+pub struct TilemapDiagnosticsPlugin;
+
+impl TilemapDiagnosticsPlugin {
+    /// The number of chunks of all tilemaps.
+    pub const CHUNKS: DiagnosticId =
+        DiagnosticId::from_u128(13182978215241031666097948262810527068);
+    /// The number of spawned chunks of all tilemaps.
+    pub const SPAWNED_CHUNKS: DiagnosticId =
+        DiagnosticId::from_u128(153038100042470527841654963512305337273);
+    /// The number of tiles in dense layers.
+    pub const DENSE_TILES: DiagnosticId =
+        DiagnosticId::from_u128(285346782011117314266482391904846722924);
+    /// The number of tiles in sparse layers.
+    pub const SPARSE_TILES: DiagnosticId =
+        DiagnosticId::from_u128(254279074068921603966768975403160244335);
+    /// The number of tiles in run length layers.
+    pub const RUN_LENGTH_TILES: DiagnosticId =
+        DiagnosticId::from_u128(139158332009010974026518861969880710553);
+    /// The number of chunk meshes which were rebuilt in the frame.
+    pub const MESH_REBUILDS: DiagnosticId =
+        DiagnosticId::from_u128(100914618864010173461624681215762158559);
+    /// The number of bytes of vertex attributes uploaded for the chunk meshes
+    /// in the frame. Every vertex of a changed mesh is counted, as Bevy
+    /// uploads its vertex buffers as a whole even if only a few tiles changed.
+    pub const ATTRIBUTE_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(181040008922112062555646444895871474660);
+    /// The number of chunk events which were handled in the frame.
+    pub const CHUNK_EVENTS: DiagnosticId =
+        DiagnosticId::from_u128(243222748162546857438299136400362479232);
+    /// The number of chunk events which are still queued after the frame, such
+    /// as the ones of paused tilemaps.
+    pub const QUEUED_CHUNK_EVENTS: DiagnosticId =
+        DiagnosticId::from_u128(107014995984797157771565897542788048593);
+
+    /// Adds the diagnostics.
+    fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        for (id, name) in [
+            (Self::CHUNKS, "tilemap_chunks"),
+            (Self::SPAWNED_CHUNKS, "tilemap_spawned_chunks"),
+            (Self::DENSE_TILES, "tilemap_dense_tiles"),
+            (Self::SPARSE_TILES, "tilemap_sparse_tiles"),
+            (Self::RUN_LENGTH_TILES, "tilemap_run_length_tiles"),
+            (Self::MESH_REBUILDS, "tilemap_mesh_rebuilds"),
+            (Self::ATTRIBUTE_BYTES, "tilemap_attribute_bytes"),
+            (Self::CHUNK_EVENTS, "tilemap_chunk_events"),
+            (Self::QUEUED_CHUNK_EVENTS, "tilemap_queued_chunk_events"),
+        ]
+        .iter()
+        {
+            diagnostics.add(Diagnostic::new(*id, *name, 20));
+        }
+    }
+
+    /// Measures the tilemaps and the work the tilemap systems did in the
+    /// frame.
+    fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        mut stats: ResMut<TilemapFrameStats>,
+        tilemap_query: Query<&Tilemap>,
+    ) {
+        let mut chunks = 0;
+        let mut spawned_chunks = 0;
+        let mut tiles: [usize; 3] = [0; 3];
+        let mut queued_chunk_events = 0;
+        for tilemap in tilemap_query.iter() {
+            chunks += tilemap.chunks().len();
+            spawned_chunks += tilemap.spawned_chunks().len();
+            for chunk in tilemap.chunks().values() {
+                for (total, count) in tiles.iter_mut().zip(chunk.tile_counts().iter()) {
+                    *total += count;
+                }
+            }
+            queued_chunk_events += tilemap.queued_chunk_event_count();
+        }
+        let stats = mem::take(&mut *stats);
+        let [dense_tiles, sparse_tiles, run_length_tiles] = tiles;
+        for (id, value) in [
+            (Self::CHUNKS, chunks),
+            (Self::SPAWNED_CHUNKS, spawned_chunks),
+            (Self::DENSE_TILES, dense_tiles),
+            (Self::SPARSE_TILES, sparse_tiles),
+            (Self::RUN_LENGTH_TILES, run_length_tiles),
+            (Self::MESH_REBUILDS, stats.mesh_rebuilds),
+            (Self::ATTRIBUTE_BYTES, stats.attribute_bytes),
+            (Self::CHUNK_EVENTS, stats.chunk_events),
+            (Self::QUEUED_CHUNK_EVENTS, queued_chunk_events),
+        ]
+        .iter()
+        {
+            diagnostics.add_measurement(*id, *value as f64);
+        }
+    }
+}
+
+impl Plugin for TilemapDiagnosticsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<TilemapFrameStats>()
+            .add_startup_system(Self::setup_system.system())
+            .add_system_to_stage(CoreStage::Last, Self::diagnostic_system.system());
+    }
+}
+
+/// The work the tilemap systems did in a frame, which they add to when the
+/// diagnostics plugin is added.
+#[derive(Default, Debug)]
+pub(crate) struct TilemapFrameStats {
+    /// The number of chunk meshes which were rebuilt.
+    mesh_rebuilds: usize,
+    /// The number of bytes of vertex attributes uploaded.
+    attribute_bytes: usize,
+    /// The number of chunk events which were handled.
+    chunk_events: usize,
+}
+
+impl TilemapFrameStats {
+    /// Counts a chunk mesh which was rebuilt, and uploaded along with it.
+    pub(crate) fn add_rebuild(&mut self, mesh: &Mesh) {
+        self.mesh_rebuilds += 1;
+        self.add_upload(mesh);
+    }
+
+    /// Counts the vertex attributes of a chunk mesh which are uploaded.
+    pub(crate) fn add_upload(&mut self, mesh: &Mesh) {
+        self.attribute_bytes += mesh.count_vertices() * render::vertex_size();
+    }
+
+    /// Counts chunk events which were handled.
+    pub(crate) fn add_chunk_events(&mut self, count: usize) {
+        self.chunk_events += count;
+    }
+
+    /// Adds the stats of a system run.
+    pub(crate) fn merge(&mut self, other: TilemapFrameStats) {
+        self.mesh_rebuilds += other.mesh_rebuilds;
+        self.attribute_bytes += other.attribute_bytes;
+        self.chunk_events += other.chunk_events;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{system::tilemap_events, tile::Tile};
+
+    #[test]
+    fn test_measure_tilemaps() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_plugin(DiagnosticsPlugin)
+            .add_plugin(TilemapDiagnosticsPlugin)
+            .add_stage_before(CoreStage::Last, "update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = Tilemap::new(Handle::weak(HandleId::random::<TextureAtlas>()), 32, 32);
+        for point in [(0, 0), (1, 0)].iter() {
+            tilemap.insert_chunk(*point).unwrap();
+        }
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        tilemap
+            .insert_tiles(vec![
+                Tile {
+                    point: (0, 0),
+                    ..Default::default()
+                },
+                Tile {
+                    point: (1, 1),
+                    ..Default::default()
+                },
+            ])
+            .unwrap();
+        app.world.spawn().insert(tilemap).insert(Visible::default());
+
+        app.update();
+
+        let bytes: usize = app
+            .world
+            .get_resource::<Assets<Mesh>>()
+            .unwrap()
+            .iter()
+            .map(|(_, mesh)| mesh.count_vertices() * render::vertex_size())
+            .sum();
+        assert!(bytes > 0);
+        let diagnostics = app.world.get_resource::<Diagnostics>().unwrap();
+        let value = |id| {
+            diagnostics
+                .get(id)
+                .and_then(|diagnostic| diagnostic.value())
+        };
+        assert_eq!(value(TilemapDiagnosticsPlugin::CHUNKS), Some(2.0));
+        assert_eq!(value(TilemapDiagnosticsPlugin::SPAWNED_CHUNKS), Some(1.0));
+        assert_eq!(value(TilemapDiagnosticsPlugin::DENSE_TILES), Some(0.0));
+        assert_eq!(value(TilemapDiagnosticsPlugin::SPARSE_TILES), Some(2.0));
+        assert_eq!(value(TilemapDiagnosticsPlugin::MESH_REBUILDS), Some(1.0));
+        assert_eq!(
+            value(TilemapDiagnosticsPlugin::ATTRIBUTE_BYTES),
+            Some(bytes as f64)
+        );
+        assert_eq!(
+            value(TilemapDiagnosticsPlugin::QUEUED_CHUNK_EVENTS),
+            Some(0.0)
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! The tilemap events.
 
-use crate::{chunk::LayerKind, lib::*};
+#[cfg(feature = "tileset")]
+use crate::tileset::Tileset;
+use crate::{chunk::LayerKind, collision::ChunkColliders, lib::*, stream::StreamProgress};
 
 #[derive(Debug)]
 /// Events that can happen to chunks.
@@ -15,11 +17,41 @@ pub enum TilemapChunkEvent {
         /// The chunk point that had been modified.
         point: Point2,
     },
-    /// An event when a chunk needs to be despawned.
+    /// An event when a chunk needs to be despawned, keeping its tiles.
     Despawned {
         /// The point of the chunk to despawn.
         point: Point2,
     },
+    /// An event when a chunk was deleted along with its tiles and needs to be
+    /// despawned if it was spawned.
+    Deleted {
+        /// The point of the deleted chunk.
+        point: Point2,
+    },
+    /// An event when a chunk needs its tiles generated, such as by procedural
+    /// generation.
+    ///
+    /// It is sent when a brand-new chunk is created automatically by inserting
+    /// tiles with `auto_chunk`, and when auto spawn requests a chunk that does
+    /// not exist or has no tiles. With `auto_chunk` a missing chunk is created
+    /// empty, otherwise it must be inserted before it can be spawned.
+    NeedsPopulation {
+        /// The point of the chunk to populate.
+        point: Point2,
+    },
+    /// An event when auto spawn created a brand-new empty chunk which did not
+    /// exist before, sent before it is spawned.
+    ///
+    /// Unlike [`Spawned`], it is never sent for a chunk which already existed,
+    /// so the chunks it is sent for are exactly the ones which need their
+    /// tiles. It is only sent with `auto_chunk` and without a generator, which
+    /// fills in the chunks it creates itself.
+    ///
+    /// [`Spawned`]: TilemapChunkEvent::Spawned
+    Created {
+        /// The point of the created chunk.
+        point: Point2,
+    },
     /// An event which adds a layer to the chunks.
     AddLayer {
         /// The layer kind to add.
@@ -33,3 +65,96 @@ pub enum TilemapChunkEvent {
         sprite_layer: usize,
     },
 }
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// An event sent every frame that a tilemap loads chunks from a stream.
+pub struct TilemapStreamEvent {
+    /// The entity of the tilemap that is streaming.
+    pub entity: Entity,
+    /// The progress of the stream.
+    pub progress: StreamProgress,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// An event sent when an inserted tile has a sprite index which is out of the
+/// bounds of its texture atlas, or a texture atlas which does not exist.
+///
+/// Only sent if the tilemap was built with
+/// [`TilemapBuilder::validate_sprite_indices`].
+///
+/// [`TilemapBuilder::validate_sprite_indices`]: crate::tilemap::TilemapBuilder::validate_sprite_indices
+pub struct TilemapSpriteIndexEvent {
+    /// The entity of the tilemap the tile is in.
+    pub entity: Entity,
+    /// The global point of the tile.
+    pub point: Point3,
+    /// The sprite order of the tile.
+    pub sprite_order: usize,
+    /// The id of the texture atlas of the tile.
+    pub atlas: usize,
+    /// The offending sprite index.
+    pub sprite_index: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// A chunk which was despawned or removed, with its tiles.
+pub struct UnloadedChunk {
+    /// The point of the chunk.
+    pub point: Point2,
+    /// The tiles of the chunk in the stream format, which are restored with
+    /// [`Tilemap::restore_chunk`].
+    ///
+    /// [`Tilemap::restore_chunk`]: crate::tilemap::Tilemap::restore_chunk
+    pub bytes: Vec<u8>,
+    /// If the chunk was removed, rather than only despawned, so its tiles are
+    /// gone from the tilemap.
+    pub removed: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// An event sent when a chunk is despawned or removed, handing out its tiles
+/// so changes to them can be persisted before they are dropped.
+///
+/// Only sent if the tilemap was built with
+/// [`TilemapBuilder::persist_unloaded_chunks`].
+///
+/// [`TilemapBuilder::persist_unloaded_chunks`]: crate::tilemap::TilemapBuilder::persist_unloaded_chunks
+pub struct TilemapChunkUnloadEvent {
+    /// The entity of the tilemap the chunk is in.
+    pub entity: Entity,
+    /// The chunk with its tiles.
+    pub chunk: UnloadedChunk,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// An event sent with the colliders of a spawned chunk which were built this
+/// frame, within the collider budget.
+///
+/// The colliders of a chunk may be spread over several frames, the last of
+/// which is marked complete. A chunk without colliders is sent once, complete
+/// and empty.
+///
+/// Only sent if the tilemap was built with
+/// [`TilemapBuilder::collider_budget`].
+///
+/// [`TilemapBuilder::collider_budget`]: crate::tilemap::TilemapBuilder::collider_budget
+pub struct TilemapCollidersEvent {
+    /// The entity of the tilemap the chunk is in.
+    pub entity: Entity,
+    /// The colliders of the chunk.
+    pub colliders: ChunkColliders,
+}
+
+#[cfg(feature = "tileset")]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// An event sent when the texture atlas of a [`Tileset`] is built and its
+/// [`TileRegistry`] is filled.
+///
+/// [`Tileset`]: crate::tileset::Tileset
+/// [`TileRegistry`]: crate::tileset::TileRegistry
+pub struct TilesetReadyEvent {
+    /// The tileset that is ready.
+    pub tileset: Handle<Tileset>,
+    /// The texture atlas built from the textures of the tileset.
+    pub texture_atlas: Handle<TextureAtlas>,
+}
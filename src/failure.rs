@@ -0,0 +1,139 @@
+//! Injecting failures into the systems of a tilemap.
+//!
+//! Some failures only happen in the wild, such as a chunk mesh which went
+//! missing, a texture atlas which is not loaded yet or an I/O error while
+//! streaming chunks in. With the `failure_injection` feature they can be
+//! injected into a tilemap with [`Tilemap::inject_failure`] to test how a
+//! game handles them.
+//!
+//! Every injected failure happens the next time the tilemap runs into the
+//! situation it stands for, until it has happened as many times as it was
+//! injected:
+//!
+//! - [`InjectedFailure::MissingMesh`] makes the update of a modified chunk
+//!   find no mesh. The error is logged and the chunk is not updated.
+//! - [`InjectedFailure::UnloadedTextureAtlas`] makes the texture atlas of a
+//!   tile appear to be loading. Its sprite index is checked again next frame
+//!   and the level of detail stand-in of a chunk is baked in white.
+//! - [`InjectedFailure::StreamIo`] makes polling a stream fail with an I/O
+//!   error, which ends the stream like a real one would.
+//!
+//! [`Tilemap::inject_failure`]: crate::tilemap::Tilemap::inject_failure
+//!
+//! # Failing a stream
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{failure::InjectedFailure, prelude::*};
+//! use bevy_utils::Duration;
+//! use std::io::Cursor;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut source = Tilemap::new(texture_atlas_handle.clone_weak(), 32, 32);
+//! source.insert_chunk((0, 0)).unwrap();
+//! let mut bytes = Vec::new();
+//! source.save_streaming(&mut bytes).unwrap();
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.load_streaming(Cursor::new(bytes), Duration::from_millis(4)).unwrap();
+//! tilemap.inject_failure(InjectedFailure::StreamIo, 1);
+//!
+//! assert!(tilemap.poll_streaming().is_err());
+//! assert_eq!(tilemap.injected_failures(InjectedFailure::StreamIo), 0);
+//! assert!(tilemap.streaming_progress().is_none());
+//! ```
+
+use crate::lib::*;
+
+/// A failure which can be injected into the systems of a tilemap.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum InjectedFailure {
+    /// The mesh of a modified chunk is missing.
+    MissingMesh,
+    /// The texture atlas of a tile is not loaded yet.
+    UnloadedTextureAtlas,
+    /// Reading a chunk from a stream fails with an I/O error.
+    StreamIo,
+}
+
+/// The number of times each failure is still to happen.
+///
+/// The counts are atomic so that systems which only read the tilemap can take
+/// failures too.
+#[derive(Debug, Default)]
+pub(crate) struct FailureInjector {
+    /// The count of [`InjectedFailure::MissingMesh`].
+    missing_mesh: AtomicUsize,
+    /// The count of [`InjectedFailure::UnloadedTextureAtlas`].
+    unloaded_texture_atlas: AtomicUsize,
+    /// The count of [`InjectedFailure::StreamIo`].
+    stream_io: AtomicUsize,
+}
+
+impl FailureInjector {
+    /// Returns the count of a failure.
+    fn count(&self, failure: InjectedFailure) -> &AtomicUsize {
+        match failure {
+            InjectedFailure::MissingMesh => &self.missing_mesh,
+            InjectedFailure::UnloadedTextureAtlas => &self.unloaded_texture_atlas,
+            InjectedFailure::StreamIo => &self.stream_io,
+        }
+    }
+
+    /// Makes a failure happen `times` more times.
+    pub(crate) fn inject(&self, failure: InjectedFailure, times: usize) {
+        self.count(failure)
+            .fetch_add(times, AtomicOrdering::Relaxed);
+    }
+
+    /// Returns the number of times a failure is still to happen.
+    pub(crate) fn pending(&self, failure: InjectedFailure) -> usize {
+        self.count(failure).load(AtomicOrdering::Relaxed)
+    }
+
+    /// Returns `true` if a failure is to happen now, counting it down.
+    pub(crate) fn take(&self, failure: InjectedFailure) -> bool {
+        self.count(failure)
+            .fetch_update(AtomicOrdering::Relaxed, AtomicOrdering::Relaxed, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Removes every failure which is still to happen.
+    pub(crate) fn clear(&self) {
+        for failure in [
+            InjectedFailure::MissingMesh,
+            InjectedFailure::UnloadedTextureAtlas,
+            InjectedFailure::StreamIo,
+        ]
+        .iter()
+        {
+            self.count(*failure).store(0, AtomicOrdering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_injector() {
+        let injector = FailureInjector::default();
+        assert!(!injector.take(InjectedFailure::MissingMesh));
+
+        injector.inject(InjectedFailure::MissingMesh, 2);
+        assert_eq!(injector.pending(InjectedFailure::MissingMesh), 2);
+        assert_eq!(injector.pending(InjectedFailure::StreamIo), 0);
+        assert!(injector.take(InjectedFailure::MissingMesh));
+        assert!(injector.take(InjectedFailure::MissingMesh));
+        assert!(!injector.take(InjectedFailure::MissingMesh));
+
+        injector.inject(InjectedFailure::StreamIo, 3);
+        injector.clear();
+        assert!(!injector.take(InjectedFailure::StreamIo));
+    }
+}
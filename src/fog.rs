@@ -0,0 +1,181 @@
+//! Fog of war over a sprite layer of a tilemap.
+//!
+//! With [`TilemapBuilder::fog_of_war`] a dense sprite layer is managed by the
+//! tilemap as a fog overlay. Every point is in one of three [`FogState`]s:
+//! unexplored points are covered by the fog sprite, explored points are
+//! covered by a darkened fog sprite and visible points are not covered at all.
+//!
+//! [`Tilemap::reveal`] explores points for good while
+//! [`Tilemap::set_visible`] replaces the points which are currently visible,
+//! such as the field of view of a player. The fog of a chunk is filled in when
+//! it is spawned, so the fog covers newly spawned chunks automatically.
+//!
+//! [`TilemapBuilder::fog_of_war`]: crate::tilemap::TilemapBuilder::fog_of_war
+//! [`Tilemap::reveal`]: crate::tilemap::Tilemap::reveal
+//! [`Tilemap::set_visible`]: crate::tilemap::Tilemap::set_visible
+//!
+//! # Revealing the map
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     fog::{FogOfWar, FogState},
+//!     prelude::*,
+//! };
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .fog_of_war(FogOfWar { sprite_order: 1, sprite_index: 0, ..Default::default() })
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.set_visible(vec![(0, 0), (1, 0)]).unwrap();
+//! tilemap.set_visible(vec![(1, 0), (2, 0)]).unwrap();
+//!
+//! assert_eq!(tilemap.fog_state((0, 0)), Some(FogState::Explored));
+//! assert_eq!(tilemap.fog_state((2, 0)), Some(FogState::Visible));
+//! assert_eq!(tilemap.fog_state((3, 0)), Some(FogState::Unexplored));
+//! ```
+
+use crate::{chunk::RawTile, lib::*};
+
+/// The state of a point under the fog of war.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FogState {
+    /// The point was never seen and is covered by the fog.
+    Unexplored,
+    /// The point was seen before but is not visible now, and is covered by a
+    /// darkened fog.
+    Explored,
+    /// The point is currently visible and is not covered.
+    Visible,
+}
+
+/// The configuration of the fog of war of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FogOfWar {
+    /// The sprite order of the dense layer the fog is drawn on.
+    pub sprite_order: usize,
+    /// The sprite index of the fog in the main texture atlas.
+    pub sprite_index: usize,
+    /// The color of the fog over unexplored points.
+    pub unexplored_color: Color,
+    /// The color of the fog over explored points which are not visible.
+    pub explored_color: Color,
+}
+
+impl Default for FogOfWar {
+    fn default() -> FogOfWar {
+        FogOfWar {
+            sprite_order: 0,
+            sprite_index: 0,
+            unexplored_color: Color::BLACK,
+            explored_color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+        }
+    }
+}
+
+/// The fog of war of a tilemap with the explored and visible points.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Fog {
+    /// The configuration of the fog.
+    pub(crate) settings: FogOfWar,
+    /// The points which were ever seen.
+    explored: HashSet<Point2>,
+    /// The points which are currently visible.
+    visible: HashSet<Point2>,
+}
+
+impl Fog {
+    /// Constructs a new fog where every point is unexplored.
+    pub(crate) fn new(settings: FogOfWar) -> Fog {
+        Fog {
+            settings,
+            explored: HashSet::default(),
+            visible: HashSet::default(),
+        }
+    }
+
+    /// Returns the state of a point.
+    pub(crate) fn state(&self, point: Point2) -> FogState {
+        if self.visible.contains(&point) {
+            FogState::Visible
+        } else if self.explored.contains(&point) {
+            FogState::Explored
+        } else {
+            FogState::Unexplored
+        }
+    }
+
+    /// Explores points, returning the points which changed state.
+    pub(crate) fn reveal<I: IntoIterator<Item = Point2>>(&mut self, points: I) -> Vec<Point2> {
+        points
+            .into_iter()
+            .filter(|point| self.explored.insert(*point) && !self.visible.contains(point))
+            .collect()
+    }
+
+    /// Replaces the visible points, returning the points which changed state.
+    pub(crate) fn set_visible<I: IntoIterator<Item = Point2>>(&mut self, points: I) -> Vec<Point2> {
+        let visible: HashSet<Point2> = points.into_iter().collect();
+        let mut changed: Vec<Point2> = self.visible.difference(&visible).copied().collect();
+        changed.extend(visible.difference(&self.visible).copied());
+        self.explored.extend(visible.iter().copied());
+        self.visible = visible;
+        changed
+    }
+
+    /// Returns the fog tile of a point, or `None` if it is not covered.
+    pub(crate) fn raw_tile(&self, point: Point2) -> Option<RawTile> {
+        let color = match self.state(point) {
+            FogState::Unexplored => self.settings.unexplored_color,
+            FogState::Explored => self.settings.explored_color,
+            FogState::Visible => return None,
+        };
+        Some(RawTile {
+            index: self.settings.sprite_index,
+            color,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fog_states() {
+        let mut fog = Fog::new(FogOfWar::default());
+        let a = Point2::new(0, 0);
+        let b = Point2::new(1, 0);
+        let c = Point2::new(2, 0);
+
+        assert_eq!(fog.reveal(vec![a, a]), vec![a]);
+        assert_eq!(fog.state(a), FogState::Explored);
+        assert_eq!(fog.state(b), FogState::Unexplored);
+
+        let mut changed = fog.set_visible(vec![a, b]);
+        changed.sort();
+        assert_eq!(changed, vec![a, b]);
+        assert_eq!(fog.state(b), FogState::Visible);
+        assert_eq!(fog.raw_tile(b), None);
+
+        let mut changed = fog.set_visible(vec![b, c]);
+        changed.sort();
+        assert_eq!(changed, vec![a, c]);
+        assert_eq!(fog.state(a), FogState::Explored);
+        assert_eq!(
+            fog.raw_tile(a).map(|tile| tile.color),
+            Some(Color::rgba(0.0, 0.0, 0.0, 0.5))
+        );
+        assert_eq!(fog.reveal(vec![c]), vec![]);
+    }
+}
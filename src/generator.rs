@@ -0,0 +1,108 @@
+//! Generating the tiles of new chunks for infinite worlds.
+//!
+//! A [`ChunkGenerator`] set with [`Tilemap::set_generator`] is run whenever
+//! the auto spawn system needs a chunk which does not exist yet. Instead of
+//! being spawned right away, the chunk is queued and generated during the
+//! next update, with all queued chunks generated in parallel on the compute
+//! task pool. The chunk is then inserted with the generated tiles and
+//! spawned. The camera can therefore wander forever without populating any
+//! chunks by hand.
+//!
+//! Chunks which exist are never generated again, and neither are chunks
+//! which are inserted or spawned by hand. Generated tiles are not part of the
+//! undo history.
+//!
+//! Tiles which depend on the tiles around them, such as autotiles, can not be
+//! picked in [`ChunkGenerator::generate`] along the edges of a chunk, as the
+//! chunks next to it may not exist yet. Once a chunk is inserted, the tiles
+//! along its edges and along the facing edges of the chunks around it are
+//! passed to [`ChunkGenerator::retile`] with a view of the whole tilemap, so
+//! transitions line up across the chunks without seams.
+//!
+//! [`Tilemap::set_generator`]: crate::tilemap::Tilemap::set_generator
+//!
+//! # Generating grass with ponds
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     dimension::Dimension3,
+//!     generator::ChunkGenerator,
+//!     point::{Coord, Point2, Point3},
+//!     prelude::*,
+//! };
+//!
+//! struct Meadow;
+//!
+//! impl ChunkGenerator for Meadow {
+//!     fn generate(&self, point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>> {
+//!         let mut tiles = Vec::new();
+//!         for y in 0..dimensions.height as Coord {
+//!             for x in 0..dimensions.width as Coord {
+//!                 // A pond in every other chunk.
+//!                 let pond = (point.x + point.y) % 2 == 0 && x == 4 && y == 4;
+//!                 tiles.push(Tile {
+//!                     point: Point3::new(x, y, 0),
+//!                     sprite_index: if pond { 1 } else { 0 },
+//!                     ..Default::default()
+//!                 });
+//!             }
+//!         }
+//!         tiles
+//!     }
+//! }
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .auto_spawn(2, 2)
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.set_generator(Meadow);
+//! ```
+
+use crate::{chunk::RawTile, lib::*, source::TileSource, tile::Tile};
+
+/// Generates the tiles of chunks which do not exist yet.
+pub trait ChunkGenerator: Send + Sync + 'static {
+    /// Returns the tiles of the chunk at a point, with tile points local to
+    /// the chunk from `(0, 0)` at its bottom left corner to one less than its
+    /// dimensions.
+    ///
+    /// It may be called for many chunks at the same time from different
+    /// threads, so it should only depend on the point of the chunk, such as
+    /// through a noise function with a fixed seed.
+    fn generate(&self, point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>>;
+
+    /// Returns the tile to replace a tile on either side of the edges of a
+    /// newly generated chunk with, or `None` to keep it.
+    ///
+    /// It is called on the main thread after the chunk is inserted, with the
+    /// global point of the tile and a view of every tile of the tilemap
+    /// before any tile is replaced. Tiles in chunks which do not exist yet
+    /// are missing from the view, and are retiled when their chunk is
+    /// generated. The default keeps every tile.
+    ///
+    /// See [`Tilemap::retile_chunk_border`] for more information.
+    ///
+    /// [`Tilemap::retile_chunk_border`]: crate::tilemap::Tilemap::retile_chunk_border
+    fn retile(
+        &self,
+        _source: &dyn TileSource,
+        _point: Point3,
+        _sprite_order: usize,
+        _tile: &RawTile,
+    ) -> Option<RawTile> {
+        None
+    }
+}
+
+impl Debug for dyn ChunkGenerator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("ChunkGenerator")
+    }
+}
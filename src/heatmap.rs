@@ -0,0 +1,183 @@
+//! Debug heatmaps which color the tiles of a tilemap by a value per point.
+//!
+//! With [`TilemapBuilder::debug_heatmap`] a dense sprite layer is managed by
+//! the tilemap as a heatmap overlay. [`Tilemap::debug_heatmap`] covers every
+//! tile of the existing chunks with a sprite tinted by the value of a field at
+//! its point, such as the cost of a path or the distance of a flow field, so
+//! they can be seen while debugging. The values are spread over the color
+//! ramp of the heatmap from the lowest to the highest value, unless the
+//! heatmap has a fixed range. Points whose value is not finite, such as walls
+//! with an infinite cost, are left uncovered.
+//!
+//! The heatmap is only drawn on demand, so it needs to be drawn again after
+//! the field changes or new chunks are inserted.
+//! [`Tilemap::clear_debug_heatmap`] removes it again.
+//!
+//! [`TilemapBuilder::debug_heatmap`]: crate::tilemap::TilemapBuilder::debug_heatmap
+//! [`Tilemap::debug_heatmap`]: crate::tilemap::Tilemap::debug_heatmap
+//! [`Tilemap::clear_debug_heatmap`]: crate::tilemap::Tilemap::clear_debug_heatmap
+//!
+//! # Showing the distance from the origin
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{heatmap::Heatmap, prelude::*};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .debug_heatmap(Heatmap { sprite_order: 1, ..Default::default() })
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap
+//!     .debug_heatmap(0, |point| (point.x.abs() + point.y.abs()) as f32)
+//!     .unwrap();
+//!
+//! // The origin is the coolest point.
+//! let heatmap = Heatmap::default();
+//! assert_eq!(tilemap.get_tile((0, 0), 1).unwrap().color, heatmap.ramp_color(0.0));
+//! ```
+
+use crate::{chunk::RawTile, lib::*};
+
+/// The configuration of the debug heatmap of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Heatmap {
+    /// The sprite order of the dense layer the heatmap is drawn on.
+    pub sprite_order: usize,
+    /// The sprite index in the main texture atlas which is tinted, ideally a
+    /// plain white sprite.
+    pub sprite_index: usize,
+    /// The colors from the lowest to the highest value, evenly spaced.
+    pub ramp: Vec<Color>,
+    /// The values at the start and end of the ramp, or `None` to use the
+    /// lowest and highest value of the field.
+    pub range: Option<(f32, f32)>,
+}
+
+impl Default for Heatmap {
+    fn default() -> Heatmap {
+        Heatmap {
+            sprite_order: 0,
+            sprite_index: 0,
+            ramp: vec![
+                Color::rgba(0.0, 0.0, 1.0, 0.5),
+                Color::rgba(0.0, 1.0, 1.0, 0.5),
+                Color::rgba(0.0, 1.0, 0.0, 0.5),
+                Color::rgba(1.0, 1.0, 0.0, 0.5),
+                Color::rgba(1.0, 0.0, 0.0, 0.5),
+            ],
+            range: None,
+        }
+    }
+}
+
+impl Heatmap {
+    /// Returns the color of the ramp at a point from 0.0 to 1.0, blended
+    /// between the two colors around it.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_render::prelude::*;
+    /// use bevy_tilemap::heatmap::Heatmap;
+    ///
+    /// let heatmap = Heatmap {
+    ///     ramp: vec![Color::rgb(0.0, 0.0, 0.0), Color::rgb(1.0, 1.0, 1.0)],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(heatmap.ramp_color(0.5), Color::rgb(0.5, 0.5, 0.5));
+    /// assert_eq!(heatmap.ramp_color(2.0), Color::rgb(1.0, 1.0, 1.0));
+    /// ```
+    pub fn ramp_color(&self, t: f32) -> Color {
+        let last = match self.ramp.len() {
+            0 => return Color::NONE,
+            len => len - 1,
+        };
+        let position = t.clamp(0.0, 1.0) * last as f32;
+        let index = (position.floor() as usize).min(last.saturating_sub(1));
+        let (start, end) = match (self.ramp.get(index), self.ramp.get((index + 1).min(last))) {
+            (Some(start), Some(end)) => (*start, *end),
+            _ => return Color::NONE,
+        };
+        let blend = position - index as f32;
+        let start: [f32; 4] = start.into();
+        let end: [f32; 4] = end.into();
+        let mut color = [0.0; 4];
+        for ((channel, start), end) in color.iter_mut().zip(start.iter()).zip(end.iter()) {
+            *channel = start + (end - start) * blend;
+        }
+        Color::rgba(color[0], color[1], color[2], color[3])
+    }
+
+    /// Returns the range the values are spread over, from the values of the
+    /// field unless the range is fixed.
+    pub(crate) fn value_range<I>(&self, values: I) -> (f32, f32)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        if let Some(range) = self.range {
+            return range;
+        }
+        values
+            .into_iter()
+            .filter(|value| value.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            })
+    }
+
+    /// Returns the heatmap tile of a value, or `None` if it is not covered.
+    pub(crate) fn raw_tile(&self, value: f32, (min, max): (f32, f32)) -> Option<RawTile> {
+        if !value.is_finite() {
+            return None;
+        }
+        let t = if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.0
+        };
+        Some(RawTile {
+            index: self.sprite_index,
+            color: self.ramp_color(t),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_tile() {
+        let heatmap = Heatmap::default();
+        let range = heatmap.value_range(vec![2.0, f32::INFINITY, 6.0, 4.0]);
+        assert_eq!(range, (2.0, 6.0));
+
+        let low = heatmap.raw_tile(2.0, range).unwrap();
+        assert_eq!(low.color, heatmap.ramp[0]);
+        let middle = heatmap.raw_tile(4.0, range).unwrap();
+        assert_eq!(middle.color, heatmap.ramp[2]);
+        let high = heatmap.raw_tile(6.0, range).unwrap();
+        assert_eq!(high.color, heatmap.ramp[4]);
+        assert_eq!(heatmap.raw_tile(f32::INFINITY, range), None);
+
+        // A fixed range clamps the values outside of it.
+        let fixed = Heatmap {
+            range: Some((0.0, 1.0)),
+            ..Default::default()
+        };
+        assert_eq!(fixed.value_range(vec![5.0]), (0.0, 1.0));
+        assert_eq!(
+            fixed.raw_tile(5.0, (0.0, 1.0)).unwrap().color,
+            fixed.ramp[4]
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Coordinate math for the hex topologies.
+//!
+//! Tiles are always addressed by their point in the tilemap, which for the
+//! staggered topologies [`HexEvenRows`], [`HexOddRows`], [`HexEvenCols`] and
+//! [`HexOddCols`] is an offset coordinate: every other row or column is pushed
+//! over by half a tile. The neighbours of a tile then depend on whether it is
+//! on an even or odd row or column, which is easy to get wrong.
+//!
+//! Axial coordinates avoid this by using two axes 60 degrees apart, so the
+//! neighbours of every tile are the same six steps away. [`HexX`] and [`HexY`]
+//! points already are axial coordinates. Cube coordinates add a third axis,
+//! which is the negated sum of the other two, and are convenient for rounding
+//! and interpolating.
+//!
+//! The functions in this module take and return tilemap points and convert to
+//! axial coordinates internally. A [`Square`] topology has no hex coordinates
+//! and its points are treated like those of [`HexY`].
+//!
+//! Directions go clockwise around a tile. On pointy top topologies, which are
+//! [`HexY`] and the staggered rows, direction `0` is to the right. On flat top
+//! topologies, which are [`HexX`] and the staggered columns, it is to the top
+//! right.
+//!
+//! [`Square`]: GridTopology::Square
+//! [`HexX`]: GridTopology::HexX
+//! [`HexY`]: GridTopology::HexY
+//! [`HexEvenRows`]: GridTopology::HexEvenRows
+//! [`HexOddRows`]: GridTopology::HexOddRows
+//! [`HexEvenCols`]: GridTopology::HexEvenCols
+//! [`HexOddCols`]: GridTopology::HexOddCols
+//!
+//! # Moving across staggered rows
+//! ```
+//! use bevy_tilemap::{hex, point::Point2, prelude::*};
+//!
+//! let topology = GridTopology::HexEvenRows;
+//!
+//! // Even rows are pushed to the right, so the top right neighbour of a tile
+//! // on an even row is one column over while on an odd row it is not.
+//! assert_eq!(hex::neighbour(topology, (0, 0), 5), Point2::new(1, 1));
+//! assert_eq!(hex::neighbour(topology, (0, 1), 5), Point2::new(0, 2));
+//!
+//! assert_eq!(hex::distance(topology, (0, 0), (3, 4)), 5);
+//! assert_eq!(hex::ring(topology, (0, 0), 2).count(), 12);
+//! assert_eq!(hex::spiral(topology, (0, 0), 2).count(), 19);
+//! ```
+
+use crate::{lib::*, prelude::GridTopology};
+
+/// The axial steps to the neighbours of a tile, clockwise.
+const DIRECTIONS: [(Coord, Coord); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// Converts a tilemap point to axial coordinates.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::{hex, point::Point2, prelude::*};
+///
+/// let axial = hex::offset_to_axial(GridTopology::HexOddCols, (3, 2));
+/// assert_eq!(axial, Point2::new(3, 1));
+/// assert_eq!(hex::axial_to_offset(GridTopology::HexOddCols, axial), Point2::new(3, 2));
+/// ```
+pub fn offset_to_axial<P: Into<Point2>>(topology: GridTopology, point: P) -> Point2 {
+    use GridTopology::*;
+    let point: Point2 = point.into();
+    match topology {
+        Square | HexX | HexY => point,
+        HexEvenRows => Point2::new(point.x - ((point.y + 1) >> 1), point.y),
+        HexOddRows => Point2::new(point.x - (point.y >> 1), point.y),
+        HexEvenCols => Point2::new(point.x, point.y - ((point.x + 1) >> 1)),
+        HexOddCols => Point2::new(point.x, point.y - (point.x >> 1)),
+    }
+}
+
+/// Converts axial coordinates to a tilemap point.
+pub fn axial_to_offset<P: Into<Point2>>(topology: GridTopology, axial: P) -> Point2 {
+    use GridTopology::*;
+    let axial: Point2 = axial.into();
+    match topology {
+        Square | HexX | HexY => axial,
+        HexEvenRows => Point2::new(axial.x + ((axial.y + 1) >> 1), axial.y),
+        HexOddRows => Point2::new(axial.x + (axial.y >> 1), axial.y),
+        HexEvenCols => Point2::new(axial.x, axial.y + ((axial.x + 1) >> 1)),
+        HexOddCols => Point2::new(axial.x, axial.y + (axial.x >> 1)),
+    }
+}
+
+/// Converts axial coordinates to cube coordinates, where the coordinates add
+/// up to zero.
+pub fn axial_to_cube<P: Into<Point2>>(axial: P) -> Point3 {
+    let axial: Point2 = axial.into();
+    Point3::new(axial.x, axial.y, -axial.x - axial.y)
+}
+
+/// Converts cube coordinates to axial coordinates.
+pub fn cube_to_axial<P: Into<Point3>>(cube: P) -> Point2 {
+    let cube: Point3 = cube.into();
+    Point2::new(cube.x, cube.y)
+}
+
+/// Converts a tilemap point to cube coordinates.
+pub fn offset_to_cube<P: Into<Point2>>(topology: GridTopology, point: P) -> Point3 {
+    axial_to_cube(offset_to_axial(topology, point))
+}
+
+/// Converts cube coordinates to a tilemap point.
+pub fn cube_to_offset<P: Into<Point3>>(topology: GridTopology, cube: P) -> Point2 {
+    axial_to_offset(topology, cube_to_axial(cube))
+}
+
+/// Returns the number of steps between two axial coordinates.
+fn axial_distance(a: Point2, b: Point2) -> u32 {
+    let dq = a.x - b.x;
+    let dr = a.y - b.y;
+    ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as u32
+}
+
+/// Returns the number of steps between two tilemap points, where each step
+/// moves to a neighbour.
+pub fn distance<P: Into<Point2>>(topology: GridTopology, a: P, b: P) -> u32 {
+    axial_distance(offset_to_axial(topology, a), offset_to_axial(topology, b))
+}
+
+/// Returns the neighbour of a tilemap point in a direction from `0` to `5`,
+/// going clockwise. See the [module] for where the directions point.
+///
+/// Directions wrap around, so `6` is the same as `0`.
+///
+/// [module]: crate::hex
+pub fn neighbour<P: Into<Point2>>(topology: GridTopology, point: P, direction: usize) -> Point2 {
+    let (dq, dr) = DIRECTIONS
+        .get(direction % DIRECTIONS.len())
+        .copied()
+        .unwrap_or_default();
+    let axial = offset_to_axial(topology, point);
+    axial_to_offset(topology, Point2::new(axial.x + dq, axial.y + dr))
+}
+
+/// Returns the six neighbours of a tilemap point, clockwise from direction
+/// `0`.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::{hex, point::Point2, prelude::*};
+///
+/// let neighbours = hex::neighbours(GridTopology::HexY, (0, 0));
+/// assert_eq!(neighbours[0], Point2::new(1, 0));
+/// assert_eq!(neighbours[5], Point2::new(0, 1));
+/// ```
+pub fn neighbours<P: Into<Point2>>(topology: GridTopology, point: P) -> [Point2; 6] {
+    let point: Point2 = point.into();
+    [
+        neighbour(topology, point, 0),
+        neighbour(topology, point, 1),
+        neighbour(topology, point, 2),
+        neighbour(topology, point, 3),
+        neighbour(topology, point, 4),
+        neighbour(topology, point, 5),
+    ]
+}
+
+/// An iterator over the tilemap points exactly a number of steps away from a
+/// center, created by [`ring`].
+#[derive(Clone, Debug)]
+pub struct HexRing {
+    /// The topology of the points.
+    topology: GridTopology,
+    /// The axial coordinates of the next point.
+    axial: Point2,
+    /// The number of steps from the center.
+    radius: u32,
+    /// The side of the ring which is being walked, from `0` to `5`.
+    side: usize,
+    /// The steps taken along the side.
+    step: u32,
+}
+
+impl Iterator for HexRing {
+    type Item = Point2;
+
+    fn next(&mut self) -> Option<Point2> {
+        let (dq, dr) = *DIRECTIONS.get(self.side)?;
+        let point = axial_to_offset(self.topology, self.axial);
+        if self.radius == 0 {
+            self.side = DIRECTIONS.len();
+            return Some(point);
+        }
+        self.axial = Point2::new(self.axial.x + dq, self.axial.y + dr);
+        self.step += 1;
+        if self.step == self.radius {
+            self.step = 0;
+            self.side += 1;
+        }
+        Some(point)
+    }
+}
+
+/// Returns an iterator over the tilemap points exactly `radius` steps away
+/// from a center, walking clockwise around it.
+///
+/// A ring with a radius of `0` is only the center, otherwise it has
+/// `6 * radius` points.
+pub fn ring<P: Into<Point2>>(topology: GridTopology, center: P, radius: u32) -> HexRing {
+    let center = offset_to_axial(topology, center);
+    let (dq, dr) = DIRECTIONS[4];
+    let radius_coord = radius as Coord;
+    HexRing {
+        topology,
+        axial: Point2::new(center.x + dq * radius_coord, center.y + dr * radius_coord),
+        radius,
+        side: 0,
+        step: 0,
+    }
+}
+
+/// Returns an iterator over the tilemap points at most `radius` steps away
+/// from a center, starting at the center and then ring by ring outwards.
+pub fn spiral<P: Into<Point2>>(
+    topology: GridTopology,
+    center: P,
+    radius: u32,
+) -> impl Iterator<Item = Point2> {
+    let center: Point2 = center.into();
+    (0..=radius).flat_map(move |radius| ring(topology, center, radius))
+}
+
+/// Rounds fractional cube coordinates to the cube coordinates of the hex
+/// containing them.
+fn cube_round(x: f32, y: f32, z: f32) -> Point3 {
+    let (mut rx, mut ry, mut rz) = (x.round(), y.round(), z.round());
+    let (dx, dy, dz) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+    // The coordinate which was rounded the most is fixed up so that they
+    // still add up to zero.
+    if dx > dy && dx > dz {
+        rx = -ry - rz;
+    } else if dy > dz {
+        ry = -rx - rz;
+    } else {
+        rz = -rx - ry;
+    }
+    Point3::new(rx as Coord, ry as Coord, rz as Coord)
+}
+
+/// Returns the tilemap points of a line between two points, including both
+/// ends, where every point is a neighbour of the one before.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::{hex, point::Point2, prelude::*};
+///
+/// let line = hex::line(GridTopology::HexOddRows, (0, 0), (2, 4));
+/// assert_eq!(line.len(), 5);
+/// assert_eq!(line[4], Point2::new(2, 4));
+/// ```
+pub fn line<P: Into<Point2>>(topology: GridTopology, from: P, to: P) -> Vec<Point2> {
+    let from = offset_to_cube(topology, from);
+    let to = offset_to_cube(topology, to);
+    let steps = axial_distance(cube_to_axial(from), cube_to_axial(to));
+    if steps == 0 {
+        return vec![cube_to_offset(topology, from)];
+    }
+
+    // Nudging the line keeps points which are exactly between two hexes from
+    // flipping between them.
+    let lerp = |a: Coord, b: Coord, nudge: f32, t: f32| a as f32 + nudge + (b - a) as f32 * t;
+    (0..=steps)
+        .map(|step| {
+            let t = step as f32 / steps as f32;
+            let cube = cube_round(
+                lerp(from.x, to.x, 1e-6, t),
+                lerp(from.y, to.y, 1e-6, t),
+                lerp(from.z, to.z, -2e-6, t),
+            );
+            cube_to_offset(topology, cube)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{outline, tilemap::TilemapBuilder};
+
+    const HEX_TOPOLOGIES: [GridTopology; 6] = [
+        GridTopology::HexX,
+        GridTopology::HexY,
+        GridTopology::HexEvenRows,
+        GridTopology::HexOddRows,
+        GridTopology::HexEvenCols,
+        GridTopology::HexOddCols,
+    ];
+
+    #[test]
+    fn test_conversions() {
+        for topology in HEX_TOPOLOGIES.iter().copied() {
+            for y in -5..=5 {
+                for x in -5..=5 {
+                    let point = Point2::new(x, y);
+                    let cube = offset_to_cube(topology, point);
+                    assert_eq!(cube.x + cube.y + cube.z, 0);
+                    assert_eq!(cube_to_offset(topology, cube), point);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbours_share_edges() {
+        for topology in HEX_TOPOLOGIES.iter().copied() {
+            let tilemap = TilemapBuilder::new()
+                .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+                .texture_dimensions(32, 32)
+                .topology(topology)
+                .finish()
+                .unwrap();
+            for point in [(0, 0), (1, 0), (0, 1), (-1, -1), (3, -2)].iter().copied() {
+                let polygon = tilemap.tile_outline(point);
+                for neighbour in neighbours(topology, point).iter() {
+                    assert_eq!(distance(topology, point.into(), *neighbour), 1);
+                    assert!(
+                        outline::polygons_share_edge(&polygon, &tilemap.tile_outline(*neighbour)),
+                        "{:?} of {:?} on {:?}",
+                        neighbour,
+                        point,
+                        topology
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rings_and_lines() {
+        for topology in HEX_TOPOLOGIES.iter().copied() {
+            let center = Point2::new(1, -2);
+            for radius in 0..4 {
+                let points: Vec<Point2> = ring(topology, center, radius).collect();
+                assert_eq!(points.len(), (6 * radius).max(1) as usize);
+                assert!(points
+                    .iter()
+                    .all(|point| distance(topology, center, *point) == radius));
+            }
+            assert_eq!(spiral(topology, center, 3).count(), 37);
+
+            let end = Point2::new(-4, 3);
+            let points = line(topology, center, end);
+            assert_eq!(points.len() as u32, distance(topology, center, end) + 1);
+            assert_eq!(points.last(), Some(&end));
+            for pair in points.windows(2) {
+                assert_eq!(distance(topology, pair[0], pair[1]), 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Undo and redo history of tile edits.
+//!
+//! Every tile set or cleared while the history is enabled is tracked with its
+//! tile before and after the edit. The tiles of one bulk edit, or of a group
+//! of edits, make up a single step which is undone or redone as a whole.
+
+use crate::{chunk::RawTile, lib::*};
+
+/// A tile which was set or cleared by an edit.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct TileChange {
+    /// The global point of the tile.
+    pub point: Point3,
+    /// The sprite order of the tile.
+    pub sprite_order: usize,
+    /// The tile before the edit, or `None` if there was no tile.
+    pub before: Option<RawTile>,
+    /// The tile after the edit, or `None` if it was cleared.
+    pub after: Option<RawTile>,
+}
+
+/// The undo and redo stacks of a tilemap.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct TilemapHistory {
+    /// The maximum number of steps which can be undone.
+    limit: usize,
+    /// The steps which can be undone, the most recent last.
+    undo: Vec<Vec<TileChange>>,
+    /// The steps which can be redone, the most recently undone last.
+    redo: Vec<Vec<TileChange>>,
+    /// The changes of the step which is being made.
+    step: Vec<TileChange>,
+    /// The number of groups which are open.
+    groups: usize,
+}
+
+impl TilemapHistory {
+    /// Constructs an empty history keeping at most `limit` steps.
+    pub(crate) fn new(limit: usize) -> TilemapHistory {
+        TilemapHistory {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// Adds a change to the step which is being made, unless the tile stayed
+    /// the same.
+    pub(crate) fn push(&mut self, change: TileChange) {
+        if change.before != change.after {
+            self.step.push(change);
+        }
+    }
+
+    /// Opens a group, which keeps the step open until it is closed.
+    pub(crate) fn begin_group(&mut self) {
+        self.groups += 1;
+    }
+
+    /// Closes a group, finishing the step if it was the last open group.
+    pub(crate) fn end_group(&mut self) {
+        self.groups = self.groups.saturating_sub(1);
+        self.finish_step();
+    }
+
+    /// Closes every open group and finishes the step.
+    pub(crate) fn end_groups(&mut self) {
+        self.groups = 0;
+        self.finish_step();
+    }
+
+    /// Pushes the step which is being made onto the undo stack and clears the
+    /// redo stack, unless a group is open or the step has no changes.
+    pub(crate) fn finish_step(&mut self) {
+        if self.groups > 0 || self.step.is_empty() {
+            return;
+        }
+        let step = mem::take(&mut self.step);
+        self.push_undo(step);
+        self.redo.clear();
+    }
+
+    /// Pushes a step onto the undo stack, dropping the oldest steps over the
+    /// limit.
+    pub(crate) fn push_undo(&mut self, step: Vec<TileChange>) {
+        self.undo.push(step);
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+
+    /// Pushes a step onto the redo stack.
+    pub(crate) fn push_redo(&mut self, step: Vec<TileChange>) {
+        self.redo.push(step);
+    }
+
+    /// Takes the most recent step which can be undone.
+    pub(crate) fn pop_undo(&mut self) -> Option<Vec<TileChange>> {
+        self.undo.pop()
+    }
+
+    /// Takes the most recently undone step which can be redone.
+    pub(crate) fn pop_redo(&mut self) -> Option<Vec<TileChange>> {
+        self.redo.pop()
+    }
+
+    /// Returns `true` if there is a step which can be undone.
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || (self.groups == 0 && !self.step.is_empty())
+    }
+
+    /// Returns `true` if there is a step which can be redone.
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+/// Returns the tile every changed point and sprite order of a step ends up
+/// with once the step is undone, or redone.
+pub(crate) fn step_tiles(step: &[TileChange], undo: bool) -> Vec<(Point3, usize, Option<RawTile>)> {
+    let mut tiles: HashMap<(Point3, usize), Option<RawTile>> = HashMap::default();
+    if undo {
+        // The earliest change of a tile holds the tile it had before the step.
+        for change in step.iter().rev() {
+            tiles.insert((change.point, change.sprite_order), change.before);
+        }
+    } else {
+        for change in step.iter() {
+            tiles.insert((change.point, change.sprite_order), change.after);
+        }
+    }
+    tiles
+        .into_iter()
+        .map(|((point, sprite_order), tile)| (point, sprite_order, tile))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(x: Coord, before: Option<usize>, after: Option<usize>) -> TileChange {
+        let tile = |index| RawTile {
+            index,
+            ..Default::default()
+        };
+        TileChange {
+            point: Point3::new(x, 0, 0),
+            sprite_order: 0,
+            before: before.map(tile),
+            after: after.map(tile),
+        }
+    }
+
+    #[test]
+    fn test_history_steps() {
+        let mut history = TilemapHistory::new(2);
+        assert!(!history.can_undo());
+
+        history.push(change(0, None, Some(1)));
+        history.push(change(1, Some(2), Some(2)));
+        history.finish_step();
+        assert_eq!(history.undo.len(), 1);
+        assert_eq!(history.undo[0].len(), 1);
+
+        history.begin_group();
+        history.push(change(0, Some(1), Some(2)));
+        history.finish_step();
+        history.push(change(0, Some(2), None));
+        assert_eq!(history.undo.len(), 1);
+        history.end_group();
+        assert_eq!(history.undo.len(), 2);
+
+        let step = history.pop_undo().unwrap();
+        assert_eq!(step_tiles(&step, true)[0].2.map(|tile| tile.index), Some(1));
+        assert_eq!(step_tiles(&step, false)[0].2, None);
+        history.push_redo(step);
+        assert!(history.can_redo());
+
+        history.push(change(5, None, Some(5)));
+        history.finish_step();
+        assert!(!history.can_redo());
+
+        history.push(change(6, None, Some(6)));
+        history.finish_step();
+        assert_eq!(history.undo.len(), 2);
+        assert_eq!(history.undo[0][0].point, Point3::new(5, 0, 0));
+    }
+}
@@ -0,0 +1,140 @@
+//! Text labels anchored to tiles.
+//!
+//! With the `text` feature a label can be put on a tile with
+//! [`Tilemap::label_tile`], such as the name of a city or a debug overlay.
+//! The label is rendered as a text entity centered on the tile, which is a
+//! child of the tilemap. It is spawned with the chunk of its tile and
+//! despawned with it, so labels of far away chunks cost nothing.
+//!
+//! [`Tilemap::label_tile`]: crate::tilemap::Tilemap::label_tile
+//!
+//! # Naming a city
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_text::TextStyle;
+//! use bevy_tilemap::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//!
+//! tilemap.label_tile((4, 2), "Ironforge", TextStyle::default());
+//! assert_eq!(tilemap.tile_label((4, 2)), Some("Ironforge"));
+//!
+//! assert!(tilemap.remove_tile_label((4, 2)));
+//! assert_eq!(tilemap.tile_label((4, 2)), None);
+//! ```
+
+use crate::{lib::*, Tilemap};
+
+/// The z of the labels, which is above the tiles of the chunks.
+const LABEL_Z: f32 = 900.0;
+
+/// A text label on a tile.
+#[derive(Clone, Debug)]
+pub(crate) struct TileLabel {
+    /// The text of the label.
+    pub(crate) text: String,
+    /// The style of the text.
+    pub(crate) style: TextStyle,
+    /// The entity of the label if it is spawned.
+    pub(crate) entity: Option<Entity>,
+    /// If the text or style changed since the entity was spawned.
+    pub(crate) changed: bool,
+}
+
+impl TileLabel {
+    /// Constructs a label which is not spawned yet.
+    pub(crate) fn new(text: String, style: TextStyle) -> TileLabel {
+        TileLabel {
+            text,
+            style,
+            entity: None,
+            changed: false,
+        }
+    }
+
+    /// Returns the text component of the label.
+    fn to_text(&self) -> Text {
+        Text::with_section(
+            self.text.clone(),
+            self.style.clone(),
+            TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        )
+    }
+}
+
+/// Spawns the labels of the spawned chunks, updates the changed ones and
+/// despawns the labels which were removed or whose chunk was despawned.
+pub(crate) fn tile_labels(
+    mut commands: Commands,
+    mut tilemap_query: Query<(Entity, &mut Tilemap)>,
+) {
+    for (tilemap_entity, mut tilemap) in tilemap_query.iter_mut() {
+        if tilemap.has_removed_label_entities() {
+            for entity in tilemap.take_removed_label_entities() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        let mut spawned = Vec::new();
+        let mut updated = Vec::new();
+        let mut despawned = Vec::new();
+        for (point, label) in tilemap.tile_labels() {
+            let chunk_point: Point2 = tilemap.point_to_chunk_point(*point).into();
+            let chunk_spawned = tilemap
+                .spawned_chunks()
+                .contains(&(chunk_point.x, chunk_point.y));
+            match (label.entity, chunk_spawned) {
+                (None, true) => spawned.push(*point),
+                (Some(entity), true) if label.changed => {
+                    commands.entity(entity).insert(label.to_text());
+                    updated.push(*point);
+                }
+                (Some(entity), false) => {
+                    commands.entity(entity).despawn_recursive();
+                    despawned.push(*point);
+                }
+                _ => {}
+            }
+        }
+        if spawned.is_empty() && updated.is_empty() && despawned.is_empty() {
+            continue;
+        }
+        let mut entities = Vec::with_capacity(spawned.len());
+        for point in spawned {
+            let center = tilemap.tile_center(point);
+            let label = match tilemap.tile_labels_mut().get_mut(&point) {
+                Some(label) => label,
+                None => continue,
+            };
+            let entity = commands
+                .spawn_bundle(Text2dBundle {
+                    text: label.to_text(),
+                    transform: Transform::from_translation(Vec3::new(center.x, center.y, LABEL_Z)),
+                    ..Default::default()
+                })
+                .id();
+            label.entity = Some(entity);
+            label.changed = false;
+            entities.push(entity);
+        }
+        for point in updated {
+            if let Some(label) = tilemap.tile_labels_mut().get_mut(&point) {
+                label.changed = false;
+            }
+        }
+        for point in despawned {
+            if let Some(label) = tilemap.tile_labels_mut().get_mut(&point) {
+                label.entity = None;
+            }
+        }
+        if !entities.is_empty() {
+            commands.entity(tilemap_entity).push_children(&entities);
+        }
+    }
+}
@@ -52,6 +52,86 @@
 //! ```
 //!
 //! See the library `bevy_tilemap_types` for more information.
+//!
+//! # Tileset feature
+//!
+//! Tilesets can be loaded as assets from a RON manifest of tile names and
+//! texture paths, which builds their texture atlas. See the `tileset` module
+//! for more information.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.4", features = ["tileset"] }
+//! ```
+//!
+//! # Scene feature
+//!
+//! Tilemaps can be saved as `.tilemap` scene assets, together with the path of
+//! their tileset, and placed in Bevy scenes. This requires the tileset feature.
+//! See the `scene` module for more information.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.4", features = ["scene"] }
+//! ```
+//!
+//! # TMX feature
+//!
+//! Tilemaps can be exported as Tiled TMX maps to be edited in Tiled, with the
+//! tiles named by a tile registry. This requires the tileset feature. See the
+//! `tmx` module for more information.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.4", features = ["tmx"] }
+//! ```
+//!
+//! # Failure injection feature
+//!
+//! For testing, failures such as a missing chunk mesh, a texture atlas which is
+//! not loaded yet or an I/O error while streaming can be injected into a
+//! tilemap. See the `failure` module for more information.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! bevy_tilemap = { version = "0.4", features = ["failure_injection"] }
+//! ```
+//!
+//! # Large coordinates feature
+//!
+//! Points use `i32` coordinates by default, which limits a tilemap to about
+//! 2^31 tiles in each direction. For huge procedurally generated worlds the
+//! coordinates can be made `i64` everywhere, including chunk points, events and
+//! serialization, through the `Coord` type.
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_tilemap = { version = "0.4", features = ["i64_coordinates"] }
+//! ```
+//!
+//! # Thread safety
+//!
+//! The public types of the library are `Send` and `Sync`, which is checked
+//! for the main ones when the library is compiled. A [`Tilemap`] can therefore
+//! be cloned out of or moved into a background thread or task, such as to
+//! generate a world or to autosave it with [`Tilemap::save_streaming`], and
+//! read from many threads at once. The same goes for the [`TilemapBuilder`], a
+//! [`TileBrush`] copied as a clipboard, a [`TilemapRecording`], chunk sync
+//! requests and responses, [`StitchedTilemaps`] and the errors.
+//!
+//! To keep it that way, the extension points require it in turn. Generators,
+//! spawn policies and the readers given to [`Tilemap::load_streaming`] must
+//! be `Send` and `Sync` themselves. Handles to texture atlases and other
+//! assets can cross threads, though the assets themselves are only accessible
+//! through the Bevy world.
+//!
+//! [`Tilemap`]: crate::tilemap::Tilemap
+//! [`Tilemap::save_streaming`]: crate::tilemap::Tilemap::save_streaming
+//! [`Tilemap::load_streaming`]: crate::tilemap::Tilemap::load_streaming
+//! [`TilemapBuilder`]: crate::tilemap::TilemapBuilder
+//! [`TileBrush`]: crate::brush::TileBrush
+//! [`TilemapRecording`]: crate::record::TilemapRecording
+//! [`StitchedTilemaps`]: crate::stitch::StitchedTilemaps
 
 #![doc(html_root_url = "https://docs.rs/bevy_tilemap/0.4.0")]
 // This was broken even further and no longer will work at all with the previous
@@ -67,7 +147,12 @@
 #![warn(missing_docs)]
 #![deny(dead_code, unused_imports)]
 // clippy
-#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+// The `Bundle` derive of Bevy forgets its components after moving them out.
+#![allow(
+    clippy::forget_non_drop,
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
 #![warn(
     clippy::cast_lossless,
     clippy::decimal_literal_representation,
@@ -80,6 +165,11 @@
     clippy::print_stdout,
     clippy::unwrap_in_result
 )]
+// Casts to `Coord` are only lossless with the `i64_coordinates` feature, where
+// `Coord::from` would not build without it.
+#![cfg_attr(feature = "i64_coordinates", allow(clippy::cast_lossless))]
+// Tests index freely, a panic there is a failed test.
+#![cfg_attr(test, allow(clippy::indexing_slicing))]
 
 #[cfg(feature = "types")]
 pub extern crate bevy_tilemap_types;
@@ -91,15 +181,49 @@ pub use bevy_tilemap_types::dimension;
 #[doc(inline)]
 pub use bevy_tilemap_types::point;
 
+#[no_implicit_prelude]
+pub mod brush;
 #[no_implicit_prelude]
 pub mod chunk;
 #[no_implicit_prelude]
+pub mod collision;
+#[no_implicit_prelude]
 pub mod default_plugin;
 #[no_implicit_prelude]
+pub mod diagnostics;
+#[no_implicit_prelude]
 pub mod entity;
+#[cfg(feature = "failure_injection")]
+#[no_implicit_prelude]
+pub mod failure;
+#[no_implicit_prelude]
+pub mod fog;
+#[no_implicit_prelude]
+pub mod heatmap;
+#[no_implicit_prelude]
+pub mod hex;
+#[no_implicit_prelude]
+mod history;
+#[cfg(feature = "text")]
+#[no_implicit_prelude]
+pub mod label;
+#[no_implicit_prelude]
+pub mod light;
+#[no_implicit_prelude]
+mod outline;
+#[no_implicit_prelude]
+pub mod parallax;
+#[no_implicit_prelude]
+pub mod pathfinding;
+#[no_implicit_prelude]
+pub mod picking;
+#[no_implicit_prelude]
+mod preload;
 #[no_implicit_prelude]
 pub mod prelude;
 #[no_implicit_prelude]
+mod raster;
+#[no_implicit_prelude]
 pub mod stage {
     //! The stages for the tilemap in the bevy app.
 
@@ -110,13 +234,55 @@ pub mod stage {
 #[no_implicit_prelude]
 pub mod event;
 #[no_implicit_prelude]
+pub mod generator;
+#[no_implicit_prelude]
+pub mod property;
+#[no_implicit_prelude]
+pub mod record;
+#[cfg(feature = "scene")]
+#[no_implicit_prelude]
+pub mod scene;
+#[no_implicit_prelude]
+pub mod shade;
+#[no_implicit_prelude]
+pub mod source;
+#[no_implicit_prelude]
+pub mod spawn_policy;
+#[no_implicit_prelude]
+pub mod square;
+#[no_implicit_prelude]
+pub mod stitch;
+#[no_implicit_prelude]
+pub mod stream;
+#[no_implicit_prelude]
+pub mod sync;
+#[no_implicit_prelude]
 mod system;
 #[no_implicit_prelude]
 pub mod tile;
 #[no_implicit_prelude]
 pub mod tilemap;
+#[no_implicit_prelude]
+pub mod tilemap_ops;
+#[cfg(feature = "tileset")]
+#[no_implicit_prelude]
+pub mod tileset;
+#[cfg(feature = "tmx")]
+#[no_implicit_prelude]
+pub mod tmx;
+#[no_implicit_prelude]
+pub mod variant;
+#[cfg(feature = "worldgen")]
+#[no_implicit_prelude]
+pub mod worldgen;
 
-use crate::{event::TilemapChunkEvent, lib::*};
+use crate::{
+    event::{
+        TilemapChunkEvent, TilemapChunkUnloadEvent, TilemapCollidersEvent, TilemapSpriteIndexEvent,
+        TilemapStreamEvent,
+    },
+    lib::*,
+};
 pub use crate::{
     tile::Tile,
     tilemap::{Tilemap, TilemapLayer},
@@ -129,20 +295,49 @@ pub struct TilemapPlugin;
 /// The tilemap system stages.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum TilemapSystem {
+    /// The streaming stage.
+    Streaming,
     /// The events stage.
     Events,
     /// The auto spawn stage.
     AutoSpawn,
+    /// The level of detail stage.
+    Lod,
+    /// The culling stage.
+    Culling,
 }
 
 impl Plugin for TilemapPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<Tilemap>()
+            .add_event::<TilemapStreamEvent>()
+            .add_event::<TilemapSpriteIndexEvent>()
+            .add_event::<TilemapChunkUnloadEvent>()
+            .add_event::<TilemapCollidersEvent>()
             .add_stage_before(
                 CoreStage::PostUpdate,
                 stage::TILEMAP,
                 SystemStage::parallel(),
             )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_streaming
+                    .system()
+                    .label(TilemapSystem::Streaming)
+                    .before(TilemapSystem::Events),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_prewarm
+                    .system()
+                    .before(TilemapSystem::Events),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_layer_zoom
+                    .system()
+                    .before(TilemapSystem::Events),
+            )
             .add_system_to_stage(
                 stage::TILEMAP,
                 crate::system::tilemap_events
@@ -155,12 +350,31 @@ impl Plugin for TilemapPlugin {
                     .system()
                     .after(TilemapSystem::Events),
             )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::chunk::system::chunk_time
+                    .system()
+                    .after(TilemapSystem::Events),
+            )
             .add_system_to_stage(
                 stage::TILEMAP,
                 crate::chunk::system::chunk_auto_radius
                     .system()
                     .after(TilemapSystem::Events),
             )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::parallax::tilemap_parallax
+                    .system()
+                    .before(TilemapSystem::AutoSpawn),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::chunk::system::chunk_preload
+                    .system()
+                    .after(TilemapSystem::Events)
+                    .before(TilemapSystem::AutoSpawn),
+            )
             .add_system_to_stage(
                 stage::TILEMAP,
                 crate::chunk::system::chunk_auto_spawn
@@ -170,37 +384,134 @@ impl Plugin for TilemapPlugin {
             )
             .add_system_to_stage(
                 stage::TILEMAP,
-                crate::system::tilemap_visibility_change.system(),
+                crate::chunk::system::chunk_generate
+                    .system()
+                    .after(TilemapSystem::AutoSpawn)
+                    .before(TilemapSystem::Lod),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::chunk::system::chunk_lod
+                    .system()
+                    .label(TilemapSystem::Lod)
+                    .after(TilemapSystem::AutoSpawn),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_visibility_change
+                    .system()
+                    .before(TilemapSystem::Culling),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::chunk::system::chunk_culling
+                    .system()
+                    .label(TilemapSystem::Culling)
+                    .after(TilemapSystem::Lod),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_sprite_validation.system(),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_unload_events
+                    .system()
+                    .after(TilemapSystem::AutoSpawn),
+            )
+            .add_system_to_stage(
+                stage::TILEMAP,
+                crate::system::tilemap_colliders
+                    .system()
+                    .after(TilemapSystem::Events),
             );
 
+        #[cfg(feature = "text")]
+        app.add_system_to_stage(
+            stage::TILEMAP,
+            crate::label::tile_labels
+                .system()
+                .after(TilemapSystem::AutoSpawn),
+        );
+
+        #[cfg(feature = "tileset")]
+        app.add_asset::<tileset::Tileset>()
+            .init_asset_loader::<tileset::TilesetLoader>()
+            .add_event::<event::TilesetReadyEvent>()
+            .add_system_to_stage(stage::TILEMAP, tileset::tileset_build.system());
+
+        #[cfg(feature = "scene")]
+        app.add_asset::<scene::TilemapScene>()
+            .init_asset_loader::<scene::TilemapSceneLoader>()
+            .register_type::<Handle<scene::TilemapScene>>()
+            .add_system_to_stage(stage::TILEMAP, scene::tilemap_scene_spawn.system());
+
         let world = app.world_mut().cell();
-        // let mut render_graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        let mut render_graph = world.get_resource_mut::<RenderGraph>().unwrap();
         let mut pipelines = world
             .get_resource_mut::<Assets<PipelineDescriptor>>()
             .unwrap();
         let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
-        crate::chunk::render::add_tilemap_graph(&mut pipelines, &mut shaders);
+        let mut textures = world.get_resource_mut::<Assets<Texture>>().unwrap();
+        crate::chunk::render::add_tilemap_graph(
+            &mut render_graph,
+            &mut pipelines,
+            &mut shaders,
+            &mut textures,
+        );
     }
 }
 
+// Fails to compile if a type which is documented to be safe to send and share
+// between threads is not.
+const _: fn() = || {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<Tilemap>();
+    send_sync::<tilemap::TilemapBuilder>();
+    send_sync::<tilemap::TilemapError>();
+    send_sync::<Tile<Point3>>();
+    send_sync::<chunk::RawTile>();
+    send_sync::<brush::TileBrush>();
+    send_sync::<record::TilemapRecording>();
+    send_sync::<sync::ChunkRequest>();
+    send_sync::<sync::ChunkResponse>();
+    send_sync::<stitch::StitchedTilemaps<'static>>();
+    send_sync::<event::UnloadedChunk>();
+    send_sync::<Box<dyn generator::ChunkGenerator>>();
+    send_sync::<Box<dyn spawn_policy::ChunkSpawnPolicy>>();
+    #[cfg(feature = "tileset")]
+    send_sync::<tileset::Tileset>();
+    #[cfg(feature = "tileset")]
+    send_sync::<tileset::TileRegistry>();
+    #[cfg(feature = "worldgen")]
+    send_sync::<worldgen::BiomeTable>();
+};
+
 /// A custom prelude around everything that we only need to use.
 #[no_implicit_prelude]
 mod lib {
+    #[cfg(feature = "tileset")]
+    extern crate anyhow;
     extern crate bevy_app;
     extern crate bevy_asset;
-    #[cfg(test)]
     extern crate bevy_core;
+    extern crate bevy_diagnostic;
     extern crate bevy_ecs;
     extern crate bevy_log;
     extern crate bevy_math;
     extern crate bevy_reflect;
     extern crate bevy_render;
     extern crate bevy_sprite;
+    extern crate bevy_tasks;
+    #[cfg(feature = "text")]
+    extern crate bevy_text;
     extern crate bevy_tilemap_types;
     extern crate bevy_transform;
     extern crate bevy_utils;
     extern crate bevy_window;
     pub extern crate bitflags;
+    #[cfg(any(feature = "tileset", test))]
+    extern crate ron;
     #[cfg(feature = "serde")]
     extern crate serde;
     extern crate std;
@@ -208,43 +519,59 @@ mod lib {
     #[cfg(test)]
     pub(crate) use bevy_app::ScheduleRunnerPlugin;
     pub(crate) use bevy_app::{
-        AppBuilder, CoreStage, Events, Plugin, PluginGroup, PluginGroupBuilder,
+        AppBuilder, CoreStage, EventWriter, Events, Plugin, PluginGroup, PluginGroupBuilder,
     };
     pub(crate) use bevy_asset::{AddAsset, Assets, Handle, HandleUntyped};
     #[cfg(test)]
     pub(crate) use bevy_asset::{AssetPlugin, HandleId};
     #[cfg(test)]
     pub(crate) use bevy_core::CorePlugin;
+    pub(crate) use bevy_core::Time;
+    #[cfg(test)]
+    pub(crate) use bevy_diagnostic::DiagnosticsPlugin;
+    pub(crate) use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
     #[cfg(test)]
     pub(crate) use bevy_ecs::system::CommandQueue;
     pub(crate) use bevy_ecs::{
         bundle::Bundle,
         entity::Entity,
-        query::Changed,
+        query::{ChangeTrackers, Changed, Without},
         schedule::{ParallelSystemDescriptorCoercion, SystemLabel, SystemStage},
-        system::{Commands, IntoSystem, Query, Res, ResMut},
+        system::{Commands, IntoSystem, Local, Query, Res, ResMut},
     };
     pub(crate) use bevy_log::{error, info, warn};
+    #[cfg(test)]
+    pub(crate) use bevy_math::Quat;
     pub(crate) use bevy_math::{Vec2, Vec3};
     pub(crate) use bevy_reflect::{TypeUuid, Uuid};
     pub(crate) use bevy_render::{
-        camera::Camera,
+        camera::{Camera, OrthographicProjection},
         color::Color,
         draw::{Draw, Visible},
-        mesh::{Indices, Mesh},
+        mesh::{Indices, Mesh, VertexAttributeValues},
         pipeline::{
             BlendFactor, BlendOperation, BlendState, ColorTargetState, ColorWrite, CompareFunction,
             DepthBiasState, DepthStencilState, PipelineDescriptor, PrimitiveTopology,
             RenderPipeline, RenderPipelines, StencilFaceState, StencilState,
         },
-        render_graph::base::MainPass,
+        render_graph::{
+            base::{node, MainPass},
+            RenderGraph, RenderResourcesNode,
+        },
+        renderer::{RenderResource, RenderResourceIterator, RenderResources},
         shader::{Shader, ShaderStage, ShaderStages},
-        texture::TextureFormat,
+        texture::{Extent3d, FilterMode, Texture, TextureDimension, TextureFormat},
+    };
+    pub(crate) use bevy_sprite::{entity::SpriteBundle, ColorMaterial, Rect, Sprite, TextureAtlas};
+    pub(crate) use bevy_tasks::{ComputeTaskPool, TaskPool};
+    #[cfg(feature = "text")]
+    pub(crate) use bevy_text::{
+        prelude::{HorizontalAlign, VerticalAlign},
+        Text, Text2dBundle, TextAlignment, TextStyle,
     };
-    pub(crate) use bevy_sprite::TextureAtlas;
     pub(crate) use bevy_tilemap_types::{
         dimension::{Dimension2, Dimension3, DimensionError},
-        point::{Point2, Point3},
+        point::{Coord, Point2, Point3},
     };
     #[cfg(test)]
     pub(crate) use bevy_transform::components::Children;
@@ -252,31 +579,62 @@ mod lib {
         components::{GlobalTransform, Parent, Transform},
         hierarchy::{BuildChildren, DespawnRecursiveExt},
     };
-    pub(crate) use bevy_utils::{HashMap, HashSet};
-    pub(crate) use bevy_window::WindowResized;
+    pub(crate) use bevy_utils::{Duration, HashMap, HashSet, Instant};
+    pub(crate) use bevy_window::{WindowId, WindowResized};
 
     pub(crate) use crate::bitflags::*;
 
     #[cfg(feature = "serde")]
     pub(crate) use serde::{Deserialize, Serialize};
 
+    #[cfg(feature = "tileset")]
+    pub(crate) use anyhow::Error as AnyhowError;
+    #[cfg(feature = "tileset")]
+    pub(crate) use bevy_app::EventReader;
+    #[cfg(feature = "tileset")]
+    pub(crate) use bevy_asset::{
+        AssetEvent, AssetLoader, AssetPath, AssetServer, LoadContext, LoadState, LoadedAsset,
+    };
+    #[cfg(feature = "tileset")]
+    pub(crate) use bevy_sprite::TextureAtlasBuilder;
+    #[cfg(feature = "tileset")]
+    pub(crate) use bevy_utils::BoxedFuture;
+    #[cfg(any(feature = "tileset", all(test, feature = "serde")))]
+    pub(crate) use ron::de::from_bytes as from_ron_bytes;
+    #[cfg(feature = "scene")]
+    pub(crate) use ron::ser::{to_string_pretty as to_ron_string_pretty, PrettyConfig};
+
+    #[cfg(test)]
+    pub(crate) use std::io::Cursor as IoCursor;
+    #[cfg(feature = "failure_injection")]
+    pub(crate) use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
     pub(crate) use std::{
         boxed::Box,
         clone::Clone,
-        cmp::Ord,
+        cmp::{Ord, PartialEq, Reverse},
+        collections::BinaryHeap,
         convert::{AsMut, AsRef, From, Into},
         default::Default,
         error::Error,
         fmt::{Debug, Display, Formatter, Result as FmtResult},
+        io::{Error as IoError, ErrorKind as IoErrorKind, Read, Write},
         iter::{Extend, IntoIterator, Iterator},
-        ops::FnMut,
+        marker::{Copy, Send, Sized, Sync},
+        mem,
+        ops::{Fn, FnMut, FnOnce},
         option::Option::{self, *},
         result::Result::{self, *},
+        string::{String, ToString},
+        sync::Arc,
         vec::Vec,
     };
 
     // Macros
-    pub(crate) use std::{vec, write};
+    #[cfg(test)]
+    pub(crate) use std::format;
+    #[cfg(feature = "tmx")]
+    pub(crate) use std::writeln;
+    pub(crate) use std::{matches, vec, write};
 
     #[cfg(debug_assertions)]
     #[allow(unused_imports)]
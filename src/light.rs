@@ -0,0 +1,238 @@
+//! Point lights which tint the tiles of a tilemap.
+//!
+//! With [`TilemapBuilder::lighting`] every tile of a spawned chunk is tinted
+//! by the light falling on it: the ambient light plus the light of every
+//! [`Light`] within reach, fading out towards the edge of its radius. The
+//! tints are applied when the meshes of the chunks are built and updated,
+//! only for the tiles whose light changed, so the colors of the tiles
+//! themselves are left untouched.
+//!
+//! Lights are added with [`Tilemap::add_light`] and can be moved or removed
+//! again. If [`Lighting::occlusion`] is set, the tiles of that sprite layer
+//! block light from reaching the tiles behind them, while still being lit
+//! themselves. The light is only updated automatically when lights change, so
+//! [`Tilemap::refresh_lighting`] needs to be called after changing the tiles
+//! of the occluding layer.
+//!
+//! [`TilemapBuilder::lighting`]: crate::tilemap::TilemapBuilder::lighting
+//! [`Tilemap::add_light`]: crate::tilemap::Tilemap::add_light
+//! [`Tilemap::refresh_lighting`]: crate::tilemap::Tilemap::refresh_lighting
+//!
+//! # Lighting a torch
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_render::prelude::*;
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     light::{Light, Lighting},
+//!     prelude::*,
+//! };
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .lighting(Lighting { ambient: Color::rgb(0.2, 0.2, 0.2), occlusion: None })
+//!     .finish()
+//!     .unwrap();
+//!
+//! let torch = Light { color: Color::rgb(1.0, 0.8, 0.0), radius: 4, intensity: 1.0 };
+//! tilemap.add_light((0, 0), torch).unwrap();
+//!
+//! assert_eq!(tilemap.light_at((10, 0)), Some(Color::rgb(0.2, 0.2, 0.2)));
+//! assert!(tilemap.light_at((1, 0)).unwrap().r() > tilemap.light_at((3, 0)).unwrap().r());
+//! ```
+
+use crate::lib::*;
+
+/// The lighting of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Lighting {
+    /// The light every tile gets, even without a light in reach.
+    pub ambient: Color,
+    /// The sprite order of the layer whose tiles block light, if any.
+    pub occlusion: Option<usize>,
+}
+
+impl Default for Lighting {
+    fn default() -> Lighting {
+        Lighting {
+            ambient: Color::WHITE,
+            occlusion: None,
+        }
+    }
+}
+
+/// A light at a point of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Light {
+    /// The color of the light.
+    pub color: Color,
+    /// The distance in tiles the light reaches.
+    pub radius: u32,
+    /// The strength of the light at its point, fading out towards its radius.
+    pub intensity: f32,
+}
+
+impl Default for Light {
+    fn default() -> Light {
+        Light {
+            color: Color::WHITE,
+            radius: 4,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// The id of a light added to a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LightId(u64);
+
+/// The lighting of a tilemap with its lights.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct LightMap {
+    /// The configuration of the lighting.
+    pub(crate) settings: Lighting,
+    /// The lights by id, with their point.
+    lights: HashMap<LightId, (Point2, Light)>,
+    /// The id of the next light.
+    next_id: u64,
+}
+
+impl LightMap {
+    /// Constructs a new light map without lights.
+    pub(crate) fn new(settings: Lighting) -> LightMap {
+        LightMap {
+            settings,
+            lights: HashMap::default(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds a light at a point, returning its id.
+    pub(crate) fn add(&mut self, point: Point2, light: Light) -> LightId {
+        let id = LightId(self.next_id);
+        self.next_id += 1;
+        self.lights.insert(id, (point, light));
+        id
+    }
+
+    /// Returns a light and its point.
+    pub(crate) fn get(&self, id: LightId) -> Option<(Point2, Light)> {
+        self.lights.get(&id).copied()
+    }
+
+    /// Moves a light to a point, returning its previous point.
+    pub(crate) fn set_point(&mut self, id: LightId, point: Point2) -> Option<Point2> {
+        let (light_point, _light) = self.lights.get_mut(&id)?;
+        Some(mem::replace(light_point, point))
+    }
+
+    /// Removes a light, returning it and its point.
+    pub(crate) fn remove(&mut self, id: LightId) -> Option<(Point2, Light)> {
+        self.lights.remove(&id)
+    }
+
+    /// Returns the lights which may reach into a rectangle of points.
+    pub(crate) fn lights_in(&self, min: Point2, max: Point2) -> Vec<(Point2, Light)> {
+        self.lights
+            .values()
+            .filter(|(point, light)| {
+                let radius = light.radius as Coord;
+                point.x + radius >= min.x
+                    && point.x - radius <= max.x
+                    && point.y + radius >= min.y
+                    && point.y - radius <= max.y
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns the ambient light as a tint.
+    pub(crate) fn ambient(&self) -> [f32; 3] {
+        let ambient = self.settings.ambient;
+        [ambient.r(), ambient.g(), ambient.b()]
+    }
+}
+
+/// Returns the light falling on a point from the ambient light and some
+/// lights.
+///
+/// The distance decides how far the point is from a light, and a light only
+/// reaches the point if it is in sight of it.
+pub(crate) fn light_at<D, S>(
+    point: Point2,
+    ambient: [f32; 3],
+    lights: &[(Point2, Light)],
+    mut distance: D,
+    mut in_sight: S,
+) -> [f32; 3]
+where
+    D: FnMut(Point2, Point2) -> f32,
+    S: FnMut(Point2, Point2) -> bool,
+{
+    let mut tint = ambient;
+    for (light_point, light) in lights.iter() {
+        let distance = distance(*light_point, point);
+        let radius = light.radius as f32;
+        if distance > radius || !in_sight(*light_point, point) {
+            continue;
+        }
+        let strength = light.intensity * (1.0 - distance / (radius + 1.0));
+        tint[0] += light.color.r() * strength;
+        tint[1] += light.color.g() * strength;
+        tint[2] += light.color.b() * strength;
+    }
+    [tint[0].min(1.0), tint[1].min(1.0), tint[2].min(1.0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_at() {
+        let light = Light {
+            color: Color::rgb(1.0, 0.5, 0.0),
+            radius: 3,
+            intensity: 1.0,
+        };
+        let lights = vec![(Point2::new(0, 0), light)];
+        let ambient = [0.1, 0.1, 0.1];
+        let distance = |a: Point2, b: Point2| ((a.x - b.x).abs() + (a.y - b.y).abs()) as f32;
+
+        let center = light_at(Point2::new(0, 0), ambient, &lights, distance, |_, _| true);
+        assert_eq!(center[0], 1.0);
+        assert!((center[1] - 0.6).abs() < 1e-6);
+        let edge = light_at(Point2::new(3, 0), ambient, &lights, distance, |_, _| true);
+        assert!((edge[0] - 0.35).abs() < 1e-6);
+        let outside = light_at(Point2::new(4, 0), ambient, &lights, distance, |_, _| true);
+        assert_eq!(outside, ambient);
+        let blocked = light_at(Point2::new(1, 0), ambient, &lights, distance, |_, _| false);
+        assert_eq!(blocked, ambient);
+
+        let mut map = LightMap::new(Lighting::default());
+        let id = map.add(Point2::new(5, 5), light);
+        assert_eq!(
+            map.lights_in(Point2::new(8, 8), Point2::new(10, 10)).len(),
+            1
+        );
+        assert!(map
+            .lights_in(Point2::new(9, 9), Point2::new(10, 10))
+            .is_empty());
+        assert_eq!(
+            map.set_point(id, Point2::new(0, 0)),
+            Some(Point2::new(5, 5))
+        );
+        assert_eq!(
+            map.remove(id).map(|(point, _)| point),
+            Some(Point2::new(0, 0))
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Outlines of tiles in tilemap space which follow the grid topology.
+
+use crate::{chunk::render::GridTopology, lib::*, system::topology_translation};
+
+/// The number of subdivisions of a pixel used to match up vertices.
+const VERTEX_PRECISION: f32 = 16.0;
+
+/// A quantized vertex which can be hashed and ordered.
+type VertexKey = (i64, i64);
+
+/// Quantizes a vertex so that shared vertices of neighbouring tiles match.
+fn vertex_key(vertex: Vec2) -> VertexKey {
+    (
+        (vertex.y * VERTEX_PRECISION).round() as i64,
+        (vertex.x * VERTEX_PRECISION).round() as i64,
+    )
+}
+
+/// Returns the polygon of a tile in tilemap space, counter clockwise from the
+/// bottom.
+///
+/// The quad of the tile is positioned exactly as the chunk shaders do for the
+/// topology, then the hexagon or square within the quad is returned.
+pub(crate) fn tile_polygon(
+    topology: GridTopology,
+    chunk_point: Point2,
+    tile_point: Point2,
+    chunk_dimensions: Dimension3,
+    texture_dimensions: Dimension2,
+) -> Vec<Vec2> {
+    use GridTopology::*;
+    let width = texture_dimensions.width as f32;
+    let height = texture_dimensions.height as f32;
+    let x0 = tile_point.x as f32 - chunk_dimensions.width as f32 / 2.0;
+    let y0 = tile_point.y as f32 - chunk_dimensions.height as f32 / 2.0;
+
+    // The bottom left corner of the quad, following the vertex shaders.
+    let mut corner = Vec2::new(x0 * width, y0 * height);
+    let col = (x0 + 1.01).floor();
+    let row = (y0 + 1.01).floor();
+    match topology {
+        Square => {}
+        HexX => {
+            corner.y += (0.5 * height).floor() * col;
+            corner.x -= col * (0.25 * width).ceil();
+        }
+        HexY => {
+            corner.x += (0.5 * width).floor() * row;
+            corner.y -= row * (0.25 * height).ceil();
+        }
+        HexEvenCols | HexOddCols => {
+            let offset = (0.25 * height).floor();
+            let even = col as i32 % 2 == 0;
+            if even == (topology == HexEvenCols) {
+                corner.y -= offset;
+            } else {
+                corner.y += offset;
+            }
+            corner.x -= col * (0.25 * width).ceil();
+        }
+        HexEvenRows | HexOddRows => {
+            let offset = (0.25 * width).floor();
+            let even = row as i32 % 2 == 0;
+            if even == (topology == HexEvenRows) {
+                corner.x -= offset;
+            } else {
+                corner.x += offset;
+            }
+            corner.y -= row * (0.25 * height).ceil();
+        }
+    }
+    let (translation_x, translation_y) =
+        topology_translation(topology, chunk_point, chunk_dimensions, texture_dimensions);
+    let x = corner.x.ceil() + translation_x;
+    let y = corner.y.ceil() + translation_y;
+
+    match topology {
+        Square => vec![
+            Vec2::new(x, y),
+            Vec2::new(x + width, y),
+            Vec2::new(x + width, y + height),
+            Vec2::new(x, y + height),
+        ],
+        HexY | HexEvenRows | HexOddRows => {
+            let side = (0.25 * height).ceil();
+            vec![
+                Vec2::new(x + width / 2.0, y),
+                Vec2::new(x + width, y + side),
+                Vec2::new(x + width, y + height - side),
+                Vec2::new(x + width / 2.0, y + height),
+                Vec2::new(x, y + height - side),
+                Vec2::new(x, y + side),
+            ]
+        }
+        HexX | HexEvenCols | HexOddCols => {
+            let side = (0.25 * width).ceil();
+            vec![
+                Vec2::new(x + side, y),
+                Vec2::new(x + width - side, y),
+                Vec2::new(x + width, y + height / 2.0),
+                Vec2::new(x + width - side, y + height),
+                Vec2::new(x + side, y + height),
+                Vec2::new(x, y + height / 2.0),
+            ]
+        }
+    }
+}
+
+/// Returns `true` if two tile polygons share an edge.
+#[cfg(test)]
+pub(crate) fn polygons_share_edge(a: &[Vec2], b: &[Vec2]) -> bool {
+    let mut edges = HashSet::default();
+    for (start, end) in polygon_edges(a) {
+        edges.insert((vertex_key(start), vertex_key(end)));
+    }
+    polygon_edges(b).any(|(start, end)| edges.contains(&(vertex_key(end), vertex_key(start))))
+}
+
+/// Iterates over the edges of a polygon.
+fn polygon_edges(polygon: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(start, end)| (*start, *end))
+}
+
+/// Merges counter clockwise polygons into the closed loops which outline
+/// them.
+///
+/// Edges shared by two polygons are removed and the rest are chained into
+/// loops, each starting at its lowest vertex. Vertices which lie on a straight
+/// line are dropped.
+pub(crate) fn merge_polygons<I>(polygons: I) -> Vec<Vec<Vec2>>
+where
+    I: IntoIterator<Item = Vec<Vec2>>,
+{
+    let mut edges: HashMap<(VertexKey, VertexKey), Vec2> = HashMap::default();
+    for polygon in polygons {
+        for (start, end) in polygon_edges(&polygon) {
+            let edge = (vertex_key(start), vertex_key(end));
+            if edges.remove(&(edge.1, edge.0)).is_none() {
+                edges.insert(edge, start);
+            }
+        }
+    }
+
+    let mut outgoing: HashMap<VertexKey, Vec<(VertexKey, Vec2)>> = HashMap::default();
+    for ((start, end), vertex) in edges.into_iter() {
+        outgoing.entry(start).or_default().push((end, vertex));
+    }
+    for ends in outgoing.values_mut() {
+        ends.sort_by_key(|(end, _)| *end);
+    }
+
+    let mut loops = Vec::new();
+    while let Some(first) = outgoing
+        .iter()
+        .filter(|(_, ends)| !ends.is_empty())
+        .map(|(start, _)| *start)
+        .min()
+    {
+        let mut vertices = Vec::new();
+        let mut current = first;
+        while let Some((end, vertex)) = outgoing.get_mut(&current).and_then(|ends| ends.pop()) {
+            vertices.push(vertex);
+            current = end;
+            if current == first {
+                break;
+            }
+        }
+        loops.push(remove_collinear(vertices));
+    }
+
+    loops
+}
+
+/// Removes vertices which lie on a straight line between their neighbours.
+fn remove_collinear(vertices: Vec<Vec2>) -> Vec<Vec2> {
+    let len = vertices.len();
+    if len < 3 {
+        return vertices;
+    }
+    let previous = vertices.iter().cycle().skip(len - 1);
+    let next = vertices.iter().cycle().skip(1);
+    vertices
+        .iter()
+        .zip(previous.zip(next))
+        .filter(|(current, (previous, next))| {
+            let a = **current - **previous;
+            let b = **next - **current;
+            (a.x * b.y - a.y * b.x).abs() > f32::EPSILON
+        })
+        .map(|(current, _)| *current)
+        .collect()
+}
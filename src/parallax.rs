@@ -0,0 +1,151 @@
+//! Parallax scrolling of tilemaps behind each other.
+//!
+//! A tilemap entity with a [`ParallaxLayer`] component follows a camera by a
+//! factor of its movement, so several tilemaps stacked by their z make up a
+//! parallax background of tile chunks. A factor of 1.0 scrolls with the world
+//! like any tilemap, a smaller factor scrolls slower and looks further away,
+//! and 0.0 stays in place on the screen.
+//!
+//! The translation and global translation of the tilemap are set before the
+//! chunks are auto spawned, so the chunks around the camera are spawned for
+//! the scrolled tilemap. The z of the translation is left as is.
+//!
+//! # A background of mountains and clouds
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_ecs::prelude::*;
+//! use bevy_math::Vec2;
+//! use bevy_render::prelude::*;
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{parallax::ParallaxLayer, prelude::*};
+//! use bevy_transform::prelude::*;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let tilemap_bundle = |z: f32| TilemapBundle {
+//!     tilemap: TilemapBuilder::new()
+//!         .texture_atlas(texture_atlas_handle.clone())
+//!         .texture_dimensions(32, 32)
+//!         .auto_chunk()
+//!         .auto_spawn(2, 2)
+//!         .finish()
+//!         .unwrap(),
+//!     visible: Visible {
+//!         is_visible: true,
+//!         is_transparent: true,
+//!     },
+//!     transform: Transform::from_xyz(0.0, 0.0, z),
+//!     global_transform: Default::default(),
+//! };
+//!
+//! let mut world = World::default();
+//! // The mountains far away.
+//! world
+//!     .spawn()
+//!     .insert_bundle(tilemap_bundle(-2.0))
+//!     .insert(ParallaxLayer::new(0.25));
+//! // The clouds drift by faster across than up and down.
+//! world
+//!     .spawn()
+//!     .insert_bundle(tilemap_bundle(-1.0))
+//!     .insert(ParallaxLayer {
+//!         factor: Vec2::new(0.5, 0.8),
+//!         ..Default::default()
+//!     });
+//! ```
+
+use crate::{lib::*, Tilemap};
+
+/// A tilemap which scrolls by a factor of the movement of a camera.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ParallaxLayer {
+    /// How much the tilemap scrolls with the camera on each axis.
+    pub factor: Vec2,
+    /// The translation of the tilemap when the camera is at the origin.
+    pub origin: Vec2,
+    /// The camera to follow, or `None` to follow the first camera which
+    /// drives the tilemap. It needs to be set if the window of the tilemap
+    /// has more than one camera, such as a UI camera.
+    pub camera: Option<Entity>,
+}
+
+impl Default for ParallaxLayer {
+    fn default() -> ParallaxLayer {
+        ParallaxLayer {
+            factor: Vec2::ONE,
+            origin: Vec2::ZERO,
+            camera: None,
+        }
+    }
+}
+
+impl ParallaxLayer {
+    /// Constructs a parallax layer which scrolls by a factor on both axes.
+    pub fn new(factor: f32) -> ParallaxLayer {
+        ParallaxLayer {
+            factor: Vec2::new(factor, factor),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the translation of the tilemap for a camera translation.
+    ///
+    /// The tilemap moves along with the part of the movement of the camera
+    /// which it does not scroll by, so it looks like it scrolls by the factor.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_math::Vec2;
+    /// use bevy_tilemap::parallax::ParallaxLayer;
+    ///
+    /// let layer = ParallaxLayer::new(0.25);
+    ///
+    /// assert_eq!(layer.translation(Vec2::new(100.0, -40.0)), Vec2::new(75.0, -30.0));
+    /// assert_eq!(ParallaxLayer::new(1.0).translation(Vec2::new(100.0, 0.0)), Vec2::ZERO);
+    /// ```
+    pub fn translation(&self, camera: Vec2) -> Vec2 {
+        self.origin + camera * (Vec2::ONE - self.factor)
+    }
+}
+
+/// Keeps the translations of the tilemaps with a parallax layer in sync with
+/// their cameras.
+pub(crate) fn tilemap_parallax(
+    camera_query: Query<(Entity, &Camera, &Transform), Without<ParallaxLayer>>,
+    mut tilemap_query: Query<(
+        &Tilemap,
+        &ParallaxLayer,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+) {
+    for (tilemap, layer, mut transform, mut global_transform) in tilemap_query.iter_mut() {
+        let camera_transform = camera_query
+            .iter()
+            .find(|(entity, camera, _camera_transform)| match layer.camera {
+                Some(camera_entity) => *entity == camera_entity,
+                None => tilemap.views_camera(camera),
+            })
+            .map(|(_entity, _camera, camera_transform)| camera_transform);
+        let camera_transform = match camera_transform {
+            Some(camera_transform) => camera_transform,
+            None => continue,
+        };
+        let camera = Vec2::new(
+            camera_transform.translation.x,
+            camera_transform.translation.y,
+        );
+        let translation = layer.translation(camera);
+        // Only writing a changed translation keeps the change detection of
+        // the transform quiet while the camera rests.
+        if transform.translation.x != translation.x || transform.translation.y != translation.y {
+            // Auto spawn follows the global transform, which is otherwise
+            // only propagated after the chunks are spawned.
+            global_transform.translation.x += translation.x - transform.translation.x;
+            global_transform.translation.y += translation.y - transform.translation.y;
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+        }
+    }
+}
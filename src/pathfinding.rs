@@ -0,0 +1,530 @@
+//! A* pathfinding over a sprite layer of a tilemap.
+//!
+//! [`Tilemap::find_path`] searches for the cheapest path between two points
+//! where a sprite layer decides which tiles can be moved onto and at what
+//! cost. Steps always follow the grid topology, so on hex topologies a step
+//! moves to one of the six tiles sharing an edge. On a square topology
+//! diagonal steps can be allowed with [`PathOptions::diagonals`].
+//!
+//! Only points within chunks that exist can be moved onto, which also bounds
+//! the search on endless tilemaps.
+//!
+//! For crowds of agents heading to the same goals, [`Tilemap::flow_field`]
+//! makes a [`FlowField`] instead. It holds the cost of the cheapest path to
+//! the nearest goal from every point of the existing chunks, stored chunk by
+//! chunk, along with the direction to step in from there, so every agent just
+//! follows the direction under it. After tiles change,
+//! [`Tilemap::update_flow_field`] only searches again from the points whose
+//! paths could have changed.
+//!
+//! [`Tilemap::find_path`]: crate::tilemap::Tilemap::find_path
+//! [`Tilemap::flow_field`]: crate::tilemap::Tilemap::flow_field
+//! [`Tilemap::update_flow_field`]: crate::tilemap::Tilemap::update_flow_field
+//!
+//! # Finding a path around a wall
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     pathfinding::{PathCost, PathOptions},
+//!     point::Point2,
+//!     prelude::*,
+//! };
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! // A wall with a single gap at the top.
+//! let wall = (-3..3).map(|y| Tile { point: (0, y), ..Default::default() });
+//! tilemap.insert_tiles(wall).unwrap();
+//!
+//! let options = PathOptions { cost: PathCost::Collision, ..Default::default() };
+//! let path = tilemap.find_path((-2, 0), (2, 0), &options).unwrap();
+//!
+//! assert_eq!(path.first(), Some(&Point2::new(-2, 0)));
+//! assert_eq!(path.last(), Some(&Point2::new(2, 0)));
+//! assert!(path.contains(&Point2::new(0, 3)));
+//! ```
+
+use crate::{chunk::RawTile, lib::*, property::TileProperties};
+
+/// How a sprite layer decides the cost of moving onto a tile.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum PathCost {
+    /// Tiles in the layer block movement, every other point costs 1.
+    #[default]
+    Collision,
+    /// Only the tiles in the layer can be moved onto, each costing 1.
+    Walkable,
+    /// The cost is the number value of a tile property of the layer, rounded
+    /// up, or 1 if the tile does not have the property. A cost below 1 blocks
+    /// movement.
+    Property(String),
+}
+
+impl PathCost {
+    /// Returns the cost of moving onto a point from its tile and properties
+    /// in the layer, or `None` if it blocks movement.
+    pub(crate) fn cost(
+        &self,
+        tile: Option<&RawTile>,
+        properties: Option<&TileProperties>,
+    ) -> Option<u32> {
+        match self {
+            PathCost::Collision => match tile {
+                Some(_) => None,
+                None => Some(1),
+            },
+            PathCost::Walkable => tile.map(|_| 1),
+            PathCost::Property(name) => {
+                let cost = match properties
+                    .and_then(|properties| properties.get(name))
+                    .and_then(|property| property.as_float())
+                {
+                    Some(cost) => cost.ceil(),
+                    None => 1.0,
+                };
+                if cost < 1.0 {
+                    None
+                } else {
+                    Some(cost.min(f64::from(u32::MAX)) as u32)
+                }
+            }
+        }
+    }
+}
+
+/// The options of a path search.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PathOptions {
+    /// The sprite order of the layer which decides the cost.
+    pub sprite_order: usize,
+    /// The z depth of the layer which decides the cost.
+    pub z_depth: usize,
+    /// How the layer decides the cost of moving onto a tile.
+    pub cost: PathCost,
+    /// If diagonal steps are allowed on a square topology. A diagonal step
+    /// costs the same as the tile it moves onto and can not cut the corner of
+    /// a tile which blocks movement.
+    pub diagonals: bool,
+}
+
+/// The cost of the points no goal can be reached from.
+const UNREACHED: u32 = u32::MAX;
+
+/// The flow field of the tiles of a chunk.
+#[derive(Clone, PartialEq, Debug)]
+struct FlowChunk {
+    /// The cost of moving onto each tile when the field was last updated.
+    step_costs: Vec<Option<u32>>,
+    /// The cost of the cheapest path from each tile to a goal.
+    costs: Vec<u32>,
+    /// The next tile of the path from each tile.
+    next_points: Vec<Option<Point2>>,
+    /// The direction from each tile to the next, in the tilemap's space.
+    directions: Vec<Vec2>,
+}
+
+/// The cheapest paths from every point of a tilemap to the nearest of a set
+/// of goals, made with [`Tilemap::flow_field`].
+///
+/// See the [`pathfinding`] module for more information.
+///
+/// [`Tilemap::flow_field`]: crate::tilemap::Tilemap::flow_field
+/// [`pathfinding`]: crate::pathfinding
+#[derive(Clone, PartialEq, Debug)]
+pub struct FlowField {
+    /// The points the paths lead to.
+    goals: Vec<Point2>,
+    /// The options of the search.
+    options: PathOptions,
+    /// The dimensions of the chunks of the tilemap.
+    chunk_dimensions: Dimension3,
+    /// The fields of the chunks by their point.
+    chunks: HashMap<Point2, FlowChunk>,
+}
+
+impl FlowField {
+    /// Constructs a field without any chunks.
+    pub(crate) fn new(
+        goals: Vec<Point2>,
+        options: PathOptions,
+        chunk_dimensions: Dimension3,
+    ) -> FlowField {
+        FlowField {
+            goals,
+            options,
+            chunk_dimensions,
+            chunks: HashMap::default(),
+        }
+    }
+
+    /// Returns the points the paths lead to.
+    pub fn goals(&self) -> &[Point2] {
+        &self.goals
+    }
+
+    /// Returns the options of the search.
+    pub fn options(&self) -> &PathOptions {
+        &self.options
+    }
+
+    /// Returns the cost of the cheapest path from a point to the nearest
+    /// goal, or `None` if no goal can be reached from it.
+    pub fn cost<P: Into<Point2>>(&self, point: P) -> Option<u32> {
+        self.path_cost(point.into())
+            .filter(|cost| *cost != UNREACHED)
+    }
+
+    /// Returns the next point of the cheapest path from a point to the
+    /// nearest goal, or `None` at a goal or if no goal can be reached from
+    /// it.
+    pub fn next_point<P: Into<Point2>>(&self, point: P) -> Option<Point2> {
+        let (chunk, index) = self.locate(point.into())?;
+        chunk.next_points.get(index).copied().flatten()
+    }
+
+    /// Returns the unit direction from a point to the next point of its
+    /// path, in the tilemap's space, or zero at a goal or if no goal can be
+    /// reached from it.
+    pub fn direction<P: Into<Point2>>(&self, point: P) -> Vec2 {
+        self.locate(point.into())
+            .and_then(|(chunk, index)| chunk.directions.get(index).copied())
+            .unwrap_or(Vec2::ZERO)
+    }
+
+    /// Returns the dimensions of the chunks the field was made with.
+    pub(crate) fn chunk_dimensions(&self) -> Dimension3 {
+        self.chunk_dimensions
+    }
+
+    /// Returns the points of the chunks in the field.
+    pub(crate) fn chunk_points(&self) -> Vec<Point2> {
+        self.chunks.keys().copied().collect()
+    }
+
+    /// Returns the step costs of the tiles of a chunk in the field.
+    pub(crate) fn step_costs(&self, chunk_point: Point2) -> Option<&[Option<u32>]> {
+        self.chunks
+            .get(&chunk_point)
+            .map(|chunk| chunk.step_costs.as_slice())
+    }
+
+    /// Inserts a chunk with the step costs of its tiles, which no goal can be
+    /// reached from yet, or sets the step costs if it is already in the field.
+    pub(crate) fn insert_chunk(&mut self, chunk_point: Point2, step_costs: Vec<Option<u32>>) {
+        if let Some(chunk) = self.chunks.get_mut(&chunk_point) {
+            chunk.step_costs = step_costs;
+            return;
+        }
+        let len = step_costs.len();
+        self.chunks.insert(
+            chunk_point,
+            FlowChunk {
+                step_costs,
+                costs: vec![UNREACHED; len],
+                next_points: vec![None; len],
+                directions: vec![Vec2::ZERO; len],
+            },
+        );
+    }
+
+    /// Removes a chunk from the field.
+    pub(crate) fn remove_chunk(&mut self, chunk_point: Point2) {
+        self.chunks.remove(&chunk_point);
+    }
+
+    /// Returns the chunk of a point and the index of the point in it.
+    fn locate(&self, point: Point2) -> Option<(&FlowChunk, usize)> {
+        let (chunk_point, index) = self.chunk_index(point);
+        self.chunks.get(&chunk_point).map(|chunk| (chunk, index))
+    }
+
+    /// Returns the point of the chunk of a point and the index of the point
+    /// in the chunk.
+    fn chunk_index(&self, point: Point2) -> (Point2, usize) {
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let chunk_x = (point.x + width / 2).div_euclid(width);
+        let chunk_y = (point.y + height / 2).div_euclid(height);
+        let x = point.x - width * chunk_x + width / 2;
+        let y = point.y - height * chunk_y + height / 2;
+        (Point2::new(chunk_x, chunk_y), (y * width + x) as usize)
+    }
+
+    /// Returns the cost of the path from a point, which is `UNREACHED` if no
+    /// goal can be reached, or `None` if its chunk is not in the field.
+    pub(crate) fn path_cost(&self, point: Point2) -> Option<u32> {
+        self.locate(point)
+            .and_then(|(chunk, index)| chunk.costs.get(index).copied())
+    }
+
+    /// Sets the cost of the path from a point, if its chunk is in the field.
+    pub(crate) fn set_path_cost(&mut self, point: Point2, cost: u32) {
+        let (chunk_point, index) = self.chunk_index(point);
+        let path_cost = self
+            .chunks
+            .get_mut(&chunk_point)
+            .and_then(|chunk| chunk.costs.get_mut(index));
+        if let Some(path_cost) = path_cost {
+            *path_cost = cost;
+        }
+    }
+
+    /// Sets the next point of the path from a point and the direction to it.
+    pub(crate) fn set_next_point(&mut self, point: Point2, next: Option<Point2>, direction: Vec2) {
+        let (chunk_point, index) = self.chunk_index(point);
+        if let Some(chunk) = self.chunks.get_mut(&chunk_point) {
+            if let Some(next_point) = chunk.next_points.get_mut(index) {
+                *next_point = next;
+            }
+            if let Some(chunk_direction) = chunk.directions.get_mut(index) {
+                *chunk_direction = direction;
+            }
+        }
+    }
+
+    /// Forgets the paths from every point whose path costs at least a cost,
+    /// returning those points.
+    pub(crate) fn invalidate_from(&mut self, cost: u32) -> Vec<Point2> {
+        let mut invalidated = Vec::new();
+        let chunk_dimensions = self.chunk_dimensions;
+        let width = chunk_dimensions.width as Coord;
+        let height = chunk_dimensions.height as Coord;
+        for (chunk_point, chunk) in self.chunks.iter_mut() {
+            let paths = chunk
+                .costs
+                .iter_mut()
+                .zip(chunk.next_points.iter_mut())
+                .zip(chunk.directions.iter_mut())
+                .enumerate();
+            for (index, ((path_cost, next_point), direction)) in paths {
+                if *path_cost < cost || *path_cost == UNREACHED {
+                    continue;
+                }
+                *path_cost = UNREACHED;
+                *next_point = None;
+                *direction = Vec2::ZERO;
+                invalidated.push(Point2::new(
+                    chunk_point.x * width - width / 2 + index as Coord % width,
+                    chunk_point.y * height - height / 2 + index as Coord / width,
+                ));
+            }
+        }
+        invalidated
+    }
+}
+
+/// Spreads the costs of the paths in a flow field out from the seeds with
+/// Dijkstra's algorithm, returning the points whose cost was lowered.
+///
+/// The cost of a step is given from the point moved from to the point moved
+/// onto, so the paths lead towards the seeds. Only points in chunks of the
+/// field are reached.
+pub(crate) fn integrate<N, C>(
+    field: &mut FlowField,
+    seeds: Vec<Point2>,
+    mut neighbours: N,
+    mut cost: C,
+) -> Vec<Point2>
+where
+    N: FnMut(Point2) -> Vec<Point2>,
+    C: FnMut(Point2, Point2) -> Option<u32>,
+{
+    let mut open = BinaryHeap::new();
+    for seed in seeds {
+        if let Some(seed_cost) = field.path_cost(seed).filter(|cost| *cost != UNREACHED) {
+            open.push(Reverse((seed_cost, (seed.x, seed.y))));
+        }
+    }
+
+    let mut lowered = Vec::new();
+    while let Some(Reverse((current_cost, (x, y)))) = open.pop() {
+        let current = Point2::new(x, y);
+        // A cheaper path from this point was already spread.
+        if field
+            .path_cost(current)
+            .is_none_or(|cost| cost < current_cost)
+        {
+            continue;
+        }
+        for neighbour in neighbours(current) {
+            let neighbour_cost = match field.path_cost(neighbour) {
+                Some(neighbour_cost) => neighbour_cost,
+                None => continue,
+            };
+            let step = if let Some(step) = cost(neighbour, current) {
+                step
+            } else {
+                continue;
+            };
+            let path_cost = current_cost.saturating_add(step).min(UNREACHED - 1);
+            if neighbour_cost <= path_cost {
+                continue;
+            }
+            field.set_path_cost(neighbour, path_cost);
+            lowered.push(neighbour);
+            open.push(Reverse((path_cost, (neighbour.x, neighbour.y))));
+        }
+    }
+    lowered
+}
+
+/// Searches for the cheapest path from a start to a goal with A*, returning
+/// the points along it including both ends.
+///
+/// The heuristic must never be more than the actual cost to the goal.
+pub(crate) fn a_star<N, C, H>(
+    start: Point2,
+    goal: Point2,
+    mut neighbours: N,
+    mut cost: C,
+    mut heuristic: H,
+) -> Option<Vec<Point2>>
+where
+    N: FnMut(Point2) -> Vec<Point2>,
+    C: FnMut(Point2, Point2) -> Option<u32>,
+    H: FnMut(Point2) -> u32,
+{
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Point2, Point2> = HashMap::default();
+    let mut costs: HashMap<Point2, u32> = HashMap::default();
+    costs.insert(start, 0);
+    open.push(Reverse((heuristic(start), 0, (start.x, start.y))));
+
+    while let Some(Reverse((_, current_cost, (x, y)))) = open.pop() {
+        let current = Point2::new(x, y);
+        if current == goal {
+            let mut path = vec![current];
+            let mut point = current;
+            while let Some(previous) = came_from.get(&point) {
+                point = *previous;
+                path.push(point);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        // A cheaper way to this point was already expanded.
+        if costs.get(&current).is_some_and(|cost| *cost < current_cost) {
+            continue;
+        }
+        for neighbour in neighbours(current) {
+            let step = if let Some(step) = cost(current, neighbour) {
+                step
+            } else {
+                continue;
+            };
+            let neighbour_cost = current_cost.saturating_add(step);
+            if costs
+                .get(&neighbour)
+                .is_some_and(|cost| *cost <= neighbour_cost)
+            {
+                continue;
+            }
+            costs.insert(neighbour, neighbour_cost);
+            came_from.insert(neighbour, current);
+            let estimate = neighbour_cost.saturating_add(heuristic(neighbour));
+            open.push(Reverse((
+                estimate,
+                neighbour_cost,
+                (neighbour.x, neighbour.y),
+            )));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a_star() {
+        // An open square grid with a blocked column at x = 1, except y = 2.
+        let neighbours = |point: Point2| {
+            vec![
+                Point2::new(point.x + 1, point.y),
+                Point2::new(point.x - 1, point.y),
+                Point2::new(point.x, point.y + 1),
+                Point2::new(point.x, point.y - 1),
+            ]
+        };
+        let cost = |_: Point2, to: Point2| {
+            let inside = to.x.abs() <= 3 && to.y.abs() <= 3;
+            let wall = to.x == 1 && to.y != 2;
+            if inside && !wall {
+                Some(1)
+            } else {
+                None
+            }
+        };
+        let goal = Point2::new(3, 0);
+        let heuristic =
+            |point: Point2| ((goal.x - point.x).abs() + (goal.y - point.y).abs()) as u32;
+
+        let path = a_star(Point2::new(0, 0), goal, neighbours, cost, heuristic).unwrap();
+        assert_eq!(path.len(), 8);
+        assert!(path.contains(&Point2::new(1, 2)));
+
+        let blocked = |_: Point2, to: Point2| if to.x == 1 { None } else { Some(1) };
+        let bounded = |point: Point2| {
+            neighbours(point)
+                .into_iter()
+                .filter(|point| point.x.abs() <= 3 && point.y.abs() <= 3)
+                .collect()
+        };
+        assert_eq!(
+            a_star(Point2::new(0, 0), goal, bounded, blocked, heuristic),
+            None
+        );
+    }
+
+    #[test]
+    fn test_integrate() {
+        // A single 4 by 4 chunk from (-2, -2) to (1, 1), with every step
+        // onto x = -1 costing 3.
+        let dimensions = Dimension3::new(4, 4, 1);
+        let mut field = FlowField::new(vec![Point2::new(-2, -2)], Default::default(), dimensions);
+        field.insert_chunk(Point2::new(0, 0), vec![Some(1); 16]);
+        assert_eq!(
+            field.chunk_index(Point2::new(-2, -2)),
+            (Point2::new(0, 0), 0)
+        );
+        assert_eq!(
+            field.chunk_index(Point2::new(1, 1)),
+            (Point2::new(0, 0), 15)
+        );
+        assert_eq!(field.chunk_index(Point2::new(2, 0)), (Point2::new(1, 0), 8));
+
+        let neighbours = |point: Point2| {
+            vec![
+                Point2::new(point.x + 1, point.y),
+                Point2::new(point.x - 1, point.y),
+                Point2::new(point.x, point.y + 1),
+                Point2::new(point.x, point.y - 1),
+            ]
+        };
+        let cost = |_: Point2, to: Point2| if to.x == -1 { Some(3) } else { Some(1) };
+        field.set_path_cost(Point2::new(-2, -2), 0);
+        let lowered = integrate(&mut field, vec![Point2::new(-2, -2)], neighbours, cost);
+        assert_eq!(lowered.len(), 15);
+        assert_eq!(field.cost((-2, 1)), Some(3));
+        // Every path from x = 0 has to step onto x = -1 once.
+        assert_eq!(field.cost((0, -2)), Some(4));
+        assert_eq!(field.cost((0, 1)), Some(7));
+        assert_eq!(field.cost((2, 0)), None);
+
+        let mut invalidated = field.invalidate_from(7);
+        invalidated.sort_by_key(|point| (point.x, point.y));
+        assert_eq!(
+            invalidated,
+            vec![Point2::new(0, 1), Point2::new(1, 0), Point2::new(1, 1)]
+        );
+        assert_eq!(field.cost((0, 1)), None);
+        assert_eq!(field.cost((0, 0)), Some(6));
+    }
+}
@@ -0,0 +1,234 @@
+//! Click masks which help resolve picks to the visually correct tile.
+//!
+//! Tall sprites, especially on hex maps, overlap the cells around them. A
+//! purely cell based pick will often land on the wrong tile near those
+//! overlaps. A [`ClickMask`] can be registered per sprite index on the
+//! [`Tilemap`] which describes which pixels of a sprite actually accept a
+//! click.
+//!
+//! [`Tilemap`]: crate::tilemap::Tilemap
+//!
+//! # Registering a click mask
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_math::Vec2;
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, picking::ClickMask};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//!
+//! // The trunk of a tree only takes up the middle of the sprite.
+//! tilemap.set_click_mask(3, ClickMask::Inset { left: 8, top: 0, right: 8, bottom: 0 });
+//!
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 3, ..Default::default() }).unwrap();
+//!
+//! assert!(tilemap.tile_contains_pixel((0, 0), 0, Vec2::new(16.0, 16.0)));
+//! assert!(!tilemap.tile_contains_pixel((0, 0), 0, Vec2::new(2.0, 16.0)));
+//! ```
+
+use crate::{
+    lib::*,
+    tilemap::{ErrorKind, TilemapResult},
+};
+
+/// A mask describing which pixels of a sprite accept a click.
+///
+/// Pixel coordinates are always relative to the bottom left corner of the
+/// sprite, with Y going up, which matches the world space of the tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ClickMask {
+    /// The whole sprite accepts clicks except for the given insets in pixels.
+    Inset {
+        /// Pixels to ignore from the left edge.
+        left: u32,
+        /// Pixels to ignore from the top edge.
+        top: u32,
+        /// Pixels to ignore from the right edge.
+        right: u32,
+        /// Pixels to ignore from the bottom edge.
+        bottom: u32,
+    },
+    /// A per pixel mask stored in rows from the top left of the sprite.
+    Bits {
+        /// The width of the mask in pixels.
+        width: u32,
+        /// The height of the mask in pixels.
+        height: u32,
+        /// If the pixel accepts a click or not.
+        bits: Vec<bool>,
+    },
+}
+
+impl ClickMask {
+    /// Bakes a bit mask from the alpha channel of a sprite in a texture.
+    ///
+    /// Every pixel with an alpha greater than `alpha_threshold` accepts a
+    /// click. The `rect` is the area of the sprite in the texture, typically
+    /// taken from [`TextureAtlas::textures`].
+    ///
+    /// [`TextureAtlas::textures`]: bevy_sprite::TextureAtlas::textures
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnsupportedImage`] if the texture is not an 8
+    /// bit, 4 channel texture, and [`ErrorKind::RectOutOfBounds`] if any part
+    /// of the rect is outside of the texture.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_math::Vec2;
+    /// use bevy_render::texture::{Extent3d, Texture, TextureDimension, TextureFormat};
+    /// use bevy_sprite::Rect;
+    /// use bevy_tilemap::{dimension::Dimension2, picking::ClickMask};
+    ///
+    /// // A 2x1 texture with an opaque pixel and a transparent pixel.
+    /// let texture = Texture::new(
+    ///     Extent3d::new(2, 1, 1),
+    ///     TextureDimension::D2,
+    ///     vec![255, 255, 255, 255, 255, 255, 255, 0],
+    ///     TextureFormat::Rgba8UnormSrgb,
+    /// );
+    /// let rect = Rect { min: Vec2::new(0.0, 0.0), max: Vec2::new(2.0, 1.0) };
+    ///
+    /// let mask = ClickMask::from_texture_alpha(&texture, &rect, 0.5).unwrap();
+    ///
+    /// assert!(mask.contains(Dimension2::new(2, 1), Vec2::new(0.5, 0.5)));
+    /// assert!(!mask.contains(Dimension2::new(2, 1), Vec2::new(1.5, 0.5)));
+    /// ```
+    pub fn from_texture_alpha(
+        texture: &Texture,
+        rect: &Rect,
+        alpha_threshold: f32,
+    ) -> TilemapResult<ClickMask> {
+        let texture_width = texture.size.width as usize;
+        let texture_height = texture.size.height as usize;
+        if texture.format.pixel_size() != 4 {
+            return Err(ErrorKind::UnsupportedImage.into());
+        }
+        if rect.min.x < 0.0 || rect.min.y < 0.0 {
+            return Err(ErrorKind::RectOutOfBounds.into());
+        }
+        let min_x = rect.min.x as usize;
+        let min_y = rect.min.y as usize;
+        let width = rect.width() as u32;
+        let height = rect.height() as u32;
+        // A rect past the right edge would otherwise wrap into the next row.
+        if min_x + width as usize > texture_width || min_y + height as usize > texture_height {
+            return Err(ErrorKind::RectOutOfBounds.into());
+        }
+        let threshold = (alpha_threshold.clamp(0.0, 1.0) * 255.0) as u8;
+
+        let mut bits = Vec::with_capacity((width * height) as usize);
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let index = ((min_y + y) * texture_width + min_x + x) * 4 + 3;
+                let alpha = texture.data.get(index).ok_or(ErrorKind::UnsupportedImage)?;
+                bits.push(*alpha > threshold);
+            }
+        }
+
+        Ok(ClickMask::Bits {
+            width,
+            height,
+            bits,
+        })
+    }
+
+    /// Returns `true` if the pixel accepts a click.
+    ///
+    /// Insets are measured against the given `sprite_dimensions`, while bit
+    /// masks use their own dimensions.
+    pub fn contains(&self, sprite_dimensions: Dimension2, pixel: Vec2) -> bool {
+        if pixel.x < 0.0 || pixel.y < 0.0 {
+            return false;
+        }
+        match self {
+            ClickMask::Inset {
+                left,
+                top,
+                right,
+                bottom,
+            } => {
+                pixel.x >= *left as f32
+                    && pixel.x < sprite_dimensions.width.saturating_sub(*right) as f32
+                    && pixel.y >= *bottom as f32
+                    && pixel.y < sprite_dimensions.height.saturating_sub(*top) as f32
+            }
+            ClickMask::Bits {
+                width,
+                height,
+                bits,
+            } => {
+                let x = pixel.x as u32;
+                let y = pixel.y as u32;
+                if x >= *width || y >= *height {
+                    return false;
+                }
+                let row = height - 1 - y;
+                bits.get((row * width + x) as usize)
+                    .copied()
+                    .unwrap_or(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x2 texture whose right column is transparent.
+    fn texture() -> Texture {
+        Texture::new(
+            Extent3d::new(2, 2, 1),
+            TextureDimension::D2,
+            vec![
+                255, 255, 255, 255, 255, 255, 255, 0, 255, 255, 255, 255, 255, 255, 255, 0,
+            ],
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    #[test]
+    fn test_rect_at_texture_edge() {
+        let rect = Rect {
+            min: Vec2::new(1.0, 0.0),
+            max: Vec2::new(2.0, 2.0),
+        };
+        let mask = ClickMask::from_texture_alpha(&texture(), &rect, 0.5).unwrap();
+        assert_eq!(
+            mask,
+            ClickMask::Bits {
+                width: 1,
+                height: 2,
+                bits: vec![false, false],
+            }
+        );
+    }
+
+    #[test]
+    fn test_rect_past_texture_edge() {
+        // Past the right edge the rect would read the next row instead.
+        let rect = Rect {
+            min: Vec2::new(1.0, 0.0),
+            max: Vec2::new(3.0, 1.0),
+        };
+        assert_eq!(
+            ClickMask::from_texture_alpha(&texture(), &rect, 0.5),
+            Err(ErrorKind::RectOutOfBounds.into())
+        );
+        let rect = Rect {
+            min: Vec2::new(0.0, 1.0),
+            max: Vec2::new(2.0, 3.0),
+        };
+        assert_eq!(
+            ClickMask::from_texture_alpha(&texture(), &rect, 0.5),
+            Err(ErrorKind::RectOutOfBounds.into())
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Spawning chunks along a known camera path ahead of the camera.
+//!
+//! The camera is expected to follow the points of the path in order. Every
+//! frame its progress along the path is taken from the nearest point it has
+//! not passed yet, and its speed is estimated from how far it moved. The
+//! points which the camera reaches within the lead time are handed out so
+//! their chunks can be spawned before the camera arrives.
+
+use crate::lib::*;
+
+/// The weight of the newest measured speed in the camera speed estimate.
+const SPEED_SMOOTHING: f32 = 0.25;
+
+/// A camera path whose chunks are spawned ahead of the camera.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct PreloadPath {
+    /// The points of the path in tiles, each with its distance along the path
+    /// from the first point.
+    points: Vec<(Vec2, f32)>,
+    /// How long ahead of the camera the chunks along the path are spawned.
+    lead_time: Duration,
+    /// The index of the point the camera is at.
+    reached: usize,
+    /// The number of points whose chunks were handed out.
+    preloaded: usize,
+    /// The position of the camera in tiles when the path was last advanced.
+    last_position: Option<Vec2>,
+    /// The estimated speed of the camera in tiles per second.
+    speed: f32,
+    /// The preloaded chunks by the index of the last point which needs them.
+    held: HashMap<Point2, usize>,
+}
+
+impl PreloadPath {
+    /// Constructs a path through tile points.
+    pub(crate) fn new(points: Vec<Point2>, lead_time: Duration) -> PreloadPath {
+        let mut distance = 0.0;
+        let mut previous: Option<Vec2> = None;
+        let points = points
+            .into_iter()
+            .map(|point| {
+                let position = Vec2::new(point.x as f32, point.y as f32);
+                if let Some(previous) = previous {
+                    distance += position.distance(previous);
+                }
+                previous = Some(position);
+                (position, distance)
+            })
+            .collect();
+        PreloadPath {
+            points,
+            lead_time,
+            reached: 0,
+            preloaded: 0,
+            last_position: None,
+            speed: 0.0,
+            held: HashMap::default(),
+        }
+    }
+
+    /// Advances the camera along the path to a position in tiles after some
+    /// time, returning the indices and tile points of the points whose chunks
+    /// are due to be spawned.
+    ///
+    /// The point after the one the camera is at is always due, so a resting
+    /// camera still has the next stretch of the path ready.
+    pub(crate) fn advance(&mut self, position: Vec2, delta: Duration) -> Vec<(usize, Point2)> {
+        let seconds = delta.as_secs_f32();
+        if let Some(last_position) = self.last_position {
+            if seconds > 0.0 {
+                let speed = position.distance(last_position) / seconds;
+                self.speed += (speed - self.speed) * SPEED_SMOOTHING;
+            }
+        }
+        self.last_position = Some(position);
+
+        let mut nearest: Option<(usize, f32)> = None;
+        for (index, (point, _distance)) in self.points.iter().enumerate().skip(self.reached) {
+            let distance = point.distance_squared(position);
+            if nearest.is_none_or(|(_, nearest)| distance < nearest) {
+                nearest = Some((index, distance));
+            }
+        }
+        if let Some((index, _distance)) = nearest {
+            self.reached = index;
+        }
+        let reach = self
+            .points
+            .get(self.reached)
+            .map_or(0.0, |(_, distance)| *distance)
+            + self.speed * self.lead_time.as_secs_f32();
+
+        let mut due = Vec::new();
+        while let Some((point, distance)) = self.points.get(self.preloaded) {
+            if *distance > reach && self.preloaded > self.reached + 1 {
+                break;
+            }
+            due.push((
+                self.preloaded,
+                Point2::new(point.x as Coord, point.y as Coord),
+            ));
+            self.preloaded += 1;
+        }
+        due
+    }
+
+    /// Keeps a chunk spawned until the camera reaches the point at an index.
+    pub(crate) fn hold(&mut self, chunk_point: Point2, index: usize) {
+        let held = self.held.entry(chunk_point).or_insert(index);
+        *held = (*held).max(index);
+    }
+
+    /// Returns `true` if a chunk is needed by a point the camera did not
+    /// reach yet.
+    pub(crate) fn holds(&self, chunk_point: Point2) -> bool {
+        self.held
+            .get(&chunk_point)
+            .is_some_and(|index| *index > self.reached)
+    }
+
+    /// Returns `true` if the camera is at the last point and every chunk
+    /// along the path was handed out.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.reached + 1 >= self.points.len() && self.preloaded >= self.points.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance() {
+        let points = (0..10).map(|x| Point2::new(x * 10, 0)).collect();
+        let mut path = PreloadPath::new(points, Duration::from_secs(2));
+        let frame = Duration::from_millis(100);
+
+        // A resting camera only needs the next point.
+        let due = path.advance(Vec2::new(0.0, 0.0), frame);
+        assert_eq!(due, vec![(0, Point2::new(0, 0)), (1, Point2::new(10, 0))]);
+        assert!(path.advance(Vec2::new(0.0, 0.0), frame).is_empty());
+
+        // Moving 5 tiles per frame is 50 tiles per second, which is smoothed.
+        let due = path.advance(Vec2::new(5.0, 0.0), frame);
+        assert_eq!(due.first(), Some(&(2, Point2::new(20, 0))));
+        assert_eq!(path.speed, 12.5);
+        path.hold(Point2::new(1, 0), due.last().unwrap().0);
+        assert!(path.holds(Point2::new(1, 0)));
+        assert!(!path.holds(Point2::new(0, 0)));
+        assert!(!path.is_finished());
+
+        path.advance(Vec2::new(90.0, 0.0), frame);
+        assert!(path.is_finished());
+        assert!(!path.holds(Point2::new(1, 0)));
+    }
+}
@@ -6,17 +6,17 @@
 //! [`bevy_tilemap::prelude::v0`], and re-exports the following.
 //!
 //! * [`bevy_tilemap::chunk`]::[`LayerKind`], the only public part
-//! of `chunk` module is the kind of layer you need to specify to create.
+//!   of `chunk` module is the kind of layer you need to specify to create.
 //! * [`bevy_tilemap::default_plugin`]::[`TilemapDefaultPlugins`], the
-//! default plugins for the library.
+//!   default plugins for the library.
 //! * [`bevy_tilemap::entity`]::[`TilemapBundle`], the component bundle
-//! for spawning with a Tilemap.
-//! * [`bevy_tilemap::tile`]::[`Tile`], a sprite tile which
-//! holds minimal amount of data for small data sizes.
+//!   for spawning with a Tilemap.
+//! * [`bevy_tilemap::tile`]::{[`Tile`], [`FlipAxis`]}, a sprite tile which
+//!   holds minimal amount of data and the axis to mirror tiles across.
 //! * [`bevy_tilemap::tilemap`]::{[`Tilemap`], [`TilemapBuilder`]},
-//! the core object that is used for virtually everything in this library.
+//!   the core object that is used for virtually everything in this library.
 //! * [`bevy_tilemap`]::[`TilemapPlugin`], the main plugin with
-//! a collection of systems, components and assets to be used in a Bevy app.
+//!   a collection of systems, components and assets to be used in a Bevy app.
 //!
 //! [`bevy_tilemap::prelude::v0`]: crate::prelude::v0
 //! [`bevy_tilemap::default_plugin`]: crate::default_plugin
@@ -32,7 +32,7 @@ pub mod v0 {
         chunk::{render::GridTopology, LayerKind},
         default_plugin::TilemapDefaultPlugins,
         entity::TilemapBundle,
-        tile::Tile,
+        tile::{FlipAxis, Tile},
         tilemap::{Tilemap, TilemapBuilder, TilemapLayer},
         TilemapPlugin,
     };
@@ -0,0 +1,157 @@
+//! Named properties stored alongside tiles for gameplay data.
+//!
+//! Gameplay data such as the movement cost, terrain type or ore content of a
+//! tile can be stored in the tilemap as a [`TileProperty`] with a name. The
+//! properties of a tile are kept per sprite layer, separate from its sprite,
+//! so they stay when the chunk is despawned or the sprite is cleared and they
+//! are serialized with the tilemap.
+//!
+//! # Setting tile properties
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, property::TileProperty};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! tilemap.set_tile_property((3, 3), 0, "movement_cost", 4).unwrap();
+//! tilemap.set_tile_property((3, 3), 0, "terrain", "swamp").unwrap();
+//!
+//! assert_eq!(
+//!     tilemap.tile_property((3, 3), 0, "movement_cost"),
+//!     Some(&TileProperty::Int(4))
+//! );
+//! assert_eq!(tilemap.tile_property((3, 3), 0, "terrain").and_then(|p| p.as_str()), Some("swamp"));
+//! ```
+
+use crate::lib::*;
+
+/// The properties of a tile by name.
+pub type TileProperties = HashMap<String, TileProperty>;
+
+/// A value of a tile property.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub enum TileProperty {
+    /// A boolean value.
+    Bool(bool),
+    /// An integer value.
+    Int(i64),
+    /// A floating point value.
+    Float(f64),
+    /// A string value.
+    String(String),
+}
+
+impl TileProperty {
+    /// Returns the value if it is a boolean.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::property::TileProperty;
+    ///
+    /// assert_eq!(TileProperty::Bool(true).as_bool(), Some(true));
+    /// assert_eq!(TileProperty::Int(1).as_bool(), None);
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TileProperty::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value if it is an integer.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::property::TileProperty;
+    ///
+    /// assert_eq!(TileProperty::Int(3).as_int(), Some(3));
+    /// assert_eq!(TileProperty::Float(3.0).as_int(), None);
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            TileProperty::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value if it is a number, converting integers.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::property::TileProperty;
+    ///
+    /// assert_eq!(TileProperty::Float(0.5).as_float(), Some(0.5));
+    /// assert_eq!(TileProperty::Int(2).as_float(), Some(2.0));
+    /// ```
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            TileProperty::Float(value) => Some(*value),
+            TileProperty::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value if it is a string.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::property::TileProperty;
+    ///
+    /// assert_eq!(TileProperty::from("grass").as_str(), Some("grass"));
+    /// assert_eq!(TileProperty::Bool(false).as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TileProperty::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for TileProperty {
+    fn from(value: bool) -> TileProperty {
+        TileProperty::Bool(value)
+    }
+}
+
+impl From<i32> for TileProperty {
+    fn from(value: i32) -> TileProperty {
+        TileProperty::Int(value.into())
+    }
+}
+
+impl From<i64> for TileProperty {
+    fn from(value: i64) -> TileProperty {
+        TileProperty::Int(value)
+    }
+}
+
+impl From<f32> for TileProperty {
+    fn from(value: f32) -> TileProperty {
+        TileProperty::Float(value.into())
+    }
+}
+
+impl From<f64> for TileProperty {
+    fn from(value: f64) -> TileProperty {
+        TileProperty::Float(value)
+    }
+}
+
+impl From<&str> for TileProperty {
+    fn from(value: &str) -> TileProperty {
+        TileProperty::String(value.to_string())
+    }
+}
+
+impl From<String> for TileProperty {
+    fn from(value: String) -> TileProperty {
+        TileProperty::String(value)
+    }
+}
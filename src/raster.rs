@@ -0,0 +1,179 @@
+//! Rasterizing the sprites of tiles onto an image, for exporting tilemaps as
+//! images.
+
+use crate::{chunk::RawTile, lib::*};
+
+/// An 8 bit RGBA or BGRA image which sprites are drawn onto.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Canvas {
+    /// The width in pixels.
+    width: usize,
+    /// The height in pixels.
+    height: usize,
+    /// The pixels of the canvas per pixel of the sprites.
+    scale: f32,
+    /// If the channels of the canvas and the sprites are in BGRA order.
+    bgra: bool,
+    /// The pixels with the rows from the top down.
+    data: Vec<u8>,
+}
+
+impl Canvas {
+    /// Constructs a transparent canvas.
+    pub(crate) fn new(width: usize, height: usize, scale: f32, bgra: bool) -> Canvas {
+        Canvas {
+            width,
+            height,
+            scale,
+            bgra,
+            data: vec![0; width * height * 4],
+        }
+    }
+
+    /// Draws a sprite of an atlas texture over the colors below it.
+    ///
+    /// The sprite is stretched from `min` to `max`, which are in pixels of
+    /// the sprites from the bottom left of the canvas. The sprite is flipped,
+    /// turned and tinted like the tile, and sampled at the nearest pixel.
+    pub(crate) fn draw_sprite(
+        &mut self,
+        texture: &Texture,
+        rect: &Rect,
+        min: Vec2,
+        max: Vec2,
+        tile: &RawTile,
+    ) {
+        let size = max - min;
+        let sprite_size = rect.max - rect.min;
+        if size.x <= 0.0 || size.y <= 0.0 || sprite_size.x <= 0.0 || sprite_size.y <= 0.0 {
+            return;
+        }
+        let texture_width = texture.size.width as usize;
+        let tint: [f32; 4] = tile.color.into();
+        let tint = if self.bgra {
+            [tint[2], tint[1], tint[0], tint[3]]
+        } else {
+            tint
+        };
+
+        let scale = self.scale;
+        let pixels = |start: f32, end: f32, len: usize| {
+            let start = (start * scale).floor().max(0.0) as usize;
+            let end = ((end * scale).ceil().max(0.0) as usize).min(len);
+            start..end
+        };
+        for y in pixels(min.y, max.y, self.height) {
+            let v = ((y as f32 + 0.5) / scale - min.y) / size.y;
+            if !(0.0..1.0).contains(&v) {
+                continue;
+            }
+            for x in pixels(min.x, max.x, self.width) {
+                let u = ((x as f32 + 0.5) / scale - min.x) / size.x;
+                if !(0.0..1.0).contains(&u) {
+                    continue;
+                }
+                let (s, t) = sprite_point(u, v, tile);
+                // The rows of the texture run from the top down.
+                let texel_x = (rect.min.x + s * sprite_size.x)
+                    .floor()
+                    .min(rect.max.x - 1.0) as usize;
+                let texel_y = (rect.min.y + (1.0 - t) * sprite_size.y)
+                    .floor()
+                    .min(rect.max.y - 1.0) as usize;
+                let texel_start = (texel_y * texture_width + texel_x) * 4;
+                let texel = match texture.data.get(texel_start..texel_start + 4) {
+                    Some(texel) => texel,
+                    None => continue,
+                };
+                let mut color = [0.0; 4];
+                for ((channel, texel), tint) in color.iter_mut().zip(texel).zip(tint.iter()) {
+                    *channel = f32::from(*texel) / 255.0 * tint;
+                }
+                let start = ((self.height - 1 - y) * self.width + x) * 4;
+                if let Some(pixel) = self.data.get_mut(start..start + 4) {
+                    blend(pixel, color);
+                }
+            }
+        }
+    }
+
+    /// Turns the canvas into a texture of a format.
+    pub(crate) fn into_texture(self, format: TextureFormat) -> Texture {
+        Texture::new(
+            Extent3d::new(self.width as u32, self.height as u32, 1),
+            TextureDimension::D2,
+            self.data,
+            format,
+        )
+    }
+}
+
+/// Returns the point of the sprite of a tile which is shown at a point of its
+/// quad, both from 0 to 1 from the bottom left.
+fn sprite_point(mut u: f32, mut v: f32, tile: &RawTile) -> (f32, f32) {
+    // Undoes the clockwise turns, which come after the flips.
+    for _ in 0..(tile.rotation & 0b11) {
+        let turned = (1.0 - v, u);
+        u = turned.0;
+        v = turned.1;
+    }
+    if tile.flip_x {
+        u = 1.0 - u;
+    }
+    if tile.flip_y {
+        v = 1.0 - v;
+    }
+    (u, v)
+}
+
+/// Blends a color over a pixel with straight alpha.
+fn blend(pixel: &mut [u8], color: [f32; 4]) {
+    let alpha = color[3];
+    let below_alpha = pixel
+        .get(3)
+        .map_or(0.0, |channel| f32::from(*channel) / 255.0);
+    let out_alpha = alpha + below_alpha * (1.0 - alpha);
+    if out_alpha <= 0.0 {
+        return;
+    }
+    for (index, (channel, color)) in pixel.iter_mut().zip(color.iter()).enumerate() {
+        let below = f32::from(*channel) / 255.0;
+        let value = if index == 3 {
+            out_alpha
+        } else {
+            (color * alpha + below * below_alpha * (1.0 - alpha)) / out_alpha
+        };
+        *channel = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite_point() {
+        let turned = RawTile {
+            rotation: 1,
+            ..Default::default()
+        };
+        // After a clockwise turn the bottom left of the sprite is at the top
+        // left of the quad.
+        assert_eq!(sprite_point(0.0, 1.0, &turned), (0.0, 0.0));
+        let flipped = RawTile {
+            flip_x: true,
+            ..Default::default()
+        };
+        assert_eq!(sprite_point(0.25, 0.5, &flipped), (0.75, 0.5));
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut pixel = [0, 0, 255, 255];
+        blend(&mut pixel, [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(pixel, [128, 0, 128, 255]);
+        // Transparent colors leave the pixel as is.
+        blend(&mut pixel, [0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(pixel, [128, 0, 128, 255]);
+    }
+}
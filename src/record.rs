@@ -0,0 +1,690 @@
+//! Recording and replaying of tilemap edits for reproducing bugs.
+//!
+//! When a map ends up wrong it is often hard to tell which edit caused it.
+//! A tilemap can record every edit made to its chunks, layers and tiles along
+//! with the frame it happened on with [`Tilemap::start_recording`]. The
+//! recording can be written to a compact file and later replayed onto a fresh
+//! tilemap, built with the same configuration, to deterministically reproduce
+//! the final map.
+//!
+//! Tiles changed through [`Tilemap::get_tile_mut`] can not be tracked and are
+//! not recorded.
+//!
+//! [`Tilemap::start_recording`]: crate::tilemap::Tilemap::start_recording
+//! [`Tilemap::get_tile_mut`]: crate::tilemap::Tilemap::get_tile_mut
+//!
+//! # Format
+//!
+//! All values are little endian.
+//!
+//! - The header is the magic bytes `BTMR`, the [`RECORDING_VERSION`] as a
+//!   `u16`, the width of a coordinate in bytes as a `u8`, the number of frames
+//!   and the number of events as `u32`s.
+//! - Each event is the frame as a `u32`, a `u8` tag then the fields of the
+//!   event. Points are coordinates, sprite orders and indices are `u32`s and
+//!   tiles are in the same format as a [stream].
+//!
+//! [stream]: crate::stream
+//!
+//! # Recording and replaying a tilemap
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, chunk::RawTile, record::TilemapRecording};
+//! use std::io::Cursor;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+//! tilemap.start_recording();
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (3, 3), sprite_index: 1, ..Default::default() }).unwrap();
+//! tilemap.clear_tile((3, 3), 0).unwrap();
+//! tilemap.insert_tile(Tile { point: (4, 4), sprite_index: 2, ..Default::default() }).unwrap();
+//!
+//! let mut bytes = Vec::new();
+//! tilemap.stop_recording().unwrap().write(&mut bytes).unwrap();
+//!
+//! let recording = TilemapRecording::read(Cursor::new(bytes)).unwrap();
+//! let mut replayed = Tilemap::new(texture_atlas_handle, 32, 32);
+//! recording.replay(&mut replayed).unwrap();
+//!
+//! assert_eq!(replayed.get_tile((3, 3), 0), None);
+//! assert_eq!(replayed.get_tile((4, 4), 0), Some(&RawTile { index: 2, ..Default::default() }));
+//! ```
+
+use crate::{
+    chunk::{LayerKind, RawTile},
+    lib::*,
+    stream::{
+        read_coord, read_coord_width, read_point2, read_raw_tile, read_u16, read_u32, read_u8,
+        write_coord_width, write_point2, write_raw_tile,
+    },
+    tile::Tile,
+    tilemap::{ErrorKind, Tilemap, TilemapLayer, TilemapResult},
+};
+
+/// The magic bytes at the start of every recording.
+const RECORDING_MAGIC: [u8; 4] = *b"BTMR";
+
+/// The current version of the recording format.
+pub const RECORDING_VERSION: u16 = 2;
+
+/// An edit made to a tilemap.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RecordedEvent {
+    /// A chunk was inserted.
+    InsertChunk {
+        /// The point of the chunk.
+        point: Point2,
+    },
+    /// A chunk was deleted.
+    RemoveChunk {
+        /// The point of the chunk.
+        point: Point2,
+    },
+    /// A chunk was spawned.
+    SpawnChunk {
+        /// The point of the chunk.
+        point: Point2,
+    },
+    /// A chunk was unloaded.
+    DespawnChunk {
+        /// The point of the chunk.
+        point: Point2,
+    },
+    /// A sprite layer was added.
+    AddLayer {
+        /// The layer that was added.
+        layer: TilemapLayer,
+        /// The sprite order of the layer.
+        sprite_order: usize,
+    },
+    /// A sprite layer was moved.
+    MoveLayer {
+        /// The sprite order the layer was moved from.
+        from_sprite_order: usize,
+        /// The sprite order the layer was moved to.
+        to_sprite_order: usize,
+    },
+    /// A sprite layer was removed.
+    RemoveLayer {
+        /// The sprite order of the layer.
+        sprite_order: usize,
+    },
+    /// A sprite layer was cleared of all tiles.
+    ClearLayer {
+        /// The sprite order of the layer.
+        sprite_order: usize,
+    },
+    /// Tiles were inserted.
+    InsertTiles {
+        /// The global point, sprite order and tile of each inserted tile.
+        tiles: Vec<(Point3, usize, RawTile)>,
+    },
+    /// Tiles were cleared.
+    ClearTiles {
+        /// The global point and sprite order of each cleared tile.
+        points: Vec<(Point3, usize)>,
+    },
+    /// The sprite indices of the main texture atlas were remapped.
+    RemapSpriteIndices {
+        /// The old and new sprite index of each remapped sprite.
+        remap: Vec<(usize, usize)>,
+    },
+    /// The dimensions of the chunks were changed.
+    SetChunkDimensions {
+        /// The new dimensions of the chunks.
+        dimensions: Dimension3,
+    },
+}
+
+impl RecordedEvent {
+    /// Applies the event to a tilemap.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the tilemap method the event replays, if any.
+    pub fn apply(&self, tilemap: &mut Tilemap) -> TilemapResult<()> {
+        use RecordedEvent::*;
+        match self {
+            InsertChunk { point } => tilemap.insert_chunk(*point),
+            RemoveChunk { point } => tilemap.delete_chunk(*point),
+            SpawnChunk { point } => tilemap.spawn_chunk(*point),
+            DespawnChunk { point } => tilemap.unload_chunk(*point),
+            AddLayer {
+                layer,
+                sprite_order,
+            } => tilemap.add_layer(*layer, *sprite_order),
+            MoveLayer {
+                from_sprite_order,
+                to_sprite_order,
+            } => tilemap.move_layer(*from_sprite_order, *to_sprite_order),
+            RemoveLayer { sprite_order } => {
+                tilemap.remove_layer(*sprite_order);
+                Ok(())
+            }
+            ClearLayer { sprite_order } => tilemap.clear_layer(*sprite_order),
+            InsertTiles { tiles } => {
+                tilemap.insert_tiles(tiles.iter().map(|(point, sprite_order, tile)| {
+                    Tile::from_raw_tile(*point, *sprite_order, tile)
+                }))
+            }
+            ClearTiles { points } => tilemap.clear_tiles(points.iter().copied()),
+            RemapSpriteIndices { remap } => {
+                let handle = tilemap.texture_atlas().clone();
+                tilemap.swap_texture_atlas(handle, &remap.iter().copied().collect());
+                Ok(())
+            }
+            SetChunkDimensions { dimensions } => {
+                tilemap.set_chunk_dimensions(dimensions.width, dimensions.height, dimensions.depth)
+            }
+        }
+    }
+
+    /// Writes the tag and fields of the event.
+    fn write<W: Write>(&self, writer: &mut W) -> TilemapResult<()> {
+        use RecordedEvent::*;
+        match self {
+            InsertChunk { point } => {
+                writer.write_all(&[0])?;
+                write_point2(writer, *point)?;
+            }
+            RemoveChunk { point } => {
+                writer.write_all(&[1])?;
+                write_point2(writer, *point)?;
+            }
+            SpawnChunk { point } => {
+                writer.write_all(&[2])?;
+                write_point2(writer, *point)?;
+            }
+            DespawnChunk { point } => {
+                writer.write_all(&[3])?;
+                write_point2(writer, *point)?;
+            }
+            AddLayer {
+                layer,
+                sprite_order,
+            } => {
+                let kind = match layer.kind {
+                    LayerKind::Dense => 0,
+                    LayerKind::Sparse => 1,
+                    LayerKind::RunLength => 2,
+                };
+                writer.write_all(&[4, kind])?;
+                write_usize(writer, *sprite_order)?;
+            }
+            MoveLayer {
+                from_sprite_order,
+                to_sprite_order,
+            } => {
+                writer.write_all(&[5])?;
+                write_usize(writer, *from_sprite_order)?;
+                write_usize(writer, *to_sprite_order)?;
+            }
+            RemoveLayer { sprite_order } => {
+                writer.write_all(&[6])?;
+                write_usize(writer, *sprite_order)?;
+            }
+            ClearLayer { sprite_order } => {
+                writer.write_all(&[7])?;
+                write_usize(writer, *sprite_order)?;
+            }
+            InsertTiles { tiles } => {
+                writer.write_all(&[8])?;
+                write_usize(writer, tiles.len())?;
+                for (point, sprite_order, tile) in tiles {
+                    write_point3(writer, *point)?;
+                    write_usize(writer, *sprite_order)?;
+                    write_raw_tile(writer, tile)?;
+                }
+            }
+            ClearTiles { points } => {
+                writer.write_all(&[9])?;
+                write_usize(writer, points.len())?;
+                for (point, sprite_order) in points {
+                    write_point3(writer, *point)?;
+                    write_usize(writer, *sprite_order)?;
+                }
+            }
+            RemapSpriteIndices { remap } => {
+                writer.write_all(&[10])?;
+                write_usize(writer, remap.len())?;
+                for (from, to) in remap {
+                    write_usize(writer, *from)?;
+                    write_usize(writer, *to)?;
+                }
+            }
+            SetChunkDimensions { dimensions } => {
+                writer.write_all(&[11])?;
+                writer.write_all(&dimensions.width.to_le_bytes())?;
+                writer.write_all(&dimensions.height.to_le_bytes())?;
+                writer.write_all(&dimensions.depth.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the tag and fields of an event.
+    ///
+    /// The lengths come from the file and can not be trusted, so nothing is
+    /// allocated up front from them.
+    fn read<R: Read>(reader: &mut R) -> TilemapResult<RecordedEvent> {
+        use RecordedEvent::*;
+        let event = match read_u8(reader)? {
+            0 => InsertChunk {
+                point: read_point2(reader)?,
+            },
+            1 => RemoveChunk {
+                point: read_point2(reader)?,
+            },
+            2 => SpawnChunk {
+                point: read_point2(reader)?,
+            },
+            3 => DespawnChunk {
+                point: read_point2(reader)?,
+            },
+            4 => {
+                let kind = match read_u8(reader)? {
+                    0 => LayerKind::Dense,
+                    1 => LayerKind::Sparse,
+                    2 => LayerKind::RunLength,
+                    _ => return Err(ErrorKind::InvalidRecording.into()),
+                };
+                AddLayer {
+                    layer: TilemapLayer { kind },
+                    sprite_order: read_usize(reader)?,
+                }
+            }
+            5 => MoveLayer {
+                from_sprite_order: read_usize(reader)?,
+                to_sprite_order: read_usize(reader)?,
+            },
+            6 => RemoveLayer {
+                sprite_order: read_usize(reader)?,
+            },
+            7 => ClearLayer {
+                sprite_order: read_usize(reader)?,
+            },
+            8 => {
+                let len = read_usize(reader)?;
+                let mut tiles = Vec::new();
+                for _ in 0..len {
+                    let point = read_point3(reader)?;
+                    let sprite_order = read_usize(reader)?;
+                    tiles.push((point, sprite_order, read_raw_tile(reader)?));
+                }
+                InsertTiles { tiles }
+            }
+            9 => {
+                let len = read_usize(reader)?;
+                let mut points = Vec::new();
+                for _ in 0..len {
+                    points.push((read_point3(reader)?, read_usize(reader)?));
+                }
+                ClearTiles { points }
+            }
+            10 => {
+                let len = read_usize(reader)?;
+                let mut remap = Vec::new();
+                for _ in 0..len {
+                    remap.push((read_usize(reader)?, read_usize(reader)?));
+                }
+                RemapSpriteIndices { remap }
+            }
+            11 => SetChunkDimensions {
+                dimensions: Dimension3::new(
+                    read_u32(reader)?,
+                    read_u32(reader)?,
+                    read_u32(reader)?,
+                ),
+            },
+            _ => return Err(ErrorKind::InvalidRecording.into()),
+        };
+        Ok(event)
+    }
+}
+
+/// A recording of the edits made to a tilemap by frame.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct TilemapRecording {
+    /// The number of frames that have passed while recording.
+    frame_count: u32,
+    /// The events in the order they happened along with their frame.
+    events: Vec<(u32, RecordedEvent)>,
+}
+
+impl TilemapRecording {
+    /// Records an event on the current frame.
+    pub(crate) fn push(&mut self, event: RecordedEvent) {
+        self.events.push((self.frame_count, event));
+    }
+
+    /// Moves on to the next frame.
+    pub(crate) fn next_frame(&mut self) {
+        self.frame_count += 1;
+    }
+
+    /// Returns the number of frames that passed while recording.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::record::TilemapRecording;
+    ///
+    /// let recording = TilemapRecording::default();
+    ///
+    /// assert_eq!(recording.frame_count(), 0);
+    /// ```
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// Returns the recorded events in order along with the frame they
+    /// happened on.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, point::Point2, record::RecordedEvent};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.start_recording();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let recording = tilemap.stop_recording().unwrap();
+    /// assert_eq!(
+    ///     recording.events(),
+    ///     &[(0, RecordedEvent::InsertChunk { point: Point2::new(0, 0) })]
+    /// );
+    /// ```
+    pub fn events(&self) -> &[(u32, RecordedEvent)] {
+        &self.events
+    }
+
+    /// Writes the recording in the recording format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::record::TilemapRecording;
+    ///
+    /// let mut bytes = Vec::new();
+    /// TilemapRecording::default().write(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(&bytes[..4], b"BTMR");
+    /// ```
+    pub fn write<W: Write>(&self, mut writer: W) -> TilemapResult<()> {
+        writer.write_all(&RECORDING_MAGIC)?;
+        writer.write_all(&RECORDING_VERSION.to_le_bytes())?;
+        write_coord_width(&mut writer)?;
+        writer.write_all(&self.frame_count.to_le_bytes())?;
+        write_usize(&mut writer, self.events.len())?;
+        for (frame, event) in self.events.iter() {
+            writer.write_all(&frame.to_le_bytes())?;
+            event.write(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a recording in the recording format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, the data is not a recording, the
+    /// version of the recording format is not supported or its coordinates
+    /// have another width than the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::record::TilemapRecording;
+    /// use std::io::Cursor;
+    ///
+    /// let mut bytes = Vec::new();
+    /// TilemapRecording::default().write(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(TilemapRecording::read(Cursor::new(bytes)).unwrap(), TilemapRecording::default());
+    /// assert!(TilemapRecording::read(Cursor::new(b"BTMS")).is_err());
+    /// ```
+    pub fn read<R: Read>(mut reader: R) -> TilemapResult<TilemapRecording> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != RECORDING_MAGIC {
+            return Err(ErrorKind::InvalidRecording.into());
+        }
+        let version = read_u16(&mut reader)?;
+        if version != RECORDING_VERSION {
+            return Err(ErrorKind::UnsupportedRecordingVersion(version).into());
+        }
+        read_coord_width(&mut reader)?;
+        let frame_count = read_u32(&mut reader)?;
+        let len = read_usize(&mut reader)?;
+        let mut events = Vec::new();
+        for _ in 0..len {
+            let frame = read_u32(&mut reader)?;
+            events.push((frame, RecordedEvent::read(&mut reader)?));
+        }
+
+        Ok(TilemapRecording {
+            frame_count,
+            events,
+        })
+    }
+
+    /// Applies every recorded event to a tilemap in order.
+    ///
+    /// The tilemap should be freshly built with the same configuration as the
+    /// recorded one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of an event that fails to apply.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+    /// tilemap.start_recording();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let recording = tilemap.stop_recording().unwrap();
+    ///
+    /// let mut replayed = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// recording.replay(&mut replayed).unwrap();
+    ///
+    /// assert!(replayed.contains_chunk((0, 0)));
+    /// ```
+    pub fn replay(&self, tilemap: &mut Tilemap) -> TilemapResult<()> {
+        for (_, event) in self.events.iter() {
+            event.apply(tilemap)?;
+        }
+        Ok(())
+    }
+
+    /// Applies the events recorded on a single frame to a tilemap in order.
+    ///
+    /// Replaying each frame in turn from a system reproduces the timing of
+    /// the edits relative to the tilemap systems.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of an event that fails to apply.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+    /// tilemap.start_recording();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let recording = tilemap.stop_recording().unwrap();
+    ///
+    /// let mut replayed = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// recording.replay_frame(&mut replayed, 1).unwrap();
+    /// assert!(!replayed.contains_chunk((0, 0)));
+    ///
+    /// recording.replay_frame(&mut replayed, 0).unwrap();
+    /// assert!(replayed.contains_chunk((0, 0)));
+    /// ```
+    pub fn replay_frame(&self, tilemap: &mut Tilemap, frame: u32) -> TilemapResult<()> {
+        let start = self.events.partition_point(|(f, _)| *f < frame);
+        let events = self.events.iter().skip(start);
+        for (_, event) in events.take_while(|(f, _)| *f == frame) {
+            event.apply(tilemap)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a `usize` stored as a little endian `u32`.
+fn read_usize<R: Read>(reader: &mut R) -> TilemapResult<usize> {
+    Ok(read_u32(reader)? as usize)
+}
+
+/// Writes a `usize` as a little endian `u32`.
+fn write_usize<W: Write>(writer: &mut W, value: usize) -> TilemapResult<()> {
+    writer.write_all(&(value as u32).to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads a point as three coordinates.
+fn read_point3<R: Read>(reader: &mut R) -> TilemapResult<Point3> {
+    let point = read_point2(reader)?;
+    Ok(Point3::new(point.x, point.y, read_coord(reader)?))
+}
+
+/// Writes a point as three coordinates.
+fn write_point3<W: Write>(writer: &mut W, point: Point3) -> TilemapResult<()> {
+    write_point2(writer, Point2::new(point.x, point.y))?;
+    writer.write_all(&point.z.to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tilemap() -> Tilemap {
+        Tilemap::new(Handle::weak(HandleId::random::<TextureAtlas>()), 32, 32)
+    }
+
+    fn tile(point: (Coord, Coord), sprite_index: usize) -> Tile<Point3> {
+        Tile {
+            point: Point3::new(point.0, point.1, 0),
+            sprite_index,
+            ..Default::default()
+        }
+    }
+
+    /// Writes a recording and reads it back.
+    fn round_trip(recording: &TilemapRecording) -> TilemapResult<TilemapRecording> {
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes)?;
+        TilemapRecording::read(&bytes[..])
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut tilemap = tilemap();
+        tilemap.start_recording();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        tilemap.add_layer(TilemapLayer::default(), 1).unwrap();
+        tilemap
+            .insert_tiles(vec![tile((1, 1), 1), tile((2, 2), 2), tile((40, 0), 3)])
+            .unwrap();
+        tilemap.clear_tile((2, 2), 0).unwrap();
+        tilemap.move_layer(1, 2).unwrap();
+        tilemap.unload_chunk((0, 0)).unwrap();
+        tilemap.delete_chunk((1, 0)).unwrap();
+        let recording = tilemap.stop_recording().unwrap();
+
+        let read = round_trip(&recording).unwrap();
+        assert_eq!(read, recording);
+
+        let mut replayed = self::tilemap();
+        read.replay(&mut replayed).unwrap();
+        assert_eq!(replayed.get_tile((1, 1), 0).map(|tile| tile.index), Some(1));
+        assert_eq!(replayed.get_tile((2, 2), 0), None);
+        assert!(!replayed.contains_chunk((1, 0)));
+        assert_eq!(replayed.get_tile((1, 1), 0), tilemap.get_tile((1, 1), 0));
+    }
+
+    #[test]
+    fn test_replay_frame() {
+        let mut recording = TilemapRecording::default();
+        recording.push(RecordedEvent::InsertChunk {
+            point: Point2::new(0, 0),
+        });
+        recording.next_frame();
+        recording.push(RecordedEvent::InsertTiles {
+            tiles: vec![(Point3::new(3, 3, 0), 0, RawTile::default())],
+        });
+        recording.push(RecordedEvent::SetChunkDimensions {
+            dimensions: Dimension3::new(8, 8, 1),
+        });
+        let recording = round_trip(&recording).unwrap();
+        assert_eq!(recording.frame_count(), 1);
+
+        let mut tilemap = tilemap();
+        recording.replay_frame(&mut tilemap, 0).unwrap();
+        assert!(tilemap.contains_chunk((0, 0)));
+        assert_eq!(tilemap.get_tile((3, 3), 0), None);
+        recording.replay_frame(&mut tilemap, 1).unwrap();
+        assert!(tilemap.get_tile((3, 3), 0).is_some());
+        assert_eq!(tilemap.chunk_dimensions(), Dimension3::new(8, 8, 1));
+    }
+
+    #[test]
+    fn test_read_truncated_recording() {
+        let mut recording = TilemapRecording::default();
+        recording.push(RecordedEvent::ClearTiles {
+            points: vec![(Point3::new(1, 2, 0), 0), (Point3::new(3, 4, 0), 0)],
+        });
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+
+        for len in 0..bytes.len() {
+            assert!(TilemapRecording::read(&bytes[..len]).is_err());
+        }
+        assert!(TilemapRecording::read(&bytes[..]).is_ok());
+    }
+
+    #[test]
+    fn test_read_bad_length() {
+        let mut recording = TilemapRecording::default();
+        recording.push(RecordedEvent::RemapSpriteIndices {
+            remap: vec![(1, 2)],
+        });
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+
+        // The number of events, then the length of the remap after the frame
+        // and tag of the event.
+        let events_len = bytes.len() - 4 - 4 - 1 - 4 - 8;
+        let remap_len = events_len + 4 + 4 + 1;
+        for offset in [events_len, remap_len].iter() {
+            let mut bytes = bytes.clone();
+            bytes[*offset..*offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(TilemapRecording::read(&bytes[..]).is_err());
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Tilemaps as assets which can be placed in Bevy scenes.
+//!
+//! A [`TilemapScene`] holds a tilemap with its layers, chunks and tiles, along
+//! with the [`Tileset`] whose texture atlas it is drawn with. It is made from a
+//! tilemap with [`TilemapScene::new`] and added to the `Assets<TilemapScene>`
+//! like any other asset.
+//!
+//! Tilemaps are saved as RON with [`TilemapScene::to_ron`] in files with the
+//! `.tilemap` extension, along with the path of their tileset, and loading the
+//! file with the asset server gives a [`TilemapScene`] asset. Maps made in an
+//! editor thereby become assets which are loaded rather than built in code.
+//!
+//! An entity with a `Handle<TilemapScene>` and no tilemap gets the tilemap of
+//! the scene inserted once the scene and the texture atlas of its tileset are
+//! loaded, along with a visible and a transform if it has none. The handle is
+//! registered for reflection, so the entity can be saved in and loaded from a
+//! `DynamicScene` like any other, which keeps the tiles out of the scene file
+//! and lets scene based editors place tilemaps. The chunks are spawned by auto
+//! spawn or with [`Tilemap::spawn_chunk`] as usual.
+//!
+//! The chunks which were spawned when the tilemap was saved are spawned
+//! again. The texture atlas handle of the tilemap is not saved, so a scene
+//! without a tileset is inserted with a default handle which needs to be
+//! replaced with [`Tilemap::set_texture_atlas`].
+//!
+//! The scenes are hot reloaded. When a `.tilemap` file changes while the
+//! asset server watches for changes, the changes are applied to the live
+//! tilemaps of its entities, so a level can be edited while the game runs.
+//! Only the chunks whose tiles changed are re-rendered, chunks which are gone
+//! are deleted and the saved spawned chunks which are new are spawned. If the
+//! chunks are laid out or drawn differently, such as with another chunk size
+//! or other layers, every chunk is replaced instead.
+//!
+//! [`Tileset`]: crate::tileset::Tileset
+//! [`Tilemap::spawn_chunk`]: crate::tilemap::Tilemap::spawn_chunk
+//! [`Tilemap::set_texture_atlas`]: crate::tilemap::Tilemap::set_texture_atlas
+//!
+//! # Placing a saved tilemap in a scene
+//! ```no_run
+//! use bevy_app::prelude::*;
+//! use bevy_asset::prelude::*;
+//! use bevy_ecs::prelude::*;
+//! use bevy_tilemap::{prelude::*, scene::TilemapScene};
+//! use bevy_transform::prelude::*;
+//!
+//! fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     let scene: Handle<TilemapScene> = asset_server.load("maps/village.tilemap");
+//!     commands
+//!         .spawn()
+//!         .insert(scene)
+//!         .insert(Transform::from_xyz(0.0, 0.0, -1.0));
+//! }
+//!
+//! App::build()
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_startup_system(setup.system())
+//!     .run()
+//! ```
+
+use crate::{
+    lib::*,
+    tilemap::{ErrorKind, TilemapResult},
+    tileset::Tileset,
+    Tilemap,
+};
+
+/// A tilemap scene file with a borrowed tilemap, to be saved.
+#[derive(Serialize)]
+struct TilemapSceneFileRef<'a> {
+    /// The path of the tileset of the tilemap.
+    tileset: Option<&'a str>,
+    /// The tilemap.
+    tilemap: &'a Tilemap,
+}
+
+/// A tilemap scene file, as stored in a `.tilemap` file.
+#[derive(Deserialize)]
+struct TilemapSceneFile {
+    /// The path of the tileset of the tilemap.
+    tileset: Option<String>,
+    /// The tilemap.
+    tilemap: Tilemap,
+}
+
+impl TilemapSceneFile {
+    /// Parses a tilemap scene file.
+    fn from_bytes(bytes: &[u8]) -> TilemapResult<TilemapSceneFile> {
+        from_ron_bytes(bytes).map_err(|_| ErrorKind::InvalidScene.into())
+    }
+}
+
+/// A tilemap which can be placed in Bevy scenes, made in code or loaded from
+/// a `.tilemap` file.
+#[derive(Debug)]
+pub struct TilemapScene {
+    /// The tileset whose texture atlas the tilemap is drawn with.
+    tileset: Option<Handle<Tileset>>,
+    /// The RON of the scene file, which a new tilemap is read from for every
+    /// entity.
+    bytes: Vec<u8>,
+}
+
+impl TypeUuid for TilemapScene {
+    const TYPE_UUID: Uuid = Uuid::from_u128(61915438162940380264839047136750386321);
+}
+
+impl TilemapScene {
+    /// Makes a scene of a tilemap and the tileset it is drawn with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tilemap can not be serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, scene::TilemapScene};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 2, ..Default::default() }).unwrap();
+    ///
+    /// let scene = TilemapScene::new(&tilemap, None).unwrap();
+    /// let mut copy = scene.tilemap().unwrap();
+    /// assert_eq!(copy.get_tile((1, 1), 0).unwrap().index, 2);
+    /// ```
+    pub fn new(tilemap: &Tilemap, tileset: Option<Handle<Tileset>>) -> TilemapResult<TilemapScene> {
+        let ron = TilemapScene::to_ron(tilemap, None)?;
+        Ok(TilemapScene {
+            tileset,
+            bytes: ron.into_bytes(),
+        })
+    }
+
+    /// Saves a tilemap and the path of its tileset, relative to the assets
+    /// folder, as the RON of a `.tilemap` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tilemap can not be serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, scene::TilemapScene};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 2, ..Default::default() }).unwrap();
+    ///
+    /// let ron = TilemapScene::to_ron(&tilemap, Some("square.tileset")).unwrap();
+    /// assert!(ron.contains("square.tileset"));
+    /// ```
+    pub fn to_ron(tilemap: &Tilemap, tileset: Option<&str>) -> TilemapResult<String> {
+        let file = TilemapSceneFileRef { tileset, tilemap };
+        to_ron_string_pretty(&file, PrettyConfig::default())
+            .map_err(|_| ErrorKind::InvalidScene.into())
+    }
+
+    /// Returns the tileset whose texture atlas the tilemap is drawn with, if
+    /// the scene has one.
+    pub fn tileset(&self) -> Option<&Handle<Tileset>> {
+        self.tileset.as_ref()
+    }
+
+    /// Returns a new tilemap read from the scene, without a texture atlas.
+    ///
+    /// The chunks which were spawned when it was saved are waiting to be
+    /// spawned again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scene is not a valid tilemap scene.
+    pub fn tilemap(&self) -> TilemapResult<Tilemap> {
+        let mut tilemap = TilemapSceneFile::from_bytes(&self.bytes)?.tilemap;
+        tilemap.respawn_saved_chunks();
+        Ok(tilemap)
+    }
+
+    /// Returns the texture atlas of the tileset of the scene, or `None` if it
+    /// has no tileset or the texture atlas is not built yet.
+    fn texture_atlas(&self, tilesets: &Assets<Tileset>) -> Option<Handle<TextureAtlas>> {
+        let tileset = tilesets.get(self.tileset.as_ref()?)?;
+        tileset.texture_atlas().cloned()
+    }
+}
+
+/// Loads a [`TilemapScene`] from a RON `.tilemap` file.
+#[derive(Default)]
+pub(crate) struct TilemapSceneLoader;
+
+impl AssetLoader for TilemapSceneLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), AnyhowError>> {
+        Box::pin(async move {
+            let file = TilemapSceneFile::from_bytes(bytes)?;
+            let mut dependencies = Vec::new();
+            let tileset = file.tileset.map(|path| {
+                let path = AssetPath::from(path.as_str()).to_owned();
+                dependencies.push(path.clone());
+                load_context.get_handle(path)
+            });
+            let scene = TilemapScene {
+                tileset,
+                bytes: bytes.to_vec(),
+            };
+            load_context.set_default_asset(LoadedAsset::new(scene).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tilemap"]
+    }
+}
+
+/// Inserts the tilemaps of the entities with a tilemap scene once the scene
+/// and the texture atlas of its tileset are loaded, and replaces them when
+/// the scene is modified.
+pub(crate) fn tilemap_scene_spawn(
+    mut commands: Commands,
+    mut scene_events: EventReader<AssetEvent<TilemapScene>>,
+    scenes: Res<Assets<TilemapScene>>,
+    tilesets: Res<Assets<Tileset>>,
+    new_query: Query<
+        (
+            Entity,
+            &Handle<TilemapScene>,
+            Option<&Visible>,
+            Option<&Transform>,
+        ),
+        Without<Tilemap>,
+    >,
+    mut reload_query: Query<(&Handle<TilemapScene>, &mut Tilemap)>,
+) {
+    for (entity, handle, visible, transform) in new_query.iter() {
+        let scene = match scenes.get(handle) {
+            Some(scene) => scene,
+            None => continue,
+        };
+        let texture_atlas = scene.texture_atlas(&tilesets);
+        // Waits for the texture atlas of the tileset to be built.
+        if scene.tileset().is_some() && texture_atlas.is_none() {
+            continue;
+        }
+        let mut tilemap = match scene.tilemap() {
+            Ok(tilemap) => tilemap,
+            Err(e) => {
+                // Reading the scene again next frame would fail the same way.
+                error!("{}", e);
+                commands.entity(entity).remove::<Handle<TilemapScene>>();
+                continue;
+            }
+        };
+        if let Some(texture_atlas) = texture_atlas {
+            tilemap.set_texture_atlas(texture_atlas);
+        }
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(tilemap);
+        if visible.is_none() {
+            entity_commands.insert(Visible {
+                is_visible: true,
+                is_transparent: true,
+            });
+        }
+        if transform.is_none() {
+            entity_commands.insert_bundle((Transform::default(), GlobalTransform::default()));
+        }
+    }
+
+    let modified = scene_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle.clone_weak()),
+            _ => None,
+        })
+        .collect::<HashSet<Handle<TilemapScene>>>();
+    if modified.is_empty() {
+        return;
+    }
+    for (handle, mut tilemap) in reload_query.iter_mut() {
+        if !modified.contains(handle) {
+            continue;
+        }
+        let scene = match scenes.get(handle) {
+            Some(scene) => scene,
+            None => continue,
+        };
+        let reloaded = match scene.tilemap() {
+            Ok(reloaded) => reloaded,
+            Err(e) => {
+                error!("{}", e);
+                continue;
+            }
+        };
+        tilemap.reload(reloaded);
+        if let Some(texture_atlas) = scene.texture_atlas(&tilesets) {
+            tilemap.set_texture_atlas(texture_atlas);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+
+    #[test]
+    fn test_scene_tilemap() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tile = Tile {
+            point: (1, 1),
+            sprite_index: 2,
+            ..Default::default()
+        };
+        tilemap.insert_tile(tile).unwrap();
+
+        let scene = TilemapScene::new(&tilemap, None).unwrap();
+        assert!(scene.tileset().is_none());
+        let mut loaded = scene.tilemap().unwrap();
+        assert_eq!(loaded.get_tile((1, 1), 0), tilemap.get_tile((1, 1), 0));
+        assert_eq!(loaded.chunk_dimensions(), tilemap.chunk_dimensions());
+
+        let broken = TilemapScene {
+            tileset: None,
+            bytes: b"(tilemap: 4)".to_vec(),
+        };
+        assert!(broken.tilemap().is_err());
+    }
+
+    #[test]
+    fn test_scene_round_trip() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tile = Tile {
+            point: (1, 1),
+            sprite_index: 2,
+            ..Default::default()
+        };
+        tilemap.insert_tile(tile).unwrap();
+
+        let ron = TilemapScene::to_ron(&tilemap, Some("square.tileset")).unwrap();
+        let file = TilemapSceneFile::from_bytes(ron.as_bytes()).unwrap();
+        assert_eq!(file.tileset, Some("square.tileset".to_string()));
+        let mut loaded = file.tilemap;
+        assert_eq!(loaded.get_tile((1, 1), 0), tilemap.get_tile((1, 1), 0));
+        assert_eq!(loaded.chunk_dimensions(), tilemap.chunk_dimensions());
+
+        assert!(TilemapSceneFile::from_bytes(b"(tilemap: 4)").is_err());
+    }
+
+    #[test]
+    fn test_scene_annotations() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.set_annotation((4, 2), "boss spawns here");
+
+        let ron = TilemapScene::to_ron(&tilemap, None).unwrap();
+        let loaded = TilemapSceneFile::from_bytes(ron.as_bytes())
+            .unwrap()
+            .tilemap;
+        assert_eq!(loaded.annotation((4, 2)), Some("boss spawns here"));
+        assert_eq!(loaded.annotations().count(), 1);
+    }
+}
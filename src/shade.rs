@@ -0,0 +1,118 @@
+//! Ambient occlusion which darkens the tiles next to walls.
+//!
+//! With [`TilemapBuilder::ambient_occlusion`] the corners of every tile of a
+//! spawned chunk are darkened by the walls touching them, where a wall is any
+//! tile of the wall layer. The shades of the corners blend over the tiles, so
+//! the floor darkens softly towards the walls, which gives dungeons some depth
+//! without any lights. The walls themselves are not darkened.
+//!
+//! The neighbours of the tiles at the edge of a chunk are looked up in the
+//! chunks next to it, so the shading is seamless across chunks. The shading
+//! is made for square grids and is only updated automatically when chunks
+//! spawn, so [`Tilemap::refresh_ambient_occlusion`] needs to be called after
+//! changing the tiles of the wall layer.
+//!
+//! [`TilemapBuilder::ambient_occlusion`]: crate::tilemap::TilemapBuilder::ambient_occlusion
+//! [`Tilemap::refresh_ambient_occlusion`]: crate::tilemap::Tilemap::refresh_ambient_occlusion
+//!
+//! # Shading a corridor
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, shade::AmbientOcclusion};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .auto_chunk()
+//!     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+//!     .ambient_occlusion(AmbientOcclusion { sprite_order: 1, strength: 0.6 })
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.insert_tile(Tile { point: (0, 1), sprite_order: 1, ..Default::default() }).unwrap();
+//!
+//! // The top corners of the tile below the wall are darkened.
+//! let shade = tilemap.shade_at((0, 0)).unwrap();
+//! assert!(shade[1] < 1.0 && shade[2] < 1.0);
+//! assert_eq!(shade[0], 1.0);
+//! assert_eq!(tilemap.shade_at((0, 1)), Some([1.0; 4]));
+//! ```
+
+use crate::lib::*;
+
+/// The ambient occlusion of a tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AmbientOcclusion {
+    /// The sprite order of the layer whose tiles are walls.
+    pub sprite_order: usize,
+    /// How much a corner enclosed by walls is darkened, from 0.0 to 1.0.
+    pub strength: f32,
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> AmbientOcclusion {
+        AmbientOcclusion {
+            sprite_order: 0,
+            strength: 0.5,
+        }
+    }
+}
+
+/// The offsets of the corners of a tile in the order of its vertices.
+const CORNERS: [(Coord, Coord); 4] = [(-1, -1), (-1, 1), (1, 1), (1, -1)];
+
+/// Returns the shade of the corners of a tile in the order of its vertices.
+///
+/// A corner is darkened by the walls of the two sides and the diagonal
+/// touching it, and fully if both sides are walls. A wall itself is not
+/// darkened.
+pub(crate) fn tile_shade<W>(point: Point2, strength: f32, mut is_wall: W) -> [f32; 4]
+where
+    W: FnMut(Point2) -> bool,
+{
+    let mut shade = [1.0; 4];
+    if is_wall(point) {
+        return shade;
+    }
+    for (corner, (x, y)) in shade.iter_mut().zip(CORNERS.iter()) {
+        let side_x = is_wall(Point2::new(point.x + x, point.y));
+        let side_y = is_wall(Point2::new(point.x, point.y + y));
+        let occluders = if side_x && side_y {
+            3
+        } else {
+            u32::from(side_x)
+                + u32::from(side_y)
+                + u32::from(is_wall(Point2::new(point.x + x, point.y + y)))
+        };
+        *corner = 1.0 - strength * occluders as f32 / 3.0;
+    }
+    shade
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_shade() {
+        let walls = [Point2::new(1, 0), Point2::new(0, 1), Point2::new(-1, -1)];
+        let is_wall = |point: Point2| walls.contains(&point);
+
+        let shade = tile_shade(Point2::new(0, 0), 0.6, is_wall);
+        // Both sides of the top right corner are walls.
+        assert!((shade[2] - 0.4).abs() < 1e-6);
+        // The top left and bottom right corners have one side.
+        assert!((shade[1] - 0.8).abs() < 1e-6);
+        assert!((shade[3] - 0.8).abs() < 1e-6);
+        // The bottom left corner only has the diagonal.
+        assert!((shade[0] - 0.8).abs() < 1e-6);
+
+        assert_eq!(tile_shade(Point2::new(1, 0), 0.6, is_wall), [1.0; 4]);
+        assert_eq!(tile_shade(Point2::new(5, 5), 0.6, is_wall), [1.0; 4]);
+    }
+}
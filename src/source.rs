@@ -0,0 +1,146 @@
+//! A minimal read only view of tiles for other crates.
+//!
+//! A [`TileSource`] is all a minimap renderer, a pathfinding crate or a
+//! viewer of generated maps needs to read the tiles of a map, without
+//! depending on the rest of the API of the tilemap. [`Tilemap`] implements
+//! it, and so can other tile based maps, so tools written against it work
+//! with any of them.
+//!
+//! The trait only takes and returns the [`Point2`], [`Point3`],
+//! [`Dimension2`] and [`RawTile`] types, and only gains new methods with
+//! defaults, so it stays stable across releases.
+//!
+//! [`Tilemap`]: crate::tilemap::Tilemap
+//! [`Dimension2`]: crate::dimension::Dimension2
+//! [`RawTile`]: crate::chunk::RawTile
+//!
+//! # Drawing a minimap of any map
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     point::{Coord, Point3},
+//!     prelude::*,
+//!     source::TileSource,
+//! };
+//!
+//! // Only depends on the trait, not on the tilemap.
+//! fn minimap(source: &dyn TileSource) -> Vec<bool> {
+//!     let dimensions = match source.dimensions() {
+//!         Some(dimensions) => dimensions,
+//!         None => return Vec::new(),
+//!     };
+//!     let origin = source.origin();
+//!     let mut pixels = Vec::new();
+//!     for y in 0..dimensions.height as Coord {
+//!         for x in 0..dimensions.width as Coord {
+//!             let point = Point3::new(origin.x + x, origin.y + y, 0);
+//!             pixels.push(source.tile_at(point, 0).is_some());
+//!         }
+//!     }
+//!     pixels
+//! }
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .dimensions(1, 1)
+//!     .chunk_dimensions(4, 4, 1)
+//!     .texture_dimensions(32, 32)
+//!     .finish()
+//!     .unwrap();
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (-2, -2), ..Default::default() }).unwrap();
+//!
+//! let pixels = minimap(&tilemap);
+//! assert_eq!(pixels.len(), 16);
+//! assert_eq!(pixels.iter().filter(|pixel| **pixel).count(), 1);
+//! assert!(pixels[0]);
+//! ```
+
+use crate::{chunk::RawTile, lib::*, Tilemap};
+
+/// A map of tiles which can be read by other crates.
+pub trait TileSource {
+    /// Returns the dimensions of the map in tiles, or `None` if it has no
+    /// fixed dimensions and grows as tiles are added.
+    fn dimensions(&self) -> Option<Dimension2>;
+
+    /// Returns the bottom left point of the dimensions of the map.
+    ///
+    /// The default is the origin, for maps whose points start at 0.
+    fn origin(&self) -> Point2 {
+        Point2::new(0, 0)
+    }
+
+    /// Returns the tile at a point, where the z of the point is its z depth,
+    /// in the sprite layer of a sprite order, or `None` if there is no tile.
+    fn tile_at(&self, point: Point3, sprite_order: usize) -> Option<RawTile>;
+}
+
+impl TileSource for Tilemap {
+    fn dimensions(&self) -> Option<Dimension2> {
+        let (min, max) = self.point_bounds()?;
+        Some(Dimension2::new(
+            (max.x - min.x + 1) as u32,
+            (max.y - min.y + 1) as u32,
+        ))
+    }
+
+    fn origin(&self) -> Point2 {
+        self.point_bounds()
+            .map_or(Point2::new(0, 0), |(min, _max)| min)
+    }
+
+    fn tile_at(&self, point: Point3, sprite_order: usize) -> Option<RawTile> {
+        self.raw_tile(point, sprite_order).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tile::Tile, tilemap::TilemapBuilder};
+
+    #[test]
+    fn test_tilemap_source() {
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .dimensions(3, 1)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap
+            .insert_tile(Tile {
+                point: (-6, -2),
+                sprite_index: 5,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let source: &dyn TileSource = &tilemap;
+        assert_eq!(source.dimensions(), Some(Dimension2::new(12, 4)));
+        assert_eq!(source.origin(), Point2::new(-6, -2));
+        assert_eq!(
+            source
+                .tile_at(Point3::new(-6, -2, 0), 0)
+                .map(|tile| tile.index),
+            Some(5)
+        );
+        assert_eq!(source.tile_at(Point3::new(-5, -2, 0), 0), None);
+        assert_eq!(source.tile_at(Point3::new(-6, -2, 0), 1), None);
+
+        // A tilemap without dimensions grows from the origin.
+        let tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .finish()
+            .unwrap();
+        assert_eq!(TileSource::dimensions(&tilemap), None);
+        assert_eq!(TileSource::origin(&tilemap), Point2::new(0, 0));
+    }
+}
@@ -0,0 +1,70 @@
+//! Policies which decide which chunks may be spawned and despawned
+//! automatically.
+//!
+//! A [`ChunkSpawnPolicy`] set with [`Tilemap::set_spawn_policy`] is consulted
+//! by the auto spawn system before it spawns or despawns a chunk. This allows
+//! keeping chunks around a town spawned no matter where the camera is, or to
+//! never spawn chunks outside of a story region. Spawning and despawning
+//! chunks by hand is not affected.
+//!
+//! [`Tilemap::set_spawn_policy`]: crate::tilemap::Tilemap::set_spawn_policy
+//!
+//! # Restricting auto spawn to a region
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{point::Point2, prelude::*, spawn_policy::ChunkSpawnPolicy};
+//!
+//! struct StoryRegion {
+//!     min: Point2,
+//!     max: Point2,
+//! }
+//!
+//! impl ChunkSpawnPolicy for StoryRegion {
+//!     fn can_spawn(&self, point: Point2) -> bool {
+//!         point.x >= self.min.x
+//!             && point.y >= self.min.y
+//!             && point.x <= self.max.x
+//!             && point.y <= self.max.y
+//!     }
+//! }
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .auto_spawn(2, 2)
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.set_spawn_policy(StoryRegion {
+//!     min: Point2::new(-2, -2),
+//!     max: Point2::new(2, 2),
+//! });
+//! ```
+
+use crate::lib::*;
+
+/// Decides which chunks the auto spawn system may spawn and despawn.
+///
+/// Both methods allow everything by default.
+pub trait ChunkSpawnPolicy: Send + Sync + 'static {
+    /// Returns `true` if the chunk at a point may be spawned automatically.
+    fn can_spawn(&self, _point: Point2) -> bool {
+        true
+    }
+
+    /// Returns `true` if the spawned chunk at a point may be despawned
+    /// automatically.
+    fn can_despawn(&self, _point: Point2) -> bool {
+        true
+    }
+}
+
+impl Debug for dyn ChunkSpawnPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("ChunkSpawnPolicy")
+    }
+}
@@ -0,0 +1,200 @@
+//! Coordinate math for the square topology.
+//!
+//! The counterpart of the [`hex`] module for a [`Square`] topology. A tile
+//! has four neighbours sharing an edge and eight when the ones sharing a
+//! corner are included. Without diagonal steps the number of steps between two
+//! tiles is their Manhattan distance, with them it is their Chebyshev
+//! distance.
+//!
+//! Directions go clockwise around a tile, starting to the right. Among the
+//! four neighbours direction `1` is down, among the eight it is down right.
+//!
+//! For code which should work on any topology, [`Tilemap::neighbours`] and
+//! [`Tilemap::distance`] pick the math of the topology of the tilemap.
+//!
+//! [`hex`]: crate::hex
+//! [`Square`]: crate::prelude::GridTopology::Square
+//! [`Tilemap::neighbours`]: crate::tilemap::Tilemap::neighbours
+//! [`Tilemap::distance`]: crate::tilemap::Tilemap::distance
+//!
+//! # Measuring around a tile
+//! ```
+//! use bevy_tilemap::{point::Point2, square};
+//!
+//! assert_eq!(square::neighbours4((0, 0))[1], Point2::new(0, -1));
+//! assert_eq!(square::neighbours8((0, 0))[1], Point2::new(1, -1));
+//!
+//! assert_eq!(square::manhattan_distance((0, 0), (3, -4)), 7);
+//! assert_eq!(square::chebyshev_distance((0, 0), (3, -4)), 4);
+//!
+//! assert_eq!(square::ring((0, 0), 2).count(), 16);
+//! assert_eq!(square::spiral((0, 0), 2).count(), 25);
+//! ```
+
+use crate::lib::*;
+
+/// The steps to the neighbours sharing an edge, clockwise.
+const DIRECTIONS_4: [(Coord, Coord); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
+
+/// The steps to the neighbours sharing an edge or a corner, clockwise.
+const DIRECTIONS_8: [(Coord, Coord); 8] = [
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Returns the point a step away from a point.
+fn step(point: Point2, (dx, dy): (Coord, Coord)) -> Point2 {
+    Point2::new(point.x + dx, point.y + dy)
+}
+
+/// Returns the four neighbours sharing an edge with a point, clockwise from
+/// the right.
+pub fn neighbours4<P: Into<Point2>>(point: P) -> [Point2; 4] {
+    let point: Point2 = point.into();
+    [
+        step(point, DIRECTIONS_4[0]),
+        step(point, DIRECTIONS_4[1]),
+        step(point, DIRECTIONS_4[2]),
+        step(point, DIRECTIONS_4[3]),
+    ]
+}
+
+/// Returns the eight neighbours sharing an edge or a corner with a point,
+/// clockwise from the right.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::{point::Point2, square};
+///
+/// let neighbours = square::neighbours8((2, 2));
+/// assert_eq!(neighbours[0], Point2::new(3, 2));
+/// assert_eq!(neighbours[7], Point2::new(3, 3));
+/// ```
+pub fn neighbours8<P: Into<Point2>>(point: P) -> [Point2; 8] {
+    let point: Point2 = point.into();
+    [
+        step(point, DIRECTIONS_8[0]),
+        step(point, DIRECTIONS_8[1]),
+        step(point, DIRECTIONS_8[2]),
+        step(point, DIRECTIONS_8[3]),
+        step(point, DIRECTIONS_8[4]),
+        step(point, DIRECTIONS_8[5]),
+        step(point, DIRECTIONS_8[6]),
+        step(point, DIRECTIONS_8[7]),
+    ]
+}
+
+/// Returns the number of steps between two points when only moving to
+/// neighbours sharing an edge.
+pub fn manhattan_distance<P: Into<Point2>>(a: P, b: P) -> u32 {
+    let a: Point2 = a.into();
+    let b: Point2 = b.into();
+    ((a.x - b.x).abs() + (a.y - b.y).abs()) as u32
+}
+
+/// Returns the number of steps between two points when moving to neighbours
+/// sharing an edge or a corner.
+pub fn chebyshev_distance<P: Into<Point2>>(a: P, b: P) -> u32 {
+    let a: Point2 = a.into();
+    let b: Point2 = b.into();
+    (a.x - b.x).abs().max((a.y - b.y).abs()) as u32
+}
+
+/// An iterator over the points exactly a number of diagonal steps away from a
+/// center, created by [`ring`].
+#[derive(Clone, Debug)]
+pub struct SquareRing {
+    /// The next point.
+    point: Point2,
+    /// The number of steps from the center.
+    radius: u32,
+    /// The side of the ring which is being walked, from `0` to `3`.
+    side: usize,
+    /// The steps taken along the side.
+    step: u32,
+}
+
+impl Iterator for SquareRing {
+    type Item = Point2;
+
+    fn next(&mut self) -> Option<Point2> {
+        let direction = *DIRECTIONS_4.get(self.side)?;
+        let point = self.point;
+        if self.radius == 0 {
+            self.side = DIRECTIONS_4.len();
+            return Some(point);
+        }
+        self.point = step(self.point, direction);
+        self.step += 1;
+        if self.step == 2 * self.radius {
+            self.step = 0;
+            self.side += 1;
+        }
+        Some(point)
+    }
+}
+
+/// Returns an iterator over the points whose Chebyshev distance to a center is
+/// exactly `radius`, walking clockwise around it from the top left corner.
+///
+/// A ring with a radius of `0` is only the center, otherwise it has
+/// `8 * radius` points.
+pub fn ring<P: Into<Point2>>(center: P, radius: u32) -> SquareRing {
+    let center: Point2 = center.into();
+    let radius_coord = radius as Coord;
+    SquareRing {
+        point: Point2::new(center.x - radius_coord, center.y + radius_coord),
+        radius,
+        side: 0,
+        step: 0,
+    }
+}
+
+/// Returns an iterator over the points whose Chebyshev distance to a center is
+/// at most `radius`, starting at the center and then ring by ring outwards.
+pub fn spiral<P: Into<Point2>>(center: P, radius: u32) -> impl Iterator<Item = Point2> {
+    let center: Point2 = center.into();
+    (0..=radius).flat_map(move |radius| ring(center, radius))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours_and_distances() {
+        let point = Point2::new(-3, 4);
+        for neighbour in neighbours4(point).iter() {
+            assert_eq!(manhattan_distance(point, *neighbour), 1);
+        }
+        for neighbour in neighbours8(point).iter() {
+            assert_eq!(chebyshev_distance(point, *neighbour), 1);
+        }
+        let diagonals = neighbours8(point)
+            .iter()
+            .filter(|neighbour| manhattan_distance(point, **neighbour) == 2)
+            .count();
+        assert_eq!(diagonals, 4);
+    }
+
+    #[test]
+    fn test_rings() {
+        let center = Point2::new(2, -1);
+        for radius in 0..4 {
+            let points: Vec<Point2> = ring(center, radius).collect();
+            assert_eq!(points.len(), (8 * radius).max(1) as usize);
+            assert!(points
+                .iter()
+                .all(|point| chebyshev_distance(center, *point) == radius));
+            let unique: HashSet<Point2> = points.iter().copied().collect();
+            assert_eq!(unique.len(), points.len());
+        }
+        assert_eq!(spiral(center, 3).count(), 49);
+    }
+}
@@ -0,0 +1,319 @@
+//! Stitching the edges of adjacent tilemaps together.
+//!
+//! A large world is often split into regions which are separate tilemaps.
+//! Algorithms which look at the tiles around a point, such as neighbours,
+//! pathfinding or line of sight, then need to read across the seams between
+//! them. [`Tilemap::stitch_edge`] declares which tilemap is on the other side
+//! of an edge and [`StitchedTilemaps`] is a view which reads tiles past the
+//! edges of a tilemap from the tilemaps stitched onto it, as if they were one.
+//!
+//! Points of the view are the points of the tilemap it is made for. The first
+//! column or row of a tilemap stitched onto an edge follows the last column or
+//! row of the tilemap, shifted along the edge by the offset of the stitch.
+//! Only the tilemaps directly stitched onto an edge are read, so the points
+//! past a corner of the tilemap are not part of the view.
+//!
+//! On the staggered hex topologies the offset of a stitch along a staggered
+//! edge should be even, otherwise the rows or columns do not line up.
+//!
+//! [`Tilemap::stitch_edge`]: crate::tilemap::Tilemap::stitch_edge
+//!
+//! # Walking into the next region
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_ecs::entity::Entity;
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     pathfinding::{PathCost, PathOptions},
+//!     point::Point2,
+//!     prelude::*,
+//!     stitch::{MapEdge, StitchedTilemaps},
+//! };
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let region = || {
+//!     let mut tilemap = TilemapBuilder::new()
+//!         .texture_atlas(texture_atlas_handle.clone_weak())
+//!         .dimensions(1, 1)
+//!         .chunk_dimensions(8, 8, 1)
+//!         .texture_dimensions(32, 32)
+//!         .finish()
+//!         .unwrap();
+//!     tilemap.insert_chunk((0, 0)).unwrap();
+//!     tilemap
+//! };
+//! let (west_entity, east_entity) = (Entity::new(0), Entity::new(1));
+//! let mut west = region();
+//! let mut east = region();
+//! west.stitch_edge(MapEdge::Right, east_entity, 0).unwrap();
+//! east.stitch_edge(MapEdge::Left, west_entity, 0).unwrap();
+//! east.insert_tile(Tile { point: (-4, 0), sprite_index: 7, ..Default::default() }).unwrap();
+//!
+//! let stitched = StitchedTilemaps::new(&west, |entity| {
+//!     if entity == east_entity {
+//!         Some(&east)
+//!     } else {
+//!         None
+//!     }
+//! });
+//!
+//! // The first column of the east region follows the last one of the west.
+//! assert_eq!(stitched.get_tile((4, 0), 0).map(|tile| tile.index), Some(7));
+//!
+//! let options = PathOptions { cost: PathCost::Collision, ..Default::default() };
+//! let path = stitched.find_path((0, 0), (6, 0), &options).unwrap();
+//! assert_eq!(path.last(), Some(&Point2::new(6, 0)));
+//! assert!(!path.contains(&Point2::new(4, 0)));
+//! ```
+
+use crate::{
+    chunk::RawTile,
+    lib::*,
+    pathfinding::{self, PathOptions},
+    prelude::GridTopology,
+    Tilemap,
+};
+
+/// An edge of a tilemap.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MapEdge {
+    /// The edge on the side of the lowest x.
+    Left,
+    /// The edge on the side of the highest x.
+    Right,
+    /// The edge on the side of the lowest y.
+    Bottom,
+    /// The edge on the side of the highest y.
+    Top,
+}
+
+impl MapEdge {
+    /// Returns the edge on the other side of a tilemap.
+    pub fn opposite(self) -> MapEdge {
+        match self {
+            MapEdge::Left => MapEdge::Right,
+            MapEdge::Right => MapEdge::Left,
+            MapEdge::Bottom => MapEdge::Top,
+            MapEdge::Top => MapEdge::Bottom,
+        }
+    }
+}
+
+/// A tilemap stitched onto an edge of another tilemap.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Stitch {
+    /// The entity of the stitched tilemap.
+    pub entity: Entity,
+    /// The number of tiles the stitched tilemap is shifted along the edge.
+    pub offset: Coord,
+}
+
+/// A view of a tilemap together with the tilemaps stitched onto its edges.
+#[derive(Clone, Debug)]
+pub struct StitchedTilemaps<'a> {
+    /// The tilemap whose points the view uses.
+    tilemap: &'a Tilemap,
+    /// The tilemaps stitched onto the edges, with the offset of the stitch.
+    stitched: HashMap<MapEdge, (&'a Tilemap, Coord)>,
+}
+
+impl<'a> StitchedTilemaps<'a> {
+    /// Constructs a view of a tilemap and the tilemaps stitched onto its
+    /// edges, which are looked up by their entity.
+    ///
+    /// In a system the lookup usually gets the tilemaps from a query, such
+    /// as with `|entity| tilemap_query.get(entity).ok()`. Edges whose tilemap
+    /// can not be looked up are left out.
+    pub fn new<F>(tilemap: &'a Tilemap, mut lookup: F) -> StitchedTilemaps<'a>
+    where
+        F: FnMut(Entity) -> Option<&'a Tilemap>,
+    {
+        let mut stitched = HashMap::default();
+        for edge in [MapEdge::Left, MapEdge::Right, MapEdge::Bottom, MapEdge::Top].iter() {
+            if let Some(stitch) = tilemap.stitched_edge(*edge) {
+                if let Some(other) = lookup(stitch.entity) {
+                    stitched.insert(*edge, (other, stitch.offset));
+                }
+            }
+        }
+        StitchedTilemaps { tilemap, stitched }
+    }
+
+    /// Returns the tilemap the view is made for.
+    pub fn tilemap(&self) -> &'a Tilemap {
+        self.tilemap
+    }
+
+    /// Returns the tilemap which has a point of the view and the point in
+    /// that tilemap, or `None` if no tilemap of the view has it.
+    pub fn resolve<P: Into<Point2>>(&self, point: P) -> Option<(&'a Tilemap, Point2)> {
+        let point: Point2 = point.into();
+        let (min, max) = match self.tilemap.point_bounds() {
+            Some(bounds) => bounds,
+            None => return Some((self.tilemap, point)),
+        };
+        let outside_x = point.x < min.x || point.x > max.x;
+        let outside_y = point.y < min.y || point.y > max.y;
+        let edge = match (outside_x, outside_y) {
+            (false, false) => return Some((self.tilemap, point)),
+            (true, true) => return None,
+            (true, false) if point.x < min.x => MapEdge::Left,
+            (true, false) => MapEdge::Right,
+            (false, true) if point.y < min.y => MapEdge::Bottom,
+            (false, true) => MapEdge::Top,
+        };
+        let (other, offset) = self.stitched.get(&edge)?;
+        let (other_min, other_max) = other.point_bounds()?;
+        let local = match edge {
+            MapEdge::Left | MapEdge::Right => {
+                let x = if edge == MapEdge::Left {
+                    other_max.x - (min.x - 1 - point.x)
+                } else {
+                    other_min.x + (point.x - max.x - 1)
+                };
+                Point2::new(x, other_min.y + point.y - min.y - offset)
+            }
+            MapEdge::Bottom | MapEdge::Top => {
+                let y = if edge == MapEdge::Bottom {
+                    other_max.y - (min.y - 1 - point.y)
+                } else {
+                    other_min.y + (point.y - max.y - 1)
+                };
+                Point2::new(other_min.x + point.x - min.x - offset, y)
+            }
+        };
+        let inside = local.x >= other_min.x
+            && local.x <= other_max.x
+            && local.y >= other_min.y
+            && local.y <= other_max.y;
+        if inside {
+            Some((other, local))
+        } else {
+            None
+        }
+    }
+
+    /// Gets a raw tile of any tilemap of the view from a point of the view
+    /// and a sprite order.
+    pub fn get_tile<P: Into<Point3>>(&self, point: P, sprite_order: usize) -> Option<&'a RawTile> {
+        let point: Point3 = point.into();
+        let (tilemap, local) = self.resolve(point)?;
+        tilemap.raw_tile(Point3::new(local.x, local.y, point.z), sprite_order)
+    }
+
+    /// Returns the neighbours of a point which are part of the view,
+    /// following the topology of the tilemap.
+    ///
+    /// See [`Tilemap::neighbours`] for which points are neighbours.
+    ///
+    /// [`Tilemap::neighbours`]: crate::tilemap::Tilemap::neighbours
+    pub fn neighbours<P: Into<Point2>>(&self, point: P, diagonals: bool) -> Vec<Point2> {
+        self.tilemap
+            .neighbours(point, diagonals)
+            .into_iter()
+            .filter(|neighbour| self.resolve(*neighbour).is_some())
+            .collect()
+    }
+
+    /// Finds the cheapest path between two points of the view with A*,
+    /// crossing into stitched tilemaps where needed.
+    ///
+    /// The cost of a point is decided by the tilemap which has it, as
+    /// described for [`Tilemap::find_path`]. Returns `None` if there is no
+    /// path.
+    ///
+    /// [`Tilemap::find_path`]: crate::tilemap::Tilemap::find_path
+    pub fn find_path<P: Into<Point2>>(
+        &self,
+        from: P,
+        to: P,
+        options: &PathOptions,
+    ) -> Option<Vec<Point2>> {
+        let from: Point2 = from.into();
+        let to: Point2 = to.into();
+        let step_cost = |point: Point2| {
+            let (tilemap, local) = self.resolve(point)?;
+            tilemap.step_cost(local, options)
+        };
+        let cost = |current: Point2, neighbour: Point2| {
+            let cost = step_cost(neighbour)?;
+            if neighbour.x != current.x && neighbour.y != current.y {
+                let corners = [
+                    Point2::new(neighbour.x, current.y),
+                    Point2::new(current.x, neighbour.y),
+                ];
+                if self.tilemap.topology() == GridTopology::Square
+                    && corners.iter().any(|corner| step_cost(*corner).is_none())
+                {
+                    return None;
+                }
+            }
+            Some(cost)
+        };
+        // Every step costs at least 1, so the number of steps never
+        // overestimates.
+        let heuristic = |point: Point2| self.tilemap.distance(point, to, options.diagonals);
+
+        step_cost(to)?;
+        pathfinding::a_star(
+            from,
+            to,
+            |point| self.tilemap.neighbours(point, options.diagonals),
+            cost,
+            heuristic,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tilemap::TilemapBuilder;
+
+    fn region() -> Tilemap {
+        TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .dimensions(1, 1)
+            .chunk_dimensions(4, 4, 1)
+            .texture_dimensions(32, 32)
+            .finish()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_edges() {
+        // The center region covers -2..=1 on both axes.
+        let mut center = region();
+        let (west, north) = (region(), region());
+        let (west_entity, north_entity) = (Entity::new(1), Entity::new(2));
+        center.stitch_edge(MapEdge::Left, west_entity, 0).unwrap();
+        center.stitch_edge(MapEdge::Top, north_entity, -2).unwrap();
+
+        let stitched = StitchedTilemaps::new(&center, |entity| match entity {
+            entity if entity == west_entity => Some(&west),
+            entity if entity == north_entity => Some(&north),
+            _ => None,
+        });
+
+        let (tilemap, point) = stitched.resolve((0, 0)).unwrap();
+        assert!(::std::ptr::eq(tilemap, &center));
+        assert_eq!(point, Point2::new(0, 0));
+
+        let (tilemap, point) = stitched.resolve((-3, -2)).unwrap();
+        assert!(::std::ptr::eq(tilemap, &west));
+        assert_eq!(point, Point2::new(1, -2));
+
+        // The north region is shifted 2 tiles to the left.
+        let (tilemap, point) = stitched.resolve((-2, 2)).unwrap();
+        assert!(::std::ptr::eq(tilemap, &north));
+        assert_eq!(point, Point2::new(0, -2));
+        assert!(stitched.resolve((0, 2)).is_none());
+
+        assert!(stitched.resolve((-3, 2)).is_none());
+        assert!(stitched.resolve((2, 0)).is_none());
+        assert_eq!(stitched.neighbours((-2, 1), false).len(), 4);
+        assert_eq!(stitched.neighbours((1, -2), false).len(), 2);
+    }
+}
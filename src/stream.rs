@@ -0,0 +1,434 @@
+//! Streamed saving and loading of tilemaps in a versioned binary format.
+//!
+//! Huge maps can take a long time to deserialize in one go. Instead a map can
+//! be written with [`Tilemap::save_streaming`] and read back with
+//! [`Tilemap::load_streaming`] which inserts the chunks over successive frames
+//! with a time budget. The tilemap plugin polls the stream every frame and
+//! sends a [`TilemapStreamEvent`] with the progress.
+//!
+//! [`Tilemap::save_streaming`]: crate::tilemap::Tilemap::save_streaming
+//! [`Tilemap::load_streaming`]: crate::tilemap::Tilemap::load_streaming
+//! [`TilemapStreamEvent`]: crate::event::TilemapStreamEvent
+//! [`Coord`]: crate::point::Coord
+//!
+//! # Format
+//!
+//! All values are little endian.
+//!
+//! - The header is the magic bytes `BTMS`, the [`STREAM_VERSION`] as a `u16`,
+//!   the width of a coordinate in bytes as a `u8`, the chunk width, height and
+//!   depth as `u32`s and the number of chunks as a `u32`.
+//! - Each chunk is the chunk point as two [`Coord`]s and the number of tiles
+//!   as a `u32`. Coordinates are `i64`s with the `i64_coordinates` feature and
+//!   `i32`s otherwise, so a stream can only be read with the same feature it
+//!   was written with, which is checked against the width in the header.
+//! - Each tile is the z depth, sprite order, index in the chunk, sprite index
+//!   and texture atlas id as `u32`s, the color as four `f32`s, the flip and
+//!   rotation flags as a `u8` then the scale and offset as two `f32`s each.
+//!
+//! # Streaming a tilemap
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, chunk::RawTile};
+//! use bevy_utils::Duration;
+//! use std::io::Cursor;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (3, 3), sprite_index: 1, ..Default::default() }).unwrap();
+//!
+//! let mut bytes = Vec::new();
+//! tilemap.save_streaming(&mut bytes).unwrap();
+//!
+//! let mut loaded = Tilemap::new(texture_atlas_handle, 32, 32);
+//! loaded.load_streaming(Cursor::new(bytes), Duration::from_millis(4)).unwrap();
+//!
+//! // Normally the plugin polls the stream once per frame.
+//! while let Some(progress) = loaded.poll_streaming().unwrap() {
+//!     if progress.is_finished() {
+//!         break;
+//!     }
+//! }
+//!
+//! assert_eq!(loaded.get_tile((3, 3), 0), Some(&RawTile { index: 1, ..Default::default() }));
+//! ```
+
+use crate::{
+    chunk::RawTile,
+    lib::*,
+    tilemap::{ErrorKind, TilemapLayer, TilemapResult},
+};
+
+/// The magic bytes at the start of every stream.
+const STREAM_MAGIC: [u8; 4] = *b"BTMS";
+
+/// The current version of the stream format.
+pub const STREAM_VERSION: u16 = 2;
+
+/// The width of a coordinate in bytes, which depends on the
+/// `i64_coordinates` feature.
+const COORD_WIDTH: u8 = mem::size_of::<Coord>() as u8;
+
+/// The progress of a streamed load.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct StreamProgress {
+    /// The number of chunks that have been loaded so far.
+    pub chunks_loaded: u32,
+    /// The total number of chunks in the stream.
+    pub chunk_count: u32,
+    /// The number of bytes that have been read so far.
+    pub bytes_read: u64,
+}
+
+impl StreamProgress {
+    /// Returns `true` if every chunk in the stream has been loaded.
+    pub fn is_finished(&self) -> bool {
+        self.chunks_loaded >= self.chunk_count
+    }
+
+    /// Returns the fraction of chunks loaded, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.chunk_count == 0 {
+            1.0
+        } else {
+            self.chunks_loaded as f32 / self.chunk_count as f32
+        }
+    }
+}
+
+/// A tile read from a stream along with its z depth, sprite order and index.
+pub(crate) type StreamTile = (usize, usize, usize, RawTile);
+
+/// A stream which is being loaded into a tilemap.
+pub(crate) struct TilemapStream {
+    /// The reader of the stream.
+    reader: Box<dyn Read + Send + Sync>,
+    /// The time that can be spent loading chunks per poll.
+    budget: Duration,
+    /// The chunk dimensions of the stream.
+    chunk_dimensions: Dimension3,
+    /// The progress of the stream so far.
+    progress: StreamProgress,
+}
+
+impl Debug for TilemapStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("TilemapStream")
+            .field("budget", &self.budget)
+            .field("progress", &self.progress)
+            .finish()
+    }
+}
+
+impl TilemapStream {
+    /// Constructs a new stream, reading and validating the header against the
+    /// chunk dimensions of the tilemap.
+    pub(crate) fn new(
+        reader: Box<dyn Read + Send + Sync>,
+        budget: Duration,
+        chunk_dimensions: Dimension3,
+    ) -> TilemapResult<TilemapStream> {
+        let mut stream = TilemapStream {
+            reader,
+            budget,
+            chunk_dimensions,
+            progress: StreamProgress::default(),
+        };
+
+        let mut magic = [0; 4];
+        stream.read_exact(&mut magic)?;
+        if magic != STREAM_MAGIC {
+            return Err(ErrorKind::InvalidStream.into());
+        }
+        let version = read_u16(&mut stream)?;
+        if version != STREAM_VERSION {
+            return Err(ErrorKind::UnsupportedStreamVersion(version).into());
+        }
+        read_coord_width(&mut stream)?;
+        let dimensions = Dimension3::new(
+            read_u32(&mut stream)?,
+            read_u32(&mut stream)?,
+            read_u32(&mut stream)?,
+        );
+        if dimensions != chunk_dimensions {
+            return Err(ErrorKind::StreamChunkDimensions(dimensions).into());
+        }
+        stream.progress.chunk_count = read_u32(&mut stream)?;
+
+        Ok(stream)
+    }
+
+    /// Returns the time that can be spent loading chunks per poll.
+    pub(crate) fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Returns the progress of the stream so far.
+    pub(crate) fn progress(&self) -> StreamProgress {
+        self.progress
+    }
+
+    /// Reads the next chunk in the stream, returning its point and tiles.
+    ///
+    /// Returns `None` if every chunk has been read.
+    pub(crate) fn read_chunk(
+        &mut self,
+        layers: &[Option<TilemapLayer>],
+    ) -> TilemapResult<Option<(Point2, Vec<StreamTile>)>> {
+        if self.progress.is_finished() {
+            return Ok(None);
+        }
+        let chunk_dimensions = self.chunk_dimensions;
+        let chunk = read_chunk(self, chunk_dimensions, layers)?;
+        self.progress.chunks_loaded += 1;
+
+        Ok(Some(chunk))
+    }
+}
+
+impl Read for TilemapStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+        let len = self.reader.read(buf)?;
+        self.progress.bytes_read += len as u64;
+        Ok(len)
+    }
+}
+
+/// Reads a `u8`.
+pub(crate) fn read_u8<R: Read>(reader: &mut R) -> TilemapResult<u8> {
+    let mut buf = [0; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(u8::from_le_bytes(buf))
+}
+
+/// Reads a little endian `u16`.
+pub(crate) fn read_u16<R: Read>(reader: &mut R) -> TilemapResult<u16> {
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+/// Reads a little endian `u32`.
+pub(crate) fn read_u32<R: Read>(reader: &mut R) -> TilemapResult<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// Reads the width of a coordinate, checking that it is the width of a
+/// [`Coord`].
+pub(crate) fn read_coord_width<R: Read>(reader: &mut R) -> TilemapResult<()> {
+    let width = read_u8(reader)?;
+    if width != COORD_WIDTH {
+        return Err(ErrorKind::StreamCoordinateWidth(width).into());
+    }
+    Ok(())
+}
+
+/// Writes the width of a [`Coord`].
+pub(crate) fn write_coord_width<W: Write>(writer: &mut W) -> TilemapResult<()> {
+    writer.write_all(&[COORD_WIDTH])?;
+    Ok(())
+}
+
+/// Reads a little endian coordinate.
+pub(crate) fn read_coord<R: Read>(reader: &mut R) -> TilemapResult<Coord> {
+    let mut buf = [0; mem::size_of::<Coord>()];
+    reader.read_exact(&mut buf)?;
+    Ok(Coord::from_le_bytes(buf))
+}
+
+/// Reads a little endian `f32`.
+pub(crate) fn read_f32<R: Read>(reader: &mut R) -> TilemapResult<f32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(f32::from_le_bytes(buf))
+}
+
+/// Reads a point as two coordinates.
+pub(crate) fn read_point2<R: Read>(reader: &mut R) -> TilemapResult<Point2> {
+    Ok(Point2::new(read_coord(reader)?, read_coord(reader)?))
+}
+
+/// Writes a point as two coordinates.
+pub(crate) fn write_point2<W: Write>(writer: &mut W, point: Point2) -> TilemapResult<()> {
+    writer.write_all(&point.x.to_le_bytes())?;
+    writer.write_all(&point.y.to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads a raw tile, starting with its sprite index.
+pub(crate) fn read_raw_tile<R: Read>(reader: &mut R) -> TilemapResult<RawTile> {
+    let mut raw_tile = RawTile {
+        index: read_u32(reader)? as usize,
+        atlas: read_u32(reader)? as usize,
+        color: Color::rgba(
+            read_f32(reader)?,
+            read_f32(reader)?,
+            read_f32(reader)?,
+            read_f32(reader)?,
+        ),
+        ..Default::default()
+    };
+    raw_tile.set_flag_bits(read_u8(reader)?);
+    raw_tile.scale = Vec2::new(read_f32(reader)?, read_f32(reader)?);
+    raw_tile.offset = Vec2::new(read_f32(reader)?, read_f32(reader)?);
+    Ok(raw_tile)
+}
+
+/// Writes a raw tile, starting with its sprite index.
+pub(crate) fn write_raw_tile<W: Write>(writer: &mut W, tile: &RawTile) -> TilemapResult<()> {
+    writer.write_all(&(tile.index as u32).to_le_bytes())?;
+    writer.write_all(&(tile.atlas as u32).to_le_bytes())?;
+    for channel in tile.color.as_rgba_f32().iter() {
+        writer.write_all(&channel.to_le_bytes())?;
+    }
+    writer.write_all(&[tile.flag_bits()])?;
+    writer.write_all(&tile.scale.x.to_le_bytes())?;
+    writer.write_all(&tile.scale.y.to_le_bytes())?;
+    writer.write_all(&tile.offset.x.to_le_bytes())?;
+    writer.write_all(&tile.offset.y.to_le_bytes())?;
+    Ok(())
+}
+
+/// Writes the stream header.
+pub(crate) fn write_header<W: Write>(
+    writer: &mut W,
+    chunk_dimensions: Dimension3,
+    chunk_count: u32,
+) -> TilemapResult<()> {
+    writer.write_all(&STREAM_MAGIC)?;
+    writer.write_all(&STREAM_VERSION.to_le_bytes())?;
+    write_coord_width(writer)?;
+    writer.write_all(&chunk_dimensions.width.to_le_bytes())?;
+    writer.write_all(&chunk_dimensions.height.to_le_bytes())?;
+    writer.write_all(&chunk_dimensions.depth.to_le_bytes())?;
+    writer.write_all(&chunk_count.to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads a chunk and all of its tiles.
+///
+/// The stream can not be trusted, so every tile is checked to fit in the
+/// chunk dimensions and to be on an existing sprite layer. The tiles are not
+/// allocated up front from the tile count, which may be made up, though a
+/// count larger than the chunk could ever hold is rejected right away.
+pub(crate) fn read_chunk<R: Read>(
+    reader: &mut R,
+    chunk_dimensions: Dimension3,
+    layers: &[Option<TilemapLayer>],
+) -> TilemapResult<(Point2, Vec<StreamTile>)> {
+    let point = read_point2(reader)?;
+    let tile_count = read_u32(reader)? as usize;
+    let area = (chunk_dimensions.width * chunk_dimensions.height) as usize;
+    let depth = chunk_dimensions.depth as usize;
+    if tile_count > area * depth * layers.len() {
+        return Err(ErrorKind::InvalidStream.into());
+    }
+    let mut tiles = Vec::new();
+    for _ in 0..tile_count {
+        let z_depth = read_u32(reader)? as usize;
+        let sprite_order = read_u32(reader)? as usize;
+        let index = read_u32(reader)? as usize;
+        if index >= area || z_depth >= depth {
+            return Err(ErrorKind::TileIndexOutOfBounds(index, z_depth).into());
+        }
+        if layers.get(sprite_order).is_none_or(Option::is_none) {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let raw_tile = read_raw_tile(reader)?;
+        tiles.push((z_depth, sprite_order, index, raw_tile));
+    }
+    Ok((point, tiles))
+}
+
+/// Writes a chunk and all of its tiles.
+pub(crate) fn write_chunk<W: Write>(
+    writer: &mut W,
+    point: Point2,
+    tiles: &[(usize, usize, usize, &RawTile)],
+) -> TilemapResult<()> {
+    write_point2(writer, point)?;
+    writer.write_all(&(tiles.len() as u32).to_le_bytes())?;
+    for (z_depth, sprite_order, index, tile) in tiles {
+        writer.write_all(&(*z_depth as u32).to_le_bytes())?;
+        writer.write_all(&(*sprite_order as u32).to_le_bytes())?;
+        writer.write_all(&(*index as u32).to_le_bytes())?;
+        write_raw_tile(writer, tile)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reject_other_coordinate_width() {
+        let dimensions = Dimension3::new(4, 4, 1);
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, dimensions, 3).unwrap();
+        let new_stream = |bytes: &[u8]| {
+            TilemapStream::new(
+                Box::new(IoCursor::new(bytes.to_vec())),
+                Duration::ZERO,
+                dimensions,
+            )
+            .map(|stream| stream.progress().chunk_count)
+        };
+        assert_eq!(new_stream(&bytes), Ok(3));
+
+        // The width follows the magic bytes and the version.
+        let other_width = if COORD_WIDTH == 4 { 8 } else { 4 };
+        if let Some(width) = bytes.get_mut(6) {
+            *width = other_width;
+        }
+        assert_eq!(
+            new_stream(&bytes),
+            Err(ErrorKind::StreamCoordinateWidth(other_width).into())
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_chunk() {
+        let dimensions = Dimension3::new(4, 4, 1);
+        let layers = [Some(TilemapLayer::default()), None];
+        let tile = RawTile::default();
+        let read = |tiles: &[(usize, usize, usize, &RawTile)]| {
+            let mut bytes = Vec::new();
+            write_chunk(&mut bytes, Point2::new(1, -1), tiles).unwrap();
+            read_chunk(&mut &bytes[..], dimensions, &layers).map(|(_, tiles)| tiles)
+        };
+
+        assert_eq!(read(&[(0, 0, 15, &tile)]), Ok(vec![(0, 0, 15, tile)]));
+        assert_eq!(
+            read(&[(0, 0, 16, &tile)]),
+            Err(ErrorKind::TileIndexOutOfBounds(16, 0).into())
+        );
+        assert_eq!(
+            read(&[(1, 0, 0, &tile)]),
+            Err(ErrorKind::TileIndexOutOfBounds(0, 1).into())
+        );
+        assert_eq!(
+            read(&[(0, 1, 0, &tile)]),
+            Err(ErrorKind::LayerDoesNotExist(1).into())
+        );
+        assert_eq!(
+            read(&[(0, 2, 0, &tile)]),
+            Err(ErrorKind::LayerDoesNotExist(2).into())
+        );
+
+        // A made up tile count is rejected before any tile is read.
+        let mut bytes = Vec::new();
+        write_point2(&mut bytes, Point2::new(0, 0)).unwrap();
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            read_chunk(&mut &bytes[..], dimensions, &layers).map(|(_, tiles)| tiles),
+            Err(ErrorKind::InvalidStream.into())
+        );
+    }
+}
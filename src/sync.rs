@@ -0,0 +1,294 @@
+//! Syncing chunks between a host and its clients by their content hash.
+//!
+//! In co-op play a client often already has most chunks from an earlier
+//! session or from before a reconnect. Rather than sending every chunk again,
+//! a client sends a [`ChunkRequest`] with the point of a chunk and the hash of
+//! the copy it has, made with [`Tilemap::request_chunk`]. The host answers
+//! with [`Tilemap::respond_to_chunk_request`], which only includes the chunk
+//! bytes if the hashes differ, and the client applies the [`ChunkResponse`]
+//! with [`Tilemap::apply_chunk_response`].
+//!
+//! Sending the requests and responses is left to the user, they are written
+//! and read with `write` and `read` in a compact binary format for any
+//! transport.
+//!
+//! The hash of a chunk is taken from its tiles in the [stream] format, so both
+//! sides need the same chunk dimensions, layers and `i64_coordinates` feature.
+//!
+//! [`Tilemap::request_chunk`]: crate::tilemap::Tilemap::request_chunk
+//! [`Tilemap::respond_to_chunk_request`]: crate::tilemap::Tilemap::respond_to_chunk_request
+//! [`Tilemap::apply_chunk_response`]: crate::tilemap::Tilemap::apply_chunk_response
+//! [stream]: crate::stream
+//!
+//! # Format
+//!
+//! All values are little endian.
+//!
+//! - A request is the chunk point as two coordinates, then a `u8` which is 1
+//!   if the client has the chunk followed by its hash as a `u64`, and 0
+//!   otherwise.
+//! - A response is a `u8` tag, 0 if the chunk is unchanged, 1 if it is
+//!   included and 2 if the host does not have it, then the chunk point. An
+//!   included chunk is followed by the length of its bytes as a `u32` and the
+//!   bytes.
+//!
+//! # Syncing a chunk
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, chunk::RawTile, sync::{ChunkRequest, ChunkResponse}};
+//! use std::io::Cursor;
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut host = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+//! host.insert_chunk((0, 0)).unwrap();
+//! host.insert_tile(Tile { point: (3, 3), sprite_index: 1, ..Default::default() }).unwrap();
+//! let mut client = Tilemap::new(texture_atlas_handle, 32, 32);
+//!
+//! // The client sends its request over the network.
+//! let mut bytes = Vec::new();
+//! client.request_chunk((0, 0)).write(&mut bytes).unwrap();
+//! let request = ChunkRequest::read(Cursor::new(bytes)).unwrap();
+//!
+//! // The host sends its response back.
+//! let mut bytes = Vec::new();
+//! host.respond_to_chunk_request(&request).unwrap().write(&mut bytes).unwrap();
+//! let response = ChunkResponse::read(Cursor::new(bytes)).unwrap();
+//!
+//! assert!(client.apply_chunk_response(&response).unwrap());
+//! assert_eq!(client.get_tile((3, 3), 0), Some(&RawTile { index: 1, ..Default::default() }));
+//! assert_eq!(client.chunk_hash((0, 0)), host.chunk_hash((0, 0)));
+//!
+//! // Asking again does not send the chunk.
+//! let response = host.respond_to_chunk_request(&client.request_chunk((0, 0))).unwrap();
+//! assert!(!response.has_chunk());
+//! ```
+
+use crate::{
+    lib::*,
+    stream::{read_point2, read_u32, read_u8, write_point2},
+    tilemap::{ErrorKind, TilemapResult},
+};
+
+/// A request of a client for a chunk of the host.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ChunkRequest {
+    /// The point of the chunk.
+    pub point: Point2,
+    /// The hash of the chunk the client has, or `None` if it does not have
+    /// the chunk.
+    pub hash: Option<u64>,
+}
+
+impl ChunkRequest {
+    /// Writes the request in the sync format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the writer fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> TilemapResult<()> {
+        write_point2(&mut writer, self.point)?;
+        match self.hash {
+            Some(hash) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&hash.to_le_bytes())?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+        Ok(())
+    }
+
+    /// Reads a request in the sync format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is not a request.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{point::Point2, sync::ChunkRequest};
+    /// use std::io::Cursor;
+    ///
+    /// let request = ChunkRequest { point: Point2::new(1, -2), hash: Some(7) };
+    /// let mut bytes = Vec::new();
+    /// request.write(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(ChunkRequest::read(Cursor::new(bytes)).unwrap(), request);
+    /// ```
+    pub fn read<R: Read>(mut reader: R) -> TilemapResult<ChunkRequest> {
+        let point = read_point2(&mut reader)?;
+        let hash = match read_u8(&mut reader)? {
+            0 => None,
+            1 => Some(read_u64(&mut reader)?),
+            _ => return Err(ErrorKind::InvalidChunkSync.into()),
+        };
+        Ok(ChunkRequest { point, hash })
+    }
+}
+
+/// The response of the host to a [`ChunkRequest`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ChunkResponse {
+    /// The chunk of the client is the same as the chunk of the host.
+    Unchanged {
+        /// The point of the chunk.
+        point: Point2,
+    },
+    /// The chunk of the client is missing or differs, so the host sends its
+    /// chunk.
+    Chunk {
+        /// The point of the chunk.
+        point: Point2,
+        /// The tiles of the chunk in the stream format.
+        bytes: Vec<u8>,
+    },
+    /// The host does not have the chunk.
+    Missing {
+        /// The point of the chunk.
+        point: Point2,
+    },
+}
+
+impl ChunkResponse {
+    /// Returns the point of the chunk.
+    pub fn point(&self) -> Point2 {
+        match self {
+            ChunkResponse::Unchanged { point }
+            | ChunkResponse::Chunk { point, .. }
+            | ChunkResponse::Missing { point } => *point,
+        }
+    }
+
+    /// Returns `true` if the response includes the chunk.
+    pub fn has_chunk(&self) -> bool {
+        matches!(self, ChunkResponse::Chunk { .. })
+    }
+
+    /// Writes the response in the sync format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the writer fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> TilemapResult<()> {
+        match self {
+            ChunkResponse::Unchanged { point } => {
+                writer.write_all(&[0])?;
+                write_point2(&mut writer, *point)?;
+            }
+            ChunkResponse::Chunk { point, bytes } => {
+                writer.write_all(&[1])?;
+                write_point2(&mut writer, *point)?;
+                writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+                writer.write_all(bytes)?;
+            }
+            ChunkResponse::Missing { point } => {
+                writer.write_all(&[2])?;
+                write_point2(&mut writer, *point)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a response in the sync format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is not a response.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{point::Point2, sync::ChunkResponse};
+    /// use std::io::Cursor;
+    ///
+    /// let response = ChunkResponse::Missing { point: Point2::new(0, 3) };
+    /// let mut bytes = Vec::new();
+    /// response.write(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(ChunkResponse::read(Cursor::new(bytes)).unwrap(), response);
+    /// assert!(ChunkResponse::read(Cursor::new(vec![9])).is_err());
+    /// ```
+    pub fn read<R: Read>(mut reader: R) -> TilemapResult<ChunkResponse> {
+        let tag = read_u8(&mut reader)?;
+        let point = read_point2(&mut reader)?;
+        let response = match tag {
+            0 => ChunkResponse::Unchanged { point },
+            1 => {
+                // The length comes from the peer, so the bytes are read as
+                // they arrive rather than allocated up front.
+                let len = u64::from(read_u32(&mut reader)?);
+                let mut bytes = Vec::new();
+                (&mut reader).take(len).read_to_end(&mut bytes)?;
+                if bytes.len() as u64 != len {
+                    return Err(ErrorKind::InvalidChunkSync.into());
+                }
+                ChunkResponse::Chunk { point, bytes }
+            }
+            2 => ChunkResponse::Missing { point },
+            _ => return Err(ErrorKind::InvalidChunkSync.into()),
+        };
+        Ok(response)
+    }
+}
+
+/// Reads a little endian `u64`.
+fn read_u64<R: Read>(reader: &mut R) -> TilemapResult<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Returns the 64 bit FNV-1a hash of some bytes.
+///
+/// Unlike the hashers of the standard library it is the same on every machine
+/// and every run, so it can be compared over the network.
+pub(crate) fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes.iter() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        // Known FNV-1a values.
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert!(content_hash(&[0, 1]) != content_hash(&[1, 0]));
+    }
+
+    #[test]
+    fn test_response_roundtrip() {
+        let response = ChunkResponse::Chunk {
+            point: Point2::new(-1, 2),
+            bytes: vec![1, 2, 3],
+        };
+        let mut bytes = Vec::new();
+        response.write(&mut bytes).unwrap();
+        let read = ChunkResponse::read(&bytes[..]).unwrap();
+        assert_eq!(read, response);
+        assert!(read.has_chunk());
+        assert_eq!(read.point(), Point2::new(-1, 2));
+    }
+
+    #[test]
+    fn test_response_length_past_end() {
+        let mut bytes = Vec::new();
+        bytes.push(1);
+        write_point2(&mut bytes, Point2::new(0, 0)).unwrap();
+        // A length of 4 GiB with only a few bytes behind it.
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(
+            ChunkResponse::read(&bytes[..]),
+            Err(ErrorKind::InvalidChunkSync.into())
+        );
+    }
+}
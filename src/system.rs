@@ -1,19 +1,30 @@
 //! The tilemap systems.
 
+#[cfg(feature = "failure_injection")]
+use crate::failure::InjectedFailure;
 use crate::{
     chunk::{
-        entity::{ChunkBundle, Modified},
+        entity::{AtlasId, ChunkBundle, ChunkColorGrading, DirtyTiles, Modified, SecondaryTexture},
         mesh::ChunkMesh,
-        render::GridTopology,
-        Chunk, LayerKind,
+        raw_tile::TileAttributes,
+        render::{self, GridTopology},
+        Chunk, LayerKind, MeshSettings,
+    },
+    diagnostics::TilemapFrameStats,
+    event::{
+        TilemapChunkUnloadEvent, TilemapCollidersEvent, TilemapSpriteIndexEvent, TilemapStreamEvent,
     },
     lib::*,
     Tilemap,
 };
 
+/// The number of frames a tile waits for its texture atlas to load before its
+/// sprite index is no longer checked.
+const MAX_VALIDATION_ATTEMPTS: u32 = 600;
+
 /// Takes a grid topology and returns altered translation coordinates.
 // TODO: set translation Z from somewhere else.
-fn topology_translation(
+pub(crate) fn topology_translation(
     topology: GridTopology,
     chunk_point: Point2,
     chunk_dimensions: Dimension3,
@@ -22,31 +33,33 @@ fn topology_translation(
     use GridTopology::*;
     let translation_x = match topology {
         HexX | HexEvenCols | HexOddCols => {
-            (((chunk_point.x * texture_dimensions.width as i32) as f32 * 0.75) as i32
-                * chunk_dimensions.width as i32) as f32
+            (((chunk_point.x * texture_dimensions.width as Coord) as f32 * 0.75) as Coord
+                * chunk_dimensions.width as Coord) as f32
         }
         HexY => {
-            (chunk_point.x * texture_dimensions.width as i32 * chunk_dimensions.width as i32) as f32
+            (chunk_point.x * texture_dimensions.width as Coord * chunk_dimensions.width as Coord)
+                as f32
                 + (chunk_point.y as f32 * chunk_dimensions.height as f32 * 0.5)
                     * texture_dimensions.width as f32
         }
         Square | HexEvenRows | HexOddRows => {
-            (chunk_point.x * texture_dimensions.width as i32 * chunk_dimensions.width as i32) as f32
+            (chunk_point.x * texture_dimensions.width as Coord * chunk_dimensions.width as Coord)
+                as f32
         }
     };
     let translation_y = match topology {
         HexX => {
-            (chunk_point.y * texture_dimensions.height as i32 * chunk_dimensions.height as i32)
+            (chunk_point.y * texture_dimensions.height as Coord * chunk_dimensions.height as Coord)
                 as f32
                 + (chunk_point.x as f32 * chunk_dimensions.width as f32 * 0.5)
                     * texture_dimensions.height as f32
         }
         HexY | HexEvenRows | HexOddRows => {
-            (((chunk_point.y * texture_dimensions.height as i32) as f32 * 0.75) as i32
-                * chunk_dimensions.height as i32) as f32
+            (((chunk_point.y * texture_dimensions.height as Coord) as f32 * 0.75) as Coord
+                * chunk_dimensions.height as Coord) as f32
         }
         Square | HexEvenCols | HexOddCols => {
-            (chunk_point.y * texture_dimensions.height as i32 * chunk_dimensions.height as i32)
+            (chunk_point.y * texture_dimensions.height as Coord * chunk_dimensions.height as Coord)
                 as f32
         }
     };
@@ -54,79 +67,295 @@ fn topology_translation(
     (translation_x, translation_y)
 }
 
+/// Returns the point of the bottom left chunk of the region of a chunk, if
+/// chunk regions are set.
+fn region_origin(tilemap: &Tilemap, point: Point2) -> Option<Point2> {
+    let regions = tilemap.chunk_regions()?;
+    let region = tilemap.chunk_region(point)?;
+    Some(Point2::new(
+        region.x * regions.width as Coord,
+        region.y * regions.height as Coord,
+    ))
+}
+
+/// Returns the translation of a chunk from the entity which parents the
+/// entities of the chunk.
+pub(crate) fn chunk_translation(tilemap: &Tilemap, point: Point2) -> (f32, f32) {
+    let topology = tilemap.topology();
+    let chunk_dimensions = tilemap.chunk_dimensions();
+    let texture_dimensions = tilemap.texture_dimensions();
+    let (translation_x, translation_y) =
+        topology_translation(topology, point, chunk_dimensions, texture_dimensions);
+    match region_origin(tilemap, point) {
+        Some(origin) => {
+            let (origin_x, origin_y) =
+                topology_translation(topology, origin, chunk_dimensions, texture_dimensions);
+            (translation_x - origin_x, translation_y - origin_y)
+        }
+        None => (translation_x, translation_y),
+    }
+}
+
+/// Returns the entity which parents the entities of a chunk, which is the
+/// tilemap unless chunk regions are set.
+///
+/// The entity of the region of the chunk is spawned if it has none yet.
+pub(crate) fn chunk_parent(
+    commands: &mut Commands,
+    tilemap_entity: Entity,
+    tilemap: &mut Tilemap,
+    point: Point2,
+) -> Entity {
+    let (region, origin) = match (tilemap.chunk_region(point), region_origin(tilemap, point)) {
+        (Some(region), Some(origin)) => (region, origin),
+        _ => return tilemap_entity,
+    };
+    if let Some(entity) = tilemap.region_entity(region) {
+        return entity;
+    }
+    let (translation_x, translation_y) = topology_translation(
+        tilemap.topology(),
+        origin,
+        tilemap.chunk_dimensions(),
+        tilemap.texture_dimensions(),
+    );
+    let entity = commands
+        .spawn_bundle((
+            Transform::from_xyz(translation_x, translation_y, 0.0),
+            GlobalTransform::default(),
+        ))
+        .id();
+    commands.entity(tilemap_entity).push_children(&[entity]);
+    tilemap.region_entities_mut().insert(region, entity);
+    entity
+}
+
+/// Spawns the entity which renders the tiles of a chunk that are in a texture
+/// atlas, returning it if it was spawned.
+fn spawn_chunk_entity(
+    commands: &mut Commands,
+    tilemap_visible: &Visible,
+    meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
+    tilemap: &mut Tilemap,
+    point: Point2,
+    atlas: usize,
+) -> Option<Entity> {
+    let texture_atlas = if let Some(texture_atlas) = tilemap.get_texture_atlas(atlas) {
+        texture_atlas.clone_weak()
+    } else {
+        warn!(
+            "Texture atlas {} does not exist, can not render it in chunk {}",
+            atlas, point
+        );
+        return None;
+    };
+    let chunk_dimensions = tilemap.chunk_dimensions();
+    let (translation_x, translation_y) = chunk_translation(tilemap, point);
+    let pipeline_handle = tilemap.chunk_pipeline();
+    let secondary_texture = tilemap.chunk_secondary_texture(atlas);
+    let color_grading = tilemap.chunk_color_grading();
+    let chunk_mesh = tilemap.chunk_mesh().clone();
+    let held_back_layers = tilemap.chunk_hidden_layers(point);
+    let holds_back_layers = held_back_layers.is_some();
+    let chunk_z = tilemap.chunk_z();
+    let (chunks, settings) = tilemap.chunks_with_mesh_settings_mut();
+    let settings = settings.with_hidden_layers(held_back_layers.as_ref());
+    let chunk = if let Some(chunk) = chunks.get_mut(&point) {
+        chunk
+    } else {
+        // NOTE: should this instead create a chunk if it doesn't exist yet?
+        warn!("Can not get chunk at {}, possible bug report me", &point);
+        return None;
+    };
+    let baked_layers = chunk.baked_layer_count();
+    let mut mesh = if baked_layers == 0 {
+        Mesh::from(&chunk_mesh)
+    } else {
+        Mesh::from(&chunk_mesh.without_layers(chunk_dimensions, baked_layers))
+    };
+    // The prewarmed attributes include the layers the chunk holds back or
+    // baked.
+    let prewarmed = chunk
+        .take_prewarmed(atlas)
+        .filter(|_| !holds_back_layers && baked_layers == 0);
+    let attributes = match prewarmed {
+        Some(attributes) => attributes,
+        None => chunk.tiles_to_renderer_parts(chunk_dimensions, atlas, &settings),
+    };
+    attributes.set_mesh_attributes(&mut mesh);
+    stats.add_rebuild(&mesh);
+    let mesh_handle = meshes.add(mesh);
+    chunk.set_mesh(atlas, mesh_handle.clone());
+
+    // The depths of the tiles are added to the z of the chunk.
+    let translation = Vec3::new(translation_x, translation_y, chunk_z);
+    let pipeline = RenderPipeline::new(pipeline_handle);
+    let entity = commands
+        .spawn()
+        .insert_bundle(ChunkBundle {
+            point,
+            texture_atlas,
+            atlas: AtlasId(atlas),
+            mesh: mesh_handle.clone_weak(),
+            transform: Transform::from_translation(translation),
+            render_pipelines: RenderPipelines::from_pipelines(vec![pipeline]),
+            draw: Default::default(),
+            visible: tilemap_visible.clone(),
+            main_pass: MainPass,
+            global_transform: Default::default(),
+            modified: Default::default(),
+            dirty_tiles: Default::default(),
+            time: Default::default(),
+            secondary_texture: SecondaryTexture(secondary_texture),
+            color_grading: ChunkColorGrading(color_grading),
+        })
+        .id();
+
+    chunk.set_entity(atlas, entity);
+    Some(entity)
+}
+
+/// Bakes the static layers of a chunk into a texture and spawns the sprite
+/// which draws them, returning it if it was spawned.
+///
+/// The layers stay in the meshes of the chunk if the tilemap is hexagonal or
+/// the texture of the main texture atlas is not loaded yet.
+fn spawn_baked_layers(
+    commands: &mut Commands,
+    textures: &mut Assets<Texture>,
+    materials: &mut Assets<ColorMaterial>,
+    texture_atlases: &Assets<TextureAtlas>,
+    tilemap_visible: &Visible,
+    tilemap: &mut Tilemap,
+    point: Point2,
+) -> Option<Entity> {
+    if tilemap.static_layers().is_empty() || tilemap.topology() != GridTopology::Square {
+        return None;
+    }
+    let texture_atlas = texture_atlases.get(tilemap.get_texture_atlas(0)?)?;
+    let texture = textures.get(&texture_atlas.texture)?;
+    let (baked_layers, image) = tilemap.bake_chunk_layers(point, texture_atlas, texture)?;
+
+    let chunk_dimensions = tilemap.chunk_dimensions();
+    let texture_dimensions = tilemap.texture_dimensions();
+    let (translation_x, translation_y) = chunk_translation(tilemap, point);
+    let size = Vec2::new(
+        (chunk_dimensions.width * texture_dimensions.width) as f32,
+        (chunk_dimensions.height * texture_dimensions.height) as f32,
+    );
+    // Below the meshes of the chunk, unless the layers have a depth.
+    let depth = baked_layers
+        .iter()
+        .filter_map(|sprite_order| tilemap.layer_depths().get(sprite_order).copied())
+        .fold(0.0, f32::min);
+    let z = tilemap.depth_z(depth - 0.5);
+    let material = materials.add(ColorMaterial::texture(textures.add(image)));
+    let entity = commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite::new(size),
+            material,
+            visible: tilemap_visible.clone(),
+            transform: Transform::from_translation(Vec3::new(translation_x, translation_y, z)),
+            ..Default::default()
+        })
+        .id();
+
+    let chunk = tilemap.chunks_mut().get_mut(&point)?;
+    chunk.set_baked_layers(baked_layers, entity);
+    Some(entity)
+}
+
 /// Handles all newly spawned chunks and attempts to spawn them.
+///
+/// A chunk always spawns an entity for the main texture atlas and one for
+/// every other texture atlas its tiles use, after baking its static layers.
 fn handle_spawned_chunks(
     commands: &mut Commands,
+    textures: &mut Assets<Texture>,
+    materials: &mut Assets<ColorMaterial>,
+    texture_atlases: &Assets<TextureAtlas>,
     tilemap_entity: Entity,
     tilemap_visible: &Visible,
     meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
     tilemap: &mut Tilemap,
     spawned_chunks: Vec<Point2>,
 ) {
-    let capacity = spawned_chunks.len();
-    let mut entities = Vec::with_capacity(capacity);
+    let mut children: HashMap<Entity, Vec<Entity>> = HashMap::default();
     for point in spawned_chunks.into_iter() {
-        if tilemap.spawned_chunks().contains(&(point.x, point.y)) {
+        // The chunk may have been deleted since it was asked to spawn.
+        if tilemap.spawned_chunks().contains(&(point.x, point.y))
+            || tilemap.get_chunk(&point).is_none()
+        {
             continue;
         } else {
             tilemap.spawned_chunks_mut().insert((point.x, point.y));
+            tilemap.fill_fog(point);
+            tilemap.fill_light(point);
+            tilemap.fill_shade(point);
+            tilemap.hold_back_layers(point);
+            tilemap.queue_colliders(point);
         }
 
-        let chunk_dimensions = tilemap.chunk_dimensions();
-        let texture_dimensions = tilemap.texture_dimensions();
-        let texture_atlas = tilemap.texture_atlas().clone_weak();
-        let pipeline_handle = tilemap.topology().into_pipeline_handle();
-        let chunk_mesh = tilemap.chunk_mesh().clone();
-        let topology = tilemap.topology();
-        let chunk = if let Some(chunk) = tilemap.chunks_mut().get_mut(&point) {
-            chunk
+        let mut atlases = if let Some(chunk) = tilemap.get_chunk(&point) {
+            chunk.atlases()
         } else {
-            // NOTE: should this instead create a chunk if it doesn't exist yet?
             warn!("Can not get chunk at {}, possible bug report me", &point);
             continue;
         };
-        let mut mesh = Mesh::from(&chunk_mesh);
-        let (indexes, colors) = chunk.tiles_to_renderer_parts(chunk_dimensions);
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes);
-        mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors);
-        let mesh_handle = meshes.add(mesh);
-        chunk.set_mesh(mesh_handle.clone());
-
-        let (translation_x, translation_y) = topology_translation(
-            topology,
-            chunk.point(),
-            chunk_dimensions,
-            texture_dimensions,
-        );
-        let translation = Vec3::new(translation_x, translation_y, 1.0);
-        let pipeline = RenderPipeline::new(pipeline_handle.clone_weak().typed());
-        let entity = commands
-            .spawn()
-            .insert_bundle(ChunkBundle {
+        atlases.insert(0);
+        let mut atlases: Vec<usize> = atlases.into_iter().collect();
+        atlases.sort_unstable();
+        let mut entities = Vec::with_capacity(atlases.len() + 1);
+        if let Some(entity) = spawn_baked_layers(
+            commands,
+            textures,
+            materials,
+            texture_atlases,
+            tilemap_visible,
+            tilemap,
+            point,
+        ) {
+            entities.push(entity);
+        }
+        for atlas in atlases {
+            if let Some(entity) = spawn_chunk_entity(
+                commands,
+                tilemap_visible,
+                meshes,
+                stats,
+                tilemap,
                 point,
-                texture_atlas: texture_atlas.clone_weak(),
-                mesh: mesh_handle.clone_weak(),
-                transform: Transform::from_translation(translation),
-                render_pipelines: RenderPipelines::from_pipelines(vec![pipeline]),
-                draw: Default::default(),
-                visible: tilemap_visible.clone(),
-                main_pass: MainPass,
-                global_transform: Default::default(),
-                modified: Default::default(),
-            })
-            .id();
+                atlas,
+            ) {
+                entities.push(entity);
+            }
+        }
+        if !entities.is_empty() {
+            let parent = chunk_parent(commands, tilemap_entity, tilemap, point);
+            children.entry(parent).or_default().extend(entities);
+        }
 
         info!("Chunk {} spawned", point);
-
-        chunk.set_entity(entity);
-        entities.push(entity);
     }
-    commands.entity(tilemap_entity).push_children(&entities);
+    for (parent, entities) in children {
+        commands.entity(parent).push_children(&entities);
+    }
 }
 
-/// Handles all modified chunks and flags them.
+/// Handles all modified chunks and flags them along with the tiles which
+/// changed.
+///
+/// If a spawned chunk now uses a texture atlas which it has no entity for yet,
+/// the entity is spawned.
 fn handle_modified_chunks(
-    modified_query: &mut Query<&mut Modified>,
+    commands: &mut Commands,
+    tilemap_entity: Entity,
+    tilemap_visible: &Visible,
+    meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
+    modified_query: &mut Query<(&mut Modified, &mut DirtyTiles)>,
     tilemap: &mut Tilemap,
     modified_chunks: Vec<Point2>,
 ) {
@@ -137,23 +366,98 @@ fn handle_modified_chunks(
             warn!("Can not get chunk at {}, skipping", &point);
             continue;
         };
-        if let Some(chunk_entity) = chunk.get_entity() {
-            if let Ok(mut modified) = modified_query.get_mut(chunk_entity) {
+        if !chunk.has_mesh() {
+            continue;
+        }
+        // The low resolution stand-in is baked again with the changes.
+        if let Some(lod_entity) = chunk.take_lod_entity() {
+            commands.entity(lod_entity).despawn_recursive();
+        }
+        let dirty_tiles = chunk.take_dirty_tiles();
+        for chunk_entity in chunk.entities() {
+            if let Ok((mut modified, mut dirty)) = modified_query.get_mut(chunk_entity) {
                 modified.0 += 1;
+                dirty.merge(dirty_tiles.as_ref());
             }
-        } else {
+        }
+        let mut new_atlases: Vec<usize> = chunk
+            .atlases()
+            .into_iter()
+            .filter(|atlas| chunk.get_entity(*atlas).is_none())
+            .collect();
+        new_atlases.sort_unstable();
+        let mut entities = Vec::with_capacity(new_atlases.len());
+        for atlas in new_atlases {
+            if let Some(entity) = spawn_chunk_entity(
+                commands,
+                tilemap_visible,
+                meshes,
+                stats,
+                tilemap,
+                point,
+                atlas,
+            ) {
+                entities.push(entity);
+            }
+        }
+        if !entities.is_empty() {
+            let parent = chunk_parent(commands, tilemap_entity, tilemap, point);
+            commands.entity(parent).push_children(&entities);
+        }
+    }
+}
+
+/// Despawns the entities of a chunk and frees its meshes, returning `true`
+/// if it had any entities.
+fn despawn_chunk_entities(commands: &mut Commands, chunk: &mut Chunk) -> bool {
+    chunk.take_meshes();
+    chunk.set_light(None);
+    chunk.compact();
+    if let Some(lod_entity) = chunk.take_lod_entity() {
+        commands.entity(lod_entity).despawn_recursive();
+    }
+
+    let entities = chunk.take_entities();
+    if entities.is_empty() {
+        return false;
+    }
+    for entity in entities {
+        commands.entity(entity).despawn_recursive();
+    }
+    true
+}
+
+/// Despawns the entities of the regions which no longer have a spawned
+/// chunk.
+fn despawn_empty_regions(commands: &mut Commands, tilemap: &mut Tilemap, regions: HashSet<Point2>) {
+    for region in regions {
+        let occupied = tilemap
+            .spawned_chunks()
+            .iter()
+            .any(|point| tilemap.chunk_region(*point) == Some(region));
+        if occupied {
             continue;
-        };
+        }
+        if let Some(entity) = tilemap.region_entities_mut().remove(&region) {
+            commands.entity(entity).despawn_recursive();
+        }
     }
 }
 
 /// Handles all despawned chunks and attempts to despawn them.
+///
+/// The entity of a region is despawned along with the last spawned chunk of
+/// the region.
 fn handle_despawned_chunks(
     commands: &mut Commands,
     tilemap: &mut Tilemap,
     despawned_chunks: Vec<Point2>,
 ) {
+    let mut regions = HashSet::default();
     for point in despawned_chunks.into_iter() {
+        if let Some(region) = tilemap.chunk_region(point) {
+            regions.insert(region);
+        }
         let chunk = if let Some(chunk) = tilemap.chunks_mut().get_mut(&point) {
             chunk
         } else {
@@ -161,27 +465,38 @@ fn handle_despawned_chunks(
             continue;
         };
 
-        chunk.take_mesh();
+        if despawn_chunk_entities(commands, chunk) {
+            info!("Chunk {} despawned", point);
+        }
+    }
+    despawn_empty_regions(commands, tilemap, regions);
+}
 
-        match chunk.take_entity() {
-            Some(e) => {
-                commands.entity(e).despawn_recursive();
-                info!("Chunk {} despawned", point);
-            }
-            None => {
-                continue;
-            }
+/// Handles all deleted chunks and despawns the ones which were spawned.
+fn handle_deleted_chunks(commands: &mut Commands, tilemap: &mut Tilemap) {
+    let mut regions = HashSet::default();
+    for mut chunk in tilemap.take_deleted_chunks() {
+        let point = chunk.point();
+        if let Some(region) = tilemap.chunk_region(point) {
+            regions.insert(region);
+        }
+        if despawn_chunk_entities(commands, &mut chunk) {
+            info!("Chunk {} deleted", point);
         }
     }
+    despawn_empty_regions(commands, tilemap, regions);
 }
 
-/// Recalculates a mesh.
+/// Recalculates a mesh of a texture atlas.
 fn recalculate_mesh(
     meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
     mesh: &Handle<Mesh>,
+    atlas: usize,
     chunk: &Chunk,
     chunk_mesh: &ChunkMesh,
     chunk_dimensions: Dimension3,
+    settings: &MeshSettings,
 ) {
     let mesh = match meshes.get_mut(mesh) {
         None => {
@@ -190,26 +505,45 @@ fn recalculate_mesh(
         }
         Some(m) => m,
     };
-    let (indexes, colors) = chunk.tiles_to_renderer_parts(chunk_dimensions);
-    mesh.set_indices(Some(Indices::U32(chunk_mesh.indices.clone())));
-    mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, chunk_mesh.vertices.clone());
-    mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_INDEX, indexes);
-    mesh.set_attribute(ChunkMesh::ATTRIBUTE_TILE_COLOR, colors);
+    let baked_layers = chunk.baked_layer_count();
+    if baked_layers == 0 {
+        mesh.set_indices(Some(Indices::U32(chunk_mesh.indices.clone())));
+        mesh.set_attribute(render::ATTRIBUTE_POSITION.name, chunk_mesh.vertices.clone());
+    } else {
+        let chunk_mesh = chunk_mesh.without_layers(chunk_dimensions, baked_layers);
+        mesh.set_indices(Some(Indices::U32(chunk_mesh.indices)));
+        mesh.set_attribute(render::ATTRIBUTE_POSITION.name, chunk_mesh.vertices);
+    }
+    chunk
+        .tiles_to_renderer_parts(chunk_dimensions, atlas, settings)
+        .set_mesh_attributes(mesh);
+    stats.add_rebuild(mesh);
 }
 
 /// Adds a sprite layer to all chunks and recalculates the mesh.
 fn handle_add_sprite_layers(
     meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
     tilemap: &mut Tilemap,
     add_sprite_layers: Vec<(LayerKind, usize)>,
 ) {
     let chunk_dimensions = tilemap.chunk_dimensions();
     let chunk_mesh = tilemap.chunk_mesh().clone();
-    for chunk in tilemap.chunks_mut().values_mut() {
+    let (chunks, settings) = tilemap.chunks_with_mesh_settings_mut();
+    for chunk in chunks.values_mut() {
         for (kind, sprite_layer) in &add_sprite_layers {
-            chunk.add_sprite_layer(&kind, *sprite_layer, chunk_dimensions);
-            if let Some(mesh) = chunk.mesh() {
-                recalculate_mesh(meshes, mesh, chunk, &chunk_mesh, chunk_dimensions);
+            chunk.add_sprite_layer(kind, *sprite_layer, chunk_dimensions);
+            for (atlas, mesh) in chunk.meshes() {
+                recalculate_mesh(
+                    meshes,
+                    stats,
+                    mesh,
+                    atlas,
+                    chunk,
+                    &chunk_mesh,
+                    chunk_dimensions,
+                    &settings,
+                );
             }
         }
     }
@@ -218,16 +552,27 @@ fn handle_add_sprite_layers(
 /// Removes a sprite layer from all chunks and recalculates the mesh if needed.
 fn handle_remove_sprite_layers(
     meshes: &mut Assets<Mesh>,
+    stats: &mut TilemapFrameStats,
     tilemap: &mut Tilemap,
     remove_sprite_layers: Vec<usize>,
 ) {
     let chunk_dimensions = tilemap.chunk_dimensions();
     let chunk_mesh = tilemap.chunk_mesh().clone();
+    let (chunks, settings) = tilemap.chunks_with_mesh_settings_mut();
     for sprite_layer in remove_sprite_layers {
-        for chunk in tilemap.chunks_mut().values_mut() {
+        for chunk in chunks.values_mut() {
             chunk.remove_sprite_layer(sprite_layer);
-            if let Some(mesh) = chunk.mesh() {
-                recalculate_mesh(meshes, mesh, chunk, &chunk_mesh, chunk_dimensions);
+            for (atlas, mesh) in chunk.meshes() {
+                recalculate_mesh(
+                    meshes,
+                    stats,
+                    mesh,
+                    atlas,
+                    chunk,
+                    &chunk_mesh,
+                    chunk_dimensions,
+                    &settings,
+                );
             }
         }
     }
@@ -242,22 +587,36 @@ fn handle_remove_sprite_layers(
 /// 1. Spawn chunks
 /// 1. Modify chunks
 /// 1. Despawn chunks
+/// 1. Delete chunks
 pub(crate) fn tilemap_events(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
     mut tilemap_query: Query<(Entity, &mut Tilemap, &Visible)>,
-    mut modified_query: Query<&mut Modified>,
+    mut modified_query: Query<(&mut Modified, &mut DirtyTiles)>,
+    frame_stats: Option<ResMut<TilemapFrameStats>>,
 ) {
+    let mut stats = TilemapFrameStats::default();
     for (tilemap_entity, mut tilemap, tilemap_visible) in tilemap_query.iter_mut() {
-        tilemap.chunk_events_update();
-        let mut reader = tilemap.chunk_events().get_reader();
+        tilemap.next_recording_frame();
+        // Events of a paused tilemap stay queued until it is resumed, and no
+        // events were handled on this frame.
+        if tilemap.is_paused() {
+            tilemap.set_handled_chunk_events(Vec::new());
+            continue;
+        }
+        let events = tilemap.take_chunk_events();
+        stats.add_chunk_events(events.len());
 
         let mut modified_chunks = Vec::new();
         let mut spawned_chunks = Vec::new();
         let mut despawned_chunks = Vec::new();
+        let mut has_deleted_chunks = false;
         let mut add_sprite_layers = Vec::new();
         let mut remove_sprite_layers = Vec::new();
-        for event in reader.iter(&tilemap.chunk_events()) {
+        for event in events.iter() {
             use crate::TilemapChunkEvent::*;
             match event {
                 Modified { ref point } => {
@@ -269,6 +628,10 @@ pub(crate) fn tilemap_events(
                 Despawned { ref point } => {
                     despawned_chunks.push(*point);
                 }
+                Deleted { .. } => {
+                    has_deleted_chunks = true;
+                }
+                NeedsPopulation { .. } | Created { .. } => {}
                 AddLayer {
                     ref layer_kind,
                     ref sprite_layer,
@@ -284,29 +647,260 @@ pub(crate) fn tilemap_events(
         if !spawned_chunks.is_empty() {
             handle_spawned_chunks(
                 &mut commands,
+                &mut textures,
+                &mut materials,
+                &texture_atlases,
                 tilemap_entity,
                 tilemap_visible,
                 &mut meshes,
+                &mut stats,
                 &mut tilemap,
                 spawned_chunks,
             );
         }
 
         if !modified_chunks.is_empty() {
-            handle_modified_chunks(&mut modified_query, &mut tilemap, modified_chunks);
+            handle_modified_chunks(
+                &mut commands,
+                tilemap_entity,
+                tilemap_visible,
+                &mut meshes,
+                &mut stats,
+                &mut modified_query,
+                &mut tilemap,
+                modified_chunks,
+            );
         }
 
         if !despawned_chunks.is_empty() {
             handle_despawned_chunks(&mut commands, &mut tilemap, despawned_chunks);
         }
 
+        if has_deleted_chunks {
+            handle_deleted_chunks(&mut commands, &mut tilemap);
+        }
+
         if !add_sprite_layers.is_empty() {
-            handle_add_sprite_layers(&mut meshes, &mut tilemap, add_sprite_layers);
+            handle_add_sprite_layers(&mut meshes, &mut stats, &mut tilemap, add_sprite_layers);
         }
 
         if !remove_sprite_layers.is_empty() {
-            handle_remove_sprite_layers(&mut meshes, &mut tilemap, remove_sprite_layers);
+            handle_remove_sprite_layers(
+                &mut meshes,
+                &mut stats,
+                &mut tilemap,
+                remove_sprite_layers,
+            );
         }
+
+        tilemap.set_handled_chunk_events(events);
+    }
+    if let Some(mut frame_stats) = frame_stats {
+        frame_stats.merge(stats);
+    }
+}
+
+/// Builds the tile attributes of the chunks waiting to be prewarmed in
+/// parallel on the compute task pool.
+pub(crate) fn tilemap_prewarm(
+    task_pool: Res<ComputeTaskPool>,
+    mut tilemap_query: Query<&mut Tilemap>,
+) {
+    for mut tilemap in tilemap_query.iter_mut() {
+        if tilemap.is_paused() {
+            continue;
+        }
+        let prewarm_queue = tilemap.take_prewarm_queue();
+        if prewarm_queue.is_empty() {
+            continue;
+        }
+        let chunk_dimensions = tilemap.chunk_dimensions();
+        let prewarmed = {
+            let settings = tilemap.mesh_settings();
+            let spawned_chunks = tilemap.spawned_chunks();
+            let chunks = tilemap.chunks();
+            task_pool.scope(|scope| {
+                for point in prewarm_queue.iter() {
+                    let chunk = match chunks.get(point) {
+                        Some(chunk) => chunk,
+                        None => continue,
+                    };
+                    if chunk.is_prewarmed() || spawned_chunks.contains(&(point.x, point.y)) {
+                        continue;
+                    }
+                    scope.spawn(async move {
+                        let mut atlases = chunk.atlases();
+                        atlases.insert(0);
+                        let attributes: Vec<(usize, TileAttributes)> = atlases
+                            .into_iter()
+                            .map(|atlas| {
+                                let attributes = chunk.tiles_to_renderer_parts(
+                                    chunk_dimensions,
+                                    atlas,
+                                    &settings,
+                                );
+                                (atlas, attributes)
+                            })
+                            .collect();
+                        (chunk.point(), attributes)
+                    });
+                }
+            })
+        };
+        for (point, attributes) in prewarmed {
+            if let Some(chunk) = tilemap.chunks_mut().get_mut(&point) {
+                for (atlas, attributes) in attributes {
+                    chunk.set_prewarmed(atlas, attributes);
+                }
+            }
+        }
+    }
+}
+
+/// Loads chunks from any tilemap streams within their time budget and sends
+/// the progress as events.
+pub(crate) fn tilemap_streaming(
+    mut tilemap_query: Query<(Entity, &mut Tilemap)>,
+    mut stream_events: EventWriter<TilemapStreamEvent>,
+) {
+    for (entity, mut tilemap) in tilemap_query.iter_mut() {
+        if tilemap.is_paused() || tilemap.streaming_progress().is_none() {
+            continue;
+        }
+        match tilemap.poll_streaming() {
+            Ok(Some(progress)) => stream_events.send(TilemapStreamEvent { entity, progress }),
+            Ok(None) => {}
+            Err(e) => error!("failed to load tilemap stream: {}", e),
+        }
+    }
+}
+
+/// Sends an event with the tiles of every chunk which was despawned or
+/// removed.
+pub(crate) fn tilemap_unload_events(
+    mut tilemap_query: Query<(Entity, &mut Tilemap)>,
+    mut unload_events: EventWriter<TilemapChunkUnloadEvent>,
+) {
+    for (entity, mut tilemap) in tilemap_query.iter_mut() {
+        for chunk in tilemap.take_unloaded_chunks() {
+            unload_events.send(TilemapChunkUnloadEvent { entity, chunk });
+        }
+    }
+}
+
+/// Builds the colliders of spawned chunks within their budget, nearest to the
+/// camera first, and sends them as events.
+pub(crate) fn tilemap_colliders(
+    mut tilemap_query: Query<(Entity, &mut Tilemap, &GlobalTransform)>,
+    camera_query: Query<(&Camera, &Transform)>,
+    mut collider_events: EventWriter<TilemapCollidersEvent>,
+) {
+    for (entity, mut tilemap, tilemap_transform) in tilemap_query.iter_mut() {
+        if tilemap.is_paused() || !tilemap.has_collider_budget() {
+            continue;
+        }
+        let focus = camera_query
+            .iter()
+            .find(|(camera, _camera_transform)| tilemap.views_camera(camera))
+            .map(|(_camera, camera_transform)| {
+                let point = tilemap
+                    .world_to_point(tilemap_transform, camera_transform.translation.truncate());
+                Point2::from(tilemap.point_to_chunk_point(point))
+            })
+            .unwrap_or_default();
+        for colliders in tilemap.build_colliders(focus) {
+            collider_events.send(TilemapCollidersEvent { entity, colliders });
+        }
+    }
+}
+
+/// Checks the sprite indices of inserted tiles against their texture atlases
+/// and sends an event for every tile that is out of bounds.
+///
+/// Tiles whose texture atlas is not loaded yet are checked again next frame.
+/// Tiles are dropped with a warning if their texture atlas is the default
+/// handle, such as the one of a tilemap loaded from a scene, which never loads,
+/// or if it did not load within [`MAX_VALIDATION_ATTEMPTS`] frames.
+pub(crate) fn tilemap_sprite_validation(
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut tilemap_query: Query<(Entity, &mut Tilemap)>,
+    mut sprite_index_events: EventWriter<TilemapSpriteIndexEvent>,
+) {
+    for (entity, mut tilemap) in tilemap_query.iter_mut() {
+        let tiles = tilemap.take_unvalidated_tiles();
+        if tiles.is_empty() {
+            continue;
+        }
+        let mut pending = Vec::new();
+        let mut dropped = 0;
+        for mut tile in tiles {
+            let len = match tilemap.get_texture_atlas(tile.atlas) {
+                Some(handle) => {
+                    let never_loads = *handle == Handle::default();
+                    let texture_atlas = texture_atlases.get(handle);
+                    #[cfg(feature = "failure_injection")]
+                    let texture_atlas = texture_atlas.filter(|_| {
+                        !tilemap.take_injected_failure(InjectedFailure::UnloadedTextureAtlas)
+                    });
+                    match texture_atlas {
+                        Some(texture_atlas) => texture_atlas.len(),
+                        None => {
+                            tile.attempts += 1;
+                            if never_loads || tile.attempts >= MAX_VALIDATION_ATTEMPTS {
+                                dropped += 1;
+                            } else {
+                                pending.push(tile);
+                            }
+                            continue;
+                        }
+                    }
+                }
+                None => 0,
+            };
+            if tilemap.themed_sprite_index(tile.sprite_index) >= len {
+                warn!(
+                    "Tile at {} has sprite index {} which is out of bounds of texture atlas {}",
+                    tile.point, tile.sprite_index, tile.atlas
+                );
+                sprite_index_events.send(TilemapSpriteIndexEvent {
+                    entity,
+                    point: tile.point,
+                    sprite_order: tile.sprite_order,
+                    atlas: tile.atlas,
+                    sprite_index: tile.sprite_index,
+                });
+            }
+        }
+        if dropped > 0 {
+            warn!(
+                "{} tiles of tilemap {:?} were not validated as their texture atlas never loaded",
+                dropped, entity
+            );
+        }
+        tilemap.return_unvalidated_tiles(pending);
+    }
+}
+
+/// Hides the sprite layers of tilemaps which are outside of their zoom range at
+/// the zoom of the first orthographic camera driving them.
+pub(crate) fn tilemap_layer_zoom(
+    camera_query: Query<(&Camera, &OrthographicProjection)>,
+    mut tilemap_query: Query<&mut Tilemap>,
+) {
+    for mut tilemap in tilemap_query.iter_mut() {
+        if tilemap.is_paused() {
+            continue;
+        }
+        let scale = if let Some((_camera, projection)) = camera_query
+            .iter()
+            .find(|(camera, _projection)| tilemap.views_camera(camera))
+        {
+            projection.scale
+        } else {
+            continue;
+        };
+        let hidden_layers = tilemap.layers_hidden_at(scale);
+        tilemap.set_hidden_layers(hidden_layers);
     }
 }
 
@@ -322,7 +916,14 @@ pub fn tilemap_visibility_change(
             continue;
         };
         for chunk in tilemap.chunks().values() {
-            if let Some(entity) = chunk.get_entity() {
+            // Chunks with a low resolution stand-in keep their meshes hidden.
+            if let Some(lod_entity) = chunk.lod_entity() {
+                if let Ok(mut lod_visible) = visibles.get_mut(lod_entity) {
+                    *lod_visible = tilemap_visible.clone();
+                }
+                continue;
+            }
+            for entity in chunk.entities() {
                 if let Ok(mut chunk_visible) = visibles.get_mut(entity) {
                     *chunk_visible = tilemap_visible.clone();
                 }
@@ -334,7 +935,12 @@ pub fn tilemap_visibility_change(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{entity::TilemapBundle, tilemap::TilemapBuilder};
+    use crate::{
+        chunk::RawTile,
+        entity::TilemapBundle,
+        tile::Tile,
+        tilemap::{TilemapBuilder, TilemapLayer},
+    };
 
     fn new_tilemap() -> Tilemap {
         TilemapBuilder::new()
@@ -418,7 +1024,7 @@ mod tests {
                 ],
             ),
         ];
-        let chunk_points = vec![
+        let chunk_points = [
             Point2::new(-2, -2),
             Point2::new(-1, -1),
             Point2::new(0, 0),
@@ -452,6 +1058,9 @@ mod tests {
             .add_stage("update", SystemStage::parallel())
             .add_system_to_stage("update", tilemap_events.system())
             .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
             .app;
         let mut command_queue = CommandQueue::default();
         let mut commands = Commands::new(&mut command_queue, &app.world);
@@ -531,6 +1140,7 @@ mod tests {
                 .iter_mut(&mut app.world)
                 .next()
                 .unwrap();
+            #[allow(deprecated)]
             tilemap.despawn_chunk(Point2::new(-1, -1)).unwrap();
         }
 
@@ -550,4 +1160,449 @@ mod tests {
         let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
         assert_eq!(meshes.len(), 2);
     }
+
+    #[test]
+    fn spawn_chunks_within_depth_range() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(2, 2, 1)
+            .add_layer(
+                TilemapLayer {
+                    kind: LayerKind::Sparse,
+                },
+                1,
+            )
+            .y_sorted_layer(1)
+            .layer_depth(0, -2.0)
+            .depth_range(10.0, 18.5)
+            .finish()
+            .unwrap();
+        for point in [(0, 0), (1, 1)].iter() {
+            tilemap.insert_chunk(*point).unwrap();
+            tilemap.spawn_chunk(*point).unwrap();
+        }
+        assert!(TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .depth_range(5.0, 5.0)
+            .finish()
+            .is_err());
+        app.world
+            .spawn()
+            .insert(tilemap)
+            .insert(Visible::default())
+            .insert(GlobalTransform::default());
+
+        app.update();
+
+        // Every vertex of every chunk is drawn within the range.
+        let mut chunks = 0;
+        let mut query = app.world.query::<(&Transform, &Handle<Mesh>, &Modified)>();
+        let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
+        for (transform, mesh, _) in query.iter(&app.world) {
+            chunks += 1;
+            assert_eq!(transform.translation.z, 10.0);
+            let depths = match meshes
+                .get(mesh)
+                .and_then(|mesh| mesh.attribute(render::ATTRIBUTE_TILE_DEPTH.name))
+            {
+                Some(VertexAttributeValues::Float(depths)) => depths,
+                _ => panic!("the chunk mesh has no depths"),
+            };
+            assert_eq!(depths.len(), 2 * 2 * 2 * 4);
+            for depth in depths.iter() {
+                let z = transform.translation.z + depth;
+                assert!((10.0..=18.5).contains(&z));
+            }
+        }
+        assert_eq!(chunks, 2);
+    }
+
+    #[test]
+    fn spawn_chunks_in_regions() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_regions(2, 2)
+            .finish()
+            .unwrap();
+        for point in [(0, 0), (1, 1), (-1, -1)].iter() {
+            tilemap.insert_chunk(*point).unwrap();
+            tilemap.spawn_chunk(*point).unwrap();
+        }
+        let tilemap_entity = app
+            .world
+            .spawn()
+            .insert(tilemap)
+            .insert(Visible::default())
+            .id();
+
+        app.update();
+
+        let (region, far_region, chunk_dimensions) = {
+            let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+            (
+                tilemap.region_entity((0, 0)).unwrap(),
+                tilemap.region_entity((-1, -1)).unwrap(),
+                tilemap.chunk_dimensions(),
+            )
+        };
+        assert_eq!(app.world.get::<Children>(tilemap_entity).unwrap().len(), 2);
+        assert_eq!(app.world.get::<Children>(region).unwrap().len(), 2);
+        let far_children = app.world.get::<Children>(far_region).unwrap();
+        assert_eq!(far_children.len(), 1);
+        // The chunk is translated from the bottom left chunk of its region.
+        let chunk_transform = app.world.get::<Transform>(far_children[0]).unwrap();
+        assert_eq!(
+            chunk_transform.translation.x,
+            (chunk_dimensions.width * 32) as f32
+        );
+        let region_transform = app.world.get::<Transform>(far_region).unwrap();
+        assert_eq!(
+            region_transform.translation.x,
+            -2.0 * (chunk_dimensions.width * 32) as f32
+        );
+
+        {
+            let mut tilemap = app.world.get_mut::<Tilemap>(tilemap_entity).unwrap();
+            tilemap.unload_chunk((-1, -1)).unwrap();
+        }
+
+        app.update();
+
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(tilemap.region_entity((-1, -1)).is_none());
+        assert!(app.world.get_entity(far_region).is_none());
+        assert!(tilemap.region_entity((0, 0)).is_some());
+    }
+
+    #[test]
+    fn delete_spawned_chunk() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = new_tilemap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        let tilemap_entity = app
+            .world
+            .spawn()
+            .insert(tilemap)
+            .insert(Visible::default())
+            .id();
+
+        app.update();
+
+        let chunk_entity = app.world.get::<Children>(tilemap_entity).unwrap()[0];
+        {
+            let mut tilemap = app.world.get_mut::<Tilemap>(tilemap_entity).unwrap();
+            tilemap.delete_chunk((0, 0)).unwrap();
+            // The chunk is gone right away, so it can be inserted again.
+            tilemap.insert_chunk((0, 0)).unwrap();
+        }
+
+        app.update();
+
+        assert!(app.world.get_entity(chunk_entity).is_none());
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(tilemap.spawned_chunks().is_empty());
+        assert!(matches!(
+            tilemap.chunk_events(),
+            [crate::TilemapChunkEvent::Deleted { .. }]
+        ));
+    }
+
+    #[test]
+    fn paused_tilemap_handles_no_events() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = new_tilemap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        let tilemap_entity = app
+            .world
+            .spawn()
+            .insert(tilemap)
+            .insert(Visible::default())
+            .id();
+
+        app.update();
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(!tilemap.chunk_events().is_empty());
+
+        {
+            let mut tilemap = app.world.get_mut::<Tilemap>(tilemap_entity).unwrap();
+            tilemap.set_paused(true);
+            tilemap.unload_chunk((0, 0)).unwrap();
+        }
+
+        // The events of the last frame are not seen again while paused.
+        app.update();
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(tilemap.chunk_events().is_empty());
+        app.update();
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(tilemap.chunk_events().is_empty());
+
+        // The queued event is handled once resumed.
+        app.world
+            .get_mut::<Tilemap>(tilemap_entity)
+            .unwrap()
+            .set_paused(false);
+        app.update();
+        let tilemap = app.world.get::<Tilemap>(tilemap_entity).unwrap();
+        assert!(matches!(
+            tilemap.chunk_events(),
+            [crate::TilemapChunkEvent::Despawned { .. }]
+        ));
+    }
+
+    #[test]
+    fn validate_sprite_indices() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_event::<TilemapSpriteIndexEvent>()
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_sprite_validation.system())
+            .add_asset::<TextureAtlas>()
+            .app;
+
+        let texture_atlas_handle = {
+            let mut texture_atlas = TextureAtlas::new_empty(Handle::default(), Vec2::new(64., 32.));
+            texture_atlas.add_texture(Rect {
+                min: Vec2::new(0., 0.),
+                max: Vec2::new(32., 32.),
+            });
+            texture_atlas.add_texture(Rect {
+                min: Vec2::new(32., 0.),
+                max: Vec2::new(64., 32.),
+            });
+            let mut texture_atlases = app
+                .world
+                .get_resource_mut::<Assets<TextureAtlas>>()
+                .unwrap();
+            texture_atlases.add(texture_atlas)
+        };
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .validate_sprite_indices()
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tiles = vec![
+            Tile {
+                point: (1, 1),
+                sprite_index: 1,
+                ..Default::default()
+            },
+            Tile {
+                point: (-2, 3),
+                sprite_index: 2,
+                ..Default::default()
+            },
+            Tile {
+                point: (4, 4),
+                sprite_index: 0,
+                atlas: 1,
+                ..Default::default()
+            },
+        ];
+        tilemap.insert_tiles(tiles).unwrap();
+        let tilemap_entity = app.world.spawn().insert(tilemap).id();
+
+        app.update();
+
+        let events = app
+            .world
+            .get_resource::<Events<TilemapSpriteIndexEvent>>()
+            .unwrap();
+        let mut offending: Vec<(Point3, usize, usize)> = events
+            .get_reader()
+            .iter(events)
+            .map(|event| {
+                assert_eq!(event.entity, tilemap_entity);
+                (event.point, event.atlas, event.sprite_index)
+            })
+            .collect();
+        offending.sort_by_key(|(_, atlas, _)| *atlas);
+        assert_eq!(
+            offending,
+            vec![(Point3::new(-2, 3, 0), 0, 2), (Point3::new(4, 4, 0), 1, 0)]
+        );
+    }
+
+    #[test]
+    fn drop_never_loaded_sprite_indices() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_event::<TilemapSpriteIndexEvent>()
+            .add_stage("update", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_sprite_validation.system())
+            .add_asset::<TextureAtlas>()
+            .app;
+
+        // The default handle of a tilemap loaded from a scene never loads, and
+        // neither does a handle of an atlas which was never added.
+        let handles = vec![
+            Handle::default(),
+            Handle::weak(HandleId::random::<TextureAtlas>()),
+        ];
+        let mut entities = Vec::new();
+        for handle in handles {
+            let mut tilemap = TilemapBuilder::new()
+                .texture_atlas(handle)
+                .texture_dimensions(32, 32)
+                .validate_sprite_indices()
+                .finish()
+                .unwrap();
+            tilemap.insert_chunk((0, 0)).unwrap();
+            tilemap
+                .insert_tile(Tile {
+                    point: (1, 1),
+                    sprite_index: 1,
+                    ..Default::default()
+                })
+                .unwrap();
+            entities.push(app.world.spawn().insert(tilemap).id());
+        }
+        let pending = |app: &mut ::bevy_app::App, entity: Entity| {
+            let mut tilemap = app.world.get_mut::<Tilemap>(entity).unwrap();
+            let tiles = tilemap.take_unvalidated_tiles();
+            let len = tiles.len();
+            tilemap.return_unvalidated_tiles(tiles);
+            len
+        };
+
+        app.update();
+        assert_eq!(pending(app, entities[0]), 0);
+        assert_eq!(pending(app, entities[1]), 1);
+
+        for _ in 1..MAX_VALIDATION_ATTEMPTS {
+            app.update();
+        }
+        assert_eq!(pending(app, entities[1]), 0);
+        let events = app
+            .world
+            .get_resource::<Events<TilemapSpriteIndexEvent>>()
+            .unwrap();
+        assert_eq!(events.get_reader().iter(events).count(), 0);
+    }
+
+    #[test]
+    fn spawned_chunk_colliders_within_budget() {
+        let mut app = AppBuilder::default();
+        let app = &mut app
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin {})
+            .add_plugin(AssetPlugin)
+            .add_event::<TilemapCollidersEvent>()
+            .add_stage("update", SystemStage::parallel())
+            .add_stage_after("update", "colliders", SystemStage::parallel())
+            .add_system_to_stage("update", tilemap_events.system())
+            .add_system_to_stage("colliders", tilemap_colliders.system())
+            .add_asset::<Mesh>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<ColorMaterial>()
+            .app;
+
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .solid_layer(0)
+            .collider_budget(10)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap
+            .fill_rect((-2, -2), (1, 1), 0, 0, RawTile::default())
+            .unwrap();
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        let tilemap_entity = app
+            .world
+            .spawn()
+            .insert(tilemap)
+            .insert(Visible::default())
+            .insert(GlobalTransform::default())
+            .id();
+
+        let sent = |events: &Events<TilemapCollidersEvent>| {
+            events
+                .get_reader()
+                .iter(events)
+                .map(|event| {
+                    assert_eq!(event.entity, tilemap_entity);
+                    (event.colliders.colliders.len(), event.colliders.complete)
+                })
+                .collect::<Vec<(usize, bool)>>()
+        };
+
+        // The 16 colliders of the chunk are spread over two frames.
+        app.update();
+        assert_eq!(sent(app.world.get_resource().unwrap()), vec![(10, false)]);
+        app.update();
+        assert_eq!(
+            sent(app.world.get_resource().unwrap()),
+            vec![(10, false), (6, true)]
+        );
+        app.update();
+        app.update();
+        assert!(sent(app.world.get_resource().unwrap()).is_empty());
+    }
 }
@@ -1,6 +1,6 @@
 //! Tile traits to implement for a custom tile.
 
-use crate::lib::*;
+use crate::{chunk::RawTile, lib::*};
 
 /// A tile with an index value and color.
 
@@ -12,8 +12,25 @@ pub struct Tile<P: Into<Point3>> {
     pub sprite_order: usize,
     /// The sprites index in the texture atlas.
     pub sprite_index: usize,
+    /// The id of the texture atlas the sprite is in, as returned by
+    /// [`Tilemap::add_texture_atlas`]. 0 is the tilemap's main texture atlas.
+    ///
+    /// [`Tilemap::add_texture_atlas`]: crate::tilemap::Tilemap::add_texture_atlas
+    pub atlas: usize,
     /// The desired tint and alpha of the tile. White means no change.
     pub tint: Color,
+    /// If the sprite is flipped horizontally.
+    pub flip_x: bool,
+    /// If the sprite is flipped vertically.
+    pub flip_y: bool,
+    /// The number of 90 degree clockwise turns of the sprite, applied after
+    /// flipping. Only the lowest 2 bits are used.
+    pub rotation: u8,
+    /// The scale of the sprite, anchored at the bottom center of the tile. A
+    /// scale larger than 1 lets a sprite overhang its tile.
+    pub scale: Vec2,
+    /// The offset of the sprite in pixels.
+    pub offset: Vec2,
 }
 
 impl<P: Into<Point3> + Default> Default for Tile<P> {
@@ -22,7 +39,75 @@ impl<P: Into<Point3> + Default> Default for Tile<P> {
             point: P::default(),
             sprite_order: 0,
             sprite_index: 0,
+            atlas: 0,
             tint: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+            rotation: 0,
+            scale: Vec2::ONE,
+            offset: Vec2::ZERO,
         }
     }
 }
+
+impl<P: Into<Point3>> Tile<P> {
+    /// Constructs a tile at a point and sprite order from a raw tile.
+    pub(crate) fn from_raw_tile(point: P, sprite_order: usize, tile: &RawTile) -> Tile<P> {
+        Tile {
+            point,
+            sprite_order,
+            sprite_index: tile.index,
+            atlas: tile.atlas,
+            tint: tile.color,
+            flip_x: tile.flip_x,
+            flip_y: tile.flip_y,
+            rotation: tile.rotation,
+            scale: tile.scale,
+            offset: tile.offset,
+        }
+    }
+
+    /// Converts the point of the tile, keeping the rest of it.
+    pub(crate) fn map_point<Q, F>(self, f: F) -> Tile<Q>
+    where
+        Q: Into<Point3>,
+        F: FnOnce(P) -> Q,
+    {
+        Tile {
+            point: f(self.point),
+            sprite_order: self.sprite_order,
+            sprite_index: self.sprite_index,
+            atlas: self.atlas,
+            tint: self.tint,
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+            rotation: self.rotation,
+            scale: self.scale,
+            offset: self.offset,
+        }
+    }
+
+    /// Returns the raw tile of the tile.
+    pub(crate) fn to_raw_tile(&self) -> RawTile {
+        RawTile {
+            index: self.sprite_index,
+            atlas: self.atlas,
+            color: self.tint,
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+            rotation: self.rotation,
+            scale: self.scale,
+            offset: self.offset,
+        }
+    }
+}
+
+/// An axis to mirror tiles across.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FlipAxis {
+    /// Mirrors horizontally, swapping left and right.
+    X,
+    /// Mirrors vertically, swapping top and bottom.
+    Y,
+}
@@ -93,12 +93,51 @@
 //! }
 //! ```
 
+#[cfg(feature = "failure_injection")]
+use crate::failure::{FailureInjector, InjectedFailure};
+#[cfg(feature = "text")]
+use crate::label::TileLabel;
 use crate::{
-    chunk::{mesh::ChunkMesh, Chunk, LayerKind, RawTile},
-    event::TilemapChunkEvent,
+    brush::TileBrush,
+    chunk::{
+        depth_bounds, fit_depth, mesh::ChunkMesh, render, Chunk, CompactionReport, LayerKind,
+        MeshSettings, RawTile,
+    },
+    collision::{
+        self, Aabb, ChunkColliders, Circle, Collider, CollisionMove, RaycastHit, TileCollider,
+        TileShape,
+    },
+    event::{TilemapChunkEvent, UnloadedChunk},
+    fog::{Fog, FogOfWar, FogState},
+    generator::ChunkGenerator,
+    heatmap::Heatmap,
+    hex,
+    history::{step_tiles, TileChange, TilemapHistory},
     lib::*,
+    light::{self, Light, LightId, LightMap, Lighting},
+    outline,
+    pathfinding::{self, FlowField, PathOptions},
+    picking::ClickMask,
+    preload::PreloadPath,
     prelude::GridTopology,
-    tile::Tile,
+    property::{TileProperties, TileProperty},
+    raster::Canvas,
+    record::{RecordedEvent, TilemapRecording},
+    shade::{self, AmbientOcclusion},
+    source::TileSource,
+    spawn_policy::ChunkSpawnPolicy,
+    square,
+    stitch::{MapEdge, Stitch},
+    stream::{self, StreamProgress, StreamTile, TilemapStream},
+    sync::{self, ChunkRequest, ChunkResponse},
+    tile::{FlipAxis, Tile},
+    tilemap_ops::{self, CircleFill},
+    variant::TileVariants,
+};
+#[cfg(feature = "tmx")]
+use crate::{
+    tileset::TileRegistry,
+    tmx::{self, TmxLayer},
 };
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -118,6 +157,59 @@ pub enum ErrorKind {
     MissingChunk,
     /// The chunk already exists.
     ChunkAlreadyExists(Point2),
+    /// An I/O error occurred while streaming.
+    StreamIo(IoErrorKind),
+    /// The stream is not in the tilemap stream format.
+    InvalidStream,
+    /// The stream format version is not supported.
+    UnsupportedStreamVersion(u16),
+    /// The chunk dimensions of the stream differ from the tilemap.
+    StreamChunkDimensions(Dimension3),
+    /// The coordinates of the stream or recording have a width in bytes which
+    /// differs from the tilemap, as it was written with or without the
+    /// `i64_coordinates` feature.
+    StreamCoordinateWidth(u8),
+    /// The tile at the point has no tile to be mirrored onto in the topology.
+    UnmirrorableTile(Point2),
+    /// The data is not in the tilemap recording format.
+    InvalidRecording,
+    /// The recording format version is not supported.
+    UnsupportedRecordingVersion(u16),
+    /// The tilemap has no fog of war.
+    MissingFogOfWar,
+    /// The index or z depth of a tile is out of bounds of a chunk.
+    TileIndexOutOfBounds(usize, usize),
+    /// The tilemap has no dimensions.
+    MissingDimensions,
+    /// The tilemap has no lighting.
+    MissingLighting,
+    /// The data is not a chunk sync request or response.
+    InvalidChunkSync,
+    /// The tile at the point is in a locked region or chunk.
+    RegionLocked(Point2),
+    /// The image is not an 8 bit RGBA or BGRA image.
+    UnsupportedImage,
+    /// The topology of the tilemap can not be exported, or a brush of the
+    /// topology can not be rotated.
+    UnsupportedTopology(GridTopology),
+    /// No layer has the name.
+    LayerNameDoesNotExist(String),
+    /// Another layer already has the name.
+    LayerNameExists(String),
+    /// The chunk is spawned.
+    ChunkSpawned(Point2),
+    /// The chunk dimensions are empty or too shallow for the tiles.
+    InvalidChunkDimensions(Dimension3),
+    /// The tilemap has no debug heatmap.
+    MissingHeatmap,
+    /// The depth range is empty or not finite.
+    InvalidDepthRange,
+    /// The data is not a tilemap scene.
+    InvalidScene,
+    /// A stream is still being loaded into the tilemap.
+    StreamInProgress,
+    /// The rect is not within the bounds of the texture.
+    RectOutOfBounds,
 }
 
 impl Display for ErrorKind {
@@ -141,9 +233,86 @@ impl Display for ErrorKind {
             MissingChunk => write!(f, "the chunk does not exist, try `add_chunk` first"),
             ChunkAlreadyExists(p) => write!(
                 f,
-                "the chunk {} already exists, if this was intentional run `remove_chunk` first",
+                "the chunk {} already exists, if this was intentional run `delete_chunk` first",
+                p
+            ),
+            StreamIo(kind) => write!(f, "an I/O error occurred while streaming: {:?}", kind),
+            InvalidStream => write!(f, "the stream is not a tilemap stream"),
+            UnsupportedStreamVersion(v) => {
+                write!(f, "the stream format version {} is not supported", v)
+            }
+            StreamChunkDimensions(d) => write!(
+                f,
+                "the stream has chunk dimensions of {} which differ from the tilemap",
+                d
+            ),
+            StreamCoordinateWidth(w) => write!(
+                f,
+                "the stream has {} byte coordinates which differ from the tilemap, check the `i64_coordinates` feature",
+                w
+            ),
+            UnmirrorableTile(p) => write!(
+                f,
+                "the tile at {} can not be mirrored onto another tile in the topology",
+                p
+            ),
+            InvalidRecording => write!(f, "the data is not a tilemap recording"),
+            UnsupportedRecordingVersion(v) => {
+                write!(f, "the recording format version {} is not supported", v)
+            }
+            MissingFogOfWar => write!(
+                f,
+                "the tilemap has no fog of war, must use `TilemapBuilder::fog_of_war`"
+            ),
+            TileIndexOutOfBounds(i, z) => write!(
+                f,
+                "the tile index {} at z depth {} is out of bounds of a chunk",
+                i, z
+            ),
+            MissingDimensions => write!(
+                f,
+                "the tilemap has no dimensions, must use `TilemapBuilder::dimensions`"
+            ),
+            MissingLighting => write!(
+                f,
+                "the tilemap has no lighting, must use `TilemapBuilder::lighting`"
+            ),
+            InvalidChunkSync => write!(f, "the data is not a chunk sync request or response"),
+            RegionLocked(p) => write!(
+                f,
+                "the tile at {} is locked, try `unlock_region` or `unlock_chunk` first",
                 p
             ),
+            UnsupportedImage => write!(f, "the image is not an 8 bit RGBA or BGRA image"),
+            UnsupportedTopology(t) => {
+                write!(f, "the {:?} topology is not supported", t)
+            }
+            LayerNameDoesNotExist(name) => write!(
+                f,
+                "no layer is named {:?}, try `set_layer_name` first",
+                name
+            ),
+            LayerNameExists(name) => write!(f, "another layer is already named {:?}", name),
+            ChunkSpawned(p) => write!(f, "the chunk {} is spawned, try `unload_chunk` first", p),
+            InvalidChunkDimensions(d) => write!(
+                f,
+                "the chunk dimensions of {} are empty or too shallow for the tiles",
+                d
+            ),
+            MissingHeatmap => write!(
+                f,
+                "the tilemap has no debug heatmap, must use `TilemapBuilder::debug_heatmap`"
+            ),
+            InvalidDepthRange => write!(
+                f,
+                "the depth range must be finite with its minimum below its maximum"
+            ),
+            InvalidScene => write!(f, "the data is not a tilemap scene"),
+            StreamInProgress => write!(
+                f,
+                "a stream is still being loaded, try `poll_streaming` until it is done first"
+            ),
+            RectOutOfBounds => write!(f, "the rect is not within the bounds of the texture"),
         }
     }
 }
@@ -178,6 +347,12 @@ impl From<DimensionError> for TilemapError {
     }
 }
 
+impl From<IoError> for TilemapError {
+    fn from(err: IoError) -> TilemapError {
+        TilemapError(Box::new(ErrorKind::StreamIo(err.kind())))
+    }
+}
+
 /// A map result.
 pub type TilemapResult<T> = Result<T, TilemapError>;
 
@@ -188,6 +363,9 @@ bitflags! {
         const AUTO_CONFIGURE = 0b0000_0000_0000_0001;
         const AUTO_CHUNK = 0b0000_0000_0000_0010;
         const AUTO_SPAWN = 0b0000_0000_0000_0100;
+        const VALIDATE_SPRITE_INDICES = 0b0000_0000_0000_1000;
+        const MERGE_COLLIDERS = 0b0000_0000_0001_0000;
+        const PERSIST_UNLOADED_CHUNKS = 0b0000_0000_0010_0000;
     }
 }
 
@@ -199,6 +377,18 @@ const DEFAULT_CHUNK_DIMENSIONS: Dimension3 = Dimension3::new(32, 32, 1);
 const DEFAULT_TILE_SCALE: (f32, f32, f32) = (1.0, 1.0, 1.0);
 /// The default z layers.
 const DEFAULT_Z_LAYERS: usize = 5;
+/// The collider of solid tiles without a collider of their own.
+static DEFAULT_TILE_COLLIDER: TileCollider = TileCollider {
+    shape: TileShape::Full,
+    sensor: false,
+};
+
+/// The number of sprite layers above which a tilemap warns when it is built,
+/// as every chunk holds a slot for each of them at every z depth.
+const Z_LAYERS_WARNING: usize = 64;
+
+/// The salt of the hashes that variants without a seed are picked with.
+const VARIANT_SALT: u64 = 0x5851_f42d_4c95_7f2d;
 
 impl Default for AutoFlags {
     fn default() -> Self {
@@ -222,6 +412,123 @@ impl Default for TilemapLayer {
     }
 }
 
+/// The outcome of inserting tiles with [`Tilemap::insert_tiles_checked`].
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TileInsertReport {
+    /// The number of tiles which were inserted.
+    pub inserted: usize,
+    /// The tiles which were not inserted along with the reason why.
+    pub rejected: Vec<(Tile<Point3>, TilemapError)>,
+}
+
+/// The range of camera zoom, as the scale of an orthographic projection, that
+/// a sprite layer renders at.
+///
+/// A larger scale is zoomed further out. Both ends are inclusive.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ZoomRange {
+    /// The smallest scale the layer renders at.
+    pub min: f32,
+    /// The largest scale the layer renders at.
+    pub max: f32,
+}
+
+impl Default for ZoomRange {
+    fn default() -> ZoomRange {
+        ZoomRange {
+            min: 0.0,
+            max: f32::INFINITY,
+        }
+    }
+}
+
+impl ZoomRange {
+    /// Returns `true` if the layer renders at the scale.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tilemap::ZoomRange;
+    ///
+    /// let range = ZoomRange { max: 2.0, ..Default::default() };
+    ///
+    /// assert!(range.contains(1.0));
+    /// assert!(!range.contains(4.0));
+    /// ```
+    pub fn contains(&self, scale: f32) -> bool {
+        scale >= self.min && scale <= self.max
+    }
+}
+
+/// A wave which moves the tiles of a sprite layer up and down over time, for
+/// layers of water such as lakes and shorelines.
+///
+/// The vertices of the tiles are moved in the chunk shaders, so the meshes are
+/// not updated while the layer moves. Neighbouring tiles share their corners
+/// and move together, even across chunks.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Wave {
+    /// The largest distance in pixels the tiles move up or down.
+    pub amplitude: f32,
+    /// The number of waves per second.
+    pub frequency: f32,
+    /// The distance in pixels between the crests of the wave along the X
+    /// axis.
+    pub wavelength: f32,
+}
+
+impl Default for Wave {
+    fn default() -> Wave {
+        Wave {
+            amplitude: 2.0,
+            frequency: 0.5,
+            wavelength: 128.0,
+        }
+    }
+}
+
+impl Wave {
+    /// Returns the wave as a vertex attribute for the chunk shaders.
+    pub(crate) fn attribute(&self) -> [f32; 3] {
+        let wave_number = if self.wavelength > 0.0 {
+            1.0 / self.wavelength
+        } else {
+            0.0
+        };
+        [self.amplitude, self.frequency, wave_number]
+    }
+}
+
+/// The color grading of a sprite layer by a lookup table of the color grading
+/// texture of the tilemap.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorGrading {
+    /// The index of the lookup table in the color grading texture, counted
+    /// from the top.
+    pub lut: usize,
+    /// How much of the graded color is blended in, from 0.0 for none to 1.0
+    /// for the graded color only.
+    pub strength: f32,
+}
+
+impl Default for ColorGrading {
+    fn default() -> ColorGrading {
+        ColorGrading {
+            lut: 0,
+            strength: 1.0,
+        }
+    }
+}
+
+impl ColorGrading {
+    /// Returns the color grading as a vertex attribute for the chunk shaders.
+    pub(crate) fn attribute(&self) -> [f32; 2] {
+        [self.lut as f32, self.strength.clamp(0.0, 1.0)]
+    }
+}
+
 /// A Tilemap which maintains chunks and its tiles within.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
@@ -242,25 +549,232 @@ pub struct Tilemap {
     /// The layers that are currently set in the tilemap in order from lowest
     /// to highest.
     layers: Vec<Option<TilemapLayer>>,
+    /// The camera zoom ranges of the sprite layers that only render at some
+    /// zoom levels.
+    layer_zoom_ranges: HashMap<usize, ZoomRange>,
+    /// The waves of the sprite layers which move over time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_waves: HashMap<usize, Wave>,
+    /// The color gradings of the sprite layers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_gradings: HashMap<usize, ColorGrading>,
+    /// The sprite layers whose opaque tiles hide the tiles below them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    occluding_layers: HashSet<usize>,
+    /// The sprite layers which never change and are baked into a texture per
+    /// chunk when it spawns.
+    #[cfg_attr(feature = "serde", serde(default))]
+    static_layers: HashSet<usize>,
+    /// The sprite layers whose tiles are depth sorted by their row.
+    #[cfg_attr(feature = "serde", serde(default))]
+    y_sorted_layers: HashSet<usize>,
+    /// The sprite layers whose tiles block movement.
+    #[cfg_attr(feature = "serde", serde(default))]
+    solid_layers: HashSet<usize>,
+    /// The colliders of the tiles of solid layers, keyed by sprite index.
+    #[cfg_attr(feature = "serde", serde(default))]
+    tile_colliders: HashMap<usize, TileCollider>,
+    /// The maximum amount of colliders to build per frame.
+    #[cfg_attr(feature = "serde", serde(default))]
+    collider_budget: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The spawned chunks whose colliders are not all built yet.
+    collider_queue: Vec<ColliderBuild>,
+    /// The keys deciding the render order of the sprite layers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_sort_keys: HashMap<usize, i32>,
+    /// The priorities deciding which sprite layers of a chunk spawn first.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_spawn_priorities: HashMap<usize, i32>,
+    /// The names of the sprite layers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_names: HashMap<usize, String>,
+    /// The depths the sprite layers render at which were set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    layer_depths: HashMap<usize, f32>,
+    /// The range of z which everything drawn for the chunks is fitted into.
+    #[cfg_attr(feature = "serde", serde(default))]
+    depth_range: Option<(f32, f32)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The sprite layers which are hidden at the current camera zoom.
+    hidden_layers: HashSet<usize>,
     /// Auto flags used for different automated features.
     auto_flags: AutoFlags,
     /// Dimensions of chunks to spawn from camera transform.
     auto_spawn: Option<Dimension2>,
+    /// The extra chunks past the auto spawn dimensions that spawned chunks
+    /// are kept within before they are despawned.
+    auto_spawn_margin: Dimension2,
+    /// The maximum amount of chunks to auto spawn and despawn per frame.
+    auto_spawn_budget: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The window whose cameras drive the tilemap, or `None` for every window.
+    window: Option<WindowId>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunks waiting to be auto spawned, nearest to the camera last.
+    auto_spawn_queue: Vec<Point2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunks waiting to be auto despawned.
+    auto_despawn_queue: Vec<Point2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The spawned chunks which hold back the sprite layers below a spawn
+    /// priority, by the lowest spawn priority they render.
+    revealing_chunks: HashMap<Point2, i32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The policy which decides which chunks may be auto spawned and
+    /// despawned.
+    spawn_policy: Option<Box<dyn ChunkSpawnPolicy>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The generator of the tiles of new chunks.
+    generator: Option<Arc<dyn ChunkGenerator>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunks waiting to be generated.
+    generate_queue: Vec<Point2>,
+    /// The distance in chunks from the camera past which chunks are rendered
+    /// at a low resolution.
+    lod_distance: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// If chunks outside of the view of every camera are hidden.
+    culling: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The spawned chunks which are hidden by culling.
+    culled_chunks: HashSet<Point2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The average colors of sprites by texture atlas id and sprite index.
+    lod_colors: HashMap<(usize, usize), Color>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// The dimensions in chunks of the regions which group chunk entities.
+    chunk_regions: Option<Dimension2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The entities of the regions with spawned chunks.
+    region_entities: HashMap<Point2, Entity>,
     /// Custom flags.
     custom_flags: Vec<u32>,
+    /// Click masks used for picking, keyed by sprite index.
+    click_masks: HashMap<usize, ClickMask>,
+    /// The sprite indices that logical tile ids are rendered with.
+    theme: HashMap<u32, usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// The weighted variants that fills pick from, keyed by sprite index.
+    tile_variants: HashMap<usize, TileVariants>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The number of variants picked without a seed, which varies the picks.
+    variant_rolls: u64,
+    /// Notes for level designers at points, which are never rendered.
+    annotations: HashMap<Point2, String>,
     #[cfg_attr(feature = "serde", serde(skip))]
     /// The handle of the texture atlas.
     texture_atlas: Handle<TextureAtlas>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The handles of the additional texture atlases, by id minus one.
+    texture_atlases: Vec<Handle<TextureAtlas>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The handle of the custom render pipeline of the chunks, if any.
+    render_pipeline: Option<Handle<PipelineDescriptor>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The handle of the secondary texture of the main texture atlas, if any.
+    secondary_texture: Option<Handle<Texture>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The handle of the color grading lookup tables, if any.
+    color_grading: Option<Handle<Texture>>,
     /// A map of all the chunks at points.
     chunks: HashMap<Point2, Chunk>,
     #[cfg_attr(feature = "serde", serde(skip))]
-    /// A map of all currently spawned entities.
-    entities: HashMap<usize, Vec<Entity>>,
+    /// The chunk events which were not taken yet.
+    chunk_events: Vec<TilemapChunkEvent>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunk events which the tilemap systems handled in their last run.
+    handled_chunk_events: Vec<TilemapChunkEvent>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The despawned and removed chunks which were not handed out yet.
+    unloaded_chunks: Vec<UnloadedChunk>,
     #[cfg_attr(feature = "serde", serde(skip))]
-    /// The events of the tilemap.
-    chunk_events: Events<TilemapChunkEvent>,
+    /// The deleted chunks whose entities were not despawned yet.
+    deleted_chunks: Vec<Chunk>,
     /// A set of all spawned chunks.
-    spawned: HashSet<(i32, i32)>,
+    spawned: HashSet<(Coord, Coord)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// A stream of chunks which is currently being loaded.
+    stream: Option<TilemapStream>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// Inserted tiles which are waiting for their sprite index to be validated.
+    unvalidated_tiles: Vec<UnvalidatedTile>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The recording of edits, if recording.
+    recording: Option<TilemapRecording>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The undo and redo history of tile edits, if enabled.
+    history: Option<TilemapHistory>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// If the systems of the tilemap are paused.
+    paused: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// If the tilemap was resumed since auto spawning last ran.
+    resumed: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The tilemaps stitched onto the edges of the tilemap.
+    stitches: HashMap<MapEdge, Stitch>,
+    #[cfg(feature = "failure_injection")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The failures which are injected into the systems of the tilemap.
+    failures: FailureInjector,
+    #[cfg(feature = "text")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The text labels on tiles.
+    tile_labels: HashMap<Point2, TileLabel>,
+    #[cfg(feature = "text")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The entities of the labels which were removed while spawned.
+    removed_label_entities: Vec<Entity>,
+    /// The fog of war, if enabled.
+    fog: Option<Fog>,
+    /// The lighting with its lights, if enabled.
+    lights: Option<LightMap>,
+    /// The ambient occlusion, if enabled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    ambient_occlusion: Option<AmbientOcclusion>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// The debug heatmap, if enabled.
+    heatmap: Option<Heatmap>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The chunks waiting to have their meshes built ahead of spawning.
+    prewarm_queue: Vec<Point2>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    /// The camera path whose chunks are spawned ahead of the camera, if any.
+    preload_path: Option<PreloadPath>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// The regions of tiles which may not be modified, as their lowest and
+    /// highest corners.
+    locked_regions: Vec<(Point2, Point2)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// The chunks whose tiles may not be modified.
+    locked_chunks: HashSet<Point2>,
+}
+
+/// A tile which was inserted and has not had its sprite index checked against
+/// its texture atlas yet.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct UnvalidatedTile {
+    /// The global point of the tile.
+    pub point: Point3,
+    /// The sprite order of the tile.
+    pub sprite_order: usize,
+    /// The id of the texture atlas of the tile.
+    pub atlas: usize,
+    /// The sprite index of the tile.
+    pub sprite_index: usize,
+    /// The number of frames the tile waited for its texture atlas to load.
+    pub attempts: u32,
+}
+
+/// A spawned chunk whose colliders are built over several frames.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct ColliderBuild {
+    /// The point of the chunk.
+    pub point: Point2,
+    /// The colliders which were not handed out yet, or `None` if they are
+    /// not made yet.
+    pub colliders: Option<Vec<Collider>>,
 }
 
 /// Tilemap factory, which can be used to construct and configure new tilemaps.
@@ -272,22 +786,28 @@ pub struct Tilemap {
 ///
 /// - [`topology`]: sets the topology of the tilemap.
 /// - [`dimensions`]: specifies the dimensions of the tilemap. If this
-/// is not set, then the tilemap will have no dimensions.
+///   is not set, then the tilemap will have no dimensions.
 /// - [`chunk_dimensions`]: specifies the chunk's dimensions in tiles.
-/// Default is 32x, 32y.
+///   Default is 32x, 32y.
 /// - [`texture_dimensions`]: specifies the tile's dimensions in pixels.
-/// Default is 32px, 32px.
+///   Default is 32px, 32px.
 /// - [`layer_offset`]: Sets the layer offset as X, Y.
 /// - [`z_layers`]: specifies the maximum number of layers that sprites
-/// can exist on. Default is 20.
+///   can exist on. Default is 20.
 /// - [`texture_atlas`]: specifies the texture atlas handle
-/// to use for the tilemap.
+///   to use for the tilemap.
 /// - [`tile_scale`]: sets the tile scale in pixels.
 /// - [`add_layer`]: adds a layer to the tilemap.
 /// - [`auto_chunk`]: set if you want the tilemap to automatically spawn new
-/// chunks.
+///   chunks.
 /// - [`auto_spawn`]: set if you want the tilemap to automatically spawn and
-/// despawn chunks.
+///   despawn chunks.
+/// - [`auto_spawn_margin`]: sets how far past the auto spawn dimensions chunks
+///   are kept before they are despawned.
+/// - [`auto_spawn_budget`]: sets the maximum amount of chunks to auto spawn and
+///   despawn per frame.
+/// - [`window`]: sets the window whose cameras drive auto spawning, level of
+///   detail and layer zoom.
 ///
 /// The [`finish`] method will take ownership and consume the builder returning
 /// a [`TilemapResult`] with either an [`TilemapError`] or the [tilemap].
@@ -330,6 +850,9 @@ pub struct Tilemap {
 /// [`add_layer`]: TilemapBuilder::add_layer
 /// [`auto_chunk`]: TilemapBuilder::auto_chunk
 /// [`auto_spawn`]: TilemapBuilder::auto_spawn
+/// [`auto_spawn_margin`]: TilemapBuilder::auto_spawn_margin
+/// [`auto_spawn_budget`]: TilemapBuilder::auto_spawn_budget
+/// [`window`]: TilemapBuilder::window
 /// [tilemap]: Tilemap
 /// [`TilemapError`]: TilemapError
 /// [`TilemapResult`]: TilemapResult
@@ -354,12 +877,65 @@ pub struct TilemapBuilder {
     layers: Option<HashMap<usize, TilemapLayer>>,
     /// If the tilemap currently has a sprite sheet handle on it or not.
     texture_atlas: Option<Handle<TextureAtlas>>,
+    /// The custom render pipeline of the chunks.
+    render_pipeline: Option<Handle<PipelineDescriptor>>,
+    /// The secondary texture of the main texture atlas.
+    secondary_texture: Option<Handle<Texture>>,
+    /// The color grading lookup tables.
+    color_grading: Option<Handle<Texture>>,
     /// Sets how many Z layers to render.
     render_depth: usize,
     /// True if this tilemap will automatically configure.
     auto_flags: AutoFlags,
     /// The radius of chunks to spawn from a camera's transform.
     auto_spawn: Option<Dimension2>,
+    /// The extra chunks past the auto spawn radius before despawning.
+    auto_spawn_margin: Dimension2,
+    /// The maximum amount of chunks to auto spawn and despawn per frame.
+    auto_spawn_budget: Option<u32>,
+    /// The window whose cameras drive the tilemap.
+    window: Option<WindowId>,
+    /// The distance in chunks past which chunks are rendered at a low
+    /// resolution.
+    lod_distance: Option<u32>,
+    /// If chunks outside of the view of every camera are hidden.
+    culling: bool,
+    /// The dimensions in chunks of the regions which group chunk entities.
+    chunk_regions: Option<Dimension2>,
+    /// The camera zoom ranges of sprite layers.
+    layer_zoom_ranges: HashMap<usize, ZoomRange>,
+    /// The waves of sprite layers.
+    layer_waves: HashMap<usize, Wave>,
+    /// The color gradings of sprite layers.
+    layer_gradings: HashMap<usize, ColorGrading>,
+    /// The sprite layers whose opaque tiles hide the tiles below them.
+    occluding_layers: HashSet<usize>,
+    /// The sprite layers which are baked into a texture per chunk.
+    static_layers: HashSet<usize>,
+    /// The sprite layers whose tiles are depth sorted by their row.
+    y_sorted_layers: HashSet<usize>,
+    /// The sprite layers whose tiles block movement.
+    solid_layers: HashSet<usize>,
+    /// The maximum amount of colliders to build per frame.
+    collider_budget: Option<u32>,
+    /// The keys deciding the render order of the sprite layers.
+    layer_sort_keys: HashMap<usize, i32>,
+    /// The priorities deciding which sprite layers of a chunk spawn first.
+    layer_spawn_priorities: HashMap<usize, i32>,
+    /// The names of the sprite layers.
+    layer_names: HashMap<usize, String>,
+    /// The depths the sprite layers render at which were set.
+    layer_depths: HashMap<usize, f32>,
+    /// The range of z which everything drawn for the chunks is fitted into.
+    depth_range: Option<(f32, f32)>,
+    /// The fog of war configuration.
+    fog_of_war: Option<FogOfWar>,
+    /// The lighting configuration.
+    lighting: Option<Lighting>,
+    /// The ambient occlusion configuration.
+    ambient_occlusion: Option<AmbientOcclusion>,
+    /// The debug heatmap configuration.
+    heatmap: Option<Heatmap>,
 }
 
 impl Default for TilemapBuilder {
@@ -384,9 +960,35 @@ impl Default for TilemapBuilder {
             z_layers: DEFAULT_Z_LAYERS,
             layers,
             texture_atlas: None,
+            render_pipeline: None,
+            secondary_texture: None,
+            color_grading: None,
             render_depth: 0,
             auto_flags: AutoFlags::NONE,
             auto_spawn: None,
+            auto_spawn_margin: Dimension2::new(0, 0),
+            auto_spawn_budget: None,
+            window: None,
+            lod_distance: None,
+            culling: false,
+            chunk_regions: None,
+            layer_zoom_ranges: HashMap::default(),
+            layer_waves: HashMap::default(),
+            layer_gradings: HashMap::default(),
+            occluding_layers: HashSet::default(),
+            static_layers: HashSet::default(),
+            y_sorted_layers: HashSet::default(),
+            solid_layers: HashSet::default(),
+            collider_budget: None,
+            layer_sort_keys: HashMap::default(),
+            layer_spawn_priorities: HashMap::default(),
+            layer_names: HashMap::default(),
+            layer_depths: HashMap::default(),
+            depth_range: None,
+            fog_of_war: None,
+            lighting: None,
+            ambient_occlusion: None,
+            heatmap: None,
         }
     }
 }
@@ -518,6 +1120,10 @@ impl TilemapBuilder {
     ///
     /// By default there are 20 if this is not set.
     ///
+    /// Every chunk holds a slot for each sprite layer at every z depth, so a
+    /// warning is logged when the tilemap is built with more than 64. Unused
+    /// slots can be freed with [`Tilemap::compact_layers`].
+    ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
@@ -577,6 +1183,68 @@ impl TilemapBuilder {
         self
     }
 
+    /// Sets a custom render pipeline for the chunks instead of the one of the
+    /// topology.
+    ///
+    /// See [`Tilemap::set_render_pipeline`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::pipeline::PipelineDescriptor;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let pipeline_handle = Handle::weak(HandleId::random::<PipelineDescriptor>());
+    ///
+    /// let builder = TilemapBuilder::new().render_pipeline(pipeline_handle);
+    /// ```
+    pub fn render_pipeline(mut self, handle: Handle<PipelineDescriptor>) -> TilemapBuilder {
+        self.render_pipeline = Some(handle);
+        self
+    }
+
+    /// Sets a secondary texture, such as a normal or emissive map, with the
+    /// same layout as the main texture atlas.
+    ///
+    /// See [`Tilemap::set_secondary_texture`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::texture::Texture;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let normal_map_handle = Handle::weak(HandleId::random::<Texture>());
+    ///
+    /// let builder = TilemapBuilder::new().secondary_texture(normal_map_handle);
+    /// ```
+    pub fn secondary_texture(mut self, handle: Handle<Texture>) -> TilemapBuilder {
+        self.secondary_texture = Some(handle);
+        self
+    }
+
+    /// Sets the texture of the color grading lookup tables.
+    ///
+    /// See [`Tilemap::set_color_grading`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::texture::Texture;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let lut_handle = Handle::weak(HandleId::random::<Texture>());
+    ///
+    /// let builder = TilemapBuilder::new().color_grading(lut_handle);
+    /// ```
+    pub fn color_grading(mut self, handle: Handle<Texture>) -> TilemapBuilder {
+        self.color_grading = Some(handle);
+        self
+    }
+
     /// Set auto_chunk if you want the tilemap to automatically spawn new chunks.
     ///
     /// This is useful if the tilemap map is meant to be endless or nearly
@@ -597,6 +1265,97 @@ impl TilemapBuilder {
         self
     }
 
+    /// Set validate_sprite_indices if you want the tilemap to check the sprite
+    /// index of every inserted tile against its texture atlas.
+    ///
+    /// Sprite indices past the number of textures in the atlas otherwise
+    /// render garbage without any warning. With this enabled, the tilemap
+    /// checks the tiles once the texture atlas is loaded and sends a
+    /// [`TilemapSpriteIndexEvent`] for each offending tile. This is mostly
+    /// useful while developing.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().validate_sprite_indices();
+    /// ```
+    ///
+    /// [`TilemapSpriteIndexEvent`]: crate::event::TilemapSpriteIndexEvent
+    pub fn validate_sprite_indices(mut self) -> Self {
+        self.auto_flags.toggle(AutoFlags::VALIDATE_SPRITE_INDICES);
+        self
+    }
+
+    /// Set merge_colliders if you want the colliders of a chunk to merge its
+    /// neighbouring solid tiles into as few cuboids as possible.
+    ///
+    /// Without it [`Tilemap::chunk_colliders`] makes a collider for every
+    /// solid tile, which physics engines struggle with on dense maps. Merged
+    /// colliders no longer tell which tile was hit.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().merge_colliders();
+    /// ```
+    pub fn merge_colliders(mut self) -> Self {
+        self.auto_flags.toggle(AutoFlags::MERGE_COLLIDERS);
+        self
+    }
+
+    /// Sets the maximum amount of colliders to build per frame for the
+    /// chunks which spawn.
+    ///
+    /// The colliders of every spawned chunk are then built a few at a time,
+    /// the chunks nearest to the camera first, and sent with a
+    /// [`TilemapCollidersEvent`] that tells when all the colliders of a chunk
+    /// are built. This keeps physics engines from hitching when a chunk with
+    /// many solid tiles spawns. See [`Tilemap::build_colliders`] for more
+    /// information.
+    ///
+    /// By default colliders are not built.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().solid_layer(0).collider_budget(64);
+    /// ```
+    ///
+    /// [`TilemapCollidersEvent`]: crate::event::TilemapCollidersEvent
+    pub fn collider_budget(mut self, colliders: u32) -> Self {
+        self.collider_budget = Some(colliders);
+        self
+    }
+
+    /// Set persist_unloaded_chunks if you want the tiles of chunks which are
+    /// despawned or removed to be handed out before they are dropped.
+    ///
+    /// The tiles are sent in a [`TilemapChunkUnloadEvent`], so changes made
+    /// to the chunks of a streamed world can be saved and restored with
+    /// [`Tilemap::restore_chunk`] when the chunk is loaded again.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().persist_unloaded_chunks();
+    /// ```
+    ///
+    /// [`TilemapChunkUnloadEvent`]: crate::event::TilemapChunkUnloadEvent
+    pub fn persist_unloaded_chunks(mut self) -> Self {
+        self.auto_flags.toggle(AutoFlags::PERSIST_UNLOADED_CHUNKS);
+        self
+    }
+
     /// Sets the tilemap to automatically spawn new chunks within given
     /// dimensions.
     ///
@@ -615,235 +1374,342 @@ impl TilemapBuilder {
         self
     }
 
-    /// Consumes the builder and returns a result.
+    /// Sets the extra chunks past the auto spawn dimensions that spawned
+    /// chunks are kept within before they are despawned.
     ///
-    /// If successful a [`TilemapResult`] is return with [tilemap] on
-    /// succes or a [`TilemapError`] if there is an issue.
+    /// Without a margin a camera moving back and forth over the edge of a
+    /// chunk spawns and despawns the same chunks over and over again.
     ///
-    /// # Errors
-    /// If a texture atlas is not set this is the only way that an error can
-    /// occur. If this happens, be sure to use [`texture_atlas`].
+    /// By default there is no margin.
     ///
     /// # Examples
     /// ```
-    /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::prelude::*;
     ///
-    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let builder = TilemapBuilder::new().auto_spawn(2, 3).auto_spawn_margin(1, 1);
+    /// ```
+    pub fn auto_spawn_margin(mut self, width: u32, height: u32) -> Self {
+        self.auto_spawn_margin = Dimension2::new(width, height);
+        self
+    }
+
+    /// Sets the maximum amount of chunks to auto spawn and despawn per frame.
     ///
-    /// let builder = TilemapBuilder::new().texture_dimensions(32, 32).texture_atlas(texture_atlas_handle);
+    /// The chunks past the budget are queued for the next frames, spawning
+    /// the chunks nearest to the camera first. This spreads the cost of a
+    /// camera moving over many chunks at once.
     ///
-    /// assert!(builder.finish().is_ok());
-    /// assert!(TilemapBuilder::new().finish().is_err());
+    /// By default there is no budget.
+    ///
+    /// # Examples
     /// ```
+    /// use bevy_tilemap::prelude::*;
     ///
-    /// [`texture_atlas`]: TilemapBuilder::texture_atlas
-    /// [tilemap]: Tilemap
-    /// [`TilemapError`]: TilemapError
-    /// [`TilemapResult`]: TilemapResult
-    pub fn finish(self) -> TilemapResult<Tilemap> {
-        let texture_atlas = if let Some(atlas) = self.texture_atlas {
-            atlas
-        } else {
-            return Err(ErrorKind::MissingTextureAtlas.into());
-        };
-        let texture_dimensions = if let Some(dimensions) = self.texture_dimensions {
-            dimensions
-        } else {
-            return Err(ErrorKind::MissingTextureDimensions.into());
-        };
-
-        let z_layers = if let Some(layers) = &self.layers {
-            if self.z_layers > layers.len() {
-                self.z_layers
-            } else {
-                layers.len()
-            }
-        } else {
-            self.z_layers
-        };
+    /// let builder = TilemapBuilder::new().auto_spawn(2, 3).auto_spawn_budget(4);
+    /// ```
+    pub fn auto_spawn_budget(mut self, chunks: u32) -> Self {
+        self.auto_spawn_budget = Some(chunks);
+        self
+    }
 
-        let layer_count = if let Some(layers) = &self.layers {
-            layers.iter().count()
-        } else {
-            0
-        };
-        let chunk_mesh =
-            ChunkMesh::new(self.chunk_dimensions, layer_count as u32, self.layer_offset);
+    /// Sets the window whose cameras drive the tilemap.
+    ///
+    /// Only the cameras rendering to the window are used to auto spawn
+    /// chunks, to pick the level of detail of chunks and to hide sprite
+    /// layers by zoom, and only resizing the window changes the auto spawn
+    /// dimensions. This lets an editor window and a game window each drive
+    /// their own tilemap.
+    ///
+    /// By default the cameras of every window are used.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_window::WindowId;
+    ///
+    /// let builder = TilemapBuilder::new().auto_spawn(2, 3).window(WindowId::primary());
+    /// ```
+    pub fn window(mut self, window: WindowId) -> Self {
+        self.window = Some(window);
+        self
+    }
 
-        let layers = {
-            let mut layers = vec![None; z_layers];
-            if let Some(map_layers) = self.layers {
-                for (index, layer) in map_layers {
-                    if let Some(l) = layers.get_mut(index) {
-                        *l = Some(layer)
-                    }
-                }
-            }
-            layers
-        };
+    /// Sets the distance in chunks from the camera past which spawned chunks
+    /// are rendered at a low resolution.
+    ///
+    /// Instead of a mesh with every tile, a far away chunk is rendered as a
+    /// single sprite with a baked texture that has one pixel per tile, colored
+    /// with the average color of the top most sprite. This keeps zoomed out
+    /// views of very large maps fast.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().lod_distance(4);
+    /// ```
+    pub fn lod_distance(mut self, distance: u32) -> Self {
+        self.lod_distance = Some(distance);
+        self
+    }
 
-        Ok(Tilemap {
-            topology: self.topology,
-            dimensions: self.dimensions,
-            chunk_dimensions: self.chunk_dimensions,
-            layer_offset: self.layer_offset,
-            chunk_mesh,
-            texture_dimensions,
-            layers,
-            auto_flags: self.auto_flags,
-            auto_spawn: self.auto_spawn,
-            custom_flags: Vec::new(),
-            texture_atlas,
-            chunks: Default::default(),
-            entities: Default::default(),
-            chunk_events: Default::default(),
-            spawned: Default::default(),
-        })
+    /// Hides spawned chunks which are outside of the view of every camera
+    /// driving the tilemap.
+    ///
+    /// This keeps large spawned areas cheap to render. Only orthographic
+    /// cameras are supported, chunks are never culled while another camera
+    /// drives the tilemap.
+    ///
+    /// By default this is not enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().culling();
+    /// ```
+    pub fn culling(mut self) -> Self {
+        self.culling = true;
+        self
     }
-}
 
-impl TypeUuid for Tilemap {
-    const TYPE_UUID: Uuid = Uuid::from_u128(109481186966523254410691740507722642628);
-}
+    /// Groups the entities of spawned chunks into regions of chunks.
+    ///
+    /// Each region with a spawned chunk has an entity which is a child of the
+    /// tilemap and the parent of the entities of its chunks. Transforming a
+    /// region entity moves all of its chunks at once, such as to sink an
+    /// island or shake a part of the map. See [`Tilemap::region_entity`].
+    ///
+    /// By default chunk entities are children of the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new().chunk_regions(4, 4);
+    /// ```
+    pub fn chunk_regions(mut self, width: u32, height: u32) -> Self {
+        self.chunk_regions = Some(Dimension2::new(width.max(1), height.max(1)));
+        self
+    }
 
-impl Default for Tilemap {
-    fn default() -> Self {
-        Tilemap {
-            topology: GridTopology::Square,
-            dimensions: None,
-            chunk_dimensions: DEFAULT_CHUNK_DIMENSIONS,
-            layer_offset: Vec2::default(),
-            chunk_mesh: ChunkMesh::default(),
-            texture_dimensions: DEFAULT_TEXTURE_DIMENSIONS,
-            layers: vec![
-                Some(TilemapLayer {
-                    kind: LayerKind::Sparse,
-                }),
-                None,
-                None,
-                None,
-                None,
-            ],
-            auto_flags: AutoFlags::NONE,
-            auto_spawn: None,
-            custom_flags: Vec::new(),
-            texture_atlas: Handle::default(),
-            chunks: Default::default(),
-            entities: Default::default(),
-            chunk_events: Default::default(),
-            spawned: Default::default(),
-        }
+    /// Sets the range of camera zoom that a sprite layer renders at.
+    ///
+    /// See [`Tilemap::set_layer_zoom_range`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{prelude::*, tilemap::ZoomRange};
+    ///
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .layer_zoom_range(1, ZoomRange { max: 2.0, ..Default::default() });
+    /// ```
+    pub fn layer_zoom_range(mut self, sprite_order: usize, range: ZoomRange) -> Self {
+        self.layer_zoom_ranges.insert(sprite_order, range);
+        self
     }
-}
 
-impl Tilemap {
-    /// Constructs a new Tilemap with the required texture atlas and default
-    /// configuration.
+    /// Sets a wave which moves the tiles of a sprite layer over time.
     ///
-    /// This differs from [`default`] in that it requires the texture atlas
-    /// handle.
+    /// See [`Tilemap::set_layer_wave`] for more information.
     ///
     /// # Examples
     /// ```
-    /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::Wave};
     ///
-    /// // In production use a strong handle from an actual source.
-    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let builder = TilemapBuilder::new().layer_wave(1, Wave { amplitude: 3.0, ..Default::default() });
+    /// ```
+    pub fn layer_wave(mut self, sprite_order: usize, wave: Wave) -> Self {
+        self.layer_waves.insert(sprite_order, wave);
+        self
+    }
+
+    /// Sets the color grading of a sprite layer.
     ///
-    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// See [`Tilemap::set_layer_grading`] for more information.
+    ///
+    /// # Examples
     /// ```
+    /// use bevy_tilemap::{prelude::*, tilemap::ColorGrading};
     ///
-    /// [`default`]: Tilemap::default
-    pub fn new(
-        texture_atlas: Handle<TextureAtlas>,
-        texture_width: u32,
-        texture_height: u32,
-    ) -> Tilemap {
-        Tilemap {
-            texture_atlas,
-            texture_dimensions: Dimension2::new(texture_width, texture_height),
-            ..Default::default()
-        }
+    /// let builder = TilemapBuilder::new().layer_grading(0, ColorGrading { lut: 1, strength: 0.8 });
+    /// ```
+    pub fn layer_grading(mut self, sprite_order: usize, grading: ColorGrading) -> Self {
+        self.layer_gradings.insert(sprite_order, grading);
+        self
     }
 
-    /// Configures the builder with the default settings.
+    /// Sets a sprite layer whose opaque tiles hide the tiles below them.
     ///
-    /// This is equivalent to [`default`] and [`builder`] method in the
-    /// [tilemap]. Start with this then you are able to method chain.
+    /// See [`Tilemap::set_layer_occluding`] for more information.
     ///
-    /// [`default`]: TilemapBuilder::default
-    /// [`builder`]: Tilemap::builder
-    /// [tilemap]: Tilemap
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Dense }, 1)
+    ///     .occluding_layer(1);
+    /// ```
+    pub fn occluding_layer(mut self, sprite_order: usize) -> Self {
+        self.occluding_layers.insert(sprite_order);
+        self
+    }
+
+    /// Sets a sprite layer which never changes, to be baked into a texture
+    /// per chunk.
+    ///
+    /// See [`Tilemap::set_layer_static`] for more information.
     ///
     /// # Examples
     /// ```
     /// use bevy_tilemap::prelude::*;
     ///
-    /// let builder = TilemapBuilder::new();
+    /// let builder = TilemapBuilder::new().static_layer(0);
+    /// ```
+    pub fn static_layer(mut self, sprite_order: usize) -> Self {
+        self.static_layers.insert(sprite_order);
+        self
+    }
+
+    /// Sets a sprite layer whose tiles are depth sorted by their row.
     ///
-    /// // Equivalent to...
+    /// See [`Tilemap::set_layer_y_sorted`] for more information.
     ///
-    /// let builder = TilemapBuilder::default();
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
     ///
-    /// // Or...
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .y_sorted_layer(1);
+    /// ```
+    pub fn y_sorted_layer(mut self, sprite_order: usize) -> Self {
+        self.y_sorted_layers.insert(sprite_order);
+        self
+    }
+
+    /// Sets a sprite layer whose tiles block movement.
     ///
-    /// let builder = Tilemap::builder();
+    /// See the [`collision`] module for more information.
+    ///
+    /// [`collision`]: crate::collision
+    ///
+    /// # Examples
     /// ```
-    pub fn builder() -> TilemapBuilder {
-        TilemapBuilder::default()
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .solid_layer(1);
+    /// ```
+    pub fn solid_layer(mut self, sprite_order: usize) -> Self {
+        self.solid_layers.insert(sprite_order);
+        self
     }
 
-    /// Sets the sprite sheet for use in the tilemap.
+    /// Sets the key deciding when a sprite layer renders.
     ///
-    /// This can be used if one wants to swap the sprite sheet for another.
+    /// See [`Tilemap::set_layer_sort_key`] for more information.
     ///
     /// # Examples
     /// ```
-    /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::prelude::*;
     ///
-    /// let mut tilemap = Tilemap::default();
+    /// // The units on layer 2 render below the roofs on layer 1.
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 2)
+    ///     .layer_sort_key(2, 0);
+    /// ```
+    pub fn layer_sort_key(mut self, sprite_order: usize, key: i32) -> Self {
+        self.layer_sort_keys.insert(sprite_order, key);
+        self
+    }
+
+    /// Sets the priority deciding when a sprite layer of a chunk spawns.
     ///
-    /// // In production use a strong handle from an actual source.
-    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// See [`Tilemap::set_layer_spawn_priority`] for more information.
     ///
-    /// tilemap.set_texture_atlas(texture_atlas_handle);
+    /// # Examples
     /// ```
-    pub fn set_texture_atlas(&mut self, handle: Handle<TextureAtlas>) {
-        self.texture_atlas = handle;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // The ground on layer 0 spawns a frame before the trees on layer 1.
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .auto_spawn(2, 2)
+    ///     .auto_spawn_budget(4)
+    ///     .layer_spawn_priority(0, 1);
+    /// ```
+    pub fn layer_spawn_priority(mut self, sprite_order: usize, priority: i32) -> Self {
+        self.layer_spawn_priorities.insert(sprite_order, priority);
+        self
     }
 
-    /// Returns a reference of the handle of the texture atlas.
+    /// Sets the name of a sprite layer, replacing the name of any other layer
+    /// with the same name.
     ///
-    /// The Handle is used to get the correct sprite sheet that is used for this
-    /// tilemap with the renderer.
+    /// See [`Tilemap::set_layer_name`] for more information.
     ///
     /// # Examples
     /// ```
-    /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::prelude::*;
     ///
-    /// // In production use a strong handle from an actual source.
-    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .layer_name(0, "ground")
+    ///     .layer_name(1, "objects");
+    /// ```
+    pub fn layer_name<S: ToString>(mut self, sprite_order: usize, name: S) -> Self {
+        let name = name.to_string();
+        self.layer_names.retain(|_, other| *other != name);
+        self.layer_names.insert(sprite_order, name);
+        self
+    }
+
+    /// Sets the depth a sprite layer renders at.
     ///
-    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    /// let texture_atlas: &Handle<TextureAtlas> = tilemap.texture_atlas();
+    /// See [`Tilemap::set_layer_depth`] for more information.
+    ///
+    /// # Examples
     /// ```
-    pub fn texture_atlas(&self) -> &Handle<TextureAtlas> {
-        &self.texture_atlas
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // Sprites between 1.0 and 3.0 above the chunks walk over the floor on
+    /// // layer 0 and under the tree tops on layer 1.
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .layer_depth(1, 3.0);
+    /// ```
+    pub fn layer_depth(mut self, sprite_order: usize, depth: f32) -> Self {
+        self.layer_depths.insert(sprite_order, depth);
+        self
     }
 
-    /// Constructs a new chunk and stores it at a coordinate position.
+    /// Reserves a range of z for the tilemap, which everything drawn for its
+    /// chunks is fitted into.
+    ///
+    /// By default the chunks are 1.0 above the tilemap and their layers are
+    /// stacked above them by their depths, so how far the tilemap reaches
+    /// depends on its layers. With a depth range the chunks are at the
+    /// minimum and the depths of the layers, their y-sorted tiles, the baked
+    /// layers and the low resolution stand-ins of the chunks are scaled into
+    /// the range in the same order, keeping clear of the maximum. Other
+    /// sprites and plugins, such as physics debug renderers, can then be drawn
+    /// outside of the range without fighting with the tiles.
+    ///
+    /// The range is relative to the transform of the tilemap. A sprite which
+    /// is drawn between two layers needs its z fitted into the range as well,
+    /// see [`Tilemap::depth_z`].
     ///
-    /// It requires that you give it a point. It then automatically sets
-    /// both a sized mesh and chunk for use based on the parameters set in the
-    /// parent tilemap.
+    /// # Errors
+    ///
+    /// [`TilemapBuilder::finish`] returns an error if the minimum is not
+    /// below the maximum or either is not finite.
     ///
     /// # Examples
     /// ```
@@ -854,116 +1720,8617 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(3, 3)
+    /// // Debug lines drawn at 10.0 stay above every tile.
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle.clone_weak())
     ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .layer_depth(1, 3.0)
+    ///     .depth_range(0.0, 5.0)
     ///     .finish()
     ///     .unwrap();
     ///
-    /// // Add some chunks.
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
-    /// assert!(tilemap.insert_chunk((-2, -2)).is_err());
+    /// assert_eq!(tilemap.depth_range(), Some((0.0, 5.0)));
+    /// assert!(tilemap.depth_z(3.0) < 5.0);
     ///
-    /// assert!(tilemap.contains_chunk((0, 0)));
-    /// assert!(tilemap.contains_chunk((1, 1)));
-    /// assert!(!tilemap.contains_chunk((-2, -2)));
+    /// assert!(TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .depth_range(5.0, 5.0)
+    ///     .finish()
+    ///     .is_err());
     /// ```
-    /// # Errors
+    pub fn depth_range(mut self, min: f32, max: f32) -> Self {
+        self.depth_range = Some((min, max));
+        self
+    }
+
+    /// Enables the fog of war, drawn on a dense layer at the sprite order of
+    /// the fog which replaces any layer added there.
     ///
-    /// If the point does not exist in the tilemap, an error is returned. This
-    /// can only be returned if you had set the dimensions on the tilemap.
+    /// See the [`fog`] module for more information.
     ///
-    /// Also will return an error if the chunk already exists. If this happens
-    /// and was intentional, it is best to remove the chunk first. This is
-    /// simply a fail safe without actually returning the chunk as it is meant
+    /// [`fog`]: crate::fog
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{fog::FogOfWar, prelude::*};
+    ///
+    /// let builder = TilemapBuilder::new().fog_of_war(FogOfWar { sprite_order: 1, ..Default::default() });
+    /// ```
+    pub fn fog_of_war(mut self, fog: FogOfWar) -> Self {
+        self.fog_of_war = Some(fog);
+        self.add_layer(
+            TilemapLayer {
+                kind: LayerKind::Dense,
+            },
+            fog.sprite_order,
+        )
+    }
+
+    /// Enables lighting, which tints the tiles of spawned chunks by the light
+    /// falling on them.
+    ///
+    /// See the [`light`] module for more information.
+    ///
+    /// [`light`]: crate::light
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{light::Lighting, prelude::*};
+    ///
+    /// let builder = TilemapBuilder::new().lighting(Lighting { occlusion: Some(1), ..Default::default() });
+    /// ```
+    pub fn lighting(mut self, lighting: Lighting) -> Self {
+        self.lighting = Some(lighting);
+        self
+    }
+
+    /// Enables ambient occlusion, which darkens the tiles of spawned chunks
+    /// next to the tiles of a wall layer.
+    ///
+    /// See the [`shade`] module for more information.
+    ///
+    /// [`shade`]: crate::shade
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{prelude::*, shade::AmbientOcclusion};
+    ///
+    /// let builder = TilemapBuilder::new()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .ambient_occlusion(AmbientOcclusion { sprite_order: 1, strength: 0.5 });
+    /// ```
+    pub fn ambient_occlusion(mut self, ambient_occlusion: AmbientOcclusion) -> Self {
+        self.ambient_occlusion = Some(ambient_occlusion);
+        self
+    }
+
+    /// Enables the debug heatmap, drawn on a dense layer at the sprite order
+    /// of the heatmap which replaces any layer added there.
+    ///
+    /// See the [`heatmap`] module for more information.
+    ///
+    /// [`heatmap`]: crate::heatmap
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{heatmap::Heatmap, prelude::*};
+    ///
+    /// let builder = TilemapBuilder::new().debug_heatmap(Heatmap { sprite_order: 2, ..Default::default() });
+    /// ```
+    pub fn debug_heatmap(mut self, heatmap: Heatmap) -> Self {
+        let sprite_order = heatmap.sprite_order;
+        self.heatmap = Some(heatmap);
+        self.add_layer(
+            TilemapLayer {
+                kind: LayerKind::Dense,
+            },
+            sprite_order,
+        )
+    }
+
+    /// Consumes the builder and returns a result.
+    ///
+    /// If successful a [`TilemapResult`] is return with [tilemap] on
+    /// succes or a [`TilemapError`] if there is an issue.
+    ///
+    /// # Errors
+    /// If a texture atlas is not set this is the only way that an error can
+    /// occur. If this happens, be sure to use [`texture_atlas`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let builder = TilemapBuilder::new().texture_dimensions(32, 32).texture_atlas(texture_atlas_handle);
+    ///
+    /// assert!(builder.finish().is_ok());
+    /// assert!(TilemapBuilder::new().finish().is_err());
+    /// ```
+    ///
+    /// [`texture_atlas`]: TilemapBuilder::texture_atlas
+    /// [tilemap]: Tilemap
+    /// [`TilemapError`]: TilemapError
+    /// [`TilemapResult`]: TilemapResult
+    pub fn finish(self) -> TilemapResult<Tilemap> {
+        let texture_atlas = if let Some(atlas) = self.texture_atlas {
+            atlas
+        } else {
+            return Err(ErrorKind::MissingTextureAtlas.into());
+        };
+        let texture_dimensions = if let Some(dimensions) = self.texture_dimensions {
+            dimensions
+        } else {
+            return Err(ErrorKind::MissingTextureDimensions.into());
+        };
+        if let Some((min, max)) = self.depth_range {
+            if !(min.is_finite() && max.is_finite() && min < max) {
+                return Err(ErrorKind::InvalidDepthRange.into());
+            }
+        }
+
+        let z_layers = if let Some(layers) = &self.layers {
+            if self.z_layers > layers.len() {
+                self.z_layers
+            } else {
+                layers.len()
+            }
+        } else {
+            self.z_layers
+        };
+        if z_layers > Z_LAYERS_WARNING {
+            warn!(
+                "the tilemap has {} sprite layers, every chunk holds a slot for each of them at \
+                 every z depth which wastes memory if most are unused",
+                z_layers
+            );
+        }
+
+        let layer_count = if let Some(layers) = &self.layers {
+            layers.iter().count()
+        } else {
+            0
+        };
+        let chunk_mesh =
+            ChunkMesh::new(self.chunk_dimensions, layer_count as u32, self.layer_offset);
+
+        let layers = {
+            let mut layers = vec![None; z_layers];
+            if let Some(map_layers) = self.layers {
+                for (index, layer) in map_layers {
+                    if let Some(l) = layers.get_mut(index) {
+                        *l = Some(layer)
+                    }
+                }
+            }
+            layers
+        };
+
+        Ok(Tilemap {
+            topology: self.topology,
+            dimensions: self.dimensions,
+            chunk_dimensions: self.chunk_dimensions,
+            layer_offset: self.layer_offset,
+            chunk_mesh,
+            texture_dimensions,
+            layers,
+            layer_zoom_ranges: self.layer_zoom_ranges,
+            layer_waves: self.layer_waves,
+            layer_gradings: self.layer_gradings,
+            occluding_layers: self.occluding_layers,
+            static_layers: self.static_layers,
+            y_sorted_layers: self.y_sorted_layers,
+            solid_layers: self.solid_layers,
+            tile_colliders: Default::default(),
+            collider_budget: self.collider_budget,
+            collider_queue: Vec::new(),
+            layer_sort_keys: self.layer_sort_keys,
+            layer_spawn_priorities: self.layer_spawn_priorities,
+            layer_names: self.layer_names,
+            layer_depths: self.layer_depths,
+            depth_range: self.depth_range,
+            hidden_layers: Default::default(),
+            auto_flags: self.auto_flags,
+            auto_spawn: self.auto_spawn,
+            auto_spawn_margin: self.auto_spawn_margin,
+            auto_spawn_budget: self.auto_spawn_budget,
+            window: self.window,
+            auto_spawn_queue: Vec::new(),
+            auto_despawn_queue: Vec::new(),
+            revealing_chunks: HashMap::default(),
+            spawn_policy: None,
+            generator: None,
+            generate_queue: Vec::new(),
+            lod_distance: self.lod_distance,
+            culling: self.culling,
+            culled_chunks: HashSet::default(),
+            lod_colors: Default::default(),
+            chunk_regions: self.chunk_regions,
+            region_entities: HashMap::default(),
+            custom_flags: Vec::new(),
+            click_masks: Default::default(),
+            theme: Default::default(),
+            tile_variants: Default::default(),
+            variant_rolls: 0,
+            annotations: Default::default(),
+            texture_atlas,
+            texture_atlases: Vec::new(),
+            render_pipeline: self.render_pipeline,
+            secondary_texture: self.secondary_texture,
+            color_grading: self.color_grading,
+            chunks: Default::default(),
+            chunk_events: Vec::new(),
+            handled_chunk_events: Vec::new(),
+            deleted_chunks: Vec::new(),
+            unloaded_chunks: Vec::new(),
+            spawned: Default::default(),
+            stream: None,
+            unvalidated_tiles: Vec::new(),
+            recording: None,
+            history: None,
+            paused: false,
+            resumed: false,
+            stitches: HashMap::default(),
+            #[cfg(feature = "failure_injection")]
+            failures: FailureInjector::default(),
+            #[cfg(feature = "text")]
+            tile_labels: HashMap::default(),
+            #[cfg(feature = "text")]
+            removed_label_entities: Vec::new(),
+            fog: self.fog_of_war.map(Fog::new),
+            lights: self.lighting.map(LightMap::new),
+            ambient_occlusion: self.ambient_occlusion,
+            heatmap: self.heatmap,
+            prewarm_queue: Vec::new(),
+            preload_path: None,
+            locked_regions: Vec::new(),
+            locked_chunks: HashSet::default(),
+        })
+    }
+}
+
+impl TypeUuid for Tilemap {
+    const TYPE_UUID: Uuid = Uuid::from_u128(109481186966523254410691740507722642628);
+}
+
+impl Default for Tilemap {
+    fn default() -> Self {
+        Tilemap {
+            topology: GridTopology::Square,
+            dimensions: None,
+            chunk_dimensions: DEFAULT_CHUNK_DIMENSIONS,
+            layer_offset: Vec2::default(),
+            chunk_mesh: ChunkMesh::new(DEFAULT_CHUNK_DIMENSIONS, 1, Vec2::default()),
+            texture_dimensions: DEFAULT_TEXTURE_DIMENSIONS,
+            layers: vec![
+                Some(TilemapLayer {
+                    kind: LayerKind::Sparse,
+                }),
+                None,
+                None,
+                None,
+                None,
+            ],
+            layer_zoom_ranges: Default::default(),
+            layer_waves: Default::default(),
+            layer_gradings: Default::default(),
+            occluding_layers: Default::default(),
+            static_layers: Default::default(),
+            y_sorted_layers: Default::default(),
+            solid_layers: Default::default(),
+            tile_colliders: Default::default(),
+            collider_budget: None,
+            collider_queue: Vec::new(),
+            layer_sort_keys: Default::default(),
+            layer_spawn_priorities: Default::default(),
+            layer_names: Default::default(),
+            layer_depths: Default::default(),
+            depth_range: None,
+            hidden_layers: Default::default(),
+            auto_flags: AutoFlags::NONE,
+            auto_spawn: None,
+            auto_spawn_margin: Dimension2::new(0, 0),
+            auto_spawn_budget: None,
+            window: None,
+            auto_spawn_queue: Vec::new(),
+            auto_despawn_queue: Vec::new(),
+            revealing_chunks: HashMap::default(),
+            spawn_policy: None,
+            generator: None,
+            generate_queue: Vec::new(),
+            lod_distance: None,
+            culling: false,
+            culled_chunks: HashSet::default(),
+            lod_colors: Default::default(),
+            chunk_regions: None,
+            region_entities: HashMap::default(),
+            custom_flags: Vec::new(),
+            click_masks: Default::default(),
+            theme: Default::default(),
+            tile_variants: Default::default(),
+            variant_rolls: 0,
+            annotations: Default::default(),
+            texture_atlas: Handle::default(),
+            texture_atlases: Vec::new(),
+            render_pipeline: None,
+            secondary_texture: None,
+            color_grading: None,
+            chunks: Default::default(),
+            chunk_events: Vec::new(),
+            handled_chunk_events: Vec::new(),
+            deleted_chunks: Vec::new(),
+            unloaded_chunks: Vec::new(),
+            spawned: Default::default(),
+            stream: None,
+            unvalidated_tiles: Vec::new(),
+            recording: None,
+            history: None,
+            paused: false,
+            resumed: false,
+            stitches: HashMap::default(),
+            #[cfg(feature = "failure_injection")]
+            failures: FailureInjector::default(),
+            #[cfg(feature = "text")]
+            tile_labels: HashMap::default(),
+            #[cfg(feature = "text")]
+            removed_label_entities: Vec::new(),
+            fog: None,
+            lights: None,
+            ambient_occlusion: None,
+            heatmap: None,
+            prewarm_queue: Vec::new(),
+            preload_path: None,
+            locked_regions: Vec::new(),
+            locked_chunks: HashSet::default(),
+        }
+    }
+}
+
+impl Tilemap {
+    /// Constructs a new Tilemap with the required texture atlas and default
+    /// configuration.
+    ///
+    /// This differs from [`default`] in that it requires the texture atlas
+    /// handle.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// ```
+    ///
+    /// [`default`]: Tilemap::default
+    pub fn new(
+        texture_atlas: Handle<TextureAtlas>,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> Tilemap {
+        Tilemap {
+            texture_atlas,
+            texture_dimensions: Dimension2::new(texture_width, texture_height),
+            ..Default::default()
+        }
+    }
+
+    /// Configures the builder with the default settings.
+    ///
+    /// This is equivalent to [`default`] and [`builder`] method in the
+    /// [tilemap]. Start with this then you are able to method chain.
+    ///
+    /// [`default`]: TilemapBuilder::default
+    /// [`builder`]: Tilemap::builder
+    /// [tilemap]: Tilemap
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let builder = TilemapBuilder::new();
+    ///
+    /// // Equivalent to...
+    ///
+    /// let builder = TilemapBuilder::default();
+    ///
+    /// // Or...
+    ///
+    /// let builder = Tilemap::builder();
+    /// ```
+    pub fn builder() -> TilemapBuilder {
+        TilemapBuilder::default()
+    }
+
+    /// Sets the sprite sheet for use in the tilemap.
+    ///
+    /// This can be used if one wants to swap the sprite sheet for another.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// let mut tilemap = Tilemap::default();
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// tilemap.set_texture_atlas(texture_atlas_handle);
+    /// ```
+    pub fn set_texture_atlas(&mut self, handle: Handle<TextureAtlas>) {
+        self.texture_atlas = handle;
+        self.lod_colors.clear();
+    }
+
+    /// Swaps the main texture atlas and remaps the sprite indices of its tiles.
+    ///
+    /// Every tile in every chunk that uses the main texture atlas and has a
+    /// sprite index in `remap` has it replaced with the mapped index, all at
+    /// once. Sprite indices which are not in `remap` are kept. All spawned
+    /// chunks are then rendered again with the new texture atlas. This is
+    /// useful for swapping between seasonal tilesets without rebuilding the
+    /// tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_utils::HashMap;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let summer_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let winter_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(summer_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 3, ..Default::default() }).unwrap();
+    ///
+    /// let mut remap = HashMap::default();
+    /// remap.insert(3, 7);
+    /// tilemap.swap_texture_atlas(winter_atlas_handle.clone(), &remap);
+    ///
+    /// assert_eq!(tilemap.texture_atlas(), &winter_atlas_handle);
+    /// assert_eq!(tilemap.get_tile((1, 1), 0), Some(&RawTile { index: 7, ..Default::default() }));
+    /// ```
+    pub fn swap_texture_atlas(
+        &mut self,
+        handle: Handle<TextureAtlas>,
+        remap: &HashMap<usize, usize>,
+    ) {
+        self.texture_atlas = handle;
+        self.lod_colors.clear();
+        if let Some(recording) = &mut self.recording {
+            let mut remap: Vec<(usize, usize)> = remap.iter().map(|(a, b)| (*a, *b)).collect();
+            remap.sort_unstable();
+            recording.push(RecordedEvent::RemapSpriteIndices { remap });
+        }
+        for chunk in self.chunks.values_mut() {
+            chunk.remap_sprite_indices(0, remap);
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: chunk.point(),
+                });
+            }
+        }
+    }
+
+    /// Replaces a sprite index of the main texture atlas with another in
+    /// every chunk, such as to turn all grass into snow or to fix the sprite
+    /// indices after the texture atlas was packed again. Returns the number
+    /// of tiles which were replaced.
+    ///
+    /// Only the tiles in the sprite layers of `sprite_orders` are replaced,
+    /// or in every sprite layer if it is `None`. Tiles in locked regions are
+    /// kept. The tiles are replaced in place, which is far quicker than
+    /// getting every tile, and only the chunks with replaced tiles are
+    /// rendered again. The replacement is part of the undo history.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// const GRASS: usize = 1;
+    /// const SNOW: usize = 5;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.fill_rect((0, 0), (3, 3), 0, 0, RawTile { index: GRASS, ..Default::default() }).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_order: 1, sprite_index: GRASS, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.replace_sprite_index(GRASS, SNOW, Some(&[0])), 16);
+    /// assert_eq!(tilemap.get_tile((2, 3), 0).unwrap().index, SNOW);
+    /// assert_eq!(tilemap.get_tile((1, 1), 1).unwrap().index, GRASS);
+    /// ```
+    pub fn replace_sprite_index(
+        &mut self,
+        from: usize,
+        to: usize,
+        sprite_orders: Option<&[usize]>,
+    ) -> usize {
+        let mut remap = HashMap::default();
+        remap.insert(from, to);
+        self.remap_tiles(&remap, sprite_orders)
+    }
+
+    /// Swaps two sprite indices of the main texture atlas in every chunk, so
+    /// the tiles of either get the other. Returns the number of tiles which
+    /// were changed.
+    ///
+    /// See [`replace_sprite_index`] for more information.
+    ///
+    /// [`replace_sprite_index`]: Tilemap::replace_sprite_index
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 2, ..Default::default() }).unwrap();
+    /// tilemap.insert_tile(Tile { point: (2, 1), sprite_index: 3, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.swap_sprite_indices(2, 3, None), 2);
+    /// assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+    /// assert_eq!(tilemap.get_tile((2, 1), 0).unwrap().index, 2);
+    /// ```
+    pub fn swap_sprite_indices(
+        &mut self,
+        a: usize,
+        b: usize,
+        sprite_orders: Option<&[usize]>,
+    ) -> usize {
+        let mut remap = HashMap::default();
+        remap.insert(a, b);
+        remap.insert(b, a);
+        self.remap_tiles(&remap, sprite_orders)
+    }
+
+    /// Replaces the sprite indices of the tiles of the main texture atlas
+    /// which are in a remap, in the sprite layers of the sprite orders or in
+    /// all of them, and returns the number of tiles which were changed.
+    fn remap_tiles(
+        &mut self,
+        remap: &HashMap<usize, usize>,
+        sprite_orders: Option<&[usize]>,
+    ) -> usize {
+        let chunk_dimensions = self.chunk_dimensions;
+        let mut remapped = Vec::new();
+        for (chunk_point, chunk) in self.chunks.iter() {
+            for (z_depth, sprite_order, index, tile) in chunk.iter_tiles(None) {
+                if tile.atlas != 0 {
+                    continue;
+                }
+                if let Some(sprite_orders) = sprite_orders {
+                    if !sprite_orders.contains(&sprite_order) {
+                        continue;
+                    }
+                }
+                let sprite_index = match remap.get(&tile.index) {
+                    Some(sprite_index) if *sprite_index != tile.index => *sprite_index,
+                    _ => continue,
+                };
+                let point =
+                    Tilemap::chunk_index_to_point(chunk_dimensions, *chunk_point, z_depth, index);
+                if self.is_locked(point) {
+                    continue;
+                }
+                remapped.push((
+                    *chunk_point,
+                    point,
+                    z_depth,
+                    sprite_order,
+                    index,
+                    *tile,
+                    sprite_index,
+                ));
+            }
+        }
+
+        let mut modified = HashSet::default();
+        let mut recorded = Vec::new();
+        for (chunk_point, point, z_depth, sprite_order, index, before, sprite_index) in
+            remapped.iter().copied()
+        {
+            let tile = self
+                .chunks
+                .get_mut(&chunk_point)
+                .and_then(|chunk| chunk.get_tile_mut(index, sprite_order, z_depth));
+            if let Some(tile) = tile {
+                tile.index = sprite_index;
+            }
+            let after = RawTile {
+                index: sprite_index,
+                ..before
+            };
+            if let Some(history) = &mut self.history {
+                history.push(TileChange {
+                    point,
+                    sprite_order,
+                    before: Some(before),
+                    after: Some(after),
+                });
+            }
+            if self.recording.is_some() {
+                recorded.push((point, sprite_order, after));
+            }
+            modified.insert(chunk_point);
+        }
+        self.finish_history_step();
+        if !recorded.is_empty() {
+            self.record(RecordedEvent::InsertTiles { tiles: recorded });
+        }
+        let mut modified: Vec<Point2> = modified.into_iter().collect();
+        modified.sort_unstable_by_key(|point| (point.x, point.y));
+        for point in modified {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point });
+        }
+        remapped.len()
+    }
+
+    /// Returns a reference of the handle of the texture atlas.
+    ///
+    /// The Handle is used to get the correct sprite sheet that is used for this
+    /// tilemap with the renderer.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// let texture_atlas: &Handle<TextureAtlas> = tilemap.texture_atlas();
+    /// ```
+    pub fn texture_atlas(&self) -> &Handle<TextureAtlas> {
+        &self.texture_atlas
+    }
+
+    /// Sets a custom render pipeline for the chunks, or `None` to use the one
+    /// of the topology.
+    ///
+    /// This makes it possible to add effects such as color grading, swaying
+    /// or palette swaps with custom shaders. The pipeline is best built with
+    /// [`chunk_pipeline`], which keeps the crate's shaders for the stages
+    /// that are not replaced. The spawned chunks switch to the pipeline when
+    /// they are next updated, which this triggers.
+    ///
+    /// [`chunk_pipeline`]: crate::chunk::render::chunk_pipeline
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::pipeline::PipelineDescriptor;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let pipeline_handle = Handle::weak(HandleId::random::<PipelineDescriptor>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_render_pipeline(Some(pipeline_handle.clone()));
+    ///
+    /// assert_eq!(tilemap.render_pipeline(), Some(&pipeline_handle));
+    /// ```
+    pub fn set_render_pipeline(&mut self, handle: Option<Handle<PipelineDescriptor>>) {
+        if self.render_pipeline == handle {
+            return;
+        }
+        self.render_pipeline = handle;
+        for chunk in self.chunks.values() {
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: chunk.point(),
+                });
+            }
+        }
+    }
+
+    /// Returns the handle of the custom render pipeline of the chunks, if
+    /// any.
+    pub fn render_pipeline(&self) -> Option<&Handle<PipelineDescriptor>> {
+        self.render_pipeline.as_ref()
+    }
+
+    /// Sets a secondary texture, such as a normal or emissive map, with the
+    /// same layout as the main texture atlas, or `None` to remove it.
+    ///
+    /// The sprites of the secondary texture are at the same places as in the
+    /// texture of the main texture atlas, so the sprite index of a tile picks
+    /// both. The chunks of the main texture atlas bind it as
+    /// [`UNIFORM_SECONDARY_TEXTURE`] and [`UNIFORM_SECONDARY_SAMPLER`], which
+    /// a custom fragment shader set with [`Tilemap::set_render_pipeline`]
+    /// samples at the same `v_Uv` as the atlas to light the tiles. The
+    /// additional texture atlases have no secondary texture.
+    ///
+    /// [`UNIFORM_SECONDARY_TEXTURE`]: crate::chunk::render::UNIFORM_SECONDARY_TEXTURE
+    /// [`UNIFORM_SECONDARY_SAMPLER`]: crate::chunk::render::UNIFORM_SECONDARY_SAMPLER
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::texture::Texture;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let normal_map_handle = Handle::weak(HandleId::random::<Texture>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_secondary_texture(Some(normal_map_handle.clone()));
+    ///
+    /// assert_eq!(tilemap.secondary_texture(), Some(&normal_map_handle));
+    /// ```
+    pub fn set_secondary_texture(&mut self, handle: Option<Handle<Texture>>) {
+        if self.secondary_texture == handle {
+            return;
+        }
+        self.secondary_texture = handle;
+        for chunk in self.chunks.values() {
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: chunk.point(),
+                });
+            }
+        }
+    }
+
+    /// Returns the handle of the secondary texture of the main texture atlas,
+    /// if any.
+    pub fn secondary_texture(&self) -> Option<&Handle<Texture>> {
+        self.secondary_texture.as_ref()
+    }
+
+    /// Sets the texture of the color grading lookup tables of the sprite
+    /// layers, or `None` to remove it.
+    ///
+    /// A lookup table of N colors per channel is a row of N squares of N by N
+    /// pixels, one per level of blue from left to right, with red increasing
+    /// to the right and green downwards within a square. The lookup tables
+    /// are stacked from the top of the texture, so the texture is N * N
+    /// pixels wide and N pixels high per lookup table, and the lookup table
+    /// of a layer is picked by the index in its [`ColorGrading`], where an
+    /// index past the last lookup table picks the last one. The texture needs
+    /// linear filtering to blend between the colors of the tables.
+    ///
+    /// The chunks bind it as [`UNIFORM_COLOR_GRADING_TEXTURE`] and
+    /// [`UNIFORM_COLOR_GRADING_SAMPLER`]. Changing it updates the meshes of
+    /// the spawned chunks once.
+    ///
+    /// [`UNIFORM_COLOR_GRADING_TEXTURE`]: crate::chunk::render::UNIFORM_COLOR_GRADING_TEXTURE
+    /// [`UNIFORM_COLOR_GRADING_SAMPLER`]: crate::chunk::render::UNIFORM_COLOR_GRADING_SAMPLER
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::texture::Texture;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::ColorGrading};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let lut_handle = Handle::weak(HandleId::random::<Texture>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_color_grading(Some(lut_handle.clone()));
+    /// tilemap.set_layer_grading(0, Some(ColorGrading::default())).unwrap();
+    ///
+    /// assert_eq!(tilemap.color_grading(), Some(&lut_handle));
+    /// ```
+    pub fn set_color_grading(&mut self, handle: Option<Handle<Texture>>) {
+        if self.color_grading == handle {
+            return;
+        }
+        self.color_grading = handle;
+        for chunk in self.chunks.values() {
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: chunk.point(),
+                });
+            }
+        }
+    }
+
+    /// Returns the handle of the texture of the color grading lookup tables,
+    /// if any.
+    pub fn color_grading(&self) -> Option<&Handle<Texture>> {
+        self.color_grading.as_ref()
+    }
+
+    /// Returns the handle of the color grading texture the chunks are
+    /// rendered with, which keeps the colors as they are if there is none.
+    pub(crate) fn chunk_color_grading(&self) -> Handle<Texture> {
+        match &self.color_grading {
+            Some(handle) => handle.clone_weak(),
+            None => render::IDENTITY_COLOR_GRADING.typed(),
+        }
+    }
+
+    /// Returns the handle of the secondary texture the chunks of a texture
+    /// atlas are rendered with, if any.
+    pub(crate) fn chunk_secondary_texture(&self, atlas: usize) -> Option<Handle<Texture>> {
+        self.secondary_texture
+            .as_ref()
+            .filter(|_| atlas == 0)
+            .map(Handle::clone_weak)
+    }
+
+    /// Returns the handle of the render pipeline the chunks are rendered with.
+    pub(crate) fn chunk_pipeline(&self) -> Handle<PipelineDescriptor> {
+        match &self.render_pipeline {
+            Some(handle) => handle.clone(),
+            None => self.topology.into_pipeline_handle().typed(),
+        }
+    }
+
+    /// Adds another texture atlas to the tilemap and returns its id.
+    ///
+    /// Tiles use the atlas by setting [`Tile::atlas`] to the returned id, while
+    /// the main texture atlas always has the id 0. Every spawned chunk renders
+    /// the tiles of each atlas it uses with a separate mesh. This makes it
+    /// possible to mix tilesets that can not be packed into one atlas.
+    ///
+    /// The sprites are still laid out on the grid of the tilemap's texture
+    /// dimensions.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    /// let other_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let atlas = tilemap.add_texture_atlas(other_atlas_handle.clone());
+    ///
+    /// assert_eq!(atlas, 1);
+    /// assert_eq!(tilemap.get_texture_atlas(atlas), Some(&other_atlas_handle));
+    ///
+    /// tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 3, atlas, ..Default::default() }).unwrap();
+    /// ```
+    ///
+    /// [`Tile::atlas`]: crate::tile::Tile::atlas
+    pub fn add_texture_atlas(&mut self, handle: Handle<TextureAtlas>) -> usize {
+        self.texture_atlases.push(handle);
+        self.texture_atlases.len()
+    }
+
+    /// Returns a reference of the handle of a texture atlas by its id, if it
+    /// exists.
+    ///
+    /// The id 0 is the main texture atlas, other ids are returned by
+    /// [`add_texture_atlas`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+    ///
+    /// assert_eq!(tilemap.get_texture_atlas(0), Some(&texture_atlas_handle));
+    /// assert_eq!(tilemap.get_texture_atlas(1), None);
+    /// ```
+    ///
+    /// [`add_texture_atlas`]: Tilemap::add_texture_atlas
+    pub fn get_texture_atlas(&self, id: usize) -> Option<&Handle<TextureAtlas>> {
+        if id == 0 {
+            Some(&self.texture_atlas)
+        } else {
+            self.texture_atlases.get(id - 1)
+        }
+    }
+
+    /// Sets the distance in chunks from the camera past which spawned chunks
+    /// are rendered at a low resolution, or `None` to always render the full
+    /// chunks.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_lod_distance(Some(3));
+    ///
+    /// assert_eq!(tilemap.lod_distance(), Some(3));
+    /// ```
+    pub fn set_lod_distance(&mut self, distance: Option<u32>) {
+        self.lod_distance = distance;
+    }
+
+    /// Returns the distance in chunks from the camera past which spawned
+    /// chunks are rendered at a low resolution, if set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .lod_distance(2)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.lod_distance(), Some(2));
+    /// ```
+    pub fn lod_distance(&self) -> Option<u32> {
+        self.lod_distance
+    }
+
+    /// Sets if spawned chunks which are outside of the view of every camera
+    /// driving the tilemap are hidden.
+    ///
+    /// See [`TilemapBuilder::culling`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert!(!tilemap.culling());
+    ///
+    /// tilemap.set_culling(true);
+    /// assert!(tilemap.culling());
+    /// ```
+    pub fn set_culling(&mut self, culling: bool) {
+        self.culling = culling;
+    }
+
+    /// Returns `true` if spawned chunks which are outside of the view of every
+    /// camera driving the tilemap are hidden.
+    pub fn culling(&self) -> bool {
+        self.culling
+    }
+
+    /// Returns the spawned chunks which are hidden by culling.
+    pub(crate) fn culled_chunks_mut(&mut self) -> &mut HashSet<Point2> {
+        &mut self.culled_chunks
+    }
+
+    /// Returns the dimensions in chunks of the regions which group chunk
+    /// entities, if set.
+    pub fn chunk_regions(&self) -> Option<Dimension2> {
+        self.chunk_regions
+    }
+
+    /// Returns the point of the region which groups the entities of a chunk,
+    /// if chunk regions are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_regions(4, 4)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.chunk_region((5, 3)), Some(Point2::new(1, 0)));
+    /// assert_eq!(tilemap.chunk_region((-1, -4)), Some(Point2::new(-1, -1)));
+    /// ```
+    pub fn chunk_region<P: Into<Point2>>(&self, chunk_point: P) -> Option<Point2> {
+        let regions = self.chunk_regions?;
+        let point: Point2 = chunk_point.into();
+        Some(Point2::new(
+            point.x.div_euclid(regions.width as Coord),
+            point.y.div_euclid(regions.height as Coord),
+        ))
+    }
+
+    /// Returns the entity of a region which parents the entities of its
+    /// spawned chunks, if any of them are spawned.
+    ///
+    /// The region entity is translated to the bottom left chunk of the
+    /// region, so it can be rotated and scaled around it. Its transform only
+    /// moves the rendered chunks, picking and auto spawning follow the
+    /// tilemap transform.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_regions(4, 4)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // No chunk of the region is spawned yet.
+    /// assert!(tilemap.region_entity((0, 0)).is_none());
+    /// ```
+    pub fn region_entity<P: Into<Point2>>(&self, region_point: P) -> Option<Entity> {
+        self.region_entities.get(&region_point.into()).copied()
+    }
+
+    /// Returns a mutable reference to the entities of the regions with
+    /// spawned chunks.
+    pub(crate) fn region_entities_mut(&mut self) -> &mut HashMap<Point2, Entity> {
+        &mut self.region_entities
+    }
+
+    /// Returns the rectangle in tilemap space which bounds every tile of a
+    /// chunk.
+    pub(crate) fn chunk_rect(&self, point: Point2) -> Rect {
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let min =
+            Tilemap::chunk_tile_point_to_point(self.chunk_dimensions, point, Point3::new(0, 0, 0));
+        let mut bounds = Rect {
+            min: Vec2::new(f32::MAX, f32::MAX),
+            max: Vec2::new(f32::MIN, f32::MIN),
+        };
+        // Staggered rows and columns reach furthest out on either of the two
+        // tiles nearest each corner.
+        for x in [0, 1, width - 2, width - 1].iter() {
+            for y in [0, 1, height - 2, height - 1].iter() {
+                let (x, y) = ((*x).max(0).min(width - 1), (*y).max(0).min(height - 1));
+                for vertex in self.tile_polygon(Point2::new(min.x + x, min.y + y)) {
+                    bounds.min = bounds.min.min(vertex);
+                    bounds.max = bounds.max.max(vertex);
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Sets the window whose cameras drive the tilemap, or `None` to use the
+    /// cameras of every window.
+    ///
+    /// See [`TilemapBuilder::window`] for more information.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_window::WindowId;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// let editor_window = WindowId::new();
+    /// tilemap.set_window(Some(editor_window));
+    ///
+    /// assert_eq!(tilemap.window(), Some(editor_window));
+    /// ```
+    pub fn set_window(&mut self, window: Option<WindowId>) {
+        self.window = window;
+    }
+
+    /// Returns the window whose cameras drive the tilemap, or `None` if the
+    /// cameras of every window are used.
+    pub fn window(&self) -> Option<WindowId> {
+        self.window
+    }
+
+    /// Returns `true` if a camera drives the tilemap.
+    pub(crate) fn views_camera(&self, camera: &Camera) -> bool {
+        self.window.is_none_or(|window| camera.window == window)
+    }
+
+    /// Constructs a new chunk and stores it at a coordinate position.
+    ///
+    /// It requires that you give it a point. It then automatically sets
+    /// both a sized mesh and chunk for use based on the parameters set in the
+    /// parent tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(3, 3)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // Add some chunks.
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    /// assert!(tilemap.insert_chunk((-2, -2)).is_err());
+    ///
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(tilemap.contains_chunk((1, 1)));
+    /// assert!(!tilemap.contains_chunk((-2, -2)));
+    /// ```
+    /// # Errors
+    ///
+    /// If the point does not exist in the tilemap, an error is returned. This
+    /// can only be returned if you had set the dimensions on the tilemap.
+    ///
+    /// Also will return an error if the chunk already exists. If this happens
+    /// and was intentional, it is best to remove the chunk first. This is
+    /// simply a fail safe without actually returning the chunk as it is meant
     /// to be kept internal.
     pub fn insert_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
         let point: Point2 = point.into();
         if let Some(dimensions) = &self.dimensions {
-            dimensions.check_point(point)?;
+            dimensions.check_centered_point(point)?;
+        }
+        let layer_kinds = self
+            .layers
+            .iter()
+            .map(|x| x.and_then(|y| Some(y.kind)))
+            .collect::<Vec<Option<LayerKind>>>();
+        let chunk = Chunk::new(point, &layer_kinds, self.chunk_dimensions);
+        match self.chunks.insert(point, chunk) {
+            Some(_) => Err(ErrorKind::ChunkAlreadyExists(point).into()),
+            None => {
+                self.record(RecordedEvent::InsertChunk { point });
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns `true` if the chunk is contained in the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(!tilemap.contains_chunk((1, 1)));
+    /// ```
+    pub fn contains_chunk<P: Into<Point2>>(&mut self, point: P) -> bool {
+        let point: Point2 = point.into();
+        self.chunks.contains_key(&point)
+    }
+
+    #[deprecated(
+        since = "0.4.0",
+        note = "Please use `add_layer` method instead with the `TilemapLayer` struct"
+    )]
+    #[doc(hidden)]
+    pub fn add_layer_with_kind(
+        &mut self,
+        kind: LayerKind,
+        sprite_order: usize,
+    ) -> TilemapResult<()> {
+        let layer = TilemapLayer { kind };
+        if let Some(some_kind) = self.layers.get_mut(sprite_order) {
+            if some_kind.is_some() {
+                return Err(ErrorKind::LayerExists(sprite_order).into());
+            }
+            *some_kind = Some(layer);
+        }
+
+        for chunk in self.chunks.values_mut() {
+            chunk.add_sprite_layer(&kind, sprite_order, self.chunk_dimensions);
+        }
+
+        Ok(())
+    }
+
+    /// Adds a layer to the tilemap.
+    ///
+    /// ***Warning:*** This is very unwise and costly if there are many chunks
+    /// in the tilemap. You should only add layers when creating the tilemap.
+    /// The meshes for every single chunk has to be recalculated!
+    ///
+    /// This method creates a layer across all chunks at the specified Z layer.
+    /// For ease of use, it by default makes a layer with a dense
+    /// [`LayerKind`] which is ideal for layers full of sprites.
+    ///
+    /// If you want to use a layer that is more performant and less data heavy,
+    /// use [`add_layer_with_kind`] with [`LayerKind::Sparse`].
+    ///
+    /// If the layer is already the specified layer's kind, then nothing
+    /// happens.
+    ///
+    /// # Errors
+    ///
+    /// If a layer is set and a different layer already exists at that Z layer
+    /// then an error is returned regarding that. This is done to prevent
+    /// accidental overwrites of a layer.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let layer = TilemapLayer {
+    ///    kind: LayerKind::Sparse,
+    ///    ..Default::default()
+    /// };
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.add_layer(layer, 2).is_ok());
+    /// assert!(tilemap.add_layer(layer, 2).is_err());
+    /// ```
+    ///
+    /// [`add_layer_with_kind`]: Tilemap::add_layer_with_kind
+    /// [`LayerKind`]: crate::chunk::LayerKind
+    /// [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse
+    pub fn add_layer(&mut self, layer: TilemapLayer, sprite_layer: usize) -> TilemapResult<()> {
+        if let Some(inner_layer) = self.layers.get_mut(sprite_layer) {
+            if inner_layer.is_some() {
+                return Err(ErrorKind::LayerExists(sprite_layer).into());
+            }
+            *inner_layer = Some(layer);
+        }
+
+        let mut layers = 0;
+        for layer in self.layers().iter() {
+            if layer.is_some() {
+                layers += 1;
+            }
+        }
+        let chunk_mesh = ChunkMesh::new(self.chunk_dimensions, layers, self.layer_offset);
+        self.chunk_mesh = chunk_mesh;
+
+        self.chunk_events.push(TilemapChunkEvent::AddLayer {
+            layer_kind: layer.kind,
+            sprite_layer,
+        });
+        self.record(RecordedEvent::AddLayer {
+            layer,
+            sprite_order: sprite_layer,
+        });
+
+        Ok(())
+    }
+
+    /// Moves a layer from one Z level to another.
+    ///
+    /// # Errors
+    ///
+    /// If the destination exists, it will throw an error. Likewise, if the
+    /// origin does not exist, it also will throw an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .z_layers(3)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Dense, ..Default::default() }, 0)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse, ..Default::default() }, 3)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // If we moved this to layer 3, it would instead fail.
+    /// assert!(tilemap.move_layer(0, 2).is_ok());
+    /// assert!(tilemap.move_layer(3, 2).is_err());
+    /// ```
+    pub fn move_layer(
+        &mut self,
+        from_sprite_order: usize,
+        to_sprite_order: usize,
+    ) -> TilemapResult<()> {
+        if let Some(layer) = self.layers.get(to_sprite_order) {
+            if layer.is_some() {
+                return Err(ErrorKind::LayerExists(to_sprite_order).into());
+            }
+        };
+        if let Some(layer) = self.layers.get(from_sprite_order) {
+            if Some(layer).is_none() {
+                return Err(ErrorKind::LayerDoesNotExist(from_sprite_order).into());
+            }
+        }
+
+        self.layers.swap(from_sprite_order, to_sprite_order);
+        if let Some(range) = self.layer_zoom_ranges.remove(&from_sprite_order) {
+            self.layer_zoom_ranges.insert(to_sprite_order, range);
+        }
+        if let Some(wave) = self.layer_waves.remove(&from_sprite_order) {
+            self.layer_waves.insert(to_sprite_order, wave);
+        }
+        if let Some(grading) = self.layer_gradings.remove(&from_sprite_order) {
+            self.layer_gradings.insert(to_sprite_order, grading);
+        }
+        if self.occluding_layers.remove(&from_sprite_order) {
+            self.occluding_layers.insert(to_sprite_order);
+        }
+        if self.static_layers.remove(&from_sprite_order) {
+            self.static_layers.insert(to_sprite_order);
+        }
+        if self.y_sorted_layers.remove(&from_sprite_order) {
+            self.y_sorted_layers.insert(to_sprite_order);
+        }
+        if self.solid_layers.remove(&from_sprite_order) {
+            self.solid_layers.insert(to_sprite_order);
+        }
+        if let Some(key) = self.layer_sort_keys.remove(&from_sprite_order) {
+            self.layer_sort_keys.insert(to_sprite_order, key);
+        }
+        if let Some(priority) = self.layer_spawn_priorities.remove(&from_sprite_order) {
+            self.layer_spawn_priorities
+                .insert(to_sprite_order, priority);
+        }
+        if let Some(name) = self.layer_names.remove(&from_sprite_order) {
+            self.layer_names.insert(to_sprite_order, name);
+        }
+        if let Some(depth) = self.layer_depths.remove(&from_sprite_order) {
+            self.layer_depths.insert(to_sprite_order, depth);
+        }
+        for chunk in self.chunks.values_mut() {
+            chunk.move_sprite_layer(from_sprite_order, to_sprite_order);
+        }
+        self.record(RecordedEvent::MoveLayer {
+            from_sprite_order,
+            to_sprite_order,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a layer from the tilemap and inner chunks.
+    ///
+    /// **Warning**: This is destructive if you have tiles that exist on that
+    /// layer. If you want to add them back in, better to use the [`move_layer`]
+    /// method instead.
+    ///
+    /// This method takes in a Z layer which is then flagged for deletion. If
+    /// the layer already does not exist, it does nothing.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.add_layer(TilemapLayer { kind: LayerKind::Sparse, ..Default::default() }, 1);
+    ///
+    /// tilemap.remove_layer(1);
+    /// ```
+    ///
+    /// [`move_layer`]: Tilemap::move_layer
+    pub fn remove_layer(&mut self, z: usize) {
+        if let Some(layer) = self.layers.get_mut(z) {
+            *layer = None;
+        } else {
+            return;
+        }
+        self.layer_zoom_ranges.remove(&z);
+        self.layer_waves.remove(&z);
+        self.layer_gradings.remove(&z);
+        self.occluding_layers.remove(&z);
+        self.static_layers.remove(&z);
+        self.y_sorted_layers.remove(&z);
+        self.solid_layers.remove(&z);
+        self.layer_sort_keys.remove(&z);
+        self.layer_spawn_priorities.remove(&z);
+        self.layer_names.remove(&z);
+        self.layer_depths.remove(&z);
+
+        for chunk in self.chunks.values_mut() {
+            chunk.remove_sprite_layer(z);
+        }
+        self.record(RecordedEvent::RemoveLayer { sprite_order: z });
+    }
+
+    /// Sets the range of camera zoom that a sprite layer renders at, or
+    /// `None` to always render it.
+    ///
+    /// The zoom is the scale of the orthographic projection of the first
+    /// camera. Outside of the range the tiles of the layer are hidden, which
+    /// is useful to hide small decorations when zoomed far out or icons when
+    /// zoomed in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::ZoomRange};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let range = ZoomRange { min: 0.5, max: 2.0 };
+    /// assert!(tilemap.set_layer_zoom_range(0, Some(range)).is_ok());
+    /// assert!(tilemap.set_layer_zoom_range(1, Some(range)).is_err());
+    ///
+    /// assert_eq!(tilemap.layer_zoom_range(0), Some(range));
+    /// ```
+    pub fn set_layer_zoom_range(
+        &mut self,
+        sprite_order: usize,
+        range: Option<ZoomRange>,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        match range {
+            Some(range) => self.layer_zoom_ranges.insert(sprite_order, range),
+            None => self.layer_zoom_ranges.remove(&sprite_order),
+        };
+
+        Ok(())
+    }
+
+    /// Returns the range of camera zoom that a sprite layer renders at, if it
+    /// has one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.layer_zoom_range(0), None);
+    /// ```
+    pub fn layer_zoom_range(&self, sprite_order: usize) -> Option<ZoomRange> {
+        self.layer_zoom_ranges.get(&sprite_order).copied()
+    }
+
+    /// Sets a wave which moves the tiles of a sprite layer up and down over
+    /// time, or `None` to keep them still.
+    ///
+    /// The tiles are moved by the chunk shaders, which gives layers such as
+    /// water cheap motion without updating any tiles every frame. Changing
+    /// the wave updates the meshes of the spawned chunks once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::Wave};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let wave = Wave { amplitude: 4.0, frequency: 1.0, wavelength: 64.0 };
+    /// assert!(tilemap.set_layer_wave(0, Some(wave)).is_ok());
+    /// assert!(tilemap.set_layer_wave(1, Some(wave)).is_err());
+    ///
+    /// assert_eq!(tilemap.layer_wave(0), Some(wave));
+    /// ```
+    pub fn set_layer_wave(&mut self, sprite_order: usize, wave: Option<Wave>) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let previous = match wave {
+            Some(wave) => self.layer_waves.insert(sprite_order, wave),
+            None => self.layer_waves.remove(&sprite_order),
+        };
+        if previous != wave {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the wave which moves the tiles of a sprite layer, if it has
+    /// one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.layer_wave(0), None);
+    /// ```
+    pub fn layer_wave(&self, sprite_order: usize) -> Option<Wave> {
+        self.layer_waves.get(&sprite_order).copied()
+    }
+
+    /// Sets the color grading of a sprite layer by a lookup table of the
+    /// color grading texture, or `None` to keep its colors as they are.
+    ///
+    /// The colors of the tiles are graded by the chunk shaders, which gives
+    /// layers a style of their own, such as desaturated caves below ground or
+    /// a dream sequence, without changing any tiles. Changing the color
+    /// grading updates the meshes of the spawned chunks once. The colors are
+    /// kept as they are while the tilemap has no color grading texture, see
+    /// [`Tilemap::set_color_grading`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::ColorGrading};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let horror = ColorGrading { lut: 2, strength: 0.75 };
+    /// assert!(tilemap.set_layer_grading(0, Some(horror)).is_ok());
+    /// assert!(tilemap.set_layer_grading(1, Some(horror)).is_err());
+    ///
+    /// assert_eq!(tilemap.layer_grading(0), Some(horror));
+    /// ```
+    pub fn set_layer_grading(
+        &mut self,
+        sprite_order: usize,
+        grading: Option<ColorGrading>,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let previous = match grading {
+            Some(grading) => self.layer_gradings.insert(sprite_order, grading),
+            None => self.layer_gradings.remove(&sprite_order),
+        };
+        if previous != grading {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the color grading of a sprite layer, if it has one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.layer_grading(0), None);
+    /// ```
+    pub fn layer_grading(&self, sprite_order: usize) -> Option<ColorGrading> {
+        self.layer_gradings.get(&sprite_order).copied()
+    }
+
+    /// Sets a uniform of 4 floats for a sprite layer in a chunk, which custom
+    /// chunk shaders can use for effects local to the chunk, such as the
+    /// strength of the wind or the amount of corruption.
+    ///
+    /// The uniform is uploaded with the chunk mesh as the
+    /// [`ATTRIBUTE_LAYER_UNIFORM`] of every vertex of the layer, and is 0 for
+    /// layers without a uniform. Changing it updates the meshes of the chunk
+    /// once. The uniforms are kept with the chunk until it is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk or the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let wind = [0.8, 0.0, 0.0, 0.0];
+    /// assert!(tilemap.set_layer_uniform((0, 0), 0, wind).is_ok());
+    /// assert!(tilemap.set_layer_uniform((0, 0), 1, wind).is_err());
+    /// assert!(tilemap.set_layer_uniform((1, 0), 0, wind).is_err());
+    ///
+    /// assert_eq!(tilemap.layer_uniform((0, 0), 0), Some(wind));
+    /// assert_eq!(tilemap.remove_layer_uniform((0, 0), 0), Some(wind));
+    /// assert_eq!(tilemap.layer_uniform((0, 0), 0), None);
+    /// ```
+    ///
+    /// [`ATTRIBUTE_LAYER_UNIFORM`]: crate::chunk::render::ATTRIBUTE_LAYER_UNIFORM
+    pub fn set_layer_uniform<P: Into<Point2>>(
+        &mut self,
+        chunk_point: P,
+        sprite_order: usize,
+        uniform: [f32; 4],
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let chunk_point = chunk_point.into();
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk,
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+        if chunk.set_layer_uniform(sprite_order, Some(uniform)) && chunk.has_mesh() {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the uniform of a sprite layer in a chunk, if it has one.
+    pub fn layer_uniform<P: Into<Point2>>(
+        &self,
+        chunk_point: P,
+        sprite_order: usize,
+    ) -> Option<[f32; 4]> {
+        self.chunks
+            .get(&chunk_point.into())
+            .and_then(|chunk| chunk.layer_uniform(sprite_order))
+    }
+
+    /// Removes the uniform of a sprite layer in a chunk, returning it if it
+    /// had one.
+    pub fn remove_layer_uniform<P: Into<Point2>>(
+        &mut self,
+        chunk_point: P,
+        sprite_order: usize,
+    ) -> Option<[f32; 4]> {
+        let chunk_point = chunk_point.into();
+        let chunk = self.chunks.get_mut(&chunk_point)?;
+        let uniform = chunk.layer_uniform(sprite_order)?;
+        chunk.set_layer_uniform(sprite_order, None);
+        if chunk.has_mesh() {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+        Some(uniform)
+    }
+
+    /// Sets if the opaque tiles of a sprite layer hide the tiles below them.
+    ///
+    /// The tiles which are fully covered by a tile of an occluding layer are
+    /// left out when the chunk meshes are built, which saves drawing floors
+    /// under roofs on maps with many layers. A tile covers the tiles below it
+    /// if its color is opaque and it is neither scaled nor offset, as long as
+    /// its layer is not hidden and has no wave. Covered tiles which are larger
+    /// than their cell are still drawn.
+    ///
+    /// The sprites of an occluding layer must be opaque, as the tiles below
+    /// would not show through their transparent pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_occluding(0, true).is_ok());
+    /// assert!(tilemap.set_layer_occluding(1, true).is_err());
+    ///
+    /// assert!(tilemap.is_layer_occluding(0));
+    /// ```
+    pub fn set_layer_occluding(
+        &mut self,
+        sprite_order: usize,
+        occluding: bool,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let changed = if occluding {
+            self.occluding_layers.insert(sprite_order)
+        } else {
+            self.occluding_layers.remove(&sprite_order)
+        };
+        if changed {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the opaque tiles of a sprite layer hide the tiles
+    /// below them.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.is_layer_occluding(0));
+    /// ```
+    pub fn is_layer_occluding(&self, sprite_order: usize) -> bool {
+        self.occluding_layers.contains(&sprite_order)
+    }
+
+    /// Sets if a sprite layer never changes, so its tiles are baked into a
+    /// texture per chunk.
+    ///
+    /// When a chunk spawns, the tiles of its static layers are drawn into a
+    /// texture the size of the chunk, which a single sprite draws from then
+    /// on. The static layers are left out of the chunk's meshes, which saves
+    /// their vertices and tile attributes on large background layers.
+    ///
+    /// Baked layers are drawn below the layers in the meshes and are not
+    /// updated when their tiles change, until the chunk spawns again. Only
+    /// the tiles of the main texture atlas are baked, which is done once its
+    /// texture is loaded. Tilemaps with a hexagonal topology are not baked.
+    /// The setting applies to the chunks spawned after it is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sprite layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_static(0, true).is_ok());
+    /// assert!(tilemap.set_layer_static(1, true).is_err());
+    ///
+    /// assert!(tilemap.is_layer_static(0));
+    /// ```
+    pub fn set_layer_static(&mut self, sprite_order: usize, is_static: bool) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        if is_static {
+            self.static_layers.insert(sprite_order);
+        } else {
+            self.static_layers.remove(&sprite_order);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if a sprite layer is baked into a texture per chunk.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.is_layer_static(0));
+    /// ```
+    pub fn is_layer_static(&self, sprite_order: usize) -> bool {
+        self.static_layers.contains(&sprite_order)
+    }
+
+    /// Sets if the tiles of a sprite layer are depth sorted by their row.
+    ///
+    /// In a y-sorted layer a tile in a lower row renders in front of a tile
+    /// in a higher row, across chunks too, so a tall object standing in
+    /// front of another overlaps it as in a top-down perspective. The layers
+    /// above a y-sorted layer still render above all of its tiles. Tiles are
+    /// sorted over 4096 rows above and below the origin.
+    ///
+    /// The tiles are sorted by the depth of their vertices, so transparent
+    /// pixels of the sprites do not hide the tiles behind them while partly
+    /// transparent pixels may.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_y_sorted(0, true).is_ok());
+    /// assert!(tilemap.set_layer_y_sorted(1, true).is_err());
+    ///
+    /// assert!(tilemap.is_layer_y_sorted(0));
+    /// ```
+    pub fn set_layer_y_sorted(&mut self, sprite_order: usize, y_sorted: bool) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let changed = if y_sorted {
+            self.y_sorted_layers.insert(sprite_order)
+        } else {
+            self.y_sorted_layers.remove(&sprite_order)
+        };
+        if changed {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the tiles of a sprite layer are depth sorted by
+    /// their row.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.is_layer_y_sorted(0));
+    /// ```
+    pub fn is_layer_y_sorted(&self, sprite_order: usize) -> bool {
+        self.y_sorted_layers.contains(&sprite_order)
+    }
+
+    /// Sets the key deciding when a sprite layer renders, or resets it to
+    /// the sprite order of the layer with `None`.
+    ///
+    /// Layers render in the order of their keys, which default to their
+    /// sprite orders, with ties broken by the sprite order. This reorders
+    /// layers without moving their tiles, which would also change the
+    /// collision, occlusion and the layers the tiles are saved in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// // Renders the layer below every layer with the default key.
+    /// assert!(tilemap.set_layer_sort_key(0, Some(-1)).is_ok());
+    /// assert!(tilemap.set_layer_sort_key(1, Some(-1)).is_err());
+    /// assert_eq!(tilemap.layer_sort_key(0), -1);
+    ///
+    /// assert!(tilemap.set_layer_sort_key(0, None).is_ok());
+    /// assert_eq!(tilemap.layer_sort_key(0), 0);
+    /// ```
+    pub fn set_layer_sort_key(
+        &mut self,
+        sprite_order: usize,
+        key: Option<i32>,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let previous = self.layer_sort_key(sprite_order);
+        match key {
+            Some(key) => {
+                self.layer_sort_keys.insert(sprite_order, key);
+            }
+            None => {
+                self.layer_sort_keys.remove(&sprite_order);
+            }
+        }
+        if self.layer_sort_key(sprite_order) != previous {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the key deciding when a sprite layer renders, which is its
+    /// sprite order unless it was set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.layer_sort_key(3), 3);
+    /// ```
+    pub fn layer_sort_key(&self, sprite_order: usize) -> i32 {
+        self.layer_sort_keys
+            .get(&sprite_order)
+            .copied()
+            .unwrap_or(sprite_order as i32)
+    }
+
+    /// Sets the priority deciding when a sprite layer of a chunk spawns, or
+    /// resets it to the default of 0 with `None`.
+    ///
+    /// When auto spawning has a budget, the layers of a spawned chunk appear
+    /// over several frames, those with the highest priority first and one
+    /// priority more every frame after. Giving the ground a higher priority
+    /// than the trees and objects on it keeps them from floating over the
+    /// void while the chunk spawns. Without a budget every layer appears at
+    /// once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_spawn_priority(0, Some(1)).is_ok());
+    /// assert!(tilemap.set_layer_spawn_priority(1, Some(1)).is_err());
+    /// assert_eq!(tilemap.layer_spawn_priority(0), 1);
+    ///
+    /// assert!(tilemap.set_layer_spawn_priority(0, None).is_ok());
+    /// assert_eq!(tilemap.layer_spawn_priority(0), 0);
+    /// ```
+    pub fn set_layer_spawn_priority(
+        &mut self,
+        sprite_order: usize,
+        priority: Option<i32>,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        match priority {
+            Some(priority) => {
+                self.layer_spawn_priorities.insert(sprite_order, priority);
+            }
+            None => {
+                self.layer_spawn_priorities.remove(&sprite_order);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the priority deciding when a sprite layer of a chunk spawns,
+    /// which is 0 unless it was set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.layer_spawn_priority(3), 0);
+    /// ```
+    pub fn layer_spawn_priority(&self, sprite_order: usize) -> i32 {
+        self.layer_spawn_priorities
+            .get(&sprite_order)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Sets the name of a sprite layer, or removes it with `None`.
+    ///
+    /// Named layers can be looked up with [`layer_index`] and their tiles
+    /// inserted, read and cleared by the name instead of the sprite order, so
+    /// game code does not depend on the order of the layers. The name stays
+    /// with the layer when it is moved and is exported to Tiled.
+    ///
+    /// [`layer_index`]: Tilemap::layer_index
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist or another layer already
+    /// has the name.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1).unwrap();
+    ///
+    /// assert!(tilemap.set_layer_name(1, Some("collision")).is_ok());
+    /// assert!(tilemap.set_layer_name(0, Some("collision")).is_err());
+    /// assert!(tilemap.set_layer_name(2, Some("decor")).is_err());
+    /// assert_eq!(tilemap.layer_name(1), Some("collision"));
+    ///
+    /// assert!(tilemap.set_layer_name(1, None).is_ok());
+    /// assert_eq!(tilemap.layer_name(1), None);
+    /// ```
+    pub fn set_layer_name(&mut self, sprite_order: usize, name: Option<&str>) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        match name {
+            Some(name) => {
+                if self
+                    .layer_index(name)
+                    .is_some_and(|other| other != sprite_order)
+                {
+                    return Err(ErrorKind::LayerNameExists(name.to_string()).into());
+                }
+                self.layer_names.insert(sprite_order, name.to_string());
+            }
+            None => {
+                self.layer_names.remove(&sprite_order);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the name of a sprite layer, if it has one.
+    pub fn layer_name(&self, sprite_order: usize) -> Option<&str> {
+        self.layer_names.get(&sprite_order).map(String::as_str)
+    }
+
+    /// Returns the sprite order of the layer with a name, if any layer has
+    /// it.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 3)
+    ///     .layer_name(3, "collision")
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.layer_index("collision"), Some(3));
+    /// assert_eq!(tilemap.layer_index("decor"), None);
+    /// ```
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layer_names
+            .iter()
+            .find(|(_, other)| *other == name)
+            .map(|(sprite_order, _)| *sprite_order)
+    }
+
+    /// Returns the sprite order of the layer with a name or an error.
+    fn named_layer_index(&self, name: &str) -> TilemapResult<usize> {
+        self.layer_index(name)
+            .ok_or_else(|| ErrorKind::LayerNameDoesNotExist(name.to_string()).into())
+    }
+
+    /// Sets the depth a sprite layer renders at, or resets it to the depth it
+    /// has by default with `None`.
+    ///
+    /// The depth is added to the z of the chunks, which are 1.0 above the
+    /// tilemap unless it has a depth range. By default every layer is at a depth of 0.0 and they are
+    /// drawn in the order of their sort keys, except that the layers above a
+    /// y-sorted layer are raised 1.0 above it. A sprite entity between the
+    /// depths of two layers renders between them, such as a player walking
+    /// behind tree tops but over the floor. The tiles of a y-sorted layer are
+    /// sorted between its depth and 1.0 above it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_depth(0, Some(2.5)).is_ok());
+    /// assert!(tilemap.set_layer_depth(1, Some(2.5)).is_err());
+    /// assert_eq!(tilemap.layer_depth(0), Some(2.5));
+    ///
+    /// assert!(tilemap.set_layer_depth(0, None).is_ok());
+    /// assert_eq!(tilemap.layer_depth(0), None);
+    /// ```
+    pub fn set_layer_depth(
+        &mut self,
+        sprite_order: usize,
+        depth: Option<f32>,
+    ) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let previous = self.layer_depth(sprite_order);
+        match depth {
+            Some(depth) => {
+                self.layer_depths.insert(sprite_order, depth);
+            }
+            None => {
+                self.layer_depths.remove(&sprite_order);
+            }
+        }
+        if self.layer_depth(sprite_order) != previous {
+            self.mark_all_chunks_modified();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the depth a sprite layer renders at if it was set.
+    pub fn layer_depth(&self, sprite_order: usize) -> Option<f32> {
+        self.layer_depths.get(&sprite_order).copied()
+    }
+
+    /// Returns the range of z reserved for the tilemap if it was set.
+    ///
+    /// See [`TilemapBuilder::depth_range`] for more information.
+    pub fn depth_range(&self) -> Option<(f32, f32)> {
+        self.depth_range
+    }
+
+    /// Returns the z relative to the tilemap which a depth of the layers is
+    /// drawn at.
+    ///
+    /// Without a depth range this is the depth 1.0 above the tilemap, where
+    /// the chunks are. With a depth range the depth is fitted into it along
+    /// with the depths of the layers, so a sprite at the z of a depth between
+    /// the depths of two layers renders between them either way.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .layer_depth(1, 3.0)
+    ///     .depth_range(-10.0, -1.0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // A player walking under the layer.
+    /// let z = tilemap.depth_z(2.0);
+    /// assert!(z > tilemap.depth_z(0.0) && z < tilemap.depth_z(3.0));
+    /// assert!(z > -10.0 && z < -1.0);
+    /// ```
+    pub fn depth_z(&self, depth: f32) -> f32 {
+        match self.depth_range {
+            Some(depth_range) => {
+                let bounds = depth_bounds(&self.y_sorted_layers, &self.layer_depths);
+                depth_range.0 + fit_depth(depth, bounds, depth_range)
+            }
+            None => 1.0 + depth,
+        }
+    }
+
+    /// Returns the z of the chunk entities relative to the tilemap, which the
+    /// depths of their tiles are added to.
+    pub(crate) fn chunk_z(&self) -> f32 {
+        self.depth_range.map_or(1.0, |(min, _max)| min)
+    }
+
+    /// Sets if the tiles of a sprite layer block movement.
+    ///
+    /// Every tile of a solid layer is solid, whatever its sprite, so walls
+    /// are best kept on their own layer apart from the floors. See the
+    /// [`collision`] module for more information.
+    ///
+    /// [`collision`]: crate::collision
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_layer_solid(0, true).is_ok());
+    /// assert!(tilemap.set_layer_solid(1, true).is_err());
+    ///
+    /// assert!(tilemap.is_layer_solid(0));
+    /// ```
+    pub fn set_layer_solid(&mut self, sprite_order: usize, solid: bool) -> TilemapResult<()> {
+        if self.layers.get(sprite_order).copied().flatten().is_none() {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        if solid {
+            self.solid_layers.insert(sprite_order);
+        } else {
+            self.solid_layers.remove(&sprite_order);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the tiles of a sprite layer block movement.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.is_layer_solid(0));
+    /// ```
+    pub fn is_layer_solid(&self, sprite_order: usize) -> bool {
+        self.solid_layers.contains(&sprite_order)
+    }
+
+    /// Returns `true` if any solid layer has a tile at a point whose collider
+    /// is not a sensor.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (1, 1), ..Default::default() }).unwrap();
+    ///
+    /// assert!(tilemap.is_solid((1, 1)));
+    /// assert!(!tilemap.is_solid((1, 2)));
+    /// ```
+    pub fn is_solid<P>(&self, point: P) -> bool
+    where
+        P: Into<Point3>,
+    {
+        let point = point.into();
+        self.solid_layers.iter().any(|sprite_order| {
+            self.raw_tile(point, *sprite_order).is_some_and(|tile| {
+                !self
+                    .tile_colliders
+                    .get(&tile.index)
+                    .is_some_and(|collider| collider.sensor)
+            })
+        })
+    }
+
+    /// Returns the first solid tile along a ray on z depth 0, from a start
+    /// position to the start plus a direction in tile units.
+    ///
+    /// The ray follows the cells of the topology of the tilemap, so it can be
+    /// cast over hexagonal tilemaps too. A ray which starts in a solid tile
+    /// hits it at the start.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (0, 4), ..Default::default() }).unwrap();
+    ///
+    /// let hit = tilemap.raycast(Vec2::new(0.5, 0.5), Vec2::new(0.0, 8.0)).unwrap();
+    /// assert_eq!(hit.point, Point2::new(0, 4));
+    /// assert_eq!(hit.normal, Vec2::new(0.0, -1.0));
+    /// assert!(tilemap.raycast(Vec2::new(0.5, 0.5), Vec2::new(0.0, 2.0)).is_none());
+    /// ```
+    pub fn raycast(&self, start: Vec2, direction: Vec2) -> Option<RaycastHit> {
+        self.topology_raycast(start, direction, direction.length(), |point| {
+            self.is_solid(point)
+        })
+    }
+
+    /// Returns the first tile of a sprite layer along a ray on z depth 0, up
+    /// to a distance from the start in tile units.
+    ///
+    /// Unlike [`raycast`], any layer can be cast against, whether or not it
+    /// is solid. Like it, the ray follows the cells of the topology of the
+    /// tilemap. Tiles whose collider is a sensor are passed through.
+    /// Positions are in tile units, which are pixels of the tilemap divided
+    /// by the texture dimensions.
+    ///
+    /// [`raycast`]: Tilemap::raycast
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexY)
+    ///     .auto_chunk()
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (4, 0), sprite_order: 1, ..Default::default() }).unwrap();
+    ///
+    /// // The centre of the hexagon at the origin in tile units.
+    /// let outline = tilemap.tile_outline((0, 0));
+    /// let start = outline.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex) / 6.0 / 32.0;
+    ///
+    /// let hit = tilemap.raycast_layer(start, Vec2::new(1.0, 0.0), 10.0, 1).unwrap();
+    /// assert_eq!(hit.point, Point2::new(4, 0));
+    /// assert!(tilemap.raycast_layer(start, Vec2::new(1.0, 0.0), 10.0, 0).is_none());
+    /// assert!(tilemap.raycast_layer(start, Vec2::new(1.0, 0.0), 2.0, 1).is_none());
+    /// ```
+    pub fn raycast_layer(
+        &self,
+        start: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        sprite_order: usize,
+    ) -> Option<RaycastHit> {
+        let is_hit = |point: Point2| {
+            self.raw_tile(Point3::new(point.x, point.y, 0), sprite_order)
+                .is_some_and(|tile| {
+                    !self
+                        .tile_colliders
+                        .get(&tile.index)
+                        .is_some_and(|collider| collider.sensor)
+                })
+        };
+        self.topology_raycast(start, direction, max_distance, is_hit)
+    }
+
+    /// Returns the first cell along a ray up to a distance which is hit,
+    /// stepping through the square grid or the cells of other topologies.
+    fn topology_raycast<F>(
+        &self,
+        start: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        is_hit: F,
+    ) -> Option<RaycastHit>
+    where
+        F: Fn(Point2) -> bool,
+    {
+        let texture_dimensions = Vec2::new(
+            self.texture_dimensions.width as f32,
+            self.texture_dimensions.height as f32,
+        );
+        if self.topology == GridTopology::Square {
+            let length = direction.length();
+            let direction = if length > 0.0 {
+                direction / length * max_distance
+            } else {
+                Vec2::ZERO
+            };
+            return collision::raycast(start, direction, is_hit);
+        }
+        let start_point =
+            self.world_to_point(&GlobalTransform::identity(), start * texture_dimensions);
+        collision::polygon_raycast(
+            start,
+            direction,
+            max_distance,
+            start_point,
+            |point| self.cell_polygon(point),
+            is_hit,
+        )
+    }
+
+    /// Returns `true` if a circle in tile units overlaps a solid tile on z
+    /// depth 0.
+    ///
+    /// The circle is tested against the cells of the topology of the
+    /// tilemap, so it follows the hexagons of hexagonal tilemaps. A solid
+    /// tile blocks its whole cell, whatever the shape of its collider, and a
+    /// circle which only touches a solid tile does not overlap it.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{collision::Circle, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (3, 0), ..Default::default() }).unwrap();
+    ///
+    /// // A building with a radius of 0.6 tiles can not be placed next to the wall.
+    /// assert!(tilemap.overlaps_solid(Circle::new(Vec2::new(2.5, 0.5), 0.6)));
+    /// assert!(!tilemap.overlaps_solid(Circle::new(Vec2::new(2.5, 0.5), 0.4)));
+    /// ```
+    pub fn overlaps_solid(&self, circle: Circle) -> bool {
+        let extent = Vec2::new(circle.radius, circle.radius);
+        self.cells_between(circle.center - extent, circle.center + extent)
+            .into_iter()
+            .any(|point| {
+                self.is_solid(point)
+                    && collision::circle_overlaps_polygon(circle, &self.cell_polygon(point))
+            })
+    }
+
+    /// Returns the points of the cells on z depth 0 whose centers are inside
+    /// a polygon in tile units, from the bottom row up.
+    ///
+    /// The polygon may be concave and in either winding. The cells follow
+    /// the topology of the tilemap and are returned whether or not they have
+    /// tiles, so an explosion can hit every tile of every layer in its area.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let site = [
+    ///     Vec2::new(0.0, 0.0),
+    ///     Vec2::new(2.0, 0.0),
+    ///     Vec2::new(2.0, 2.0),
+    ///     Vec2::new(0.0, 2.0),
+    /// ];
+    /// assert_eq!(
+    ///     tilemap.tiles_in_polygon(&site),
+    ///     vec![Point2::new(0, 0), Point2::new(1, 0), Point2::new(0, 1), Point2::new(1, 1)]
+    /// );
+    /// ```
+    pub fn tiles_in_polygon(&self, polygon: &[Vec2]) -> Vec<Point2> {
+        let first = match polygon.first() {
+            Some(first) => *first,
+            None => return Vec::new(),
+        };
+        let (min, max) = polygon.iter().fold((first, first), |(min, max), vertex| {
+            (min.min(*vertex), max.max(*vertex))
+        });
+        self.cells_between(min, max)
+            .into_iter()
+            .filter(|point| {
+                let cell = self.cell_polygon(*point);
+                let center =
+                    cell.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex) / cell.len() as f32;
+                collision::encloses(polygon, center)
+            })
+            .collect()
+    }
+
+    /// Returns the counter clockwise polygon of the cell of a point on z
+    /// depth 0 in tile units.
+    ///
+    /// Square cells cover `x..x + 1` and `y..y + 1` like the rest of the
+    /// collision queries, other cells are the polygons of their tiles.
+    fn cell_polygon(&self, point: Point2) -> Vec<Vec2> {
+        if self.topology == GridTopology::Square {
+            let min = Vec2::new(point.x as f32, point.y as f32);
+            return vec![
+                min,
+                min + Vec2::new(1.0, 0.0),
+                min + Vec2::new(1.0, 1.0),
+                min + Vec2::new(0.0, 1.0),
+            ];
+        }
+        let texture_dimensions = Vec2::new(
+            self.texture_dimensions.width as f32,
+            self.texture_dimensions.height as f32,
+        );
+        self.tile_polygon(point)
+            .into_iter()
+            .map(|vertex| vertex / texture_dimensions)
+            .collect()
+    }
+
+    /// Returns the points of the cells on z depth 0 which may overlap a box
+    /// in tile units, from the bottom row up.
+    fn cells_between(&self, min: Vec2, max: Vec2) -> Vec<Point2> {
+        let (low, high) = if self.topology == GridTopology::Square {
+            (
+                Point2::new(min.x.floor() as Coord, min.y.floor() as Coord),
+                Point2::new(max.x.floor() as Coord, max.y.floor() as Coord),
+            )
+        } else {
+            let texture_dimensions = Vec2::new(
+                self.texture_dimensions.width as f32,
+                self.texture_dimensions.height as f32,
+            );
+            let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+            let points = corners.iter().map(|corner| {
+                self.world_to_point(&GlobalTransform::identity(), *corner * texture_dimensions)
+            });
+            let (low, high) = points.fold(
+                (
+                    Point2::new(Coord::MAX, Coord::MAX),
+                    Point2::new(Coord::MIN, Coord::MIN),
+                ),
+                |(low, high), point| {
+                    (
+                        Point2::new(low.x.min(point.x), low.y.min(point.y)),
+                        Point2::new(high.x.max(point.x), high.y.max(point.y)),
+                    )
+                },
+            );
+            // The cells are staggered, so the cells at the edges of the box
+            // can be a point past its corners.
+            (low - Point2::new(1, 1), high + Point2::new(1, 1))
+        };
+        (low.y..=high.y)
+            .flat_map(|y| (low.x..=high.x).map(move |x| Point2::new(x, y)))
+            .collect()
+    }
+
+    /// Moves a box in tile units by a motion on z depth 0, stopping at the
+    /// solid tiles in its way.
+    ///
+    /// The box is moved along the x axis first and then along the y axis, so
+    /// it slides along walls instead of sticking to them. Every tile the box
+    /// would pass through is tested, so fast boxes do not tunnel through
+    /// thin walls. A box which already overlaps a solid tile is not pushed
+    /// out of it.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{collision::Aabb, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (0, -1), ..Default::default() }).unwrap();
+    ///
+    /// let player = Aabb::new(Vec2::new(0.25, 2.0), Vec2::new(0.75, 3.0));
+    /// let moved = tilemap.move_and_collide(player, Vec2::new(0.0, -5.0));
+    ///
+    /// assert!(moved.blocked_y);
+    /// assert_eq!(moved.aabb.min.y, 0.0);
+    /// ```
+    pub fn move_and_collide(&self, aabb: Aabb, motion: Vec2) -> CollisionMove {
+        collision::move_and_collide(aabb, motion, |point| self.is_solid(point))
+    }
+
+    /// Sets the collider of all tiles with the given sprite index.
+    ///
+    /// Tiles of solid layers without a collider set for their sprite index
+    /// are full squares which block. If a collider already exists for the
+    /// sprite index, it is replaced.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{collision::{Slope, TileCollider, TileShape}, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let collider = TileCollider { shape: TileShape::Slope(Slope::BottomLeft), sensor: false };
+    /// tilemap.set_tile_collider(3, collider.clone());
+    ///
+    /// assert_eq!(tilemap.tile_collider(3), Some(&collider));
+    /// ```
+    pub fn set_tile_collider(&mut self, sprite_index: usize, collider: TileCollider) {
+        self.tile_colliders.insert(sprite_index, collider);
+    }
+
+    /// Returns a reference to the collider of a sprite index, if any.
+    pub fn tile_collider(&self, sprite_index: usize) -> Option<&TileCollider> {
+        self.tile_colliders.get(&sprite_index)
+    }
+
+    /// Removes and returns the collider of a sprite index, if any.
+    pub fn remove_tile_collider(&mut self, sprite_index: usize) -> Option<TileCollider> {
+        self.tile_colliders.remove(&sprite_index)
+    }
+
+    /// Returns the colliders of the solid tiles on z depth 0 in tile units,
+    /// ready to be handed to a physics engine.
+    ///
+    /// Neighbouring tiles whose colliders are the same rectangle, such as
+    /// full squares or the same half, are merged into a single cuboid, which
+    /// cuts down the colliders of a large map by orders of magnitude. Other
+    /// shapes make a convex polygon per tile. Where solid layers overlap, the
+    /// tile of the highest layer gives the collider.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{chunk::RawTile, collision::{Aabb, ColliderShape}, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.fill_rect((0, 0), (9, 0), 0, 0, RawTile::default()).unwrap();
+    ///
+    /// let colliders = tilemap.solid_colliders();
+    /// assert_eq!(colliders.len(), 1);
+    /// assert_eq!(
+    ///     colliders[0].shape,
+    ///     ColliderShape::Cuboid(Aabb::new(Vec2::new(0.0, 0.0), Vec2::new(10.0, 1.0)))
+    /// );
+    /// ```
+    pub fn solid_colliders(&self) -> Vec<Collider> {
+        let tiles = self.solid_tiles(self.chunks.iter());
+        collision::merge_colliders(&tiles)
+    }
+
+    /// Returns the colliders of the solid tiles on z depth 0 of a chunk in
+    /// tile units, such as to add them to a physics engine when the chunk
+    /// spawns.
+    ///
+    /// With [`TilemapBuilder::merge_colliders`] neighbouring tiles are merged
+    /// like with [`solid_colliders`], otherwise every solid tile has its own
+    /// collider. Tiles are never merged across chunks, so the colliders of a
+    /// chunk can be removed again when it despawns.
+    ///
+    /// [`TilemapBuilder::merge_colliders`]: TilemapBuilder::merge_colliders
+    /// [`solid_colliders`]: Tilemap::solid_colliders
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_dimensions(8, 8, 1)
+    ///     .auto_chunk()
+    ///     .merge_colliders()
+    ///     .solid_layer(0)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.fill_rect((-4, -4), (3, 3), 0, 0, RawTile::default()).unwrap();
+    ///
+    /// assert_eq!(tilemap.chunk_colliders((0, 0)).len(), 1);
+    /// assert!(tilemap.chunk_colliders((1, 0)).is_empty());
+    /// ```
+    pub fn chunk_colliders<P: Into<Point2>>(&self, chunk_point: P) -> Vec<Collider> {
+        let chunk_point: Point2 = chunk_point.into();
+        let tiles = self.solid_tiles(self.chunks.get_key_value(&chunk_point).into_iter());
+        if self.auto_flags.contains(AutoFlags::MERGE_COLLIDERS) {
+            collision::merge_colliders(&tiles)
+        } else {
+            collision::tile_colliders(&tiles)
+        }
+    }
+
+    /// Builds the colliders of the spawned chunks waiting for them, up to the
+    /// collider budget, nearest to a chunk point first.
+    ///
+    /// Only chunks which spawned since the tilemap was built with
+    /// [`TilemapBuilder::collider_budget`] wait for their colliders. The
+    /// colliders of a chunk are made with [`chunk_colliders`] when the chunk
+    /// is first reached and are then handed out over as many calls as the
+    /// budget needs, the last of which is marked complete. A chunk which
+    /// despawns before it is complete stops waiting. The tilemap systems call
+    /// this every frame with the chunk under the camera and send the result
+    /// as [`TilemapCollidersEvent`]s, so this is only needed when using a
+    /// tilemap without them.
+    ///
+    /// [`TilemapBuilder::collider_budget`]: TilemapBuilder::collider_budget
+    /// [`chunk_colliders`]: Tilemap::chunk_colliders
+    /// [`TilemapCollidersEvent`]: crate::event::TilemapCollidersEvent
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_dimensions(4, 4, 1)
+    ///     .solid_layer(0)
+    ///     .collider_budget(10)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.fill_rect((-2, -2), (1, 1), 0, 0, RawTile::default()).unwrap();
+    /// tilemap.queue_colliders((0, 0));
+    ///
+    /// let first = tilemap.build_colliders((0, 0));
+    /// assert_eq!(first[0].colliders.len(), 10);
+    /// assert!(!first[0].complete);
+    ///
+    /// let second = tilemap.build_colliders((0, 0));
+    /// assert_eq!(second[0].colliders.len(), 6);
+    /// assert!(second[0].complete);
+    /// assert!(tilemap.build_colliders((0, 0)).is_empty());
+    /// ```
+    pub fn build_colliders<P: Into<Point2>>(&mut self, focus: P) -> Vec<ChunkColliders> {
+        let focus: Point2 = focus.into();
+        let mut budget = match self.collider_budget {
+            Some(budget) => budget as usize,
+            None => return Vec::new(),
+        };
+        self.collider_queue.sort_by_key(|build| {
+            (build.point.x - focus.x)
+                .abs()
+                .max((build.point.y - focus.y).abs())
+        });
+
+        let mut built = Vec::new();
+        let mut queue = mem::take(&mut self.collider_queue).into_iter();
+        for mut build in &mut queue {
+            let mut colliders = match build.colliders.take() {
+                Some(colliders) => colliders,
+                None => self.chunk_colliders(build.point),
+            };
+            if budget == 0 && !colliders.is_empty() {
+                build.colliders = Some(colliders);
+                self.collider_queue.push(build);
+                break;
+            }
+            let count = colliders.len().min(budget);
+            budget -= count;
+            let rest = colliders.split_off(count);
+            let complete = rest.is_empty();
+            let point = build.point;
+            if !complete {
+                build.colliders = Some(rest);
+                self.collider_queue.push(build);
+            }
+            built.push(ChunkColliders {
+                point,
+                colliders,
+                complete,
+            });
+        }
+        self.collider_queue.extend(queue);
+        built
+    }
+
+    /// Queues a spawned chunk to have its colliders built, if there is a
+    /// collider budget.
+    ///
+    /// The tilemap systems queue every chunk they spawn, so this is only
+    /// needed when using a tilemap without them. See [`build_colliders`] for
+    /// more information.
+    ///
+    /// [`build_colliders`]: Tilemap::build_colliders
+    pub fn queue_colliders<P: Into<Point2>>(&mut self, point: P) {
+        let point: Point2 = point.into();
+        if self.collider_budget.is_none()
+            || self.collider_queue.iter().any(|build| build.point == point)
+        {
+            return;
+        }
+        self.collider_queue.push(ColliderBuild {
+            point,
+            colliders: None,
+        });
+    }
+
+    /// Returns `true` if the tilemap builds the colliders of its spawned
+    /// chunks within a budget.
+    pub(crate) fn has_collider_budget(&self) -> bool {
+        self.collider_budget.is_some()
+    }
+
+    /// Returns the solid tiles on z depth 0 of some chunks with their
+    /// colliders, where the tile of the highest solid layer wins.
+    fn solid_tiles<'a, I>(&'a self, chunks: I) -> Vec<(Point2, &'a TileCollider)>
+    where
+        I: Iterator<Item = (&'a Point2, &'a Chunk)>,
+    {
+        let chunk_dimensions = self.chunk_dimensions;
+        let mut tiles: HashMap<Point2, (usize, &TileCollider)> = HashMap::default();
+        for (chunk_point, chunk) in chunks {
+            for (z_depth, sprite_order, index, tile) in chunk.raw_tiles() {
+                if z_depth != 0 || !self.solid_layers.contains(&sprite_order) {
+                    continue;
+                }
+                let point: Point2 =
+                    Tilemap::chunk_index_to_point(chunk_dimensions, *chunk_point, z_depth, index)
+                        .into();
+                let collider = self
+                    .tile_colliders
+                    .get(&tile.index)
+                    .unwrap_or(&DEFAULT_TILE_COLLIDER);
+                let entry = tiles.entry(point).or_insert((sprite_order, collider));
+                if entry.0 < sprite_order {
+                    *entry = (sprite_order, collider);
+                }
+            }
+        }
+        tiles
+            .into_iter()
+            .map(|(point, (_sprite_order, collider))| (point, collider))
+            .collect()
+    }
+
+    /// Spawns a chunk at a given index or coordinate.
+    ///
+    /// Does nothing if the chunk does not exist.
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0));
+    ///
+    /// // Ideally you should want to set some tiles here else nothing will
+    /// // display in the render...
+    ///
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.spawn_chunk((1, 1)).is_err());
+    /// assert!(tilemap.spawn_chunk((-1, -1)).is_err());
+    /// ```
+    pub fn spawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point: Point2 = point.into();
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_centered_point(point)?;
+        }
+
+        if self.spawned.contains(&(point.x, point.y)) {
+            return Ok(());
+        } else {
+            self.chunk_events.push(TilemapChunkEvent::Spawned { point });
+            self.record(RecordedEvent::SpawnChunk { point });
+        }
+
+        Ok(())
+    }
+
+    /// Spawns a chunk at a given tile point.
+    ///
+    /// # Errors
+    ///
+    /// If the coordinate or index is out of bounds or if the chunk does not
+    /// exist, an error will be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .chunk_dimensions(32, 32, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .dimensions(1, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let point = (15, 15);
+    /// let sprite_index = 0;
+    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    ///
+    /// tilemap.insert_tile(tile);
+    ///
+    /// assert!(tilemap.spawn_chunk_containing_point(point).is_ok());
+    /// assert!(tilemap.spawn_chunk_containing_point((16, 16)).is_err());
+    /// assert!(tilemap.spawn_chunk_containing_point((-18, -18)).is_err());
+    /// ```
+    pub fn spawn_chunk_containing_point<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point = self.point_to_chunk_point(point);
+        self.spawn_chunk(point)
+    }
+
+    /// Sets the policy which decides which chunks may be spawned and despawned
+    /// automatically, replacing any previous one.
+    ///
+    /// See the [`spawn_policy`] module for more information.
+    ///
+    /// [`spawn_policy`]: crate::spawn_policy
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*, spawn_policy::ChunkSpawnPolicy};
+    ///
+    /// // Keeps the chunk of the town spawned once it is spawned.
+    /// struct KeepTown;
+    ///
+    /// impl ChunkSpawnPolicy for KeepTown {
+    ///     fn can_despawn(&self, point: Point2) -> bool {
+    ///         point != Point2::new(0, 0)
+    ///     }
+    /// }
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.set_spawn_policy(KeepTown);
+    /// ```
+    pub fn set_spawn_policy<P: ChunkSpawnPolicy>(&mut self, policy: P) {
+        self.spawn_policy = Some(Box::new(policy));
+    }
+
+    /// Removes the policy which decides which chunks may be spawned and
+    /// despawned automatically, so that all of them may be.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.clear_spawn_policy();
+    /// ```
+    pub fn clear_spawn_policy(&mut self) {
+        self.spawn_policy = None;
+    }
+
+    /// Sets the generator which generates the tiles of the chunks auto spawn
+    /// needs but which do not exist yet, replacing any previous one.
+    ///
+    /// See the [`generator`] module for more information.
+    ///
+    /// [`generator`]: crate::generator
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{
+    ///     dimension::Dimension3,
+    ///     generator::ChunkGenerator,
+    ///     point::{Coord, Point2, Point3},
+    ///     prelude::*,
+    /// };
+    ///
+    /// // Fills every chunk with water.
+    /// struct Ocean;
+    ///
+    /// impl ChunkGenerator for Ocean {
+    ///     fn generate(&self, _point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>> {
+    ///         let mut tiles = Vec::new();
+    ///         for y in 0..dimensions.height as Coord {
+    ///             for x in 0..dimensions.width as Coord {
+    ///                 tiles.push(Tile { point: Point3::new(x, y, 0), ..Default::default() });
+    ///             }
+    ///         }
+    ///         tiles
+    ///     }
+    /// }
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.set_generator(Ocean);
+    /// assert!(tilemap.has_generator());
+    /// ```
+    pub fn set_generator<G: ChunkGenerator>(&mut self, generator: G) {
+        self.generator = Some(Arc::new(generator));
+    }
+
+    /// Removes the generator of new chunks, so that auto spawn populates them
+    /// with `NeedsPopulation` events again.
+    ///
+    /// Chunks which are queued to be generated are not spawned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.clear_generator();
+    /// assert!(!tilemap.has_generator());
+    /// ```
+    pub fn clear_generator(&mut self) {
+        self.generator = None;
+        self.generate_queue.clear();
+    }
+
+    /// Returns `true` if a generator of new chunks is set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.has_generator());
+    /// ```
+    pub fn has_generator(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    /// Re-evaluates the tiles on either side of the edges of a chunk, such as
+    /// to pick autotiles by the tiles across the edges.
+    ///
+    /// The tiles along the edges of the chunk and along the facing edges and
+    /// corners of the chunks around it are passed to `retile` with their
+    /// global point, sprite order and a view of the tilemap, which returns the
+    /// tile to replace each with or `None` to keep it. Every tile is retiled
+    /// from the tiles before any is replaced, and the replacements are set in
+    /// one go with [`insert_tiles`], so every chunk is only modified once.
+    /// Tiles in locked regions and chunks which do not exist are skipped.
+    ///
+    /// Call it after populating a chunk by hand. Generated chunks are retiled
+    /// with [`ChunkGenerator::retile`] automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{chunk::RawTile, point::Point3, prelude::*, source::TileSource};
+    ///
+    /// const WATER: usize = 0;
+    /// const LAND: usize = 1;
+    /// const SHORE: usize = 2;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .chunk_dimensions(4, 4, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // Land in the first chunk and water in the one right of it.
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.fill_rect((-2, -2), (1, 1), 0, 0, RawTile { index: LAND, ..Default::default() }).unwrap();
+    /// tilemap.insert_chunk((1, 0)).unwrap();
+    /// tilemap.fill_rect((2, -2), (5, 1), 0, 0, RawTile { index: WATER, ..Default::default() }).unwrap();
+    ///
+    /// tilemap
+    ///     .retile_chunk_border((1, 0), |source, point, sprite_order, tile| {
+    ///         let right = source.tile_at(Point3::new(point.x + 1, point.y, point.z), sprite_order)?;
+    ///         if tile.index == LAND && right.index == WATER {
+    ///             Some(RawTile { index: SHORE, ..*tile })
+    ///         } else {
+    ///             None
+    ///         }
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((1, 0), 0).unwrap().index, SHORE);
+    /// assert_eq!(tilemap.get_tile((0, 0), 0).unwrap().index, LAND);
+    /// ```
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`ChunkGenerator::retile`]: crate::generator::ChunkGenerator::retile
+    pub fn retile_chunk_border<P, F>(&mut self, point: P, mut retile: F) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        F: FnMut(&dyn TileSource, Point3, usize, &RawTile) -> Option<RawTile>,
+    {
+        let chunk_point: Point2 = point.into();
+        let chunk_dimensions = self.chunk_dimensions;
+        let min =
+            Tilemap::chunk_tile_point_to_point(chunk_dimensions, chunk_point, Point3::new(0, 0, 0));
+        let max = Tilemap::chunk_tile_point_to_point(
+            chunk_dimensions,
+            chunk_point,
+            Point3::new(
+                chunk_dimensions.width as Coord - 1,
+                chunk_dimensions.height as Coord - 1,
+                0,
+            ),
+        );
+
+        let mut tiles = Vec::new();
+        for y in min.y - 1..=max.y + 1 {
+            for x in min.x - 1..=max.x + 1 {
+                // Only the rings of tiles just inside and outside the edges.
+                if x > min.x && x < max.x && y > min.y && y < max.y {
+                    continue;
+                }
+                for z in 0..chunk_dimensions.depth as Coord {
+                    let point = Point3::new(x, y, z);
+                    if self.is_locked(point) {
+                        continue;
+                    }
+                    for sprite_order in 0..self.layers.len() {
+                        let tile = match self.raw_tile(point, sprite_order) {
+                            Some(tile) => tile,
+                            None => continue,
+                        };
+                        if let Some(retiled) = retile(&*self, point, sprite_order, tile) {
+                            tiles.push(Tile::from_raw_tile(point, sprite_order, &retiled));
+                        }
+                    }
+                }
+            }
+        }
+        if tiles.is_empty() {
+            return Ok(());
+        }
+        self.insert_tiles(tiles)
+    }
+
+    /// Builds the meshes of chunks ahead of spawning them.
+    ///
+    /// The tile attributes of the meshes are built in parallel on the compute
+    /// task pool during the next update, such as while a loading screen or
+    /// menu is shown. When a prewarmed chunk spawns later on its meshes only
+    /// need to be uploaded, so the first camera pan into a new region does not
+    /// pay the cost of building them. Changing any tile of a prewarmed chunk
+    /// before it spawns throws the prewarmed meshes away.
+    ///
+    /// Chunks which are already spawned or prewarmed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// If a coordinate is out of bounds or a chunk does not exist, an error is
+    /// returned and no chunk is prewarmed.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_chunk((1, 0)).unwrap();
+    ///
+    /// assert!(tilemap.prewarm_chunks(vec![(0, 0), (1, 0)]).is_ok());
+    /// assert!(tilemap.prewarm_chunks(vec![(2, 0)]).is_err());
+    /// ```
+    pub fn prewarm_chunks<P, I>(&mut self, points: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let mut prewarm = Vec::new();
+        for point in points {
+            let point: Point2 = point.into();
+            if let Some(dimensions) = &self.dimensions {
+                dimensions.check_centered_point(point)?;
+            }
+            if !self.chunks.contains_key(&point) {
+                return Err(ErrorKind::MissingChunk.into());
+            }
+            prewarm.push(point);
+        }
+        self.prewarm_queue.extend(prewarm);
+
+        Ok(())
+    }
+
+    /// Spawns the chunks along a known camera path ahead of the camera, such
+    /// as the path of a cutscene or a fast travel route, replacing any
+    /// previous path.
+    ///
+    /// The points are tile points which the camera follows in order. While
+    /// the camera moves along the path its speed is estimated, and the chunks
+    /// around the points it reaches within the lead time are spawned before
+    /// it arrives, no matter the auto spawn radius. The chunks around a point
+    /// are those within the auto spawn dimensions if set, otherwise the
+    /// chunk of the point and its neighbours. Auto spawning does not despawn
+    /// them until the camera reaches their point, and the path is done once
+    /// the camera reaches the last point.
+    ///
+    /// The camera is the first camera the tilemap views.
+    ///
+    /// # Errors
+    ///
+    /// If a point is out of bounds, an error is returned and the previous
+    /// path is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(3, 3)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let path = vec![(0, 0), (16, 0), (32, 8)];
+    /// assert!(tilemap.preload_path(path, Duration::from_secs(2)).is_ok());
+    /// assert!(tilemap.is_preloading());
+    ///
+    /// assert!(tilemap.preload_path(vec![(1000, 0)], Duration::from_secs(2)).is_err());
+    ///
+    /// tilemap.clear_preload_path();
+    /// assert!(!tilemap.is_preloading());
+    /// ```
+    pub fn preload_path<P, I>(&mut self, points: I, lead_time: Duration) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let mut path = Vec::new();
+        for point in points {
+            let point: Point2 = point.into();
+            if let Some(dimensions) = &self.dimensions {
+                dimensions.check_centered_point(self.point_to_chunk_point(point).into())?;
+            }
+            path.push(point);
+        }
+        self.preload_path = if path.is_empty() {
+            None
+        } else {
+            Some(PreloadPath::new(path, lead_time))
+        };
+
+        Ok(())
+    }
+
+    /// Stops spawning chunks along the camera path, if there is one.
+    ///
+    /// The chunks which were spawned for the path stay spawned until auto
+    /// spawning despawns them.
+    pub fn clear_preload_path(&mut self) {
+        self.preload_path = None;
+    }
+
+    /// Returns `true` if chunks are being spawned along a camera path.
+    pub fn is_preloading(&self) -> bool {
+        self.preload_path.is_some()
+    }
+
+    /// Unloads a chunk, despawning its entities while keeping its tiles so it
+    /// can be spawned again.
+    ///
+    /// Sends a [`TilemapChunkEvent::Despawned`] event if the chunk is spawned
+    /// or waiting to be spawned. Otherwise there is nothing to despawn, so no
+    /// event is sent, nothing is persisted and nothing is recorded. Its tiles
+    /// are left as they are either way.
+    ///
+    /// [`TilemapChunkEvent::Despawned`]: crate::event::TilemapChunkEvent::Despawned
+    ///
+    /// # Errors
+    ///
+    /// If the point is out of bounds or the chunk does not exist, an error is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// // Ideally you should want to set some tiles here else nothing will
+    /// // display in the render...
+    ///
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    ///
+    /// // Later a frame or more on...
+    ///
+    /// assert!(tilemap.unload_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(tilemap.unload_chunk((-1, -1)).is_err());
+    /// ```
+    pub fn unload_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point: Point2 = point.into();
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_centered_point(point)?;
+        }
+        if !self.chunks.contains_key(&point) {
+            return Err(ErrorKind::MissingChunk.into());
+        }
+        let spawning = self.chunk_events.iter().any(|event| {
+            matches!(event, TilemapChunkEvent::Spawned { point: spawned } if *spawned == point)
+        });
+        if !spawning && !self.spawned.contains(&(point.x, point.y)) {
+            return Ok(());
+        }
+
+        self.send_unloaded(point, false)?;
+        self.record(RecordedEvent::DespawnChunk { point });
+
+        Ok(())
+    }
+
+    /// Deletes a chunk along with its tiles, despawning it if it is spawned.
+    ///
+    /// Sends a [`TilemapChunkEvent::Deleted`] event. The chunk is gone from
+    /// the tilemap right away, so it can be inserted again, but it is not
+    /// recoverable unless the tilemap persists unloaded chunks.
+    ///
+    /// [`TilemapChunkEvent::Deleted`]: crate::event::TilemapChunkEvent::Deleted
+    ///
+    /// # Errors
+    ///
+    /// If the point is out of bounds or the chunk does not exist, an error is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(3, 3)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // Add some chunks.
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    /// assert!(tilemap.spawn_chunk((1, 1)).is_ok());
+    ///
+    /// assert!(tilemap.delete_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.delete_chunk((1, 1)).is_ok());
+    /// assert!(!tilemap.contains_chunk((1, 1)));
+    ///
+    /// // Neither chunk exists anymore.
+    /// assert!(tilemap.delete_chunk((0, 0)).is_err());
+    /// assert!(tilemap.delete_chunk((-2, -2)).is_err());
+    /// ```
+    pub fn delete_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        let point = point.into();
+        self.send_unloaded(point, true)?;
+
+        if let Some(chunk) = self.chunks.remove(&point) {
+            self.deleted_chunks.push(chunk);
+        }
+        self.record(RecordedEvent::RemoveChunk { point });
+
+        Ok(())
+    }
+
+    /// Unloads every spawned chunk, keeping the chunks and their tiles.
+    ///
+    /// Sends a [`TilemapChunkEvent::Despawned`] event for each, so their
+    /// entities are despawned and their meshes freed, the same as calling
+    /// [`unload_chunk`] for every spawned chunk.
+    ///
+    /// [`TilemapChunkEvent::Despawned`]: crate::event::TilemapChunkEvent::Despawned
+    /// [`unload_chunk`]: Tilemap::unload_chunk
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`unload_chunk`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 0)).is_ok());
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.spawn_chunk((1, 0)).is_ok());
+    ///
+    /// assert!(tilemap.unload_all_chunks().is_ok());
+    /// // The chunks and their tiles are kept.
+    /// assert!(tilemap.contains_chunk((0, 0)));
+    /// assert!(tilemap.contains_chunk((1, 0)));
+    /// ```
+    pub fn unload_all_chunks(&mut self) -> TilemapResult<()> {
+        let mut points: Vec<(Coord, Coord)> = self.spawned.iter().copied().collect();
+        points.sort_unstable();
+        for point in points {
+            self.unload_chunk(point)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes every chunk along with its tiles, despawning the ones which
+    /// are spawned.
+    ///
+    /// Sends a [`TilemapChunkEvent::Deleted`] event for each, the same as
+    /// calling [`delete_chunk`] for every chunk, which is handy for resetting
+    /// a level without tracking the points of its chunks. The settings of the
+    /// tilemap, such as its layers, are kept.
+    ///
+    /// [`TilemapChunkEvent::Deleted`]: crate::event::TilemapChunkEvent::Deleted
+    /// [`delete_chunk`]: Tilemap::delete_chunk
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`delete_chunk`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((-1, 2)).is_ok());
+    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
+    ///
+    /// assert!(tilemap.delete_all_chunks().is_ok());
+    /// assert!(!tilemap.contains_chunk((0, 0)));
+    /// assert!(!tilemap.contains_chunk((-1, 2)));
+    ///
+    /// // The chunks can be inserted again right away.
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// ```
+    pub fn delete_all_chunks(&mut self) -> TilemapResult<()> {
+        let mut points: Vec<Point2> = self.chunks.keys().copied().collect();
+        points.sort_unstable_by_key(|point| (point.x, point.y));
+        for point in points {
+            self.delete_chunk(point)?;
+        }
+        Ok(())
+    }
+
+    /// Sets if a chunk is active, spawning it with [`spawn_chunk`] or
+    /// unloading it with [`unload_chunk`]. Its tiles are kept either way.
+    ///
+    /// [`spawn_chunk`]: Tilemap::spawn_chunk
+    /// [`unload_chunk`]: Tilemap::unload_chunk
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`spawn_chunk`] and [`unload_chunk`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.set_chunk_active((0, 0), true).is_ok());
+    /// assert!(tilemap.set_chunk_active((0, 0), false).is_ok());
+    /// assert!(tilemap.set_chunk_active((1, 0), false).is_err());
+    /// ```
+    pub fn set_chunk_active<P: Into<Point2>>(
+        &mut self,
+        point: P,
+        active: bool,
+    ) -> TilemapResult<()> {
+        if active {
+            self.spawn_chunk(point)
+        } else {
+            self.unload_chunk(point)
+        }
+    }
+
+    /// Unloads a chunk, keeping its tiles.
+    ///
+    /// # Migration
+    ///
+    /// This has been renamed to [`unload_chunk`], which does the same. Use
+    /// [`delete_chunk`] to also drop the tiles of the chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`unload_chunk`].
+    ///
+    /// [`unload_chunk`]: Tilemap::unload_chunk
+    /// [`delete_chunk`]: Tilemap::delete_chunk
+    #[deprecated(since = "0.5.0", note = "Please use `unload_chunk` instead")]
+    pub fn despawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        self.unload_chunk(point)
+    }
+
+    /// Deletes a chunk along with its tiles.
+    ///
+    /// # Migration
+    ///
+    /// This has been renamed to [`delete_chunk`], which does the same. Use
+    /// [`unload_chunk`] instead if the tiles of the chunk should be kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`delete_chunk`].
+    ///
+    /// [`delete_chunk`]: Tilemap::delete_chunk
+    /// [`unload_chunk`]: Tilemap::unload_chunk
+    #[deprecated(since = "0.5.0", note = "Please use `delete_chunk` instead")]
+    pub fn remove_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
+        self.delete_chunk(point)
+    }
+
+    /// Marks a chunk as no longer spawned and sends the event to despawn or
+    /// delete it.
+    ///
+    /// The tiles of the chunk are handed out to be persisted if unloaded
+    /// chunks are persisted, where `removed` tells if the chunk is deleted.
+    fn send_unloaded(&mut self, point: Point2, removed: bool) -> TilemapResult<()> {
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_centered_point(point)?;
+        }
+        if !self.chunks.contains_key(&point) {
+            return Err(ErrorKind::MissingChunk.into());
+        }
+
+        self.spawned.remove(&(point.x, point.y));
+        self.revealing_chunks.remove(&point);
+        self.collider_queue.retain(|build| build.point != point);
+
+        if removed {
+            self.chunk_events.push(TilemapChunkEvent::Deleted { point });
+        } else {
+            self.chunk_events
+                .push(TilemapChunkEvent::Despawned { point });
+        }
+        if self.auto_flags.contains(AutoFlags::PERSIST_UNLOADED_CHUNKS) {
+            if let Some(bytes) = self.chunk_bytes(point) {
+                self.unloaded_chunks.push(UnloadedChunk {
+                    point,
+                    bytes,
+                    removed,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes a tile point and changes it into a chunk point.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let tile_point = (15, 15);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((0, 0), chunk_point);
+    ///
+    /// let tile_point = (16, 16);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((1, 1), chunk_point);
+    ///
+    /// let tile_point = (-16, -16);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((-0, -0), chunk_point);
+    ///
+    /// let tile_point = (-17, -17);
+    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
+    /// assert_eq!((-1, -1), chunk_point);
+    /// ```
+    pub fn point_to_chunk_point<P: Into<Point2>>(&self, point: P) -> (Coord, Coord) {
+        let point: Point2 = point.into();
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let x = (point.x + width / 2).div_euclid(width);
+        let y = (point.y + height / 2).div_euclid(height);
+        (x, y)
+    }
+
+    /// Returns a hash of a tile point and a salt, for picking variations of
+    /// tiles by their position.
+    ///
+    /// The hash only depends on its arguments and is the same on every run
+    /// and platform, so maps decorated with it look the same every session
+    /// and for every player. Different salts give unrelated hashes for the
+    /// same point, such as one for the variant of a grass tile and one for if
+    /// a flower grows on it. The [`worldgen`] noise is built on the same hash.
+    ///
+    /// [`worldgen`]: crate::worldgen
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // One of 4 grass sprites for every tile.
+    /// let grass = |point: (i32, i32)| Tilemap::position_hash(point, 1) % 4;
+    ///
+    /// assert_eq!(grass((12, -7)), grass((12, -7)));
+    /// assert!(grass((12, -7)) < 4);
+    /// ```
+    pub fn position_hash<P: Into<Point2>>(point: P, salt: u64) -> u64 {
+        let point: Point2 = point.into();
+        // The SplitMix64 finalizer over the salt and the point.
+        let mut hash = salt
+            ^ (point.x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            ^ (point.y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+        hash ^= hash >> 30;
+        hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash ^= hash >> 27;
+        hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^ (hash >> 31)
+    }
+
+    /// Sorts tiles into the chunks they belong to.
+    fn sort_tiles_to_chunks<P, I>(
+        &mut self,
+        tiles: I,
+    ) -> TilemapResult<HashMap<Point2, Vec<Tile<Point3>>>>
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = Tile<P>>,
+    {
+        let mut chunk_map: HashMap<Point2, Vec<Tile<Point3>>> = HashMap::default();
+        for tile in tiles.into_iter() {
+            let (chunk_point, chunk_tile) = self.sort_tile(tile.map_point(Into::into))?;
+            chunk_map.entry(chunk_point).or_default().push(chunk_tile);
+        }
+        Ok(chunk_map)
+    }
+
+    /// Checks that a tile can be inserted and returns the point of its chunk
+    /// along with the tile at its point within that chunk.
+    fn sort_tile(&mut self, tile: Tile<Point3>) -> TilemapResult<(Point2, Tile<Point3>)> {
+        let (chunk_point, chunk_tile) = Tilemap::point_to_chunk_tile(self.chunk_dimensions, tile);
+        // A z depth past the chunk would encode to a tile of another layer.
+        self.chunk_dimensions
+            .check_encodable_point(chunk_tile.point)?;
+        self.check_unlocked(tile.point.into())?;
+        self.add_missing_layer(tile.sprite_order)?;
+        Ok((chunk_point, chunk_tile))
+    }
+
+    /// Adds a default layer at a sprite order if there is room for one and it
+    /// does not exist yet.
+    fn add_missing_layer(&mut self, sprite_order: usize) -> TilemapResult<()> {
+        if let Some(layer) = self.layers.get(sprite_order) {
+            if layer.as_ref().is_none() {
+                self.add_layer(TilemapLayer::default(), sprite_order)?;
+            }
+            Ok(())
+        } else {
+            Err(ErrorKind::LayerDoesNotExist(sprite_order).into())
+        }
+    }
+
+    /// Takes a global point and returns the point of its chunk along with its
+    /// index within that chunk.
+    fn chunk_index(chunk_dimensions: Dimension3, point: Point3) -> (Point2, usize) {
+        let tile = Tile {
+            point,
+            ..Default::default()
+        };
+        let (chunk_point, tile) = Tilemap::point_to_chunk_tile(chunk_dimensions, tile);
+        (
+            chunk_point,
+            chunk_dimensions.encode_point_unchecked(tile.point),
+        )
+    }
+
+    /// Takes a tile with a global point and returns the point of its chunk
+    /// along with the tile at its point within that chunk.
+    fn point_to_chunk_tile(
+        chunk_dimensions: Dimension3,
+        tile: Tile<Point3>,
+    ) -> (Point2, Tile<Point3>) {
+        let width = chunk_dimensions.width as Coord;
+        let height = chunk_dimensions.height as Coord;
+        let global_tile_point = tile.point;
+        let chunk_point = Point2::new(
+            (global_tile_point.x + width / 2).div_euclid(width),
+            (global_tile_point.y + height / 2).div_euclid(height),
+        );
+        let chunk_tile = tile.map_point(|point| {
+            Point3::new(
+                point.x - (width * chunk_point.x) + (width / 2),
+                point.y - (height * chunk_point.y) + (height / 2),
+                point.z,
+            )
+        });
+        (chunk_point, chunk_tile)
+    }
+
+    /// Makes sure the chunk which tiles are inserted into exists, creating it
+    /// if `AUTO_CHUNK` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk does not exist and may not be created.
+    fn insert_tiles_chunk(&mut self, chunk_point: Point2) -> TilemapResult<()> {
+        if self.chunks.contains_key(&chunk_point) {
+            return Ok(());
+        }
+        if !self.auto_flags.contains(AutoFlags::AUTO_CHUNK) {
+            return Err(ErrorKind::MissingChunk.into());
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.push(RecordedEvent::InsertChunk { point: chunk_point });
+        }
+        self.chunk_events
+            .push(TilemapChunkEvent::NeedsPopulation { point: chunk_point });
+        let layer_kinds = self
+            .layers
+            .iter()
+            .map(|x| x.and_then(|y| Some(y.kind)))
+            .collect::<Vec<Option<LayerKind>>>();
+        self.chunks.insert(
+            chunk_point,
+            Chunk::new(chunk_point, &layer_kinds, self.chunk_dimensions),
+        );
+        Ok(())
+    }
+
+    /// Validates, records and sends the modified event for tiles which were
+    /// written to a chunk.
+    fn insert_tiles_finish(&mut self, chunk_point: Point2, tiles: &[Tile<Point3>]) {
+        let chunk_dimensions = self.chunk_dimensions;
+        let to_point = |tile_point| {
+            Tilemap::chunk_tile_point_to_point(chunk_dimensions, chunk_point, tile_point)
+        };
+
+        if self.auto_flags.contains(AutoFlags::VALIDATE_SPRITE_INDICES) {
+            self.unvalidated_tiles
+                .extend(tiles.iter().map(|tile| UnvalidatedTile {
+                    point: to_point(tile.point),
+                    sprite_order: tile.sprite_order,
+                    atlas: tile.atlas,
+                    sprite_index: tile.sprite_index,
+                    attempts: 0,
+                }));
+        }
+
+        if let Some(recording) = &mut self.recording {
+            let tiles = tiles
+                .iter()
+                .map(|tile| (to_point(tile.point), tile.sprite_order, tile.to_raw_tile()))
+                .collect();
+            recording.push(RecordedEvent::InsertTiles { tiles });
+        }
+
+        if self
+            .chunks
+            .get(&chunk_point)
+            .is_some_and(|chunk| chunk.has_mesh())
+        {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+    }
+
+    /// Sets many tiles, creating new chunks if needed.
+    ///
+    /// If setting a single tile is more preferable, then use the [`insert_tile`]
+    /// method instead.
+    ///
+    /// If the chunk does not yet exist, it will create a new one automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given coordinate or index is out of bounds, the
+    /// layer or chunk does not exist. If either the layer or chunk error occurs
+    /// then creating what is missing will resolve it. No tiles are set if a
+    /// tile is locked, see [`lock_region`].
+    ///
+    /// [`lock_region`]: Tilemap::lock_region
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let mut tiles = vec![
+    ///     Tile { point: (1, 1), sprite_index: 0, ..Default::default() },
+    ///     Tile { point: (2, 2), sprite_index: 1, ..Default::default() },
+    ///     Tile { point: (3, 3), sprite_index: 2, ..Default::default() },
+    /// ];
+    ///
+    /// // Set multiple tiles and unwrap the result
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((1, 1), 0), Some(&RawTile { index: 0, color: Color::WHITE, ..Default::default() }));
+    /// assert_eq!(tilemap.get_tile((2, 2), 0), Some(&RawTile { index: 1, color: Color::WHITE, ..Default::default() }));
+    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 2, color: Color::WHITE, ..Default::default() }));
+    /// assert_eq!(tilemap.get_tile((4, 4), 0), None);
+    /// ```
+    ///
+    /// [`insert_tile`]: Tilemap::insert_tile
+    pub fn insert_tiles<P, I>(&mut self, tiles: I) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = Tile<P>>,
+    {
+        let chunk_map = self.sort_tiles_to_chunks(tiles)?;
+        for (chunk_point, tiles) in chunk_map.into_iter() {
+            if let Err(error) = self.insert_chunk_tiles(chunk_point, &tiles) {
+                self.finish_history_step();
+                return Err(error);
+            }
+        }
+        self.finish_history_step();
+
+        Ok(())
+    }
+
+    /// Sets many tiles like [`insert_tiles`], skipping the tiles which can not
+    /// be set instead of failing.
+    ///
+    /// Every tile which can be set is set, and the others are returned in the
+    /// report along with the error they would have caused, such as when
+    /// their sprite layer does not exist, their z depth is out of bounds,
+    /// they are locked or their chunk does not exist and chunks are not
+    /// automatically created. This suits generators which place tiles at the
+    /// edges of a map without checking every point.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point3, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let tiles = vec![
+    ///     Tile { point: (1, 1), ..Default::default() },
+    ///     // The chunk of this tile does not exist.
+    ///     Tile { point: (100, 0), ..Default::default() },
+    ///     // There is no sprite layer this high.
+    ///     Tile { point: (2, 2), sprite_order: 100, ..Default::default() },
+    /// ];
+    /// let report = tilemap.insert_tiles_checked(tiles);
+    ///
+    /// assert_eq!(report.inserted, 1);
+    /// assert_eq!(report.rejected.len(), 2);
+    /// assert!(report.rejected.iter().any(|(tile, _)| tile.point == Point3::new(100, 0, 0)));
+    /// assert!(tilemap.get_tile((1, 1), 0).is_some());
+    /// ```
+    pub fn insert_tiles_checked<P, I>(&mut self, tiles: I) -> TileInsertReport
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = Tile<P>>,
+    {
+        let mut report = TileInsertReport::default();
+        let mut chunk_map: HashMap<Point2, Vec<(Tile<Point3>, Tile<Point3>)>> = HashMap::default();
+        for tile in tiles.into_iter() {
+            let tile: Tile<Point3> = tile.map_point(Into::into);
+            match self.sort_tile(tile) {
+                Ok((chunk_point, chunk_tile)) => chunk_map
+                    .entry(chunk_point)
+                    .or_default()
+                    .push((tile, chunk_tile)),
+                Err(error) => report.rejected.push((tile, error)),
+            }
+        }
+        for (chunk_point, tiles) in chunk_map.into_iter() {
+            let (tiles, chunk_tiles): (Vec<Tile<Point3>>, Vec<Tile<Point3>>) =
+                tiles.into_iter().unzip();
+            // Chunks are only ever rejected as a whole, before any tile is set.
+            match self.insert_chunk_tiles(chunk_point, &chunk_tiles) {
+                Ok(()) => report.inserted += chunk_tiles.len(),
+                Err(error) => report
+                    .rejected
+                    .extend(tiles.into_iter().map(|tile| (tile, error.clone()))),
+            }
+        }
+        self.finish_history_step();
+
+        report
+    }
+
+    /// Converts the pixels of an image into tiles, such as a biome or height
+    /// map made by a world generator.
+    ///
+    /// The bottom left pixel is put at the origin, with the top row of the
+    /// image at the highest row of tiles. The mapping turns the color of
+    /// every pixel into a tile or none, where the point of the tile is
+    /// relative to the pixel, so the default point puts it on the pixel. The
+    /// pixels are converted and inserted one chunk at a time, so large images
+    /// do not need all of their tiles at once.
+    ///
+    /// The image must be 8 bit RGBA or BGRA, where sRGB formats give colors
+    /// in sRGB and the others linear colors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image is in another format, or if a chunk is
+    /// missing and chunks are not automatically created, a tile is locked or
+    /// a sprite layer does not exist. Tiles of the chunks before the error
+    /// stay inserted.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::{prelude::*, texture::{Extent3d, TextureDimension, TextureFormat}};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, point::Point3};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// // A 2 by 1 image of a blue and a green pixel.
+    /// let image = Texture::new(
+    ///     Extent3d::new(2, 1, 1),
+    ///     TextureDimension::D2,
+    ///     vec![0, 0, 255, 255, 0, 255, 0, 255],
+    ///     TextureFormat::Rgba8UnormSrgb,
+    /// );
+    ///
+    /// tilemap.insert_image((10, 10), &image, |color: Color| {
+    ///     let sprite_index = if color.b() > 0.5 { 1 } else { 2 };
+    ///     Some(Tile { point: Point3::default(), sprite_index, ..Default::default() })
+    /// }).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((10, 10), 0).map(|tile| tile.index), Some(1));
+    /// assert_eq!(tilemap.get_tile((11, 10), 0).map(|tile| tile.index), Some(2));
+    /// ```
+    pub fn insert_image<P, F>(
+        &mut self,
+        origin: P,
+        image: &Texture,
+        mut mapping: F,
+    ) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        F: FnMut(Color) -> Option<Tile<Point3>>,
+    {
+        let origin: Point2 = origin.into();
+        let (bgra, srgb) = match image.format {
+            TextureFormat::Rgba8Unorm => (false, false),
+            TextureFormat::Rgba8UnormSrgb => (false, true),
+            TextureFormat::Bgra8Unorm => (true, false),
+            TextureFormat::Bgra8UnormSrgb => (true, true),
+            _ => return Err(ErrorKind::UnsupportedImage.into()),
+        };
+        let width = image.size.width as Coord;
+        let height = image.size.height as Coord;
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        if image.data.len() < (width * height * 4) as usize {
+            return Err(ErrorKind::UnsupportedImage.into());
+        }
+
+        let pixel = |x: Coord, y: Coord| -> Color {
+            // The rows of the image run from the top down.
+            let offset = (((height - 1 - y) * width + x) * 4) as usize;
+            let (r, g, b, a) = match image.data.get(offset..offset + 4) {
+                Some(&[b, g, r, a]) if bgra => (r, g, b, a),
+                Some(&[r, g, b, a]) => (r, g, b, a),
+                _ => (0, 0, 0, 0),
+            };
+            if srgb {
+                Color::rgba_u8(r, g, b, a)
+            } else {
+                Color::rgba_linear(
+                    f32::from(r) / 255.0,
+                    f32::from(g) / 255.0,
+                    f32::from(b) / 255.0,
+                    f32::from(a) / 255.0,
+                )
+            }
+        };
+
+        let chunk_width = self.chunk_dimensions.width as Coord;
+        let chunk_height = self.chunk_dimensions.height as Coord;
+        let min: Point2 = self.point_to_chunk_point(origin).into();
+        let max: Point2 = self
+            .point_to_chunk_point((origin.x + width - 1, origin.y + height - 1))
+            .into();
+        for chunk_y in min.y..=max.y {
+            for chunk_x in min.x..=max.x {
+                let chunk_min: Point2 = Tilemap::chunk_tile_point_to_point(
+                    self.chunk_dimensions,
+                    Point2::new(chunk_x, chunk_y),
+                    Point3::new(0, 0, 0),
+                )
+                .into();
+                let mut tiles = Vec::new();
+                for y in
+                    chunk_min.y.max(origin.y)..(chunk_min.y + chunk_height).min(origin.y + height)
+                {
+                    for x in
+                        chunk_min.x.max(origin.x)..(chunk_min.x + chunk_width).min(origin.x + width)
+                    {
+                        if let Some(tile) = mapping(pixel(x - origin.x, y - origin.y)) {
+                            tiles.push(
+                                tile.map_point(|point| {
+                                    Point3::new(point.x + x, point.y + y, point.z)
+                                }),
+                            );
+                        }
+                    }
+                }
+                let chunk_map = match self.sort_tiles_to_chunks(tiles) {
+                    Ok(chunk_map) => chunk_map,
+                    Err(error) => {
+                        self.finish_history_step();
+                        return Err(error);
+                    }
+                };
+                for (chunk_point, tiles) in chunk_map.into_iter() {
+                    if let Err(error) = self.insert_chunk_tiles(chunk_point, &tiles) {
+                        self.finish_history_step();
+                        return Err(error);
+                    }
+                }
+            }
+        }
+        self.finish_history_step();
+
+        Ok(())
+    }
+
+    /// Renders the tiles of a region into an image, such as for screenshots,
+    /// sharing maps or baking textures of far away chunks.
+    ///
+    /// The region is from `min` to `max` including both, and every tile is
+    /// the size of the texture dimensions times the scale in pixels. The
+    /// sprites of the layers are drawn from the bottom up like the chunk
+    /// shaders draw them, with the tint, flips, rotation, scale and offset of
+    /// every tile and the theme of the tilemap. Sprites are sampled at the
+    /// nearest pixel and parts of sprites which overhang the region are cut
+    /// off.
+    ///
+    /// The texture atlas and its texture must be of the main texture atlas of
+    /// the tilemap, whose texture must be 8 bit RGBA or BGRA. The image has the
+    /// same format. Tiles of other texture atlases are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the texture is in another format.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_render::{prelude::*, texture::{Extent3d, TextureDimension, TextureFormat}};
+    /// use bevy_sprite::{prelude::*, Rect};
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // A texture atlas of a red and a blue sprite of 1 by 1 pixels.
+    /// let texture = Texture::new(
+    ///     Extent3d::new(2, 1, 1),
+    ///     TextureDimension::D2,
+    ///     vec![255, 0, 0, 255, 0, 0, 255, 255],
+    ///     TextureFormat::Rgba8UnormSrgb,
+    /// );
+    /// // In production use a strong handle from an actual source.
+    /// let texture_handle = Handle::weak(HandleId::random::<Texture>());
+    /// let mut texture_atlas = TextureAtlas::new_empty(texture_handle, Vec2::new(2.0, 1.0));
+    /// texture_atlas.add_texture(Rect { min: Vec2::new(0.0, 0.0), max: Vec2::new(1.0, 1.0) });
+    /// texture_atlas.add_texture(Rect { min: Vec2::new(1.0, 0.0), max: Vec2::new(2.0, 1.0) });
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 1, 1);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 0, ..Default::default() }).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 0), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// // Every tile is 2 by 2 pixels at a scale of 2.
+    /// let image = tilemap.render_to_image((0, 0), (1, 0), 2.0, &texture_atlas, &texture).unwrap();
+    ///
+    /// assert_eq!(image.size, Extent3d::new(4, 2, 1));
+    /// assert_eq!(&image.data[..8], &[255, 0, 0, 255, 255, 0, 0, 255]);
+    /// assert_eq!(&image.data[8..16], &[0, 0, 255, 255, 0, 0, 255, 255]);
+    /// ```
+    pub fn render_to_image<P: Into<Point2>>(
+        &self,
+        min: P,
+        max: P,
+        scale: f32,
+        texture_atlas: &TextureAtlas,
+        texture: &Texture,
+    ) -> TilemapResult<Texture> {
+        self.render_region(min.into(), max.into(), scale, texture_atlas, texture, None)
+    }
+
+    /// Bakes the static layers of a chunk into a texture the size of the
+    /// chunk, returning the baked layers along with it, or `None` if the
+    /// chunk has no static layers or the texture is in another format.
+    pub(crate) fn bake_chunk_layers(
+        &self,
+        point: Point2,
+        texture_atlas: &TextureAtlas,
+        texture: &Texture,
+    ) -> Option<(HashSet<usize>, Texture)> {
+        let chunk = self.chunks.get(&point)?;
+        let baked_layers = chunk.sprite_layers_in(&self.static_layers);
+        if baked_layers.is_empty() {
+            return None;
+        }
+        let min =
+            Tilemap::chunk_tile_point_to_point(self.chunk_dimensions, point, Point3::new(0, 0, 0));
+        let max = Point2::new(
+            min.x + self.chunk_dimensions.width as Coord - 1,
+            min.y + self.chunk_dimensions.height as Coord - 1,
+        );
+        let image = self
+            .render_region(
+                Point2::new(min.x, min.y),
+                max,
+                1.0,
+                texture_atlas,
+                texture,
+                Some(&baked_layers),
+            )
+            .ok()?;
+        Some((baked_layers, image))
+    }
+
+    /// Renders the tiles of a region into an image, only drawing the tiles
+    /// of some sprite layers if given.
+    fn render_region(
+        &self,
+        min: Point2,
+        max: Point2,
+        scale: f32,
+        texture_atlas: &TextureAtlas,
+        texture: &Texture,
+        sprite_orders: Option<&HashSet<usize>>,
+    ) -> TilemapResult<Texture> {
+        let bgra = match texture.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => return Err(ErrorKind::UnsupportedImage.into()),
+        };
+        let (a, b) = (min, max);
+        let min = Point2::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point2::new(a.x.max(b.x), a.y.max(b.y));
+        let scale = scale.max(0.0);
+        let tile_size = Vec2::new(
+            self.texture_dimensions.width as f32,
+            self.texture_dimensions.height as f32,
+        );
+        let width = ((max.x - min.x + 1) as f32 * tile_size.x * scale).ceil() as usize;
+        let height = ((max.y - min.y + 1) as f32 * tile_size.y * scale).ceil() as usize;
+        let mut canvas = Canvas::new(width, height, scale, bgra);
+
+        let chunk_width = self.chunk_dimensions.width as usize;
+        let min_chunk: Point2 = self.point_to_chunk_point(min).into();
+        let max_chunk: Point2 = self.point_to_chunk_point(max).into();
+        let mut tiles = Vec::new();
+        for chunk_y in min_chunk.y..=max_chunk.y {
+            for chunk_x in min_chunk.x..=max_chunk.x {
+                let chunk_point = Point2::new(chunk_x, chunk_y);
+                let chunk = match self.chunks.get(&chunk_point) {
+                    Some(chunk) => chunk,
+                    None => continue,
+                };
+                for (z_depth, sprite_order, index, tile) in chunk.raw_tiles() {
+                    let drawn = sprite_orders
+                        .is_none_or(|sprite_orders| sprite_orders.contains(&sprite_order));
+                    if tile.atlas != 0 || !drawn {
+                        continue;
+                    }
+                    let point = Tilemap::chunk_tile_point_to_point(
+                        self.chunk_dimensions,
+                        chunk_point,
+                        Point3::new(
+                            (index % chunk_width) as Coord,
+                            (index / chunk_width) as Coord,
+                            0,
+                        ),
+                    );
+                    if point.x < min.x || point.y < min.y || point.x > max.x || point.y > max.y {
+                        continue;
+                    }
+                    // The rows of y-sorted layers are drawn from the top down.
+                    let row = if self.y_sorted_layers.contains(&sprite_order) {
+                        -point.y
+                    } else {
+                        0
+                    };
+                    let order = (
+                        z_depth,
+                        self.layer_sort_key(sprite_order),
+                        sprite_order,
+                        row,
+                    );
+                    tiles.push((order, Point2::new(point.x, point.y), tile));
+                }
+            }
+        }
+        tiles.sort_by_key(|(order, _point, _tile)| *order);
+
+        for (_order, point, tile) in tiles {
+            let rect = match texture_atlas
+                .textures
+                .get(self.themed_sprite_index(tile.index))
+            {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let sprite_size = rect.max - rect.min;
+            let cell = Vec2::new((point.x - min.x) as f32, (point.y - min.y) as f32) * tile_size;
+            // The sprite is scaled from the bottom center of its cell.
+            let sprite_min =
+                cell + Vec2::new((1.0 - tile.scale.x) * 0.5 * sprite_size.x, 0.0) + tile.offset;
+            let sprite_max = sprite_min + sprite_size * tile.scale;
+            canvas.draw_sprite(texture, rect, sprite_min, sprite_max, tile);
+        }
+
+        Ok(canvas.into_texture(texture.format))
+    }
+
+    /// Sets tiles with points local to a chunk, creating the chunk if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk does not exist and may not be created.
+    fn insert_chunk_tiles(
+        &mut self,
+        chunk_point: Point2,
+        tiles: &[Tile<Point3>],
+    ) -> TilemapResult<()> {
+        self.insert_tiles_chunk(chunk_point)?;
+        let chunk_dimensions = self.chunk_dimensions;
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(c) => c,
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+
+        for tile in tiles.iter() {
+            let index = chunk_dimensions.encode_point_unchecked(tile.point);
+            if let Some(history) = &mut self.history {
+                history.push(TileChange {
+                    point: Tilemap::chunk_tile_point_to_point(
+                        chunk_dimensions,
+                        chunk_point,
+                        tile.point,
+                    ),
+                    sprite_order: tile.sprite_order,
+                    before: chunk
+                        .get_tile(index, tile.sprite_order, tile.point.z as usize)
+                        .copied(),
+                    after: Some(tile.to_raw_tile()),
+                });
+            }
+            chunk.set_tile(index, *tile);
+        }
+
+        self.insert_tiles_finish(chunk_point, tiles);
+        Ok(())
+    }
+
+    /// Sets many tiles like [`insert_tiles`], splitting the work across a task
+    /// pool.
+    ///
+    /// Sorting the tiles into their chunks is split into a batch per thread
+    /// and every chunk is then written to in its own task, since chunks are
+    /// independent of each other. This pays off for large batches of tiles
+    /// spread over many chunks, such as when generating a map. For a handful
+    /// of tiles the overhead of the tasks outweighs the gain and
+    /// [`insert_tiles`] should be used instead.
+    ///
+    /// Within a system the [`ComputeTaskPool`] resource can be used as the task
+    /// pool.
+    ///
+    /// Unlike [`insert_tiles`], the sprite layers and chunks of all the tiles
+    /// are checked before any tile is set, so no tiles are set if an error is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a layer of the tiles does not exist, a chunk does
+    /// not exist and `AUTO_CHUNK` is not set, or a tile is locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tasks::TaskPool;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let task_pool = TaskPool::new();
+    /// let tiles = (-100..100).flat_map(|x| {
+    ///     (-100..100).map(move |y| Tile { point: (x, y), sprite_index: 1, ..Default::default() })
+    /// });
+    ///
+    /// tilemap.insert_tiles_parallel(&task_pool, tiles).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((-100, 99), 0), Some(&RawTile { index: 1, color: Color::WHITE, ..Default::default() }));
+    /// ```
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`ComputeTaskPool`]: bevy_tasks::ComputeTaskPool
+    pub fn insert_tiles_parallel<P, I>(
+        &mut self,
+        task_pool: &TaskPool,
+        tiles: I,
+    ) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = Tile<P>>,
+    {
+        let tiles: Vec<Tile<Point3>> = tiles
+            .into_iter()
+            .map(|tile| tile.map_point(Into::into))
+            .collect();
+        if tiles.is_empty() {
+            return Ok(());
+        }
+        for tile in tiles.iter() {
+            let (_chunk_point, chunk_tile) =
+                Tilemap::point_to_chunk_tile(self.chunk_dimensions, *tile);
+            self.chunk_dimensions
+                .check_encodable_point(chunk_tile.point)?;
+            self.check_unlocked(tile.point.into())?;
+        }
+
+        let mut sprite_orders: Vec<usize> = tiles.iter().map(|tile| tile.sprite_order).collect();
+        sprite_orders.sort_unstable();
+        sprite_orders.dedup();
+        for sprite_order in sprite_orders {
+            self.add_missing_layer(sprite_order)?;
+        }
+
+        // Batches are returned in the order they were spawned in, so later
+        // tiles at the same point still win as they do in `insert_tiles`.
+        let chunk_dimensions = self.chunk_dimensions;
+        let batch_size = (tiles.len() / task_pool.thread_num().max(1)).max(1);
+        let batches = task_pool.scope(|scope| {
+            for batch in tiles.chunks(batch_size) {
+                scope.spawn(async move {
+                    let mut chunk_map: HashMap<Point2, Vec<Tile<Point3>>> = HashMap::default();
+                    for tile in batch.iter() {
+                        let (chunk_point, chunk_tile) =
+                            Tilemap::point_to_chunk_tile(chunk_dimensions, *tile);
+                        chunk_map.entry(chunk_point).or_default().push(chunk_tile);
+                    }
+                    chunk_map
+                });
+            }
+        });
+        let mut chunk_map: HashMap<Point2, Vec<Tile<Point3>>> = HashMap::default();
+        for batch in batches.into_iter() {
+            for (chunk_point, mut tiles) in batch.into_iter() {
+                chunk_map.entry(chunk_point).or_default().append(&mut tiles);
+            }
+        }
+
+        if !self.auto_flags.contains(AutoFlags::AUTO_CHUNK)
+            && chunk_map
+                .keys()
+                .any(|chunk_point| !self.chunks.contains_key(chunk_point))
+        {
+            return Err(ErrorKind::MissingChunk.into());
+        }
+        for chunk_point in chunk_map.keys() {
+            self.insert_tiles_chunk(*chunk_point)?;
+        }
+
+        if let Some(history) = &mut self.history {
+            for (chunk_point, tiles) in chunk_map.iter() {
+                let chunk = match self.chunks.get(chunk_point) {
+                    Some(chunk) => chunk,
+                    None => continue,
+                };
+                for tile in tiles.iter() {
+                    let index = chunk_dimensions.encode_point_unchecked(tile.point);
+                    history.push(TileChange {
+                        point: Tilemap::chunk_tile_point_to_point(
+                            chunk_dimensions,
+                            *chunk_point,
+                            tile.point,
+                        ),
+                        sprite_order: tile.sprite_order,
+                        before: chunk
+                            .get_tile(index, tile.sprite_order, tile.point.z as usize)
+                            .copied(),
+                        after: Some(tile.to_raw_tile()),
+                    });
+                }
+            }
+        }
+
+        let chunk_map = &chunk_map;
+        let chunks = &mut self.chunks;
+        task_pool.scope(|scope| {
+            for (chunk_point, chunk) in chunks.iter_mut() {
+                if let Some(tiles) = chunk_map.get(chunk_point) {
+                    scope.spawn(async move {
+                        for tile in tiles.iter() {
+                            let index = chunk_dimensions.encode_point_unchecked(tile.point);
+                            chunk.set_tile(index, *tile);
+                        }
+                    });
+                }
+            }
+        });
+
+        for (chunk_point, tiles) in chunk_map.iter() {
+            self.insert_tiles_finish(*chunk_point, tiles);
+        }
+        self.finish_history_step();
+
+        Ok(())
+    }
+
+    /// Sets a single tile at a coordinate position, creating a chunk if necessary.
+    ///
+    /// If you are setting more than one tile at a time, it is highly
+    /// recommended not to run this method! Instead use
+    /// [`insert_tiles`]. Every single tile that is created creates a new
+    /// event. With bulk tiles, it creates 1 event for all.
+    ///
+    /// If the chunk does not yet exist, it will create a new one automatically.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let point = (9, 3);
+    /// let sprite_index = 3;
+    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    ///
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE, ..Default::default() }));
+    ///
+    /// // The chunks are only 1 tile deep.
+    /// let tile = Tile { point: (9, 3, 1), sprite_index, ..Default::default() };
+    /// assert!(tilemap.insert_tile(tile).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given coordinate or index is out of bounds,
+    /// such as a z depth which is negative or past the depth of the chunks.
+    pub fn insert_tile<P: Into<Point3>>(&mut self, tile: Tile<P>) -> TilemapResult<()> {
+        let tiles = vec![tile];
+        self.insert_tiles(tiles)
+    }
+
+    /// Sets many tiles on the layer with a name, whatever their sprite
+    /// orders are, creating new chunks if needed.
+    ///
+    /// See [`insert_tiles`] and [`set_layer_name`] for more information.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`set_layer_name`]: Tilemap::set_layer_name
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 2)
+    ///     .layer_name(2, "collision")
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let tiles = vec![Tile { point: (1, 1), sprite_index: 4, ..Default::default() }];
+    /// assert!(tilemap.insert_tiles_in_layer("collision", tiles).is_ok());
+    /// assert_eq!(tilemap.get_tile_in_layer((1, 1), "collision").map(|tile| tile.index), Some(4));
+    /// assert_eq!(tilemap.get_tile((1, 1), 0), None);
+    ///
+    /// assert!(tilemap.clear_tile_in_layer((1, 1), "collision").is_ok());
+    /// assert_eq!(tilemap.get_tile_in_layer((1, 1), "collision"), None);
+    /// assert!(tilemap.clear_tile_in_layer((1, 1), "decor").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no layer has the name, or for the same reasons as
+    /// [`insert_tiles`].
+    pub fn insert_tiles_in_layer<P, I>(&mut self, name: &str, tiles: I) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = Tile<P>>,
+    {
+        let sprite_order = self.named_layer_index(name)?;
+        self.insert_tiles(tiles.into_iter().map(|tile| Tile {
+            sprite_order,
+            ..tile
+        }))
+    }
+
+    /// Clears the tiles at the specified points from the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// let mut tiles = vec![
+    ///     Tile { point: (1, 1, 0), ..Default::default() },
+    ///     Tile { point: (2, 2, 0), ..Default::default() },
+    ///     Tile { point: (3, 3, 0), ..Default::default() },
+    /// ];
+    ///
+    /// // Set multiple tiles and unwrap the result
+    /// assert!(tilemap.insert_tiles(tiles.clone()).is_ok());
+    ///
+    /// // Then later on... Do note that if this done in the same frame, the
+    /// // tiles will not even exist at all.
+    /// let mut to_remove = vec![
+    ///     ((1, 1), 0),
+    ///     ((2, 2), 0),
+    /// ];
+    ///
+    /// tilemap.clear_tiles(to_remove).unwrap();
+    /// assert_eq!(tilemap.get_tile((1, 1, 0), 0), None);
+    /// assert_eq!(tilemap.get_tile((2, 2, 0), 0), None);
+    /// assert_eq!(tilemap.get_tile((3, 3, 0), 0), Some(&RawTile { index: 0, color: Color::WHITE, ..Default::default() } ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error can occure if the point is outside of the tilemap. This can
+    /// only happen if the tilemap has dimensions. No tiles are cleared if a
+    /// tile is locked, see [`lock_region`].
+    ///
+    /// [`lock_region`]: Tilemap::lock_region
+    pub fn clear_tiles<P, I>(&mut self, points: I) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+        I: IntoIterator<Item = (P, usize)>,
+    {
+        let mut tiles = Vec::new();
+        for (point, sprite_order) in points {
+            tiles.push(Tile {
+                point: point.into(),
+                sprite_index: 0,
+                sprite_order,
+                tint: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                ..Default::default()
+            });
+        }
+        let chunk_map = self.sort_tiles_to_chunks(tiles)?;
+        for (chunk_point, tiles) in chunk_map.into_iter() {
+            if let Err(error) = self.clear_chunk_tiles(chunk_point, &tiles) {
+                self.finish_history_step();
+                return Err(error);
+            }
+        }
+        self.finish_history_step();
+
+        Ok(())
+    }
+
+    /// Clears every tile of every chunk, keeping the chunks.
+    ///
+    /// The tiles are cleared in one go with [`clear_tiles`], so every chunk
+    /// is only modified once and the clearing can be undone.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`clear_tiles`]. No tiles are cleared if a
+    /// tile is locked.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// assert!(tilemap.insert_chunk((1, 0)).is_ok());
+    /// tilemap.fill_rect((-4, 0), (20, 0), 0, 0, RawTile::default()).unwrap();
+    ///
+    /// assert!(tilemap.clear_all_tiles().is_ok());
+    /// assert_eq!(tilemap.get_tile((-4, 0), 0), None);
+    /// assert_eq!(tilemap.get_tile((20, 0), 0), None);
+    /// assert!(tilemap.contains_chunk((1, 0)));
+    /// ```
+    ///
+    /// [`clear_tiles`]: Tilemap::clear_tiles
+    pub fn clear_all_tiles(&mut self) -> TilemapResult<()> {
+        let chunk_dimensions = self.chunk_dimensions;
+        let mut points = Vec::new();
+        for (chunk_point, chunk) in self.chunks.iter() {
+            for (z_depth, sprite_order, index, _tile) in chunk.raw_tiles() {
+                let point =
+                    Tilemap::chunk_index_to_point(chunk_dimensions, *chunk_point, z_depth, index);
+                points.push((point, sprite_order));
+            }
+        }
+        if points.is_empty() {
+            return Ok(());
+        }
+        self.clear_tiles(points)
+    }
+
+    /// Clears tiles with points local to a chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk does not exist.
+    fn clear_chunk_tiles(
+        &mut self,
+        chunk_point: Point2,
+        tiles: &[Tile<Point3>],
+    ) -> TilemapResult<()> {
+        let chunk_dimensions = self.chunk_dimensions;
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(c) => c,
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+        for tile in tiles.iter() {
+            let index = chunk_dimensions.encode_point_unchecked(tile.point);
+            let z_depth = tile.point.z as usize;
+            if let Some(history) = &mut self.history {
+                history.push(TileChange {
+                    point: Tilemap::chunk_tile_point_to_point(
+                        chunk_dimensions,
+                        chunk_point,
+                        tile.point,
+                    ),
+                    sprite_order: tile.sprite_order,
+                    before: chunk.get_tile(index, tile.sprite_order, z_depth).copied(),
+                    after: None,
+                });
+            }
+            chunk.remove_tile(index, tile.sprite_order, z_depth);
+        }
+
+        if let Some(recording) = &mut self.recording {
+            let points = tiles
+                .iter()
+                .map(|tile| {
+                    let point = Tilemap::chunk_tile_point_to_point(
+                        chunk_dimensions,
+                        chunk_point,
+                        tile.point,
+                    );
+                    (point, tile.sprite_order)
+                })
+                .collect();
+            recording.push(RecordedEvent::ClearTiles { points });
+        }
+
+        self.chunk_events
+            .push(TilemapChunkEvent::Modified { point: chunk_point });
+        Ok(())
+    }
+
+    /// Returns the points within a rectangle between two corners, including
+    /// both corners, in any order.
+    fn rect_points(a: Point2, b: Point2) -> impl Iterator<Item = Point2> {
+        let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+        let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+        (min_y..=max_y).flat_map(move |y| (min_x..=max_x).map(move |x| Point2::new(x, y)))
+    }
+
+    /// Returns copies of a raw tile at points, a sprite order and a z depth,
+    /// each with a variant of the sprite index of the raw tile if it has
+    /// variants.
+    fn template_tiles<I>(
+        &mut self,
+        points: I,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> Vec<Tile<Point3>>
+    where
+        I: IntoIterator<Item = Point2>,
+    {
+        points
+            .into_iter()
+            .map(|point| {
+                let point3 = Point3::new(point.x, point.y, z_depth as Coord);
+                let mut tile = Tile::from_raw_tile(point3, sprite_order, &tile);
+                tile.sprite_index = self.pick_variant(point, tile.sprite_index);
+                tile
+            })
+            .collect()
+    }
+
+    /// Fills a rectangle between two corners, including both corners, with
+    /// copies of a raw tile at a sprite order and z depth.
+    ///
+    /// All the tiles are set in one go with [`insert_tiles`], so every chunk
+    /// is only modified once. If the sprite index of the raw tile has
+    /// [`tile_variants`], every tile gets one of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.fill_rect((-2, -2), (2, 2), 0, 0, RawTile { index: 3, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((2, -2), 0).map(|tile| tile.index), Some(3));
+    /// assert_eq!(tilemap.get_tile((3, 0), 0), None);
+    /// ```
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`tile_variants`]: Tilemap::set_tile_variants
+    pub fn fill_rect<P: Into<Point2>>(
+        &mut self,
+        min: P,
+        max: P,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        let points = Tilemap::rect_points(min.into(), max.into());
+        let tiles = self.template_tiles(points, sprite_order, z_depth, tile);
+        self.insert_tiles(tiles)
+    }
+
+    /// Fills a row from one x coordinate to another, including both, with
+    /// copies of a raw tile at a sprite order and z depth.
+    ///
+    /// See [`fill_rect`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.fill_row(1, -4, 4, 0, 0, RawTile { index: 2, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((-4, 1), 0).map(|tile| tile.index), Some(2));
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
+    /// ```
+    ///
+    /// [`fill_rect`]: Tilemap::fill_rect
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn fill_row(
+        &mut self,
+        y: Coord,
+        min_x: Coord,
+        max_x: Coord,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        self.fill_rect((min_x, y), (max_x, y), sprite_order, z_depth, tile)
+    }
+
+    /// Fills a column from one y coordinate to another, including both, with
+    /// copies of a raw tile at a sprite order and z depth.
+    ///
+    /// See [`fill_rect`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.fill_column(1, -4, 4, 0, 0, RawTile { index: 2, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((1, 4), 0).map(|tile| tile.index), Some(2));
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
+    /// ```
+    ///
+    /// [`fill_rect`]: Tilemap::fill_rect
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn fill_column(
+        &mut self,
+        x: Coord,
+        min_y: Coord,
+        max_y: Coord,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        self.fill_rect((x, min_y), (x, max_y), sprite_order, z_depth, tile)
+    }
+
+    /// Clears the tiles of a sprite order and z depth in a rectangle between
+    /// two corners, including both corners.
+    ///
+    /// All the tiles are cleared in one go with [`clear_tiles`], so every
+    /// chunk is only modified once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`clear_tiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.fill_rect((-2, -2), (2, 2), 0, 0, RawTile::default()).unwrap();
+    /// tilemap.clear_rect((-1, -1), (1, 1), 0, 0).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
+    /// assert!(tilemap.get_tile((2, 2), 0).is_some());
+    /// ```
+    ///
+    /// [`clear_tiles`]: Tilemap::clear_tiles
+    pub fn clear_rect<P: Into<Point2>>(
+        &mut self,
+        min: P,
+        max: P,
+        sprite_order: usize,
+        z_depth: usize,
+    ) -> TilemapResult<()> {
+        let points = Tilemap::rect_points(min.into(), max.into()).map(|point| {
+            (
+                Point3::new(point.x, point.y, z_depth as Coord),
+                sprite_order,
+            )
+        });
+        self.clear_tiles(points)
+    }
+
+    /// Takes a global tile point and returns a tile point in a chunk.
+    fn point_to_tile_point(&self, point: Point3) -> Point3 {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        Point3::new(
+            point.x - (width * chunk_point.x) + (width / 2),
+            point.y - (height * chunk_point.y) + (height / 2),
+            point.z,
+        )
+    }
+
+    /// Takes a chunk point and the z depth and index of a tile in the chunk and
+    /// returns the global point of the tile.
+    fn chunk_index_to_point(
+        chunk_dimensions: Dimension3,
+        chunk_point: Point2,
+        z_depth: usize,
+        index: usize,
+    ) -> Point3 {
+        let width = chunk_dimensions.width as usize;
+        let height = chunk_dimensions.height as usize;
+        let tile_point = Point3::new(
+            (index % width) as Coord,
+            (index / width % height) as Coord,
+            z_depth as Coord,
+        );
+        Tilemap::chunk_tile_point_to_point(chunk_dimensions, chunk_point, tile_point)
+    }
+
+    /// Takes a tile point in a chunk and returns the global tile point.
+    fn chunk_tile_point_to_point(
+        chunk_dimensions: Dimension3,
+        chunk_point: Point2,
+        tile_point: Point3,
+    ) -> Point3 {
+        let width = chunk_dimensions.width as Coord;
+        let height = chunk_dimensions.height as Coord;
+        Point3::new(
+            tile_point.x + (width * chunk_point.x) - (width / 2),
+            tile_point.y + (height * chunk_point.y) - (height / 2),
+            tile_point.z,
+        )
+    }
+
+    /// Clear a single tile at the specified point from the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    ///
+    /// let point = (3, 1);
+    /// let sprite_index = 1;
+    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    ///
+    /// // Set a single tile and unwrap the result
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    ///
+    /// // Later on...
+    /// assert!(tilemap.clear_tile(point, 0).is_ok());
+    /// assert_eq!(tilemap.get_tile((3, 1), 0), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error can occure if the point is outside of the tilemap. This can
+    /// only happen if the tilemap has dimensions.
+    pub fn clear_tile<P>(&mut self, point: P, sprite_order: usize) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+    {
+        let points = vec![(point, sprite_order)];
+        self.clear_tiles(points)
+    }
+
+    /// Clears a single tile at the specified point from the layer with a
+    /// name.
+    ///
+    /// See [`insert_tiles_in_layer`] for an example.
+    ///
+    /// [`insert_tiles_in_layer`]: Tilemap::insert_tiles_in_layer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no layer has the name, or for the same reasons as
+    /// [`clear_tile`].
+    ///
+    /// [`clear_tile`]: Tilemap::clear_tile
+    pub fn clear_tile_in_layer<P>(&mut self, point: P, name: &str) -> TilemapResult<()>
+    where
+        P: Into<Point3>,
+    {
+        let sprite_order = self.named_layer_index(name)?;
+        self.clear_tile(point, sprite_order)
+    }
+
+    /// Locks the tiles within a rectangle between two corners, including both
+    /// corners, against modification.
+    ///
+    /// Every sprite layer and z depth of the region is locked. Calls which
+    /// insert, clear or otherwise change a locked tile return
+    /// [`ErrorKind::RegionLocked`] without changing any tile, which includes
+    /// undoing, pasting and applying chunk responses. This is meant to keep
+    /// protected areas, such as spawn zones in a multiplayer build mode,
+    /// intact whichever code path tries to edit them. Internal tiles such as
+    /// the fog of war are not affected.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.lock_region((-2, -2), (2, 2));
+    ///
+    /// assert!(tilemap.insert_tile(Tile { point: (1, 1), ..Default::default() }).is_err());
+    /// assert!(tilemap.insert_tile(Tile { point: (3, 1), ..Default::default() }).is_ok());
+    ///
+    /// // No tile is set if any of them is locked.
+    /// let tiles = vec![
+    ///     Tile { point: (4, 4), ..Default::default() },
+    ///     Tile { point: (0, 0), ..Default::default() },
+    /// ];
+    /// assert!(tilemap.insert_tiles(tiles).is_err());
+    /// assert_eq!(tilemap.get_tile((4, 4), 0), None);
+    ///
+    /// assert!(tilemap.unlock_region((-2, -2), (2, 2)));
+    /// assert!(tilemap.insert_tile(Tile { point: (1, 1), ..Default::default() }).is_ok());
+    /// ```
+    pub fn lock_region<P: Into<Point2>>(&mut self, min: P, max: P) {
+        let region = Tilemap::region_corners(min.into(), max.into());
+        if !self.locked_regions.contains(&region) {
+            self.locked_regions.push(region);
+        }
+    }
+
+    /// Unlocks a region locked with [`lock_region`] with the same corners,
+    /// returning `true` if it was locked.
+    ///
+    /// Tiles which are also within other locked regions or chunks stay
+    /// locked.
+    ///
+    /// [`lock_region`]: Tilemap::lock_region
+    pub fn unlock_region<P: Into<Point2>>(&mut self, min: P, max: P) -> bool {
+        let region = Tilemap::region_corners(min.into(), max.into());
+        let len = self.locked_regions.len();
+        self.locked_regions.retain(|locked| *locked != region);
+        self.locked_regions.len() != len
+    }
+
+    /// Locks every tile of a chunk against modification, whether the chunk
+    /// exists yet or not.
+    ///
+    /// See [`lock_region`] for how locked tiles are treated.
+    ///
+    /// [`lock_region`]: Tilemap::lock_region
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap::ErrorKind};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.lock_chunk((0, 0));
+    ///
+    /// let error = tilemap.clear_tile((5, 5), 0).unwrap_err();
+    /// assert_eq!(*error.0, ErrorKind::RegionLocked((5, 5).into()));
+    /// assert!(tilemap.is_chunk_locked((0, 0)));
+    ///
+    /// assert!(tilemap.unlock_chunk((0, 0)));
+    /// assert!(tilemap.clear_tile((5, 5), 0).is_ok());
+    /// ```
+    pub fn lock_chunk<P: Into<Point2>>(&mut self, point: P) {
+        self.locked_chunks.insert(point.into());
+    }
+
+    /// Unlocks a chunk locked with [`lock_chunk`], returning `true` if it was
+    /// locked.
+    ///
+    /// [`lock_chunk`]: Tilemap::lock_chunk
+    pub fn unlock_chunk<P: Into<Point2>>(&mut self, point: P) -> bool {
+        self.locked_chunks.remove(&point.into())
+    }
+
+    /// Unlocks every locked region and chunk.
+    pub fn clear_locks(&mut self) {
+        self.locked_regions.clear();
+        self.locked_chunks.clear();
+    }
+
+    /// Returns `true` if the tile at a point may not be modified.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.lock_region((4, 0), (0, 4));
+    ///
+    /// assert!(tilemap.is_locked((2, 2)));
+    /// assert!(tilemap.is_locked((0, 4, 1)));
+    /// assert!(!tilemap.is_locked((5, 2)));
+    /// ```
+    pub fn is_locked<P: Into<Point3>>(&self, point: P) -> bool {
+        let point: Point3 = point.into();
+        let point: Point2 = point.into();
+        if self.locked_regions.iter().any(|(min, max)| {
+            point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+        }) {
+            return true;
+        }
+        if self.locked_chunks.is_empty() {
+            return false;
+        }
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        self.locked_chunks.contains(&chunk_point)
+    }
+
+    /// Returns `true` if a chunk is locked or has a tile in a locked region.
+    pub fn is_chunk_locked<P: Into<Point2>>(&self, point: P) -> bool {
+        let point: Point2 = point.into();
+        if self.locked_chunks.contains(&point) {
+            return true;
+        }
+        let (chunk_min, chunk_max) = self.chunk_tile_bounds(point);
+        self.locked_regions.iter().any(|(min, max)| {
+            min.x <= chunk_max.x
+                && max.x >= chunk_min.x
+                && min.y <= chunk_max.y
+                && max.y >= chunk_min.y
+        })
+    }
+
+    /// Returns the lowest and highest global points of the tiles of a chunk.
+    fn chunk_tile_bounds(&self, point: Point2) -> (Point2, Point2) {
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let chunk_min = Point2::new(point.x * width - width / 2, point.y * height - height / 2);
+        let chunk_max = Point2::new(chunk_min.x + width - 1, chunk_min.y + height - 1);
+        (chunk_min, chunk_max)
+    }
+
+    /// Returns the lowest and highest corners of a rectangle between two
+    /// corners in any order.
+    fn region_corners(a: Point2, b: Point2) -> (Point2, Point2) {
+        (
+            Point2::new(a.x.min(b.x), a.y.min(b.y)),
+            Point2::new(a.x.max(b.x), a.y.max(b.y)),
+        )
+    }
+
+    /// Returns an error if the tile at a point is locked.
+    fn check_unlocked(&self, point: Point2) -> TilemapResult<()> {
+        if self.is_locked(point) {
+            return Err(ErrorKind::RegionLocked(point).into());
+        }
+        Ok(())
+    }
+
+    /// Gets a raw tile from a given point and z order.
+    ///
+    /// This is different thant he usual [`Tile`] struct in that it only
+    /// contains the sprite index and the tint.
+    ///
+    /// [`Tile`]: crate::tile::Tile
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let point = (9, 3);
+    /// let sprite_index = 3;
+    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    ///
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE, ..Default::default() }));
+    /// assert_eq!(tilemap.get_tile((10, 4), 0), None);
+    /// ```
+    pub fn get_tile<P>(&mut self, point: P, sprite_order: usize) -> Option<&RawTile>
+    where
+        P: Into<Point3>,
+    {
+        self.raw_tile(point.into(), sprite_order)
+    }
+
+    /// Gets a raw tile from a given point on the layer with a name, or `None`
+    /// if there is no tile or no layer has the name.
+    ///
+    /// See [`insert_tiles_in_layer`] for an example.
+    ///
+    /// [`insert_tiles_in_layer`]: Tilemap::insert_tiles_in_layer
+    pub fn get_tile_in_layer<P>(&self, point: P, name: &str) -> Option<&RawTile>
+    where
+        P: Into<Point3>,
+    {
+        let sprite_order = self.layer_index(name)?;
+        self.raw_tile(point.into(), sprite_order)
+    }
+
+    /// Gets a raw tile from a given point and sprite order without mutable
+    /// access to the tilemap.
+    pub(crate) fn raw_tile(&self, point: Point3, sprite_order: usize) -> Option<&RawTile> {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let chunk = self.chunks.get(&chunk_point)?;
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        chunk.get_tile(index, sprite_order, point.z as usize)
+    }
+
+    /// Returns `true` if a sprite layer has a tile at a point.
+    ///
+    /// Dense and sparse sprite layers keep a bitset of the tiles they have,
+    /// so this is quicker than getting the tile when only whether there is
+    /// one matters, such as for walls. Tiles made transparent through
+    /// [`get_tile_mut`] still count until they are removed.
+    ///
+    /// [`get_tile_mut`]: Tilemap::get_tile_mut
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse }, 1)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (1, 2), sprite_order: 1, ..Default::default() }).unwrap();
+    ///
+    /// assert!(tilemap.is_occupied((1, 2), 1));
+    /// assert!(!tilemap.is_occupied((1, 2), 0));
+    /// assert!(!tilemap.is_occupied((1, 2, 1), 1));
+    /// ```
+    pub fn is_occupied<P: Into<Point3>>(&self, point: P, sprite_order: usize) -> bool {
+        let point = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let chunk = match self.chunks.get(&chunk_point) {
+            Some(chunk) => chunk,
+            None => return false,
+        };
+        let index = self
+            .chunk_dimensions
+            .encode_point_unchecked(self.point_to_tile_point(point));
+        chunk.is_occupied(index, sprite_order, point.z as usize)
+    }
+
+    /// Returns an iterator over every tile which is set in the tilemap, along
+    /// with its point and sprite order, in no particular order.
+    ///
+    /// The tiles are visited chunk by chunk as the iterator is advanced, so
+    /// nothing is collected up front.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point3, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_chunk((1, 0)).unwrap();
+    ///
+    /// let tiles = vec![
+    ///     Tile { point: (1, 1), sprite_index: 1, ..Default::default() },
+    ///     Tile { point: (20, 3), sprite_index: 2, ..Default::default() },
+    /// ];
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// let mut points: Vec<Point3> = tilemap.iter_tiles().map(|(point, _, _)| point).collect();
+    /// points.sort_by_key(|point| point.x);
+    /// assert_eq!(points, vec![Point3::new(1, 1, 0), Point3::new(20, 3, 0)]);
+    /// ```
+    pub fn iter_tiles(&self) -> impl Iterator<Item = (Point3, usize, &RawTile)> + '_ {
+        self.tiles_in_layers(None)
+    }
+
+    /// Returns an iterator over every tile which is set in a sprite layer of
+    /// the tilemap, along with its point, in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer(TilemapLayer::default(), 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let tiles = vec![
+    ///     Tile { point: (1, 1), sprite_index: 1, ..Default::default() },
+    ///     Tile { point: (1, 1), sprite_order: 1, sprite_index: 4, ..Default::default() },
+    /// ];
+    /// tilemap.insert_tiles(tiles).unwrap();
+    ///
+    /// let sprite_indices: Vec<usize> = tilemap
+    ///     .iter_layer_tiles(1)
+    ///     .map(|(_, tile)| tile.index)
+    ///     .collect();
+    /// assert_eq!(sprite_indices, vec![4]);
+    /// ```
+    pub fn iter_layer_tiles(
+        &self,
+        sprite_order: usize,
+    ) -> impl Iterator<Item = (Point3, &RawTile)> + '_ {
+        self.tiles_in_layers(Some(sprite_order))
+            .map(|(point, _sprite_order, tile)| (point, tile))
+    }
+
+    /// Returns an iterator over the tiles of every sprite layer, or only of a
+    /// sprite layer if given, along with their points and sprite orders.
+    fn tiles_in_layers(
+        &self,
+        sprite_order: Option<usize>,
+    ) -> impl Iterator<Item = (Point3, usize, &RawTile)> + '_ {
+        let chunk_dimensions = self.chunk_dimensions;
+        self.chunks.iter().flat_map(move |(chunk_point, chunk)| {
+            chunk
+                .iter_tiles(sprite_order)
+                .map(move |(z_depth, sprite_order, index, tile)| {
+                    let point = Tilemap::chunk_index_to_point(
+                        chunk_dimensions,
+                        *chunk_point,
+                        z_depth,
+                        index,
+                    );
+                    (point, sprite_order, tile)
+                })
+        })
+    }
+
+    /// Gets a mutable raw tile from a given point and z order.
+    ///
+    /// This is different thant he usual [`Tile`] struct in that it only
+    /// contains the sprite index and the tint. Locked tiles are not handed
+    /// out, see [`lock_region`].
+    ///
+    /// [`Tile`]: crate::tile::Tile
+    /// [`lock_region`]: Tilemap::lock_region
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let point = (2, 5);
+    /// let sprite_index = 2;
+    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    ///
+    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// assert_eq!(tilemap.get_tile_mut((2, 5), 0), Some(&mut RawTile { index: 2, color: Color::WHITE, ..Default::default() }));
+    /// assert_eq!(tilemap.get_tile_mut((1, 4), 0), None);
+    /// ```
+    pub fn get_tile_mut<P>(&mut self, point: P, sprite_order: usize) -> Option<&mut RawTile>
+    where
+        P: Into<Point3>,
+    {
+        let point: Point3 = point.into();
+        if self.is_locked(point) {
+            return None;
+        }
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let chunk = self.chunks.get_mut(&chunk_point)?;
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        let mut layers = HashMap::default();
+        layers.insert(sprite_order, chunk_point);
+        self.chunk_events.push(TilemapChunkEvent::Modified {
+            point: chunk.point(),
+        });
+        chunk.get_tile_mut(index, sprite_order, point.z as usize)
+    }
+
+    /// Gets a raw tile by its index within a chunk, sprite order and z depth.
+    ///
+    /// The index is `y * chunk_width + x` where `x` and `y` are the point of
+    /// the tile within the chunk, starting at its bottom left tile. This skips
+    /// converting a global point for every tile, for systems which already
+    /// work chunk by chunk such as generators and cellular automata.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let tile = RawTile { index: 3, ..Default::default() };
+    /// assert!(tilemap.set_tile_in_chunk((0, 0), 33, 0, 0, tile).is_ok());
+    ///
+    /// assert_eq!(tilemap.get_tile_in_chunk((0, 0), 33, 0, 0), Some(&tile));
+    /// // The tile at (1, 1) in the chunk is at (-15, -15) in the tilemap.
+    /// assert_eq!(tilemap.get_tile((-15, -15), 0), Some(&tile));
+    /// ```
+    pub fn get_tile_in_chunk<P: Into<Point2>>(
+        &self,
+        chunk_point: P,
+        index: usize,
+        sprite_order: usize,
+        z_depth: usize,
+    ) -> Option<&RawTile> {
+        self.chunks
+            .get(&chunk_point.into())?
+            .get_tile(index, sprite_order, z_depth)
+    }
+
+    /// Sets a raw tile by its index within a chunk, sprite order and z depth.
+    ///
+    /// See [`get_tile_in_chunk`] for how the index is laid out.
+    ///
+    /// [`get_tile_in_chunk`]: Tilemap::get_tile_in_chunk
+    ///
+    /// # Errors
+    ///
+    /// If the chunk or sprite layer does not exist, the index or z depth is
+    /// out of bounds of the chunk, or the tile is locked, an error is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let tile = RawTile { index: 3, ..Default::default() };
+    /// assert!(tilemap.set_tile_in_chunk((0, 0), 0, 0, 0, tile).is_ok());
+    /// assert!(tilemap.set_tile_in_chunk((0, 0), 32 * 32, 0, 0, tile).is_err());
+    /// assert!(tilemap.set_tile_in_chunk((1, 0), 0, 0, 0, tile).is_err());
+    /// ```
+    pub fn set_tile_in_chunk<P: Into<Point2>>(
+        &mut self,
+        chunk_point: P,
+        index: usize,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        let chunk_point: Point2 = chunk_point.into();
+        let chunk_dimensions = self.chunk_dimensions;
+        if index >= (chunk_dimensions.width * chunk_dimensions.height) as usize
+            || z_depth >= chunk_dimensions.depth as usize
+        {
+            return Err(ErrorKind::TileIndexOutOfBounds(index, z_depth).into());
+        }
+        if self
+            .layers
+            .get(sprite_order)
+            .is_none_or(|layer| layer.is_none())
+        {
+            return Err(ErrorKind::LayerDoesNotExist(sprite_order).into());
+        }
+        let width = chunk_dimensions.width as usize;
+        let tile_point = Point3::new(
+            (index % width) as Coord,
+            (index / width) as Coord,
+            z_depth as Coord,
+        );
+        let point = Tilemap::chunk_tile_point_to_point(chunk_dimensions, chunk_point, tile_point);
+        self.check_unlocked(point.into())?;
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk,
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+        let before = chunk.get_tile(index, sprite_order, z_depth).copied();
+        chunk.set_raw_tile(index, sprite_order, z_depth, tile);
+        if chunk.has_mesh() {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+
+        let validate = self.auto_flags.contains(AutoFlags::VALIDATE_SPRITE_INDICES);
+        if validate || self.recording.is_some() || self.history.is_some() {
+            if validate {
+                self.unvalidated_tiles.push(UnvalidatedTile {
+                    point,
+                    sprite_order,
+                    atlas: tile.atlas,
+                    sprite_index: tile.index,
+                    attempts: 0,
+                });
+            }
+            if let Some(recording) = &mut self.recording {
+                recording.push(RecordedEvent::InsertTiles {
+                    tiles: vec![(point, sprite_order, tile)],
+                });
+            }
+            if let Some(history) = &mut self.history {
+                history.push(TileChange {
+                    point,
+                    sprite_order,
+                    before,
+                    after: Some(tile),
+                });
+            }
+        }
+        self.finish_history_step();
+
+        Ok(())
+    }
+
+    /// Sets a named property of the tile at a point and sprite order,
+    /// returning the previous value if there was one.
+    ///
+    /// Properties are stored separately from the sprite of the tile, so a
+    /// point does not need a tile to have properties and they stay when the
+    /// tile is cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk or the sprite layer does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, property::TileProperty};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.set_tile_property((1, 1), 0, "ore", 20).is_err());
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// assert_eq!(tilemap.set_tile_property((1, 1), 0, "ore", 20), Ok(None));
+    /// assert_eq!(
+    ///     tilemap.set_tile_property((1, 1), 0, "ore", 15),
+    ///     Ok(Some(TileProperty::Int(20)))
+    /// );
+    /// ```
+    pub fn set_tile_property<P, V>(
+        &mut self,
+        point: P,
+        sprite_order: usize,
+        name: &str,
+        value: V,
+    ) -> TilemapResult<Option<TileProperty>>
+    where
+        P: Into<Point3>,
+        V: Into<TileProperty>,
+    {
+        let point: Point3 = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk,
+            None => return Err(ErrorKind::MissingChunk.into()),
+        };
+        let properties = match chunk.layer_properties_mut(sprite_order, point.z as usize) {
+            Some(properties) => properties,
+            None => return Err(ErrorKind::LayerDoesNotExist(sprite_order).into()),
+        };
+
+        Ok(properties
+            .entry(index)
+            .or_default()
+            .insert(name.to_string(), value.into()))
+    }
+
+    /// Returns a named property of the tile at a point and sprite order, if
+    /// it is set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, property::TileProperty};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.set_tile_property((1, 1), 0, "walkable", false).unwrap();
+    ///
+    /// assert_eq!(tilemap.tile_property((1, 1), 0, "walkable"), Some(&TileProperty::Bool(false)));
+    /// assert_eq!(tilemap.tile_property((1, 1), 0, "ore"), None);
+    /// ```
+    pub fn tile_property<P>(
+        &self,
+        point: P,
+        sprite_order: usize,
+        name: &str,
+    ) -> Option<&TileProperty>
+    where
+        P: Into<Point3>,
+    {
+        self.tile_properties(point, sprite_order)
+            .and_then(|properties| properties.get(name))
+    }
+
+    /// Returns all the properties of the tile at a point and sprite order, if
+    /// it has any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.set_tile_property((1, 1), 0, "terrain", "sand").unwrap();
+    /// tilemap.set_tile_property((1, 1), 0, "movement_cost", 2.5).unwrap();
+    ///
+    /// assert_eq!(tilemap.tile_properties((1, 1), 0).map(|p| p.len()), Some(2));
+    /// assert!(tilemap.tile_properties((2, 2), 0).is_none());
+    /// ```
+    pub fn tile_properties<P>(&self, point: P, sprite_order: usize) -> Option<&TileProperties>
+    where
+        P: Into<Point3>,
+    {
+        let point: Point3 = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        self.chunks
+            .get(&chunk_point)
+            .and_then(|chunk| chunk.layer_properties(sprite_order, point.z as usize))
+            .and_then(|properties| properties.get(&index))
+    }
+
+    /// Removes a named property of the tile at a point and sprite order,
+    /// returning it if it was set.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, property::TileProperty};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.set_tile_property((1, 1), 0, "ore", 20).unwrap();
+    ///
+    /// assert_eq!(tilemap.remove_tile_property((1, 1), 0, "ore"), Some(TileProperty::Int(20)));
+    /// assert_eq!(tilemap.tile_property((1, 1), 0, "ore"), None);
+    /// ```
+    pub fn remove_tile_property<P>(
+        &mut self,
+        point: P,
+        sprite_order: usize,
+        name: &str,
+    ) -> Option<TileProperty>
+    where
+        P: Into<Point3>,
+    {
+        let point: Point3 = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        let layer_properties = self
+            .chunks
+            .get_mut(&chunk_point)?
+            .layer_properties_mut(sprite_order, point.z as usize)?;
+        let properties = layer_properties.get_mut(&index)?;
+        let property = properties.remove(name);
+        if properties.is_empty() {
+            layer_properties.remove(&index);
+        }
+        property
+    }
+
+    /// Leaves an annotation at a point, returning the annotation which was
+    /// there before.
+    ///
+    /// Annotations are notes for level designers, such as "boss spawns here".
+    /// They are never rendered and do not need a chunk to exist, but are
+    /// serialized with the tilemap so they travel with the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.set_annotation((4, 2), "boss spawns here"), None);
+    /// assert_eq!(
+    ///     tilemap.set_annotation((4, 2), "boss spawns here at night"),
+    ///     Some("boss spawns here".to_string())
+    /// );
+    /// ```
+    pub fn set_annotation<P, S>(&mut self, point: P, annotation: S) -> Option<String>
+    where
+        P: Into<Point2>,
+        S: Into<String>,
+    {
+        self.annotations.insert(point.into(), annotation.into())
+    }
+
+    /// Returns the annotation at a point, if there is one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_annotation((4, 2), "boss spawns here");
+    ///
+    /// assert_eq!(tilemap.annotation((4, 2)), Some("boss spawns here"));
+    /// assert_eq!(tilemap.annotation((2, 4)), None);
+    /// ```
+    pub fn annotation<P: Into<Point2>>(&self, point: P) -> Option<&str> {
+        self.annotations
+            .get(&point.into())
+            .map(|annotation| annotation.as_str())
+    }
+
+    /// Removes the annotation at a point, returning it if there was one.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_annotation((4, 2), "boss spawns here");
+    ///
+    /// assert_eq!(tilemap.remove_annotation((4, 2)), Some("boss spawns here".to_string()));
+    /// assert_eq!(tilemap.annotation((4, 2)), None);
+    /// ```
+    pub fn remove_annotation<P: Into<Point2>>(&mut self, point: P) -> Option<String> {
+        self.annotations.remove(&point.into())
+    }
+
+    /// Returns an iterator over all the annotations and the points they are
+    /// at, in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_annotation((4, 2), "boss spawns here");
+    ///
+    /// let annotations: Vec<(Point2, &str)> = tilemap.annotations().collect();
+    /// assert_eq!(annotations, vec![(Point2::new(4, 2), "boss spawns here")]);
+    /// ```
+    pub fn annotations(&self) -> impl Iterator<Item = (Point2, &str)> {
+        self.annotations
+            .iter()
+            .map(|(point, annotation)| (*point, annotation.as_str()))
+    }
+
+    /// Removes every annotation.
+    pub fn clear_annotations(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Explores points of the fog of war for good.
+    ///
+    /// Explored points which are not visible are covered by a darkened fog.
+    /// See the [`fog`] module for more information.
+    ///
+    /// [`fog`]: crate::fog
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{
+    ///     fog::{FogOfWar, FogState},
+    ///     prelude::*,
+    /// };
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .fog_of_war(FogOfWar { sprite_order: 1, ..Default::default() })
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.reveal(vec![(3, 3), (3, 4)]).unwrap();
+    ///
+    /// assert_eq!(tilemap.fog_state((3, 3)), Some(FogState::Explored));
+    /// assert_eq!(tilemap.fog_state((3, 5)), Some(FogState::Unexplored));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no fog of war, an error is returned.
+    pub fn reveal<P, I>(&mut self, points: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let changed = match &mut self.fog {
+            Some(fog) => fog.reveal(points.into_iter().map(|point| point.into())),
+            None => return Err(ErrorKind::MissingFogOfWar.into()),
+        };
+        self.update_fog_tiles(changed);
+
+        Ok(())
+    }
+
+    /// Sets the points of the fog of war which are currently visible, such as
+    /// the field of view of a player.
+    ///
+    /// The visible points are explored and not covered by fog. Points which
+    /// were visible before and are not anymore are covered by a darkened fog.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{
+    ///     fog::{FogOfWar, FogState},
+    ///     prelude::*,
+    /// };
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .fog_of_war(FogOfWar { sprite_order: 1, ..Default::default() })
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.set_visible(vec![(3, 3)]).unwrap();
+    /// assert_eq!(tilemap.fog_state((3, 3)), Some(FogState::Visible));
+    ///
+    /// tilemap.set_visible(vec![(3, 4)]).unwrap();
+    /// assert_eq!(tilemap.fog_state((3, 3)), Some(FogState::Explored));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no fog of war, an error is returned.
+    pub fn set_visible<P, I>(&mut self, points: I) -> TilemapResult<()>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let changed = match &mut self.fog {
+            Some(fog) => fog.set_visible(points.into_iter().map(|point| point.into())),
+            None => return Err(ErrorKind::MissingFogOfWar.into()),
+        };
+        self.update_fog_tiles(changed);
+
+        Ok(())
+    }
+
+    /// Returns the state of a point under the fog of war, or `None` if the
+    /// tilemap has no fog of war.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{fog::FogState, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.fog_state((0, 0)), None);
+    /// ```
+    pub fn fog_state<P: Into<Point2>>(&self, point: P) -> Option<FogState> {
+        self.fog.as_ref().map(|fog| fog.state(point.into()))
+    }
+
+    /// Updates the fog tiles of points in chunks which exist.
+    fn update_fog_tiles(&mut self, points: Vec<Point2>) {
+        let fog = match &self.fog {
+            Some(fog) => fog,
+            None => return,
+        };
+        let mut modified_chunks = HashSet::default();
+        for point in points {
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let tile_point = self.point_to_tile_point(Point3::new(point.x, point.y, 0));
+            let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+            let chunk = match self.chunks.get_mut(&chunk_point) {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            match fog.raw_tile(point) {
+                Some(raw_tile) => chunk.set_raw_tile(index, fog.settings.sprite_order, 0, raw_tile),
+                None => chunk.remove_tile(index, fog.settings.sprite_order, 0),
+            }
+            if chunk.has_mesh() {
+                modified_chunks.insert(chunk_point);
+            }
+        }
+        for point in modified_chunks {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point });
+        }
+    }
+
+    /// Covers all the tiles of a chunk with the fog of war.
+    pub(crate) fn fill_fog(&mut self, chunk_point: Point2) {
+        let fog = match &self.fog {
+            Some(fog) => fog,
+            None => return,
+        };
+        let chunk_dimensions = self.chunk_dimensions;
+        let chunk = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk,
+            None => return,
+        };
+        for y in 0..chunk_dimensions.height {
+            for x in 0..chunk_dimensions.width {
+                let tile_point = Point3::new(x as Coord, y as Coord, 0);
+                let point =
+                    Tilemap::chunk_tile_point_to_point(chunk_dimensions, chunk_point, tile_point);
+                let index = chunk_dimensions.encode_point_unchecked(tile_point);
+                match fog.raw_tile(point.into()) {
+                    Some(raw_tile) => {
+                        chunk.set_raw_tile(index, fog.settings.sprite_order, 0, raw_tile)
+                    }
+                    None => chunk.remove_tile(index, fog.settings.sprite_order, 0),
+                }
+            }
+        }
+    }
+
+    /// Adds a light at a point, returning its id.
+    ///
+    /// The tiles of spawned chunks within reach of the light are tinted right
+    /// away. See the [`light`] module for more information.
+    ///
+    /// [`light`]: crate::light
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no lighting, an error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{
+    ///     light::{Light, Lighting},
+    ///     prelude::*,
+    /// };
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .lighting(Lighting { ambient: Color::BLACK, occlusion: None })
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let light = tilemap.add_light((0, 0), Light { radius: 2, ..Default::default() }).unwrap();
+    /// assert_eq!(tilemap.light_at((5, 0)), Some(Color::BLACK));
+    ///
+    /// assert!(tilemap.move_light(light, (5, 0)));
+    /// assert_eq!(tilemap.light_at((5, 0)), Some(Color::WHITE));
+    ///
+    /// assert!(tilemap.remove_light(light).is_some());
+    /// assert_eq!(tilemap.light_at((5, 0)), Some(Color::BLACK));
+    /// ```
+    pub fn add_light<P: Into<Point2>>(&mut self, point: P, light: Light) -> TilemapResult<LightId> {
+        let point: Point2 = point.into();
+        let id = match &mut self.lights {
+            Some(lights) => lights.add(point, light),
+            None => return Err(ErrorKind::MissingLighting.into()),
+        };
+        self.relight_reach(point, light.radius);
+        Ok(id)
+    }
+
+    /// Moves a light to a point, returning `false` if there is no such light.
+    pub fn move_light<P: Into<Point2>>(&mut self, id: LightId, point: P) -> bool {
+        let point: Point2 = point.into();
+        let (previous, light) = match self.lights.as_mut().and_then(|lights| {
+            let (_point, light) = lights.get(id)?;
+            Some((lights.set_point(id, point)?, light))
+        }) {
+            Some(moved) => moved,
+            None => return false,
+        };
+        if previous != point {
+            self.relight_reach(previous, light.radius);
+            self.relight_reach(point, light.radius);
+        }
+        true
+    }
+
+    /// Removes a light, returning it if it existed.
+    pub fn remove_light(&mut self, id: LightId) -> Option<Light> {
+        let (point, light) = self.lights.as_mut()?.remove(id)?;
+        self.relight_reach(point, light.radius);
+        Some(light)
+    }
+
+    /// Sets the light every tile gets, even without a light in reach.
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no lighting, an error is returned.
+    pub fn set_ambient_light(&mut self, color: Color) -> TilemapResult<()> {
+        match &mut self.lights {
+            Some(lights) => lights.settings.ambient = color,
+            None => return Err(ErrorKind::MissingLighting.into()),
+        }
+        self.refresh_lighting();
+        Ok(())
+    }
+
+    /// Updates the light falling on every tile of the spawned chunks.
+    ///
+    /// The light is updated automatically when lights change, but not when
+    /// the tiles of the occluding layer change, which is when this needs to
+    /// be called.
+    pub fn refresh_lighting(&mut self) {
+        let spawned: Vec<Point2> = self.spawned.iter().map(|&point| point.into()).collect();
+        for point in spawned {
+            self.relight_chunk(point);
+        }
+    }
+
+    /// Returns the light falling on a point, or `None` if the tilemap has no
+    /// lighting.
+    pub fn light_at<P: Into<Point2>>(&self, point: P) -> Option<Color> {
+        let point: Point2 = point.into();
+        let lights = self.lights.as_ref()?;
+        let [r, g, b] = light::light_at(
+            point,
+            lights.ambient(),
+            &lights.lights_in(point, point),
+            |a, b| self.light_distance(a, b),
+            |a, b| self.in_light_sight(a, b),
+        );
+        Some(Color::rgb(r, g, b))
+    }
+
+    /// Returns the distance light travels between two points, which is round
+    /// on a square topology.
+    fn light_distance(&self, a: Point2, b: Point2) -> f32 {
+        match self.topology {
+            GridTopology::Square => {
+                let (x, y) = ((a.x - b.x) as f32, (a.y - b.y) as f32);
+                (x * x + y * y).sqrt()
+            }
+            topology => hex::distance(topology, a, b) as f32,
+        }
+    }
+
+    /// Returns `true` if no tile of the occluding layer is between a light
+    /// and a point.
+    fn in_light_sight(&self, light_point: Point2, point: Point2) -> bool {
+        let sprite_order = match self
+            .lights
+            .as_ref()
+            .and_then(|lights| lights.settings.occlusion)
+        {
+            Some(sprite_order) => sprite_order,
+            None => return true,
+        };
+        let line = match self.topology {
+            GridTopology::Square => tilemap_ops::bresenham_line(light_point, point),
+            topology => hex::line(topology, light_point, point),
+        };
+        let depth = self.chunk_dimensions.depth as Coord;
+        // Occluding tiles are lit themselves, only the tiles behind them are
+        // not.
+        line.iter()
+            .skip(1)
+            .take(line.len().saturating_sub(2))
+            .all(|between| {
+                (0..depth).all(|z| !self.is_occupied((between.x, between.y, z), sprite_order))
+            })
+    }
+
+    /// Returns the light falling on every tile of a chunk by index, or `None`
+    /// if the tilemap has no lighting.
+    fn chunk_light(&self, chunk_point: Point2) -> Option<Vec<[f32; 3]>> {
+        let lights = self.lights.as_ref()?;
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let min: Point2 = Tilemap::chunk_tile_point_to_point(
+            self.chunk_dimensions,
+            chunk_point,
+            Point3::new(0, 0, 0),
+        )
+        .into();
+        let max = Point2::new(min.x + width - 1, min.y + height - 1);
+        let ambient = lights.ambient();
+        let area = (width * height) as usize;
+        let reaching = lights.lights_in(min, max);
+        if reaching.is_empty() {
+            return Some(vec![ambient; area]);
+        }
+        let mut tints = Vec::with_capacity(area);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                tints.push(light::light_at(
+                    Point2::new(x, y),
+                    ambient,
+                    &reaching,
+                    |a, b| self.light_distance(a, b),
+                    |a, b| self.in_light_sight(a, b),
+                ));
+            }
+        }
+        Some(tints)
+    }
+
+    /// Updates the light falling on the tiles of a spawned chunk.
+    fn relight_chunk(&mut self, chunk_point: Point2) {
+        if !self.spawned.contains(&(chunk_point.x, chunk_point.y)) {
+            return;
+        }
+        let light = self.chunk_light(chunk_point);
+        let changed = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk.set_light(light) && chunk.has_mesh(),
+            None => false,
+        };
+        if changed {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+    }
+
+    /// Updates the light falling on the tiles of the spawned chunks within
+    /// reach of a light.
+    fn relight_reach(&mut self, point: Point2, radius: u32) {
+        let radius = radius as Coord;
+        let min: Point2 = self
+            .point_to_chunk_point((point.x - radius, point.y - radius))
+            .into();
+        let max: Point2 = self
+            .point_to_chunk_point((point.x + radius, point.y + radius))
+            .into();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.relight_chunk(Point2::new(x, y));
+            }
+        }
+    }
+
+    /// Lights all the tiles of a chunk which is spawned.
+    pub(crate) fn fill_light(&mut self, chunk_point: Point2) {
+        if self.lights.is_some() {
+            self.relight_chunk(chunk_point);
+        }
+    }
+
+    /// Sets the ambient occlusion, or disables it with `None`, and updates
+    /// the shade of every tile of the spawned chunks.
+    ///
+    /// See the [`shade`] module for more information.
+    ///
+    /// [`shade`]: crate::shade
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, shade::AmbientOcclusion};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert_eq!(tilemap.shade_at((0, 0)), None);
+    ///
+    /// tilemap.set_ambient_occlusion(Some(AmbientOcclusion::default()));
+    /// assert_eq!(tilemap.shade_at((0, 0)), Some([1.0; 4]));
+    /// ```
+    pub fn set_ambient_occlusion(&mut self, ambient_occlusion: Option<AmbientOcclusion>) {
+        if self.ambient_occlusion == ambient_occlusion {
+            return;
+        }
+        self.ambient_occlusion = ambient_occlusion;
+        self.refresh_ambient_occlusion();
+    }
+
+    /// Returns the ambient occlusion, if enabled.
+    pub fn ambient_occlusion(&self) -> Option<AmbientOcclusion> {
+        self.ambient_occlusion
+    }
+
+    /// Updates the shade of every tile of the spawned chunks.
+    ///
+    /// The shade is updated automatically when chunks spawn, but not when the
+    /// tiles of the wall layer change, which is when this needs to be called.
+    pub fn refresh_ambient_occlusion(&mut self) {
+        let spawned: Vec<Point2> = self.spawned.iter().map(|&point| point.into()).collect();
+        for point in spawned {
+            self.reshade_chunk(point);
+        }
+    }
+
+    /// Returns the shade of the corners of the tile at a point in the order
+    /// bottom left, top left, top right and bottom right, or `None` if the
+    /// tilemap has no ambient occlusion.
+    ///
+    /// A shade of 1.0 is not darkened at all.
+    pub fn shade_at<P: Into<Point2>>(&self, point: P) -> Option<[f32; 4]> {
+        let point: Point2 = point.into();
+        let ambient_occlusion = self.ambient_occlusion?;
+        Some(shade::tile_shade(
+            point,
+            ambient_occlusion.strength,
+            |point| self.is_wall(point, ambient_occlusion.sprite_order),
+        ))
+    }
+
+    /// Returns `true` if the wall layer has a tile at a point on any z depth.
+    fn is_wall(&self, point: Point2, sprite_order: usize) -> bool {
+        (0..self.chunk_dimensions.depth as Coord)
+            .any(|z| self.is_occupied((point.x, point.y, z), sprite_order))
+    }
+
+    /// Returns the shade of the corners of every tile of a chunk by index, or
+    /// `None` if the tilemap has no ambient occlusion.
+    fn chunk_shade(&self, chunk_point: Point2) -> Option<Vec<[f32; 4]>> {
+        let ambient_occlusion = self.ambient_occlusion?;
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let min: Point2 = Tilemap::chunk_tile_point_to_point(
+            self.chunk_dimensions,
+            chunk_point,
+            Point3::new(0, 0, 0),
+        )
+        .into();
+        // The walls of the chunk with a border of one tile, so every wall is
+        // only looked up once.
+        let stride = width + 2;
+        let mut walls = Vec::with_capacity(((width + 2) * (height + 2)) as usize);
+        for y in min.y - 1..=min.y + height {
+            for x in min.x - 1..=min.x + width {
+                walls.push(self.is_wall(Point2::new(x, y), ambient_occlusion.sprite_order));
+            }
+        }
+        let mut shades = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                shades.push(shade::tile_shade(
+                    Point2::new(x, y),
+                    ambient_occlusion.strength,
+                    |point| {
+                        walls
+                            .get(((point.y + 1) * stride + point.x + 1) as usize)
+                            .copied()
+                            .unwrap_or(false)
+                    },
+                ));
+            }
+        }
+        Some(shades)
+    }
+
+    /// Updates the shade of the tiles of a spawned chunk.
+    fn reshade_chunk(&mut self, chunk_point: Point2) {
+        if !self.spawned.contains(&(chunk_point.x, chunk_point.y)) {
+            return;
+        }
+        let shade = self.chunk_shade(chunk_point);
+        let changed = match self.chunks.get_mut(&chunk_point) {
+            Some(chunk) => chunk.set_shade(shade) && chunk.has_mesh(),
+            None => false,
+        };
+        if changed {
+            self.chunk_events
+                .push(TilemapChunkEvent::Modified { point: chunk_point });
+        }
+    }
+
+    /// Shades all the tiles of a chunk which is spawned.
+    pub(crate) fn fill_shade(&mut self, chunk_point: Point2) {
+        if self.ambient_occlusion.is_some() {
+            self.reshade_chunk(chunk_point);
+        }
+    }
+
+    /// Draws the debug heatmap over every tile of the existing chunks at a z
+    /// depth, colored by the value of a field at the point of the tile.
+    ///
+    /// The heatmap is replaced every time it is drawn. See the [`heatmap`]
+    /// module for more information.
+    ///
+    /// [`heatmap`]: crate::heatmap
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no debug heatmap or the z depth is out of bounds of
+    /// the chunks, an error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{heatmap::Heatmap, point::Point2, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .debug_heatmap(Heatmap { sprite_order: 1, ..Default::default() })
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// // Walls along the y axis can not be walked over.
+    /// let cost = |point: Point2| if point.x == 0 { f32::INFINITY } else { point.x.abs() as f32 };
+    /// assert!(tilemap.debug_heatmap(0, cost).is_ok());
+    ///
+    /// assert!(tilemap.get_tile((0, 3), 1).is_none());
+    /// assert!(tilemap.get_tile((4, 3), 1).is_some());
+    /// assert!(tilemap.debug_heatmap(1, cost).is_err());
+    /// ```
+    pub fn debug_heatmap<F>(&mut self, z_depth: usize, field: F) -> TilemapResult<()>
+    where
+        F: Fn(Point2) -> f32,
+    {
+        let heatmap = self.heatmap.clone().ok_or(ErrorKind::MissingHeatmap)?;
+        let chunk_dimensions = self.chunk_dimensions;
+        chunk_dimensions.check_encodable_point(Point3::new(0, 0, z_depth as Coord))?;
+
+        let area = (chunk_dimensions.width * chunk_dimensions.height) as usize;
+        let chunk_values: Vec<(Point2, Vec<f32>)> = self
+            .chunks
+            .keys()
+            .map(|chunk_point| {
+                let values = (0..area)
+                    .map(|index| {
+                        let point = Tilemap::chunk_index_to_point(
+                            chunk_dimensions,
+                            *chunk_point,
+                            z_depth,
+                            index,
+                        );
+                        field(point.into())
+                    })
+                    .collect();
+                (*chunk_point, values)
+            })
+            .collect();
+        let range = heatmap.value_range(
+            chunk_values
+                .iter()
+                .flat_map(|(_chunk_point, values)| values.iter().copied()),
+        );
+
+        for (chunk_point, values) in chunk_values {
+            let chunk = match self.chunks.get_mut(&chunk_point) {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            for (index, value) in values.into_iter().enumerate() {
+                match heatmap.raw_tile(value, range) {
+                    Some(raw_tile) => {
+                        chunk.set_raw_tile(index, heatmap.sprite_order, z_depth, raw_tile)
+                    }
+                    None => chunk.remove_tile(index, heatmap.sprite_order, z_depth),
+                }
+            }
+            if chunk.has_mesh() {
+                self.chunk_events
+                    .push(TilemapChunkEvent::Modified { point: chunk_point });
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the debug heatmap from every chunk.
+    ///
+    /// # Errors
+    ///
+    /// If the tilemap has no debug heatmap, an error is returned.
+    pub fn clear_debug_heatmap(&mut self) -> TilemapResult<()> {
+        let sprite_order = self
+            .heatmap
+            .as_ref()
+            .ok_or(ErrorKind::MissingHeatmap)?
+            .sprite_order;
+        for (chunk_point, chunk) in self.chunks.iter_mut() {
+            let tiles: Vec<(usize, usize)> = chunk
+                .iter_tiles(Some(sprite_order))
+                .map(|(z_depth, _sprite_order, index, _tile)| (z_depth, index))
+                .collect();
+            if tiles.is_empty() {
+                continue;
+            }
+            for (z_depth, index) in tiles {
+                chunk.remove_tile(index, sprite_order, z_depth);
+            }
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: *chunk_point,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts a text label on a tile, replacing its label if it has one.
+    ///
+    /// The label is centered on the tile and shown while the chunk of the
+    /// tile is spawned. See the [`label`] module for more information.
+    ///
+    /// [`label`]: crate::label
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_text::TextStyle;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.label_tile((0, 0), "Start", TextStyle::default());
+    /// tilemap.label_tile((0, 0), "Goal", TextStyle::default());
+    ///
+    /// assert_eq!(tilemap.tile_label((0, 0)), Some("Goal"));
+    /// ```
+    #[cfg(feature = "text")]
+    pub fn label_tile<P, S>(&mut self, point: P, text: S, style: TextStyle)
+    where
+        P: Into<Point2>,
+        S: Into<String>,
+    {
+        let point: Point2 = point.into();
+        let text = text.into();
+        match self.tile_labels.get_mut(&point) {
+            Some(label) => {
+                label.text = text;
+                label.style = style;
+                label.changed = true;
+            }
+            None => {
+                self.tile_labels.insert(point, TileLabel::new(text, style));
+            }
+        }
+    }
+
+    /// Returns the text of the label on a tile, if it has one.
+    #[cfg(feature = "text")]
+    pub fn tile_label<P: Into<Point2>>(&self, point: P) -> Option<&str> {
+        self.tile_labels
+            .get(&point.into())
+            .map(|label| label.text.as_str())
+    }
+
+    /// Removes the label of a tile, returning `false` if it had none.
+    #[cfg(feature = "text")]
+    pub fn remove_tile_label<P: Into<Point2>>(&mut self, point: P) -> bool {
+        match self.tile_labels.remove(&point.into()) {
+            Some(label) => {
+                self.removed_label_entities.extend(label.entity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the labels of every tile.
+    #[cfg(feature = "text")]
+    pub fn clear_tile_labels(&mut self) {
+        let entities = self
+            .tile_labels
+            .drain()
+            .filter_map(|(_point, label)| label.entity);
+        self.removed_label_entities.extend(entities);
+    }
+
+    /// The text labels on tiles.
+    #[cfg(feature = "text")]
+    pub(crate) fn tile_labels(&self) -> &HashMap<Point2, TileLabel> {
+        &self.tile_labels
+    }
+
+    /// The text labels on tiles.
+    #[cfg(feature = "text")]
+    pub(crate) fn tile_labels_mut(&mut self) -> &mut HashMap<Point2, TileLabel> {
+        &mut self.tile_labels
+    }
+
+    /// Returns `true` if labels were removed while their entity was spawned.
+    #[cfg(feature = "text")]
+    pub(crate) fn has_removed_label_entities(&self) -> bool {
+        !self.removed_label_entities.is_empty()
+    }
+
+    /// Takes the entities of the labels which were removed while spawned.
+    #[cfg(feature = "text")]
+    pub(crate) fn take_removed_label_entities(&mut self) -> Vec<Entity> {
+        mem::take(&mut self.removed_label_entities)
+    }
+
+    /// Clears a layer of all the tiles.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_render::prelude::*;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .add_layer( TilemapLayer { kind: LayerKind::Dense}, 0)
+    ///     .add_layer( TilemapLayer { kind: LayerKind::Sparse}, 1)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert!(tilemap.clear_layer(0).is_ok());
+    /// assert!(tilemap.clear_layer(1).is_ok());
+    /// assert!(tilemap.clear_layer(2).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// Fails if the layer does not exist or has a locked tile.
+    pub fn clear_layer(&mut self, layer: usize) -> Result<(), TilemapError> {
+        if let Some(l) = self.layers.get(layer) {
+            if l.is_none() {
+                return Err(ErrorKind::LayerDoesNotExist(layer).into());
+            }
+        } else {
+            return Err(ErrorKind::LayerDoesNotExist(layer).into());
+        }
+        if !self.locked_regions.is_empty() || !self.locked_chunks.is_empty() {
+            let chunk_dimensions = self.chunk_dimensions;
+            for (chunk_point, chunk) in self.chunks.iter() {
+                for (z_depth, sprite_order, index, _tile) in chunk.raw_tiles() {
+                    if sprite_order != layer {
+                        continue;
+                    }
+                    let point = Tilemap::chunk_index_to_point(
+                        chunk_dimensions,
+                        *chunk_point,
+                        z_depth,
+                        index,
+                    );
+                    self.check_unlocked(point.into())?;
+                }
+            }
+        }
+
+        for chunk in self.chunks.values_mut() {
+            chunk.clear_layer(layer);
+        }
+        self.record(RecordedEvent::ClearLayer {
+            sprite_order: layer,
+        });
+
+        Ok(())
+    }
+
+    /// Compacts the sprite layers of every chunk and reports the memory that
+    /// was reclaimed.
+    ///
+    /// Every chunk holds a slot for each sprite layer at every z depth, and a
+    /// dense sprite layer stores a tile for every point of the chunk even if
+    /// it has no tiles. Compacting frees the storage of dense sprite layers
+    /// without tiles and the empty slots after the last sprite layer, which
+    /// adds up for deep chunks or when many sprite layers were added. Freed
+    /// storage is allocated again as soon as a tile is set. Chunks are also
+    /// compacted when they are despawned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_dimensions(16, 16, 4)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// let report = tilemap.compact_layers();
+    /// assert_eq!(report.chunks, 1);
+    /// assert!(report.reclaimed_bytes > 0);
+    /// assert_eq!(tilemap.get_tile((1, 1), 0).map(|tile| tile.index), Some(1));
+    /// ```
+    pub fn compact_layers(&mut self) -> CompactionReport {
+        let mut report = CompactionReport::default();
+        for chunk in self.chunks.values_mut() {
+            report.chunks += 1;
+            report.reclaimed_bytes += chunk.compact();
+        }
+        report
+    }
+
+    /// Sets the theme of the tilemap, which maps logical tile ids to the sprite
+    /// indices they are rendered with, replacing any previous theme.
+    ///
+    /// With a theme the sprite index stored in a tile is its logical id. If the
+    /// theme has the id the tile is rendered with the sprite index it maps to,
+    /// otherwise with the id itself. Switching between themes, such as "ruins"
+    /// and "snow", redraws all the spawned chunks without changing any of the
+    /// stored tiles. Click masks are looked up by the rendered sprite index.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_utils::HashMap;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// const GRASS: u32 = 0;
+    /// let mut snow = HashMap::default();
+    /// snow.insert(GRASS, 12);
+    ///
+    /// tilemap.set_theme(snow);
+    /// assert_eq!(tilemap.theme().get(&GRASS), Some(&12));
+    /// ```
+    pub fn set_theme(&mut self, theme: HashMap<u32, usize>) {
+        self.theme = theme;
+        self.mark_all_chunks_modified();
+    }
+
+    /// Removes the theme of the tilemap, so that tiles are rendered with their
+    /// stored sprite index again.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_utils::HashMap;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let mut snow = HashMap::default();
+    /// snow.insert(0, 12);
+    /// tilemap.set_theme(snow);
+    ///
+    /// tilemap.clear_theme();
+    /// assert!(tilemap.theme().is_empty());
+    /// ```
+    pub fn clear_theme(&mut self) {
+        self.set_theme(HashMap::default());
+    }
+
+    /// Returns the theme of the tilemap, which maps logical tile ids to the
+    /// sprite indices they are rendered with.
+    pub fn theme(&self) -> &HashMap<u32, usize> {
+        &self.theme
+    }
+
+    /// Sets the weighted variants which the fills pick from for tiles with a
+    /// sprite index, or removes them with `None`.
+    ///
+    /// See the [`variant`] module for more information.
+    ///
+    /// [`variant`]: crate::variant
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, variant::TileVariants};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.set_tile_variants(1, Some(TileVariants::new().with(1, 3).with(2, 1)));
+    /// assert!(tilemap.tile_variants(1).is_some());
+    ///
+    /// tilemap.set_tile_variants(1, None);
+    /// assert!(tilemap.tile_variants(1).is_none());
+    /// ```
+    pub fn set_tile_variants(&mut self, sprite_index: usize, variants: Option<TileVariants>) {
+        match variants {
+            Some(variants) => self.tile_variants.insert(sprite_index, variants),
+            None => self.tile_variants.remove(&sprite_index),
+        };
+    }
+
+    /// Returns the weighted variants of a sprite index, if it has any.
+    pub fn tile_variants(&self, sprite_index: usize) -> Option<&TileVariants> {
+        self.tile_variants.get(&sprite_index)
+    }
+
+    /// Picks one of the weighted variants of a sprite index for a tile at a
+    /// point, or returns the sprite index if it has no variants.
+    ///
+    /// Variants with a seed always give the same sprite index for the same
+    /// point. Without a seed every pick is rolled anew.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, variant::TileVariants};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.set_tile_variants(1, Some(TileVariants::new().with(1, 3).with(2, 1).seeded(7)));
+    ///
+    /// let sprite_index = tilemap.pick_variant((4, 2), 1);
+    /// assert!(sprite_index == 1 || sprite_index == 2);
+    /// assert_eq!(tilemap.pick_variant((4, 2), 1), sprite_index);
+    /// assert_eq!(tilemap.pick_variant((4, 2), 5), 5);
+    /// ```
+    pub fn pick_variant<P: Into<Point2>>(&mut self, point: P, sprite_index: usize) -> usize {
+        let variants = match self.tile_variants.get(&sprite_index) {
+            Some(variants) => variants,
+            None => return sprite_index,
+        };
+        let roll = match variants.seed() {
+            Some(seed) => Tilemap::position_hash(point, seed),
+            None => {
+                self.variant_rolls = self.variant_rolls.wrapping_add(1);
+                Tilemap::position_hash(point, VARIANT_SALT ^ self.variant_rolls)
+            }
+        };
+        variants.pick(roll).unwrap_or(sprite_index)
+    }
+
+    /// Sets the click mask for all tiles with the given sprite index.
+    ///
+    /// Click masks are used by [`tile_contains_pixel`] to check if a pixel of
+    /// a tile accepts a click. This allows tall sprites that overlap other
+    /// tiles to resolve clicks to the tile which is visually under the cursor.
+    /// If a mask already exists for the sprite index, it is replaced.
+    ///
+    /// [`tile_contains_pixel`]: Tilemap::tile_contains_pixel
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, picking::ClickMask};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// let mask = ClickMask::Inset { left: 8, top: 0, right: 8, bottom: 0 };
+    /// tilemap.set_click_mask(3, mask.clone());
+    ///
+    /// assert_eq!(tilemap.click_mask(3), Some(&mask));
+    /// ```
+    pub fn set_click_mask(&mut self, sprite_index: usize, mask: ClickMask) {
+        self.click_masks.insert(sprite_index, mask);
+    }
+
+    /// Returns a reference to the click mask of a sprite index, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert_eq!(tilemap.click_mask(0), None);
+    /// ```
+    pub fn click_mask(&self, sprite_index: usize) -> Option<&ClickMask> {
+        self.click_masks.get(&sprite_index)
+    }
+
+    /// Removes and returns the click mask of a sprite index, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, picking::ClickMask};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.set_click_mask(3, ClickMask::Inset { left: 8, top: 0, right: 8, bottom: 0 });
+    ///
+    /// assert!(tilemap.remove_click_mask(3).is_some());
+    /// assert!(tilemap.remove_click_mask(3).is_none());
+    /// ```
+    pub fn remove_click_mask(&mut self, sprite_index: usize) -> Option<ClickMask> {
+        self.click_masks.remove(&sprite_index)
+    }
+
+    /// Returns `true` if a tile exists at the point and the pixel accepts a
+    /// click.
+    ///
+    /// The pixel is relative to the bottom left corner of the tile's sprite.
+    /// If no click mask is set for the tile's sprite index then the whole
+    /// sprite accepts clicks.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, picking::ClickMask};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 1, ..Default::default() }).unwrap();
+    /// tilemap.set_click_mask(1, ClickMask::Inset { left: 0, top: 16, right: 0, bottom: 0 });
+    ///
+    /// assert!(tilemap.tile_contains_pixel((1, 1), 0, Vec2::new(4.0, 4.0)));
+    /// assert!(!tilemap.tile_contains_pixel((1, 1), 0, Vec2::new(4.0, 20.0)));
+    /// assert!(!tilemap.tile_contains_pixel((2, 2), 0, Vec2::new(4.0, 4.0)));
+    /// ```
+    pub fn tile_contains_pixel<P>(&self, point: P, sprite_order: usize, pixel: Vec2) -> bool
+    where
+        P: Into<Point3>,
+    {
+        let point: Point3 = point.into();
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(point);
+        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+        match self
+            .chunks
+            .get(&chunk_point)
+            .and_then(|chunk| chunk.get_tile(index, sprite_order, point.z as usize))
+        {
+            Some(tile) => self.sprite_contains_pixel(tile, pixel),
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the pixel of a tile's sprite accepts a click.
+    fn sprite_contains_pixel(&self, tile: &RawTile, pixel: Vec2) -> bool {
+        match self.click_masks.get(&self.themed_sprite_index(tile.index)) {
+            Some(mask) => mask.contains(self.texture_dimensions, pixel),
+            None => {
+                pixel.x >= 0.0
+                    && pixel.y >= 0.0
+                    && pixel.x < self.texture_dimensions.width as f32
+                    && pixel.y < self.texture_dimensions.height as f32
+            }
+        }
+    }
+
+    /// Returns the dimensions of a tile's sprite, which are those of its bit
+    /// mask if it has one and the texture dimensions otherwise.
+    fn sprite_dimensions(&self, tile: &RawTile) -> Vec2 {
+        match self.click_masks.get(&self.themed_sprite_index(tile.index)) {
+            Some(ClickMask::Bits { width, height, .. }) => Vec2::new(*width as f32, *height as f32),
+            _ => Vec2::new(
+                self.texture_dimensions.width as f32,
+                self.texture_dimensions.height as f32,
+            ),
+        }
+    }
+
+    /// Returns the pixel of a tile's sprite under a local position, relative
+    /// to the bottom left corner of the sprite.
+    ///
+    /// The sprite is scaled from the bottom center of its cell and then
+    /// offset, the same as the shader draws it.
+    fn sprite_pixel(&self, point: Point2, tile: &RawTile, local: Vec2) -> Option<Vec2> {
+        if tile.scale.x == 0.0 || tile.scale.y == 0.0 {
+            return None;
+        }
+        let corner = self
+            .tile_polygon(point)
+            .iter()
+            .fold(Vec2::new(f32::MAX, f32::MAX), |corner, vertex| {
+                corner.min(*vertex)
+            });
+        let dimensions = self.sprite_dimensions(tile);
+        let origin =
+            corner + Vec2::new(dimensions.x * (1.0 - tile.scale.x) / 2.0, 0.0) + tile.offset;
+        let mut pixel = (local - origin) / tile.scale;
+        if tile.flip_x {
+            pixel.x = dimensions.x - pixel.x;
+        }
+        if tile.flip_y {
+            pixel.y = dimensions.y - pixel.y;
+        }
+        Some(pixel)
+    }
+
+    /// Writes every chunk of the tilemap to a writer in the versioned binary
+    /// stream format.
+    ///
+    /// The stream can later be loaded with [`load_streaming`].
+    ///
+    /// [`load_streaming`]: Tilemap::load_streaming
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the writer fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// let mut bytes = Vec::new();
+    /// assert!(tilemap.save_streaming(&mut bytes).is_ok());
+    /// assert!(!bytes.is_empty());
+    /// ```
+    pub fn save_streaming<W: Write>(&self, mut writer: W) -> TilemapResult<()> {
+        stream::write_header(&mut writer, self.chunk_dimensions, self.chunks.len() as u32)?;
+        for (point, chunk) in self.chunks.iter() {
+            stream::write_chunk(&mut writer, *point, &chunk.raw_tiles())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes every chunk of the tilemap to a writer as a TMX map which can
+    /// be opened in the Tiled map editor.
+    ///
+    /// The tiles are named after the tiles of the registry and show their
+    /// textures where the registry has their paths. See the [`tmx`] module
+    /// for how the layers and tiles are laid out.
+    ///
+    /// [`tmx`]: crate::tmx
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tilemap is not square or writing to the writer
+    /// fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tileset::TileRegistry};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.insert("grass", 1);
+    ///
+    /// let mut bytes = Vec::new();
+    /// assert!(tilemap.export_tmx(&mut bytes, &registry).is_ok());
+    /// assert!(String::from_utf8(bytes).unwrap().contains(r#"<property name="name" value="grass"/>"#));
+    /// ```
+    #[cfg(feature = "tmx")]
+    pub fn export_tmx<W: Write>(
+        &self,
+        mut writer: W,
+        registry: &TileRegistry,
+    ) -> TilemapResult<()> {
+        if self.topology != GridTopology::Square {
+            return Err(ErrorKind::UnsupportedTopology(self.topology).into());
+        }
+        let mut layers = Vec::new();
+        for z_depth in 0..self.chunk_dimensions.depth as usize {
+            for (sprite_order, layer) in self.layers.iter().enumerate() {
+                if layer.is_some() {
+                    layers.push(TmxLayer::new(
+                        z_depth,
+                        sprite_order,
+                        self.layer_name(sprite_order),
+                    ));
+                }
+            }
+        }
+        let mut points: Vec<&Point2> = self.chunks.keys().collect();
+        points.sort();
+        for point in points {
+            let chunk = match self.chunks.get(point) {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            let bottom_left: Point2 = Tilemap::chunk_tile_point_to_point(
+                self.chunk_dimensions,
+                *point,
+                Point3::new(0, 0, 0),
+            )
+            .into();
+            for (z_depth, sprite_order, index, tile) in chunk.raw_tiles() {
+                if tile.atlas != 0 {
+                    continue;
+                }
+                let layer = layers
+                    .iter_mut()
+                    .find(|layer| layer.is_layer(z_depth, sprite_order));
+                if let Some(layer) = layer {
+                    layer.set_tile(bottom_left, self.chunk_dimensions, index, tile);
+                }
+            }
+        }
+        tmx::write_map(
+            &mut writer,
+            self.texture_dimensions,
+            self.chunk_dimensions,
+            &layers,
+            registry,
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Starts loading chunks from a reader in the versioned binary stream
+    /// format.
+    ///
+    /// Only the header is read immediately. Afterwards the chunks are inserted
+    /// by [`poll_streaming`], which the tilemap plugin runs every frame,
+    /// spending no more than the `budget` each time. This means that the
+    /// whole map never has to be in memory before the first chunk appears. It
+    /// is best to wrap the reader in a buffered reader.
+    ///
+    /// Chunks that already exist have their tiles overwritten by the streamed
+    /// tiles. Any stream already being loaded is replaced.
+    ///
+    /// [`poll_streaming`]: Tilemap::poll_streaming
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header can not be read, is not in the tilemap
+    /// stream format, is of an unsupported version or has different chunk
+    /// dimensions or coordinate width than the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_utils::Duration;
+    /// use std::io::Cursor;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle.clone_weak(), 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let mut bytes = Vec::new();
+    /// tilemap.save_streaming(&mut bytes).unwrap();
+    ///
+    /// let mut loaded = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// let budget = Duration::from_millis(4);
+    ///
+    /// assert!(loaded.load_streaming(Cursor::new(bytes), budget).is_ok());
+    /// assert!(loaded.load_streaming(Cursor::new(vec![0, 1, 2]), budget).is_err());
+    /// ```
+    pub fn load_streaming<R>(&mut self, reader: R, budget: Duration) -> TilemapResult<()>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        self.stream = None;
+        let stream = TilemapStream::new(Box::new(reader), budget, self.chunk_dimensions)?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Inserts chunks from the stream being loaded until its time budget is
+    /// spent, returning the progress.
+    ///
+    /// At least one chunk is inserted per poll. Once every chunk has been
+    /// loaded the stream is dropped. Returns `None` if there is no stream
+    /// being loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the stream fails, in which case the
+    /// stream is dropped. Chunks loaded before the error are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_utils::Duration;
+    /// use std::io::Cursor;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle.clone_weak(), 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_chunk((1, 0)).unwrap();
+    ///
+    /// let mut bytes = Vec::new();
+    /// tilemap.save_streaming(&mut bytes).unwrap();
+    ///
+    /// let mut loaded = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// loaded.load_streaming(Cursor::new(bytes), Duration::from_millis(4)).unwrap();
+    ///
+    /// let progress = loaded.poll_streaming().unwrap().unwrap();
+    /// assert!(progress.is_finished());
+    /// assert_eq!(progress.chunk_count, 2);
+    /// assert!(loaded.contains_chunk((1, 0)));
+    /// assert_eq!(loaded.poll_streaming(), Ok(None));
+    /// ```
+    pub fn poll_streaming(&mut self) -> TilemapResult<Option<StreamProgress>> {
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => return Ok(None),
+        };
+        // Like a real I/O error, an injected one ends the stream.
+        #[cfg(feature = "failure_injection")]
+        if self.take_injected_failure(InjectedFailure::StreamIo) {
+            return Err(ErrorKind::StreamIo(IoErrorKind::Other).into());
+        }
+
+        let start = Instant::now();
+        while let Some((point, tiles)) = stream.read_chunk(&self.layers)? {
+            if let Some(dimensions) = &self.dimensions {
+                dimensions.check_centered_point(point)?;
+            }
+            let layers = &self.layers;
+            let chunk_dimensions = self.chunk_dimensions;
+            if let Some(recording) = &mut self.recording {
+                if !self.chunks.contains_key(&point) {
+                    recording.push(RecordedEvent::InsertChunk { point });
+                }
+                let tiles = tiles
+                    .iter()
+                    .map(|(z_depth, sprite_order, index, raw_tile)| {
+                        let point = Tilemap::chunk_index_to_point(
+                            chunk_dimensions,
+                            point,
+                            *z_depth,
+                            *index,
+                        );
+                        (point, *sprite_order, *raw_tile)
+                    })
+                    .collect();
+                recording.push(RecordedEvent::InsertTiles { tiles });
+            }
+            let chunk = self.chunks.entry(point).or_insert_with(|| {
+                let layer_kinds = layers
+                    .iter()
+                    .map(|x| x.and_then(|y| Some(y.kind)))
+                    .collect::<Vec<Option<LayerKind>>>();
+                Chunk::new(point, &layer_kinds, chunk_dimensions)
+            });
+            for (z_depth, sprite_order, index, raw_tile) in tiles.into_iter() {
+                chunk.set_raw_tile(index, sprite_order, z_depth, raw_tile);
+            }
+            if chunk.has_mesh() {
+                self.chunk_events
+                    .push(TilemapChunkEvent::Modified { point });
+            }
+
+            if start.elapsed() >= stream.budget() {
+                break;
+            }
+        }
+
+        let progress = stream.progress();
+        if !progress.is_finished() {
+            self.stream = Some(stream);
+        }
+        Ok(Some(progress))
+    }
+
+    /// Returns the progress of the stream being loaded, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(tilemap.streaming_progress().is_none());
+    /// ```
+    pub fn streaming_progress(&self) -> Option<StreamProgress> {
+        self.stream.as_ref().map(|stream| stream.progress())
+    }
+
+    /// Returns the tiles of a chunk in the stream format, sorted so that the
+    /// same tiles always give the same bytes.
+    fn chunk_bytes(&self, point: Point2) -> Option<Vec<u8>> {
+        let mut tiles = self.chunks.get(&point)?.raw_tiles();
+        tiles
+            .sort_by_key(|(z_depth, sprite_order, index, _tile)| (*z_depth, *sprite_order, *index));
+        let mut bytes = Vec::new();
+        // Writing to a vector can not fail.
+        stream::write_chunk(&mut bytes, point, &tiles).ok()?;
+        Some(bytes)
+    }
+
+    /// Returns the hash of the tiles of a chunk, or `None` if the chunk does
+    /// not exist.
+    ///
+    /// The hash is the same for the same tiles on every machine, so it can be
+    /// used to tell if the chunks of two tilemaps differ. See the [`sync`]
+    /// module for more information.
+    ///
+    /// [`sync`]: crate::sync
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert_eq!(tilemap.chunk_hash((0, 0)), None);
+    ///
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let empty = tilemap.chunk_hash((0, 0));
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 1, ..Default::default() }).unwrap();
+    ///
+    /// assert!(empty.is_some());
+    /// assert_ne!(tilemap.chunk_hash((0, 0)), empty);
+    /// ```
+    pub fn chunk_hash<P: Into<Point2>>(&self, point: P) -> Option<u64> {
+        self.chunk_bytes(point.into())
+            .map(|bytes| sync::content_hash(&bytes))
+    }
+
+    /// Returns a request for a chunk with the hash of the chunk of this
+    /// tilemap, to be sent to the host.
+    ///
+    /// See the [`sync`] module for more information.
+    ///
+    /// [`sync`]: crate::sync
+    pub fn request_chunk<P: Into<Point2>>(&self, point: P) -> ChunkRequest {
+        let point: Point2 = point.into();
+        ChunkRequest {
+            point,
+            hash: self.chunk_hash(point),
+        }
+    }
+
+    /// Returns the response of this tilemap, as the host, to a request for a
+    /// chunk.
+    ///
+    /// The chunk is only included if the hash of the request differs from the
+    /// hash of the chunk of this tilemap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the point of the request is outside of the
+    /// dimensions of the tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, point::Point2, sync::{ChunkRequest, ChunkResponse}};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let point = Point2::new(0, 0);
+    /// let request = ChunkRequest { point, hash: None };
+    /// assert!(tilemap.respond_to_chunk_request(&request).unwrap().has_chunk());
+    ///
+    /// let request = ChunkRequest { point, hash: tilemap.chunk_hash(point) };
+    /// assert_eq!(tilemap.respond_to_chunk_request(&request), Ok(ChunkResponse::Unchanged { point }));
+    ///
+    /// let point = Point2::new(1, 0);
+    /// let request = ChunkRequest { point, hash: None };
+    /// assert_eq!(tilemap.respond_to_chunk_request(&request), Ok(ChunkResponse::Missing { point }));
+    /// ```
+    pub fn respond_to_chunk_request(&self, request: &ChunkRequest) -> TilemapResult<ChunkResponse> {
+        let point = request.point;
+        if let Some(dimensions) = &self.dimensions {
+            dimensions.check_centered_point(point)?;
+        }
+        let bytes = match self.chunk_bytes(point) {
+            Some(bytes) => bytes,
+            None => return Ok(ChunkResponse::Missing { point }),
+        };
+        if request.hash == Some(sync::content_hash(&bytes)) {
+            return Ok(ChunkResponse::Unchanged { point });
+        }
+        Ok(ChunkResponse::Chunk { point, bytes })
+    }
+
+    /// Applies the response of the host to a request for a chunk, returning
+    /// `true` if the chunk was updated.
+    ///
+    /// An included chunk replaces every tile of the chunk of this tilemap,
+    /// inserting the chunk if needed. Responses without a chunk leave the
+    /// tilemap as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk bytes can not be read or do not fit the
+    /// tilemap, such as when a sprite layer is missing, or the chunk has a
+    /// locked tile.
+    pub fn apply_chunk_response(&mut self, response: &ChunkResponse) -> TilemapResult<bool> {
+        let (point, bytes) = match response {
+            ChunkResponse::Chunk { point, bytes } => (*point, bytes),
+            _ => return Ok(false),
+        };
+        let (chunk_point, tiles) =
+            stream::read_chunk(&mut &bytes[..], self.chunk_dimensions, &self.layers)?;
+        if chunk_point != point {
+            return Err(ErrorKind::InvalidChunkSync.into());
+        }
+        self.replace_chunk_tiles(point, &tiles)?;
+
+        Ok(true)
+    }
+
+    /// Restores a chunk from its bytes in the stream format, such as the
+    /// bytes of a [`TilemapChunkUnloadEvent`], returning the point of the
+    /// chunk.
+    ///
+    /// The tiles replace every tile of the chunk, inserting the chunk if
+    /// needed.
+    ///
+    /// [`TilemapChunkUnloadEvent`]: crate::event::TilemapChunkUnloadEvent
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk bytes can not be read or do not fit the
+    /// tilemap, such as when a sprite layer is missing, or the chunk has a
+    /// locked tile.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, point::Point2};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .persist_unloaded_chunks()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (2, 2), sprite_index: 4, ..Default::default() }).unwrap();
+    /// let hash = tilemap.chunk_hash((0, 0));
+    /// tilemap.delete_chunk((0, 0)).unwrap();
+    ///
+    /// // The tilemap systems hand the chunk out with an unload event.
+    /// let unloaded = tilemap.take_unloaded_chunks().remove(0);
+    /// assert!(unloaded.removed);
+    ///
+    /// assert_eq!(tilemap.restore_chunk(&unloaded.bytes), Ok(Point2::new(0, 0)));
+    /// assert_eq!(tilemap.chunk_hash(unloaded.point), hash);
+    /// ```
+    pub fn restore_chunk(&mut self, bytes: &[u8]) -> TilemapResult<Point2> {
+        let (point, tiles) =
+            stream::read_chunk(&mut &bytes[..], self.chunk_dimensions, &self.layers)?;
+        self.replace_chunk_tiles(point, &tiles)?;
+
+        Ok(point)
+    }
+
+    /// Replaces every tile of a chunk with the tiles of a chunk read from the
+    /// stream format, inserting the chunk if needed.
+    ///
+    /// Every tile is checked before the chunk is cleared, so on an error the
+    /// chunk is left as it was.
+    fn replace_chunk_tiles(&mut self, point: Point2, tiles: &[StreamTile]) -> TilemapResult<()> {
+        let chunk_dimensions = self.chunk_dimensions;
+
+        if self.is_chunk_locked(point) {
+            // Like every locked error this names a tile, the first locked one.
+            let (chunk_min, chunk_max) = self.chunk_tile_bounds(point);
+            let locked = Tilemap::rect_points(chunk_min, chunk_max)
+                .find(|tile_point| self.is_locked(*tile_point))
+                .unwrap_or(chunk_min);
+            return Err(ErrorKind::RegionLocked(locked).into());
+        }
+        let area = (chunk_dimensions.width * chunk_dimensions.height) as usize;
+        for (z_depth, sprite_order, index, _raw_tile) in tiles.iter() {
+            if *index >= area || *z_depth >= chunk_dimensions.depth as usize {
+                return Err(ErrorKind::TileIndexOutOfBounds(*index, *z_depth).into());
+            }
+            if self.layers.get(*sprite_order).is_none_or(Option::is_none) {
+                return Err(ErrorKind::LayerDoesNotExist(*sprite_order).into());
+            }
+        }
+
+        if !self.chunks.contains_key(&point) {
+            self.insert_chunk(point)?;
+        }
+        let cleared: Vec<(Point3, usize)> = match self.chunks.get(&point) {
+            Some(chunk) => chunk
+                .raw_tiles()
+                .into_iter()
+                .map(|(z_depth, sprite_order, index, _tile)| {
+                    let tile_point =
+                        Tilemap::chunk_index_to_point(chunk_dimensions, point, z_depth, index);
+                    (tile_point, sprite_order)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        self.clear_tiles(cleared)?;
+        self.insert_tiles(
+            tiles
+                .iter()
+                .map(|(z_depth, sprite_order, index, raw_tile)| {
+                    let tile_point =
+                        Tilemap::chunk_index_to_point(chunk_dimensions, point, *z_depth, *index);
+                    Tile::from_raw_tile(tile_point, *sprite_order, raw_tile)
+                }),
+        )?;
+
+        Ok(())
+    }
+
+    /// Starts recording every edit made to the tilemap.
+    ///
+    /// Any recording already in progress is discarded. The recording can be
+    /// taken with [`stop_recording`] then written and replayed as described
+    /// in the [`record`] module.
+    ///
+    /// [`stop_recording`]: Tilemap::stop_recording
+    /// [`record`]: crate::record
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.start_recording();
+    ///
+    /// assert!(tilemap.is_recording());
+    /// ```
+    pub fn start_recording(&mut self) {
+        self.recording = Some(TilemapRecording::default());
+    }
+
+    /// Stops recording and returns the recording, if recording.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert!(tilemap.stop_recording().is_none());
+    ///
+    /// tilemap.start_recording();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    ///
+    /// let recording = tilemap.stop_recording().unwrap();
+    /// assert_eq!(recording.events().len(), 1);
+    /// assert!(!tilemap.is_recording());
+    /// ```
+    pub fn stop_recording(&mut self) -> Option<TilemapRecording> {
+        self.recording.take()
+    }
+
+    /// Returns `true` if the edits to the tilemap are being recorded.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// assert!(!tilemap.is_recording());
+    /// ```
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Records an event on the current frame, if recording.
+    fn record(&mut self, event: RecordedEvent) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(event);
+        }
+    }
+
+    /// Enables the undo and redo history of tile edits, keeping at most
+    /// `limit` steps which can be undone.
+    ///
+    /// Every tile set or cleared from then on is tracked. The tiles of a
+    /// single call, such as [`insert_tiles`] or [`fill_rect`], make up one
+    /// step, and several calls can be made into one step with
+    /// [`begin_history_group`]. Chunks, layers, the fog of war and tiles
+    /// changed through [`get_tile_mut`] are not tracked.
+    ///
+    /// Any history already kept is discarded.
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// [`fill_rect`]: Tilemap::fill_rect
+    /// [`begin_history_group`]: Tilemap::begin_history_group
+    /// [`get_tile_mut`]: Tilemap::get_tile_mut
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.enable_history(100);
+    ///
+    /// tilemap.fill_rect((0, 0), (3, 3), 0, 0, RawTile { index: 1, ..Default::default() }).unwrap();
+    /// tilemap.clear_tile((1, 1), 0).unwrap();
+    ///
+    /// assert!(tilemap.undo().unwrap());
+    /// assert_eq!(tilemap.get_tile((1, 1), 0).map(|tile| tile.index), Some(1));
+    /// assert!(tilemap.undo().unwrap());
+    /// assert_eq!(tilemap.get_tile((3, 3), 0), None);
+    /// assert!(!tilemap.undo().unwrap());
+    ///
+    /// assert!(tilemap.redo().unwrap());
+    /// assert_eq!(tilemap.get_tile((3, 3), 0).map(|tile| tile.index), Some(1));
+    /// ```
+    pub fn enable_history(&mut self, limit: usize) {
+        self.history = Some(TilemapHistory::new(limit));
+    }
+
+    /// Disables the undo and redo history, discarding it.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Returns `true` if the undo and redo history is enabled.
+    pub fn has_history(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// Returns `true` if there is a step of tile edits which can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.can_undo())
+    }
+
+    /// Returns `true` if there is an undone step of tile edits which can be
+    /// redone.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.can_redo())
+    }
+
+    /// Starts a group of tile edits which is undone and redone as a single
+    /// step, until [`end_history_group`] is called.
+    ///
+    /// Groups can be nested, in which case the step ends with the outermost
+    /// group. Does nothing if the history is not enabled.
+    ///
+    /// [`end_history_group`]: Tilemap::end_history_group
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.enable_history(100);
+    ///
+    /// tilemap.begin_history_group();
+    /// tilemap.insert_tile(Tile { point: (0, 0), sprite_index: 1, ..Default::default() }).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 0), sprite_index: 2, ..Default::default() }).unwrap();
+    /// tilemap.end_history_group();
+    ///
+    /// tilemap.undo().unwrap();
+    /// assert_eq!(tilemap.get_tile((0, 0), 0), None);
+    /// assert_eq!(tilemap.get_tile((1, 0), 0), None);
+    /// assert!(!tilemap.can_undo());
+    /// ```
+    pub fn begin_history_group(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.begin_group();
+        }
+    }
+
+    /// Ends a group of tile edits started with [`begin_history_group`].
+    ///
+    /// [`begin_history_group`]: Tilemap::begin_history_group
+    pub fn end_history_group(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.end_group();
+        }
+    }
+
+    /// Undoes the most recent step of tile edits, returning `false` if there
+    /// was nothing to undo.
+    ///
+    /// Any open history groups are ended first. The undone tiles are set and
+    /// cleared like any other edit, so they are recorded and their chunks are
+    /// updated, but they are not tracked as a new step. See
+    /// [`enable_history`] for an example.
+    ///
+    /// [`enable_history`]: Tilemap::enable_history
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a chunk or sprite layer of the step does not exist
+    /// anymore or a tile of the step is locked. Nothing is changed then, and
+    /// the step can be undone again once the error is resolved.
+    pub fn undo(&mut self) -> TilemapResult<bool> {
+        self.apply_history_step(true)
+    }
+
+    /// Redoes the most recently undone step of tile edits, returning `false`
+    /// if there was nothing to redo.
+    ///
+    /// Undone steps can only be redone until a new edit is made. See
+    /// [`enable_history`] for an example.
+    ///
+    /// [`enable_history`]: Tilemap::enable_history
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a chunk or sprite layer of the step does not exist
+    /// anymore or a tile of the step is locked. Nothing is changed then, and
+    /// the step can be redone again once the error is resolved.
+    pub fn redo(&mut self) -> TilemapResult<bool> {
+        self.apply_history_step(false)
+    }
+
+    /// Pauses or resumes the systems of the tilemap.
+    ///
+    /// While paused no chunks are spawned or despawned automatically, no
+    /// meshes are rebuilt and no chunks are prewarmed, streamed or swapped for
+    /// their level of detail. Tiles and chunks can still be edited, their
+    /// events are queued and processed once the tilemap is resumed. Auto
+    /// spawning catches up with the cameras as soon as the tilemap resumes.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.set_paused(true);
+    /// assert!(tilemap.is_paused());
+    ///
+    /// tilemap.set_paused(false);
+    /// assert!(!tilemap.is_paused());
+    /// ```
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused && !paused {
+            self.resumed = true;
+        }
+        self.paused = paused;
+    }
+
+    /// Returns `true` if the systems of the tilemap are paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns `true` once if the tilemap was resumed since the last call.
+    pub(crate) fn take_resumed(&mut self) -> bool {
+        mem::take(&mut self.resumed)
+    }
+
+    /// Injects a failure into the systems of the tilemap which happens the
+    /// next `times` times the tilemap runs into it.
+    ///
+    /// See the [`failure`] module for the failures and how they show up.
+    ///
+    /// [`failure`]: crate::failure
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{failure::InjectedFailure, prelude::*};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// tilemap.inject_failure(InjectedFailure::MissingMesh, 2);
+    /// assert_eq!(tilemap.injected_failures(InjectedFailure::MissingMesh), 2);
+    ///
+    /// tilemap.clear_injected_failures();
+    /// assert_eq!(tilemap.injected_failures(InjectedFailure::MissingMesh), 0);
+    /// ```
+    #[cfg(feature = "failure_injection")]
+    pub fn inject_failure(&mut self, failure: InjectedFailure, times: usize) {
+        self.failures.inject(failure, times);
+    }
+
+    /// Returns the number of times an injected failure is still to happen.
+    #[cfg(feature = "failure_injection")]
+    pub fn injected_failures(&self, failure: InjectedFailure) -> usize {
+        self.failures.pending(failure)
+    }
+
+    /// Removes every injected failure which is still to happen.
+    #[cfg(feature = "failure_injection")]
+    pub fn clear_injected_failures(&mut self) {
+        self.failures.clear();
+    }
+
+    /// Returns `true` if an injected failure is to happen now, counting it
+    /// down.
+    #[cfg(feature = "failure_injection")]
+    pub(crate) fn take_injected_failure(&self, failure: InjectedFailure) -> bool {
+        self.failures.take(failure)
+    }
+
+    /// Undoes or redoes a step of the history, moving it onto the other
+    /// stack if it succeeded.
+    fn apply_history_step(&mut self, undo: bool) -> TilemapResult<bool> {
+        let mut history = match self.history.take() {
+            Some(history) => history,
+            None => return Ok(false),
+        };
+        history.end_groups();
+        let step = if undo {
+            history.pop_undo()
+        } else {
+            history.pop_redo()
+        };
+        let step = match step {
+            Some(step) => step,
+            None => {
+                self.history = Some(history);
+                return Ok(false);
+            }
+        };
+
+        let tiles = step_tiles(&step, undo);
+        // Every tile is checked first, so a step which fails is not left half
+        // applied.
+        let result = self.check_history_tiles(&tiles).and_then(|_| {
+            let mut cleared = Vec::new();
+            let mut inserted = Vec::new();
+            for (point, sprite_order, tile) in tiles {
+                match tile {
+                    Some(raw_tile) => {
+                        inserted.push(Tile::from_raw_tile(point, sprite_order, &raw_tile))
+                    }
+                    None => cleared.push((point, sprite_order)),
+                }
+            }
+            // The history is taken out, so these edits are not tracked.
+            self.clear_tiles(cleared)
+                .and_then(|_| self.insert_tiles(inserted))
+        });
+
+        match (&result, undo) {
+            (Ok(_), true) => history.push_redo(step),
+            (Ok(_), false) => history.push_undo(step),
+            (Err(_), true) => history.push_undo(step),
+            (Err(_), false) => history.push_redo(step),
+        }
+        self.history = Some(history);
+        result.map(|_| true)
+    }
+
+    /// Checks that the tiles of a history step can all be set or cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that setting or clearing the first tile which can not
+    /// be would return.
+    fn check_history_tiles(&self, tiles: &[(Point3, usize, Option<RawTile>)]) -> TilemapResult<()> {
+        for (point, sprite_order, tile) in tiles.iter() {
+            let (chunk_point, chunk_tile) = Tilemap::point_to_chunk_tile(
+                self.chunk_dimensions,
+                Tile {
+                    point: *point,
+                    ..Default::default()
+                },
+            );
+            self.chunk_dimensions
+                .check_encodable_point(chunk_tile.point)?;
+            self.check_unlocked((*point).into())?;
+            if self.layers.get(*sprite_order).is_none() {
+                return Err(ErrorKind::LayerDoesNotExist(*sprite_order).into());
+            }
+            let creates_chunk = tile.is_some() && self.auto_flags.contains(AutoFlags::AUTO_CHUNK);
+            if !creates_chunk && !self.chunks.contains_key(&chunk_point) {
+                return Err(ErrorKind::MissingChunk.into());
+            }
         }
-        let layer_kinds = self
-            .layers
-            .iter()
-            .map(|x| x.and_then(|y| Some(y.kind)))
-            .collect::<Vec<Option<LayerKind>>>();
-        let chunk = Chunk::new(point, &layer_kinds, self.chunk_dimensions);
-        match self.chunks.insert(point, chunk) {
-            Some(_) => Err(ErrorKind::ChunkAlreadyExists(point).into()),
-            None => Ok(()),
+        Ok(())
+    }
+
+    /// Finishes the step of tile edits which is being made, if the history is
+    /// enabled and no group is open.
+    fn finish_history_step(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.finish_step();
         }
     }
 
-    /// Returns `true` if the chunk is contained in the tilemap.
+    /// Moves the recording on to the next frame, if recording.
+    pub(crate) fn next_recording_frame(&mut self) {
+        if let Some(recording) = &mut self.recording {
+            recording.next_frame();
+        }
+    }
+
+    /// Returns the polygon of a tile in tilemap space.
+    fn tile_polygon(&self, point: Point2) -> Vec<Vec2> {
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let tile_point = self.point_to_tile_point(Point3::new(point.x, point.y, 0));
+        outline::tile_polygon(
+            self.topology,
+            chunk_point,
+            Point2::new(tile_point.x, tile_point.y),
+            self.chunk_dimensions,
+            self.texture_dimensions,
+        )
+    }
+
+    /// Returns the outline of a single tile as a closed polygon, counter
+    /// clockwise from the bottom.
+    ///
+    /// The points are in the tilemap's space, which is world space if the
+    /// tilemap is at the origin. The tile is a hexagon or a square following
+    /// the topology of the tilemap and is placed exactly as it is rendered.
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::prelude::*;
+    /// use bevy_math::Vec2;
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
     ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.contains_chunk((0, 0)));
-    /// assert!(!tilemap.contains_chunk((1, 1)));
+    /// let outline = tilemap.tile_outline((0, 0));
+    /// assert_eq!(outline, vec![
+    ///     Vec2::new(0.0, 0.0),
+    ///     Vec2::new(32.0, 0.0),
+    ///     Vec2::new(32.0, 32.0),
+    ///     Vec2::new(0.0, 32.0),
+    /// ]);
     /// ```
-    pub fn contains_chunk<P: Into<Point2>>(&mut self, point: P) -> bool {
-        let point: Point2 = point.into();
-        self.chunks.contains_key(&point)
+    pub fn tile_outline<P: Into<Point2>>(&self, point: P) -> Vec<Vec2> {
+        self.tile_polygon(point.into())
     }
 
-    #[deprecated(
-        since = "0.4.0",
-        note = "Please use `add_layer` method instead with the `TilemapLayer` struct"
-    )]
-    #[doc(hidden)]
-    pub fn add_layer_with_kind(
-        &mut self,
-        kind: LayerKind,
-        sprite_order: usize,
-    ) -> TilemapResult<()> {
-        let layer = TilemapLayer { kind };
-        if let Some(some_kind) = self.layers.get_mut(sprite_order) {
-            if some_kind.is_some() {
-                return Err(ErrorKind::LayerExists(sprite_order).into());
+    /// Returns a world position in the space of a tilemap, undoing the
+    /// translation, rotation and scale of its global transform.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_math::{Quat, Vec2, Vec3};
+    /// use bevy_tilemap::prelude::*;
+    /// use bevy_transform::prelude::*;
+    ///
+    /// let transform = GlobalTransform {
+    ///     translation: Vec3::new(100.0, 0.0, 0.0),
+    ///     rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+    ///     scale: Vec3::new(2.0, 2.0, 1.0),
+    /// };
+    ///
+    /// let local = Tilemap::world_to_local(&transform, Vec2::new(100.0, 64.0));
+    /// assert!((local - Vec2::new(32.0, 0.0)).length() < 0.001);
+    /// ```
+    pub fn world_to_local(tilemap_transform: &GlobalTransform, position: Vec2) -> Vec2 {
+        let position = Vec3::new(position.x, position.y, tilemap_transform.translation.z);
+        let local = (position - tilemap_transform.translation) / tilemap_transform.scale;
+        let local = tilemap_transform.rotation.inverse() * local;
+        Vec2::new(local.x, local.y)
+    }
+
+    /// Returns the point of the tile under a world position, for a tilemap
+    /// with a global transform.
+    ///
+    /// The tilemap may be translated, rotated and scaled. The tile is the
+    /// one whose cell in the grid topology is nearest the position, whether
+    /// or not a tile is set there.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::{Vec2, Vec3};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    /// use bevy_transform::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// // Every tile is 64 by 64 pixels on the screen.
+    /// let transform = GlobalTransform::from_scale(Vec3::new(2.0, 2.0, 1.0));
+    ///
+    /// assert_eq!(tilemap.world_to_point(&transform, Vec2::new(70.0, 10.0)), Point2::new(1, 0));
+    /// assert_eq!(tilemap.world_to_point(&transform, Vec2::new(-10.0, -70.0)), Point2::new(-1, -2));
+    /// ```
+    pub fn world_to_point(&self, tilemap_transform: &GlobalTransform, position: Vec2) -> Point2 {
+        let local = Tilemap::world_to_local(tilemap_transform, position);
+        let width = self.texture_dimensions.width as f32;
+        let height = self.texture_dimensions.height as f32;
+        let center = |point: Point2| {
+            let polygon = self.tile_polygon(point);
+            polygon.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex) / polygon.len() as f32
+        };
+        let distance = |point: Point2| (center(point) - local).length_squared();
+
+        // Hexagonal topologies shift their cells, so the guess is walked
+        // towards the position before the nearest of its neighbours is taken.
+        let mut point = Point2::new(
+            (local.x / width).floor() as Coord,
+            (local.y / height).floor() as Coord,
+        );
+        for _ in 0..8 {
+            let offset = local - center(point);
+            let step = Point2::new(
+                (offset.x / width).round() as Coord,
+                (offset.y / height).round() as Coord,
+            );
+            if step == Point2::new(0, 0) {
+                break;
+            }
+            point += step;
+        }
+        let mut nearest = (point, distance(point));
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let neighbour = Point2::new(point.x + x, point.y + y);
+                let neighbour_distance = distance(neighbour);
+                if neighbour_distance < nearest.1 {
+                    nearest = (neighbour, neighbour_distance);
+                }
             }
-            *some_kind = Some(layer);
         }
+        nearest.0
+    }
 
-        for chunk in self.chunks.values_mut() {
-            chunk.add_sprite_layer(&kind, sprite_order, self.chunk_dimensions);
+    /// Returns the point of the tile of a sprite layer under a world
+    /// position, if a tile is set there and its sprite accepts a click at
+    /// the position.
+    ///
+    /// The tilemap may be translated, rotated and scaled by its global
+    /// transform. Tall, scaled and offset sprites of nearby cells which
+    /// overhang the position are picked as well, and when sprites overlap the
+    /// topmost one in draw order whose click mask accepts the position wins.
+    /// Bit masks give the size of their sprites, which are otherwise the
+    /// texture dimensions. Click masks are tested with
+    /// [`tile_contains_pixel`].
+    ///
+    /// [`tile_contains_pixel`]: Tilemap::tile_contains_pixel
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::{Quat, Vec2};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
+    /// use bevy_transform::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 0), ..Default::default() }).unwrap();
+    ///
+    /// // Turned a quarter to the left, the tile is above the origin.
+    /// let transform =
+    ///     GlobalTransform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+    ///
+    /// assert_eq!(tilemap.pick_tile(&transform, Vec2::new(-10.0, 40.0), 0), Some(Point2::new(1, 0)));
+    /// assert_eq!(tilemap.pick_tile(&transform, Vec2::new(40.0, 10.0), 0), None);
+    /// ```
+    pub fn pick_tile(
+        &self,
+        tilemap_transform: &GlobalTransform,
+        position: Vec2,
+        sprite_order: usize,
+    ) -> Option<Point2> {
+        let point = self.world_to_point(tilemap_transform, position);
+        let local = Tilemap::world_to_local(tilemap_transform, position);
+
+        // Sprites grow up from the bottom center of their cell, so the cells
+        // below and to the sides of the cursor may overhang it.
+        let (width, height) = self.click_masks.values().fold(
+            (
+                self.texture_dimensions.width,
+                self.texture_dimensions.height,
+            ),
+            |(width, height), mask| match mask {
+                ClickMask::Bits {
+                    width: mask_width,
+                    height: mask_height,
+                    ..
+                } => (width.max(*mask_width), height.max(*mask_height)),
+                ClickMask::Inset { .. } => (width, height),
+            },
+        );
+        let columns = (width as f32 / self.texture_dimensions.width as f32).ceil() as Coord + 1;
+        let rows = (height as f32 / self.texture_dimensions.height as f32).ceil() as Coord + 1;
+
+        // Higher depths draw over lower ones, and on a y-sorted layer lower
+        // rows draw over higher ones. Otherwise the cell under the cursor
+        // wins, as it is tried first.
+        let y_sorted = self.is_layer_y_sorted(sprite_order);
+        let mut candidates = vec![point];
+        for y in point.y - rows..=point.y + 1 {
+            for x in point.x - columns..=point.x + columns {
+                let candidate = Point2::new(x, y);
+                if candidate != point {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        let mut topmost: Option<((usize, Coord), Point2)> = None;
+        for candidate in candidates {
+            for z in 0..self.chunk_dimensions.depth as usize {
+                let tile = match self.raw_tile(
+                    Point3::new(candidate.x, candidate.y, z as Coord),
+                    sprite_order,
+                ) {
+                    Some(tile) => tile,
+                    None => continue,
+                };
+                let hit = self
+                    .sprite_pixel(candidate, tile, local)
+                    .is_some_and(|pixel| self.sprite_contains_pixel(tile, pixel));
+                if !hit {
+                    continue;
+                }
+                let key = (z, if y_sorted { -candidate.y } else { 0 });
+                if topmost.is_none_or(|(topmost_key, _)| key > topmost_key) {
+                    topmost = Some((key, candidate));
+                }
+            }
         }
+        topmost.map(|(_, point)| point)
+    }
 
-        Ok(())
+    /// Returns the outlines around a group of tiles.
+    ///
+    /// Edges shared between the tiles are removed so that each returned
+    /// closed polygon outlines a connected area. Areas with holes, such as a
+    /// ring, have an extra polygon for each hole. Each polygon starts at its
+    /// lowest vertex.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    ///
+    /// // Two touching tiles make a single rectangle.
+    /// let outlines = tilemap.tiles_outline(vec![(0, 0), (1, 0)]);
+    /// assert_eq!(outlines.len(), 1);
+    /// assert_eq!(outlines[0].len(), 4);
+    ///
+    /// // Two apart tiles make two squares.
+    /// let outlines = tilemap.tiles_outline(vec![(0, 0), (2, 0)]);
+    /// assert_eq!(outlines.len(), 2);
+    /// ```
+    pub fn tiles_outline<P, I>(&self, points: I) -> Vec<Vec<Vec2>>
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        outline::merge_polygons(
+            points
+                .into_iter()
+                .map(|point| self.tile_polygon(point.into())),
+        )
     }
 
-    /// Adds a layer to the tilemap.
+    /// Returns the outline around a row of tiles which spans every chunk
+    /// currently in the tilemap on that row.
     ///
-    /// ***Warning:*** This is very unwise and costly if there are many chunks
-    /// in the tilemap. You should only add layers when creating the tilemap.
-    /// The meshes for every single chunk has to be recalculated!
+    /// Useful for drawing guides and rulers. Returns nothing if there are no
+    /// chunks on the row.
     ///
-    /// This method creates a layer across all chunks at the specified Z layer.
-    /// For ease of use, it by default makes a layer with a dense
-    /// [`LayerKind`] which is ideal for layers full of sprites.
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
     ///
-    /// If you want to use a layer that is more performant and less data heavy,
-    /// use [`add_layer_with_kind`] with [`LayerKind::Sparse`].
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// If the layer is already the specified layer's kind, then nothing
-    /// happens.
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// # Errors
+    /// let outlines = tilemap.row_outline(3);
+    /// assert_eq!(outlines.len(), 1);
+    /// assert_eq!(outlines[0].len(), 4);
+    /// assert!(tilemap.row_outline(100).is_empty());
+    /// ```
+    pub fn row_outline(&self, y: Coord) -> Vec<Vec<Vec2>> {
+        let chunk_y = self.point_to_chunk_point((0, y)).1;
+        let width = self.chunk_dimensions.width;
+        let points = self
+            .chunks
+            .keys()
+            .filter(|point| point.y == chunk_y)
+            .flat_map(|point| {
+                let start = (point.x as f32 * width as f32 - width as f32 / 2.0).ceil() as Coord;
+                (start..start + width as Coord).map(move |x| Point2::new(x, y))
+            })
+            .collect::<Vec<Point2>>();
+        self.tiles_outline(points)
+    }
+
+    /// Returns the outline around a column of tiles which spans every chunk
+    /// currently in the tilemap on that column.
     ///
-    /// If a layer is set and a different layer already exists at that Z layer
-    /// then an error is returned regarding that. This is done to prevent
-    /// accidental overwrites of a layer.
+    /// Useful for drawing guides and rulers. Returns nothing if there are no
+    /// chunks on the column.
     ///
     /// # Examples
     /// ```
@@ -974,142 +10341,489 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let layer = TilemapLayer {
-    ///    kind: LayerKind::Sparse,
-    ///    ..Default::default()
-    /// };
     /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_chunk((0, 1)).unwrap();
     ///
-    /// assert!(tilemap.add_layer(layer, 2).is_ok());
-    /// assert!(tilemap.add_layer(layer, 2).is_err());
+    /// let outlines = tilemap.column_outline(-4);
+    /// assert_eq!(outlines.len(), 1);
+    /// assert_eq!(outlines[0].len(), 4);
+    /// ```
+    pub fn column_outline(&self, x: Coord) -> Vec<Vec<Vec2>> {
+        let chunk_x = self.point_to_chunk_point((x, 0)).0;
+        let height = self.chunk_dimensions.height;
+        let points = self
+            .chunks
+            .keys()
+            .filter(|point| point.x == chunk_x)
+            .flat_map(|point| {
+                let start = (point.y as f32 * height as f32 - height as f32 / 2.0).ceil() as Coord;
+                (start..start + height as Coord).map(move |y| Point2::new(x, y))
+            })
+            .collect::<Vec<Point2>>();
+        self.tiles_outline(points)
+    }
+
+    /// Returns the smallest rectangle of points, as its bottom left and top
+    /// right corners, which contains every tile of the tilemap or only the
+    /// tiles of a z depth.
+    ///
+    /// Returns `None` if there are no tiles. Chunks which lie within the
+    /// rectangle found so far are skipped, so mostly only the chunks on the
+    /// edges of the map are scanned. This is useful to fit a camera to
+    /// everything that was built or to crop an export.
+    ///
+    /// # Examples
     /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{point::Point2, prelude::*};
     ///
-    /// [`add_layer_with_kind`]: Tilemap::add_layer_with_kind
-    /// [`LayerKind`]: crate::chunk::LayerKind
-    /// [`LayerKind::Sparse`]: crate::chunk::LayerKind::Sparse
-    pub fn add_layer(&mut self, layer: TilemapLayer, sprite_layer: usize) -> TilemapResult<()> {
-        if let Some(inner_layer) = self.layers.get_mut(sprite_layer) {
-            if inner_layer.is_some() {
-                return Err(ErrorKind::LayerExists(sprite_layer).into());
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.used_bounds(None), None);
+    ///
+    /// tilemap.insert_tile(Tile { point: (-40, 3), ..Default::default() }).unwrap();
+    /// tilemap.insert_tile(Tile { point: (7, 50), ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.used_bounds(None), Some((Point2::new(-40, 3), Point2::new(7, 50))));
+    /// assert_eq!(tilemap.used_bounds(Some(1)), None);
+    /// ```
+    pub fn used_bounds(&self, z_depth: Option<usize>) -> Option<(Point2, Point2)> {
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let mut bounds: Option<(Point2, Point2)> = None;
+        for (chunk_point, chunk) in self.chunks.iter() {
+            let chunk_min = Tilemap::chunk_tile_point_to_point(
+                self.chunk_dimensions,
+                *chunk_point,
+                Point3::new(0, 0, 0),
+            );
+            if let Some((min, max)) = bounds {
+                if chunk_min.x >= min.x
+                    && chunk_min.y >= min.y
+                    && chunk_min.x + width - 1 <= max.x
+                    && chunk_min.y + height - 1 <= max.y
+                {
+                    continue;
+                }
             }
-            *inner_layer = Some(layer);
+            let (chunk_tiles_min, chunk_tiles_max) =
+                match chunk.tile_bounds(self.chunk_dimensions, z_depth) {
+                    Some(chunk_bounds) => chunk_bounds,
+                    None => continue,
+                };
+            let tiles_min = Point2::new(
+                chunk_min.x + chunk_tiles_min.x,
+                chunk_min.y + chunk_tiles_min.y,
+            );
+            let tiles_max = Point2::new(
+                chunk_min.x + chunk_tiles_max.x,
+                chunk_min.y + chunk_tiles_max.y,
+            );
+            bounds = Some(match bounds {
+                Some((min, max)) => (
+                    Point2::new(min.x.min(tiles_min.x), min.y.min(tiles_min.y)),
+                    Point2::new(max.x.max(tiles_max.x), max.y.max(tiles_max.y)),
+                ),
+                None => (tiles_min, tiles_max),
+            });
         }
+        bounds
+    }
 
-        let mut layers = 0;
-        for layer in self.layers().iter() {
-            if layer.is_some() {
-                layers += 1;
+    /// Returns the rectangle in tilemap space which bounds every tile of the
+    /// tilemap, if it has dimensions.
+    ///
+    /// On hex topologies the staggered edges of the tilemap are inside the
+    /// rectangle.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle.clone_weak())
+    ///     .dimensions(1, 1)
+    ///     .chunk_dimensions(4, 4, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let bounds = tilemap.bounds().unwrap();
+    /// assert_eq!(bounds.min, Vec2::new(-64.0, -64.0));
+    /// assert_eq!(bounds.max, Vec2::new(64.0, 64.0));
+    ///
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert!(tilemap.bounds().is_none());
+    /// ```
+    pub fn bounds(&self) -> Option<Rect> {
+        let (min, max) = self.point_bounds()?;
+        // Only the tiles on the edges can reach the bounds.
+        let rows = (min.x..=max.x).flat_map(|x| vec![Point2::new(x, min.y), Point2::new(x, max.y)]);
+        let columns =
+            (min.y..=max.y).flat_map(|y| vec![Point2::new(min.x, y), Point2::new(max.x, y)]);
+        let mut bounds = Rect {
+            min: Vec2::new(f32::MAX, f32::MAX),
+            max: Vec2::new(f32::MIN, f32::MIN),
+        };
+        for point in rows.chain(columns) {
+            for vertex in self.tile_polygon(point) {
+                bounds.min = bounds.min.min(vertex);
+                bounds.max = bounds.max.max(vertex);
             }
         }
-        let chunk_mesh = ChunkMesh::new(self.chunk_dimensions, layers, self.layer_offset);
-        self.chunk_mesh = chunk_mesh;
+        Some(bounds)
+    }
 
-        self.chunk_events.send(TilemapChunkEvent::AddLayer {
-            layer_kind: layer.kind,
-            sprite_layer,
-        });
+    /// Returns the bottom left and top right points of the tilemap, if it has
+    /// dimensions.
+    pub(crate) fn point_bounds(&self) -> Option<(Point2, Point2)> {
+        let dimensions = self.dimensions?;
+        let width = self.chunk_dimensions.width as Coord;
+        let height = self.chunk_dimensions.height as Coord;
+        let min = Tilemap::chunk_tile_point_to_point(
+            self.chunk_dimensions,
+            Point2::new(dimensions.x_min(), dimensions.y_min()),
+            Point3::new(0, 0, 0),
+        );
+        let max = Tilemap::chunk_tile_point_to_point(
+            self.chunk_dimensions,
+            Point2::new(dimensions.x_max(), dimensions.y_max()),
+            Point3::new(width - 1, height - 1, 0),
+        );
+        Some((min.into(), max.into()))
+    }
 
-        Ok(())
+    /// Returns four walls of a thickness around the [`bounds`] of the tilemap
+    /// in tilemap space, if it has dimensions.
+    ///
+    /// The walls are in the order left, right, bottom and top, where the left
+    /// and right walls cover the corners. They are meant to be turned into
+    /// static colliders of a physics engine so that entities can not leave the
+    /// tilemap, such as with a cuboid with the center and half of the size of
+    /// each wall.
+    ///
+    /// [`bounds`]: Tilemap::bounds
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .dimensions(1, 1)
+    ///     .chunk_dimensions(4, 4, 1)
+    ///     .texture_dimensions(32, 32)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// let [left, _right, bottom, _top] = tilemap.boundary_walls(8.0).unwrap();
+    /// assert_eq!(left.min, Vec2::new(-72.0, -72.0));
+    /// assert_eq!(left.max, Vec2::new(-64.0, 72.0));
+    /// assert_eq!(bottom.min, Vec2::new(-64.0, -72.0));
+    /// assert_eq!(bottom.max, Vec2::new(64.0, -64.0));
+    /// ```
+    pub fn boundary_walls(&self, thickness: f32) -> Option<[Rect; 4]> {
+        let Rect { min, max } = self.bounds()?;
+        Some([
+            Rect {
+                min: Vec2::new(min.x - thickness, min.y - thickness),
+                max: Vec2::new(min.x, max.y + thickness),
+            },
+            Rect {
+                min: Vec2::new(max.x, min.y - thickness),
+                max: Vec2::new(max.x + thickness, max.y + thickness),
+            },
+            Rect {
+                min: Vec2::new(min.x, min.y - thickness),
+                max: Vec2::new(max.x, min.y),
+            },
+            Rect {
+                min: Vec2::new(min.x, max.y),
+                max: Vec2::new(max.x, max.y + thickness),
+            },
+        ])
     }
 
-    /// Moves a layer from one Z level to another.
+    /// Returns the center of a tile in tilemap space.
+    pub(crate) fn tile_center(&self, point: Point2) -> Vec2 {
+        let polygon = self.tile_polygon(point);
+        let sum = polygon.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex);
+        sum / polygon.len() as f32
+    }
+
+    /// Mirrors all the tiles in a rectangular region across an axis.
+    ///
+    /// The tiles of every sprite layer and z depth are moved to their mirrored
+    /// point, and their flip flags, rotation and offset are remapped so that
+    /// the sprites look mirrored too. The axis runs through the middle of the
+    /// bottom row for [`FlipAxis::X`] and of the left column for
+    /// [`FlipAxis::Y`], with points matched by their position on screen so it
+    /// works with every topology. On some hex topologies this means mirrored
+    /// tiles land outside of the region.
     ///
     /// # Errors
     ///
-    /// If the destination exists, it will throw an error. Likewise, if the
-    /// origin does not exist, it also will throw an error.
+    /// If a tile has no tile to be mirrored onto, such as when flipping an odd
+    /// number of staggered hex rows, an error is returned and no tiles are
+    /// changed. Likewise if a chunk is missing and chunks are not
+    /// automatically created.
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .z_layers(3)
-    ///     .texture_dimensions(32, 32)
-    ///     .add_layer(TilemapLayer { kind: LayerKind::Dense, ..Default::default() }, 0)
-    ///     .add_layer(TilemapLayer { kind: LayerKind::Sparse, ..Default::default() }, 3)
-    ///     .finish()
-    ///     .unwrap();
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (0, 1), sprite_index: 3, ..Default::default() }).unwrap();
     ///
-    /// // If we moved this to layer 3, it would instead fail.
-    /// assert!(tilemap.move_layer(0, 2).is_ok());
-    /// assert!(tilemap.move_layer(3, 2).is_err());
+    /// tilemap.flip_region((0, 0), (3, 1), FlipAxis::X).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((0, 1), 0), None);
+    /// assert_eq!(
+    ///     tilemap.get_tile((3, 1), 0),
+    ///     Some(&RawTile { index: 3, flip_x: true, ..Default::default() })
+    /// );
     /// ```
-    pub fn move_layer(
+    pub fn flip_region<P: Into<Point2>>(
         &mut self,
-        from_sprite_order: usize,
-        to_sprite_order: usize,
+        min: P,
+        max: P,
+        axis: FlipAxis,
     ) -> TilemapResult<()> {
-        if let Some(layer) = self.layers.get(to_sprite_order) {
-            if layer.is_some() {
-                return Err(ErrorKind::LayerExists(to_sprite_order).into());
-            }
+        let (a, b): (Point2, Point2) = (min.into(), max.into());
+        let min = Point2::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point2::new(a.x.max(b.x), a.y.max(b.y));
+
+        // Every tile a mirrored tile can land on, bucketed by half a tile.
+        let half_tile = Vec2::new(
+            self.texture_dimensions.width as f32 / 2.0,
+            self.texture_dimensions.height as f32 / 2.0,
+        );
+        let bucket = |center: Vec2| {
+            (
+                (center.x / half_tile.x).round() as Coord,
+                (center.y / half_tile.y).round() as Coord,
+            )
         };
-        if let Some(layer) = self.layers.get(from_sprite_order) {
-            if Some(layer).is_none() {
-                return Err(ErrorKind::LayerDoesNotExist(from_sprite_order).into());
+        let margin = (max.x - min.x) + (max.y - min.y) + 1;
+        let mut candidates: HashMap<(Coord, Coord), Vec<(Vec2, Point2)>> = HashMap::default();
+        for y in min.y - margin..=max.y + margin {
+            for x in min.x - margin..=max.x + margin {
+                let point = Point2::new(x, y);
+                let center = self.tile_center(point);
+                candidates
+                    .entry(bucket(center))
+                    .or_default()
+                    .push((center, point));
             }
         }
+        let nearest = |center: Vec2| {
+            let (bucket_x, bucket_y) = bucket(center);
+            let mut nearest: Option<(f32, Point2)> = None;
+            for y in bucket_y - 1..=bucket_y + 1 {
+                for x in bucket_x - 1..=bucket_x + 1 {
+                    for (candidate, point) in candidates.get(&(x, y)).into_iter().flatten() {
+                        let difference = (*candidate - center).abs();
+                        if difference.x > half_tile.x / 2.0 || difference.y > half_tile.y / 2.0 {
+                            continue;
+                        }
+                        let distance = difference.length_squared();
+                        if let Some((nearest_distance, _)) = nearest {
+                            if distance >= nearest_distance {
+                                continue;
+                            }
+                        }
+                        nearest = Some((distance, *point));
+                    }
+                }
+            }
+            nearest.map(|(_, point)| point)
+        };
 
-        self.layers.swap(from_sprite_order, to_sprite_order);
-        for chunk in self.chunks.values_mut() {
-            chunk.move_sprite_layer(from_sprite_order, to_sprite_order);
+        let first = self.tile_center(min);
+        let mirror = match axis {
+            FlipAxis::X => {
+                let axis_x = first.x + self.tile_center(Point2::new(max.x, min.y)).x;
+                Vec2::new(axis_x, 0.0)
+            }
+            FlipAxis::Y => {
+                let axis_y = first.y + self.tile_center(Point2::new(min.x, max.y)).y;
+                Vec2::new(0.0, axis_y)
+            }
+        };
+
+        let mut cleared = Vec::new();
+        let mut tiles = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let point = Point2::new(x, y);
+                let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+                let chunk = if let Some(chunk) = self.chunks.get(&chunk_point) {
+                    chunk
+                } else {
+                    continue;
+                };
+                let mut destination = None;
+                for z in 0..self.chunk_dimensions.depth as Coord {
+                    let tile_point = self.point_to_tile_point(Point3::new(x, y, z));
+                    let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+                    for sprite_order in 0..self.layers.len() {
+                        let raw_tile = if let Some(raw_tile) =
+                            chunk.get_tile(index, sprite_order, z as usize)
+                        {
+                            raw_tile
+                        } else {
+                            continue;
+                        };
+                        let destination = match destination {
+                            Some(destination) => destination,
+                            None => {
+                                let center = self.tile_center(point);
+                                let mirrored = match axis {
+                                    FlipAxis::X => Vec2::new(mirror.x - center.x, center.y),
+                                    FlipAxis::Y => Vec2::new(center.x, mirror.y - center.y),
+                                };
+                                let found =
+                                    nearest(mirrored).ok_or(ErrorKind::UnmirrorableTile(point))?;
+                                destination = Some(found);
+                                found
+                            }
+                        };
+                        cleared.push((Point3::new(x, y, z), sprite_order));
+                        tiles.push(Tile::from_raw_tile(
+                            Point3::new(destination.x, destination.y, z),
+                            sprite_order,
+                            &raw_tile.mirrored(axis),
+                        ));
+                    }
+                }
+            }
         }
 
-        Ok(())
+        if !self.auto_flags.contains(AutoFlags::AUTO_CHUNK) {
+            for tile in tiles.iter() {
+                let chunk_point: Point2 = self.point_to_chunk_point(tile.point).into();
+                if !self.chunks.contains_key(&chunk_point) {
+                    return Err(ErrorKind::MissingChunk.into());
+                }
+            }
+        }
+        for (point, _sprite_order) in cleared.iter() {
+            self.check_unlocked((*point).into())?;
+        }
+        for tile in tiles.iter() {
+            self.check_unlocked(tile.point.into())?;
+        }
+        self.begin_history_group();
+        let result = self.clear_tiles(cleared);
+        let result = result.and_then(|_| self.insert_tiles(tiles));
+        self.end_history_group();
+        result
     }
 
-    /// Removes a layer from the tilemap and inner chunks.
+    /// Copies the tiles of a rectangular region between two corners, including
+    /// both corners, into a brush.
     ///
-    /// **Warning**: This is destructive if you have tiles that exist on that
-    /// layer. If you want to add them back in, better to use the [`move_layer`]
-    /// method instead.
+    /// The tiles of every z depth are copied, from the given sprite orders or
+    /// from every sprite layer if `None`. Points in chunks which do not exist
+    /// are copied as empty. See the [`brush`] module for more information.
     ///
-    /// This method takes in a Z layer which is then flagged for deletion. If
-    /// the layer already does not exist, it does nothing.
+    /// [`brush`]: crate::brush
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.fill_rect((0, 0), (3, 3), 0, 0, RawTile { index: 1, ..Default::default() }).unwrap();
     ///
-    /// tilemap.add_layer(TilemapLayer { kind: LayerKind::Sparse, ..Default::default() }, 1);
-    ///
-    /// tilemap.remove_layer(1);
+    /// let brush = tilemap.copy_region((2, 2), (5, 5), Some(&[0]));
+    /// assert_eq!((brush.width(), brush.height()), (4, 4));
+    /// assert_eq!(brush.len(), 4);
     /// ```
-    ///
-    /// [`move_layer`]: Tilemap::move_layer
-    pub fn remove_layer(&mut self, z: usize) {
-        if let Some(layer) = self.layers.get_mut(z) {
-            *layer = None;
-        } else {
-            return;
-        }
+    pub fn copy_region<P: Into<Point2>>(
+        &self,
+        min: P,
+        max: P,
+        sprite_orders: Option<&[usize]>,
+    ) -> TileBrush {
+        let (a, b): (Point2, Point2) = (min.into(), max.into());
+        let min = Point2::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point2::new(a.x.max(b.x), a.y.max(b.y));
+        let all_sprite_orders: Vec<usize> = (0..self.layers.len()).collect();
+        let sprite_orders = sprite_orders.unwrap_or(&all_sprite_orders);
 
-        for chunk in self.chunks.values_mut() {
-            chunk.remove_sprite_layer(z);
+        let mut tiles = Vec::new();
+        for point in Tilemap::rect_points(min, max) {
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let chunk = if let Some(chunk) = self.chunks.get(&chunk_point) {
+                chunk
+            } else {
+                continue;
+            };
+            for z in 0..self.chunk_dimensions.depth as Coord {
+                let tile_point = self.point_to_tile_point(Point3::new(point.x, point.y, z));
+                let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+                for sprite_order in sprite_orders.iter() {
+                    if let Some(raw_tile) = chunk.get_tile(index, *sprite_order, z as usize) {
+                        let brush_point = Point3::new(point.x - min.x, point.y - min.y, z);
+                        tiles.push((brush_point, *sprite_order, *raw_tile));
+                    }
+                }
+            }
         }
+
+        TileBrush::new(
+            (max.x - min.x + 1) as u32,
+            (max.y - min.y + 1) as u32,
+            self.topology,
+            tiles,
+        )
     }
 
-    /// Spawns a chunk at a given index or coordinate.
+    /// Stamps the tiles of a brush with the bottom left corner of the brush at
+    /// a point.
     ///
-    /// Does nothing if the chunk does not exist.
+    /// Only the tiles in the brush are set, empty points of the brush leave
+    /// the tiles below them as they are. All the tiles are set in one go with
+    /// [`insert_tiles`].
     ///
     /// # Errors
     ///
-    /// If the coordinate or index is out of bounds.
+    /// Returns the same errors as [`insert_tiles`].
     ///
     /// # Examples
     /// ```
@@ -1120,43 +10834,36 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
-    ///     .texture_dimensions(32, 32)
-    ///     .finish()
-    ///     .unwrap();
-    ///
-    /// tilemap.insert_chunk((0, 0));
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 7, ..Default::default() }).unwrap();
     ///
-    /// // Ideally you should want to set some tiles here else nothing will
-    /// // display in the render...
+    /// let brush = tilemap.copy_region((0, 0), (2, 2), None);
+    /// tilemap.paste(&brush, (-8, -8)).unwrap();
     ///
-    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.spawn_chunk((1, 1)).is_err());
-    /// assert!(tilemap.spawn_chunk((-1, -1)).is_err());
+    /// assert_eq!(tilemap.get_tile((-7, -7), 0).map(|tile| tile.index), Some(7));
     /// ```
-    pub fn spawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point: Point2 = point.into();
-        if let Some(dimensions) = &self.dimensions {
-            dimensions.check_point(point)?;
-        }
-
-        if self.spawned.contains(&(point.x, point.y)) {
-            return Ok(());
-        } else {
-            self.chunk_events.send(TilemapChunkEvent::Spawned { point });
-        }
-
-        Ok(())
+    ///
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn paste<P: Into<Point2>>(&mut self, brush: &TileBrush, point: P) -> TilemapResult<()> {
+        let origin: Point2 = point.into();
+        let tiles = brush.tiles().map(|(point, sprite_order, raw_tile)| {
+            let point = Point3::new(origin.x + point.x, origin.y + point.y, point.z);
+            Tile::from_raw_tile(point, sprite_order, raw_tile)
+        });
+        self.insert_tiles(tiles)
     }
 
-    /// Spawns a chunk at a given tile point.
+    /// Returns the neighbours of a point, following the topology of the
+    /// tilemap.
     ///
-    /// # Errors
+    /// On hex topologies these are the six tiles sharing an edge, see the
+    /// [`hex`] module. On a square topology these are the four tiles sharing
+    /// an edge, and the four sharing a corner too if `diagonals` is set, see
+    /// the [`square`] module.
     ///
-    /// If the coordinate or index is out of bounds or if the chunk does not
-    /// exist, an error will be returned.
+    /// [`hex`]: crate::hex
+    /// [`square`]: crate::square
     ///
     /// # Examples
     /// ```
@@ -1167,36 +10874,26 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .chunk_dimensions(32, 32, 1)
-    ///     .texture_dimensions(32, 32)
-    ///     .dimensions(1, 1)
-    ///     .finish()
-    ///     .unwrap();
-    ///
-    /// let point = (15, 15);
-    /// let sprite_index = 0;
-    /// let tile = Tile { point, sprite_index, ..Default::default() };
-    ///
-    /// tilemap.insert_tile(tile);
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
     ///
-    /// assert!(tilemap.spawn_chunk_containing_point(point).is_ok());
-    /// assert!(tilemap.spawn_chunk_containing_point((16, 16)).is_err());
-    /// assert!(tilemap.spawn_chunk_containing_point((-18, -18)).is_err());
+    /// assert_eq!(tilemap.neighbours((0, 0), false).len(), 4);
+    /// assert_eq!(tilemap.neighbours((0, 0), true).len(), 8);
     /// ```
-    pub fn spawn_chunk_containing_point<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point = self.point_to_chunk_point(point);
-        self.spawn_chunk(point)
+    pub fn neighbours<P: Into<Point2>>(&self, point: P, diagonals: bool) -> Vec<Point2> {
+        match self.topology {
+            GridTopology::Square if diagonals => square::neighbours8(point).to_vec(),
+            GridTopology::Square => square::neighbours4(point).to_vec(),
+            topology => hex::neighbours(topology, point).to_vec(),
+        }
     }
 
-    /// De-spawns a spawned chunk at a given index or coordinate.
+    /// Returns the number of steps between two points, where each step moves
+    /// to one of the [`neighbours`] of a point.
     ///
-    /// If the chunk is not spawned this will result in nothing.
-    ///
-    /// # Errors
+    /// On a square topology this is the Chebyshev distance if `diagonals` is
+    /// set and the Manhattan distance otherwise.
     ///
-    /// If the coordinate or index is out of bounds, an error will be returned.
+    /// [`neighbours`]: Tilemap::neighbours
     ///
     /// # Examples
     /// ```
@@ -1207,443 +10904,722 @@ impl Tilemap {
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
+    /// let tilemap = TilemapBuilder::new()
     ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
     ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexY)
     ///     .finish()
     ///     .unwrap();
     ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    ///
-    /// // Ideally you should want to set some tiles here else nothing will
-    /// // display in the render...
-    ///
-    /// assert!(tilemap.spawn_chunk((0, 0)).is_ok());
-    ///
-    /// // Later a frame or more on...
-    ///
-    /// assert!(tilemap.despawn_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.despawn_chunk((-1, -1)).is_err());
+    /// assert_eq!(tilemap.distance((0, 0), (2, -1), false), 2);
     /// ```
-    pub fn despawn_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point: Point2 = point.into();
-        if let Some(dimensions) = &self.dimensions {
-            dimensions.check_point(point)?;
-        }
-
-        self.spawned.remove(&(point.x, point.y));
-
-        if self.chunks.get_mut(&point).is_some() {
-            self.chunk_events
-                .send(TilemapChunkEvent::Despawned { point });
-            Ok(())
-        } else {
-            Err(ErrorKind::MissingChunk.into())
+    pub fn distance<P: Into<Point2>>(&self, a: P, b: P, diagonals: bool) -> u32 {
+        match self.topology {
+            GridTopology::Square if diagonals => square::chebyshev_distance(a, b),
+            GridTopology::Square => square::manhattan_distance(a, b),
+            topology => hex::distance(topology, a, b),
         }
     }
 
-    /// Destructively removes a chunk at a coordinate position and despawns them
-    /// if needed.
+    /// Stitches another tilemap onto an edge of the tilemap, so that a
+    /// [`StitchedTilemaps`] view reads the tiles past that edge from it.
     ///
-    /// Internally, this sends an event to the tilemap's system flagging which
-    /// chunks must be removed by index and entity. A chunk is not recoverable
-    /// if this action is done.
+    /// The offset is the number of tiles the stitched tilemap is shifted along
+    /// the edge, starting from the bottom or left corner of the tilemap.
+    /// Stitching an edge again replaces the tilemap stitched onto it. Both
+    /// tilemaps need dimensions, and the other tilemap is usually stitched
+    /// back onto the opposite edge.
     ///
-    /// Does nothing if the chunk does not exist.
+    /// See the [`stitch`] module for more information.
+    ///
+    /// [`StitchedTilemaps`]: crate::stitch::StitchedTilemaps
+    /// [`stitch`]: crate::stitch
     ///
     /// # Errors
     ///
-    /// If the coordinate or index is out of bounds, an error will be returned.
+    /// Returns an error if the tilemap has no dimensions.
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_ecs::entity::Entity;
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::{prelude::*, stitch::MapEdge};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(3, 3)
+    ///     .texture_atlas(texture_atlas_handle.clone_weak())
+    ///     .dimensions(2, 2)
     ///     .texture_dimensions(32, 32)
     ///     .finish()
     ///     .unwrap();
     ///
-    /// // Add some chunks.
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.insert_chunk((1, 1)).is_ok());
+    /// let east = Entity::new(1);
+    /// assert!(tilemap.stitch_edge(MapEdge::Right, east, 0).is_ok());
+    /// assert_eq!(tilemap.stitched_edge(MapEdge::Right).map(|stitch| stitch.entity), Some(east));
     ///
-    /// assert!(tilemap.remove_chunk((0, 0)).is_ok());
-    /// assert!(tilemap.remove_chunk((1, 1)).is_ok());
-    /// assert!(tilemap.remove_chunk((-2, -2)).is_err());
+    /// let mut endless = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// assert!(endless.stitch_edge(MapEdge::Right, east, 0).is_err());
     /// ```
-    pub fn remove_chunk<P: Into<Point2>>(&mut self, point: P) -> TilemapResult<()> {
-        let point = point.into();
-        self.despawn_chunk(point)?;
+    pub fn stitch_edge(
+        &mut self,
+        edge: MapEdge,
+        entity: Entity,
+        offset: Coord,
+    ) -> TilemapResult<()> {
+        if self.dimensions.is_none() {
+            return Err(ErrorKind::MissingDimensions.into());
+        }
+        self.stitches.insert(edge, Stitch { entity, offset });
+        Ok(())
+    }
 
-        self.chunks.remove(&point);
+    /// Removes the tilemap stitched onto an edge, returning it if there was
+    /// one.
+    pub fn unstitch_edge(&mut self, edge: MapEdge) -> Option<Stitch> {
+        self.stitches.remove(&edge)
+    }
 
-        Ok(())
+    /// Returns the tilemap stitched onto an edge, if any.
+    pub fn stitched_edge(&self, edge: MapEdge) -> Option<Stitch> {
+        self.stitches.get(&edge).copied()
     }
 
-    /// Takes a tile point and changes it into a chunk point.
+    /// Returns the tiles which are exactly `radius` steps away from the
+    /// center, where each step moves to a tile sharing an edge.
+    ///
+    /// On hex topologies this is a hex ring. On a square topology this is a
+    /// diamond.
+    fn ring_points(&self, center: Point2, radius: u32) -> Vec<Point2> {
+        let mut visited = HashSet::default();
+        visited.insert(center);
+        let mut frontier = vec![center];
+        for _ in 0..radius {
+            let mut next = Vec::new();
+            for point in frontier.iter() {
+                for neighbour in self.neighbours(*point, false) {
+                    if visited.insert(neighbour) {
+                        next.push(neighbour);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        frontier
+    }
+
+    /// Returns the tiles which are at most `radius` steps away from the
+    /// center, where each step moves to a tile sharing an edge.
+    fn disc_points(&self, center: Point2, radius: u32) -> Vec<Point2> {
+        let mut visited = HashSet::default();
+        visited.insert(center);
+        let mut disc = vec![center];
+        let mut frontier = vec![center];
+        for _ in 0..radius {
+            let mut next = Vec::new();
+            for point in frontier.iter() {
+                for neighbour in self.neighbours(*point, false) {
+                    if visited.insert(neighbour) {
+                        next.push(neighbour);
+                    }
+                }
+            }
+            disc.extend(next.iter().copied());
+            frontier = next;
+        }
+        disc
+    }
+
+    /// Finds the cheapest path between two points with A*, returning the
+    /// points along it including both ends.
+    ///
+    /// A sprite layer chosen by the options decides which tiles can be moved
+    /// onto and at what cost, as described in the [`pathfinding`] module.
+    /// Each step moves to a tile sharing an edge, following the topology of
+    /// the tilemap. Returns `None` if there is no path.
+    ///
+    /// [`pathfinding`]: crate::pathfinding
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::prelude::*;
+    /// use bevy_tilemap::{
+    ///     pathfinding::{PathCost, PathOptions},
+    ///     point::Point2,
+    ///     prelude::*,
+    /// };
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    ///
-    /// let tile_point = (15, 15);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((0, 0), chunk_point);
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexOddRows)
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let tile_point = (16, 16);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((1, 1), chunk_point);
+    /// // Wading through the mud costs more than walking around it.
+    /// for y in -2..=2 {
+    ///     tilemap.set_tile_property((0, y), 0, "cost", 20).unwrap();
+    /// }
     ///
-    /// let tile_point = (-16, -16);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((-0, -0), chunk_point);
+    /// let options = PathOptions {
+    ///     cost: PathCost::Property("cost".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let path = tilemap.find_path((-3, 0), (3, 0), &options).unwrap();
     ///
-    /// let tile_point = (-17, -17);
-    /// let chunk_point = tilemap.point_to_chunk_point(tile_point);
-    /// assert_eq!((-1, -1), chunk_point);
+    /// assert_eq!(path.first(), Some(&Point2::new(-3, 0)));
+    /// assert!(!path.contains(&Point2::new(0, 0)));
+    /// assert_eq!(tilemap.find_path((-3, 0), (100, 0), &options), None);
     /// ```
-    pub fn point_to_chunk_point<P: Into<Point2>>(&self, point: P) -> (i32, i32) {
-        let point: Point2 = point.into();
-        let width = self.chunk_dimensions.width as f32;
-        let height = self.chunk_dimensions.height as f32;
-        let x = ((point.x as f32 + width / 2.0) / width).floor() as i32;
-        let y = ((point.y as f32 + height / 2.0) / height).floor() as i32;
-        (x, y)
-    }
-
-    /// Sorts tiles into the chunks they belong to.
-    fn sort_tiles_to_chunks<P, I>(
-        &mut self,
-        tiles: I,
-    ) -> TilemapResult<HashMap<Point2, Vec<Tile<Point3>>>>
-    where
-        P: Into<Point3>,
-        I: IntoIterator<Item = Tile<P>>,
-    {
-        let width = self.chunk_dimensions.width as i32;
-        let height = self.chunk_dimensions.height as i32;
+    pub fn find_path<P: Into<Point2>>(
+        &self,
+        from: P,
+        to: P,
+        options: &PathOptions,
+    ) -> Option<Vec<Point2>> {
+        let from: Point2 = from.into();
+        let to: Point2 = to.into();
+        let step_cost = |point: Point2| self.step_cost(point, options);
 
-        let mut chunk_map: HashMap<Point2, Vec<Tile<Point3>>> = HashMap::default();
-        for tile in tiles.into_iter() {
-            let global_tile_point: Point3 = tile.point.into();
-            let chunk_point: Point2 = self.point_to_chunk_point(global_tile_point).into();
-
-            if let Some(layer) = self.layers.get(tile.sprite_order as usize) {
-                if layer.as_ref().is_none() {
-                    self.add_layer(TilemapLayer::default(), tile.sprite_order as usize)?;
-                }
-            } else {
-                return Err(ErrorKind::LayerDoesNotExist(tile.sprite_order).into());
+        // Every step costs at least 1 and moves at most this far, which keeps
+        // the heuristic from overestimating.
+        let goal_center = self.tile_center(to);
+        let from_center = self.tile_center(from);
+        let step_distance = self
+            .neighbours(from, options.diagonals)
+            .into_iter()
+            .map(|neighbour| self.tile_center(neighbour).distance(from_center))
+            .fold(0.0, f32::max)
+            * 1.01;
+        let heuristic = |point: Point2| {
+            if step_distance <= 0.0 {
+                return 0;
             }
+            (self.tile_center(point).distance(goal_center) / step_distance) as u32
+        };
 
-            let tile_point = Point3::new(
-                global_tile_point.x - (width * chunk_point.x) + (width / 2),
-                global_tile_point.y - (height * chunk_point.y) + (height / 2),
-                global_tile_point.z,
-            );
+        step_cost(to)?;
+        pathfinding::a_star(
+            from,
+            to,
+            |point| self.neighbours(point, options.diagonals),
+            |current, neighbour| self.move_cost(current, neighbour, options),
+            heuristic,
+        )
+    }
 
-            let chunk_tile: Tile<Point3> = Tile {
-                point: tile_point,
-                sprite_order: tile.sprite_order,
-                sprite_index: tile.sprite_index,
-                tint: tile.tint,
-            };
-            if let Some(tiles) = chunk_map.get_mut(&chunk_point) {
-                tiles.push(chunk_tile);
-            } else {
-                let tiles = vec![chunk_tile];
-                chunk_map.insert(chunk_point, tiles);
+    /// Returns the cost of moving from a point onto a neighbour in a path
+    /// search, or `None` if the neighbour blocks movement or a diagonal step
+    /// would cut the corner of a tile which does.
+    fn move_cost(&self, current: Point2, neighbour: Point2, options: &PathOptions) -> Option<u32> {
+        let cost = self.step_cost(neighbour, options)?;
+        if neighbour.x != current.x && neighbour.y != current.y {
+            let corners = [
+                Point2::new(neighbour.x, current.y),
+                Point2::new(current.x, neighbour.y),
+            ];
+            if self.topology == GridTopology::Square
+                && corners
+                    .iter()
+                    .any(|corner| self.step_cost(*corner, options).is_none())
+            {
+                return None;
             }
         }
-        Ok(chunk_map)
+        Some(cost)
     }
 
-    /// Sets many tiles, creating new chunks if needed.
+    /// Returns the cost of moving onto a point in a path search, or `None` if
+    /// it blocks movement or its chunk does not exist.
+    pub(crate) fn step_cost(&self, point: Point2, options: &PathOptions) -> Option<u32> {
+        let point3 = Point3::new(point.x, point.y, options.z_depth as Coord);
+        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+        let chunk = self.chunks.get(&chunk_point)?;
+        let index = self
+            .chunk_dimensions
+            .encode_point_unchecked(self.point_to_tile_point(point3));
+        let tile = chunk.get_tile(index, options.sprite_order, options.z_depth);
+        let properties = chunk
+            .layer_properties(options.sprite_order, options.z_depth)
+            .and_then(|properties| properties.get(&index));
+        options.cost.cost(tile, properties)
+    }
+
+    /// Makes a flow field of the cheapest paths from every point of the
+    /// existing chunks to the nearest of a set of goals.
+    ///
+    /// A sprite layer chosen by the options decides which tiles can be moved
+    /// onto and at what cost, just like with [`find_path`]. Each step moves
+    /// to a tile sharing an edge, following the topology of the tilemap.
+    /// Goals which block movement or are outside of the existing chunks are
+    /// never reached. See the [`pathfinding`] module for more information.
+    ///
+    /// [`find_path`]: Tilemap::find_path
+    /// [`pathfinding`]: crate::pathfinding
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_math::Vec2;
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{
+    ///     pathfinding::{PathCost, PathOptions},
+    ///     point::Point2,
+    ///     prelude::*,
+    /// };
     ///
-    /// If setting a single tile is more preferable, then use the [`insert_tile`]
-    /// method instead.
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// If the chunk does not yet exist, it will create a new one automatically.
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// # Errors
+    /// let options = PathOptions { cost: PathCost::Collision, ..Default::default() };
+    /// let field = tilemap.flow_field(vec![(0, 0), (10, 0)], &options);
     ///
-    /// Returns an error if the given coordinate or index is out of bounds, the
-    /// layer or chunk does not exist. If either the layer or chunk error occurs
-    /// then creating what is missing will resolve it.
+    /// assert_eq!(field.cost((3, 0)), Some(3));
+    /// assert_eq!(field.next_point((3, 0)), Some(Point2::new(2, 0)));
+    /// assert_eq!(field.direction((8, 0)), Vec2::new(1.0, 0.0));
+    /// assert_eq!(field.direction((0, 0)), Vec2::ZERO);
+    /// ```
+    pub fn flow_field<P, I>(&self, goals: I, options: &PathOptions) -> FlowField
+    where
+        P: Into<Point2>,
+        I: IntoIterator<Item = P>,
+    {
+        let goals = goals.into_iter().map(Into::into).collect();
+        let mut field = FlowField::new(goals, options.clone(), self.chunk_dimensions);
+        for chunk_point in self.chunks.keys() {
+            field.insert_chunk(*chunk_point, self.flow_step_costs(*chunk_point, options));
+        }
+        self.spread_flow_field(&mut field, Vec::new(), Vec::new());
+        field
+    }
+
+    /// Brings a flow field up to date with the tiles and chunks of the
+    /// tilemap, returning `true` if anything changed.
     ///
-    /// # Examples
+    /// The paths which are cheaper than any path through a changed tile stay
+    /// as they are and only the rest are searched again, so tiles changing
+    /// far from the goals are quick to update. Inserted chunks are added to
+    /// the field and removed chunks are removed from it. If the chunk
+    /// dimensions changed, the field is made again.
     ///
+    /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_tilemap::{
+    ///     pathfinding::{PathCost, PathOptions},
+    ///     prelude::*,
+    /// };
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
-    ///     .texture_dimensions(32, 32)
-    ///     .finish()
-    ///     .unwrap();
-    ///
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
     /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let mut tiles = vec![
-    ///     Tile { point: (1, 1), sprite_index: 0, ..Default::default() },
-    ///     Tile { point: (2, 2), sprite_index: 1, ..Default::default() },
-    ///     Tile { point: (3, 3), sprite_index: 2, ..Default::default() },
-    /// ];
+    /// let options = PathOptions { cost: PathCost::Collision, ..Default::default() };
+    /// let mut field = tilemap.flow_field(vec![(0, 0)], &options);
+    /// assert_eq!(field.cost((2, 0)), Some(2));
     ///
-    /// // Set multiple tiles and unwrap the result
-    /// tilemap.insert_tiles(tiles).unwrap();
+    /// // A wall between the goal and the point makes the way around longer.
+    /// let wall = (-1..=1).map(|y| Tile { point: (1, y), ..Default::default() });
+    /// tilemap.insert_tiles(wall).unwrap();
     ///
-    /// assert_eq!(tilemap.get_tile((1, 1), 0), Some(&RawTile { index: 0, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((2, 2), 0), Some(&RawTile { index: 1, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((3, 3), 0), Some(&RawTile { index: 2, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((4, 4), 0), None);
+    /// assert!(tilemap.update_flow_field(&mut field));
+    /// assert_eq!(field.cost((2, 0)), Some(6));
+    /// assert_eq!(field.cost((1, 0)), None);
+    /// assert!(!tilemap.update_flow_field(&mut field));
     /// ```
-    ///
-    /// [`insert_tile`]: Tilemap::insert_tile
-    pub fn insert_tiles<P, I>(&mut self, tiles: I) -> TilemapResult<()>
-    where
-        P: Into<Point3>,
-        I: IntoIterator<Item = Tile<P>>,
-    {
-        let chunk_map = self.sort_tiles_to_chunks(tiles)?;
-        for (chunk_point, tiles) in chunk_map.into_iter() {
-            // Is there a better way to do this? Clippy hates if I don't do it
-            // like this talking about constructing regardless yet, here it is,
-            // copying stuff regardless because it doesn't like self in the
-            // `FnOnce`.
-            let layers = self.layers.clone();
-            let chunk_dimensions = self.chunk_dimensions;
-            let chunk = if self.auto_flags.contains(AutoFlags::AUTO_CHUNK) {
-                self.chunks.entry(chunk_point).or_insert_with(|| {
-                    let layer_kinds = layers
-                        .iter()
-                        .map(|x| x.and_then(|y| Some(y.kind)))
-                        .collect::<Vec<Option<LayerKind>>>();
-                    Chunk::new(chunk_point, &layer_kinds, chunk_dimensions)
-                })
-            } else {
-                match self.chunks.get_mut(&chunk_point) {
-                    Some(c) => c,
-                    None => return Err(ErrorKind::MissingChunk.into()),
+    pub fn update_flow_field(&self, field: &mut FlowField) -> bool {
+        let options = field.options().clone();
+        if field.chunk_dimensions() != self.chunk_dimensions {
+            *field = self.flow_field(field.goals().to_vec(), &options);
+            return true;
+        }
+
+        let chunk_point_of = |chunk_point: Point2, index: usize| {
+            let point = Tilemap::chunk_index_to_point(self.chunk_dimensions, chunk_point, 0, index);
+            Point2::new(point.x, point.y)
+        };
+        let mut changed = Vec::new();
+        let mut updated = Vec::new();
+        for chunk_point in self.chunks.keys() {
+            let step_costs = self.flow_step_costs(*chunk_point, &options);
+            let changed_len = changed.len();
+            match field.step_costs(*chunk_point) {
+                Some(old_costs) => {
+                    for (index, (old_cost, cost)) in
+                        old_costs.iter().zip(step_costs.iter()).enumerate()
+                    {
+                        if old_cost != cost {
+                            changed.push(chunk_point_of(*chunk_point, index));
+                        }
+                    }
+                    if changed.len() > changed_len {
+                        updated.push((*chunk_point, step_costs));
+                    }
                 }
-            };
+                None => {
+                    for (index, cost) in step_costs.iter().enumerate() {
+                        if cost.is_some() {
+                            changed.push(chunk_point_of(*chunk_point, index));
+                        }
+                    }
+                    updated.push((*chunk_point, step_costs));
+                }
+            }
+        }
+        let removed: Vec<Point2> = field
+            .chunk_points()
+            .into_iter()
+            .filter(|chunk_point| !self.chunks.contains_key(chunk_point))
+            .collect();
+        for chunk_point in removed.iter() {
+            if let Some(old_costs) = field.step_costs(*chunk_point) {
+                for (index, old_cost) in old_costs.iter().enumerate() {
+                    if old_cost.is_some() {
+                        changed.push(chunk_point_of(*chunk_point, index));
+                    }
+                }
+            }
+        }
+        if updated.is_empty() && removed.is_empty() {
+            return false;
+        }
 
-            for tile in tiles.iter() {
-                let index = self.chunk_dimensions.encode_point_unchecked(tile.point);
-                chunk.set_tile(index, *tile);
+        // A path through a changed tile costs at least as much as the path
+        // from a tile next to it, so every cheaper path stays the same.
+        let threshold = changed
+            .iter()
+            .flat_map(|point| {
+                let mut around = self.neighbours(*point, options.diagonals);
+                around.push(*point);
+                around
+            })
+            .filter_map(|point| field.path_cost(point))
+            .min();
+        for chunk_point in removed {
+            field.remove_chunk(chunk_point);
+        }
+        for (chunk_point, step_costs) in updated {
+            field.insert_chunk(chunk_point, step_costs);
+        }
+        let invalidated =
+            threshold.map_or_else(Vec::new, |threshold| field.invalidate_from(threshold));
+
+        // The paths are spread again from the points next to the forgotten
+        // ones.
+        let mut seeds = Vec::new();
+        for point in invalidated.iter() {
+            for neighbour in self.neighbours(*point, options.diagonals) {
+                if field.cost(neighbour).is_some() {
+                    seeds.push(neighbour);
+                }
             }
+        }
+        changed.extend(invalidated);
+        self.spread_flow_field(field, seeds, changed);
+        true
+    }
 
-            if chunk.mesh().is_some() {
-                self.chunk_events.send(TilemapChunkEvent::Modified {
-                    point: chunk.point(),
-                });
+    /// Returns the cost of moving onto every tile of a chunk in a flow field.
+    fn flow_step_costs(&self, chunk_point: Point2, options: &PathOptions) -> Vec<Option<u32>> {
+        let len = (self.chunk_dimensions.width * self.chunk_dimensions.height) as usize;
+        (0..len)
+            .map(|index| {
+                let point =
+                    Tilemap::chunk_index_to_point(self.chunk_dimensions, chunk_point, 0, index);
+                self.step_cost(Point2::new(point.x, point.y), options)
+            })
+            .collect()
+    }
+
+    /// Spreads the paths of a flow field from its goals and the seeds, then
+    /// sets the next points of the changed points and the points next to
+    /// them.
+    fn spread_flow_field(
+        &self,
+        field: &mut FlowField,
+        mut seeds: Vec<Point2>,
+        mut changed: Vec<Point2>,
+    ) {
+        let options = field.options().clone();
+        for goal in field.goals().to_vec() {
+            if field.path_cost(goal).is_none() || self.step_cost(goal, &options).is_none() {
+                continue;
             }
+            if field.cost(goal) != Some(0) {
+                field.set_path_cost(goal, 0);
+                changed.push(goal);
+            }
+            seeds.push(goal);
         }
+        let lowered = pathfinding::integrate(
+            field,
+            seeds,
+            |point| self.neighbours(point, options.diagonals),
+            |from, to| {
+                self.step_cost(from, &options)?;
+                self.move_cost(from, to, &options)
+            },
+        );
+        changed.extend(lowered);
 
-        Ok(())
+        let mut points: HashSet<Point2> = HashSet::default();
+        for point in changed {
+            points.insert(point);
+            points.extend(self.neighbours(point, options.diagonals));
+        }
+        for point in points {
+            let next = match field.cost(point) {
+                Some(cost) if cost > 0 => self
+                    .neighbours(point, options.diagonals)
+                    .into_iter()
+                    .filter_map(|neighbour| {
+                        let step = self.move_cost(point, neighbour, &options)?;
+                        let cost = field.cost(neighbour)?.saturating_add(step);
+                        Some((cost, neighbour))
+                    })
+                    .min_by_key(|(cost, _)| *cost)
+                    .map(|(_, neighbour)| neighbour),
+                _ => None,
+            };
+            let direction = next.map_or(Vec2::ZERO, |next| {
+                (self.tile_center(next) - self.tile_center(point)).normalize()
+            });
+            field.set_next_point(point, next, direction);
+        }
     }
 
-    /// Sets a single tile at a coordinate position, creating a chunk if necessary.
-    ///
-    /// If you are setting more than one tile at a time, it is highly
-    /// recommended not to run this method! Instead use
-    /// [`insert_tiles`]. Every single tile that is created creates a new
-    /// event. With bulk tiles, it creates 1 event for all.
-    ///
-    /// If the chunk does not yet exist, it will create a new one automatically.
+    /// Returns the outline of the ring of tiles which are exactly `radius`
+    /// steps away from the center tile.
     ///
-    /// [`insert_tiles`]: Tilemap::insert_tiles
+    /// On hex topologies this is a hex ring, which for a radius above 0 is an
+    /// outer and an inner polygon. On a square topology each step moves to a
+    /// tile sharing an edge, so the ring is a diamond. This is useful for range
+    /// indicators.
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_tilemap::prelude::*;
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    ///
-    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexY)
+    ///     .finish()
+    ///     .unwrap();
     ///
-    /// let point = (9, 3);
-    /// let sprite_index = 3;
-    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    /// // A radius of 0 is the center hexagon.
+    /// let outlines = tilemap.ring_outline((0, 0), 0);
+    /// assert_eq!(outlines.len(), 1);
+    /// assert_eq!(outlines[0].len(), 6);
     ///
-    /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE }))
+    /// // Any other radius has an outer and an inner outline.
+    /// assert_eq!(tilemap.ring_outline((0, 0), 2).len(), 2);
     /// ```
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the given coordinate or index is out of bounds.
-    pub fn insert_tile<P: Into<Point3>>(&mut self, tile: Tile<P>) -> TilemapResult<()> {
-        let tiles = vec![tile];
-        self.insert_tiles(tiles)
+    pub fn ring_outline<P: Into<Point2>>(&self, center: P, radius: u32) -> Vec<Vec<Vec2>> {
+        self.tiles_outline(self.ring_points(center.into(), radius))
     }
 
-    /// Clears the tiles at the specified points from the tilemap.
+    /// Returns the points of a line between two points, including both ends.
+    ///
+    /// On a square topology this is a Bresenham line. On hex topologies every
+    /// step moves to the tile sharing an edge which stays closest to the line
+    /// between the centers of both ends. See the [`tilemap_ops`] module for
+    /// more information.
+    ///
+    /// [`tilemap_ops`]: crate::tilemap_ops
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_tilemap::{point::Point2, prelude::*};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
+    /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
     ///
-    /// let mut tiles = vec![
-    ///     Tile { point: (1, 1, 0), ..Default::default() },
-    ///     Tile { point: (2, 2, 0), ..Default::default() },
-    ///     Tile { point: (3, 3, 0), ..Default::default() },
-    /// ];
+    /// let line = tilemap.line_points((0, 0), (3, 3));
+    /// assert_eq!(line.len(), 4);
+    /// assert_eq!(line[2], Point2::new(2, 2));
+    /// ```
+    pub fn line_points<P: Into<Point2>>(&self, from: P, to: P) -> Vec<Point2> {
+        let from: Point2 = from.into();
+        let to: Point2 = to.into();
+        if self.topology == GridTopology::Square {
+            return tilemap_ops::bresenham_line(from, to);
+        }
+
+        let from_center = self.tile_center(from);
+        let to_center = self.tile_center(to);
+        let direction = to_center - from_center;
+        let line_distance = |center: Vec2| {
+            let length_squared = direction.length_squared();
+            if length_squared <= 0.0 {
+                return center.distance(from_center);
+            }
+            let along = ((center - from_center).dot(direction) / length_squared).clamp(0.0, 1.0);
+            center.distance(from_center + direction * along)
+        };
+
+        let mut point = from;
+        let mut points = vec![from];
+        while point != to {
+            // Only stepping closer to the end guarantees that the line ends.
+            let distance = self.tile_center(point).distance(to_center);
+            let mut next: Option<(f32, Point2)> = None;
+            for neighbour in self.neighbours(point, false) {
+                let center = self.tile_center(neighbour);
+                if center.distance(to_center) >= distance {
+                    continue;
+                }
+                let off_line = line_distance(center);
+                if next.is_none_or(|(nearest, _)| off_line < nearest) {
+                    next = Some((off_line, neighbour));
+                }
+            }
+            point = match next {
+                Some((_, next)) => next,
+                None => break,
+            };
+            points.push(point);
+        }
+        points
+    }
+
+    /// Returns the points of a circle around a center.
     ///
-    /// // Set multiple tiles and unwrap the result
-    /// assert!(tilemap.insert_tiles(tiles.clone()).is_ok());
+    /// On a square topology the circle is round. On hex topologies it is the
+    /// hexagon of tiles at most `radius` steps away from the center, and its
+    /// edge is the hex ring `radius` steps away. See the [`tilemap_ops`]
+    /// module for more information.
     ///
-    /// // Then later on... Do note that if this done in the same frame, the
-    /// // tiles will not even exist at all.
-    /// let mut to_remove = vec![
-    ///     ((1, 1), 0),
-    ///     ((2, 2), 0),
-    /// ];
+    /// [`tilemap_ops`]: crate::tilemap_ops
     ///
-    /// tilemap.clear_tiles(to_remove).unwrap();
-    /// assert_eq!(tilemap.get_tile((1, 1, 0), 0), None);
-    /// assert_eq!(tilemap.get_tile((2, 2, 0), 0), None);
-    /// assert_eq!(tilemap.get_tile((3, 3, 0), 0), Some(&RawTile { index: 0, color: Color::WHITE} ));
+    /// # Examples
     /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, tilemap_ops::CircleFill};
     ///
-    /// # Errors
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// An error can occure if the point is outside of the tilemap. This can
-    /// only happen if the tilemap has dimensions.
-    pub fn clear_tiles<P, I>(&mut self, points: I) -> TilemapResult<()>
-    where
-        P: Into<Point3>,
-        I: IntoIterator<Item = (P, usize)>,
-    {
-        let mut tiles = Vec::new();
-        for (point, sprite_order) in points {
-            tiles.push(Tile {
-                point: point.into(),
-                sprite_index: 0,
-                sprite_order,
-                tint: Color::rgba(0.0, 0.0, 0.0, 0.0),
-            });
+    /// let tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .topology(GridTopology::HexY)
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tilemap.circle_points((0, 0), 1, CircleFill::Filled).len(), 7);
+    /// assert_eq!(tilemap.circle_points((0, 0), 2, CircleFill::Hollow).len(), 12);
+    /// ```
+    pub fn circle_points<P: Into<Point2>>(
+        &self,
+        center: P,
+        radius: u32,
+        fill: CircleFill,
+    ) -> Vec<Point2> {
+        let center: Point2 = center.into();
+        if self.topology == GridTopology::Square {
+            return tilemap_ops::square_circle(center, radius, fill);
         }
-        let chunk_map = self.sort_tiles_to_chunks(tiles)?;
-        for (chunk_point, tiles) in chunk_map.into_iter() {
-            let chunk = match self.chunks.get_mut(&chunk_point) {
-                Some(c) => c,
-                None => return Err(ErrorKind::MissingChunk.into()),
-            };
-            for tile in tiles.iter() {
-                let index = self.chunk_dimensions.encode_point_unchecked(tile.point);
-                chunk.remove_tile(index, tile.sprite_order, tile.point.z as usize);
-            }
-
-            self.chunk_events.send(TilemapChunkEvent::Modified {
-                point: chunk.point(),
-            });
+        match fill {
+            CircleFill::Filled => self.disc_points(center, radius),
+            CircleFill::Hollow => self.ring_points(center, radius),
         }
-
-        Ok(())
-    }
-
-    /// Takes a global tile point and returns a tile point in a chunk.
-    fn point_to_tile_point(&self, point: Point3) -> Point3 {
-        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
-        let width = self.chunk_dimensions.width as i32;
-        let height = self.chunk_dimensions.height as i32;
-        Point3::new(
-            point.x - (width * chunk_point.x) + (width / 2),
-            point.y - (height * chunk_point.y) + (height / 2),
-            point.z,
-        )
     }
 
-    /// Clear a single tile at the specified point from the tilemap.
+    /// Returns the points of the region of a sprite layer and z depth which is
+    /// connected to the start and has the same sprite as it, or no tile at
+    /// all.
+    ///
+    /// Points are connected through tiles sharing an edge, following the
+    /// topology of the tilemap. Only points within chunks that exist are part
+    /// of the region, so an empty region is bounded by the existing chunks.
+    /// Returns an empty region if the chunk of the start does not exist.
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_tilemap::{chunk::RawTile, point::Point2, prelude::*};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
+    /// tilemap.fill_row(0, -2, 2, 0, 0, RawTile { index: 4, ..Default::default() }).unwrap();
     ///
-    /// assert!(tilemap.insert_chunk((0, 0)).is_ok());
-    ///
-    /// let point = (3, 1);
-    /// let sprite_index = 1;
-    /// let tile = Tile { point, sprite_index, ..Default::default() };
-    ///
-    /// // Set a single tile and unwrap the result
-    /// assert!(tilemap.insert_tile(tile).is_ok());
+    /// let region = tilemap.flood_fill_points((0, 0), 0, 0);
+    /// assert_eq!(region.len(), 5);
+    /// assert!(!region.contains(&Point2::new(0, 1)));
     ///
-    /// // Later on...
-    /// assert!(tilemap.clear_tile(point, 0).is_ok());
-    /// assert_eq!(tilemap.get_tile((3, 1), 0), None);
+    /// assert!(tilemap.flood_fill_points((100, 100), 0, 0).is_empty());
     /// ```
-    ///
-    /// # Errors
-    ///
-    /// An error can occure if the point is outside of the tilemap. This can
-    /// only happen if the tilemap has dimensions.
-    pub fn clear_tile<P>(&mut self, point: P, sprite_order: usize) -> TilemapResult<()>
-    where
-        P: Into<Point3>,
-    {
-        let points = vec![(point, sprite_order)];
-        self.clear_tiles(points)
+    pub fn flood_fill_points<P: Into<Point2>>(
+        &self,
+        start: P,
+        sprite_order: usize,
+        z_depth: usize,
+    ) -> Vec<Point2> {
+        let start: Point2 = start.into();
+        let sprite_at = |point: Point2| {
+            let chunk_point: Point2 = self.point_to_chunk_point(point).into();
+            let chunk = self.chunks.get(&chunk_point)?;
+            let tile_point =
+                self.point_to_tile_point(Point3::new(point.x, point.y, z_depth as Coord));
+            let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
+            Some(
+                chunk
+                    .get_tile(index, sprite_order, z_depth)
+                    .map(|tile| (tile.atlas, tile.index)),
+            )
+        };
+        let sprite = match sprite_at(start) {
+            Some(sprite) => sprite,
+            None => return Vec::new(),
+        };
+        tilemap_ops::flood_fill(
+            start,
+            |point| self.neighbours(point, false),
+            |point| sprite_at(point) == Some(sprite),
+        )
     }
 
-    /// Gets a raw tile from a given point and z order.
+    /// Sets copies of a raw tile on a line between two points at a sprite order
+    /// and z depth.
     ///
-    /// This is different thant he usual [`Tile`] struct in that it only
-    /// contains the sprite index and the tint.
+    /// See [`line_points`] for the points of the line. All the tiles are set in
+    /// one go with [`insert_tiles`].
     ///
-    /// [`Tile`]: crate::tile::Tile
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::{prelude::*, chunk::RawTile};
     ///
@@ -1651,117 +11627,116 @@ impl Tilemap {
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    ///
     /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let point = (9, 3);
-    /// let sprite_index = 3;
-    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    /// tilemap.draw_line((0, 0), (6, 3), 0, 0, RawTile { index: 5, ..Default::default() }).unwrap();
     ///
-    /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile((9, 3), 0), Some(&RawTile { index: 3, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile((10, 4), 0), None);
+    /// assert_eq!(tilemap.get_tile((6, 3), 0).map(|tile| tile.index), Some(5));
     /// ```
-    pub fn get_tile<P>(&mut self, point: P, sprite_order: usize) -> Option<&RawTile>
-    where
-        P: Into<Point3>,
-    {
-        let point: Point3 = point.into();
-        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
-        let tile_point = self.point_to_tile_point(point);
-        let chunk = self.chunks.get(&chunk_point)?;
-        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
-        chunk.get_tile(index, sprite_order, point.z as usize)
+    ///
+    /// [`line_points`]: Tilemap::line_points
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn draw_line<P: Into<Point2>>(
+        &mut self,
+        from: P,
+        to: P,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        let points = self.line_points(from, to);
+        let tiles = self.template_tiles(points, sprite_order, z_depth, tile);
+        self.insert_tiles(tiles)
     }
 
-    /// Gets a mutable raw tile from a given point and z order.
+    /// Sets copies of a raw tile on a circle around a center at a sprite order
+    /// and z depth.
     ///
-    /// This is different thant he usual [`Tile`] struct in that it only
-    /// contains the sprite index and the tint.
+    /// See [`circle_points`] for the points of the circle. All the tiles are
+    /// set in one go with [`insert_tiles`].
     ///
-    /// [`Tile`]: crate::tile::Tile
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
-    /// use bevy_tilemap::{prelude::*, chunk::RawTile};
+    /// use bevy_tilemap::{prelude::*, chunk::RawTile, tilemap_ops::CircleFill};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
     /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
-    ///
     /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// let point = (2, 5);
-    /// let sprite_index = 2;
-    /// let tile = Tile { point, sprite_index, ..Default::default() };
+    /// let tile = RawTile { index: 5, ..Default::default() };
+    /// tilemap.draw_circle((0, 0), 3, CircleFill::Filled, 0, 0, tile).unwrap();
     ///
-    /// assert!(tilemap.insert_tile(tile).is_ok());
-    /// assert_eq!(tilemap.get_tile_mut((2, 5), 0), Some(&mut RawTile { index: 2, color: Color::WHITE }));
-    /// assert_eq!(tilemap.get_tile_mut((1, 4), 0), None);
+    /// assert_eq!(tilemap.get_tile((0, 3), 0).map(|tile| tile.index), Some(5));
+    /// assert_eq!(tilemap.get_tile((3, 3), 0), None);
     /// ```
-    pub fn get_tile_mut<P>(&mut self, point: P, sprite_order: usize) -> Option<&mut RawTile>
-    where
-        P: Into<Point3>,
-    {
-        let point: Point3 = point.into();
-        let chunk_point: Point2 = self.point_to_chunk_point(point).into();
-        let tile_point = self.point_to_tile_point(point);
-        let chunk = self.chunks.get_mut(&chunk_point)?;
-        let index = self.chunk_dimensions.encode_point_unchecked(tile_point);
-        let mut layers = HashMap::default();
-        layers.insert(sprite_order, chunk_point);
-        self.chunk_events.send(TilemapChunkEvent::Modified {
-            point: chunk.point(),
-        });
-        chunk.get_tile_mut(index, sprite_order, point.z as usize)
+    ///
+    /// [`circle_points`]: Tilemap::circle_points
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn draw_circle<P: Into<Point2>>(
+        &mut self,
+        center: P,
+        radius: u32,
+        fill: CircleFill,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        let points = self.circle_points(center, radius, fill);
+        let tiles = self.template_tiles(points, sprite_order, z_depth, tile);
+        self.insert_tiles(tiles)
     }
 
-    /// Clears a layer of all the tiles.
+    /// Sets copies of a raw tile on the region connected to the start at a
+    /// sprite order and z depth.
+    ///
+    /// The region is found in the sprite layer of the sprite order, see
+    /// [`flood_fill_points`]. All the tiles are set in one go with
+    /// [`insert_tiles`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`insert_tiles`].
     ///
     /// # Examples
     /// ```
     /// use bevy_asset::{prelude::*, HandleId};
-    /// use bevy_render::prelude::*;
     /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::{prelude::*, chunk::RawTile};
     ///
     /// // In production use a strong handle from an actual source.
     /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
     ///
-    /// let mut tilemap = TilemapBuilder::new()
-    ///     .texture_atlas(texture_atlas_handle)
-    ///     .dimensions(1, 1)
-    ///     .texture_dimensions(32, 32)
-    ///     .add_layer( TilemapLayer { kind: LayerKind::Dense}, 0)
-    ///     .add_layer( TilemapLayer { kind: LayerKind::Sparse}, 1)
-    ///     .finish()
-    ///     .unwrap();
+    /// let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+    /// tilemap.insert_chunk((0, 0)).unwrap();
     ///
-    /// assert!(tilemap.clear_layer(0).is_ok());
-    /// assert!(tilemap.clear_layer(1).is_ok());
-    /// assert!(tilemap.clear_layer(2).is_err());
+    /// let wall = RawTile { index: 1, ..Default::default() };
+    /// tilemap.fill_column(2, -16, 15, 0, 0, wall).unwrap();
+    /// tilemap.flood_fill((0, 0), 0, 0, RawTile { index: 2, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(tilemap.get_tile((-16, 15), 0).map(|tile| tile.index), Some(2));
+    /// assert_eq!(tilemap.get_tile((3, 0), 0), None);
     /// ```
     ///
-    /// # Errors
-    /// Fails if the layer does not exist.
-    pub fn clear_layer(&mut self, layer: usize) -> Result<(), TilemapError> {
-        if let Some(l) = self.layers.get(layer) {
-            if l.is_none() {
-                return Err(ErrorKind::LayerDoesNotExist(layer).into());
-            }
-        } else {
-            return Err(ErrorKind::LayerDoesNotExist(layer).into());
-        }
-
-        for chunk in self.chunks.values_mut() {
-            chunk.clear_layer(layer);
-        }
-
-        Ok(())
+    /// [`flood_fill_points`]: Tilemap::flood_fill_points
+    /// [`insert_tiles`]: Tilemap::insert_tiles
+    pub fn flood_fill<P: Into<Point2>>(
+        &mut self,
+        start: P,
+        sprite_order: usize,
+        z_depth: usize,
+        tile: RawTile,
+    ) -> TilemapResult<()> {
+        let points = self.flood_fill_points(start, sprite_order, z_depth);
+        let tiles = self.template_tiles(points, sprite_order, z_depth, tile);
+        self.insert_tiles(tiles)
     }
 
     /// Returns the center tile, if the tilemap has dimensions.
@@ -1796,11 +11771,11 @@ impl Tilemap {
     ///
     /// assert_eq!(center, None);
     /// ```
-    pub fn center_tile_coord(&self) -> Option<(i32, i32)> {
+    pub fn center_tile_coord(&self) -> Option<(Coord, Coord)> {
         self.dimensions.map(|dimensions| {
             (
-                (dimensions.width / 2 * self.chunk_dimensions.width) as i32,
-                (dimensions.height / 2 * self.chunk_dimensions.height) as i32,
+                (dimensions.width / 2 * self.chunk_dimensions.width) as Coord,
+                (dimensions.height / 2 * self.chunk_dimensions.height) as Coord,
             )
         })
     }
@@ -1921,6 +11896,194 @@ impl Tilemap {
         self.chunk_dimensions.height
     }
 
+    /// Changes the dimensions of the chunks, moving every tile into the new
+    /// chunks at the same point.
+    ///
+    /// This is useful for maps which were made with another chunk size than
+    /// the tilemap is built with. The tiles keep their properties, and
+    /// locked chunks become locked regions of the same tiles. Areas covered
+    /// by chunks stay covered by chunks, even without any tiles, though empty
+    /// new chunks outside the dimensions of the tilemap are left out. Per
+    /// chunk data such as user data and layer uniforms is dropped, and queued
+    /// chunks are no longer queued. The dimensions of the tilemap are still
+    /// in chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a chunk is spawned, a stream is being loaded, the
+    /// dimensions are empty or not deep enough for the z depths of the tiles,
+    /// or a new chunk with tiles is outside the dimensions of the tilemap.
+    /// The tilemap is left as is.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::prelude::*;
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .chunk_dimensions(32, 32, 1)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
+    /// tilemap.insert_tile(Tile { point: (10, 10), sprite_index: 3, ..Default::default() }).unwrap();
+    ///
+    /// assert!(tilemap.set_chunk_dimensions(8, 8, 1).is_ok());
+    ///
+    /// assert_eq!(tilemap.chunk_width(), 8);
+    /// assert_eq!(tilemap.point_to_chunk_point((10, 10)), (1, 1));
+    /// assert_eq!(tilemap.get_tile((10, 10), 0).unwrap().index, 3);
+    /// assert!(tilemap.set_chunk_dimensions(0, 8, 1).is_err());
+    /// ```
+    pub fn set_chunk_dimensions(
+        &mut self,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> TilemapResult<()> {
+        let dimensions = Dimension3::new(width, height, depth);
+        if dimensions == self.chunk_dimensions {
+            return Ok(());
+        }
+        if width == 0 || height == 0 || depth == 0 {
+            return Err(ErrorKind::InvalidChunkDimensions(dimensions).into());
+        }
+        if let Some((x, y)) = self.spawned.iter().next() {
+            return Err(ErrorKind::ChunkSpawned(Point2::new(*x, *y)).into());
+        }
+        // The stream would insert chunks of the old dimensions.
+        if self.stream.is_some() {
+            return Err(ErrorKind::StreamInProgress.into());
+        }
+
+        let old_dimensions = self.chunk_dimensions;
+        let mut tiles = Vec::new();
+        let mut properties = Vec::new();
+        for (chunk_point, chunk) in self.chunks.iter() {
+            for (z_depth, sprite_order, index, raw_tile) in chunk.raw_tiles() {
+                let point =
+                    Tilemap::chunk_index_to_point(old_dimensions, *chunk_point, z_depth, index);
+                tiles.push((point, sprite_order, *raw_tile));
+            }
+            // Points may have properties without a tile.
+            for z_depth in 0..old_dimensions.depth as usize {
+                for sprite_order in 0..self.layers.len() {
+                    let layer_properties = match chunk.layer_properties(sprite_order, z_depth) {
+                        Some(layer_properties) => layer_properties,
+                        None => continue,
+                    };
+                    for (index, tile_properties) in layer_properties.iter() {
+                        let point = Tilemap::chunk_index_to_point(
+                            old_dimensions,
+                            *chunk_point,
+                            z_depth,
+                            *index,
+                        );
+                        properties.push((point, sprite_order, tile_properties.clone()));
+                    }
+                }
+            }
+        }
+        let points = tiles
+            .iter()
+            .map(|(point, _sprite_order, _tile)| *point)
+            .chain(
+                properties
+                    .iter()
+                    .map(|(point, _sprite_order, _properties)| *point),
+            );
+        for point in points {
+            if point.z >= depth as Coord {
+                return Err(ErrorKind::InvalidChunkDimensions(dimensions).into());
+            }
+            if let Some(tilemap_dimensions) = &self.dimensions {
+                let chunk_point = Tilemap::chunk_index(dimensions, point).0;
+                tilemap_dimensions.check_centered_point(chunk_point)?;
+            }
+        }
+
+        let layer_kinds = self
+            .layers
+            .iter()
+            .map(|layer| layer.map(|layer| layer.kind))
+            .collect::<Vec<Option<LayerKind>>>();
+        let layer_count = layer_kinds.iter().flatten().count();
+        for chunk_point in self.locked_chunks.drain() {
+            let min = Tilemap::chunk_tile_point_to_point(
+                old_dimensions,
+                chunk_point,
+                Point3::new(0, 0, 0),
+            );
+            let max = Point2::new(
+                min.x + old_dimensions.width as Coord - 1,
+                min.y + old_dimensions.height as Coord - 1,
+            );
+            self.locked_regions.push((Point2::new(min.x, min.y), max));
+        }
+        let mut covered = HashSet::default();
+        for chunk_point in self.chunks.keys() {
+            let min = Tilemap::chunk_tile_point_to_point(
+                old_dimensions,
+                *chunk_point,
+                Point3::new(0, 0, 0),
+            );
+            let max = Point3::new(
+                min.x + old_dimensions.width as Coord - 1,
+                min.y + old_dimensions.height as Coord - 1,
+                0,
+            );
+            let min = Tilemap::chunk_index(dimensions, min).0;
+            let max = Tilemap::chunk_index(dimensions, max).0;
+            for point in Tilemap::rect_points(min, max) {
+                let in_bounds = self
+                    .dimensions
+                    .as_ref()
+                    .is_none_or(|dimensions| dimensions.check_centered_point(point).is_ok());
+                if in_bounds {
+                    covered.insert(point);
+                }
+            }
+        }
+        self.chunks.clear();
+        self.auto_spawn_queue.clear();
+        self.auto_despawn_queue.clear();
+        self.generate_queue.clear();
+        self.prewarm_queue.clear();
+        self.chunk_dimensions = dimensions;
+        self.chunk_mesh = ChunkMesh::new(dimensions, layer_count as u32, self.layer_offset);
+        for (point, sprite_order, raw_tile) in tiles {
+            let (chunk_point, index) = Tilemap::chunk_index(dimensions, point);
+            self.chunks
+                .entry(chunk_point)
+                .or_insert_with(|| Chunk::new(chunk_point, &layer_kinds, dimensions))
+                .set_raw_tile(index, sprite_order, point.z as usize, raw_tile);
+        }
+        for (point, sprite_order, tile_properties) in properties {
+            let (chunk_point, index) = Tilemap::chunk_index(dimensions, point);
+            let layer_properties = self
+                .chunks
+                .entry(chunk_point)
+                .or_insert_with(|| Chunk::new(chunk_point, &layer_kinds, dimensions))
+                .layer_properties_mut(sprite_order, point.z as usize);
+            if let Some(layer_properties) = layer_properties {
+                layer_properties.insert(index, tile_properties);
+            }
+        }
+        for chunk_point in covered {
+            self.chunks
+                .entry(chunk_point)
+                .or_insert_with(|| Chunk::new(chunk_point, &layer_kinds, dimensions));
+        }
+        self.record(RecordedEvent::SetChunkDimensions { dimensions });
+
+        Ok(())
+    }
+
     /// The width of a tile in pixels.
     ///
     /// # Examples
@@ -2009,16 +12172,19 @@ impl Tilemap {
         self.topology
     }
 
-    /// Returns a reference to the tilemap chunk events.
+    /// Returns the chunk events which the tilemap systems handled in their
+    /// last run.
     ///
     /// This is handy if it is needed to know when new chunks are created which
     /// can then be used to trigger events with other systems. For example,
     /// if you have a system that adds tiles procedurally to the chunks, upon
     /// a chunk event this can be used to trigger the creation of those tiles.
     ///
+    /// The events are replaced every frame rather than piling up, so a
+    /// system which runs every frame sees every event once.
+    ///
     /// # Examples
     /// ```
-    /// use bevy_app::{prelude::*, Events};
     /// use bevy_asset::{prelude::*, HandleId};
     /// use bevy_sprite::prelude::*;
     /// use bevy_tilemap::{prelude::*, event::TilemapChunkEvent};
@@ -2028,25 +12194,431 @@ impl Tilemap {
     ///
     /// let tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
     ///
-    /// let events: &Events<TilemapChunkEvent> = tilemap.chunk_events();
+    /// let events: &[TilemapChunkEvent] = tilemap.chunk_events();
+    /// assert!(events.is_empty());
+    /// ```
+    // The events returned are the ones the systems handled, not the queued
+    // ones in `chunk_events`.
+    #[allow(clippy::misnamed_getters)]
+    pub fn chunk_events(&self) -> &[TilemapChunkEvent] {
+        &self.handled_chunk_events
+    }
+
+    /// Takes the chunk events which were sent since they were last taken.
+    ///
+    /// The tilemap systems take the events every frame to spawn, update and
+    /// despawn the chunks, so this is only needed when using a tilemap
+    /// without them, such as in tools or tests. Until then the events are
+    /// kept, so they need to be taken regularly.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_asset::{prelude::*, HandleId};
+    /// use bevy_sprite::prelude::*;
+    /// use bevy_tilemap::{prelude::*, event::TilemapChunkEvent};
+    ///
+    /// // In production use a strong handle from an actual source.
+    /// let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+    ///
+    /// let mut tilemap = TilemapBuilder::new()
+    ///     .texture_atlas(texture_atlas_handle)
+    ///     .texture_dimensions(32, 32)
+    ///     .auto_chunk()
+    ///     .finish()
+    ///     .unwrap();
+    ///
+    /// tilemap.insert_tile(Tile { point: (0, 0), ..Default::default() }).unwrap();
+    ///
+    /// let events = tilemap.take_chunk_events();
+    /// assert!(events.iter().any(|event| matches!(
+    ///     event,
+    ///     TilemapChunkEvent::NeedsPopulation { .. }
+    /// )));
+    /// assert!(tilemap.take_chunk_events().is_empty());
     /// ```
-    pub fn chunk_events(&self) -> &Events<TilemapChunkEvent> {
-        &self.chunk_events
+    pub fn take_chunk_events(&mut self) -> Vec<TilemapChunkEvent> {
+        mem::take(&mut self.chunk_events)
+    }
+
+    /// Takes the chunks which were despawned or removed since they were last
+    /// taken, with their tiles in the stream format.
+    ///
+    /// Chunks are only kept if the tilemap was built with
+    /// [`TilemapBuilder::persist_unloaded_chunks`]. The tilemap systems take
+    /// them every frame to send a [`TilemapChunkUnloadEvent`] for each, so
+    /// this is only needed when using a tilemap without them.
+    ///
+    /// [`TilemapChunkUnloadEvent`]: crate::event::TilemapChunkUnloadEvent
+    pub fn take_unloaded_chunks(&mut self) -> Vec<UnloadedChunk> {
+        mem::take(&mut self.unloaded_chunks)
+    }
+
+    /// Returns the number of chunk events which were not taken yet.
+    pub(crate) fn queued_chunk_event_count(&self) -> usize {
+        self.chunk_events.len()
+    }
+
+    /// Stores the chunk events which the tilemap systems handled, replacing
+    /// the ones of their previous run.
+    pub(crate) fn set_handled_chunk_events(&mut self, events: Vec<TilemapChunkEvent>) {
+        self.handled_chunk_events = events;
+    }
+
+    /// Takes the deleted chunks whose entities were not despawned yet.
+    pub(crate) fn take_deleted_chunks(&mut self) -> Vec<Chunk> {
+        mem::take(&mut self.deleted_chunks)
+    }
+
+    /// Sends the events to spawn the chunks which were spawned when a
+    /// deserialized tilemap was saved, as their entities were not saved.
+    #[cfg(feature = "scene")]
+    pub(crate) fn respawn_saved_chunks(&mut self) {
+        for (x, y) in mem::take(&mut self.spawned) {
+            self.chunk_events.push(TilemapChunkEvent::Spawned {
+                point: Point2::new(x, y),
+            });
+        }
+    }
+
+    /// Returns `true` if the chunks of a reloaded tilemap are laid out and
+    /// drawn like the chunks of the tilemap, so they can be patched in place.
+    #[cfg(feature = "scene")]
+    fn draws_chunks_like(&self, other: &Tilemap) -> bool {
+        self.topology == other.topology
+            && self.chunk_dimensions == other.chunk_dimensions
+            && self.texture_dimensions == other.texture_dimensions
+            && self.layer_offset == other.layer_offset
+            && self.chunk_mesh == other.chunk_mesh
+            && self.layers == other.layers
+            && self.layer_waves == other.layer_waves
+            && self.layer_gradings == other.layer_gradings
+            && self.layer_depths == other.layer_depths
+            && self.depth_range == other.depth_range
+            && self.hidden_layers == other.hidden_layers
+            && self.occluding_layers == other.occluding_layers
+            && self.static_layers == other.static_layers
+            && self.y_sorted_layers == other.y_sorted_layers
+    }
+
+    /// Applies a reloaded tilemap whose saved chunks are about to be spawned.
+    ///
+    /// The settings of the reloaded tilemap are taken. While its chunks are
+    /// laid out and drawn the same way, the chunks are diffed: a chunk with the
+    /// same sprite layers is patched in place and only re-rendered if any of
+    /// its tiles changed, a chunk which is gone is deleted and a new chunk is
+    /// inserted. Every other chunk is replaced, and respawned if it was
+    /// spawned. The handles and region entities, which are not saved, are
+    /// kept.
+    #[cfg(feature = "scene")]
+    pub(crate) fn reload(&mut self, mut tilemap: Tilemap) {
+        let patch = self.draws_chunks_like(&tilemap);
+        tilemap.deleted_chunks = mem::take(&mut self.deleted_chunks);
+        for (point, mut chunk) in self.chunks.drain() {
+            let spawned = self.spawned.contains(&(point.x, point.y));
+            let queued_spawn = tilemap.chunk_events.iter().position(|event| {
+                matches!(event, TilemapChunkEvent::Spawned { point: queued } if *queued == point)
+            });
+            match tilemap.chunks.remove(&point) {
+                Some(reloaded) if patch && chunk.has_layers_of(&reloaded) => {
+                    if chunk.patch(reloaded) && chunk.has_mesh() {
+                        tilemap
+                            .chunk_events
+                            .push(TilemapChunkEvent::Modified { point });
+                    }
+                    if spawned {
+                        tilemap.spawned.insert((point.x, point.y));
+                        if let Some(index) = queued_spawn {
+                            tilemap.chunk_events.remove(index);
+                        }
+                    }
+                    tilemap.chunks.insert(point, chunk);
+                }
+                reloaded => {
+                    if let Some(reloaded) = reloaded {
+                        tilemap.chunks.insert(point, reloaded);
+                        if spawned && queued_spawn.is_none() {
+                            tilemap
+                                .chunk_events
+                                .push(TilemapChunkEvent::Spawned { point });
+                        }
+                    }
+                    tilemap.deleted_chunks.push(chunk);
+                    tilemap
+                        .chunk_events
+                        .push(TilemapChunkEvent::Deleted { point });
+                }
+            }
+        }
+        tilemap.region_entities = mem::take(&mut self.region_entities);
+        tilemap.texture_atlas = self.texture_atlas.clone();
+        tilemap.texture_atlases = mem::take(&mut self.texture_atlases);
+        tilemap.render_pipeline = self.render_pipeline.take();
+        tilemap.secondary_texture = self.secondary_texture.take();
+        tilemap.color_grading = self.color_grading.take();
+        *self = tilemap;
+    }
+
+    /// Takes the inserted tiles which are waiting to be validated.
+    pub(crate) fn take_unvalidated_tiles(&mut self) -> Vec<UnvalidatedTile> {
+        mem::take(&mut self.unvalidated_tiles)
+    }
+
+    /// Puts back tiles which could not be validated yet.
+    pub(crate) fn return_unvalidated_tiles(&mut self, tiles: Vec<UnvalidatedTile>) {
+        self.unvalidated_tiles.extend(tiles);
+    }
+
+    /// Returns an option containing a Dimension2.
+    pub(crate) fn auto_spawn(&self) -> Option<Dimension2> {
+        self.auto_spawn
+    }
+
+    /// Returns a mutable reference to the cached average sprite colors.
+    pub(crate) fn lod_colors_mut(&mut self) -> &mut HashMap<(usize, usize), Color> {
+        &mut self.lod_colors
+    }
+
+    /// Takes the chunks which are waiting to be prewarmed.
+    pub(crate) fn take_prewarm_queue(&mut self) -> Vec<Point2> {
+        mem::take(&mut self.prewarm_queue)
+    }
+
+    /// Sets the auto spawn radius.
+    pub(crate) fn set_auto_spawn(&mut self, dimension: Dimension2) {
+        self.auto_spawn = Some(dimension);
+    }
+
+    /// Returns the extra chunks past the auto spawn radius before despawning.
+    pub(crate) fn auto_spawn_margin(&self) -> Dimension2 {
+        self.auto_spawn_margin
+    }
+
+    /// Returns the maximum amount of chunks to auto spawn and despawn per
+    /// frame, if any.
+    pub(crate) fn auto_spawn_budget(&self) -> Option<u32> {
+        self.auto_spawn_budget
+    }
+
+    /// Spawns a chunk for auto spawn, sending an event if it needs to be
+    /// populated.
+    ///
+    /// A missing chunk is created with a `Created` event if `auto_chunk` is
+    /// set and is otherwise not spawned.
+    pub(crate) fn auto_spawn_chunk(&mut self, point: Point2) -> TilemapResult<()> {
+        let needs_population = match self.chunks.get(&point) {
+            Some(chunk) => chunk.is_empty(),
+            None if self.generator.is_some() => {
+                if !self.generate_queue.contains(&point) {
+                    self.generate_queue.push(point);
+                }
+                return Ok(());
+            }
+            None if self.auto_flags.contains(AutoFlags::AUTO_CHUNK) => {
+                self.insert_chunk(point)?;
+                self.chunk_events.push(TilemapChunkEvent::Created { point });
+                true
+            }
+            None => {
+                self.chunk_events
+                    .push(TilemapChunkEvent::NeedsPopulation { point });
+                return Ok(());
+            }
+        };
+        if needs_population {
+            self.chunk_events
+                .push(TilemapChunkEvent::NeedsPopulation { point });
+        }
+        self.spawn_chunk(point)
+    }
+
+    /// Returns the generator of new chunks and takes the chunks waiting to
+    /// be generated.
+    pub(crate) fn take_generate_queue(&mut self) -> Option<(Arc<dyn ChunkGenerator>, Vec<Point2>)> {
+        let generator = self.generator.clone()?;
+        if self.generate_queue.is_empty() {
+            return None;
+        }
+        Some((generator, mem::take(&mut self.generate_queue)))
+    }
+
+    /// Inserts a generated chunk with its tiles local to the chunk, retiles
+    /// the tiles along its edges and spawns it, without recording the tiles
+    /// in the history.
+    ///
+    /// A chunk which was inserted since it was queued keeps its tiles.
+    pub(crate) fn insert_generated_chunk(
+        &mut self,
+        point: Point2,
+        tiles: Vec<Tile<Point3>>,
+    ) -> TilemapResult<()> {
+        if !self.chunks.contains_key(&point) {
+            let chunk_dimensions = self.chunk_dimensions;
+            for tile in tiles.iter() {
+                chunk_dimensions.encode_point(tile.point)?;
+            }
+            self.insert_chunk(point)?;
+            let history = self.history.take();
+            let mut result = self.insert_tiles(tiles.into_iter().map(|tile| {
+                tile.map_point(|tile_point| {
+                    Tilemap::chunk_tile_point_to_point(chunk_dimensions, point, tile_point)
+                })
+            }));
+            // The tiles across the edges of the chunk can only be picked now
+            // that it exists.
+            match self.generator.clone() {
+                Some(generator) if result.is_ok() => {
+                    result = self.retile_chunk_border(
+                        point,
+                        |source, tile_point, sprite_order, tile| {
+                            generator.retile(source, tile_point, sprite_order, tile)
+                        },
+                    );
+                }
+                _ => {}
+            }
+            self.history = history;
+            result?;
+        }
+        self.spawn_chunk(point)
+    }
+
+    /// Returns `true` if the spawn policy allows auto spawning a chunk.
+    pub(crate) fn can_auto_spawn(&self, point: Point2) -> bool {
+        self.spawn_policy
+            .as_ref()
+            .is_none_or(|policy| policy.can_spawn(point))
+    }
+
+    /// Returns `true` if the spawn policy allows auto despawning a chunk.
+    pub(crate) fn can_auto_despawn(&self, point: Point2) -> bool {
+        let preloaded = self
+            .preload_path
+            .as_ref()
+            .is_some_and(|path| path.holds(point));
+        !preloaded
+            && self
+                .spawn_policy
+                .as_ref()
+                .is_none_or(|policy| policy.can_despawn(point))
+    }
+
+    /// Advances the camera along the preload path to a position in tiles
+    /// after some time and spawns the chunks which are due.
+    pub(crate) fn advance_preload_path(&mut self, position: Vec2, delta: Duration) {
+        let mut path = if let Some(path) = self.preload_path.take() {
+            path
+        } else {
+            return;
+        };
+        let radius = self.auto_spawn().unwrap_or_else(|| Dimension2::new(1, 1));
+        let radius_x = radius.width as Coord;
+        let radius_y = radius.height as Coord;
+        for (index, point) in path.advance(position, delta) {
+            let (chunk_x, chunk_y) = self.point_to_chunk_point(point);
+            for y in chunk_y - radius_y..=chunk_y + radius_y {
+                for x in chunk_x - radius_x..=chunk_x + radius_x {
+                    let chunk_point = Point2::new(x, y);
+                    if let Some(dimensions) = &self.dimensions {
+                        if dimensions.check_centered_point(chunk_point).is_err() {
+                            continue;
+                        }
+                    }
+                    path.hold(chunk_point, index);
+                    if !self.spawned.contains(&(x, y)) {
+                        if let Err(e) = self.auto_spawn_chunk(chunk_point) {
+                            warn!("{}", e);
+                        }
+                    }
+                }
+            }
+        }
+        if !path.is_finished() {
+            self.preload_path = Some(path);
+        }
+    }
+
+    /// Replaces the chunks waiting to be auto spawned and despawned.
+    pub(crate) fn set_auto_spawn_queues(&mut self, spawn: Vec<Point2>, despawn: Vec<Point2>) {
+        self.auto_spawn_queue = spawn;
+        self.auto_despawn_queue = despawn;
+    }
+
+    /// Takes the next chunk waiting to be auto spawned, or despawned if none
+    /// are waiting to be spawned, and if it is to be spawned.
+    pub(crate) fn pop_auto_spawn_queue(&mut self) -> Option<(Point2, bool)> {
+        if let Some(point) = self.auto_spawn_queue.pop() {
+            return Some((point, true));
+        }
+        self.auto_despawn_queue.pop().map(|point| (point, false))
     }
 
-    /// Updates the chunk events. This should only be done once per frame.
-    pub(crate) fn chunk_events_update(&mut self) {
-        self.chunk_events.update()
+    /// Returns the spawn priorities of the layers in the tilemap.
+    fn spawn_priorities(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.is_some())
+            .map(move |(sprite_order, _)| (sprite_order, self.layer_spawn_priority(sprite_order)))
     }
 
-    /// Returns an option containing a Dimension2.
-    pub(crate) fn auto_spawn(&self) -> Option<Dimension2> {
-        self.auto_spawn
+    /// Holds back the sprite layers of a newly spawned chunk below the
+    /// highest spawn priority, if auto spawning has a budget and the layers
+    /// have different priorities.
+    pub(crate) fn hold_back_layers(&mut self, point: Point2) {
+        if self.auto_spawn_budget.is_none() {
+            return;
+        }
+        let highest = self.spawn_priorities().map(|(_, priority)| priority).max();
+        let lowest = self.spawn_priorities().map(|(_, priority)| priority).min();
+        if let (Some(highest), Some(lowest)) = (highest, lowest) {
+            if highest > lowest {
+                self.revealing_chunks.insert(point, highest);
+            }
+        }
     }
 
-    /// Sets the auto spawn radius.
-    pub(crate) fn set_auto_spawn(&mut self, dimension: Dimension2) {
-        self.auto_spawn = Some(dimension);
+    /// Returns the sprite layers which are hidden in a chunk, which are the
+    /// hidden layers along with the layers it still holds back, if it holds
+    /// back any.
+    pub(crate) fn chunk_hidden_layers(&self, point: Point2) -> Option<HashSet<usize>> {
+        let revealed = self.revealing_chunks.get(&point)?;
+        let mut hidden_layers = self.hidden_layers.clone();
+        hidden_layers.extend(
+            self.spawn_priorities()
+                .filter(|(_, priority)| priority < revealed)
+                .map(|(sprite_order, _)| sprite_order),
+        );
+        Some(hidden_layers)
+    }
+
+    /// Reveals the sprite layers of the next lower spawn priority in every
+    /// chunk which holds back layers, and sends events to update their
+    /// meshes.
+    pub(crate) fn reveal_held_back_layers(&mut self) {
+        if self.revealing_chunks.is_empty() {
+            return;
+        }
+        let revealing_chunks = mem::take(&mut self.revealing_chunks);
+        for (point, revealed) in revealing_chunks.into_iter() {
+            let next = self
+                .spawn_priorities()
+                .map(|(_, priority)| priority)
+                .filter(|priority| *priority < revealed)
+                .max();
+            if let Some(next) = next {
+                let lowest = self.spawn_priorities().map(|(_, priority)| priority).min();
+                if lowest.is_some_and(|lowest| lowest < next) {
+                    self.revealing_chunks.insert(point, next);
+                }
+            }
+            if let Some(chunk) = self.chunks.get_mut(&point) {
+                chunk.mark_all_dirty();
+                if chunk.has_mesh() {
+                    self.chunk_events
+                        .push(TilemapChunkEvent::Modified { point });
+                }
+            }
+        }
     }
 
     /// Returns a copy of the chunk's dimensions.
@@ -2060,12 +12632,12 @@ impl Tilemap {
     }
 
     /// Returns a reference to the hash set of spawned chunks.
-    pub(crate) fn spawned_chunks(&self) -> &HashSet<(i32, i32)> {
+    pub(crate) fn spawned_chunks(&self) -> &HashSet<(Coord, Coord)> {
         &self.spawned
     }
 
     /// Returns a mutable reference to the spawned chunk points.
-    pub(crate) fn spawned_chunks_mut(&mut self) -> &mut HashSet<(i32, i32)> {
+    pub(crate) fn spawned_chunks_mut(&mut self) -> &mut HashSet<(Coord, Coord)> {
         &mut self.spawned
     }
 
@@ -2084,10 +12656,102 @@ impl Tilemap {
         &mut self.chunks
     }
 
+    /// Returns the settings deciding how the tiles of the chunks are turned
+    /// into mesh attributes.
+    pub(crate) fn mesh_settings(&self) -> MeshSettings<'_> {
+        MeshSettings {
+            hidden_layers: &self.hidden_layers,
+            waves: &self.layer_waves,
+            occluding_layers: &self.occluding_layers,
+            y_sorted_layers: &self.y_sorted_layers,
+            sort_keys: &self.layer_sort_keys,
+            layer_depths: &self.layer_depths,
+            depth_range: self.depth_range,
+            gradings: &self.layer_gradings,
+            theme: &self.theme,
+        }
+    }
+
+    /// Returns a mutable reference to the inner chunks along with the mesh
+    /// settings, so the meshes of the chunks can be rebuilt while they
+    /// change.
+    pub(crate) fn chunks_with_mesh_settings_mut(
+        &mut self,
+    ) -> (&mut HashMap<Point2, Chunk>, MeshSettings<'_>) {
+        let settings = MeshSettings {
+            hidden_layers: &self.hidden_layers,
+            waves: &self.layer_waves,
+            occluding_layers: &self.occluding_layers,
+            y_sorted_layers: &self.y_sorted_layers,
+            sort_keys: &self.layer_sort_keys,
+            layer_depths: &self.layer_depths,
+            depth_range: self.depth_range,
+            gradings: &self.layer_gradings,
+            theme: &self.theme,
+        };
+        (&mut self.chunks, settings)
+    }
+
     /// A reference of a chunk's mesh.
     pub(crate) fn chunk_mesh(&self) -> &ChunkMesh {
         &self.chunk_mesh
     }
+
+    /// Returns the sprite layers which are hidden at a camera zoom.
+    pub(crate) fn layers_hidden_at(&self, scale: f32) -> HashSet<usize> {
+        self.layer_zoom_ranges
+            .iter()
+            .filter(|(_, range)| !range.contains(scale))
+            .map(|(sprite_order, _)| *sprite_order)
+            .collect()
+    }
+
+    /// The waves of the sprite layers which move over time.
+    pub(crate) fn layer_waves(&self) -> &HashMap<usize, Wave> {
+        &self.layer_waves
+    }
+
+    /// Returns the sprite layers which are baked into a texture per chunk.
+    pub(crate) fn static_layers(&self) -> &HashSet<usize> {
+        &self.static_layers
+    }
+
+    /// The depths the sprite layers render at which were set.
+    pub(crate) fn layer_depths(&self) -> &HashMap<usize, f32> {
+        &self.layer_depths
+    }
+
+    /// Sets the sprite layers which are hidden and updates the meshes of all
+    /// the spawned chunks if they changed.
+    pub(crate) fn set_hidden_layers(&mut self, hidden_layers: HashSet<usize>) {
+        if self.hidden_layers == hidden_layers {
+            return;
+        }
+        self.hidden_layers = hidden_layers;
+        self.mark_all_chunks_modified();
+    }
+
+    /// Returns the sprite index that a sprite index is rendered with after
+    /// looking it up in the theme.
+    pub(crate) fn themed_sprite_index(&self, sprite_index: usize) -> usize {
+        self.theme
+            .get(&(sprite_index as u32))
+            .copied()
+            .unwrap_or(sprite_index)
+    }
+
+    /// Marks every tile of every chunk as changed and sends an event to update
+    /// the meshes of the spawned chunks.
+    fn mark_all_chunks_modified(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.mark_all_dirty();
+            if chunk.has_mesh() {
+                self.chunk_events.push(TilemapChunkEvent::Modified {
+                    point: chunk.point(),
+                });
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -2099,8 +12763,1198 @@ mod tests {
         /// purposes only.
         pub(crate) fn modify_chunk(&mut self, point: Point2) {
             self.chunk_events
-                .send(TilemapChunkEvent::Modified { point });
+                .push(TilemapChunkEvent::Modified { point });
+        }
+    }
+
+    #[test]
+    fn test_position_hash() {
+        // The hashes must never change, or decorated maps would change with
+        // them.
+        assert_eq!(Tilemap::position_hash((3, -4), 7), 0xeb99_8e67_fb26_7dc0);
+        assert!(Tilemap::position_hash((3, -4), 8) != Tilemap::position_hash((3, -4), 7));
+        assert!(Tilemap::position_hash((4, -3), 7) != Tilemap::position_hash((3, -4), 7));
+    }
+
+    #[test]
+    fn test_retile_generated_chunks() {
+        struct Shores;
+
+        impl ChunkGenerator for Shores {
+            fn generate(&self, point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>> {
+                let mut tiles = Vec::new();
+                for y in 0..dimensions.height as Coord {
+                    for x in 0..dimensions.width as Coord {
+                        tiles.push(Tile {
+                            point: Point3::new(x, y, 0),
+                            sprite_index: if point.x == 0 { 1 } else { 0 },
+                            ..Default::default()
+                        });
+                    }
+                }
+                tiles
+            }
+
+            fn retile(
+                &self,
+                source: &dyn TileSource,
+                point: Point3,
+                sprite_order: usize,
+                tile: &RawTile,
+            ) -> Option<RawTile> {
+                let right = source.tile_at(Point3::new(point.x + 1, point.y, 0), sprite_order)?;
+                if tile.index == 1 && right.index == 0 {
+                    Some(RawTile { index: 2, ..*tile })
+                } else {
+                    None
+                }
+            }
+        }
+
+        // The shore is the same whichever chunk is generated first.
+        for order in [[(0, 0), (1, 0)], [(1, 0), (0, 0)]].iter() {
+            let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+            let mut tilemap = TilemapBuilder::new()
+                .texture_atlas(texture_atlas_handle)
+                .texture_dimensions(32, 32)
+                .chunk_dimensions(4, 4, 1)
+                .finish()
+                .unwrap();
+            tilemap.set_generator(Shores);
+            for point in order.iter() {
+                let point: Point2 = (*point).into();
+                let tiles = Shores.generate(point, tilemap.chunk_dimensions());
+                tilemap.insert_generated_chunk(point, tiles).unwrap();
+            }
+            assert_eq!(tilemap.get_tile((1, -2), 0).unwrap().index, 2);
+            assert_eq!(tilemap.get_tile((0, -2), 0).unwrap().index, 1);
+            assert_eq!(tilemap.get_tile((2, -2), 0).unwrap().index, 0);
+        }
+    }
+
+    #[test]
+    fn test_replace_sprite_index() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+        tilemap.insert_chunk((0, 1)).unwrap();
+        tilemap
+            .fill_rect(
+                (-2, -2),
+                (5, 1),
+                0,
+                0,
+                RawTile {
+                    index: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tilemap.enable_history(4);
+        tilemap.lock_region((-2, -2), (-2, 1));
+        tilemap.take_chunk_events();
+
+        // The locked column is kept.
+        assert_eq!(tilemap.replace_sprite_index(1, 2, None), 28);
+        assert_eq!(tilemap.get_tile((5, 1), 0).unwrap().index, 2);
+        assert_eq!(tilemap.get_tile((-2, 0), 0).unwrap().index, 1);
+        // Only the chunks with replaced tiles are modified.
+        assert_eq!(tilemap.take_chunk_events().len(), 2);
+        assert_eq!(tilemap.replace_sprite_index(1, 2, None), 0);
+
+        assert!(tilemap.undo().unwrap());
+        assert_eq!(tilemap.get_tile((5, 1), 0).unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_undo_into_locked_region() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tile = |point, sprite_index| Tile {
+            point,
+            sprite_index,
+            ..Default::default()
+        };
+        tilemap.insert_tile(tile((2, 2), 1)).unwrap();
+        tilemap.enable_history(4);
+        tilemap
+            .insert_tiles(vec![tile((1, 1), 2), tile((2, 2), 3)])
+            .unwrap();
+        tilemap.lock_region((2, 2), (2, 2));
+
+        // Undoing would clear the first tile and restore the locked one.
+        assert_eq!(
+            tilemap.undo(),
+            Err(ErrorKind::RegionLocked(Point2::new(2, 2)).into())
+        );
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 2);
+        assert_eq!(tilemap.get_tile((2, 2), 0).unwrap().index, 3);
+
+        tilemap.unlock_region((2, 2), (2, 2));
+        assert!(tilemap.undo().unwrap());
+        assert_eq!(tilemap.get_tile((1, 1), 0), None);
+        assert_eq!(tilemap.get_tile((2, 2), 0).unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_replace_chunk_tiles() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tile = |point, sprite_index| Tile {
+            point,
+            sprite_index,
+            ..Default::default()
+        };
+        tilemap
+            .insert_tiles(vec![tile((0, 0), 1), tile((1, 1), 2)])
+            .unwrap();
+        let bytes = tilemap.chunk_bytes(Point2::new(0, 0)).unwrap();
+        tilemap.clear_tile((0, 0), 0).unwrap();
+
+        // Locked chunks report their first locked tile.
+        tilemap.lock_region((1, 1), (5, 5));
+        assert_eq!(
+            tilemap.restore_chunk(&bytes),
+            Err(ErrorKind::RegionLocked(Point2::new(1, 1)).into())
+        );
+        tilemap.unlock_region((1, 1), (5, 5));
+        tilemap.lock_chunk((0, 0));
+        assert_eq!(
+            tilemap.restore_chunk(&bytes),
+            Err(ErrorKind::RegionLocked(Point2::new(-2, -2)).into())
+        );
+        tilemap.unlock_chunk((0, 0));
+
+        // A bad tile leaves the chunk as it was.
+        let raw_tile = RawTile {
+            index: 3,
+            ..Default::default()
+        };
+        let tiles = vec![(0, 0, 0, raw_tile), (0, 100, 1, raw_tile)];
+        assert_eq!(
+            tilemap.replace_chunk_tiles(Point2::new(0, 0), &tiles),
+            Err(ErrorKind::LayerDoesNotExist(100).into())
+        );
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 2);
+        assert_eq!(tilemap.get_tile((-2, -2), 0), None);
+
+        assert_eq!(tilemap.restore_chunk(&bytes), Ok(Point2::new(0, 0)));
+        assert_eq!(tilemap.get_tile((0, 0), 0).unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_insert_tiles_parallel() {
+        let tilemap = |auto_chunk: bool| {
+            let builder = TilemapBuilder::new()
+                .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+                .texture_dimensions(32, 32)
+                .chunk_dimensions(4, 4, 1);
+            if auto_chunk {
+                builder.auto_chunk()
+            } else {
+                builder
+            }
+            .finish()
+            .unwrap()
+        };
+        // Later tiles at the same point replace the earlier ones.
+        let tiles: Vec<Tile<(Coord, Coord)>> = (0..2)
+            .flat_map(|pass| {
+                (-10..10).flat_map(move |x| {
+                    (-10..10).map(move |y| Tile {
+                        point: (x, y),
+                        sprite_index: (pass * 400 + (x + 10) * 20 + y + 10) as usize,
+                        ..Default::default()
+                    })
+                })
+            })
+            .collect();
+        let task_pool = TaskPool::new();
+        let mut serial = tilemap(true);
+        serial.insert_tiles(tiles.clone()).unwrap();
+        let mut parallel = tilemap(true);
+        parallel
+            .insert_tiles_parallel(&task_pool, tiles.clone())
+            .unwrap();
+        assert_eq!(parallel.chunks.len(), serial.chunks.len());
+        for x in -10..10 {
+            for y in -10..10 {
+                assert_eq!(parallel.get_tile((x, y), 0), serial.get_tile((x, y), 0));
+            }
+        }
+        assert_eq!(parallel.get_tile((-10, -10), 0).unwrap().index, 400);
+
+        // No tile is set if any of them can not be.
+        let mut parallel = tilemap(false);
+        parallel.insert_chunk((0, 0)).unwrap();
+        assert_eq!(
+            parallel.insert_tiles_parallel(&task_pool, tiles.clone()),
+            Err(ErrorKind::MissingChunk.into())
+        );
+        assert_eq!(parallel.get_tile((0, 0), 0), None);
+        let mut parallel = tilemap(true);
+        parallel.lock_region((9, 9), (9, 9));
+        assert_eq!(
+            parallel.insert_tiles_parallel(&task_pool, tiles),
+            Err(ErrorKind::RegionLocked(Point2::new(9, 9)).into())
+        );
+        assert!(parallel.chunks.is_empty());
+    }
+
+    #[test]
+    fn test_insert_tiles_checked() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.lock_region((-2, -2), (-2, -2));
+        let tile = |point, sprite_order| Tile {
+            point,
+            sprite_order,
+            sprite_index: 3,
+            ..Default::default()
+        };
+
+        let report = tilemap.insert_tiles_checked(vec![
+            tile((1, 1, 0), 0),
+            tile((0, 1, 0), 0),
+            tile((10, 0, 0), 0),
+            tile((0, 0, 0), 100),
+            tile((-2, -2, 0), 0),
+            tile((0, 0, 1), 0),
+        ]);
+        assert_eq!(report.inserted, 2);
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 3);
+        assert_eq!(tilemap.get_tile((0, 1), 0).unwrap().index, 3);
+        let reason = |point: Point3| {
+            report
+                .rejected
+                .iter()
+                .find(|(tile, _)| tile.point == point)
+                .map(|(_, error)| error.clone())
+        };
+        assert_eq!(report.rejected.len(), 4);
+        assert_eq!(
+            reason(Point3::new(10, 0, 0)),
+            Some(ErrorKind::MissingChunk.into())
+        );
+        assert_eq!(
+            reason(Point3::new(0, 0, 0)),
+            Some(ErrorKind::LayerDoesNotExist(100).into())
+        );
+        assert_eq!(
+            reason(Point3::new(-2, -2, 0)),
+            Some(ErrorKind::RegionLocked(Point2::new(-2, -2)).into())
+        );
+        assert!(reason(Point3::new(0, 0, 1)).is_some());
+        assert_eq!(tilemap.get_tile((-2, -2), 0), None);
+    }
+
+    #[test]
+    fn test_tile_in_chunk() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((1, 0)).unwrap();
+        let raw_tile = RawTile {
+            index: 3,
+            ..Default::default()
+        };
+
+        // The tile at (1, 2) in the chunk right of the center is at (3, 0).
+        tilemap
+            .set_tile_in_chunk((1, 0), 9, 0, 0, raw_tile)
+            .unwrap();
+        assert_eq!(tilemap.get_tile((3, 0), 0), Some(&raw_tile));
+        assert_eq!(tilemap.get_tile_in_chunk((1, 0), 9, 0, 0), Some(&raw_tile));
+        tilemap
+            .insert_tile(Tile {
+                point: (-2, -2),
+                sprite_index: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(tilemap.get_tile_in_chunk((0, 0), 0, 0, 0).unwrap().index, 1);
+        assert_eq!(tilemap.get_tile_in_chunk((0, 0), 1, 0, 0), None);
+        assert_eq!(tilemap.get_tile_in_chunk((5, 5), 0, 0, 0), None);
+
+        assert_eq!(
+            tilemap.set_tile_in_chunk((0, 0), 16, 0, 0, raw_tile),
+            Err(ErrorKind::TileIndexOutOfBounds(16, 0).into())
+        );
+        assert_eq!(
+            tilemap.set_tile_in_chunk((0, 0), 0, 0, 1, raw_tile),
+            Err(ErrorKind::TileIndexOutOfBounds(0, 1).into())
+        );
+        assert_eq!(
+            tilemap.set_tile_in_chunk((0, 0), 0, 1, 0, raw_tile),
+            Err(ErrorKind::LayerDoesNotExist(1).into())
+        );
+        assert_eq!(
+            tilemap.set_tile_in_chunk((5, 5), 0, 0, 0, raw_tile),
+            Err(ErrorKind::MissingChunk.into())
+        );
+        tilemap.lock_region((3, 0), (3, 0));
+        assert_eq!(
+            tilemap.set_tile_in_chunk((1, 0), 9, 0, 0, RawTile::default()),
+            Err(ErrorKind::RegionLocked(Point2::new(3, 0)).into())
+        );
+        assert_eq!(tilemap.get_tile((3, 0), 0), Some(&raw_tile));
+    }
+
+    #[test]
+    fn test_used_bounds() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 2)
+            .add_layer(
+                TilemapLayer {
+                    kind: LayerKind::Sparse,
+                },
+                0,
+            )
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        assert_eq!(tilemap.used_bounds(None), None);
+        let tile = |point| Tile {
+            point,
+            sprite_index: 1,
+            ..Default::default()
+        };
+        tilemap
+            .insert_tiles(vec![
+                tile((-7, 3, 0)),
+                tile((9, -1, 0)),
+                tile((0, -10, 1)),
+                tile((1, 1, 0)),
+            ])
+            .unwrap();
+        // An empty chunk does not count.
+        tilemap.insert_chunk((5, 5)).unwrap();
+
+        assert_eq!(
+            tilemap.used_bounds(None),
+            Some((Point2::new(-7, -10), Point2::new(9, 3)))
+        );
+        assert_eq!(
+            tilemap.used_bounds(Some(0)),
+            Some((Point2::new(-7, -1), Point2::new(9, 3)))
+        );
+        assert_eq!(
+            tilemap.used_bounds(Some(1)),
+            Some((Point2::new(0, -10), Point2::new(0, -10)))
+        );
+
+        tilemap.clear_tile((9, -1, 0), 0).unwrap();
+        assert_eq!(
+            tilemap.used_bounds(Some(0)),
+            Some((Point2::new(-7, 1), Point2::new(1, 3)))
+        );
+    }
+
+    #[test]
+    fn test_layer_names() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .add_layer(TilemapLayer::default(), 1)
+            .layer_name(0, "ground")
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap.set_layer_name(1, Some("collision")).unwrap();
+        assert_eq!(tilemap.layer_index("ground"), Some(0));
+        assert_eq!(tilemap.layer_index("collision"), Some(1));
+        assert_eq!(tilemap.layer_name(1), Some("collision"));
+        assert_eq!(
+            tilemap.set_layer_name(0, Some("collision")),
+            Err(ErrorKind::LayerNameExists("collision".to_string()).into())
+        );
+        assert_eq!(
+            tilemap.set_layer_name(5, Some("water")),
+            Err(ErrorKind::LayerDoesNotExist(5).into())
+        );
+
+        // The tiles take the sprite order of the layer with the name.
+        let tile = Tile {
+            point: (1, 1),
+            sprite_index: 4,
+            ..Default::default()
+        };
+        tilemap
+            .insert_tiles_in_layer("collision", vec![tile])
+            .unwrap();
+        assert_eq!(tilemap.get_tile((1, 1), 1).unwrap().index, 4);
+        assert_eq!(tilemap.get_tile((1, 1), 0), None);
+        assert_eq!(
+            tilemap
+                .get_tile_in_layer((1, 1), "collision")
+                .map(|tile| tile.index),
+            Some(4)
+        );
+        assert_eq!(
+            tilemap.insert_tiles_in_layer("water", vec![tile]),
+            Err(ErrorKind::LayerNameDoesNotExist("water".to_string()).into())
+        );
+        tilemap.clear_tile_in_layer((1, 1), "collision").unwrap();
+        assert_eq!(tilemap.get_tile_in_layer((1, 1), "collision"), None);
+
+        // Names follow their layers around and go away with them.
+        tilemap.move_layer(1, 3).unwrap();
+        assert_eq!(tilemap.layer_index("collision"), Some(3));
+        tilemap.remove_layer(3);
+        assert_eq!(tilemap.layer_index("collision"), None);
+        tilemap.set_layer_name(0, None).unwrap();
+        assert_eq!(tilemap.layer_name(0), None);
+    }
+
+    #[test]
+    fn test_annotations() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.take_chunk_events();
+
+        assert_eq!(tilemap.set_annotation((4, 2), "boss spawns here"), None);
+        assert_eq!(
+            tilemap.set_annotation((4, 2), "boss spawns later"),
+            Some("boss spawns here".to_string())
+        );
+        tilemap.set_annotation((-1, 0), "door");
+        assert_eq!(tilemap.annotation((4, 2)), Some("boss spawns later"));
+        assert_eq!(tilemap.annotation((0, 0)), None);
+        assert_eq!(tilemap.annotations().count(), 2);
+        // Annotations are never rendered, so no chunk is updated.
+        assert!(tilemap.take_chunk_events().is_empty());
+
+        assert_eq!(tilemap.remove_annotation((-1, 0)), Some("door".to_string()));
+        assert_eq!(tilemap.remove_annotation((-1, 0)), None);
+        tilemap.clear_annotations();
+        assert_eq!(tilemap.annotations().count(), 0);
+    }
+
+    #[test]
+    fn test_unload_chunk() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .persist_unloaded_chunks()
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.take_chunk_events();
+
+        // A chunk which was never spawned has nothing to despawn.
+        tilemap.unload_chunk((0, 0)).unwrap();
+        assert!(tilemap.take_chunk_events().is_empty());
+        assert!(tilemap.take_unloaded_chunks().is_empty());
+        assert!(tilemap.unload_chunk((1, 0)).is_err());
+
+        // A chunk waiting to be spawned is despawned again.
+        tilemap.spawn_chunk((0, 0)).unwrap();
+        tilemap.unload_chunk((0, 0)).unwrap();
+        let events = tilemap.take_chunk_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], TilemapChunkEvent::Despawned { .. }));
+        assert_eq!(tilemap.take_unloaded_chunks().len(), 1);
+
+        // The tilemap systems mark the chunk as spawned.
+        tilemap.spawned_chunks_mut().insert((0, 0));
+        tilemap.unload_chunk((0, 0)).unwrap();
+        let events = tilemap.take_chunk_events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], TilemapChunkEvent::Despawned { .. }));
+        assert!(tilemap.spawned_chunks().is_empty());
+        assert!(tilemap.contains_chunk((0, 0)));
+    }
+
+    #[test]
+    fn test_reset_all_chunks() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        for point in [(0, 0), (1, 0), (0, 1)].iter() {
+            tilemap.insert_chunk(*point).unwrap();
+        }
+        // The tilemap systems mark the chunks as spawned.
+        tilemap.spawned_chunks_mut().insert((0, 0));
+        tilemap.spawned_chunks_mut().insert((1, 0));
+        tilemap
+            .fill_rect((-2, -2), (5, 1), 0, 0, RawTile::default())
+            .unwrap();
+        tilemap.take_chunk_events();
+
+        tilemap.clear_all_tiles().unwrap();
+        assert_eq!(tilemap.get_tile((5, 1), 0), None);
+        assert_eq!(tilemap.take_chunk_events().len(), 2);
+
+        tilemap.unload_all_chunks().unwrap();
+        let events = tilemap.take_chunk_events();
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| matches!(event, TilemapChunkEvent::Despawned { .. })));
+        assert!(tilemap.spawned_chunks().is_empty());
+
+        tilemap.spawned_chunks_mut().insert((0, 1));
+        tilemap.delete_all_chunks().unwrap();
+        let events = tilemap.take_chunk_events();
+        assert_eq!(events.len(), 3);
+        assert!(events
+            .iter()
+            .all(|event| matches!(event, TilemapChunkEvent::Deleted { .. })));
+        assert_eq!(tilemap.take_deleted_chunks().len(), 3);
+        assert!(tilemap.spawned_chunks().is_empty());
+        assert!(!tilemap.contains_chunk((0, 1)));
+    }
+
+    #[test]
+    fn test_fill_tile_variants() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let variants = TileVariants::new().with(4, 1).with(5, 1).seeded(3);
+        tilemap.set_tile_variants(1, Some(variants.clone()));
+        let tile = RawTile {
+            index: 1,
+            ..Default::default()
+        };
+
+        tilemap.fill_rect((-4, -4), (4, 4), 0, 0, tile).unwrap();
+        let sprites = |tilemap: &mut Tilemap| {
+            Tilemap::rect_points(Point2::new(-4, -4), Point2::new(4, 4))
+                .map(|point| tilemap.get_tile(point, 0).unwrap().index)
+                .collect::<Vec<usize>>()
+        };
+        let filled = sprites(&mut tilemap);
+        assert!(filled.contains(&4));
+        assert!(filled.contains(&5));
+        assert_eq!(
+            filled[0],
+            variants.pick(Tilemap::position_hash((-4, -4), 3)).unwrap()
+        );
+
+        // Seeded variants are the same every time.
+        tilemap.fill_rect((-4, -4), (4, 4), 0, 0, tile).unwrap();
+        assert_eq!(sprites(&mut tilemap), filled);
+
+        // Without variants the tile is copied as is.
+        tilemap.set_tile_variants(1, None);
+        tilemap.fill_row(0, -4, 4, 0, 0, tile).unwrap();
+        assert_eq!(tilemap.get_tile((2, 0), 0).unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_fill_rect() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        for point in [(0, 0), (1, 0)].iter() {
+            tilemap.insert_chunk(*point).unwrap();
+            let chunk = tilemap.chunks.get_mut(&(*point).into()).unwrap();
+            chunk.set_mesh(0, Handle::weak(HandleId::random::<Mesh>()));
+        }
+        tilemap.take_chunk_events();
+        let tile = RawTile {
+            index: 7,
+            ..Default::default()
+        };
+        let modified = |tilemap: &mut Tilemap| {
+            let mut points: Vec<Point2> = tilemap
+                .take_chunk_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    TilemapChunkEvent::Modified { point } => Some(point),
+                    _ => None,
+                })
+                .collect();
+            points.sort_by_key(|point| point.x);
+            points
+        };
+
+        // The corners are inclusive in either order, with one event for each
+        // chunk.
+        tilemap.fill_rect((5, 1), (-2, -2), 0, 0, tile).unwrap();
+        assert_eq!(
+            modified(&mut tilemap),
+            vec![Point2::new(0, 0), Point2::new(1, 0)]
+        );
+        for point in [(-2, -2), (5, -2), (-2, 1), (5, 1)].iter() {
+            assert_eq!(tilemap.get_tile(*point, 0), Some(&tile));
+        }
+        assert_eq!(tilemap.get_tile((6, 0), 0), None);
+
+        tilemap.clear_rect((-2, -2), (5, 1), 0, 0).unwrap();
+        assert_eq!(
+            modified(&mut tilemap),
+            vec![Point2::new(0, 0), Point2::new(1, 0)]
+        );
+        assert_eq!(tilemap.get_tile((0, 0), 0), None);
+
+        tilemap.fill_row(1, 3, -2, 0, 0, tile).unwrap();
+        assert_eq!(
+            modified(&mut tilemap),
+            vec![Point2::new(0, 0), Point2::new(1, 0)]
+        );
+        assert_eq!(tilemap.get_tile((3, 1), 0), Some(&tile));
+        assert_eq!(tilemap.get_tile((3, 0), 0), None);
+        tilemap.fill_column(4, -2, 0, 0, 0, tile).unwrap();
+        assert_eq!(modified(&mut tilemap), vec![Point2::new(1, 0)]);
+        assert_eq!(tilemap.get_tile((4, -2), 0), Some(&tile));
+        assert_eq!(tilemap.get_tile((4, 1), 0), None);
+    }
+
+    #[test]
+    fn test_fill_sprite_order_and_z_depth() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(8, 8, 2)
+            .add_layer(TilemapLayer::default(), 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let tile = RawTile {
+            index: 7,
+            ..Default::default()
+        };
+
+        // The sprite order comes before the z depth, as in `get_tile`.
+        tilemap.fill_rect((0, 0), (1, 1), 1, 0, tile).unwrap();
+        assert_eq!(tilemap.get_tile((1, 1, 0), 1), Some(&tile));
+        assert_eq!(tilemap.get_tile((1, 1, 0), 0), None);
+        assert!(tilemap.fill_column(0, 0, 1, 0, 2, tile).is_err());
+
+        tilemap.clear_rect((0, 0), (1, 1), 1, 0).unwrap();
+        assert_eq!(tilemap.get_tile((1, 1, 0), 1), None);
+    }
+
+    #[test]
+    fn test_draw_sprite_order_and_z_depth() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(8, 8, 2)
+            .add_layer(TilemapLayer::default(), 1)
+            .finish()
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        let wall = RawTile {
+            index: 3,
+            ..Default::default()
+        };
+        let floor = RawTile {
+            index: 4,
+            ..Default::default()
+        };
+
+        tilemap
+            .draw_circle((0, 0), 2, CircleFill::Hollow, 1, 0, wall)
+            .unwrap();
+        assert_eq!(tilemap.get_tile((2, 0, 0), 1), Some(&wall));
+        assert_eq!(tilemap.get_tile((2, 0, 0), 0), None);
+
+        tilemap.flood_fill((0, 0), 1, 0, floor).unwrap();
+        assert_eq!(tilemap.get_tile((0, 0, 0), 1), Some(&floor));
+        assert_eq!(tilemap.get_tile((3, 0, 0), 1), None);
+
+        tilemap.draw_line((-3, 3), (3, 3), 1, 0, wall).unwrap();
+        assert_eq!(tilemap.get_tile((3, 3, 0), 1), Some(&wall));
+    }
+
+    #[test]
+    fn test_build_colliders() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .auto_chunk()
+            .solid_layer(0)
+            .collider_budget(12)
+            .finish()
+            .unwrap();
+        // 16 solid tiles in each of three chunks, and none in a fourth.
+        tilemap
+            .fill_rect((-2, -2), (9, 1), 0, 0, RawTile::default())
+            .unwrap();
+        tilemap.insert_chunk((0, 1)).unwrap();
+        for point in [(0, 0), (1, 0), (2, 0), (0, 1)].iter() {
+            tilemap.queue_colliders(*point);
+            tilemap.spawned_chunks_mut().insert(*point);
+        }
+        tilemap.queue_colliders((0, 0));
+
+        // The chunks nearest to the focus are built first, within the budget.
+        let built = tilemap.build_colliders((2, 0));
+        assert_eq!(built.len(), 1);
+        assert_eq!(built[0].point, Point2::new(2, 0));
+        assert_eq!(built[0].colliders.len(), 12);
+        assert!(!built[0].complete);
+
+        let built = tilemap.build_colliders((2, 0));
+        let points: Vec<(Point2, usize, bool)> = built
+            .iter()
+            .map(|built| (built.point, built.colliders.len(), built.complete))
+            .collect();
+        assert_eq!(
+            points,
+            vec![(Point2::new(2, 0), 4, true), (Point2::new(1, 0), 8, false)]
+        );
+
+        // A chunk which despawns stops waiting for its colliders.
+        tilemap.unload_chunk((1, 0)).unwrap();
+        let built = tilemap.build_colliders((2, 0));
+        let points: Vec<(Point2, usize, bool)> = built
+            .iter()
+            .map(|built| (built.point, built.colliders.len(), built.complete))
+            .collect();
+        assert_eq!(
+            points,
+            vec![(Point2::new(0, 0), 12, false), (Point2::new(0, 1), 0, true)]
+        );
+        assert_eq!(tilemap.build_colliders((2, 0))[0].colliders.len(), 4);
+        assert!(tilemap.build_colliders((2, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_set_chunk_dimensions() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        tilemap
+            .insert_tile(Tile {
+                point: (-3, 5),
+                sprite_index: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.set_tile_property((1, 1), 0, "ore", 20).unwrap();
+        tilemap.lock_chunk((1, 0));
+
+        tilemap.set_chunk_dimensions(2, 2, 1).unwrap();
+        assert_eq!(tilemap.chunk_dimensions(), Dimension3::new(2, 2, 1));
+        assert_eq!(tilemap.get_tile((-3, 5), 0).map(|tile| tile.index), Some(2));
+        assert!(tilemap.tile_property((1, 1), 0, "ore").is_some());
+        // The locked chunk covered the points from (2, -2) to (5, 1).
+        assert!(tilemap.is_locked((5, 1)));
+        assert!(!tilemap.is_locked((6, 1)));
+
+        assert!(tilemap.set_chunk_dimensions(2, 2, 0).is_err());
+        tilemap.spawned_chunks_mut().insert((0, 0));
+        let error = tilemap.set_chunk_dimensions(8, 8, 1).unwrap_err();
+        assert_eq!(*error.0, ErrorKind::ChunkSpawned(Point2::new(0, 0)));
+    }
+
+    #[test]
+    fn test_set_chunk_dimensions_keeps_empty_chunks() {
+        let builder = || {
+            TilemapBuilder::new()
+                .texture_atlas(Handle::weak(HandleId::random::<TextureAtlas>()))
+                .texture_dimensions(32, 32)
+                .chunk_dimensions(4, 4, 1)
+                .finish()
+                .unwrap()
+        };
+        let mut tilemap = builder();
+        tilemap.start_recording();
+        tilemap.insert_chunk((3, 3)).unwrap();
+
+        tilemap.set_chunk_dimensions(2, 2, 1).unwrap();
+        // The empty chunk covered the points from (10, 10) to (13, 13).
+        for point in [(10, 10), (13, 10), (10, 13), (13, 13)].iter() {
+            let chunk_point = tilemap.point_to_chunk_point(*point);
+            assert!(tilemap.contains_chunk(chunk_point));
+        }
+        // The new chunks are centered on their points, so 3 by 3 are needed.
+        assert_eq!(tilemap.chunks.len(), 9);
+
+        // The change is replayed along with the chunks.
+        let recording = tilemap.stop_recording().unwrap();
+        let mut replayed = builder();
+        recording.replay(&mut replayed).unwrap();
+        assert_eq!(replayed.chunk_dimensions(), Dimension3::new(2, 2, 1));
+        assert_eq!(replayed.chunks.len(), 9);
+
+        // A stream of the old dimensions can not be loaded into new chunks.
+        let mut bytes = Vec::new();
+        builder().save_streaming(&mut bytes).unwrap();
+        let mut streaming = builder();
+        streaming
+            .load_streaming(IoCursor::new(bytes), Duration::from_millis(4))
+            .unwrap();
+        let error = streaming.set_chunk_dimensions(2, 2, 1).unwrap_err();
+        assert_eq!(*error.0, ErrorKind::StreamInProgress);
+        assert_eq!(streaming.chunk_dimensions(), Dimension3::new(4, 4, 1));
+    }
+
+    #[test]
+    fn test_layer_spawn_priorities() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .add_layer(
+                TilemapLayer {
+                    kind: LayerKind::Sparse,
+                },
+                1,
+            )
+            .add_layer(
+                TilemapLayer {
+                    kind: LayerKind::Sparse,
+                },
+                2,
+            )
+            .auto_spawn_budget(1)
+            .layer_spawn_priority(0, 2)
+            .layer_spawn_priority(1, 1)
+            .finish()
+            .unwrap();
+        let point = Point2::new(0, 0);
+        tilemap.insert_chunk(point).unwrap();
+
+        tilemap.hold_back_layers(point);
+        let held_back: HashSet<usize> = vec![1, 2].into_iter().collect();
+        assert_eq!(tilemap.chunk_hidden_layers(point), Some(held_back));
+        tilemap.reveal_held_back_layers();
+        let held_back: HashSet<usize> = vec![2].into_iter().collect();
+        assert_eq!(tilemap.chunk_hidden_layers(point), Some(held_back));
+        tilemap.reveal_held_back_layers();
+        assert_eq!(tilemap.chunk_hidden_layers(point), None);
+
+        // Without a budget every layer spawns at once.
+        tilemap.auto_spawn_budget = None;
+        tilemap.hold_back_layers(point);
+        assert_eq!(tilemap.chunk_hidden_layers(point), None);
+    }
+
+    #[test]
+    fn test_auto_spawn_created_chunk() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .auto_chunk()
+            .finish()
+            .unwrap();
+        let existing = Point2::new(0, 0);
+        tilemap.insert_chunk(existing).unwrap();
+        tilemap.take_chunk_events();
+
+        let created_points = |events: Vec<TilemapChunkEvent>| {
+            events
+                .into_iter()
+                .filter_map(|event| match event {
+                    TilemapChunkEvent::Created { point } => Some(point),
+                    _ => None,
+                })
+                .collect::<Vec<Point2>>()
+        };
+
+        // An existing empty chunk needs population but was not created.
+        tilemap.auto_spawn_chunk(existing).unwrap();
+        let events = tilemap.take_chunk_events();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TilemapChunkEvent::NeedsPopulation { point } if *point == existing
+        )));
+        assert!(created_points(events).is_empty());
+
+        let created = Point2::new(1, 0);
+        tilemap.auto_spawn_chunk(created).unwrap();
+        assert_eq!(created_points(tilemap.take_chunk_events()), vec![created]);
+        assert!(tilemap.contains_chunk(created));
+    }
+
+    #[cfg(feature = "scene")]
+    #[test]
+    fn test_reload() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let tile = |sprite_index| Tile {
+            point: (1, 1),
+            sprite_index,
+            ..Default::default()
+        };
+        let mut tilemap = Tilemap::new(texture_atlas_handle.clone(), 32, 32);
+        tilemap.insert_chunk((0, 0)).unwrap();
+        tilemap.insert_chunk((0, 1)).unwrap();
+        tilemap.insert_tile(tile(1)).unwrap();
+        tilemap.spawned.insert((0, 0));
+        tilemap
+            .chunks
+            .get_mut(&Point2::new(0, 0))
+            .unwrap()
+            .set_mesh(0, Handle::weak(HandleId::random::<Mesh>()));
+        tilemap.take_chunk_events();
+
+        let mut reloaded = Tilemap::new(Handle::default(), 32, 32);
+        reloaded.insert_chunk((0, 0)).unwrap();
+        reloaded.insert_chunk((1, 0)).unwrap();
+        reloaded.insert_tile(tile(2)).unwrap();
+        reloaded.take_chunk_events();
+        reloaded.spawned.insert((0, 0));
+        reloaded.spawned.insert((1, 0));
+        reloaded.respawn_saved_chunks();
+        tilemap.reload(reloaded);
+
+        // The spawned chunk is patched in place and the missing one deleted.
+        assert_eq!(tilemap.texture_atlas(), &texture_atlas_handle);
+        assert_eq!(tilemap.get_tile((1, 1), 0).unwrap().index, 2);
+        assert!(tilemap.spawned.contains(&(0, 0)));
+        assert!(!tilemap.contains_chunk((0, 1)));
+        let deleted = tilemap.take_deleted_chunks();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].point(), Point2::new(0, 1));
+        let events = tilemap.take_chunk_events();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TilemapChunkEvent::Modified { point } if *point == Point2::new(0, 0)
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TilemapChunkEvent::Spawned { point } if *point == Point2::new(1, 0)
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TilemapChunkEvent::Deleted { point } if *point == Point2::new(0, 1)
+        )));
+
+        // Chunks of another size are replaced and respawned.
+        let mut resized = TilemapBuilder::new()
+            .texture_atlas(Handle::default())
+            .chunk_dimensions(16, 16, 1)
+            .texture_dimensions(32, 32)
+            .finish()
+            .unwrap();
+        resized.insert_chunk((0, 0)).unwrap();
+        resized.take_chunk_events();
+        tilemap.reload(resized);
+        assert_eq!(tilemap.take_deleted_chunks().len(), 2);
+        assert!(tilemap.take_chunk_events().iter().any(|event| matches!(
+            event,
+            TilemapChunkEvent::Spawned { point } if *point == Point2::new(0, 0)
+        )));
+    }
+
+    #[test]
+    fn test_hex_shape_queries() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .topology(GridTopology::HexY)
+            .auto_chunk()
+            .solid_layer(0)
+            .finish()
+            .unwrap();
+        let point = Point2::new(2, 1);
+        tilemap
+            .insert_tile(Tile {
+                point,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let cell = tilemap.cell_polygon(point);
+        let center = cell.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex) / 6.0;
+        assert_eq!(tilemap.tiles_in_polygon(&cell), vec![point]);
+        assert!(tilemap.overlaps_solid(Circle::new(center, 0.1)));
+
+        let neighbour = tilemap.cell_polygon(Point2::new(0, 0));
+        let neighbour = neighbour
+            .iter()
+            .fold(Vec2::ZERO, |sum, vertex| sum + *vertex)
+            / 6.0;
+        assert!(!tilemap.overlaps_solid(Circle::new(neighbour, 0.1)));
+    }
+
+    #[test]
+    fn test_hex_raycast() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .topology(GridTopology::HexY)
+            .auto_chunk()
+            .solid_layer(0)
+            .finish()
+            .unwrap();
+        tilemap
+            .insert_tile(Tile {
+                point: (4, 0),
+                ..Default::default()
+            })
+            .unwrap();
+        let cell = tilemap.cell_polygon(Point2::new(0, 0));
+        let start = cell.iter().fold(Vec2::ZERO, |sum, vertex| sum + *vertex) / 6.0;
+
+        // The ray hits the edge of the hexagon, which is half a tile further
+        // than the edge of the square of the same point.
+        let hit = tilemap.raycast(start, Vec2::new(10.0, 0.0)).unwrap();
+        let edge = tilemap
+            .cell_polygon(Point2::new(4, 0))
+            .iter()
+            .fold(f32::MAX, |min, vertex| min.min(vertex.x));
+        assert_eq!(hit.point, Point2::new(4, 0));
+        assert!((hit.position.x - edge).abs() < 0.001);
+        assert!((edge - 4.5).abs() < 0.001);
+        assert_eq!(
+            tilemap.raycast_layer(start, Vec2::new(1.0, 0.0), 10.0, 0),
+            Some(hit)
+        );
+        assert!(tilemap.raycast(start, Vec2::new(2.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_pick_overhanging_hex_sprites() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .topology(GridTopology::HexY)
+            .auto_chunk()
+            .y_sorted_layer(0)
+            .finish()
+            .unwrap();
+        let tree = |bits| ClickMask::Bits {
+            width: 32,
+            height: 64,
+            bits,
+        };
+        tilemap.set_click_mask(1, tree(vec![true; 32 * 64]));
+
+        // A cursor in the upper half of a tree, over another cell.
+        let transform = GlobalTransform::identity();
+        let corner = tilemap
+            .tile_polygon(Point2::new(0, 0))
+            .iter()
+            .fold(Vec2::new(f32::MAX, f32::MAX), |corner, vertex| {
+                corner.min(*vertex)
+            });
+        let cursor = corner + Vec2::new(16.0, 48.0);
+        let cell = tilemap.world_to_point(&transform, cursor);
+        assert!(cell.y > 0);
+
+        for point in [Point2::new(0, 0), cell].iter() {
+            tilemap
+                .insert_tile(Tile {
+                    point: *point,
+                    sprite_index: 1,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        // The lower tree is drawn in front of the one behind it.
+        assert_eq!(
+            tilemap.pick_tile(&transform, cursor, 0),
+            Some(Point2::new(0, 0))
+        );
+
+        // Unless its crown lets the click through.
+        let mut bits = vec![true; 32 * 64];
+        for bit in bits.iter_mut().take(32 * 32) {
+            *bit = false;
+        }
+        tilemap.set_click_mask(1, tree(bits));
+        assert_eq!(tilemap.pick_tile(&transform, cursor, 0), Some(cell));
+
+        // Nothing is picked where neither tree reaches.
+        let far = corner + Vec2::new(16.0, 320.0);
+        assert_eq!(tilemap.pick_tile(&transform, far, 0), None);
+    }
+
+    #[test]
+    fn test_update_flow_field() {
+        let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+        let mut tilemap = TilemapBuilder::new()
+            .texture_atlas(texture_atlas_handle)
+            .texture_dimensions(32, 32)
+            .chunk_dimensions(4, 4, 1)
+            .finish()
+            .unwrap();
+        for chunk_point in [(0, 0), (1, 0), (0, 1)].iter() {
+            tilemap.insert_chunk(*chunk_point).unwrap();
         }
+        let goals = vec![(-2, -2), (5, 1)];
+        let options = PathOptions {
+            diagonals: true,
+            ..Default::default()
+        };
+        let mut field = tilemap.flow_field(goals.clone(), &options);
+        assert_eq!(field.cost((0, 0)), Some(2));
+
+        // A wall between the goals, which a diagonal step can not cut past.
+        let wall = (-2..=4).map(|y| Tile {
+            point: (1, y),
+            ..Default::default()
+        });
+        tilemap.insert_tiles(wall).unwrap();
+        assert!(tilemap.update_flow_field(&mut field));
+        assert_eq!(field, tilemap.flow_field(goals.clone(), &options));
+        assert_eq!(field.cost((1, 0)), None);
+
+        tilemap.clear_tile((1, 4), 0).unwrap();
+        tilemap
+            .insert_tile(Tile {
+                point: (3, -1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(tilemap.update_flow_field(&mut field));
+        assert_eq!(field, tilemap.flow_field(goals.clone(), &options));
+
+        tilemap.insert_chunk((1, 1)).unwrap();
+        tilemap.delete_chunk((1, 0)).unwrap();
+        assert!(tilemap.update_flow_field(&mut field));
+        assert_eq!(field, tilemap.flow_field(goals, &options));
+        assert_eq!(field.cost((5, 1)), None);
+        assert!(!tilemap.update_flow_field(&mut field));
     }
 
     // fn new_tilemap_no_auto() -> Tilemap {
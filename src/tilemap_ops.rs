@@ -0,0 +1,187 @@
+//! Rasterizing lines, circles and flood fills into tiles.
+//!
+//! [`Tilemap::line_points`], [`Tilemap::circle_points`] and
+//! [`Tilemap::flood_fill_points`] return the points a shape covers, following
+//! the grid topology of the tilemap, which is useful for previews such as the
+//! area of a spell. [`Tilemap::draw_line`], [`Tilemap::draw_circle`] and
+//! [`Tilemap::flood_fill`] set copies of a raw tile on those points in one
+//! go.
+//!
+//! On a square topology lines are Bresenham lines and circles are round. On
+//! hex topologies every step of a line moves to a tile sharing an edge and
+//! circles are hexagons of tiles within a number of steps of the center.
+//!
+//! [`Tilemap::line_points`]: crate::tilemap::Tilemap::line_points
+//! [`Tilemap::circle_points`]: crate::tilemap::Tilemap::circle_points
+//! [`Tilemap::flood_fill_points`]: crate::tilemap::Tilemap::flood_fill_points
+//! [`Tilemap::draw_line`]: crate::tilemap::Tilemap::draw_line
+//! [`Tilemap::draw_circle`]: crate::tilemap::Tilemap::draw_circle
+//! [`Tilemap::flood_fill`]: crate::tilemap::Tilemap::flood_fill
+//!
+//! # Drawing a room
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, chunk::RawTile, tilemap_ops::CircleFill};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! let wall = RawTile { index: 1, ..Default::default() };
+//! let floor = RawTile { index: 2, ..Default::default() };
+//! tilemap.draw_circle((0, 0), 5, CircleFill::Hollow, 0, 0, wall).unwrap();
+//! tilemap.flood_fill((0, 0), 0, 0, floor).unwrap();
+//!
+//! assert_eq!(tilemap.get_tile((5, 0), 0).map(|tile| tile.index), Some(1));
+//! assert_eq!(tilemap.get_tile((4, 0), 0).map(|tile| tile.index), Some(2));
+//! assert_eq!(tilemap.get_tile((6, 0), 0), None);
+//! ```
+
+use crate::lib::*;
+
+/// Which points of a circle are covered.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CircleFill {
+    /// Every point within the circle.
+    Filled,
+    /// Only the points on the edge of the circle.
+    Hollow,
+}
+
+/// Returns the points of a Bresenham line between two points, including
+/// both ends.
+pub(crate) fn bresenham_line(from: Point2, to: Point2) -> Vec<Point2> {
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let step_x = if from.x < to.x { 1 } else { -1 };
+    let step_y = if from.y < to.y { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut point = from;
+    let mut points = vec![point];
+    while point != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            point.x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            point.y += step_y;
+        }
+        points.push(point);
+    }
+    points
+}
+
+/// Returns the points of a round circle on a square grid.
+///
+/// The edge of a hollow circle is every point of the filled circle with a
+/// point sharing an edge outside of it.
+pub(crate) fn square_circle(center: Point2, radius: u32, fill: CircleFill) -> Vec<Point2> {
+    let radius = radius as Coord;
+    // Adding the radius rounds the edge out, which avoids lone points at the
+    // four extremes.
+    let inside = |x: Coord, y: Coord| x * x + y * y <= radius * radius + radius;
+    let mut points = Vec::new();
+    for y in -radius..=radius {
+        for x in -radius..=radius {
+            if !inside(x, y) {
+                continue;
+            }
+            let edge =
+                !inside(x + 1, y) || !inside(x - 1, y) || !inside(x, y + 1) || !inside(x, y - 1);
+            if fill == CircleFill::Filled || edge {
+                points.push(Point2::new(center.x + x, center.y + y));
+            }
+        }
+    }
+    points
+}
+
+/// Returns every point connected to the start through accepted points,
+/// including the start if it is accepted.
+pub(crate) fn flood_fill<N, A>(start: Point2, mut neighbours: N, mut accept: A) -> Vec<Point2>
+where
+    N: FnMut(Point2) -> Vec<Point2>,
+    A: FnMut(Point2) -> bool,
+{
+    if !accept(start) {
+        return Vec::new();
+    }
+    let mut visited = HashSet::default();
+    visited.insert(start);
+    let mut filled = Vec::new();
+    let mut frontier = vec![start];
+    while let Some(point) = frontier.pop() {
+        filled.push(point);
+        for neighbour in neighbours(point) {
+            if visited.insert(neighbour) && accept(neighbour) {
+                frontier.push(neighbour);
+            }
+        }
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bresenham_line() {
+        let line = bresenham_line(Point2::new(0, 0), Point2::new(4, 2));
+        assert_eq!(
+            line,
+            vec![
+                Point2::new(0, 0),
+                Point2::new(1, 1),
+                Point2::new(2, 1),
+                Point2::new(3, 2),
+                Point2::new(4, 2),
+            ]
+        );
+        assert_eq!(
+            bresenham_line(Point2::new(2, 2), Point2::new(2, 2)),
+            vec![Point2::new(2, 2)]
+        );
+        assert_eq!(
+            bresenham_line(Point2::new(0, 3), Point2::new(0, -3)).len(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_square_circle() {
+        let center = Point2::new(10, 10);
+        assert_eq!(square_circle(center, 0, CircleFill::Filled), vec![center]);
+
+        let filled = square_circle(center, 3, CircleFill::Filled);
+        let hollow = square_circle(center, 3, CircleFill::Hollow);
+        assert!(filled.contains(&center));
+        assert!(!hollow.contains(&center));
+        assert!(hollow.contains(&Point2::new(13, 10)));
+        assert!(hollow.iter().all(|point| filled.contains(point)));
+        assert!(!filled.contains(&Point2::new(13, 13)));
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let neighbours = |point: Point2| {
+            vec![
+                Point2::new(point.x + 1, point.y),
+                Point2::new(point.x - 1, point.y),
+                Point2::new(point.x, point.y + 1),
+                Point2::new(point.x, point.y - 1),
+            ]
+        };
+        // A 7 by 7 box split by a wall at x = 1.
+        let open = |point: Point2| point.x.abs() <= 3 && point.y.abs() <= 3 && point.x != 1;
+
+        assert_eq!(flood_fill(Point2::new(0, 0), neighbours, open).len(), 28);
+        assert_eq!(flood_fill(Point2::new(2, 0), neighbours, open).len(), 14);
+        assert!(flood_fill(Point2::new(1, 0), neighbours, open).is_empty());
+    }
+}
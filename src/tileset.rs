@@ -0,0 +1,321 @@
+//! Texture atlases built from a manifest of tiles.
+//!
+//! Instead of loading a folder of textures and looking up sprite indices by
+//! their paths, the tiles of a tilemap can be listed by name in a RON
+//! manifest with the `.tileset` extension.
+//!
+//! ```ron
+//! (
+//!     tiles: [
+//!         (name: "floor", path: "textures/square-floor.png"),
+//!         (name: "wall", path: "textures/square-wall.png"),
+//!     ],
+//! )
+//! ```
+//!
+//! Loading the manifest loads all of its textures as a [`Tileset`]. Once they
+//! are loaded the texture atlas is built, the [`TileRegistry`] of the tileset
+//! is filled with the sprite index of every tile name and a
+//! [`TilesetReadyEvent`] is sent.
+//!
+//! [`TilesetReadyEvent`]: crate::event::TilesetReadyEvent
+//!
+//! # Building a tilemap from a tileset
+//! ```no_run
+//! use bevy_app::prelude::*;
+//! use bevy_asset::prelude::*;
+//! use bevy_ecs::prelude::*;
+//! use bevy_render::prelude::*;
+//! use bevy_tilemap::{event::TilesetReadyEvent, prelude::*, tileset::Tileset};
+//!
+//! fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     let tileset: Handle<Tileset> = asset_server.load("square.tileset");
+//!     commands.insert_resource(tileset);
+//! }
+//!
+//! fn build(
+//!     mut commands: Commands,
+//!     mut ready_events: EventReader<TilesetReadyEvent>,
+//!     tilesets: Res<Assets<Tileset>>,
+//! ) {
+//!     for event in ready_events.iter() {
+//!         let tileset = tilesets.get(&event.tileset).unwrap();
+//!         let mut tilemap = Tilemap::new(event.texture_atlas.clone(), 32, 32);
+//!         let floor = tileset.registry().get("floor").unwrap();
+//!         tilemap.insert_tile(Tile { point: (0, 0), sprite_index: floor, ..Default::default() }).unwrap();
+//!         commands.spawn().insert_bundle(TilemapBundle {
+//!             tilemap,
+//!             visible: Visible { is_visible: true, is_transparent: true },
+//!             transform: Default::default(),
+//!             global_transform: Default::default(),
+//!         });
+//!     }
+//! }
+//!
+//! App::build()
+//!     .add_plugins(TilemapDefaultPlugins)
+//!     .add_startup_system(setup.system())
+//!     .add_system(build.system())
+//!     .run()
+//! ```
+
+use crate::{event::TilesetReadyEvent, lib::*};
+
+/// A tile in a tileset manifest.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub struct TilesetManifestTile {
+    /// The name the tile is registered with.
+    pub name: String,
+    /// The path of the texture of the tile, relative to the assets folder.
+    pub path: String,
+}
+
+/// A manifest of the tiles in a tileset, as stored in a `.tileset` file.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub struct TilesetManifest {
+    /// The tiles of the tileset.
+    pub tiles: Vec<TilesetManifestTile>,
+}
+
+/// The sprite indices of tiles in a texture atlas by name.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TileRegistry {
+    /// The sprite indices by name.
+    indices: HashMap<String, usize>,
+    /// The paths of the textures of the tiles by name, relative to the assets
+    /// folder.
+    paths: HashMap<String, String>,
+}
+
+impl TileRegistry {
+    /// Registers the sprite index of a tile name, returning the previous index
+    /// if the name was already registered.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tileset::TileRegistry;
+    ///
+    /// let mut registry = TileRegistry::default();
+    ///
+    /// assert_eq!(registry.insert("grass", 3), None);
+    /// assert_eq!(registry.insert("grass", 4), Some(3));
+    /// ```
+    pub fn insert<S: Into<String>>(&mut self, name: S, sprite_index: usize) -> Option<usize> {
+        self.indices.insert(name.into(), sprite_index)
+    }
+
+    /// Returns the sprite index of a tile name.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tileset::TileRegistry;
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.insert("grass", 3);
+    ///
+    /// assert_eq!(registry.get("grass"), Some(3));
+    /// assert_eq!(registry.get("water"), None);
+    /// ```
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Sets the path of the texture of a tile name, relative to the assets
+    /// folder, returning the previous path if it had one.
+    ///
+    /// The paths are set for the tiles of a tileset manifest and are used to
+    /// show the textures of the tiles when a tilemap is exported to Tiled.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::tileset::TileRegistry;
+    ///
+    /// let mut registry = TileRegistry::default();
+    /// registry.insert("grass", 3);
+    /// registry.set_path("grass", "textures/grass.png");
+    ///
+    /// assert_eq!(registry.path("grass"), Some("textures/grass.png"));
+    /// assert_eq!(registry.path("water"), None);
+    /// ```
+    pub fn set_path<N, P>(&mut self, name: N, path: P) -> Option<String>
+    where
+        N: Into<String>,
+        P: Into<String>,
+    {
+        self.paths.insert(name.into(), path.into())
+    }
+
+    /// Returns the path of the texture of a tile name, if it has one.
+    pub fn path(&self, name: &str) -> Option<&str> {
+        self.paths.get(name).map(|path| path.as_str())
+    }
+
+    /// Returns an iterator over the tile names and their sprite indices.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.indices
+            .iter()
+            .map(|(name, sprite_index)| (name.as_str(), *sprite_index))
+    }
+
+    /// Returns the number of registered tiles.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns `true` if no tiles are registered.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// The textures of a tileset manifest and the texture atlas built from them.
+#[derive(Debug)]
+pub struct Tileset {
+    /// The handles of the textures of the tiles by name.
+    textures: Vec<(String, Handle<Texture>)>,
+    /// The paths of the textures of the tiles by name.
+    paths: HashMap<String, String>,
+    /// The texture atlas, once all the textures are loaded.
+    texture_atlas: Option<Handle<TextureAtlas>>,
+    /// The sprite indices of the tiles in the texture atlas.
+    registry: TileRegistry,
+}
+
+impl TypeUuid for Tileset {
+    const TYPE_UUID: Uuid = Uuid::from_u128(228941352082397124611093524089236725114);
+}
+
+impl Tileset {
+    /// Returns the texture atlas of the tileset, or `None` if its textures are
+    /// not loaded yet.
+    pub fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        self.texture_atlas.as_ref()
+    }
+
+    /// Returns the sprite indices of the tiles by name. This is empty until the
+    /// texture atlas is built.
+    pub fn registry(&self) -> &TileRegistry {
+        &self.registry
+    }
+
+    /// Returns an iterator over the tile names and the handles of their
+    /// textures.
+    pub fn textures(&self) -> impl Iterator<Item = (&str, &Handle<Texture>)> {
+        self.textures
+            .iter()
+            .map(|(name, handle)| (name.as_str(), handle))
+    }
+}
+
+/// Loads a [`Tileset`] from a RON [`TilesetManifest`].
+#[derive(Default)]
+pub(crate) struct TilesetLoader;
+
+impl AssetLoader for TilesetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), AnyhowError>> {
+        Box::pin(async move {
+            let manifest: TilesetManifest = from_ron_bytes(bytes)?;
+            let mut textures = Vec::with_capacity(manifest.tiles.len());
+            let mut paths = HashMap::default();
+            let mut dependencies = Vec::with_capacity(manifest.tiles.len());
+            for tile in manifest.tiles {
+                let path = AssetPath::from(tile.path.as_str()).to_owned();
+                textures.push((tile.name.clone(), load_context.get_handle(path.clone())));
+                paths.insert(tile.name, tile.path);
+                dependencies.push(path);
+            }
+            let tileset = Tileset {
+                textures,
+                paths,
+                texture_atlas: None,
+                registry: TileRegistry::default(),
+            };
+            load_context
+                .set_default_asset(LoadedAsset::new(tileset).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tileset"]
+    }
+}
+
+/// Builds the texture atlas of tilesets once all their textures are loaded.
+pub(crate) fn tileset_build(
+    mut pending: Local<Vec<Handle<Tileset>>>,
+    mut asset_events: EventReader<AssetEvent<Tileset>>,
+    mut ready_events: EventWriter<TilesetReadyEvent>,
+    asset_server: Res<AssetServer>,
+    mut tilesets: ResMut<Assets<Tileset>>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    for event in asset_events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                // Setting the texture atlas below modifies the tileset too.
+                let unbuilt = tilesets
+                    .get(handle)
+                    .is_some_and(|tileset| tileset.texture_atlas.is_none());
+                if unbuilt && !pending.contains(handle) {
+                    pending.push(handle.clone_weak());
+                }
+            }
+            AssetEvent::Removed { handle } => pending.retain(|pending| pending != handle),
+        }
+    }
+
+    pending.retain(|handle| {
+        let tileset = match tilesets.get_mut(handle) {
+            Some(tileset) => tileset,
+            None => return false,
+        };
+        let load_state = asset_server
+            .get_group_load_state(tileset.textures.iter().map(|(_, texture)| texture.id));
+        match load_state {
+            LoadState::Loaded => {}
+            LoadState::Failed => {
+                error!("a texture of a tileset failed to load");
+                return false;
+            }
+            _ => return true,
+        }
+
+        let mut builder = TextureAtlasBuilder::default();
+        for (_, texture_handle) in tileset.textures.iter() {
+            if let Some(texture) = textures.get(texture_handle) {
+                builder.add_texture(texture_handle.clone_weak(), texture);
+            }
+        }
+        let texture_atlas = match builder.finish(&mut textures) {
+            Ok(texture_atlas) => texture_atlas,
+            Err(e) => {
+                error!("can not build the texture atlas of a tileset: {:?}", e);
+                return false;
+            }
+        };
+
+        let mut registry = TileRegistry::default();
+        for (name, texture_handle) in tileset.textures.iter() {
+            if let Some(sprite_index) = texture_atlas.get_texture_index(texture_handle) {
+                registry.insert(name.as_str(), sprite_index);
+                if let Some(path) = tileset.paths.get(name) {
+                    registry.set_path(name.as_str(), path.as_str());
+                }
+            }
+        }
+        let texture_atlas = texture_atlases.add(texture_atlas);
+        tileset.texture_atlas = Some(texture_atlas.clone());
+        tileset.registry = registry;
+        ready_events.send(TilesetReadyEvent {
+            tileset: handle.clone_weak(),
+            texture_atlas,
+        });
+        false
+    });
+}
@@ -0,0 +1,396 @@
+//! Exporting tilemaps to the TMX format of the Tiled map editor.
+//!
+//! [`Tilemap::export_tmx`] writes every chunk of a tilemap as an infinite
+//! Tiled map, so procedurally generated maps can be opened and polished by
+//! level designers in Tiled.
+//!
+//! - Every sprite layer at every z depth is a tile layer, from the bottom up,
+//!   named by the name of the sprite layer if it has one. The z depth and
+//!   sprite order of a layer are kept in its `z_depth` and `sprite_order`
+//!   properties.
+//! - Every chunk is a chunk of the layers in the map. Tiled counts rows
+//!   downwards, so the tile at `(x, y)` is at `(x, -1 - y)` in Tiled.
+//! - The tiles reference an embedded image collection tileset where the id of
+//!   a tile is its sprite index. The tiles of the [`TileRegistry`] are named
+//!   by a `name` property and show the texture at their path, if the registry
+//!   has one.
+//! - The flips and rotation of a tile are turned into the flip flags of Tiled.
+//!
+//! The tint, scale and offset of tiles are not exported, and neither are the
+//! tiles of texture atlases other than the main one. Only square tilemaps can
+//! be exported.
+//!
+//! [`Tilemap::export_tmx`]: crate::tilemap::Tilemap::export_tmx
+//!
+//! # Exporting a map for Tiled
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{prelude::*, tileset::TileRegistry};
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//! tilemap.insert_tile(Tile { point: (1, 1), sprite_index: 2, flip_x: true, ..Default::default() }).unwrap();
+//!
+//! let mut registry = TileRegistry::default();
+//! registry.insert("wall", 2);
+//! registry.set_path("wall", "textures/square-wall.png");
+//!
+//! let mut bytes = Vec::new();
+//! tilemap.export_tmx(&mut bytes, &registry).unwrap();
+//!
+//! let tmx = String::from_utf8(bytes).unwrap();
+//! assert!(tmx.contains(r#"<image source="textures/square-wall.png"/>"#));
+//! // The global id of the tile is its sprite index plus one, with the flag
+//! // of a horizontal flip.
+//! assert!(tmx.contains("2147483651"));
+//! ```
+
+use crate::{chunk::RawTile, lib::*, tilemap::TilemapResult, tileset::TileRegistry};
+
+/// The version of the TMX format which is written.
+const TMX_VERSION: &str = "1.5";
+
+/// The flag of a global tile id which flips the tile horizontally.
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+
+/// The flag of a global tile id which flips the tile vertically.
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+
+/// The flag of a global tile id which flips the tile across its diagonal from
+/// the top left to the bottom right, before the other flips.
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+
+/// The Tiled flags of a tile by the flip and rotation bits of a raw tile.
+///
+/// A raw tile is flipped and then turned clockwise, while Tiled flips a tile
+/// across its diagonal, then horizontally and then vertically. A clockwise
+/// turn is a diagonal and then a horizontal flip.
+const TILED_FLAGS: [u32; 16] = [
+    0,
+    FLIPPED_HORIZONTALLY,
+    FLIPPED_VERTICALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_DIAGONALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY,
+    FLIPPED_DIAGONALLY,
+    FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY,
+    FLIPPED_VERTICALLY,
+    FLIPPED_HORIZONTALLY,
+    0,
+    FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY,
+    FLIPPED_DIAGONALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY,
+    FLIPPED_HORIZONTALLY | FLIPPED_DIAGONALLY,
+];
+
+/// A tile layer of a TMX map.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct TmxLayer {
+    /// The z depth of the sprite layer.
+    z_depth: usize,
+    /// The sprite order of the sprite layer.
+    sprite_order: usize,
+    /// The name of the sprite layer, if it has one.
+    name: Option<String>,
+    /// The chunks of the layer by the Tiled point of their top left tile,
+    /// with the global ids of their tiles row by row from the top.
+    chunks: Vec<(Point2, Vec<u32>)>,
+}
+
+impl TmxLayer {
+    /// Constructs a layer without any chunks.
+    pub(crate) fn new(z_depth: usize, sprite_order: usize, name: Option<&str>) -> TmxLayer {
+        TmxLayer {
+            z_depth,
+            sprite_order,
+            name: name.map(ToString::to_string),
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the layer is the sprite layer at a z depth.
+    pub(crate) fn is_layer(&self, z_depth: usize, sprite_order: usize) -> bool {
+        self.z_depth == z_depth && self.sprite_order == sprite_order
+    }
+
+    /// Sets a tile of a chunk by its index in the chunk, where the chunk is
+    /// the one whose bottom left tile is at a point.
+    pub(crate) fn set_tile(
+        &mut self,
+        bottom_left: Point2,
+        dimensions: Dimension3,
+        index: usize,
+        tile: &RawTile,
+    ) {
+        let width = dimensions.width as usize;
+        let height = dimensions.height as usize;
+        let top_left = Point2::new(bottom_left.x, -bottom_left.y - height as Coord);
+        // The tiles are set chunk by chunk, so the chunk is nearly always the
+        // last one.
+        let position = match self
+            .chunks
+            .iter()
+            .rposition(|(point, _)| *point == top_left)
+        {
+            Some(position) => position,
+            None => {
+                self.chunks.push((top_left, vec![0; width * height]));
+                self.chunks.len() - 1
+            }
+        };
+        let (x, y) = (index % width, index / width);
+        if let Some(global_id) = self
+            .chunks
+            .get_mut(position)
+            .and_then(|(_, tiles)| tiles.get_mut((height - 1 - y) * width + x))
+        {
+            *global_id = global_id_of(tile);
+        }
+    }
+}
+
+/// Returns the global id of a tile in the tileset, which starts at 1, along
+/// with its flip flags.
+fn global_id_of(tile: &RawTile) -> u32 {
+    let flags = TILED_FLAGS
+        .get(tile.flag_bits() as usize)
+        .copied()
+        .unwrap_or(0);
+    (tile.index as u32 + 1) | flags
+}
+
+/// Writes a TMX map of layers whose tiles are in a tileset of textures with
+/// the given dimensions.
+pub(crate) fn write_map<W: Write>(
+    writer: &mut W,
+    texture_dimensions: Dimension2,
+    chunk_dimensions: Dimension3,
+    layers: &[TmxLayer],
+    registry: &TileRegistry,
+) -> TilemapResult<()> {
+    let mut min = Point2::new(0, 0);
+    let mut max = Point2::new(0, 0);
+    let chunk_points = layers
+        .iter()
+        .flat_map(|layer| layer.chunks.iter().map(|(point, _)| *point));
+    for (count, point) in chunk_points.enumerate() {
+        let end = Point2::new(
+            point.x + chunk_dimensions.width as Coord,
+            point.y + chunk_dimensions.height as Coord,
+        );
+        if count == 0 {
+            min = point;
+            max = end;
+        }
+        min = Point2::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point2::new(max.x.max(end.x), max.y.max(end.y));
+    }
+
+    write!(
+        writer,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <map version=\"{}\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+         width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"1\" \
+         nextlayerid=\"{}\" nextobjectid=\"1\">\n",
+        TMX_VERSION,
+        max.x - min.x,
+        max.y - min.y,
+        texture_dimensions.width,
+        texture_dimensions.height,
+        layers.len() + 1
+    )?;
+    write_tileset(writer, texture_dimensions, registry)?;
+    for (id, layer) in layers.iter().enumerate() {
+        write_layer(
+            writer,
+            id + 1,
+            layer,
+            chunk_dimensions,
+            max.x - min.x,
+            max.y - min.y,
+        )?;
+    }
+    writer.write_all(b"</map>\n")?;
+    Ok(())
+}
+
+/// Writes the embedded image collection tileset of the registered tiles.
+fn write_tileset<W: Write>(
+    writer: &mut W,
+    texture_dimensions: Dimension2,
+    registry: &TileRegistry,
+) -> TilemapResult<()> {
+    write!(
+        writer,
+        " <tileset firstgid=\"1\" name=\"tiles\" tilewidth=\"{}\" tileheight=\"{}\" \
+         tilecount=\"{}\" columns=\"0\">\n  <grid orientation=\"orthogonal\" width=\"1\" \
+         height=\"1\"/>\n",
+        texture_dimensions.width,
+        texture_dimensions.height,
+        registry.len()
+    )?;
+    let mut tiles: Vec<(&str, usize)> = registry.iter().collect();
+    tiles.sort_by_key(|(_name, sprite_index)| *sprite_index);
+    for (name, sprite_index) in tiles {
+        write!(
+            writer,
+            "  <tile id=\"{}\">\n   <properties>\n    <property name=\"name\" value=\"",
+            sprite_index
+        )?;
+        write_escaped(writer, name)?;
+        writer.write_all(b"\"/>\n   </properties>\n")?;
+        if let Some(path) = registry.path(name) {
+            writer.write_all(b"   <image source=\"")?;
+            write_escaped(writer, path)?;
+            writer.write_all(b"\"/>\n")?;
+        }
+        writer.write_all(b"  </tile>\n")?;
+    }
+    writer.write_all(b" </tileset>\n")?;
+    Ok(())
+}
+
+/// Writes a tile layer with its chunks as CSV.
+fn write_layer<W: Write>(
+    writer: &mut W,
+    id: usize,
+    layer: &TmxLayer,
+    chunk_dimensions: Dimension3,
+    width: Coord,
+    height: Coord,
+) -> TilemapResult<()> {
+    write!(writer, " <layer id=\"{}\" name=\"", id)?;
+    match &layer.name {
+        Some(name) => write_escaped(writer, name)?,
+        None => write!(writer, "layer {}", layer.sprite_order)?,
+    }
+    if layer.z_depth > 0 {
+        write!(writer, " at z {}", layer.z_depth)?;
+    }
+    write!(
+        writer,
+        "\" width=\"{}\" height=\"{}\">\n  <properties>\n   \
+         <property name=\"z_depth\" type=\"int\" value=\"{}\"/>\n   \
+         <property name=\"sprite_order\" type=\"int\" value=\"{}\"/>\n  \
+         </properties>\n  <data encoding=\"csv\">\n",
+        width, height, layer.z_depth, layer.sprite_order
+    )?;
+    for (point, tiles) in layer.chunks.iter() {
+        writeln!(
+            writer,
+            "   <chunk x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\">",
+            point.x, point.y, chunk_dimensions.width, chunk_dimensions.height
+        )?;
+        let width = chunk_dimensions.width as usize;
+        let row_count = tiles.len() / width;
+        for (row_index, row) in tiles.chunks(width).enumerate() {
+            for (column, global_id) in row.iter().enumerate() {
+                if column > 0 {
+                    writer.write_all(b",")?;
+                }
+                write!(writer, "{}", global_id)?;
+            }
+            // Every row but the last ends with a comma.
+            if row_index + 1 < row_count {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(b"\n")?;
+        }
+        writer.write_all(b"   </chunk>\n")?;
+    }
+    writer.write_all(b"  </data>\n </layer>\n")?;
+    Ok(())
+}
+
+/// Writes text escaped for an XML attribute.
+fn write_escaped<W: Write>(writer: &mut W, text: &str) -> TilemapResult<()> {
+    for c in text.chars() {
+        match c {
+            '&' => writer.write_all(b"&amp;")?,
+            '<' => writer.write_all(b"&lt;")?,
+            '>' => writer.write_all(b"&gt;")?,
+            '"' => writer.write_all(b"&quot;")?,
+            '\'' => writer.write_all(b"&apos;")?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A transform of the corners of a tile in Tiled's coordinates, where y
+    /// points down.
+    type Matrix = [[i32; 2]; 2];
+
+    const IDENTITY: Matrix = [[1, 0], [0, 1]];
+    const FLIP_X: Matrix = [[-1, 0], [0, 1]];
+    const FLIP_Y: Matrix = [[1, 0], [0, -1]];
+    const DIAGONAL: Matrix = [[0, 1], [1, 0]];
+    const CLOCKWISE: Matrix = [[0, -1], [1, 0]];
+
+    fn mul(a: Matrix, b: Matrix) -> Matrix {
+        let mut c = [[0; 2]; 2];
+        for (i, row) in c.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+            }
+        }
+        c
+    }
+
+    fn when(condition: bool, matrix: Matrix) -> Matrix {
+        if condition {
+            matrix
+        } else {
+            IDENTITY
+        }
+    }
+
+    #[test]
+    fn test_tiled_flags() {
+        for (bits, flags) in TILED_FLAGS.iter().enumerate() {
+            let mut tile = RawTile::default();
+            tile.set_flag_bits(bits as u8);
+            let mut transform = mul(when(tile.flip_y, FLIP_Y), when(tile.flip_x, FLIP_X));
+            for _ in 0..tile.rotation {
+                transform = mul(CLOCKWISE, transform);
+            }
+            let tiled = mul(
+                when(flags & FLIPPED_VERTICALLY != 0, FLIP_Y),
+                mul(
+                    when(flags & FLIPPED_HORIZONTALLY != 0, FLIP_X),
+                    when(flags & FLIPPED_DIAGONALLY != 0, DIAGONAL),
+                ),
+            );
+            assert_eq!(transform, tiled, "flag bits {:04b}", bits);
+        }
+    }
+
+    #[test]
+    fn test_layer_chunks() {
+        let dimensions = Dimension3::new(2, 2, 1);
+        let mut layer = TmxLayer::new(0, 0, None);
+        let tile = RawTile {
+            index: 4,
+            ..Default::default()
+        };
+        // The bottom right tile of the chunk from (-1, -1) to (0, 0).
+        layer.set_tile(Point2::new(-1, -1), dimensions, 1, &tile);
+        layer.set_tile(Point2::new(1, -1), dimensions, 0, &tile);
+        assert_eq!(
+            layer.chunks,
+            vec![
+                (Point2::new(-1, -1), vec![0, 0, 0, 5]),
+                (Point2::new(1, -1), vec![0, 0, 5, 0]),
+            ]
+        );
+    }
+}
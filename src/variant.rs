@@ -0,0 +1,141 @@
+//! Weighted random variations of tiles.
+//!
+//! A group of [`TileVariants`] set with [`Tilemap::set_tile_variants`] stands
+//! for a sprite index, such as four grass sprites which one grass tile is
+//! drawn with. The fill and paint operations of the tilemap, such as
+//! [`Tilemap::fill_rect`], [`Tilemap::draw_line`] and [`Tilemap::flood_fill`],
+//! pick one of the variants by its weight for every tile they place with that
+//! sprite index, so large fills look natural without picking sprites by
+//! hand. [`Tilemap::pick_variant`] picks a variant for any other tile.
+//!
+//! Variants with a seed are picked by the point of the tile, so the same
+//! point always gets the same variant on every run and platform. Without a
+//! seed every pick is different, and filling the same tiles again shuffles
+//! them.
+//!
+//! [`Tilemap::set_tile_variants`]: crate::tilemap::Tilemap::set_tile_variants
+//! [`Tilemap::fill_rect`]: crate::tilemap::Tilemap::fill_rect
+//! [`Tilemap::draw_line`]: crate::tilemap::Tilemap::draw_line
+//! [`Tilemap::flood_fill`]: crate::tilemap::Tilemap::flood_fill
+//! [`Tilemap::pick_variant`]: crate::tilemap::Tilemap::pick_variant
+//!
+//! # Filling a meadow
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{chunk::RawTile, prelude::*, variant::TileVariants};
+//!
+//! // In production use a strong handle from an actual source.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = Tilemap::new(texture_atlas_handle, 32, 32);
+//! tilemap.insert_chunk((0, 0)).unwrap();
+//!
+//! // Mostly plain grass, sometimes with flowers.
+//! const GRASS: usize = 4;
+//! let grass = TileVariants::new()
+//!     .with(4, 6)
+//!     .with(5, 2)
+//!     .with(6, 1)
+//!     .with(7, 1)
+//!     .seeded(42);
+//! tilemap.set_tile_variants(GRASS, Some(grass));
+//!
+//! tilemap.fill_rect((-8, -8), (8, 8), 0, 0, RawTile { index: GRASS, ..Default::default() }).unwrap();
+//!
+//! let sprite = tilemap.get_tile((3, -2), 0).unwrap().index;
+//! assert!((4..8).contains(&sprite));
+//! ```
+
+use crate::lib::*;
+
+/// The sprite indices a tile can be drawn with, by their weights.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TileVariants {
+    /// The sprite indices and their weights.
+    variants: Vec<(usize, u32)>,
+    /// The seed which the variants are picked by the point with, if any.
+    seed: Option<u64>,
+}
+
+impl TileVariants {
+    /// Constructs a group without any variants.
+    pub fn new() -> TileVariants {
+        TileVariants::default()
+    }
+
+    /// Adds a sprite index which is picked by a weight relative to the weights
+    /// of the other variants.
+    pub fn with(mut self, sprite_index: usize, weight: u32) -> TileVariants {
+        self.variants.push((sprite_index, weight));
+        self
+    }
+
+    /// Picks the variants by the point of the tile and a seed, so the same
+    /// point always gets the same variant.
+    pub fn seeded(mut self, seed: u64) -> TileVariants {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the sprite indices and their weights.
+    pub fn variants(&self) -> &[(usize, u32)] {
+        &self.variants
+    }
+
+    /// Returns the seed the variants are picked by the point with, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the variant a roll of a hash falls on, or `None` if the
+    /// weights add up to 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::variant::TileVariants;
+    ///
+    /// let variants = TileVariants::new().with(1, 3).with(2, 1);
+    ///
+    /// assert_eq!(variants.pick(2), Some(1));
+    /// assert_eq!(variants.pick(3), Some(2));
+    /// assert_eq!(variants.pick(4), Some(1));
+    /// assert_eq!(TileVariants::new().pick(4), None);
+    /// ```
+    pub fn pick(&self, roll: u64) -> Option<usize> {
+        let total: u64 = self
+            .variants
+            .iter()
+            .map(|(_, weight)| u64::from(*weight))
+            .sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = roll % total;
+        for (sprite_index, weight) in self.variants.iter() {
+            let weight = u64::from(*weight);
+            if roll < weight {
+                return Some(*sprite_index);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_by_weight() {
+        let variants = TileVariants::new().with(1, 0).with(2, 3).with(3, 1);
+        let mut counts = [0; 4];
+        for roll in 0..400 {
+            counts[variants.pick(roll).unwrap()] += 1;
+        }
+        // A variant without weight is never picked.
+        assert_eq!(counts, [0, 0, 300, 100]);
+    }
+}
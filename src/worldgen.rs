@@ -0,0 +1,402 @@
+//! Deterministic noise based world generation.
+//!
+//! With the `worldgen` feature this module has seedable noise and generators
+//! built on it, which pick the sprites of tiles from their points alone. The
+//! same seed always gives the same world on every machine, so a world can be
+//! shared by its seed and chunks can be generated in any order.
+//!
+//! - [`Noise`] is smooth fractal value noise, and [`random`] is white noise
+//!   for scattering details such as trees.
+//! - [`HeightMap`] picks the sprite of a tile by thresholds of the height of
+//!   the noise, such as water below sand below grass below mountains.
+//! - [`BiomeTable`] picks the biome with the nearest temperature and moisture
+//!   from two noises.
+//!
+//! Both generators can be used standalone to look up tiles or set as the
+//! [`ChunkGenerator`] of a tilemap with [`Tilemap::set_generator`].
+//!
+//! [`ChunkGenerator`]: crate::generator::ChunkGenerator
+//! [`Tilemap::set_generator`]: crate::tilemap::Tilemap::set_generator
+//!
+//! # Islands
+//! ```
+//! use bevy_asset::{prelude::*, HandleId};
+//! use bevy_sprite::prelude::*;
+//! use bevy_tilemap::{
+//!     point::Point2,
+//!     prelude::*,
+//!     worldgen::{HeightMap, Noise},
+//! };
+//!
+//! let islands = HeightMap::new(Noise::new(42))
+//!     // Water
+//!     .threshold(0.4, 0)
+//!     // Sand
+//!     .threshold(0.45, 1)
+//!     // Grass
+//!     .threshold(1.0, 2);
+//!
+//! let sprite_index = islands.sprite_index(Point2::new(10, -3)).unwrap();
+//! assert!(sprite_index <= 2);
+//! assert_eq!(islands.sprite_index(Point2::new(10, -3)), Some(sprite_index));
+//!
+//! // This must be set in Asset<TextureAtlas>.
+//! let texture_atlas_handle = Handle::weak(HandleId::random::<TextureAtlas>());
+//!
+//! let mut tilemap = TilemapBuilder::new()
+//!     .texture_atlas(texture_atlas_handle)
+//!     .texture_dimensions(32, 32)
+//!     .auto_spawn(2, 2)
+//!     .finish()
+//!     .unwrap();
+//!
+//! tilemap.set_generator(islands);
+//! ```
+
+use crate::{generator::ChunkGenerator, lib::*, tile::Tile, Tilemap};
+
+/// The frequency of new noise.
+const DEFAULT_FREQUENCY: f32 = 0.05;
+
+/// The octaves of new noise.
+const DEFAULT_OCTAVES: u32 = 4;
+
+/// Mixed into the seed of the moisture noise of a biome table, so it differs
+/// from the temperature noise.
+const MOISTURE_SEED: u64 = 0x6d6f_6973_7475_7265;
+
+/// Smooth fractal value noise from 0.0 to 1.0.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Noise {
+    /// The seed of the noise.
+    pub seed: u64,
+    /// How many hills and valleys there are per tile of the first octave.
+    pub frequency: f32,
+    /// The amount of layers of noise, each with twice the frequency and half
+    /// the strength of the one before it.
+    pub octaves: u32,
+}
+
+impl Noise {
+    /// Constructs noise from a seed with a frequency of 0.05 and 4 octaves.
+    pub fn new(seed: u64) -> Noise {
+        Noise {
+            seed,
+            frequency: DEFAULT_FREQUENCY,
+            octaves: DEFAULT_OCTAVES,
+        }
+    }
+
+    /// Returns the noise at a point, from 0.0 to 1.0.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{point::Point2, worldgen::Noise};
+    ///
+    /// let noise = Noise::new(7);
+    /// let value = noise.get(Point2::new(3, 4));
+    ///
+    /// assert!((0.0..=1.0).contains(&value));
+    /// assert_eq!(Noise::new(7).get(Point2::new(3, 4)), value);
+    /// ```
+    pub fn get(&self, point: Point2) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut frequency = self.frequency;
+        for octave in 0..self.octaves.max(1) {
+            let seed = self.seed.wrapping_add(octave as u64);
+            value += amplitude
+                * value_noise(seed, point.x as f32 * frequency, point.y as f32 * frequency);
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        value / total
+    }
+}
+
+/// Returns white noise at a point from 0.0 up to but excluding 1.0, which
+/// differs for every point and seed.
+///
+/// # Examples
+/// ```
+/// use bevy_tilemap::{point::Point2, worldgen::random};
+///
+/// // A tree on about a tenth of the tiles.
+/// let has_tree = |point: Point2| random(3, point) < 0.1;
+///
+/// assert_eq!(has_tree(Point2::new(5, 5)), has_tree(Point2::new(5, 5)));
+/// ```
+pub fn random(seed: u64, point: Point2) -> f32 {
+    unit(hash(seed, point.x, point.y))
+}
+
+/// A generator which picks the sprite of a tile by the height of noise.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct HeightMap {
+    /// The noise of the height.
+    pub noise: Noise,
+    /// The sprite layer the generated tiles are on.
+    pub sprite_order: usize,
+    /// The highest height of each sprite index from the lowest up.
+    pub thresholds: Vec<(f32, usize)>,
+}
+
+impl HeightMap {
+    /// Constructs a height map on sprite layer 0 without any thresholds.
+    pub fn new(noise: Noise) -> HeightMap {
+        HeightMap {
+            noise,
+            sprite_order: 0,
+            thresholds: Vec::new(),
+        }
+    }
+
+    /// Sets the sprite layer the generated tiles are on.
+    pub fn sprite_order(mut self, sprite_order: usize) -> HeightMap {
+        self.sprite_order = sprite_order;
+        self
+    }
+
+    /// Adds a threshold where the heights up to and including it, and above
+    /// the next lower threshold, are the sprite index.
+    pub fn threshold(mut self, height: f32, sprite_index: usize) -> HeightMap {
+        let index = self
+            .thresholds
+            .iter()
+            .position(|(threshold, _)| *threshold > height)
+            .unwrap_or(self.thresholds.len());
+        self.thresholds.insert(index, (height, sprite_index));
+        self
+    }
+
+    /// Returns the sprite index at a point, or `None` if its height is above
+    /// every threshold.
+    pub fn sprite_index(&self, point: Point2) -> Option<usize> {
+        let height = self.noise.get(point);
+        self.thresholds
+            .iter()
+            .find(|(threshold, _)| height <= *threshold)
+            .map(|(_, sprite_index)| *sprite_index)
+    }
+}
+
+impl ChunkGenerator for HeightMap {
+    fn generate(&self, point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>> {
+        chunk_tiles(point, dimensions, self.sprite_order, |point| {
+            self.sprite_index(point)
+        })
+    }
+}
+
+/// A biome of a [`BiomeTable`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Biome {
+    /// The temperature the biome is most common at, from 0.0 to 1.0.
+    pub temperature: f32,
+    /// The moisture the biome is most common at, from 0.0 to 1.0.
+    pub moisture: f32,
+    /// The sprite index of the tiles of the biome.
+    pub sprite_index: usize,
+}
+
+/// A generator which picks the biome with the nearest temperature and
+/// moisture of two noises.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct BiomeTable {
+    /// The noise of the temperature.
+    pub temperature: Noise,
+    /// The noise of the moisture.
+    pub moisture: Noise,
+    /// The sprite layer the generated tiles are on.
+    pub sprite_order: usize,
+    /// The biomes to pick from.
+    pub biomes: Vec<Biome>,
+}
+
+impl BiomeTable {
+    /// Constructs a biome table on sprite layer 0 without any biomes, whose
+    /// temperature and moisture noises have broad features.
+    ///
+    /// # Examples
+    /// ```
+    /// use bevy_tilemap::{point::Point2, worldgen::{Biome, BiomeTable}};
+    ///
+    /// let biomes = BiomeTable::new(1)
+    ///     .biome(Biome { temperature: 0.9, moisture: 0.1, sprite_index: 0 })
+    ///     .biome(Biome { temperature: 0.5, moisture: 0.6, sprite_index: 1 })
+    ///     .biome(Biome { temperature: 0.1, moisture: 0.5, sprite_index: 2 });
+    ///
+    /// assert!(biomes.biome_at(Point2::new(0, 0)).is_some());
+    /// assert!(BiomeTable::new(1).biome_at(Point2::new(0, 0)).is_none());
+    /// ```
+    pub fn new(seed: u64) -> BiomeTable {
+        let noise = Noise {
+            seed,
+            frequency: DEFAULT_FREQUENCY / 4.0,
+            octaves: DEFAULT_OCTAVES,
+        };
+        BiomeTable {
+            temperature: noise,
+            moisture: Noise {
+                seed: seed ^ MOISTURE_SEED,
+                ..noise
+            },
+            sprite_order: 0,
+            biomes: Vec::new(),
+        }
+    }
+
+    /// Sets the sprite layer the generated tiles are on.
+    pub fn sprite_order(mut self, sprite_order: usize) -> BiomeTable {
+        self.sprite_order = sprite_order;
+        self
+    }
+
+    /// Adds a biome.
+    pub fn biome(mut self, biome: Biome) -> BiomeTable {
+        self.biomes.push(biome);
+        self
+    }
+
+    /// Returns the biome at a point, or `None` if there are no biomes.
+    pub fn biome_at(&self, point: Point2) -> Option<&Biome> {
+        let temperature = self.temperature.get(point);
+        let moisture = self.moisture.get(point);
+        let distance = |biome: &Biome| {
+            let temperature = biome.temperature - temperature;
+            let moisture = biome.moisture - moisture;
+            temperature * temperature + moisture * moisture
+        };
+        let mut nearest: Option<(&Biome, f32)> = None;
+        for biome in self.biomes.iter() {
+            let distance = distance(biome);
+            if nearest.is_none_or(|(_, nearest)| distance < nearest) {
+                nearest = Some((biome, distance));
+            }
+        }
+        nearest.map(|(biome, _)| biome)
+    }
+}
+
+impl ChunkGenerator for BiomeTable {
+    fn generate(&self, point: Point2, dimensions: Dimension3) -> Vec<Tile<Point3>> {
+        chunk_tiles(point, dimensions, self.sprite_order, |point| {
+            self.biome_at(point).map(|biome| biome.sprite_index)
+        })
+    }
+}
+
+/// Returns the tiles of a chunk with points local to the chunk, where the
+/// sprite index of each tile is picked from its global point.
+fn chunk_tiles<F>(
+    chunk_point: Point2,
+    dimensions: Dimension3,
+    sprite_order: usize,
+    mut sprite_index: F,
+) -> Vec<Tile<Point3>>
+where
+    F: FnMut(Point2) -> Option<usize>,
+{
+    let width = dimensions.width as Coord;
+    let height = dimensions.height as Coord;
+    let mut tiles = Vec::with_capacity((dimensions.width * dimensions.height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let point = Point2::new(
+                x + width * chunk_point.x - width / 2,
+                y + height * chunk_point.y - height / 2,
+            );
+            if let Some(sprite_index) = sprite_index(point) {
+                tiles.push(Tile {
+                    point: Point3::new(x, y, 0),
+                    sprite_index,
+                    sprite_order,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    tiles
+}
+
+/// Returns the value noise of a seed at a position, from 0.0 to 1.0.
+///
+/// The random values at the surrounding lattice points are blended with a
+/// smoothstep, so the noise has no creases.
+fn value_noise(seed: u64, x: f32, y: f32) -> f32 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let tx = smoothstep(x - x0);
+    let ty = smoothstep(y - y0);
+    let (x0, y0) = (x0 as Coord, y0 as Coord);
+    let corner = |x: Coord, y: Coord| unit(hash(seed, x, y));
+    let bottom = lerp(corner(x0, y0), corner(x0 + 1, y0), tx);
+    let top = lerp(corner(x0, y0 + 1), corner(x0 + 1, y0 + 1), tx);
+    lerp(bottom, top, ty)
+}
+
+/// Eases a value from 0.0 to 1.0 in and out.
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Linearly interpolates between two values.
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Hashes a seed and a point.
+fn hash(seed: u64, x: Coord, y: Coord) -> u64 {
+    Tilemap::position_hash((x, y), seed)
+}
+
+/// Turns the top 24 bits of a hash into a value from 0.0 up to but excluding
+/// 1.0.
+fn unit(hash: u64) -> f32 {
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_range() {
+        let noise = Noise::new(11);
+        for y in -50..50 {
+            for x in -50..50 {
+                assert!((0.0..=1.0).contains(&noise.get(Point2::new(x, y))));
+                assert!((0.0..1.0).contains(&random(11, Point2::new(x, y))));
+            }
+        }
+        // Neighbouring points of smooth noise are close.
+        let a = noise.get(Point2::new(0, 0));
+        let b = noise.get(Point2::new(1, 0));
+        assert!((a - b).abs() < 0.2);
+        assert!(Noise::new(12).get(Point2::new(5, 5)) != noise.get(Point2::new(5, 5)));
+    }
+
+    #[test]
+    fn test_height_map_chunk() {
+        let height_map = HeightMap::new(Noise::new(3))
+            .threshold(1.0, 2)
+            .threshold(0.5, 1);
+        assert_eq!(height_map.thresholds, vec![(0.5, 1), (1.0, 2)]);
+
+        let tiles = height_map.generate(Point2::new(-1, 2), Dimension3::new(4, 4, 1));
+        assert_eq!(tiles.len(), 16);
+        // The first tile is at the bottom left corner of the chunk.
+        let tile = tiles[0];
+        assert_eq!(tile.point, Point3::new(0, 0, 0));
+        assert_eq!(
+            Some(tile.sprite_index),
+            height_map.sprite_index(Point2::new(-6, 6))
+        );
+    }
+}